@@ -1,4 +1,4 @@
-//! Control-flow directives (`n:for`/`n:if`) compile pass.
+//! Control-flow directives (`n:for`/`n:if`/`n:switch`) compile pass.
 //!
 //! Walks a template [`Value`] tree (the shape `process_component_element`
 //! produces) and resolves the Vue-style namespaced attributes:
@@ -6,6 +6,16 @@
 //! * `n:if` — compile-time. Converts the condition into a `bind_visible`
 //!   binding on the component. The node stays in the tree; the binding toggles
 //!   its `visible` property at runtime.
+//! * `n:else-if` / `n:else` on the siblings after an `n:if`, and `n:case` /
+//!   `n:else` on the children of an `n:switch="<source>"` — compile-time.
+//!   Each branch gets its position in the chain as the `branch` property and
+//!   one binding per condition up to its own (`branch_0`, `branch_1`, …); the
+//!   renderer shows the first branch whose condition holds
+//!   (`nemo_layout::branch::is_branch_active`). A lone `n:if` keeps the plain
+//!   `bind_visible` form.
+//! * `n:show-when="width < 900"` — runtime. Stored (normalized) as the
+//!   `show_when` property; the renderer evaluates it against the window size
+//!   (`nemo_layout::responsive::is_shown`).
 //! * `n:for` over a static list — compile-time. Expands the node into N copies,
 //!   each with the loop variable substituted into `${item}` placeholders, and
 //!   strips the `n:for`/`n:key` attributes.
@@ -13,12 +23,14 @@
 //!   list container: strips `n:for`/`n:key`, stores a `list_binding` metadata
 //!   field recording the source path, item variable, key expression, and the
 //!   loop body template.
+//! * `<virtual-list>` — its child becomes the `item_template` property and its
+//!   `n:key` the `item_key` property; rows are instantiated by the renderer.
 //!
 //! The pass is applied after XML parsing and before the runtime's
 //! `parse_layout_config`, so `parse_layout_config` and everything downstream
 //! sees ordinary `Value` nodes (or list-container nodes for live-data `n:for`).
 
-use crate::Value;
+use crate::{ConfigPath, ValidationError, Value, ViewportCondition};
 use indexmap::IndexMap;
 
 /// Compiles all `n:`-prefixed directives in a config `Value` tree.
@@ -66,11 +78,120 @@ pub fn compile_directives_node(node: &mut Value) {
     compile_node(node);
 }
 
+/// Reports misplaced branch directives: an `n:else-if`/`n:else` that doesn't
+/// follow an `n:if`/`n:else-if` (or `n:case`) sibling, one combined with
+/// `n:if` or `n:for` on the same node, and an `n:case` outside an `n:switch`.
+/// Also reports `n:show-when` expressions that don't parse.
+/// Runs on the uncompiled tree, over the same roots as [`compile_directives`].
+pub fn check_directives(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    if let Some(layout) = config.get("layout") {
+        check_children(layout, &ConfigPath::from_key("layout"), &mut errors);
+    }
+    if let Some(sfc_map) = config.get("sfc").and_then(|v| v.as_object()) {
+        for (tag, def) in sfc_map {
+            if let Some(template) = def.get("template") {
+                let path = ConfigPath::from_key("sfc")
+                    .join_key(tag.clone())
+                    .join_key("template");
+                check_children(template, &path, &mut errors);
+            }
+        }
+    }
+    if let Some(templates) = config
+        .get("templates")
+        .and_then(|t| t.get("template"))
+        .and_then(|v| v.as_object())
+    {
+        for (name, body) in templates {
+            let path = ConfigPath::from_key("templates")
+                .join_key("template")
+                .join_key(name.clone());
+            check_children(body, &path, &mut errors);
+        }
+    }
+    errors
+}
+
+/// [`check_directives`] for one node's `component` children, recursively.
+fn check_children(node: &Value, path: &ConfigPath, errors: &mut Vec<ValidationError>) {
+    let Some(children) = node.get("component").and_then(|v| v.as_object()) else {
+        return;
+    };
+    let in_switch = node.get("n:switch").is_some();
+    // Whether the previous siblings leave a chain open for an
+    // `n:else-if`/`n:else` to continue.
+    let mut open = false;
+    for (id, child) in children {
+        let child_path = path.join_key("component").join_key(id.clone());
+        let has = |key: &str| child.get(key).is_some();
+        if let Some(expr) = child.get("n:show_when") {
+            let parsed = expr
+                .as_str()
+                .ok_or_else(|| "expected a string".to_string())
+                .and_then(ViewportCondition::parse);
+            if let Err(e) = parsed {
+                errors.push(ValidationError::invalid_directive(
+                    child_path.clone(),
+                    format!("invalid n:show-when on '{id}': {e}"),
+                ));
+            }
+        }
+        if has("n:case") && !in_switch {
+            errors.push(ValidationError::invalid_directive(
+                child_path.clone(),
+                format!("n:case on '{id}' is not inside an n:switch"),
+            ));
+        }
+        if let Some(attr) = ["n:else_if", "n:else"].into_iter().find(|a| has(a)) {
+            let shown = attr.replace('_', "-");
+            let problem = if has("n:if") {
+                Some(format!("'{id}' has both n:if and {shown}"))
+            } else if has("n:for") {
+                Some(format!("{shown} on '{id}' can't be combined with n:for"))
+            } else if !open {
+                Some(format!(
+                    "{shown} on '{id}' doesn't follow an n:if or n:else-if sibling"
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = problem {
+                errors.push(ValidationError::invalid_directive(
+                    child_path.clone(),
+                    message,
+                ));
+            }
+        }
+        open = if has("n:for") || has("n:else") {
+            false
+        } else if has("n:if") || has("n:else_if") || has("n:case") {
+            true
+        } else {
+            // Other children of a switch don't interrupt its cases.
+            open && in_switch
+        };
+        check_children(child, &child_path, errors);
+    }
+}
+
 /// Compiles directives in a single component `Value` node, recursively.
 ///
 /// Handles `n:if` and `n:for` on this node, then recurses into the `component`
 /// children map. The node is modified in place.
 fn compile_node(node: &mut Value) {
+    // `n:show-when` is evaluated at render time; keep it as a plain property.
+    if let Some(obj) = node.as_object_mut() {
+        if let Some(expr) = obj.shift_remove("n:show_when") {
+            let normalized = expr
+                .as_str()
+                .and_then(|e| ViewportCondition::parse(e).ok())
+                .map(|condition| Value::String(condition.to_string()))
+                .unwrap_or(expr);
+            obj.insert("show_when".to_string(), normalized);
+        }
+    }
+
     // Determine the directives on this node.
     let (n_if, n_for, n_key) = {
         let Some(obj) = node.as_object() else {
@@ -82,6 +203,22 @@ fn compile_node(node: &mut Value) {
             obj.get("n:key").or_else(|| obj.get("key")).cloned(),
         )
     };
+    // Taken here rather than in `strip_directives` so static `n:for` copies
+    // still switch.
+    let n_switch = node
+        .as_object_mut()
+        .and_then(|o| o.shift_remove("n:switch"))
+        .and_then(|v| v.as_str().map(|s| s.trim().to_string()));
+
+    // `<virtual-list>`: its child is an item template rendered per visible
+    // item, not a subtree to build once.
+    if node.get("type").and_then(|v| v.as_str()) == Some("virtual_list") {
+        compile_virtual_list(node, n_key.as_ref());
+        if let Some(cond) = &n_if {
+            apply_n_if(node, cond.as_str().unwrap_or(""));
+        }
+        return;
+    }
 
     // Live-data `n:for`: convert this node into a list container in place.
     // Static `n:for` is handled by the parent (`compile_children_map`), because
@@ -117,13 +254,16 @@ fn compile_node(node: &mut Value) {
     }
 
     // Recurse into children (this level expands static `n:for` on each child).
-    compile_children_map(node);
+    compile_children_map(node, n_switch.as_deref());
 }
 
 /// Strips all `n:`-prefixed directive attributes from a node.
 fn strip_directives(node: &mut Value) {
     if let Some(obj) = node.as_object_mut() {
         obj.shift_remove("n:if");
+        obj.shift_remove("n:else_if");
+        obj.shift_remove("n:else");
+        obj.shift_remove("n:case");
         obj.shift_remove("n:for");
         obj.shift_remove("n:key");
         obj.shift_remove("key");
@@ -132,7 +272,9 @@ fn strip_directives(node: &mut Value) {
 
 /// Walks the `component` children map of a node, expanding static `n:for` on
 /// each child (replacing it with N sibling copies) and recursing into the rest.
-fn compile_children_map(node: &mut Value) {
+/// Branch chains among the children are compiled first; `switch` is the
+/// node's `n:switch` source, which its `n:case` children compare against.
+fn compile_children_map(node: &mut Value, switch: Option<&str>) {
     let Some(map) = node
         .as_object_mut()
         .and_then(|o| o.get_mut("component"))
@@ -142,7 +284,15 @@ fn compile_children_map(node: &mut Value) {
     };
 
     let original = std::mem::take(map);
-    for (id, mut child) in original {
+    let kinds: Vec<BranchKind> = original
+        .values()
+        .map(|child| BranchKind::of(child, switch))
+        .collect();
+    let roles = branch_roles(&kinds, switch.is_some());
+    for ((id, mut child), role) in original.into_iter().zip(roles) {
+        if let Some(role) = role {
+            apply_branch(&mut child, &role);
+        }
         // Static `n:for` on the child → expand into N sibling entries.
         let n_for = child
             .get("n:for")
@@ -178,6 +328,170 @@ fn compile_children_map(node: &mut Value) {
     }
 }
 
+/// A branch condition as a binding source and optional comparison transform.
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    source: String,
+    transform: Option<String>,
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Self {
+        let (source, transform) = parse_condition(condition);
+        Condition {
+            source: source.to_string(),
+            transform,
+        }
+    }
+}
+
+/// The part a child plays in a branch chain.
+#[derive(Debug, Clone, PartialEq)]
+enum BranchKind {
+    If(Condition),
+    ElseIf(Condition),
+    Else,
+    /// An `n:case` of the parent's `n:switch`.
+    Case(Condition),
+    Other,
+}
+
+impl BranchKind {
+    fn of(child: &Value, switch: Option<&str>) -> Self {
+        // A looped node can't be part of a chain (`check_directives` reports
+        // branch directives on one).
+        if child.get("n:for").is_some() {
+            return BranchKind::Other;
+        }
+        let text = |key: &str| child.get(key).and_then(|v| v.as_str());
+        if let Some(cond) = text("n:if") {
+            BranchKind::If(Condition::parse(cond))
+        } else if let Some(cond) = text("n:else_if") {
+            BranchKind::ElseIf(Condition::parse(cond))
+        } else if child.get("n:else").is_some() {
+            BranchKind::Else
+        } else if let (Some(source), Some(case)) = (switch, child.get("n:case")) {
+            BranchKind::Case(Condition {
+                source: source.to_string(),
+                transform: Some(format!("== {}", literal_text(case))),
+            })
+        } else {
+            BranchKind::Other
+        }
+    }
+}
+
+/// A child's place in a chain: the conditions of every branch up to and
+/// including its own (an `n:else` has none of its own).
+#[derive(Debug, Clone, PartialEq)]
+struct BranchRole {
+    conditions: Vec<Condition>,
+    is_else: bool,
+}
+
+/// Groups sibling kinds into chains. An `n:if` starts one only when an
+/// `n:else-if`/`n:else` follows it; orphaned branches get no role.
+fn branch_roles(kinds: &[BranchKind], in_switch: bool) -> Vec<Option<BranchRole>> {
+    let mut roles = vec![None; kinds.len()];
+    let mut chain: Option<Vec<Condition>> = None;
+    for (i, kind) in kinds.iter().enumerate() {
+        let role = |conditions: &[Condition], is_else| {
+            Some(BranchRole {
+                conditions: conditions.to_vec(),
+                is_else,
+            })
+        };
+        match kind {
+            BranchKind::If(cond) => {
+                let continued = matches!(
+                    kinds.get(i + 1),
+                    Some(BranchKind::ElseIf(_) | BranchKind::Else)
+                );
+                chain = continued.then(|| vec![cond.clone()]);
+                roles[i] = chain.as_deref().and_then(|c| role(c, false));
+            }
+            BranchKind::ElseIf(cond) | BranchKind::Case(cond) => {
+                // A case may open the chain; an else-if only continues one.
+                if matches!(kind, BranchKind::Case(_)) {
+                    chain.get_or_insert_with(Vec::new);
+                }
+                if let Some(conditions) = &mut chain {
+                    conditions.push(cond.clone());
+                    roles[i] = role(conditions, false);
+                }
+            }
+            BranchKind::Else => {
+                if let Some(conditions) = chain.take() {
+                    roles[i] = role(&conditions, true);
+                }
+            }
+            BranchKind::Other => {
+                // Other children of a switch don't interrupt its cases.
+                if !in_switch {
+                    chain = None;
+                }
+            }
+        }
+    }
+    roles
+}
+
+/// Marks a node as a chain branch: its position as `n:branch` (plus
+/// `n:branch_else` for an `n:else`) and a binding from each condition to
+/// `n:branch_<n>`. The property names match `nemo_layout::branch`; the
+/// namespace keeps them off a component's own `branch` attribute.
+fn apply_branch(node: &mut Value, role: &BranchRole) {
+    let Some(obj) = node.as_object_mut() else {
+        return;
+    };
+    for key in ["n:if", "n:else_if", "n:else", "n:case"] {
+        obj.shift_remove(key);
+    }
+    let index = if role.is_else {
+        role.conditions.len()
+    } else {
+        role.conditions.len().saturating_sub(1)
+    };
+    obj.insert("n:branch".to_string(), Value::Integer(index as i64));
+    if role.is_else {
+        obj.insert("n:branch_else".to_string(), Value::Bool(true));
+    }
+    for (n, cond) in role.conditions.iter().enumerate() {
+        let mut b = IndexMap::new();
+        b.insert("source".to_string(), Value::String(cond.source.clone()));
+        b.insert("target".to_string(), Value::String(format!("n:branch_{n}")));
+        if let Some(t) = &cond.transform {
+            b.insert("transform".to_string(), Value::String(t.clone()));
+        }
+        push_binding(obj, Value::Object(b));
+    }
+}
+
+/// Adds a binding block to a node, keeping any it already has.
+fn push_binding(obj: &mut IndexMap<String, Value>, binding: Value) {
+    let merged = match obj.shift_remove("binding") {
+        None => binding,
+        Some(Value::Array(mut list)) => {
+            list.push(binding);
+            Value::Array(list)
+        }
+        Some(existing) => Value::Array(vec![existing, binding]),
+    };
+    obj.insert("binding".to_string(), merged);
+}
+
+/// The comparison literal for an `n:case` value (the parser has already
+/// coerced numbers and booleans).
+fn literal_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => "null".to_string(),
+    }
+}
+
 /// Applies `n:if` to a node: emits a `bind_visible` binding from the condition
 /// to the component's `visible` property.
 ///
@@ -446,6 +760,18 @@ fn compile_live_data_n_for(
 
     obj.insert("list_binding".to_string(), Value::Object(lb));
 
+    // Drag attributes make each instance draggable among its siblings; on the
+    // container they would drag the whole list instead. Attributes that read
+    // the loop variable only have a value per item.
+    for key in ["draggable", "drag_group", "drag_data", "drag_label"] {
+        obj.shift_remove(key);
+    }
+    let (whole, field) = (format!("${{{}}}", item_var), format!("${{{}.", item_var));
+    obj.retain(|_, val| {
+        !val.as_str()
+            .is_some_and(|s| s.contains(&whole) || s.contains(&field))
+    });
+
     // The container has no static children — the runtime populates it. Remove
     // any existing `component` children (they are the loop body, now stored as
     // the template).
@@ -457,6 +783,44 @@ fn compile_live_data_n_for(
     let _ = for_expr; // already parsed
 }
 
+/// Turns a `<virtual-list>` node's child into its item template.
+///
+/// The first child is stored, uncompiled, as the `item_template` property and
+/// the `component` children map is removed. Its directives are left for each
+/// row: the renderer expands the template for an item as it scrolls into
+/// view, settles the directives that test the item and compiles the rest
+/// (`nemo_layout::virtual_list::row_node`). `n:key` may sit on the list or on
+/// the template and is kept as the `item_key` property.
+fn compile_virtual_list(node: &mut Value, n_key: Option<&Value>) {
+    strip_directives(node);
+    let Some(obj) = node.as_object_mut() else {
+        return;
+    };
+    let mut template = obj
+        .shift_remove("component")
+        .and_then(|children| match children {
+            Value::Object(map) => map.into_iter().next().map(|(_, child)| child),
+            Value::Array(arr) => arr.into_iter().next(),
+            _ => None,
+        });
+    let key = n_key.cloned().or_else(|| {
+        template
+            .as_ref()
+            .and_then(|t| t.get("n:key").or_else(|| t.get("key")))
+            .cloned()
+    });
+    if let Some(template) = template.as_mut().and_then(|t| t.as_object_mut()) {
+        template.shift_remove("n:key");
+        template.shift_remove("key");
+    }
+    if let Some(key) = key {
+        obj.insert("item_key".to_string(), key);
+    }
+    if let Some(template) = template {
+        obj.insert("item_template".to_string(), template);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -624,6 +988,93 @@ mod tests {
         assert!(o.get("component").is_none());
     }
 
+    /// Drag attributes on a live-data `n:for` stay on the instances only.
+    #[test]
+    fn test_n_for_live_data_keeps_drag_attributes_on_instances() {
+        let mut node = obj(vec![
+            ("id", s("cards")),
+            ("type", s("panel")),
+            ("n:for", s("card in data.board.cards")),
+            ("draggable", Value::Bool(true)),
+            ("drag_group", s("card")),
+            ("drag_data", s("${card.id}")),
+        ]);
+        compile_node(&mut node);
+        let o = node.as_object().unwrap();
+        for key in ["draggable", "drag_group", "drag_data"] {
+            assert!(o.get(key).is_none(), "{key} left on the container");
+        }
+        let template = o
+            .get("list_binding")
+            .and_then(|lb| lb.get("template"))
+            .unwrap();
+        assert_eq!(template.get("draggable"), Some(&Value::Bool(true)));
+        assert_eq!(
+            template.get("drag_data").and_then(|v| v.as_str()),
+            Some("${card.id}")
+        );
+    }
+
+    /// An attribute reading the loop variable is left to the instances; the
+    /// container has no item to resolve it against.
+    #[test]
+    fn test_n_for_live_data_keeps_item_attributes_off_the_container() {
+        let mut node = obj(vec![
+            ("id", s("cards")),
+            ("type", s("card")),
+            ("n:for", s("card in data.cards")),
+            ("title", s("Card ${card.title}")),
+            ("variant", s("outline")),
+        ]);
+        compile_node(&mut node);
+        let o = node.as_object().unwrap();
+        assert!(o.get("title").is_none());
+        assert_eq!(o.get("variant"), Some(&s("outline")));
+        let template = o
+            .get("list_binding")
+            .and_then(|lb| lb.get("template"))
+            .unwrap();
+        assert_eq!(template.get("title"), Some(&s("Card ${card.title}")));
+    }
+
+    /// A `<virtual-list>`'s child becomes its unexpanded, uncompiled item
+    /// template.
+    #[test]
+    fn test_virtual_list_stores_item_template() {
+        let mut node = obj(vec![
+            ("id", s("feed")),
+            ("type", s("virtual_list")),
+            ("item", s("msg")),
+            (
+                "component",
+                obj(vec![(
+                    "row",
+                    obj(vec![
+                        ("type", s("label")),
+                        ("n:key", s("msg.id")),
+                        ("n:if", s("msg.unread")),
+                        ("text", s("${msg.body}")),
+                    ]),
+                )]),
+            ),
+        ]);
+        compile_node(&mut node);
+        let o = node.as_object().unwrap();
+        assert!(o.get("component").is_none());
+        assert_eq!(o.get("item_key").and_then(|v| v.as_str()), Some("msg.id"));
+        let template = o.get("item_template").and_then(|v| v.as_object()).unwrap();
+        assert_eq!(
+            template.get("text").and_then(|v| v.as_str()),
+            Some("${msg.body}")
+        );
+        assert!(template.get("n:key").is_none());
+        // Left for each row to settle against its item.
+        assert_eq!(
+            template.get("n:if").and_then(|v| v.as_str()),
+            Some("msg.unread")
+        );
+    }
+
     /// `n:for` + `n:if` on the same node: `n:for` wins, `n:if` is folded into
     /// the list_binding (live data) or each expansion (static).
     #[test]
@@ -686,4 +1137,159 @@ mod tests {
         assert!(sfc_tmpl.get("n:if").is_none());
         assert!(sfc_tmpl.get("bind_visible").is_some());
     }
+
+    /// The `target`s of a node's binding blocks.
+    fn binding_targets(node: &Value) -> Vec<String> {
+        let bindings = match node.get("binding") {
+            Some(Value::Array(list)) => list.clone(),
+            Some(b) => vec![b.clone()],
+            None => Vec::new(),
+        };
+        bindings
+            .iter()
+            .filter_map(|b| b.get("target").and_then(|t| t.as_str()))
+            .map(String::from)
+            .collect()
+    }
+
+    /// `n:if` / `n:else-if` / `n:else` siblings compile into one chain.
+    #[test]
+    fn test_if_else_if_else_chain() {
+        let mut node = obj(vec![
+            ("type", s("stack")),
+            (
+                "component",
+                obj(vec![
+                    ("err", obj(vec![("n:if", s("data.status == 'error'"))])),
+                    ("load", obj(vec![("n:else_if", s("data.loading"))])),
+                    (
+                        "ok",
+                        obj(vec![("n:else", Value::Bool(true)), ("branch", s("main"))]),
+                    ),
+                    ("alone", obj(vec![("n:if", s("data.show"))])),
+                ]),
+            ),
+        ]);
+        compile_directives_node(&mut node);
+        let c = node.get("component").unwrap();
+        let err = c.get("err").unwrap();
+        assert_eq!(err.get("n:branch"), Some(&Value::Integer(0)));
+        assert!(err.get("n:if").is_none() && err.get("bind_visible").is_none());
+        assert_eq!(binding_targets(err), vec!["n:branch_0"]);
+        let load = c.get("load").unwrap();
+        assert_eq!(load.get("n:branch"), Some(&Value::Integer(1)));
+        assert_eq!(binding_targets(load), vec!["n:branch_0", "n:branch_1"]);
+        let ok = c.get("ok").unwrap();
+        assert_eq!(ok.get("n:branch"), Some(&Value::Integer(2)));
+        assert_eq!(ok.get("n:branch_else"), Some(&Value::Bool(true)));
+        assert!(ok.get("n:else").is_none());
+        assert_eq!(binding_targets(ok), vec!["n:branch_0", "n:branch_1"]);
+        // The component's own `branch` attribute is left alone.
+        assert_eq!(ok.get("branch"), Some(&s("main")));
+        // A lone n:if after the chain keeps the bind_visible form.
+        let alone = c.get("alone").unwrap();
+        assert!(alone.get("n:branch").is_none());
+        assert!(alone.get("bind_visible").is_some());
+    }
+
+    /// `n:case` children compare against the parent's `n:switch` source.
+    #[test]
+    fn test_switch_case_else() {
+        let mut node = obj(vec![
+            ("type", s("stack")),
+            ("n:switch", s("data.status")),
+            (
+                "component",
+                obj(vec![
+                    ("loading", obj(vec![("n:case", s("'loading'"))])),
+                    ("header", obj(vec![("type", s("label"))])),
+                    ("two", obj(vec![("n:case", Value::Integer(2))])),
+                    ("other", obj(vec![("n:else", Value::Bool(true))])),
+                ]),
+            ),
+        ]);
+        compile_directives_node(&mut node);
+        assert!(node.get("n:switch").is_none());
+        let c = node.get("component").unwrap();
+        let binding = c.get("loading").and_then(|n| n.get("binding")).unwrap();
+        assert_eq!(binding.get("source"), Some(&s("data.status")));
+        assert_eq!(binding.get("transform"), Some(&s("== 'loading'")));
+        assert!(c.get("header").unwrap().get("n:branch").is_none());
+        let two = c.get("two").unwrap();
+        assert_eq!(two.get("n:branch"), Some(&Value::Integer(1)));
+        assert!(two.get("n:case").is_none());
+        let other = c.get("other").unwrap();
+        assert_eq!(other.get("n:branch"), Some(&Value::Integer(2)));
+        assert_eq!(binding_targets(other), vec!["n:branch_0", "n:branch_1"]);
+    }
+
+    /// Misplaced branch directives are reported with their path.
+    #[test]
+    fn test_check_directives_misplaced() {
+        let config = obj(vec![(
+            "layout",
+            obj(vec![
+                ("type", s("stack")),
+                (
+                    "component",
+                    obj(vec![
+                        ("a", obj(vec![("n:if", s("data.a"))])),
+                        ("b", obj(vec![("type", s("label"))])),
+                        ("c", obj(vec![("n:else", Value::Bool(true))])),
+                        ("d", obj(vec![("n:case", s("'x'"))])),
+                        (
+                            "e",
+                            obj(vec![("n:if", s("data.e")), ("n:else_if", s("data.f"))]),
+                        ),
+                        ("f", obj(vec![("n:if", s("data.f"))])),
+                        ("g", obj(vec![("n:else_if", s("data.g"))])),
+                        ("h", obj(vec![("n:else", Value::Bool(true))])),
+                    ]),
+                ),
+            ]),
+        )]);
+        let errors = check_directives(&config);
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "layout.component.c",
+                "layout.component.d",
+                "layout.component.e"
+            ]
+        );
+        assert!(errors[0].message.contains("doesn't follow"));
+        assert!(errors[1].message.contains("n:switch"));
+    }
+
+    /// `n:show-when` becomes a normalized `show_when` property; malformed
+    /// expressions are reported.
+    #[test]
+    fn test_n_show_when() {
+        let mut node = obj(vec![
+            ("type", s("stack")),
+            (
+                "component",
+                obj(vec![(
+                    "side",
+                    obj(vec![("type", s("panel")), ("n:show_when", s("width>=900"))]),
+                )]),
+            ),
+        ]);
+        compile_directives_node(&mut node);
+        let side = node.get("component").and_then(|c| c.get("side")).unwrap();
+        assert!(side.get("n:show_when").is_none());
+        assert_eq!(side.get("show_when"), Some(&s("width >= 900")));
+
+        let config = obj(vec![(
+            "layout",
+            obj(vec![(
+                "component",
+                obj(vec![("wide", obj(vec![("n:show_when", s("size > 3"))]))]),
+            )]),
+        )]);
+        let errors = check_directives(&config);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("n:show-when"));
+    }
 }
@@ -12,8 +12,9 @@ pub enum ConfigError {
     #[error("Parse error: {0}")]
     Parse(#[from] ParseError),
 
-    /// Error during validation.
-    #[error("Validation failed with {} error(s)", errors.len())]
+    /// Error during validation. Each error is listed on its own line with
+    /// the config path it applies to.
+    #[error("Validation failed with {} error(s):{}", errors.len(), list_errors(errors))]
     Validation { errors: Vec<ValidationError> },
 
     /// Error during expression resolution.
@@ -38,6 +39,36 @@ pub enum ConfigError {
          <include> fragments."
     )]
     DeprecatedXmlEntry { path: String },
+
+    /// `--profile` named a profile the project's `nemo.toml` doesn't declare.
+    #[error("Unknown profile '{name}'{}", list_profiles(available))]
+    UnknownProfile {
+        name: String,
+        available: Vec<String>,
+    },
+}
+
+/// Describes the profiles a manifest does declare, for an unknown-profile error.
+fn list_profiles(available: &[String]) -> String {
+    if available.is_empty() {
+        " (no [profiles] declared in nemo.toml)".to_string()
+    } else {
+        format!(" (nemo.toml declares: {})", available.join(", "))
+    }
+}
+
+/// Renders validation errors as indented `path: message` lines.
+fn list_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(|e| {
+            if e.path.is_root() {
+                format!("\n  {}", e.message)
+            } else {
+                format!("\n  {}: {}", e.path, e.message)
+            }
+        })
+        .collect()
 }
 
 /// Error during configuration parsing.
@@ -158,6 +189,31 @@ impl ValidationError {
         }
     }
 
+    /// Creates an "invalid directive" error (misplaced or malformed).
+    pub fn invalid_directive(path: ConfigPath, message: String) -> Self {
+        ValidationError {
+            path,
+            message,
+            expected: None,
+            actual: None,
+            location: None,
+            code: ErrorCode::InvalidValue,
+        }
+    }
+
+    /// Creates an "invalid prop" error: an SFC instance attribute that doesn't
+    /// coerce to its prop's declared type (or enum).
+    pub fn invalid_prop(path: ConfigPath, message: String, expected: &str, actual: &str) -> Self {
+        ValidationError {
+            path,
+            message,
+            expected: Some(expected.to_string()),
+            actual: Some(actual.to_string()),
+            location: None,
+            code: ErrorCode::InvalidType,
+        }
+    }
+
     /// Creates a "schema not found" error.
     pub fn schema_not_found(name: &str) -> Self {
         ValidationError {
@@ -226,6 +282,14 @@ pub enum ResolveError {
     /// Invalid path.
     #[error("Invalid path '{path}': {message}")]
     InvalidPath { path: String, message: String },
+
+    /// A `${secret.name}` that the project's store doesn't hold.
+    #[error("Secret '{name}' is not set in the {store}; add it with `nemo secret set {name}`")]
+    UnknownSecret { name: String, store: String },
+
+    /// The secret store couldn't be read (locked, wrong passphrase, ...).
+    #[error("Failed to read secret '{name}': {message}")]
+    Secret { name: String, message: String },
 }
 
 /// Schema-related errors.
@@ -0,0 +1,662 @@
+//! Message catalogs for `<i18n dir="locales" default="en"/>`.
+//!
+//! Each file in the catalog directory holds one locale's messages, named by
+//! the locale: `locales/en.ftl`, `locales/de.json`, or a `locales/fr/`
+//! subdirectory whose files are merged. Two formats are read:
+//!
+//! - **Fluent** (`.ftl`) — the message subset: `key = text`, indented
+//!   continuation lines, `.attr = text` attributes (stored as `key.attr`),
+//!   `-term = text` terms and `#` comments. Patterns may reference
+//!   `{ $arg }` arguments, `{ -term }` terms, other messages by key, and
+//!   `{ "literal" }` strings. Select expressions are rejected at load.
+//! - **JSON** (`.json`) — an object of messages; nested objects flatten to
+//!   dotted keys (`{"nav": {"home": "Home"}}` → `nav.home`). Patterns use the
+//!   same `{ $arg }` placeholders.
+//!
+//! The parser embeds the loaded catalogs into `config["i18n"]["messages"]`
+//! so a `dist/` build carries them; [`Messages::from_config`] reads them back
+//! for the resolver's `t()` function, binding transforms and scripts.
+
+use crate::error::ParseError;
+use crate::location::SourceLocation;
+use crate::Value;
+use indexmap::IndexMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// One locale's messages, keyed by message id.
+pub type Catalog = IndexMap<String, String>;
+
+/// The marker that makes an attribute localizable: a `${t(…)}` call.
+const T_CALL: &str = "${t(";
+
+/// Loaded catalogs plus the active and fallback locales.
+#[derive(Debug, Clone, Default)]
+pub struct Messages {
+    catalogs: Arc<IndexMap<String, Catalog>>,
+    default_locale: String,
+    locale: String,
+}
+
+impl Messages {
+    /// Creates a message set with `default_locale` active. An empty or
+    /// unknown default falls back to the first catalog.
+    pub fn new(catalogs: IndexMap<String, Catalog>, default_locale: &str) -> Self {
+        let default_locale = if catalogs.contains_key(default_locale) {
+            default_locale.to_string()
+        } else {
+            catalogs.keys().next().cloned().unwrap_or_default()
+        };
+        Self {
+            catalogs: Arc::new(catalogs),
+            locale: default_locale.clone(),
+            default_locale,
+        }
+    }
+
+    /// Reads the catalogs embedded under `config["i18n"]`, or `None` when the
+    /// app declares no `<i18n>` block.
+    pub fn from_config(config: &Value) -> Option<Self> {
+        let i18n = config.get("i18n")?;
+        let messages = i18n.get("messages")?.as_object()?;
+        let catalogs = messages
+            .iter()
+            .map(|(locale, entries)| {
+                let catalog = entries
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                    .collect();
+                (locale.clone(), catalog)
+            })
+            .collect();
+        let default = i18n.get("default").and_then(|v| v.as_str()).unwrap_or("en");
+        Some(Self::new(catalogs, default))
+    }
+
+    /// The locales with a catalog, in load order.
+    pub fn locales(&self) -> Vec<String> {
+        self.catalogs.keys().cloned().collect()
+    }
+
+    /// The active locale.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The fallback locale.
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Switches the active locale to the best match for `requested` (see
+    /// [`resolve_locale`](Self::resolve_locale)) and returns it.
+    pub fn set_locale(&mut self, requested: &str) -> &str {
+        self.locale = self.resolve_locale(requested);
+        &self.locale
+    }
+
+    /// Returns a copy with the active locale set to `requested`, or
+    /// unchanged when `requested` is `None`.
+    pub fn with_locale(mut self, requested: Option<&str>) -> Self {
+        if let Some(locale) = requested {
+            self.set_locale(locale);
+        }
+        self
+    }
+
+    /// Picks the catalog for `requested`: an exact match (`de-AT`), then its
+    /// language (`de`), then any regional catalog of that language, then the
+    /// default locale.
+    pub fn resolve_locale(&self, requested: &str) -> String {
+        let requested = requested.trim().replace('_', "-");
+        let find = |wanted: &str| {
+            self.catalogs
+                .keys()
+                .find(|l| l.eq_ignore_ascii_case(wanted))
+                .cloned()
+        };
+        if let Some(exact) = find(&requested) {
+            return exact;
+        }
+        let language = requested.split('-').next().unwrap_or_default();
+        if let Some(lang) = find(language) {
+            return lang;
+        }
+        self.catalogs
+            .keys()
+            .find(|l| {
+                l.split('-')
+                    .next()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(language))
+            })
+            .cloned()
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// Looks up `key` in the active locale, falling back to the default
+    /// locale. `None` when neither has it.
+    pub fn lookup(&self, key: &str) -> Option<&str> {
+        [&self.locale, &self.default_locale]
+            .into_iter()
+            .find_map(|locale| self.catalogs.get(locale)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Translates `key` in the active locale with `args`: an object supplies
+    /// named `{ $name }` arguments, any other non-null value is `{ $value }`.
+    /// A key missing from every fallback renders as the key itself.
+    pub fn translate(&self, key: &str, args: &Value) -> String {
+        match self.lookup(key) {
+            Some(pattern) => self.format(pattern, args, 0),
+            None => key.to_string(),
+        }
+    }
+
+    /// Expands the placeables in `pattern`. `depth` bounds message and term
+    /// references so a cycle can't recurse forever.
+    fn format(&self, pattern: &str, args: &Value, depth: usize) -> String {
+        let mut out = String::with_capacity(pattern.len());
+        let mut rest = pattern;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                out.push_str(&rest[open..]);
+                return out;
+            };
+            let inner = rest[open + 1..open + close].trim();
+            out.push_str(&self.placeable(inner, args, depth));
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Renders one `{ … }` placeable. Unresolvable ones render as
+    /// `{inner}`, the way Fluent shows a missing argument.
+    fn placeable(&self, inner: &str, args: &Value, depth: usize) -> String {
+        if let Some(name) = inner.strip_prefix('$') {
+            let arg = match args {
+                Value::Object(map) => map.get(name),
+                Value::Null => None,
+                other if name == "value" => Some(other),
+                _ => None,
+            };
+            if let Some(arg) = arg {
+                return arg.to_string();
+            }
+        } else if inner.len() >= 2 && inner.starts_with('"') && inner.ends_with('"') {
+            return inner[1..inner.len() - 1].to_string();
+        } else if depth < 8 {
+            if let Some(pattern) = self.lookup(inner) {
+                return self.format(pattern, args, depth + 1);
+            }
+        }
+        format!("{{{inner}}}")
+    }
+
+    /// Lists `(locale, key)` pairs for every key that some locale lacks:
+    /// keys in `used` (those the app references) plus any message another
+    /// catalog defines. Terms are only compared across catalogs.
+    pub fn missing_keys(&self, used: &[String]) -> Vec<(String, String)> {
+        let mut keys: Vec<&str> = used.iter().map(String::as_str).collect();
+        for catalog in self.catalogs.values() {
+            keys.extend(catalog.keys().map(String::as_str));
+        }
+        let mut seen = std::collections::HashSet::new();
+        keys.retain(|k| seen.insert(*k));
+
+        let mut missing = Vec::new();
+        for (locale, catalog) in self.catalogs.iter() {
+            for key in &keys {
+                if !catalog.contains_key(*key) {
+                    missing.push((locale.clone(), key.to_string()));
+                }
+            }
+        }
+        missing
+    }
+}
+
+/// Loads every catalog in `dir`: `<locale>.ftl` / `<locale>.json` files and
+/// `<locale>/` subdirectories of them. Locales are sorted by name.
+pub fn load_catalogs(dir: &Path) -> Result<IndexMap<String, Catalog>, ParseError> {
+    let mut catalogs: IndexMap<String, Catalog> = IndexMap::new();
+    for path in sorted_entries(dir)? {
+        if path.is_dir() {
+            let Some(locale) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let mut catalog = Catalog::new();
+            for file in sorted_entries(&path)? {
+                if let Some(entries) = read_catalog_file(&file)? {
+                    catalog.extend(entries);
+                }
+            }
+            catalogs
+                .entry(locale.to_string())
+                .or_default()
+                .extend(catalog);
+        } else if let Some(entries) = read_catalog_file(&path)? {
+            let Some(locale) = path.file_stem().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            catalogs
+                .entry(locale.to_string())
+                .or_default()
+                .extend(entries);
+        }
+    }
+    catalogs.sort_keys();
+    Ok(catalogs)
+}
+
+/// Lists a directory's entries in name order.
+fn sorted_entries(dir: &Path) -> Result<Vec<std::path::PathBuf>, ParseError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        ParseError::new(
+            format!("Failed to read i18n directory {}: {}", dir.display(), e),
+            SourceLocation::new(dir.display().to_string(), 1, 1),
+        )
+    })?;
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads one `.ftl` or `.json` catalog file; `None` for any other file.
+fn read_catalog_file(path: &Path) -> Result<Option<Catalog>, ParseError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    if ext != "ftl" && ext != "json" {
+        return Ok(None);
+    }
+    let source_name = path.display().to_string();
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ParseError::new(
+            format!("Failed to read catalog {}: {}", source_name, e),
+            SourceLocation::new(&source_name, 1, 1),
+        )
+    })?;
+    let catalog = if ext == "ftl" {
+        parse_ftl(&content, &source_name)?
+    } else {
+        parse_json_catalog(&content, &source_name)?
+    };
+    Ok(Some(catalog))
+}
+
+/// Parses the Fluent message subset described in the module docs.
+pub fn parse_ftl(content: &str, source_name: &str) -> Result<Catalog, ParseError> {
+    let mut catalog = Catalog::new();
+    // The entry (message or attribute key) continuation lines extend.
+    let mut current: Option<String> = None;
+    let mut message: Option<String> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let error = |message: &str| {
+            ParseError::new(
+                message.to_string(),
+                SourceLocation::new(source_name, line_no, 1),
+            )
+            .with_source(line)
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.trim_end().ends_with("->") {
+            return Err(error(
+                "select expressions are not supported; use a separate key per variant",
+            ));
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if indented {
+            let text = line.trim();
+            if let Some(attr) = text.strip_prefix('.') {
+                let Some(parent) = &message else {
+                    return Err(error("attribute without a message"));
+                };
+                let (name, value) =
+                    split_entry(attr).ok_or_else(|| error("expected `.attr = value`"))?;
+                let key = format!("{parent}.{name}");
+                catalog.insert(key.clone(), value.to_string());
+                current = Some(key);
+                continue;
+            }
+            let Some(key) = &current else {
+                return Err(error("indented line outside a message"));
+            };
+            let entry = catalog.entry(key.clone()).or_default();
+            if !entry.is_empty() {
+                entry.push('\n');
+            }
+            entry.push_str(text);
+            continue;
+        }
+
+        let (key, value) = split_entry(line).ok_or_else(|| error("expected `key = value`"))?;
+        catalog.insert(key.to_string(), value.to_string());
+        current = Some(key.to_string());
+        message = Some(key.to_string());
+    }
+    Ok(catalog)
+}
+
+/// Splits `key = value` into a valid identifier and its trimmed value.
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    let ident = key.strip_prefix('-').unwrap_or(key);
+    let valid = ident.starts_with(|c: char| c.is_ascii_alphabetic())
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some((key, value.trim()))
+}
+
+/// Parses a JSON catalog, flattening nested objects to dotted keys.
+pub fn parse_json_catalog(content: &str, source_name: &str) -> Result<Catalog, ParseError> {
+    let json: serde_json::Value = serde_json::from_str(content).map_err(|e| {
+        ParseError::new(
+            format!("invalid JSON catalog: {e}"),
+            SourceLocation::new(source_name, e.line(), e.column()),
+        )
+    })?;
+    let serde_json::Value::Object(map) = json else {
+        return Err(ParseError::new(
+            "a JSON catalog must be an object of messages",
+            SourceLocation::new(source_name, 1, 1),
+        ));
+    };
+    let mut catalog = Catalog::new();
+    flatten_json("", &map, &mut catalog);
+    Ok(catalog)
+}
+
+fn flatten_json(prefix: &str, map: &serde_json::Map<String, serde_json::Value>, out: &mut Catalog) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            serde_json::Value::Object(nested) => flatten_json(&key, nested, out),
+            serde_json::Value::String(s) => {
+                out.insert(key, s.clone());
+            }
+            other => {
+                out.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+/// Builds the `config["i18n"]` block for `<i18n dir default>`, loading the
+/// catalogs from `dir`.
+pub(crate) fn i18n_block(dir: &Path, dir_attr: &str, default: &str) -> Result<Value, ParseError> {
+    let catalogs = load_catalogs(dir)?;
+    let messages = catalogs
+        .into_iter()
+        .map(|(locale, catalog)| {
+            let entries = catalog
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect();
+            (locale, Value::Object(entries))
+        })
+        .collect();
+    let mut block = IndexMap::new();
+    block.insert("dir".to_string(), Value::String(dir_attr.to_string()));
+    block.insert("default".to_string(), Value::String(default.to_string()));
+    block.insert("messages".to_string(), Value::Object(messages));
+    Ok(Value::Object(block))
+}
+
+/// Records the source text of every attribute holding a `${t(…)}` call
+/// under the node's `i18n_attrs`, in the layout, the `<templates>` and the
+/// SFC template bodies. Load-time resolution replaces those attributes with
+/// text in the startup locale; the runtime re-renders them from
+/// `i18n_attrs` when the locale changes.
+pub fn record_translations(config: &mut Value) {
+    let Some(obj) = config.as_object_mut() else {
+        return;
+    };
+    if let Some(layout) = obj.get_mut("layout") {
+        record_children(layout);
+    }
+    if let Some(sfc_map) = obj.get_mut("sfc").and_then(|v| v.as_object_mut()) {
+        for def in sfc_map.values_mut() {
+            if let Some(template) = def.as_object_mut().and_then(|o| o.get_mut("template")) {
+                record_node(template);
+                record_children(template);
+            }
+        }
+    }
+    if let Some(templates) = obj
+        .get_mut("templates")
+        .and_then(|t| t.as_object_mut())
+        .and_then(|t| t.get_mut("template"))
+        .and_then(|v| v.as_object_mut())
+    {
+        for body in templates.values_mut() {
+            record_node(body);
+            record_children(body);
+        }
+    }
+}
+
+fn record_children(node: &mut Value) {
+    let children = match node.as_object_mut().and_then(|o| o.get_mut("component")) {
+        Some(Value::Object(map)) => map.values_mut().collect::<Vec<_>>(),
+        Some(Value::Array(arr)) => arr.iter_mut().collect(),
+        _ => return,
+    };
+    for child in children {
+        record_node(child);
+        record_children(child);
+    }
+}
+
+fn record_node(node: &mut Value) {
+    let Some(obj) = node.as_object_mut() else {
+        return;
+    };
+    let attrs: IndexMap<String, Value> = obj
+        .iter()
+        .filter(|(k, v)| {
+            k.as_str() != "component" && v.as_str().is_some_and(|s| s.contains(T_CALL))
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if !attrs.is_empty() {
+        obj.insert("i18n_attrs".to_string(), Value::Object(attrs));
+    }
+}
+
+/// Collects the message keys an app references: literal first arguments of
+/// `t('key', …)` in attribute text and binding transforms anywhere in the
+/// layout, templates and SFC bodies.
+pub fn collect_message_keys(config: &Value) -> Vec<String> {
+    let mut keys = Vec::new();
+    for section in ["layout", "templates", "sfc"] {
+        if let Some(value) = config.get(section) {
+            collect_keys_in(value, &mut keys);
+        }
+    }
+    keys
+}
+
+fn collect_keys_in(value: &Value, keys: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            for key in message_keys_in(s) {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter().for_each(|v| collect_keys_in(v, keys)),
+        Value::Object(map) => map.values().for_each(|v| collect_keys_in(v, keys)),
+        _ => {}
+    }
+}
+
+/// Finds the quoted keys of the `t(…)` calls in `text`.
+fn message_keys_in(text: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for (pos, _) in text.match_indices("t(") {
+        let standalone = text[..pos]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '.'));
+        if !standalone {
+            continue;
+        }
+        let rest = text[pos + 2..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            continue;
+        };
+        if let Some(end) = rest[1..].find(quote) {
+            keys.push(rest[1..1 + end].to_string());
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages() -> Messages {
+        let en = parse_ftl(
+            r#"
+# Shared strings
+-brand = Nemo
+greeting = Hello, { $name }!
+title = Welcome to { -brand }
+about =
+    First line
+    second line
+save = Save
+    .tooltip = Save the document
+count = { $value } items
+"#,
+            "en.ftl",
+        )
+        .unwrap();
+        let de = parse_json_catalog(
+            r#"{"greeting": "Hallo, { $name }!", "nav": {"home": "Startseite"}}"#,
+            "de.json",
+        )
+        .unwrap();
+        let mut catalogs = IndexMap::new();
+        catalogs.insert("de".to_string(), de);
+        catalogs.insert("en".to_string(), en);
+        Messages::new(catalogs, "en")
+    }
+
+    #[test]
+    fn parses_the_fluent_subset() {
+        let m = messages();
+        let name = Value::Object(IndexMap::from([(
+            "name".to_string(),
+            Value::String("Ada".into()),
+        )]));
+        assert_eq!(m.translate("greeting", &name), "Hello, Ada!");
+        assert_eq!(m.translate("title", &Value::Null), "Welcome to Nemo");
+        assert_eq!(
+            m.translate("about", &Value::Null),
+            "First line\nsecond line"
+        );
+        assert_eq!(
+            m.translate("save.tooltip", &Value::Null),
+            "Save the document"
+        );
+        assert_eq!(m.translate("count", &Value::Integer(3)), "3 items");
+        assert_eq!(m.translate("greeting", &Value::Null), "Hello, {$name}!");
+    }
+
+    #[test]
+    fn falls_back_through_locales_to_the_key() {
+        let mut m = messages();
+        assert_eq!(m.set_locale("de_AT"), "de");
+        let name = Value::Object(IndexMap::from([(
+            "name".to_string(),
+            Value::String("Ada".into()),
+        )]));
+        assert_eq!(m.translate("greeting", &name), "Hallo, Ada!");
+        assert_eq!(m.translate("nav.home", &Value::Null), "Startseite");
+        // Missing in `de`, present in the default locale.
+        assert_eq!(m.translate("save", &Value::Null), "Save");
+        assert_eq!(m.translate("nope", &Value::Null), "nope");
+        assert_eq!(m.set_locale("ja"), "en");
+    }
+
+    #[test]
+    fn reports_bad_ftl_lines_with_their_location() {
+        let err = parse_ftl("ok = fine\nnot a message\n", "de.ftl").unwrap_err();
+        assert_eq!(err.location.line, 2);
+        assert!(err.message.contains("key = value"));
+
+        let err = parse_ftl("n = { $n ->\n  *[one] one\n}\n", "de.ftl").unwrap_err();
+        assert!(err.message.contains("select expressions"));
+    }
+
+    #[test]
+    fn lists_missing_keys_per_locale() {
+        let m = messages();
+        let missing = m.missing_keys(&["nav.home".to_string(), "extra".to_string()]);
+        assert!(missing.contains(&("en".to_string(), "nav.home".to_string())));
+        assert!(missing.contains(&("de".to_string(), "extra".to_string())));
+        assert!(missing.contains(&("de".to_string(), "save.tooltip".to_string())));
+        assert!(!missing.iter().any(|(l, k)| l == "de" && k == "greeting"));
+    }
+
+    #[test]
+    fn loads_catalog_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("en.ftl"), "hi = Hi\n").unwrap();
+        std::fs::create_dir(dir.path().join("fr")).unwrap();
+        std::fs::write(
+            dir.path().join("fr").join("main.json"),
+            r#"{"hi": "Salut"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "ignored").unwrap();
+
+        let catalogs = load_catalogs(dir.path()).unwrap();
+        assert_eq!(catalogs.keys().collect::<Vec<_>>(), ["en", "fr"]);
+        assert_eq!(catalogs["fr"]["hi"], "Salut");
+    }
+
+    #[test]
+    fn records_and_collects_translated_attributes() {
+        let mut config = crate::XmlParser::new()
+            .parse(
+                r#"<nemo><layout>
+                     <label id="hello" text="${t('greeting')}" tooltip="plain" />
+                     <label id="live" bind_text="data.n" />
+                   </layout></nemo>"#,
+            )
+            .unwrap();
+        record_translations(&mut config);
+        let hello = config
+            .get("layout")
+            .and_then(|l| l.get("component"))
+            .and_then(|c| c.get("hello"))
+            .unwrap();
+        let attrs = hello.get("i18n_attrs").and_then(|v| v.as_object()).unwrap();
+        assert_eq!(attrs.keys().collect::<Vec<_>>(), ["text"]);
+        assert_eq!(collect_message_keys(&config), ["greeting"]);
+        assert_eq!(
+            message_keys_in("t(\"a\") format(x) t('b', value)"),
+            ["a", "b"]
+        );
+    }
+}
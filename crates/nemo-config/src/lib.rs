@@ -5,35 +5,55 @@
 
 mod directives;
 mod error;
+mod i18n;
 mod loader;
 mod location;
 mod manifest;
+mod overlay;
 mod path;
 mod pkg;
 mod registry;
 mod resolver;
 mod schema;
+mod secret;
+mod sfc_props;
+mod source_error;
 mod validator;
-pub use directives::{compile_directives, compile_directives_node};
+mod viewport;
+pub use directives::{check_directives, compile_directives, compile_directives_node};
 mod value;
 mod xml_parser;
 
 pub use error::{ConfigError, ErrorCode, ParseError, ResolveError, SchemaError, ValidationError};
+pub use i18n::{
+    collect_message_keys, load_catalogs, parse_ftl, parse_json_catalog, record_translations,
+    Catalog, Messages,
+};
 pub use loader::{ConfigurationLoader, DIST_LAYOUT_FILE};
 pub use location::SourceLocation;
 pub use manifest::{
-    find_project_root, BuildConfig, LoadMode, ManifestError, PackageConfig, ProjectManifest,
-    MANIFEST_FILE,
+    find_project_root, BuildConfig, LoadMode, ManifestError, PackageConfig, Profile,
+    ProjectManifest, MANIFEST_FILE,
 };
+pub use overlay::{merge_overlay, Overlay, SetOverride};
 pub use path::{ConfigPath, PathParseError, PathSegment};
 pub use pkg::{is_module_path, package_dir, packages_dir, LockedPackage, Lockfile, LOCKFILE};
 pub use registry::SchemaRegistry;
 pub use resolver::{ConfigFunction, ConfigResolver, ResolveContext};
 pub use schema::{ConfigSchema, PropertySchema, ValidationRule, ValueType};
+pub use secret::{
+    redact, register_redaction, resolve_secret_refs, EncryptedFileProvider, RedactingWriter,
+    SecretError, SecretProvider, SecretProviderKind, SecretServiceProvider, Secrets, SecretsConfig,
+    PASSPHRASE_ENV, REDACTED,
+};
+pub use sfc_props::coerce_sfc_props;
+pub use source_error::{SourceError, ORIGIN_KEY, SCRIPT_ORIGIN_KEY};
 pub use validator::{ConfigValidator, ValidationResult};
 pub use value::Value;
+pub use viewport::ViewportCondition;
 pub use xml_parser::{
-    sfc_default_tag, sfc_definition_to_value, AppBlocks, SfcDefinition, SfcProp, SfcSlot, XmlParser,
+    coerce_prop_value, sfc_default_tag, sfc_definition_to_value, AppBlocks, SfcDefinition, SfcProp,
+    SfcSlot, XmlParser,
 };
 
 #[cfg(test)]
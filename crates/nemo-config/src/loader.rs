@@ -1,13 +1,15 @@
 //! Configuration loader - main entry point for loading configurations.
 
 use crate::error::ConfigError;
+use crate::overlay::{merge_overlay, Overlay, SetOverride};
 use crate::registry::SchemaRegistry;
 use crate::resolver::{ConfigResolver, ResolveContext};
+use crate::secret::Secrets;
 use crate::validator::{ConfigValidator, ValidationResult};
 use crate::xml_parser::XmlParser;
 use crate::Value;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 /// The serialized resolved-config file inside a built `dist/` tree.
 pub const DIST_LAYOUT_FILE: &str = "layout.json";
@@ -16,6 +18,21 @@ pub const DIST_LAYOUT_FILE: &str = "layout.json";
 pub struct ConfigurationLoader {
     validator: ConfigValidator,
     resolver: ConfigResolver,
+    /// The locale `${t(…)}` resolves into; the `<i18n default>` when unset.
+    locale: RwLock<Option<String>>,
+    /// The `nemo.toml` profile whose overlays [`load`](Self::load) applies.
+    profile: RwLock<Option<String>>,
+    /// Overlays applied after the profile's (the runtime's `overrides.xml`).
+    overlays: RwLock<Vec<Overlay>>,
+    /// `--set` overrides, applied last.
+    overrides: RwLock<Vec<SetOverride>>,
+    /// The store `${secret.name}` reads; discovered from the entry's project
+    /// when unset.
+    secrets: RwLock<Option<Secrets>>,
+    /// Leave `${secret.name}` unresolved (for `nemo build`).
+    defer_secrets: RwLock<bool>,
+    /// Record component origins (see [`XmlParser::with_origins`]).
+    origins: RwLock<bool>,
     #[allow(dead_code)]
     schema_registry: Arc<SchemaRegistry>,
 }
@@ -26,19 +43,236 @@ impl ConfigurationLoader {
         ConfigurationLoader {
             validator: ConfigValidator::new(Arc::clone(&schema_registry)),
             resolver: ConfigResolver::new(),
+            locale: RwLock::new(None),
+            profile: RwLock::new(None),
+            overlays: RwLock::new(Vec::new()),
+            overrides: RwLock::new(Vec::new()),
+            secrets: RwLock::new(None),
+            defer_secrets: RwLock::new(false),
+            origins: RwLock::new(false),
             schema_registry,
         }
     }
 
+    /// Sets the locale later loads and [`localize`](Self::localize) calls
+    /// translate `${t(…)}` into; `None` restores the app's default locale.
+    pub fn set_locale(&self, locale: Option<&str>) {
+        if let Ok(mut current) = self.locale.write() {
+            *current = locale.map(str::to_string);
+        }
+    }
+
+    /// Returns the locale set with [`set_locale`](Self::set_locale).
+    pub fn locale(&self) -> Option<String> {
+        self.locale.read().ok().and_then(|l| l.clone())
+    }
+
+    /// Selects the `nemo.toml` profile whose overlay files later loads merge
+    /// over the entry; `None` loads the entry as written.
+    pub fn set_profile(&self, profile: Option<&str>) {
+        if let Ok(mut current) = self.profile.write() {
+            *current = profile.map(str::to_string);
+        }
+    }
+
+    /// Returns the profile set with [`set_profile`](Self::set_profile).
+    pub fn profile(&self) -> Option<String> {
+        self.profile.read().ok().and_then(|p| p.clone())
+    }
+
+    /// Sets the overlays merged after the profile's, in order.
+    pub fn set_overlays(&self, overlays: Vec<Overlay>) {
+        if let Ok(mut current) = self.overlays.write() {
+            *current = overlays;
+        }
+    }
+
+    /// Sets the `--set` overrides applied after every overlay.
+    pub fn set_overrides(&self, overrides: Vec<SetOverride>) {
+        if let Ok(mut current) = self.overrides.write() {
+            *current = overrides;
+        }
+    }
+
+    /// Sets the store `${secret.name}` reads. `None` (the default) uses the
+    /// `[secrets]` store of the project each load's entry belongs to.
+    pub fn set_secrets(&self, secrets: Option<Secrets>) {
+        if let Ok(mut current) = self.secrets.write() {
+            *current = secrets;
+        }
+    }
+
+    /// Keeps `${secret.name}` references as written instead of resolving them,
+    /// so the result can be written to disk without the values.
+    /// [`load_from_dist`](Self::load_from_dist) resolves them at launch.
+    pub fn set_defer_secrets(&self, defer: bool) {
+        if let Ok(mut current) = self.defer_secrets.write() {
+            *current = defer;
+        }
+    }
+
+    /// Records where each component and SFC script was written
+    /// ([`ORIGIN_KEY`](crate::ORIGIN_KEY) /
+    /// [`SCRIPT_ORIGIN_KEY`](crate::SCRIPT_ORIGIN_KEY), as
+    /// `file:line:column`), so runtime errors can point back at the source.
+    /// Off by default.
+    pub fn set_origins(&self, origins: bool) {
+        if let Ok(mut current) = self.origins.write() {
+            *current = origins;
+        }
+    }
+
+    /// The explicit store, else the one of the project containing `base`.
+    fn secrets_for(&self, base: Option<&Path>) -> Option<Secrets> {
+        self.secrets
+            .read()
+            .ok()
+            .and_then(|s| s.as_ref().map(Secrets::for_load))
+            .or_else(|| base.map(Secrets::discover))
+    }
+
+    /// Reads a `<nemo>` XML overlay file, parsed but not resolved.
+    pub fn read_overlay(&self, path: &Path) -> Result<Overlay, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let source_name = path.display().to_string();
+        let value = self
+            .parser_for(&source_name, path.parent())
+            .parse(&content)
+            .map_err(ConfigError::Parse)?;
+        Ok(Overlay::new(source_name, value))
+    }
+
+    /// Merges the selected profile's overlays, then the
+    /// [`set_overlays`](Self::set_overlays) layers, then the `--set`
+    /// overrides into `config`. `entry` locates the project's `nemo.toml`.
+    ///
+    /// [`load`](Self::load) calls this on the unresolved tree; a built
+    /// `dist/` tree takes [`apply_overlays_to_dist`](Self::apply_overlays_to_dist).
+    pub fn apply_overlays(&self, config: &mut Value, entry: &Path) -> Result<(), ConfigError> {
+        for overlay in self.overlay_layers(entry)? {
+            merge_overlay(config, &overlay.value);
+        }
+        self.apply_set_overrides(config);
+        Ok(())
+    }
+
+    /// [`apply_overlays`](Self::apply_overlays) for a built `dist/` tree,
+    /// whose values were resolved at build time. Each overlay's `${…}` is
+    /// resolved before it is merged, against the tree's variables with every
+    /// layer's (and `--set var.*`) applied. Those variable overrides can't
+    /// re-flow into values the build already resolved, so they are reported
+    /// with a warning rather than dropped silently.
+    pub fn apply_overlays_to_dist(
+        &self,
+        config: &mut Value,
+        entry: &Path,
+    ) -> Result<(), ConfigError> {
+        let layers = self.overlay_layers(entry)?;
+        let mut variables = config.clone();
+        for overlay in &layers {
+            merge_overlay(&mut variables, &overlay.value);
+        }
+        self.apply_set_overrides(&mut variables);
+        let mut context = self.build_context(&variables);
+        context.secrets = self.secrets_for(entry.parent());
+
+        for overlay in &layers {
+            if let Some(names) = overlay.value.get("variable").and_then(|v| v.as_object()) {
+                for name in names.keys() {
+                    tracing::warn!(
+                        "{}: variable '{name}' only reaches the overlay's own ${{…}} on a built dist tree; load the source entry to apply it everywhere",
+                        overlay.source
+                    );
+                }
+            }
+            let resolved = self
+                .resolver
+                .resolve(overlay.value.clone(), &context)
+                .map_err(ConfigError::Resolve)?;
+            merge_overlay(config, &resolved);
+        }
+        if let Ok(overrides) = self.overrides.read() {
+            for o in overrides.iter().filter(|o| o.is_variable()) {
+                tracing::warn!(
+                    "--set {o} only reaches overlay ${{…}} on a built dist tree; load the source entry to apply it everywhere"
+                );
+            }
+        }
+        self.apply_set_overrides(config);
+        Ok(())
+    }
+
+    /// The selected profile's overlays followed by the
+    /// [`set_overlays`](Self::set_overlays) layers.
+    fn overlay_layers(&self, entry: &Path) -> Result<Vec<Overlay>, ConfigError> {
+        let mut layers = match self.profile() {
+            Some(profile) => self.profile_overlays(entry, &profile)?,
+            None => Vec::new(),
+        };
+        if let Ok(overlays) = self.overlays.read() {
+            layers.extend(overlays.iter().cloned());
+        }
+        Ok(layers)
+    }
+
+    /// Writes the `--set` overrides into `config`.
+    fn apply_set_overrides(&self, config: &mut Value) {
+        if let Ok(overrides) = self.overrides.read() {
+            for o in overrides.iter() {
+                if !o.apply(config) {
+                    tracing::warn!("Skipping --set {o}: the path runs through a non-object value");
+                }
+            }
+        }
+    }
+
+    /// Reads the overlay files of `profile` from the `nemo.toml` above `entry`.
+    fn profile_overlays(&self, entry: &Path, profile: &str) -> Result<Vec<Overlay>, ConfigError> {
+        let unknown = |available| ConfigError::UnknownProfile {
+            name: profile.to_string(),
+            available,
+        };
+        let Some(root) = crate::manifest::find_project_root(entry) else {
+            return Err(unknown(Vec::new()));
+        };
+        let manifest_path = root.join(crate::manifest::MANIFEST_FILE);
+        let manifest =
+            crate::ProjectManifest::load(&manifest_path).map_err(|e| ConfigError::Io {
+                path: manifest_path.display().to_string(),
+                message: e.to_string(),
+            })?;
+        let Some(selected) = manifest.profiles.get(profile) else {
+            return Err(unknown(manifest.profile_names()));
+        };
+        selected
+            .overlays
+            .iter()
+            .map(|file| self.read_overlay(&root.join(file)))
+            .collect()
+    }
+
+    /// Resolves one `${…}` text (typically an `i18n_attrs` entry) against a
+    /// loaded config's variables and catalogs, in the current locale.
+    pub fn localize(&self, text: &str, config: &Value) -> Result<Value, ConfigError> {
+        let context = self.build_context(config);
+        self.resolver
+            .resolve(Value::String(text.to_string()), &context)
+            .map_err(ConfigError::Resolve)
+    }
+
     /// Loads an application entry file. The entry MUST be an `app.nemo` SFC:
     /// it is compiled to a `Value` tree ([`compile_app_sfc`](crate::XmlParser::compile_app_sfc)),
+    /// patched with the configured overlays ([`apply_overlays`](Self::apply_overlays)),
     /// then run through directive-compile + `${}` resolution.
     ///
     /// A non-`.nemo` path (a legacy `app.xml` entry) is rejected with
     /// [`ConfigError::DeprecatedXmlEntry`]. XML is still parsed for `<include>`
-    /// fragments (via the parser directly) and the `overrides.xml` settings
-    /// overlay (via [`load_xml_string`](Self::load_xml_string)) — just not as an
-    /// application entry.
+    /// fragments (via the parser directly) and for overlays (profile files and
+    /// `overrides.xml`, via [`read_overlay`](Self::read_overlay)) — just not as
+    /// an application entry.
     pub fn load(&self, path: &Path) -> Result<Value, ConfigError> {
         if !path.extension().map(|e| e == "nemo").unwrap_or(false) {
             return Err(ConfigError::DeprecatedXmlEntry {
@@ -52,7 +286,12 @@ impl ConfigurationLoader {
         })?;
 
         let source_name = path.display().to_string();
-        self.load_nemo_string(&content, &source_name, path.parent())
+        let mut raw_value = self
+            .parser_for(&source_name, path.parent())
+            .compile_app_sfc(&content)
+            .map_err(ConfigError::Parse)?;
+        self.apply_overlays(&mut raw_value, path)?;
+        self.compile_resolve(&mut raw_value, path.parent())
     }
 
     /// Loads configuration from an XML string.
@@ -64,7 +303,7 @@ impl ConfigurationLoader {
     ) -> Result<Value, ConfigError> {
         let parser = self.parser_for(source_name, base_dir);
         let mut raw_value = parser.parse(content).map_err(ConfigError::Parse)?;
-        self.compile_resolve(&mut raw_value)
+        self.compile_resolve(&mut raw_value, base_dir)
     }
 
     /// Loads an `app.nemo` SFC string: compiles it to the same `Value` tree
@@ -80,7 +319,7 @@ impl ConfigurationLoader {
         let mut raw_value = parser
             .compile_app_sfc(content)
             .map_err(ConfigError::Parse)?;
-        self.compile_resolve(&mut raw_value)
+        self.compile_resolve(&mut raw_value, base_dir)
     }
 
     /// Builds an [`XmlParser`] configured with the source name, base directory,
@@ -88,6 +327,9 @@ impl ConfigurationLoader {
     /// versions so remote module imports resolve.
     fn parser_for(&self, source_name: &str, base_dir: Option<&Path>) -> XmlParser {
         let mut parser = XmlParser::new().with_source_name(source_name);
+        if self.origins.read().map(|o| *o).unwrap_or(false) {
+            parser = parser.with_origins();
+        }
         if let Some(dir) = base_dir {
             parser = parser.with_base_dir(dir);
             if let Some(root) = crate::manifest::find_project_root(dir) {
@@ -100,38 +342,70 @@ impl ConfigurationLoader {
         parser
     }
 
-    /// Runs the shared post-parse pipeline: directive compilation then `${}`
-    /// expression resolution.
-    fn compile_resolve(&self, raw_value: &mut Value) -> Result<Value, ConfigError> {
+    /// Runs the shared post-parse pipeline: directive compilation, `${}`
+    /// expression resolution (translating `${t(…)}` into the current
+    /// locale and reading `${secret.name}` from the store of the project at
+    /// `base_dir`), then SFC prop coercion.
+    fn compile_resolve(
+        &self,
+        raw_value: &mut Value,
+        base_dir: Option<&Path>,
+    ) -> Result<Value, ConfigError> {
         // Compile control-flow directives (n:if / n:for) in the layout and
         // SFC templates before resolution — the pass rewrites the Value tree
         // into ordinary nodes (or list-container nodes for live-data n:for).
+        // Misplaced n:else/n:else-if/n:case would silently render, so they
+        // fail the load instead.
+        let errors = crate::check_directives(raw_value);
+        if !errors.is_empty() {
+            return Err(ConfigError::Validation { errors });
+        }
         crate::compile_directives(raw_value);
+        // Keep the `${t(…)}` source of translated attributes so a locale
+        // switch can re-render them after resolution replaces the text.
+        crate::record_translations(raw_value);
 
         // Build resolve context from the parsed config, then resolve expressions.
-        let context = self.build_context(raw_value);
-        let resolved = self
+        let mut context = self.build_context(raw_value);
+        context.defer_secrets = self.defer_secrets.read().map(|d| *d).unwrap_or(false);
+        if !context.defer_secrets {
+            context.secrets = self.secrets_for(base_dir);
+        }
+        let mut resolved = self
             .resolver
             .resolve(std::mem::take(raw_value), &context)
             .map_err(ConfigError::Resolve)?;
+
+        // Coerce SFC instance props to their declared types, once `${}`
+        // expressions in instance attributes have been resolved.
+        let errors = crate::coerce_sfc_props(&mut resolved);
+        if !errors.is_empty() {
+            return Err(ConfigError::Validation { errors });
+        }
         Ok(resolved)
     }
 
     /// Loads a pre-built `dist/` tree produced by `nemo build`: deserializes
     /// `<dir>/layout.json` (a serialized, fully-resolved config `Value`) back into
     /// the same `Value` the source path produces — with no XML parse, `${}`
-    /// resolution, or `<import>`/`<include>` file reads. This is opt-in; the
-    /// default launch path stays on [`load`], and `nemo dev` never uses it.
+    /// resolution, or `<import>`/`<include>` file reads — except the
+    /// `${secret.name}` references the build kept out of the tree, which are
+    /// read from the project's store here. This is opt-in; the default launch
+    /// path stays on [`load`], and `nemo dev` never uses it.
     pub fn load_from_dist(&self, dir: &Path) -> Result<Value, ConfigError> {
         let path = dir.join(DIST_LAYOUT_FILE);
         let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::Io {
             path: path.display().to_string(),
             message: e.to_string(),
         })?;
-        serde_json::from_str(&content).map_err(|e| ConfigError::Io {
+        let mut value: Value = serde_json::from_str(&content).map_err(|e| ConfigError::Io {
             path: path.display().to_string(),
             message: format!("invalid dist {DIST_LAYOUT_FILE}: {e}"),
-        })
+        })?;
+        if let Some(secrets) = self.secrets_for(Some(dir)) {
+            crate::secret::resolve_secret_refs(&mut value, &secrets)?;
+        }
+        Ok(value)
     }
 
     /// Loads and validates configuration against a schema.
@@ -176,6 +450,9 @@ impl ConfigurationLoader {
             }
         }
 
+        context.messages =
+            crate::Messages::from_config(config).map(|m| m.with_locale(self.locale().as_deref()));
+
         context
     }
 }
@@ -333,4 +610,228 @@ mod tests {
             Some(&Value::String("Hello World".to_string()))
         );
     }
+
+    #[test]
+    fn test_load_translates_with_i18n_catalogs() {
+        let dir = tempfile::tempdir().unwrap();
+        let locales = dir.path().join("locales");
+        std::fs::create_dir(&locales).unwrap();
+        std::fs::write(locales.join("en.ftl"), "greeting = Hello\n").unwrap();
+        std::fs::write(locales.join("de.json"), r#"{"greeting": "Hallo"}"#).unwrap();
+
+        let loader = create_test_loader();
+        let content = r#"
+        <nemo>
+            <i18n dir="locales" default="en" />
+            <layout type="stack">
+                <label id="lbl" text="${t('greeting')}" />
+            </layout>
+        </nemo>
+        "#;
+
+        let value = loader
+            .load_xml_string(content, "test.xml", Some(dir.path()))
+            .unwrap();
+        let lbl = value
+            .get("layout")
+            .unwrap()
+            .get("component")
+            .unwrap()
+            .get("lbl")
+            .unwrap();
+        assert_eq!(lbl.get("text"), Some(&Value::String("Hello".to_string())));
+
+        // The recorded source re-renders in another locale.
+        let source = lbl.get("i18n_attrs").and_then(|a| a.get("text")).unwrap();
+        loader.set_locale(Some("de-DE"));
+        let text = loader.localize(source.as_str().unwrap(), &value).unwrap();
+        assert_eq!(text, Value::String("Hallo".to_string()));
+    }
+
+    #[test]
+    fn test_load_applies_profile_overlays_then_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("nemo.toml"),
+            "name = \"app\"\n[profiles.staging]\noverlays = [\"profiles/staging.xml\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("profiles")).unwrap();
+        std::fs::write(
+            dir.path().join("profiles/staging.xml"),
+            r#"<nemo>
+                 <variable name="host" default="staging.example.com" />
+                 <variable name="beta" default="true" />
+               </nemo>"#,
+        )
+        .unwrap();
+        let entry = dir.path().join("app.nemo");
+        std::fs::write(
+            &entry,
+            r#"<variable name="host" default="localhost" />
+               <variable name="beta" default="false" />
+               <data>
+                 <source name="api" type="http" url="https://${var.host}/v1" />
+               </data>
+               <template name="app">
+                 <stack id="root"><label id="flag" text="${var.beta}" /></stack>
+               </template>"#,
+        )
+        .unwrap();
+
+        let loader = create_test_loader();
+        let url = |value: &Value| {
+            value
+                .get("data")
+                .and_then(|d| d.get("source"))
+                .and_then(|s| s.get("api"))
+                .and_then(|a| a.get("url"))
+                .cloned()
+        };
+        let base = loader.load(&entry).unwrap();
+        assert_eq!(
+            url(&base),
+            Some(Value::String("https://localhost/v1".into()))
+        );
+
+        // The profile's variables re-flow into `${var.host}`; `--set` wins
+        // over the profile.
+        loader.set_profile(Some("staging"));
+        loader.set_overrides(vec![SetOverride::parse("var.beta=false").unwrap()]);
+        let staging = loader.load(&entry).unwrap();
+        assert_eq!(
+            url(&staging),
+            Some(Value::String("https://staging.example.com/v1".into()))
+        );
+        let flag = staging
+            .get("layout")
+            .and_then(|l| l.get("component"))
+            .and_then(|c| c.get("flag"))
+            .and_then(|f| f.get("text"))
+            .cloned();
+        assert_eq!(flag, Some(Value::Bool(false)));
+
+        loader.set_profile(Some("prod"));
+        let err = loader.load(&entry).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile 'prod' (nemo.toml declares: staging)"
+        );
+    }
+
+    #[test]
+    fn test_dist_overlays_resolve_before_merging() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("nemo.toml"),
+            "name = \"app\"\n[profiles.staging]\noverlays = [\"staging.xml\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("staging.xml"),
+            r#"<nemo>
+                 <variable name="host" default="staging.example.com" />
+                 <data>
+                   <source name="api" type="http" url="https://${var.host}/${var.version}" />
+                 </data>
+               </nemo>"#,
+        )
+        .unwrap();
+        let entry = dir.path().join("app.nemo");
+        std::fs::write(
+            &entry,
+            r#"<variable name="host" default="localhost" />
+               <variable name="version" default="v1" />
+               <template name="app">
+                 <stack id="root"><label id="host" text="${var.host}" /></stack>
+               </template>"#,
+        )
+        .unwrap();
+
+        let loader = create_test_loader();
+        let mut dist = loader.load(&entry).unwrap();
+        loader.set_profile(Some("staging"));
+        loader.set_overrides(vec![SetOverride::parse("var.version=v2").unwrap()]);
+        loader.apply_overlays_to_dist(&mut dist, &entry).unwrap();
+
+        // The overlay's `${…}` sees the overridden variables; values the build
+        // already resolved keep theirs.
+        let url = dist
+            .get("data")
+            .and_then(|d| d.get("source"))
+            .and_then(|s| s.get("api"))
+            .and_then(|a| a.get("url"))
+            .cloned();
+        assert_eq!(
+            url,
+            Some(Value::String("https://staging.example.com/v2".into()))
+        );
+        let text = dist
+            .get("layout")
+            .and_then(|l| l.get("component"))
+            .and_then(|c| c.get("host"))
+            .and_then(|h| h.get("text"))
+            .cloned();
+        assert_eq!(text, Some(Value::String("localhost".into())));
+    }
+
+    #[test]
+    fn test_secrets_resolve_or_stay_deferred_for_dist() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = Secrets::new(
+            crate::EncryptedFileProvider::new(
+                dir.path().join("secrets.json"),
+                Some("pass".to_string()),
+            )
+            .with_iterations(1_000),
+        );
+        secrets.set("api_token", "tok-5e3a91").unwrap();
+        let xml = r#"<nemo>
+            <data>
+              <source name="api" type="http" url="https://example.com"
+                      header="Bearer ${secret.api_token}" />
+            </data>
+        </nemo>"#;
+        let header = |value: &Value| {
+            value
+                .get("data")
+                .and_then(|d| d.get("source"))
+                .and_then(|s| s.get("api"))
+                .and_then(|a| a.get("header"))
+                .cloned()
+        };
+
+        let loader = create_test_loader();
+        loader.set_secrets(Some(secrets));
+        let live = loader.load_xml_string(xml, "app.xml", None).unwrap();
+        assert_eq!(
+            header(&live),
+            Some(Value::String("Bearer tok-5e3a91".into()))
+        );
+
+        // A build keeps the reference; loading the dist tree resolves it.
+        loader.set_defer_secrets(true);
+        let built = loader.load_xml_string(xml, "app.xml", None).unwrap();
+        assert_eq!(
+            header(&built),
+            Some(Value::String("Bearer ${secret.api_token}".into()))
+        );
+        std::fs::write(
+            dir.path().join(DIST_LAYOUT_FILE),
+            serde_json::to_string(&built).unwrap(),
+        )
+        .unwrap();
+        let launched = loader.load_from_dist(dir.path()).unwrap();
+        assert_eq!(header(&launched), header(&live));
+
+        loader.set_defer_secrets(false);
+        let err = loader
+            .load_xml_string(
+                r#"<nemo><variable name="x" default="${secret.nope}" /></nemo>"#,
+                "app.xml",
+                None,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("nemo secret set nope"), "{err}");
+    }
 }
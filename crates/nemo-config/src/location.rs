@@ -2,8 +2,10 @@
 
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 /// A location in source code.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SourceLocation {
     /// The file path or name.
     pub file: String,
@@ -32,6 +34,46 @@ impl SourceLocation {
         }
     }
 
+    /// The location of byte `offset` in `source` (clamped to its length).
+    pub fn from_offset(file: impl Into<String>, source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        SourceLocation::new(file, line, column)
+    }
+
+    /// Parses the `file:line:column` form [`Display`](fmt::Display) produces.
+    /// The file part may itself contain colons (e.g. a Windows drive).
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next().filter(|file| !file.is_empty())?;
+        Some(SourceLocation::new(file, line, column))
+    }
+
+    /// The byte offset of this location in `source`, if it lies within it.
+    pub fn offset_in(&self, source: &str) -> Option<usize> {
+        if self.line == 0 {
+            return None;
+        }
+        let mut start = 0;
+        for _ in 1..self.line {
+            start += source[start..].find('\n')? + 1;
+        }
+        let line = source[start..].split('\n').next().unwrap_or("");
+        let column = line
+            .char_indices()
+            .nth(self.column.saturating_sub(1))
+            .map_or(line.len(), |(i, _)| i);
+        Some(start + column)
+    }
+
     /// Returns true if this location is unknown.
     pub fn is_unknown(&self) -> bool {
         self.line == 0 && self.column == 0
@@ -101,4 +143,19 @@ mod tests {
         assert!(context.contains("line 3"));
         assert!(context.contains("line 4"));
     }
+
+    #[test]
+    fn test_offset_round_trip() {
+        let source = "<nemo>\n  <label id=\"a\"/>\n</nemo>";
+        let offset = source.find("<label").unwrap();
+        let loc = SourceLocation::from_offset("app.nemo", source, offset);
+        assert_eq!(loc, SourceLocation::new("app.nemo", 2, 3));
+        assert_eq!(loc.offset_in(source), Some(offset));
+        assert_eq!(SourceLocation::parse(&loc.to_string()), Some(loc));
+        assert_eq!(
+            SourceLocation::parse("C:/apps/app.nemo:4:1"),
+            Some(SourceLocation::new("C:/apps/app.nemo", 4, 1))
+        );
+        assert_eq!(SourceLocation::parse("app.nemo"), None);
+    }
 }
@@ -1,10 +1,12 @@
 //! Project manifest (`nemo.toml`) — the per-project build/dependency manifest.
 //!
 //! A `nemo.toml` at a project root names the app entry (`entry`), the build
-//! output directory and load mode (`[build]`), and remote component-library
-//! dependencies (`[dependencies]`). It is **optional and additive**: pointing
-//! `nemo --app-config app.xml` at a single file keeps working with no manifest,
-//! and `nemo dev` never consults `[build] load`.
+//! output directory and load mode (`[build]`), remote component-library
+//! dependencies (`[dependencies]`), environment profiles (`[profiles]`)
+//! that `--profile` selects, and the secret store (`[secrets]`). It is
+//! **optional and additive**: pointing `nemo --app-config app.xml` at a single
+//! file keeps working with no manifest, and `nemo dev` never consults
+//! `[build] load`.
 //!
 //! Name distinction: the global user-prefs TOML is `config.toml`
 //! (`crates/nemo/src/config/`, the `--config` flag); this per-project manifest
@@ -15,6 +17,8 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::secret::SecretsConfig;
+
 /// The manifest filename that marks a project root.
 pub const MANIFEST_FILE: &str = "nemo.toml";
 
@@ -47,6 +51,17 @@ pub struct ProjectManifest {
     /// deterministic for display and future lockfile emission.
     #[serde(default)]
     pub dependencies: BTreeMap<String, String>,
+
+    /// Environment profiles (`[profiles.<name>]`), selected at launch with
+    /// `--profile <name>`. See
+    /// [`ConfigurationLoader::apply_overlays`](crate::ConfigurationLoader::apply_overlays)
+    /// for how they layer.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Where `${secret.name}` values are stored (`[secrets]`).
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
 fn default_entry() -> String {
@@ -92,6 +107,17 @@ pub struct PackageConfig {
     pub exports: Vec<String>,
 }
 
+/// A `[profiles.<name>]` table — overlay files patched over the entry when the
+/// profile is selected.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// `<nemo>` XML overlay files, relative to the project root, merged in
+    /// order (later files win).
+    #[serde(default)]
+    pub overlays: Vec<String>,
+}
+
 /// Where the launcher loads the resolved layout from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -139,6 +165,11 @@ impl ProjectManifest {
             source,
         })
     }
+
+    /// Returns the names of the declared profiles, sorted.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
 }
 
 /// Walks up from `start` (a file or directory) looking for the nearest
@@ -200,6 +231,7 @@ mod tests {
         assert_eq!(m.build.load, LoadMode::Source);
         assert!(m.dependencies.is_empty());
         assert!(m.package.is_none());
+        assert!(m.profiles.is_empty());
     }
 
     #[test]
@@ -224,6 +256,39 @@ mod tests {
         assert!(m.package.unwrap().exports.is_empty());
     }
 
+    #[test]
+    fn parses_profiles() {
+        let m = ProjectManifest::parse(
+            r#"name = "app"
+               [profiles.staging]
+               overlays = ["profiles/common.xml", "profiles/staging.xml"]
+               [profiles.dev]"#,
+        )
+        .unwrap();
+        assert_eq!(m.profile_names(), ["dev", "staging"]);
+        assert_eq!(
+            m.profiles["staging"].overlays,
+            ["profiles/common.xml", "profiles/staging.xml"]
+        );
+        assert!(m.profiles["dev"].overlays.is_empty());
+    }
+
+    #[test]
+    fn parses_secrets() {
+        let m = ProjectManifest::parse(r#"name = "app""#).unwrap();
+        assert_eq!(m.secrets, SecretsConfig::default());
+
+        let m = ProjectManifest::parse(
+            r#"name = "app"
+               [secrets]
+               provider = "file"
+               file = "config/secrets.json""#,
+        )
+        .unwrap();
+        assert_eq!(m.secrets.provider, crate::SecretProviderKind::File);
+        assert_eq!(m.secrets.file, "config/secrets.json");
+    }
+
     #[test]
     fn missing_name_is_an_error() {
         assert!(ProjectManifest::parse(r#"entry = "app.xml""#).is_err());
@@ -0,0 +1,223 @@
+//! Config overlays — the layers merged over an app entry before resolution.
+//!
+//! A launch can patch the entry without editing it. Layers apply in a fixed
+//! order, each winning over the ones before it:
+//!
+//! 1. the entry (`app.nemo`);
+//! 2. the overlay files of the `nemo.toml` profile picked with `--profile`
+//!    (`[profiles.<name>] overlays = [...]`), in the order listed;
+//! 3. the `overrides.xml` settings overlay the runtime writes;
+//! 4. `--set key=value` overrides from the command line.
+//!
+//! An overlay file is a plain `<nemo>` XML fragment, the same shape as
+//! `overrides.xml`. It is parsed but not resolved, then deep-merged into the
+//! entry's raw `Value` tree ([`merge_overlay`]): objects merge key by key, and
+//! anything else (scalars, arrays such as a source's `topic` list) replaces the
+//! entry's value. Because the merge happens before `${}` resolution, patching a
+//! `<variable>` default re-flows into every `${var.x}` that uses it.
+
+use crate::path::{ConfigPath, PathSegment};
+use crate::Value;
+use indexmap::IndexMap;
+use std::fmt;
+
+/// One parsed overlay layer and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    /// The file (or other origin) the layer was read from, for diagnostics.
+    pub source: String,
+    /// The unresolved config tree to merge.
+    pub value: Value,
+}
+
+impl Overlay {
+    /// Creates an overlay from an already-parsed tree.
+    pub fn new(source: impl Into<String>, value: Value) -> Self {
+        Overlay {
+            source: source.into(),
+            value,
+        }
+    }
+}
+
+/// Deep-merges `overlay` into `base`: objects merge recursively, any other
+/// overlay value replaces the base's.
+pub fn merge_overlay(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_overlay(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// A `--set key=value` override.
+///
+/// `key` is a config path (`data.source.prices.url`, `app.window.width`).
+/// `var.<name>` is shorthand for the variable's default,
+/// `variable.<name>.default`. The value is typed the way an XML attribute is:
+/// `true`/`false`, numbers and JSON arrays are coerced, anything else stays a
+/// string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetOverride {
+    /// The path the value is written to.
+    pub path: ConfigPath,
+    /// The coerced value.
+    pub value: Value,
+}
+
+impl SetOverride {
+    /// Parses a `key=value` spec. Usable directly as a clap `value_parser`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (key, raw) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got '{spec}'"))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("missing key in '{spec}'"));
+        }
+        let key = match key.strip_prefix("var.") {
+            Some(name) => format!("variable.{name}.default"),
+            None => key.to_string(),
+        };
+        let path = ConfigPath::parse(&key).map_err(|e| format!("invalid key '{key}': {e}"))?;
+        Ok(SetOverride {
+            path,
+            value: crate::xml_parser::coerce_value(raw),
+        })
+    }
+
+    /// Writes the value into `config`, creating intermediate objects as
+    /// needed. An index past the end of an array, or a path through a
+    /// scalar, leaves `config` unchanged and returns `false`.
+    pub fn apply(&self, config: &mut Value) -> bool {
+        let mut node = config;
+        for segment in self.path.segments() {
+            node = match segment {
+                PathSegment::Key(key) => {
+                    if node.is_null() {
+                        *node = Value::Object(IndexMap::new());
+                    }
+                    let Value::Object(map) = node else {
+                        return false;
+                    };
+                    map.entry(key.clone()).or_insert(Value::Null)
+                }
+                PathSegment::Index(index) => match node {
+                    Value::Array(items) if *index < items.len() => &mut items[*index],
+                    _ => return false,
+                },
+            };
+        }
+        *node = self.value.clone();
+        true
+    }
+
+    /// Whether this overrides a variable (`var.<name>` or `variable.…`).
+    pub fn is_variable(&self) -> bool {
+        matches!(self.path.segments().first(), Some(PathSegment::Key(key)) if key == "variable")
+    }
+}
+
+impl fmt::Display for SetOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.path, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XmlParser;
+
+    #[test]
+    fn overlay_patches_sources_and_variables() {
+        let mut base = XmlParser::new()
+            .parse(
+                r#"<nemo>
+                  <variable name="api" default="http://localhost:8080" />
+                  <data>
+                    <source name="prices" type="mqtt" host="localhost">
+                      <topic value="dev/prices" />
+                    </source>
+                  </data>
+                </nemo>"#,
+            )
+            .unwrap();
+        let overlay = XmlParser::new()
+            .parse(
+                r#"<nemo>
+                  <variable name="api" default="https://staging.example.com" />
+                  <data>
+                    <source name="prices" host="broker.staging">
+                      <topic value="staging/prices" />
+                    </source>
+                  </data>
+                </nemo>"#,
+            )
+            .unwrap();
+        merge_overlay(&mut base, &overlay);
+
+        let api = base.get("variable").and_then(|v| v.get("api")).unwrap();
+        assert_eq!(
+            api.get("default"),
+            Some(&Value::String("https://staging.example.com".into()))
+        );
+        let prices = base
+            .get("data")
+            .and_then(|d| d.get("source"))
+            .and_then(|s| s.get("prices"))
+            .unwrap();
+        // Untouched keys survive; patched ones win; lists are replaced.
+        assert_eq!(prices.get("type"), Some(&Value::String("mqtt".into())));
+        assert_eq!(
+            prices.get("host"),
+            Some(&Value::String("broker.staging".into()))
+        );
+        assert_eq!(
+            prices.get("topic"),
+            Some(&Value::Array(vec![Value::String("staging/prices".into())]))
+        );
+    }
+
+    #[test]
+    fn set_override_parses_and_applies() {
+        let mut config = XmlParser::new()
+            .parse(r#"<nemo><variable name="beta" default="false" /></nemo>"#)
+            .unwrap();
+
+        let beta = SetOverride::parse("var.beta=true").unwrap();
+        assert_eq!(beta.path.to_string(), "variable.beta.default");
+        assert!(beta.apply(&mut config));
+        let width = SetOverride::parse("app.window.width=1024").unwrap();
+        assert!(width.apply(&mut config));
+
+        assert_eq!(
+            config
+                .get("variable")
+                .and_then(|v| v.get("beta"))
+                .and_then(|b| b.get("default")),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            config
+                .get("app")
+                .and_then(|a| a.get("window"))
+                .and_then(|w| w.get("width")),
+            Some(&Value::Integer(1024))
+        );
+
+        assert!(SetOverride::parse("no-equals").is_err());
+        assert!(SetOverride::parse("=1").is_err());
+        assert!(!SetOverride::parse("variable.beta.default.x=1")
+            .unwrap()
+            .apply(&mut config));
+    }
+}
@@ -1,6 +1,8 @@
 //! Configuration expression resolver.
 
 use crate::error::ResolveError;
+use crate::i18n::Messages;
+use crate::secret::Secrets;
 use crate::Value;
 use indexmap::IndexMap;
 use std::collections::HashMap;
@@ -14,6 +16,13 @@ pub struct ResolveContext {
     pub config: Value,
     /// Environment variables.
     pub env: HashMap<String, String>,
+    /// Message catalogs for `t()`, with the locale to translate into.
+    pub messages: Option<Messages>,
+    /// The project's secret store for `${secret.name}`.
+    pub secrets: Option<Secrets>,
+    /// Leave `${secret.name}` unresolved (as its literal text), so a built
+    /// tree never holds secret values. See [`resolve_secret_refs`](crate::resolve_secret_refs).
+    pub defer_secrets: bool,
 }
 
 impl ResolveContext {
@@ -106,8 +115,15 @@ impl ConfigResolver {
                         // The `list_binding` metadata (from live-data `n:for`)
                         // stores a loop template with runtime `${item.*}`
                         // placeholders resolved per-instance by the runtime, not
-                        // at load time. Pass it through verbatim like `sfc`.
-                        if k == "sfc" || k == "list_binding" {
+                        // at load time. Pass it through verbatim like `sfc`, and
+                        // likewise a `<virtual-list>`'s per-row `item_template`.
+                        // `i18n` holds message patterns (`{ $name }`), and
+                        // `i18n_attrs` the `${t(…)}` source text the runtime
+                        // re-resolves on a locale switch.
+                        if matches!(
+                            k.as_str(),
+                            "sfc" | "list_binding" | "item_template" | "i18n" | "i18n_attrs"
+                        ) {
                             Ok((k, v))
                         } else {
                             self.resolve(v, context).map(|rv| (k, rv))
@@ -255,6 +271,21 @@ impl ConfigResolver {
             }
         }
 
+        // Secret reference: secret.name
+        if let Some(name) = expr.strip_prefix("secret.") {
+            let name = name.trim();
+            if context.defer_secrets {
+                return Ok(Value::String(format!("${{secret.{name}}}")));
+            }
+            return match &context.secrets {
+                Some(secrets) => secrets.resolve(name),
+                None => Err(ResolveError::Secret {
+                    name: name.to_string(),
+                    message: "no secret store is configured".to_string(),
+                }),
+            };
+        }
+
         // Variable reference: var.name or env.NAME
         if let Some(var_path) = expr.strip_prefix("var.") {
             return self.resolve_variable_path(var_path, context);
@@ -313,6 +344,11 @@ impl ConfigResolver {
         args_str: &str,
         context: &ResolveContext,
     ) -> Result<Value, ResolveError> {
+        // `t()` reads the context's catalogs, which a `ConfigFunction` can't.
+        if name == "t" {
+            return self.translate(args_str, context);
+        }
+
         let func = self
             .functions
             .get(name)
@@ -324,6 +360,23 @@ impl ConfigResolver {
         func.call(args)
     }
 
+    /// Evaluates `t("key")` / `t("key", args)`: the message for `key` in the
+    /// context's locale, or the key itself when no catalog has it.
+    fn translate(&self, args_str: &str, context: &ResolveContext) -> Result<Value, ResolveError> {
+        let mut args = self.parse_function_args(args_str, context)?.into_iter();
+        let Some(Value::String(key)) = args.next() else {
+            return Err(ResolveError::InvalidArgument {
+                function: "t".to_string(),
+                message: "expected a message key string".to_string(),
+            });
+        };
+        let params = args.next().unwrap_or(Value::Null);
+        Ok(Value::String(match &context.messages {
+            Some(messages) => messages.translate(&key, &params),
+            None => key,
+        }))
+    }
+
     /// Parses function arguments.
     fn parse_function_args(
         &self,
@@ -518,4 +571,26 @@ mod tests {
             .unwrap();
         assert_eq!(result, Value::String("yes".to_string()));
     }
+
+    #[test]
+    fn test_resolve_translation() {
+        let resolver = ConfigResolver::new();
+        let catalog = crate::i18n::parse_ftl("greeting = Hello, { $name }!\n", "en.ftl").unwrap();
+        let mut user = IndexMap::new();
+        user.insert("name".to_string(), Value::String("Ada".to_string()));
+        let mut context = ResolveContext::new().with_variable("user", Value::Object(user));
+        context.messages = Some(Messages::new(
+            IndexMap::from([("en".to_string(), catalog)]),
+            "en",
+        ));
+
+        let value = Value::String("${t('greeting', var.user)}".to_string());
+        let result = resolver.resolve(value, &context).unwrap();
+        assert_eq!(result, Value::String("Hello, Ada!".to_string()));
+
+        // Unknown keys render as the key; a missing key argument is an error.
+        let result = resolver.resolve_expression("t('nope')", &context).unwrap();
+        assert_eq!(result, Value::String("nope".to_string()));
+        assert!(resolver.resolve_expression("t(42)", &context).is_err());
+    }
 }
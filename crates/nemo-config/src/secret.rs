@@ -0,0 +1,828 @@
+//! Secrets — `${secret.name}` values read from a pluggable store.
+//!
+//! Credentials don't belong in `app.nemo` or in `${env.X}`. A `${secret.name}`
+//! expression resolves through the project's [`Secrets`] store, picked by the
+//! `[secrets]` table of `nemo.toml` ([`SecretsConfig`]):
+//!
+//! * `provider = "secret-service"` (the default) keeps secrets in the
+//!   freedesktop Secret Service (GNOME Keyring, KWallet) through libsecret's
+//!   `secret-tool`. When no service is reachable — CI, containers, tests — it
+//!   falls back to the encrypted file below.
+//! * `provider = "file"` keeps them in an encrypted JSON file
+//!   (`file = ".nemo/secrets.json"` by default). Each value is sealed with
+//!   AES-256-GCM under a key derived from the `NEMO_SECRET_PASSPHRASE`
+//!   passphrase (PBKDF2-HMAC-SHA256). Names stay readable, so `nemo secret
+//!   list` needs no passphrase.
+//!
+//! Every value a store hands out is registered for redaction: [`redact`]
+//! replaces it with [`REDACTED`] in any text, and [`RedactingWriter`] does the
+//! same for a log stream.
+
+use crate::error::ResolveError;
+use crate::manifest::{find_project_root, ProjectManifest, MANIFEST_FILE};
+use crate::Value;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// The environment variable holding the encrypted file's passphrase.
+pub const PASSPHRASE_ENV: &str = "NEMO_SECRET_PASSPHRASE";
+
+/// What a redacted secret is replaced with.
+pub const REDACTED: &str = "[redacted]";
+
+/// PBKDF2 rounds for a new store.
+const DEFAULT_ITERATIONS: u32 = 600_000;
+
+/// Plaintext sealed under the key so a wrong passphrase is caught on open.
+const CHECK_PLAINTEXT: &[u8] = b"nemo-secrets";
+
+/// Values shorter than this aren't redacted — they would mangle unrelated text.
+const MIN_REDACT_LEN: usize = 4;
+
+/// An error reading or writing a secret store.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    /// The encrypted file exists but no passphrase was given.
+    #[error("set {PASSPHRASE_ENV} to unlock {}", path.display())]
+    Locked { path: PathBuf },
+    /// The passphrase doesn't open the encrypted file.
+    #[error("wrong passphrase for {}", path.display())]
+    WrongPassphrase { path: PathBuf },
+    /// The store file couldn't be read or written.
+    #[error("failed to access {}: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+    /// The store file isn't a secrets file this version understands.
+    #[error("{} is not a valid secrets file: {message}", path.display())]
+    Corrupt { path: PathBuf, message: String },
+    /// A name that `${secret.name}` couldn't refer to.
+    #[error("invalid secret name '{0}': use letters, digits, '_', '-' and '.'")]
+    InvalidName(String),
+    /// The Secret Service rejected a request.
+    #[error("secret service: {0}")]
+    Service(String),
+}
+
+/// A backend that stores named secrets.
+pub trait SecretProvider: Send + Sync {
+    /// Describes the store for messages, e.g. `encrypted file .nemo/secrets.json`.
+    fn describe(&self) -> String;
+    /// Returns the secret's value, or `None` if it isn't set.
+    fn get(&self, name: &str) -> Result<Option<String>, SecretError>;
+    /// Stores (or replaces) a secret.
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretError>;
+    /// Returns the names of the stored secrets, sorted.
+    fn list(&self) -> Result<Vec<String>, SecretError>;
+}
+
+/// The on-disk shape of an [`EncryptedFileProvider`] store. Binary fields are
+/// hex: the salt, and each sealed value as nonce ‖ ciphertext ‖ tag.
+#[derive(Debug, Serialize, Deserialize)]
+struct SecretsFile {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    check: String,
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+/// Secrets in a passphrase-encrypted JSON file.
+///
+/// The derived key is kept for the file's salt and rounds, so only the first
+/// read pays for PBKDF2; a file re-created with a new salt derives again.
+pub struct EncryptedFileProvider {
+    path: PathBuf,
+    passphrase: Option<String>,
+    iterations: NonZeroU32,
+    key: Mutex<Option<CachedKey>>,
+}
+
+/// A derived key and the salt (hex) and rounds it was derived with.
+struct CachedKey {
+    salt: String,
+    iterations: u32,
+    key: [u8; 32],
+}
+
+impl EncryptedFileProvider {
+    /// Creates a provider for `path` unlocked with `passphrase`.
+    pub fn new(path: impl Into<PathBuf>, passphrase: Option<String>) -> Self {
+        EncryptedFileProvider {
+            path: path.into(),
+            passphrase,
+            iterations: NonZeroU32::new(DEFAULT_ITERATIONS).expect("non-zero"),
+            key: Mutex::new(None),
+        }
+    }
+
+    /// Creates a provider for `path` unlocked with [`PASSPHRASE_ENV`].
+    pub fn from_env(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, std::env::var(PASSPHRASE_ENV).ok())
+    }
+
+    /// Sets the PBKDF2 rounds used when this provider creates the file. An
+    /// existing file keeps the count it was created with.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = NonZeroU32::new(iterations.max(1)).expect("non-zero");
+        self
+    }
+
+    /// The store file's path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Option<SecretsFile>, SecretError> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(self.io_error(e)),
+        };
+        let file: SecretsFile =
+            serde_json::from_str(&content).map_err(|e| self.corrupt(e.to_string()))?;
+        if file.version != 1 {
+            return Err(self.corrupt(format!("unsupported version {}", file.version)));
+        }
+        Ok(Some(file))
+    }
+
+    fn write(&self, file: &SecretsFile) -> Result<(), SecretError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| self.io_error(e))?;
+        }
+        let content =
+            serde_json::to_string_pretty(file).map_err(|e| self.corrupt(e.to_string()))?;
+        std::fs::write(&self.path, content + "\n").map_err(|e| self.io_error(e))
+    }
+
+    /// Derives the file's key and checks it against the sealed check value.
+    fn unlock(&self, file: &SecretsFile) -> Result<LessSafeKey, SecretError> {
+        let passphrase = self
+            .passphrase
+            .as_deref()
+            .ok_or_else(|| SecretError::Locked {
+                path: self.path.clone(),
+            })?;
+        let iterations = NonZeroU32::new(file.iterations)
+            .ok_or_else(|| self.corrupt("zero iterations".into()))?;
+        let key = self.key_for(passphrase, &file.salt, iterations)?;
+        match open(&key, "", &file.check) {
+            Some(check) if check == CHECK_PLAINTEXT => Ok(key),
+            _ => Err(SecretError::WrongPassphrase {
+                path: self.path.clone(),
+            }),
+        }
+    }
+
+    /// The key for a salt (hex) and round count, derived once and then
+    /// reused while the file keeps them.
+    fn key_for(
+        &self,
+        passphrase: &str,
+        salt: &str,
+        iterations: NonZeroU32,
+    ) -> Result<LessSafeKey, SecretError> {
+        let mut cached = self.key.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(c) = cached
+            .as_ref()
+            .filter(|c| c.salt == salt && c.iterations == iterations.get())
+        {
+            return Ok(aead_key(&c.key));
+        }
+        let salt_bytes = from_hex(salt).ok_or_else(|| self.corrupt("bad salt".into()))?;
+        let key = derive_key(passphrase, &salt_bytes, iterations);
+        *cached = Some(CachedKey {
+            salt: salt.to_string(),
+            iterations: iterations.get(),
+            key,
+        });
+        Ok(aead_key(&key))
+    }
+
+    fn io_error(&self, e: io::Error) -> SecretError {
+        SecretError::Io {
+            path: self.path.clone(),
+            message: e.to_string(),
+        }
+    }
+
+    fn corrupt(&self, message: String) -> SecretError {
+        SecretError::Corrupt {
+            path: self.path.clone(),
+            message,
+        }
+    }
+}
+
+impl SecretProvider for EncryptedFileProvider {
+    fn describe(&self) -> String {
+        format!("encrypted file {}", self.path.display())
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, SecretError> {
+        let Some(file) = self.read()? else {
+            return Ok(None);
+        };
+        let Some(sealed) = file.secrets.get(name) else {
+            return Ok(None);
+        };
+        let key = self.unlock(&file)?;
+        let plain = open(&key, name, sealed)
+            .ok_or_else(|| self.corrupt(format!("secret '{name}' doesn't decrypt")))?;
+        String::from_utf8(plain)
+            .map(Some)
+            .map_err(|_| self.corrupt(format!("secret '{name}' isn't UTF-8")))
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretError> {
+        let (mut file, key) = match self.read()? {
+            Some(file) => {
+                let key = self.unlock(&file)?;
+                (file, key)
+            }
+            None => {
+                let passphrase = self
+                    .passphrase
+                    .as_deref()
+                    .ok_or_else(|| SecretError::Locked {
+                        path: self.path.clone(),
+                    })?;
+                let mut salt = [0u8; 16];
+                SystemRandom::new()
+                    .fill(&mut salt)
+                    .map_err(|_| self.corrupt("no system randomness".into()))?;
+                let salt = to_hex(&salt);
+                let key = self.key_for(passphrase, &salt, self.iterations)?;
+                let file = SecretsFile {
+                    version: 1,
+                    iterations: self.iterations.get(),
+                    salt,
+                    check: seal(&key, "", CHECK_PLAINTEXT)?,
+                    secrets: BTreeMap::new(),
+                };
+                (file, key)
+            }
+        };
+        file.secrets
+            .insert(name.to_string(), seal(&key, name, value.as_bytes())?);
+        self.write(&file)
+    }
+
+    fn list(&self) -> Result<Vec<String>, SecretError> {
+        Ok(self
+            .read()?
+            .map(|file| file.secrets.into_keys().collect())
+            .unwrap_or_default())
+    }
+}
+
+/// Secrets in the freedesktop Secret Service, through libsecret's
+/// `secret-tool`, falling back to an encrypted file when the service (or the
+/// tool) isn't available.
+pub struct SecretServiceProvider {
+    /// Distinguishes this project's items from other projects' (the root).
+    scope: String,
+    /// The `secret-tool` executable.
+    tool: PathBuf,
+    fallback: EncryptedFileProvider,
+}
+
+impl SecretServiceProvider {
+    /// Creates a provider whose items carry `scope`, backed by `fallback`.
+    pub fn new(scope: impl Into<String>, fallback: EncryptedFileProvider) -> Self {
+        SecretServiceProvider {
+            scope: scope.into(),
+            tool: PathBuf::from("secret-tool"),
+            fallback,
+        }
+    }
+
+    /// Uses another `secret-tool` executable.
+    pub fn with_tool(mut self, tool: impl Into<PathBuf>) -> Self {
+        self.tool = tool.into();
+        self
+    }
+
+    /// Runs `secret-tool` with this project's attributes appended. `None`
+    /// means the service isn't usable (no tool, no session bus).
+    fn run(&self, args: &[&str], extra: &[(&str, &str)], input: Option<&str>) -> Option<Output> {
+        let mut command = Command::new(&self.tool);
+        command.args(args);
+        command.args(["application", "nemo", "scope", &self.scope]);
+        for (key, value) in extra {
+            command.args([key, value]);
+        }
+        command
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = command.spawn().ok()?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.as_bytes()).ok()?;
+        }
+        let output = child.wait_with_output().ok()?;
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !output.status.success() && !stderr.is_empty() {
+            tracing::debug!("secret-tool unavailable, using the file fallback: {stderr}");
+            return None;
+        }
+        Some(Output {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        })
+    }
+}
+
+/// The parts of a `secret-tool` run the provider reads.
+struct Output {
+    success: bool,
+    stdout: String,
+}
+
+impl SecretProvider for SecretServiceProvider {
+    fn describe(&self) -> String {
+        format!(
+            "secret service (falling back to {})",
+            self.fallback.describe()
+        )
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, SecretError> {
+        match self.run(&["lookup"], &[("name", name)], None) {
+            Some(out) if out.success => Ok(Some(
+                out.stdout
+                    .strip_suffix('\n')
+                    .unwrap_or(&out.stdout)
+                    .to_string(),
+            )),
+            _ => self.fallback.get(name),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretError> {
+        let label = format!("--label=nemo: {name}");
+        match self.run(&["store", &label], &[("name", name)], Some(value)) {
+            Some(out) if out.success => Ok(()),
+            Some(_) => Err(SecretError::Service(format!("could not store '{name}'"))),
+            None => self.fallback.set(name, value),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<String>, SecretError> {
+        let mut names = self.fallback.list()?;
+        if let Some(out) = self.run(&["search", "--all"], &[], None) {
+            names.extend(
+                out.stdout
+                    .lines()
+                    .filter_map(|l| l.trim().strip_prefix("attribute.name = "))
+                    .map(str::to_string),
+            );
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+/// Which backend a project's `[secrets]` table selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretProviderKind {
+    /// The freedesktop Secret Service, falling back to the file (default).
+    #[default]
+    SecretService,
+    /// The encrypted file only.
+    File,
+}
+
+/// The `[secrets]` table of `nemo.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretsConfig {
+    /// The backend.
+    #[serde(default)]
+    pub provider: SecretProviderKind,
+    /// The encrypted file, relative to the project root. Defaults to
+    /// `.nemo/secrets.json`.
+    #[serde(default = "default_secrets_file")]
+    pub file: String,
+}
+
+fn default_secrets_file() -> String {
+    ".nemo/secrets.json".to_string()
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            provider: SecretProviderKind::default(),
+            file: default_secrets_file(),
+        }
+    }
+}
+
+/// A project's secret store. Cheap to clone; values it returns are
+/// registered for [`redact`].
+///
+/// `${secret.name}` resolution asks the provider once per name: clones share
+/// the memoized values, and [`for_load`](Self::for_load) starts afresh.
+#[derive(Clone)]
+pub struct Secrets {
+    provider: Arc<dyn SecretProvider>,
+    resolved: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Secrets {
+    /// Wraps a provider.
+    pub fn new(provider: impl SecretProvider + 'static) -> Self {
+        Secrets {
+            provider: Arc::new(provider),
+            resolved: Arc::default(),
+        }
+    }
+
+    /// The same store with nothing memoized, for a new config load to pick
+    /// up values changed since the last one.
+    pub fn for_load(&self) -> Self {
+        Secrets {
+            provider: Arc::clone(&self.provider),
+            resolved: Arc::default(),
+        }
+    }
+
+    /// The store `config` selects for the project at `root`, unlocked with
+    /// [`PASSPHRASE_ENV`].
+    pub fn for_project(root: &Path, config: &SecretsConfig) -> Self {
+        let file = EncryptedFileProvider::from_env(root.join(&config.file));
+        match config.provider {
+            SecretProviderKind::File => Self::new(file),
+            SecretProviderKind::SecretService => {
+                Self::new(SecretServiceProvider::new(root.display().to_string(), file))
+            }
+        }
+    }
+
+    /// The store of the project containing `start` (a file or directory):
+    /// the nearest `nemo.toml`'s `[secrets]`, or the defaults rooted at
+    /// `start`'s directory when there is no manifest.
+    pub fn discover(start: &Path) -> Self {
+        if let Some(root) = find_project_root(start) {
+            if let Ok(manifest) = ProjectManifest::load(&root.join(MANIFEST_FILE)) {
+                return Self::for_project(&root, &manifest.secrets);
+            }
+        }
+        let dir = if start.is_file() {
+            start.parent().unwrap_or(start)
+        } else {
+            start
+        };
+        Self::for_project(dir, &SecretsConfig::default())
+    }
+
+    /// Describes the backing store.
+    pub fn describe(&self) -> String {
+        self.provider.describe()
+    }
+
+    /// Returns a secret's value, registering it for redaction.
+    pub fn get(&self, name: &str) -> Result<Option<String>, SecretError> {
+        let value = self.provider.get(name)?;
+        if let Some(value) = &value {
+            register_redaction(value);
+        }
+        Ok(value)
+    }
+
+    /// Stores a secret.
+    pub fn set(&self, name: &str, value: &str) -> Result<(), SecretError> {
+        if !is_valid_name(name) {
+            return Err(SecretError::InvalidName(name.to_string()));
+        }
+        self.provider.set(name, value)
+    }
+
+    /// Returns the stored names, sorted.
+    pub fn list(&self) -> Result<Vec<String>, SecretError> {
+        self.provider.list()
+    }
+
+    /// Resolves `${secret.name}` for the config resolver.
+    pub(crate) fn resolve(&self, name: &str) -> Result<Value, ResolveError> {
+        if let Some(value) = self.resolved.lock().ok().and_then(|r| r.get(name).cloned()) {
+            return Ok(Value::String(value));
+        }
+        match self.get(name) {
+            Ok(Some(value)) => {
+                if let Ok(mut resolved) = self.resolved.lock() {
+                    resolved.insert(name.to_string(), value.clone());
+                }
+                Ok(Value::String(value))
+            }
+            Ok(None) => Err(ResolveError::UnknownSecret {
+                name: name.to_string(),
+                store: self.describe(),
+            }),
+            Err(e) => Err(ResolveError::Secret {
+                name: name.to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Secrets")
+            .field(&self.provider.describe())
+            .finish()
+    }
+}
+
+/// Substitutes every `${secret.name}` left in `value`'s strings — the
+/// references `nemo build` keeps out of `dist/` — from `secrets`.
+pub fn resolve_secret_refs(value: &mut Value, secrets: &Secrets) -> Result<(), ResolveError> {
+    match value {
+        Value::String(s) if s.contains("${secret.") => {
+            let mut out = String::with_capacity(s.len());
+            let mut rest = s.as_str();
+            while let Some(start) = rest.find("${secret.") {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                let name = rest[start + "${secret.".len()..start + len].trim();
+                out.push_str(&rest[..start]);
+                out.push_str(&secrets.resolve(name)?.to_string());
+                rest = &rest[start + len + 1..];
+            }
+            out.push_str(rest);
+            *s = out;
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve_secret_refs(item, secrets)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_secret_refs(item, secrets)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Whether `name` can be written as `${secret.name}`.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+fn redactions() -> &'static RwLock<Vec<String>> {
+    static REDACTIONS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    REDACTIONS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Registers a value [`redact`] hides from now on.
+pub fn register_redaction(value: &str) {
+    if value.len() < MIN_REDACT_LEN {
+        return;
+    }
+    if let Ok(mut values) = redactions().write() {
+        if !values.iter().any(|v| v == value) {
+            values.push(value.to_string());
+            // Longest first, so a secret containing another is hidden whole.
+            values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        }
+    }
+}
+
+/// Replaces every resolved secret in `text` with [`REDACTED`].
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let Ok(values) = redactions().read() else {
+        return Cow::Borrowed(text);
+    };
+    let mut text = Cow::Borrowed(text);
+    for value in values.iter() {
+        if text.contains(value.as_str()) {
+            text = Cow::Owned(text.replace(value.as_str(), REDACTED));
+        }
+    }
+    text
+}
+
+/// An [`io::Write`] that [`redact`]s what passes through it — wrap a log
+/// sink with it. Each write is redacted on its own, which suits writers fed
+/// one formatted record at a time.
+pub struct RedactingWriter<W>(pub W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) => self.0.write_all(redact(text).as_bytes())?,
+            Err(_) => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: NonZeroU32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    key
+}
+
+fn aead_key(key: &[u8; 32]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("32-byte key"))
+}
+
+/// Seals `plain` with a fresh nonce, bound to `name`, as hex.
+fn seal(key: &LessSafeKey, name: &str, plain: &[u8]) -> Result<String, SecretError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| SecretError::Service("no system randomness".into()))?;
+    let mut data = plain.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(name.as_bytes()),
+        &mut data,
+    )
+    .map_err(|_| SecretError::Service("encryption failed".into()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(data);
+    Ok(to_hex(&sealed))
+}
+
+/// Opens a [`seal`]ed hex value; `None` if it was tampered with or the key
+/// is wrong.
+fn open(key: &LessSafeKey, name: &str, sealed: &str) -> Option<Vec<u8>> {
+    let sealed = from_hex(sealed)?;
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, data) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut data = data.to_vec();
+    let plain = key
+        .open_in_place(nonce, Aad::from(name.as_bytes()), &mut data)
+        .ok()?;
+    Some(plain.to_vec())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_provider(dir: &Path, passphrase: Option<&str>) -> EncryptedFileProvider {
+        EncryptedFileProvider::new(dir.join("secrets.json"), passphrase.map(String::from))
+            .with_iterations(1_000)
+    }
+
+    #[test]
+    fn encrypted_file_round_trips_and_checks_the_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_provider(dir.path(), Some("correct horse"));
+        assert_eq!(store.get("api_token").unwrap(), None);
+        store.set("api_token", "tok-123456").unwrap();
+        store.set("db.password", "hunter22").unwrap();
+        assert_eq!(
+            store.get("api_token").unwrap().as_deref(),
+            Some("tok-123456")
+        );
+        assert_eq!(store.list().unwrap(), ["api_token", "db.password"]);
+
+        // The value isn't stored in the clear.
+        let raw = std::fs::read_to_string(store.path()).unwrap();
+        assert!(!raw.contains("tok-123456"));
+
+        // Names are listed without a passphrase; values need the right one.
+        let locked = file_provider(dir.path(), None);
+        assert_eq!(locked.list().unwrap().len(), 2);
+        assert!(matches!(
+            locked.get("api_token"),
+            Err(SecretError::Locked { .. })
+        ));
+        let wrong = file_provider(dir.path(), Some("battery staple"));
+        assert!(matches!(
+            wrong.get("api_token"),
+            Err(SecretError::WrongPassphrase { .. })
+        ));
+        assert!(wrong.set("other", "x").is_err());
+    }
+
+    #[test]
+    fn cached_key_follows_the_file_salt() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_provider(dir.path(), Some("pass"));
+        store.set("a", "first-value").unwrap();
+        assert_eq!(store.get("a").unwrap().as_deref(), Some("first-value"));
+
+        // Re-created by another provider, the file has a new salt.
+        std::fs::remove_file(store.path()).unwrap();
+        file_provider(dir.path(), Some("pass"))
+            .set("b", "second-value")
+            .unwrap();
+        assert_eq!(store.get("b").unwrap().as_deref(), Some("second-value"));
+    }
+
+    #[test]
+    fn resolution_memoizes_per_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = Secrets::new(file_provider(dir.path(), Some("pass")));
+        secrets.set("token", "memo-old-value").unwrap();
+        let resolve = |secrets: &Secrets| secrets.resolve("token").unwrap();
+        assert_eq!(resolve(&secrets), Value::String("memo-old-value".into()));
+
+        secrets.set("token", "memo-new-value").unwrap();
+        assert_eq!(resolve(&secrets), Value::String("memo-old-value".into()));
+        assert_eq!(
+            resolve(&secrets.for_load()),
+            Value::String("memo-new-value".into())
+        );
+    }
+
+    #[test]
+    fn secret_service_falls_back_to_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = Secrets::new(
+            SecretServiceProvider::new("test", file_provider(dir.path(), Some("pass")))
+                .with_tool(dir.path().join("no-such-secret-tool")),
+        );
+        secrets.set("mqtt_password", "s3cret-pass").unwrap();
+        assert_eq!(
+            secrets.get("mqtt_password").unwrap().as_deref(),
+            Some("s3cret-pass")
+        );
+        assert_eq!(secrets.list().unwrap(), ["mqtt_password"]);
+        assert!(matches!(
+            secrets.set("bad name", "x"),
+            Err(SecretError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn resolved_values_are_redacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = Secrets::new(file_provider(dir.path(), Some("pass")));
+        secrets.set("token", "redact-me-9f8e7d").unwrap();
+
+        let mut value = Value::String("Bearer ${secret.token}".into());
+        resolve_secret_refs(&mut value, &secrets).unwrap();
+        assert_eq!(value, Value::String("Bearer redact-me-9f8e7d".into()));
+
+        assert_eq!(
+            redact("auth failed for Bearer redact-me-9f8e7d"),
+            "auth failed for Bearer [redacted]"
+        );
+        let mut log = RedactingWriter(Vec::new());
+        log.write_all(b"header=redact-me-9f8e7d\n").unwrap();
+        assert_eq!(log.0, b"header=[redacted]\n");
+
+        let mut missing = Value::String("${secret.nope}".into());
+        let err = resolve_secret_refs(&mut missing, &secrets).unwrap_err();
+        assert!(
+            err.to_string().contains("Secret 'nope' is not set"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Load-time coercion of SFC instance props.
+//!
+//! An SFC declares its props in `<props>` (`name`, `type`, `one-of`,
+//! `default`, `required`). Instance attributes arrive from the XML reader as
+//! strings (or loosely typed scalars), so this pass walks every SFC usage in
+//! the `layout`, the `<templates>` and the SFC template bodies, and replaces
+//! each declared prop's attribute with its value coerced to the declared type
+//! — `"42"` → `42` for an `int`, a JSON string → an array for an `array`.
+//!
+//! Values that don't coerce (or fall outside a `one-of` list) are reported as
+//! [`ValidationError`]s at the instance's path, so they fail the load the way a
+//! misplaced directive does. Attributes still holding a `${…}` placeholder
+//! (a prop passed down from an enclosing SFC, or a live `n:for` item) are left
+//! for the runtime, as are bound props (`:name="data.x"`), which name a data
+//! source rather than a value.
+
+use crate::xml_parser::coerce_prop_value;
+use crate::{ConfigPath, ValidationError, Value};
+use std::collections::HashMap;
+
+/// A declared prop as stored under `sfc.<tag>.props`.
+struct PropDecl {
+    name: String,
+    ty: String,
+    one_of: Vec<String>,
+}
+
+/// Per-tag prop declarations plus the file each SFC was loaded from.
+struct SfcDecls {
+    props: Vec<PropDecl>,
+    source_path: String,
+}
+
+/// Coerces declared props on every SFC instance in `config` in place,
+/// returning one error per attribute that doesn't fit its declaration.
+pub fn coerce_sfc_props(config: &mut Value) -> Vec<ValidationError> {
+    let decls = read_decls(config);
+    let mut errors = Vec::new();
+    if decls.is_empty() {
+        return errors;
+    }
+    let Some(obj) = config.as_object_mut() else {
+        return errors;
+    };
+
+    if let Some(layout) = obj.get_mut("layout") {
+        coerce_children(layout, &ConfigPath::from_key("layout"), &decls, &mut errors);
+    }
+    if let Some(sfc_map) = obj.get_mut("sfc").and_then(|v| v.as_object_mut()) {
+        for (tag, def) in sfc_map {
+            if let Some(template) = def.as_object_mut().and_then(|o| o.get_mut("template")) {
+                let path = ConfigPath::from_key("sfc")
+                    .join_key(tag.clone())
+                    .join_key("template");
+                coerce_children(template, &path, &decls, &mut errors);
+            }
+        }
+    }
+    if let Some(templates) = obj
+        .get_mut("templates")
+        .and_then(|t| t.as_object_mut())
+        .and_then(|t| t.get_mut("template"))
+        .and_then(|v| v.as_object_mut())
+    {
+        for (name, body) in templates {
+            let path = ConfigPath::from_key("templates")
+                .join_key("template")
+                .join_key(name.clone());
+            coerce_children(body, &path, &decls, &mut errors);
+        }
+    }
+    errors
+}
+
+/// Reads the prop declarations of every SFC that declares at least one.
+fn read_decls(config: &Value) -> HashMap<String, SfcDecls> {
+    let mut decls = HashMap::new();
+    let Some(sfc_map) = config.get("sfc").and_then(|v| v.as_object()) else {
+        return decls;
+    };
+    for (tag, def) in sfc_map {
+        let props: Vec<PropDecl> = def
+            .get("props")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| {
+                Some(PropDecl {
+                    name: p.get("name").and_then(|v| v.as_str())?.to_string(),
+                    ty: p
+                        .get("type")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("string")
+                        .to_string(),
+                    one_of: p
+                        .get("one_of")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten()
+                        .map(|v| v.to_string())
+                        .collect(),
+                })
+            })
+            .collect();
+        if props.is_empty() {
+            continue;
+        }
+        let source_path = def
+            .get("source_path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(tag)
+            .to_string();
+        decls.insert(tag.clone(), SfcDecls { props, source_path });
+    }
+    decls
+}
+
+/// Coerces the props of each `component` child of `node` that is an SFC
+/// instance, recursing into every child.
+fn coerce_children(
+    node: &mut Value,
+    path: &ConfigPath,
+    decls: &HashMap<String, SfcDecls>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(children) = node.as_object_mut().and_then(|o| o.get_mut("component")) else {
+        return;
+    };
+    let path = path.join_key("component");
+    match children {
+        Value::Object(map) => {
+            for (id, child) in map.iter_mut() {
+                let child_path = path.join_key(id.clone());
+                coerce_instance(child, id, &child_path, decls, errors);
+                coerce_children(child, &child_path, decls, errors);
+            }
+        }
+        Value::Array(arr) => {
+            for (i, child) in arr.iter_mut().enumerate() {
+                let child_path = path.join_index(i);
+                let id = child
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("<anonymous>")
+                    .to_string();
+                coerce_instance(child, &id, &child_path, decls, errors);
+                coerce_children(child, &child_path, decls, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Coerces the declared props of one node, if it is an SFC instance.
+fn coerce_instance(
+    node: &mut Value,
+    id: &str,
+    path: &ConfigPath,
+    decls: &HashMap<String, SfcDecls>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(obj) = node.as_object_mut() else {
+        return;
+    };
+    let Some(tag) = obj.get("type").and_then(|v| v.as_str()).map(str::to_string) else {
+        return;
+    };
+    let Some(decl) = decls.get(&tag) else {
+        return;
+    };
+    for prop in &decl.props {
+        if obj.contains_key(&format!(":{}", prop.name)) {
+            if obj.contains_key(&prop.name) {
+                errors.push(ValidationError::invalid_prop(
+                    path.join_key(prop.name.clone()),
+                    format!(
+                        "<{tag}> '{id}' sets prop '{}' both statically and with :{}",
+                        prop.name, prop.name
+                    ),
+                    "one value for the prop",
+                    "both",
+                ));
+            }
+            continue;
+        }
+        let Some(value) = obj.get(&prop.name) else {
+            continue;
+        };
+        if value.as_str().is_some_and(|s| s.contains("${")) {
+            continue;
+        }
+        match coerce_prop_value(&prop.ty, &prop.one_of, value) {
+            Ok(coerced) => {
+                obj.insert(prop.name.clone(), coerced);
+            }
+            Err(expected) => {
+                errors.push(ValidationError::invalid_prop(
+                    path.join_key(prop.name.clone()),
+                    format!(
+                        "<{tag}> '{id}': prop '{}' {}, got '{}' (declared in {})",
+                        prop.name, expected, value, decl.source_path
+                    ),
+                    &expected,
+                    &value.to_string(),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XmlParser;
+
+    /// Parses an app with a `gauge` SFC declared inline under `sfc`.
+    fn config(layout: &str) -> Value {
+        let sfc = r#"
+        <props>
+          <prop name="pct" type="int" default="0" />
+          <prop name="tone" type="string" one-of="info,warn" />
+          <prop name="marks" type="array" />
+        </props>
+        <template name="gauge">
+          <progress value="${pct}" />
+        </template>
+        "#;
+        let def = XmlParser::new().parse_sfc(sfc).unwrap();
+        let mut config = XmlParser::new()
+            .parse(&format!("<nemo><layout>{layout}</layout></nemo>"))
+            .unwrap();
+        let mut sfc_map = indexmap::IndexMap::new();
+        sfc_map.insert(
+            "gauge".to_string(),
+            crate::sfc_definition_to_value(def, "components/gauge.nemo"),
+        );
+        if let Value::Object(obj) = &mut config {
+            obj.insert("sfc".to_string(), Value::Object(sfc_map));
+        }
+        config
+    }
+
+    fn instance<'a>(config: &'a Value, id: &str) -> &'a Value {
+        config
+            .get("layout")
+            .and_then(|l| l.get("component"))
+            .and_then(|c| c.get(id))
+            .unwrap()
+    }
+
+    #[test]
+    fn coerces_instance_attributes_to_declared_types() {
+        let mut config = config(
+            r#"<gauge id="cpu" pct="42" tone="warn" marks="[25, 50]" />
+               <gauge id="mem" :pct="data.mem.pct" />"#,
+        );
+        assert!(coerce_sfc_props(&mut config).is_empty());
+
+        let cpu = instance(&config, "cpu");
+        assert_eq!(cpu.get("pct"), Some(&Value::Integer(42)));
+        assert_eq!(cpu.get("tone"), Some(&Value::String("warn".into())));
+        assert_eq!(
+            cpu.get("marks"),
+            Some(&Value::Array(vec![Value::Integer(25), Value::Integer(50)]))
+        );
+        // A bound prop keeps its data source.
+        let mem = instance(&config, "mem");
+        assert_eq!(mem.get(":pct"), Some(&Value::String("data.mem.pct".into())));
+    }
+
+    #[test]
+    fn reports_props_that_do_not_fit_their_declaration() {
+        let mut config = config(
+            r#"<gauge id="cpu" pct="lots" tone="loud" marks="${marks}" />
+               <gauge id="mem" pct="1" :pct="data.mem.pct" />"#,
+        );
+        let errors = coerce_sfc_props(&mut config);
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 3, "{messages:?}");
+        assert_eq!(errors[0].path.to_string(), "layout.component.cpu.pct");
+        assert_eq!(
+            messages[0],
+            "<gauge> 'cpu': prop 'pct' expected int, got 'lots' (declared in components/gauge.nemo)"
+        );
+        assert!(messages[1].contains("expected one of info, warn"));
+        assert!(messages[2].contains("both statically and with :pct"));
+    }
+}
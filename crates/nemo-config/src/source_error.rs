@@ -0,0 +1,174 @@
+//! Runtime errors mapped back to the `.nemo` source they came from.
+//!
+//! Parse and validate diagnostics carry a [`SourceLocation`] from the start;
+//! errors raised later — a failing Rhai handler, a binding transform that
+//! can't extract its field, a template that won't expand — only know the
+//! component or script they happened in. [`SourceError`] pairs such a message
+//! with the origin the parser recorded for that component (or the script's
+//! file and line), and [`SourceError::render`] prints it as a miette report
+//! with a snippet of the offending line.
+
+use std::fmt;
+use std::path::Path;
+
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, NamedSource, SourceSpan};
+use thiserror::Error;
+
+use crate::location::SourceLocation;
+
+/// The key under which a parsed component records where it was written
+/// (`file:line:column`). Namespaced like the `n:` directives so it can't
+/// collide with an attribute or SFC prop named `origin`.
+pub const ORIGIN_KEY: &str = "n:origin";
+
+/// The key under which an SFC definition records where its `<script>` body
+/// starts, namespaced like [`ORIGIN_KEY`].
+pub const SCRIPT_ORIGIN_KEY: &str = "n:script_origin";
+
+/// An error raised at runtime, located in the app's source when known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    /// What went wrong.
+    pub message: String,
+    /// Where in the app's source it happened, if known.
+    pub location: Option<SourceLocation>,
+}
+
+impl SourceError {
+    /// Creates an error without a location.
+    pub fn new(message: impl Into<String>) -> Self {
+        SourceError {
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    /// Sets the location.
+    pub fn at(mut self, location: SourceLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Sets the location unless one is already known, so the innermost origin
+    /// (the closest to where the error happened) wins as an error propagates.
+    pub fn or_at(mut self, location: Option<&SourceLocation>) -> Self {
+        if self.location.is_none() {
+            self.location = location.cloned();
+        }
+        self
+    }
+
+    /// Renders the error as a miette report: the `file:line:col` header and a
+    /// snippet of the source line with the error labelled. Falls back to the
+    /// one-line [`Display`](fmt::Display) form when the location is unknown
+    /// or its file can't be read.
+    pub fn render(&self) -> String {
+        let Some(location) = &self.location else {
+            return self.to_string();
+        };
+        match std::fs::read_to_string(Path::new(&location.file)) {
+            Ok(source) => self.render_with_source(&source),
+            Err(_) => self.to_string(),
+        }
+    }
+
+    /// [`render`](Self::render) against `source` instead of reading the file.
+    pub fn render_with_source(&self, source: &str) -> String {
+        let Some(location) = &self.location else {
+            return self.to_string();
+        };
+        let Some(offset) = location.offset_in(source) else {
+            return self.to_string();
+        };
+        let len = source[offset..]
+            .find(|c: char| c.is_whitespace() || c == '>')
+            .unwrap_or(source.len() - offset)
+            .max(1)
+            .min(source.len() - offset);
+        let report = Report {
+            message: self.message.clone(),
+            src: NamedSource::new(location.file.clone(), source.to_string()),
+            span: (offset, len).into(),
+        };
+        let mut out = String::new();
+        let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
+        match handler.render_report(&mut out, &report) {
+            Ok(()) => out,
+            Err(_) => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+impl From<String> for SourceError {
+    fn from(message: String) -> Self {
+        SourceError::new(message)
+    }
+}
+
+impl From<&str> for SourceError {
+    fn from(message: &str) -> Self {
+        SourceError::new(message)
+    }
+}
+
+/// The miette view of a located [`SourceError`].
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+struct Report {
+    message: String,
+    #[source_code]
+    src: NamedSource<String>,
+    #[label("here")]
+    span: SourceSpan,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_prefixes_location() {
+        let err = SourceError::new("boom").at(SourceLocation::new("app.nemo", 42, 7));
+        assert_eq!(err.to_string(), "app.nemo:42:7: boom");
+        assert_eq!(SourceError::new("boom").to_string(), "boom");
+    }
+
+    #[test]
+    fn test_or_at_keeps_innermost_location() {
+        let inner = SourceLocation::new("card.nemo", 3, 5);
+        let outer = SourceLocation::new("app.nemo", 10, 1);
+        let err = SourceError::new("boom")
+            .or_at(Some(&inner))
+            .or_at(Some(&outer));
+        assert_eq!(err.location, Some(inner));
+    }
+
+    #[test]
+    fn test_render_shows_snippet() {
+        let source =
+            "<template name=\"app\">\n  <button id=\"go\" on-click=\"nope\"/>\n</template>\n";
+        let err =
+            SourceError::new("handler 'nope' failed").at(SourceLocation::new("app.nemo", 2, 3));
+        let report = err.render_with_source(source);
+        assert!(report.contains("app.nemo:2:3"), "{report}");
+        assert!(report.contains("<button id=\"go\""), "{report}");
+        assert!(report.contains("handler 'nope' failed"), "{report}");
+    }
+
+    #[test]
+    fn test_render_without_source_falls_back() {
+        let err = SourceError::new("boom").at(SourceLocation::new("/no/such/app.nemo", 1, 1));
+        assert_eq!(err.render(), "/no/such/app.nemo:1:1: boom");
+    }
+}
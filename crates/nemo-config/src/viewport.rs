@@ -0,0 +1,255 @@
+//! Window-size conditions for responsive layouts.
+//!
+//! Two syntaxes parse to the same [`ViewportCondition`]:
+//!
+//! * `n:show-when` expressions — `width < 900`, `width >= 600 && height > 400`,
+//!   `width < 600 || width > 1800`. `&&`/`and` binds tighter than `||`/`or`.
+//! * SFC `<style>` `@media` preludes — `(max-width: 900px)`,
+//!   `screen and (min-width: 600px) and (max-height: 800px)`, with `,`
+//!   separating alternatives.
+//!
+//! A condition displays in the `n:show-when` syntax, which is how folded
+//! `@media` rules store theirs.
+
+use std::fmt;
+
+/// A window dimension a condition compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Width,
+    Height,
+}
+
+/// A comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    /// Operators by their text, two-character ones first so `<=` isn't read
+    /// as `<`.
+    const ALL: [(&'static str, Op); 6] = [
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ];
+
+    fn text(self) -> &'static str {
+        Op::ALL
+            .iter()
+            .find(|(_, op)| *op == self)
+            .map(|(text, _)| *text)
+            .unwrap_or("==")
+    }
+}
+
+/// One `<dimension> <op> <pixels>` comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Comparison {
+    dimension: Dimension,
+    op: Op,
+    value: f32,
+}
+
+impl Comparison {
+    fn holds(&self, width: f32, height: f32) -> bool {
+        let actual = match self.dimension {
+            Dimension::Width => width,
+            Dimension::Height => height,
+        };
+        match self.op {
+            Op::Lt => actual < self.value,
+            Op::Le => actual <= self.value,
+            Op::Gt => actual > self.value,
+            Op::Ge => actual >= self.value,
+            Op::Eq => actual == self.value,
+            Op::Ne => actual != self.value,
+        }
+    }
+}
+
+/// A condition on the window size: it holds when every comparison of any one
+/// clause does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportCondition {
+    clauses: Vec<Vec<Comparison>>,
+}
+
+/// Parses a pixel length, with or without a `px` suffix.
+fn parse_pixels(text: &str) -> Result<f32, String> {
+    let number = text.trim().trim_end_matches("px").trim();
+    number
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("'{}' is not a number of pixels", text.trim()))
+}
+
+fn parse_dimension(text: &str) -> Result<Dimension, String> {
+    match text.trim() {
+        "width" => Ok(Dimension::Width),
+        "height" => Ok(Dimension::Height),
+        other => Err(format!("unknown dimension '{other}' (use width or height)")),
+    }
+}
+
+impl ViewportCondition {
+    /// Parses an `n:show-when` expression.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let normalized = expr.replace("||", " or ").replace("&&", " and ");
+        let clauses = normalized
+            .split(" or ")
+            .map(|clause| {
+                clause
+                    .split(" and ")
+                    .map(|comparison| {
+                        let comparison = comparison.trim();
+                        let (at, text, op) = Op::ALL
+                            .iter()
+                            .filter_map(|(text, op)| {
+                                comparison.find(text).map(|at| (at, *text, *op))
+                            })
+                            .min_by_key(|(at, text, _)| (*at, std::cmp::Reverse(text.len())))
+                            .ok_or_else(|| format!("'{comparison}' is not a comparison"))?;
+                        Ok(Comparison {
+                            dimension: parse_dimension(&comparison[..at])?,
+                            op,
+                            value: parse_pixels(&comparison[at + text.len()..])?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(ViewportCondition { clauses })
+    }
+
+    /// Parses an `@media` prelude (the text between `@media` and `{`). The
+    /// `screen`/`all` media types are accepted and ignored.
+    pub fn parse_media(query: &str) -> Result<Self, String> {
+        let clauses = query
+            .split(',')
+            .map(|alternative| {
+                let mut comparisons = Vec::new();
+                for part in alternative.split(" and ") {
+                    let part = part.trim();
+                    if matches!(part, "screen" | "all" | "only screen" | "only all") {
+                        continue;
+                    }
+                    let feature = part
+                        .strip_prefix('(')
+                        .and_then(|p| p.strip_suffix(')'))
+                        .ok_or_else(|| format!("unsupported media query part '{part}'"))?;
+                    let (name, value) = feature
+                        .split_once(':')
+                        .ok_or_else(|| format!("'{feature}' has no value"))?;
+                    let (op, dimension) = match name.trim() {
+                        "min-width" => (Op::Ge, Dimension::Width),
+                        "max-width" => (Op::Le, Dimension::Width),
+                        "min-height" => (Op::Ge, Dimension::Height),
+                        "max-height" => (Op::Le, Dimension::Height),
+                        other => return Err(format!("unsupported media feature '{other}'")),
+                    };
+                    comparisons.push(Comparison {
+                        dimension,
+                        op,
+                        value: parse_pixels(value)?,
+                    });
+                }
+                if comparisons.is_empty() {
+                    return Err(format!("'{}' has no size condition", alternative.trim()));
+                }
+                Ok(comparisons)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(ViewportCondition { clauses })
+    }
+
+    /// Whether the condition holds for a `width`×`height` window (logical
+    /// pixels).
+    pub fn matches(&self, width: f32, height: f32) -> bool {
+        self.clauses
+            .iter()
+            .any(|clause| clause.iter().all(|c| c.holds(width, height)))
+    }
+}
+
+impl fmt::Display for ViewportCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, clause) in self.clauses.iter().enumerate() {
+            if i > 0 {
+                write!(f, " || ")?;
+            }
+            for (j, c) in clause.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " && ")?;
+                }
+                let dimension = match c.dimension {
+                    Dimension::Width => "width",
+                    Dimension::Height => "height",
+                };
+                write!(f, "{} {} {}", dimension, c.op.text(), c.value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_show_when() {
+        let narrow = ViewportCondition::parse("width < 900").unwrap();
+        assert!(narrow.matches(800.0, 600.0));
+        assert!(!narrow.matches(900.0, 600.0));
+
+        let both = ViewportCondition::parse("width>=600 && height <= 400px").unwrap();
+        assert!(both.matches(600.0, 400.0));
+        assert!(!both.matches(600.0, 401.0));
+
+        let either = ViewportCondition::parse("width < 600 or width > 1800").unwrap();
+        assert!(either.matches(2000.0, 0.0));
+        assert!(!either.matches(1000.0, 0.0));
+
+        assert!(ViewportCondition::parse("depth < 3").is_err());
+        assert!(ViewportCondition::parse("width < wide").is_err());
+        assert!(ViewportCondition::parse("width").is_err());
+    }
+
+    #[test]
+    fn test_parse_media() {
+        let query = ViewportCondition::parse_media("screen and (max-width: 900px)").unwrap();
+        assert!(query.matches(900.0, 0.0));
+        assert!(!query.matches(901.0, 0.0));
+
+        let alternatives =
+            ViewportCondition::parse_media("(min-width: 1800px), (max-height: 500px)").unwrap();
+        assert!(alternatives.matches(1900.0, 900.0));
+        assert!(alternatives.matches(1000.0, 400.0));
+        assert!(!alternatives.matches(1000.0, 900.0));
+
+        assert!(ViewportCondition::parse_media("print").is_err());
+        assert!(ViewportCondition::parse_media("(orientation: portrait)").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let query = ViewportCondition::parse_media(
+            "(min-width: 600px) and (max-width: 900px), (max-height: 400px)",
+        )
+        .unwrap();
+        let text = query.to_string();
+        assert_eq!(text, "width >= 600 && width <= 900 || height <= 400");
+        assert_eq!(ViewportCondition::parse(&text).unwrap(), query);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::error::ParseError;
 use crate::location::SourceLocation;
+use crate::source_error::{ORIGIN_KEY, SCRIPT_ORIGIN_KEY};
 use crate::Value;
 use indexmap::IndexMap;
 use quick_xml::events::{BytesCData, BytesStart, Event};
@@ -35,12 +36,27 @@ pub struct SfcDefinition {
     pub style: Option<String>,
     /// Raw `<script>` body (Rhai), if present. Loaded under `sfc:<tag>`.
     pub script: Option<String>,
+    /// Where the script body starts in the file, recorded when the parser
+    /// tracks [origins](XmlParser::with_origins), so a script error's line
+    /// maps back onto the `.nemo` file.
+    pub script_origin: Option<SourceLocation>,
     /// Declared props from an optional `<props>` block. Empty when omitted (props
     /// are then stringly-typed and have no defaults).
     pub props: Vec<SfcProp>,
     /// Slots declared by `<slot [name] [required] [multiple]/>` in the template,
     /// in document order. Used for slot validation and schema export.
     pub slots: Vec<SfcSlot>,
+    /// Custom events declared by an optional `<emits><event name/></emits>`
+    /// block (names kebab→snake normalized). The SFC's script raises them
+    /// with `emit("name", payload)`; an instance handles them with
+    /// `on-name="…"`.
+    pub emits: Vec<String>,
+    /// Per-instance reactive fields from an optional `<state>` block: field
+    /// name → initial value (typed like an app `<state>` field). Each instance
+    /// gets its own copy; the template reads them as `${state.name}` and the
+    /// script as `self.state.name`. Empty for an `app.nemo`, whose `<state>`
+    /// is the app-level block.
+    pub state: IndexMap<String, Value>,
     /// App-level blocks (an `app.nemo` SFC). `None` for a component `.nemo`.
     ///
     /// * `app` — the `<app>` block processed by `process_app` (window/theme/etc).
@@ -98,8 +114,12 @@ pub struct SfcSlot {
 pub struct SfcProp {
     /// Prop name (matches the `${name}` placeholder and the instance attribute).
     pub name: String,
-    /// Declared type: `string` (default), `int`, `float`, or `bool`.
+    /// Declared type: `string` (default), `int`, `float`, `bool`, `array` or
+    /// `object` (the last two written as JSON).
     pub ty: String,
+    /// Allowed values from `one-of="a,b,c"` (compared after coercion to `ty`).
+    /// Empty when the prop is not an enum.
+    pub one_of: Vec<String>,
     /// Default value (already coerced to `ty`) used when an instance omits the prop.
     pub default: Option<Value>,
     /// Whether the prop must be supplied by the instance (checked by `nemo validate`).
@@ -108,18 +128,42 @@ pub struct SfcProp {
 
 /// Coerces a raw string to the given SFC prop type. Type names mirror the scalar
 /// `#[derive(NemoComponent)]` model: `string`/`int`/`float`/`bool` (with common
-/// aliases). Returns `None` if the value doesn't parse as that type.
+/// aliases), plus `array`/`object` parsed from JSON. Returns `None` if the value
+/// doesn't parse as that type.
 pub(crate) fn coerce_typed_value(ty: &str, raw: &str) -> Option<Value> {
     let raw = raw.trim();
     match ty {
         "int" | "integer" | "i64" => raw.parse::<i64>().ok().map(Value::Integer),
         "float" | "number" | "f64" => raw.parse::<f64>().ok().map(Value::Float),
         "bool" | "boolean" => raw.parse::<bool>().ok().map(Value::Bool),
+        "array" | "list" => serde_json::from_str::<Value>(raw)
+            .ok()
+            .filter(|v| matches!(v, Value::Array(_))),
+        "object" | "map" => serde_json::from_str::<Value>(raw)
+            .ok()
+            .filter(|v| matches!(v, Value::Object(_))),
         // "string" and anything unrecognized → string.
         _ => Some(Value::String(raw.to_string())),
     }
 }
 
+/// Coerces a prop value (an attribute, possibly already typed by the XML
+/// reader) to the declared `ty`, then checks it against `one_of`. The error
+/// names what was expected.
+pub fn coerce_prop_value(ty: &str, one_of: &[String], value: &Value) -> Result<Value, String> {
+    let coerced = match value {
+        Value::Array(_) if matches!(ty, "array" | "list") => Some(value.clone()),
+        Value::Object(_) if matches!(ty, "object" | "map") => Some(value.clone()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+        scalar => coerce_typed_value(ty, &scalar.to_string()),
+    }
+    .ok_or_else(|| format!("expected {}", ty))?;
+    if !one_of.is_empty() && !one_of.contains(&coerced.to_string()) {
+        return Err(format!("expected one of {}", one_of.join(", ")));
+    }
+    Ok(coerced)
+}
+
 /// The pre-split result of a `.nemo` SFC: the `<template>` half (still XML) plus
 /// the verbatim raw-text bodies of `<script>`/`<style>`, extracted *before* the
 /// XML reader sees them so their contents never need `<![CDATA[…]]>`.
@@ -127,11 +171,14 @@ pub(crate) fn coerce_typed_value(ty: &str, raw: &str) -> Option<Value> {
 /// `script`/`style` are `None` only when the block is absent or empty after
 /// trimming, matching the old `__cdata__` filter at `parse_sfc`.
 struct SfcBlocks {
-    /// The `.nemo` source with `<script>`/`<style>` blocks removed, leaving
-    /// `<template>` (and `<props>`) for `quick-xml`. Whitespace where the
-    /// blocks stood is collapsed so the XML reader sees clean siblings.
+    /// The `.nemo` source with `<script>`/`<style>` blocks blanked out, leaving
+    /// `<template>` (and `<props>`) for `quick-xml`. The blocks become
+    /// whitespace of the same length, so byte offsets (and line numbers)
+    /// still match the source.
     template_xml: String,
     script: Option<String>,
+    /// Byte offset of the captured script body in the source.
+    script_offset: Option<usize>,
     style: Option<String>,
 }
 
@@ -159,6 +206,7 @@ fn split_sfc_blocks(content: &str) -> SfcBlocks {
     let bytes = content.as_bytes();
     let len = bytes.len();
     let mut script: Option<String> = None;
+    let mut script_offset: Option<usize> = None;
     let mut style: Option<String> = None;
     let mut removals: Vec<(usize, usize)> = Vec::new();
     let mut depth: usize = 0;
@@ -245,7 +293,11 @@ fn split_sfc_blocks(content: &str) -> SfcBlocks {
                     let captured = stripped.trim().to_string();
                     if !captured.is_empty() {
                         match name {
-                            "script" => script = Some(captured),
+                            "script" => {
+                                script_offset =
+                                    body.find(captured.as_str()).map(|at| body_start + at);
+                                script = Some(captured);
+                            }
                             "style" => style = Some(captured),
                             _ => {}
                         }
@@ -270,6 +322,7 @@ fn split_sfc_blocks(content: &str) -> SfcBlocks {
     SfcBlocks {
         template_xml,
         script: script.filter(|s| !s.trim().is_empty()),
+        script_offset,
         style: style.filter(|s| !s.trim().is_empty()),
     }
 }
@@ -285,18 +338,24 @@ fn strip_cdata(body: &str) -> String {
     s.to_string()
 }
 
-/// Removes `ranges` (byte-offset spans, inclusive start, exclusive end) from
-/// `content`, replacing each with a single space so siblings don't fuse.
-/// Ranges are applied back-to-front to keep earlier indices valid.
-fn remove_ranges(content: &str, mut ranges: Vec<(usize, usize)>) -> String {
+/// Blanks `ranges` (byte-offset spans, inclusive start, exclusive end) out of
+/// `content`: every byte becomes a space except newlines, which are kept, so
+/// siblings don't fuse and offsets after a range are unchanged.
+fn remove_ranges(content: &str, ranges: Vec<(usize, usize)>) -> String {
     if ranges.is_empty() {
         return content.to_string();
     }
-    ranges.sort_unstable_by_key(|r| std::cmp::Reverse(r.0));
-    let mut out = content.to_string();
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
     for (start, end) in ranges {
-        out.replace_range(start..end, " ");
+        out.push_str(&content[last..start]);
+        out.extend(content.as_bytes()[start..end].iter().map(|&b| match b {
+            b'\n' => '\n',
+            _ => ' ',
+        }));
+        last = end;
     }
+    out.push_str(&content[last..]);
     out
 }
 
@@ -316,6 +375,9 @@ pub struct XmlParser {
     packages_dir: Option<PathBuf>,
     /// `module → version` from `nemo.lock`, used to pick the cached package dir.
     locked_versions: BTreeMap<String, String>,
+    /// Record each component's `file:line:column` under `origin` (see
+    /// [`with_origins`](Self::with_origins)).
+    origins: bool,
 }
 
 impl XmlParser {
@@ -327,6 +389,7 @@ impl XmlParser {
             anon_counter: Cell::new(0),
             packages_dir: None,
             locked_versions: BTreeMap::new(),
+            origins: false,
         }
     }
 
@@ -354,6 +417,28 @@ impl XmlParser {
         self
     }
 
+    /// Records where each component was written: layout and template nodes
+    /// carry an [`ORIGIN_KEY`] key (`file:line:column`, the position of the
+    /// element's `<`) and an SFC's script its [`SCRIPT_ORIGIN_KEY`], so runtime
+    /// errors can point back at the source. Off by default, leaving the
+    /// parsed tree free of positions. Included and imported files inherit it.
+    pub fn with_origins(mut self) -> Self {
+        self.origins = true;
+        self
+    }
+
+    /// A parser for a file `<include>`d or `<import>`ed from this one.
+    fn sub_parser(&self, path: &Path) -> XmlParser {
+        let parser = XmlParser::new()
+            .with_source_name(path.display().to_string())
+            .with_base_dir(path.parent().unwrap_or_else(|| Path::new(".")));
+        if self.origins {
+            parser.with_origins()
+        } else {
+            parser
+        }
+    }
+
     /// Parses XML content into a Value.
     pub fn parse(&self, content: &str) -> Result<Value, ParseError> {
         // Reset the anonymous-component counter so ids are deterministic per
@@ -363,10 +448,18 @@ impl XmlParser {
         let mut reader = Reader::from_str(content);
         reader.config_mut().trim_text(true);
 
-        let root = self
+        let mut root = self
             .parse_element(&mut reader, None)
             .map_err(|e| ParseError::new(e, SourceLocation::new(&self.source_name, 1, 1)))?;
+        self.locate_origins(&mut root, content);
+        let mut value = self.process_document(&root)?;
+        strip_raw_origins(&mut value);
+        Ok(value)
+    }
 
+    /// Processes the parsed document root: unwraps `<nemo>` and processes its
+    /// children into top-level keys.
+    fn process_document(&self, root: &Value) -> Result<Value, ParseError> {
         // The document-level parse returns an object with __children__ containing the <nemo> element.
         // We need to unwrap <nemo> and process its children into top-level keys.
         if let Some(doc_children) = root.as_object().and_then(|m| m.get("__children__")) {
@@ -429,6 +522,18 @@ impl XmlParser {
                 "data" => {
                     self.process_data(obj, &mut result);
                 }
+                "state" => {
+                    result.insert("state".to_string(), self.process_state(obj));
+                }
+                "commands" => {
+                    result.insert("commands".to_string(), self.process_commands(obj));
+                }
+                "keymap" => {
+                    result.insert("keymap".to_string(), self.process_keymap(obj));
+                }
+                "i18n" => {
+                    result.insert("i18n".to_string(), self.process_i18n(obj)?);
+                }
                 "template" => {
                     self.process_template(obj, &mut result);
                 }
@@ -865,6 +970,156 @@ impl XmlParser {
         }
     }
 
+    /// Processes a `<state>` block into the `state` object.
+    ///
+    /// Attributes (`persist`, `version`, `migrate`, `file`, `debounce`) are
+    /// copied through. Each `<field name="…" type="…" default="…"/>` child
+    /// becomes an entry in `state.fields` holding its initial value: coerced
+    /// to the declared `type` when present, a JSON object when the default
+    /// looks like one, and otherwise whatever attribute coercion produced
+    /// (arrays are already parsed from JSON by `coerce_value`). A field with no
+    /// default starts as `null`.
+    fn process_state(&self, obj: &IndexMap<String, Value>) -> Value {
+        let mut state = IndexMap::new();
+
+        for (key, val) in obj {
+            match key.as_str() {
+                "__type__" | "__children__" | "__cdata__" => continue,
+                _ => {
+                    state.insert(key.clone(), val.clone());
+                }
+            }
+        }
+
+        let mut fields = IndexMap::new();
+        if let Some(children) = obj.get("__children__").and_then(|v| v.as_array()) {
+            for child in children {
+                let Some(child_obj) = child.as_object() else {
+                    continue;
+                };
+                if child_obj.get("__type__").and_then(|v| v.as_str()) != Some("field") {
+                    continue;
+                }
+                let Some(name) = child_obj
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .filter(|n| !n.is_empty())
+                else {
+                    continue;
+                };
+                let ty = child_obj.get("type").and_then(|v| v.as_str());
+                let initial = match child_obj.get("default") {
+                    None => Value::Null,
+                    Some(Value::String(s)) if s.trim_start().starts_with('{') => {
+                        serde_json::from_str::<serde_json::Value>(s)
+                            .map(Value::from)
+                            .unwrap_or_else(|_| Value::String(s.clone()))
+                    }
+                    // Like a prop `default`, a scalar may already be typed by
+                    // `coerce_value`; render it back and coerce to `type`.
+                    Some(
+                        v @ (Value::String(_)
+                        | Value::Integer(_)
+                        | Value::Float(_)
+                        | Value::Bool(_)),
+                    ) => match ty {
+                        Some(ty) => {
+                            coerce_typed_value(ty, &v.to_string()).unwrap_or_else(|| v.clone())
+                        }
+                        None => v.clone(),
+                    },
+                    Some(other) => other.clone(),
+                };
+                fields.insert(name.to_string(), initial);
+            }
+        }
+        state.insert("fields".to_string(), Value::Object(fields));
+
+        Value::Object(state)
+    }
+
+    /// Processes a `<commands>` block into the `commands` array: one object
+    /// per `<command>` child with its attributes (`id`, `name`, `key`,
+    /// `description`, `on_run`). A command without a `name` can't be shown in
+    /// the palette and is skipped; one without an `id` gets its name.
+    fn process_commands(&self, obj: &IndexMap<String, Value>) -> Value {
+        let mut commands = Vec::new();
+        if let Some(children) = obj.get("__children__").and_then(|v| v.as_array()) {
+            for child in children {
+                let Some(child_obj) = child.as_object() else {
+                    continue;
+                };
+                if child_obj.get("__type__").and_then(|v| v.as_str()) != Some("command") {
+                    continue;
+                }
+                let Some(name) = child_obj
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .filter(|n| !n.trim().is_empty())
+                else {
+                    continue;
+                };
+                let mut command = IndexMap::new();
+                command.insert("id".to_string(), Value::String(name.to_string()));
+                for (key, val) in child_obj {
+                    match key.as_str() {
+                        "__type__" | "__children__" | "__cdata__" => continue,
+                        _ => {
+                            command.insert(key.clone(), val.clone());
+                        }
+                    }
+                }
+                commands.push(Value::Object(command));
+            }
+        }
+        Value::Array(commands)
+    }
+
+    /// Processes a `<keymap>` block into the `keymap` array: one object per
+    /// `<key>` child with its attributes (`binding`, `handler`, `when`). A key
+    /// without a `binding` is skipped; `nemo validate` reports the rest.
+    fn process_keymap(&self, obj: &IndexMap<String, Value>) -> Value {
+        let mut keys = Vec::new();
+        if let Some(children) = obj.get("__children__").and_then(|v| v.as_array()) {
+            for child in children {
+                let Some(child_obj) = child.as_object() else {
+                    continue;
+                };
+                if child_obj.get("__type__").and_then(|v| v.as_str()) != Some("key") {
+                    continue;
+                }
+                let has_binding = child_obj
+                    .get("binding")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|b| !b.trim().is_empty());
+                if !has_binding {
+                    continue;
+                }
+                let key: IndexMap<String, Value> = child_obj
+                    .iter()
+                    .filter(|(k, _)| {
+                        !matches!(k.as_str(), "__type__" | "__children__" | "__cdata__")
+                    })
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                keys.push(Value::Object(key));
+            }
+        }
+        Value::Array(keys)
+    }
+
+    /// Processes `<i18n dir="locales" default="en"/>`: loads the message
+    /// catalogs from `dir` (relative to the including file) into the
+    /// `i18n` block. See [`crate::i18n`] for the catalog formats.
+    fn process_i18n(&self, obj: &IndexMap<String, Value>) -> Result<Value, ParseError> {
+        let dir = obj.get("dir").and_then(|v| v.as_str()).unwrap_or("locales");
+        let default = obj.get("default").and_then(|v| v.as_str()).unwrap_or("en");
+        let path = self.resolve_path(dir);
+        crate::i18n::i18n_block(&path, dir, default).map_err(|e| {
+            ParseError::new(format!("<i18n dir=\"{dir}\">: {}", e.message), e.location)
+        })
+    }
+
     /// Cleans a data element (source/sink), removing internal keys and `name`.
     fn clean_data_element(&self, obj: &IndexMap<String, Value>) -> Value {
         let mut cleaned = IndexMap::new();
@@ -964,7 +1219,7 @@ impl XmlParser {
         let mut template_attrs = IndexMap::new();
         for (key, val) in obj {
             match key.as_str() {
-                "__type__" | "__children__" | "__cdata__" | "name" => continue,
+                "__type__" | "__children__" | "__cdata__" | "__origin__" | "name" => continue,
                 _ => {
                     template_attrs.insert(key.clone(), val.clone());
                 }
@@ -1032,14 +1287,7 @@ impl XmlParser {
             )
         })?;
 
-        let include_parser = XmlParser::new()
-            .with_source_name(include_path.display().to_string())
-            .with_base_dir(
-                include_path
-                    .parent()
-                    .unwrap_or(Path::new("."))
-                    .to_path_buf(),
-            );
+        let include_parser = self.sub_parser(&include_path);
 
         let included = include_parser.parse(&content)?;
 
@@ -1073,9 +1321,12 @@ impl XmlParser {
         let mut reader = Reader::from_str(&blocks.template_xml);
         reader.config_mut().trim_text(true);
 
-        let root = self
+        let mut root = self
             .parse_element(&mut reader, None)
             .map_err(|e| ParseError::new(e, SourceLocation::new(&self.source_name, 1, 1)))?;
+        // The template XML keeps the source's byte offsets (the raw-text
+        // blocks are blanked in place), so positions map onto `content`.
+        self.locate_origins(&mut root, content);
 
         let children = root
             .as_object()
@@ -1088,6 +1339,8 @@ impl XmlParser {
         let mut template: Option<Value> = None;
         let mut props: Vec<SfcProp> = Vec::new();
         let mut slots: Vec<SfcSlot> = Vec::new();
+        let mut emits: Vec<String> = Vec::new();
+        let mut state: IndexMap<String, Value> = IndexMap::new();
         // App-level block accumulators (app.nemo). Each is built with the same
         // process_* logic process_root uses, so compile_app_sfc can assemble
         // the identical Value tree.
@@ -1109,6 +1362,7 @@ impl XmlParser {
                             | "import"
                             | "components"
                             | "themes"
+                            | "i18n"
                     ) || (t == "script" && child.as_object().unwrap().get("__children__").is_none())
                 })
                 .unwrap_or(false)
@@ -1145,19 +1399,33 @@ impl XmlParser {
                                 .and_then(|v| v.as_str())
                                 .unwrap_or("string")
                                 .to_string();
+                            let one_of: Vec<String> = po
+                                .get("one_of")
+                                .map(|v| {
+                                    v.to_string()
+                                        .split(',')
+                                        .map(|s| s.trim().to_string())
+                                        .filter(|s| !s.is_empty())
+                                        .collect()
+                                })
+                                .unwrap_or_default();
                             // `default` is an attribute, so `coerce_value` may
-                            // have already typed it (e.g. `"3"` → Integer). Render
-                            // it back to a string and coerce to the declared type.
-                            let default = po.get("default").and_then(|v| {
-                                let raw = match v {
-                                    Value::String(s) => s.clone(),
-                                    Value::Integer(i) => i.to_string(),
-                                    Value::Float(f) => f.to_string(),
-                                    Value::Bool(b) => b.to_string(),
-                                    _ => return None,
-                                };
-                                coerce_typed_value(&ty, &raw)
-                            });
+                            // have already typed it (e.g. `"3"` → Integer); it is
+                            // coerced to the declared type like an instance value.
+                            let default = match po.get("default") {
+                                Some(v) => {
+                                    Some(coerce_prop_value(&ty, &one_of, v).map_err(|e| {
+                                        ParseError::new(
+                                            format!(
+                                                "SFC <prop name=\"{}\"> default '{}': {}",
+                                                pname, v, e
+                                            ),
+                                            SourceLocation::new(&self.source_name, 1, 1),
+                                        )
+                                    })?)
+                                }
+                                None => None,
+                            };
                             let required = po
                                 .get("required")
                                 .and_then(|v| v.as_bool())
@@ -1165,12 +1433,36 @@ impl XmlParser {
                             props.push(SfcProp {
                                 name: pname,
                                 ty,
+                                one_of,
                                 default,
                                 required,
                             });
                         }
                     }
                 }
+                Some("emits") => {
+                    let events = obj
+                        .get("__children__")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|c| c.as_object())
+                        .filter(|o| o.get("__type__").and_then(|v| v.as_str()) == Some("event"));
+                    for event in events {
+                        let ename = match event.get("name").and_then(|v| v.as_str()) {
+                            Some(n) if !n.is_empty() => kebab_to_snake(n),
+                            _ => {
+                                return Err(ParseError::new(
+                                    "SFC <event> requires a non-empty name".to_string(),
+                                    SourceLocation::new(&self.source_name, 1, 1),
+                                ))
+                            }
+                        };
+                        if !emits.contains(&ename) {
+                            emits.push(ename);
+                        }
+                    }
+                }
                 Some("template") => {
                     name = obj
                         .get("name")
@@ -1214,6 +1506,28 @@ impl XmlParser {
                 Some("data") => {
                     self.process_data(obj, &mut app_result);
                 }
+                Some("state") if has_app_blocks => {
+                    // Passes through `extra` to the top-level `state` key.
+                    app_result.insert("state".to_string(), self.process_state(obj));
+                }
+                Some("state") => {
+                    // A component's per-instance state: same `<field>` syntax,
+                    // but only the fields matter (nothing is persisted).
+                    if let Some(Value::Object(fields)) =
+                        self.process_state(obj).get("fields").cloned()
+                    {
+                        state = fields;
+                    }
+                }
+                Some("commands") => {
+                    app_result.insert("commands".to_string(), self.process_commands(obj));
+                }
+                Some("keymap") => {
+                    app_result.insert("keymap".to_string(), self.process_keymap(obj));
+                }
+                Some("i18n") => {
+                    app_result.insert("i18n".to_string(), self.process_i18n(obj)?);
+                }
                 Some("variable") => {
                     self.process_variable(obj, &mut app_result);
                 }
@@ -1280,13 +1594,21 @@ impl XmlParser {
             )
         })?;
 
-        Ok(SfcDefinition {
+        let script_origin = blocks
+            .script_offset
+            .filter(|_| self.origins)
+            .map(|offset| SourceLocation::from_offset(&self.source_name, content, offset));
+
+        let mut sfc = SfcDefinition {
             name,
             template,
             style: blocks.style,
             script: blocks.script,
+            script_origin,
             props,
             slots,
+            emits,
+            state,
             app_blocks: if has_app_blocks {
                 let app = app_result.shift_remove("app");
                 let data = app_result
@@ -1316,7 +1638,25 @@ impl XmlParser {
             } else {
                 None
             },
-        })
+        };
+        if self.origins {
+            strip_raw_origins(&mut sfc.template);
+            if let Some(blocks) = &mut sfc.app_blocks {
+                for value in [
+                    &mut blocks.data,
+                    &mut blocks.variables,
+                    &mut blocks.sfc_imports,
+                    &mut blocks.scripts,
+                    &mut blocks.extra,
+                ] {
+                    strip_raw_origins(value);
+                }
+                if let Some(app) = &mut blocks.app {
+                    strip_raw_origins(app);
+                }
+            }
+        }
+        Ok(sfc)
     }
 
     /// Recursively walks a raw parsed element tree collecting `<slot>` elements
@@ -1414,9 +1754,7 @@ impl XmlParser {
             )
         })?;
 
-        let sfc_parser = XmlParser::new()
-            .with_source_name(import_path.display().to_string())
-            .with_base_dir(import_path.parent().unwrap_or_else(|| Path::new(".")));
+        let sfc_parser = self.sub_parser(&import_path);
         let sfc = sfc_parser.parse_sfc(&content)?;
 
         // Resolve the tag: as= > <template name> > filename stem. The `as=`
@@ -1453,6 +1791,12 @@ impl XmlParser {
         if let Some(script) = sfc.script {
             entry.insert("script".to_string(), Value::String(script));
         }
+        if let Some(origin) = sfc.script_origin {
+            entry.insert(
+                SCRIPT_ORIGIN_KEY.to_string(),
+                Value::String(origin.to_string()),
+            );
+        }
         if !sfc.props.is_empty() {
             let props: Vec<Value> = sfc
                 .props
@@ -1461,6 +1805,10 @@ impl XmlParser {
                     let mut m = IndexMap::new();
                     m.insert("name".to_string(), Value::String(p.name));
                     m.insert("type".to_string(), Value::String(p.ty));
+                    if !p.one_of.is_empty() {
+                        let one_of = p.one_of.into_iter().map(Value::String).collect();
+                        m.insert("one_of".to_string(), Value::Array(one_of));
+                    }
                     if let Some(default) = p.default {
                         m.insert("default".to_string(), default);
                     }
@@ -1484,6 +1832,13 @@ impl XmlParser {
                 .collect();
             entry.insert("slots".to_string(), Value::Array(slots));
         }
+        if !sfc.emits.is_empty() {
+            let emits = sfc.emits.into_iter().map(Value::String).collect();
+            entry.insert("emits".to_string(), Value::Array(emits));
+        }
+        if !sfc.state.is_empty() {
+            entry.insert("state".to_string(), Value::Object(sfc.state));
+        }
         entry.insert(
             "source_path".to_string(),
             Value::String(source_path.to_string()),
@@ -1555,9 +1910,7 @@ impl XmlParser {
                     SourceLocation::new(&self.source_name, 1, 1),
                 )
             })?;
-            let sfc_parser = XmlParser::new()
-                .with_source_name(path.display().to_string())
-                .with_base_dir(path.parent().unwrap_or(dir));
+            let sfc_parser = self.sub_parser(&path);
             let sfc = sfc_parser.parse_sfc(&content)?;
             if let Some(mut tag) = sfc_default_tag(sfc.name.as_deref(), &path) {
                 if let Some(prefix) = tag_prefix {
@@ -1633,6 +1986,9 @@ impl XmlParser {
         for (key, val) in obj {
             match key.as_str() {
                 "__type__" | "__children__" | "name" => continue,
+                "__origin__" => {
+                    component.insert(ORIGIN_KEY.to_string(), val.clone());
+                }
                 _ => {
                     component.insert(key.clone(), val.clone());
                 }
@@ -1736,6 +2092,9 @@ impl XmlParser {
             match key.as_str() {
                 "__type__" | "__children__" | "__cdata__" => continue,
                 "id" => continue, // id is used as the map key, not a property
+                "__origin__" => {
+                    component.insert(ORIGIN_KEY.to_string(), val.clone());
+                }
                 _ => {
                     component.insert(key.clone(), val.clone());
                 }
@@ -1873,11 +2232,17 @@ impl XmlParser {
         loop {
             match reader.read_event() {
                 Ok(Event::Start(ref tag)) => {
-                    let child = self.parse_element(reader, Some(tag))?;
+                    // `<` + tag + `>`, ending where the reader now stands.
+                    let at = self.tag_offset(reader, tag.len() + 2);
+                    let mut child = self.parse_element(reader, Some(tag))?;
+                    mark_origin(&mut child, at);
                     children.push(child);
                 }
                 Ok(Event::Empty(ref tag)) => {
-                    let child = self.parse_empty_element(tag)?;
+                    // `<` + tag + `/>`.
+                    let at = self.tag_offset(reader, tag.len() + 3);
+                    let mut child = self.parse_empty_element(tag)?;
+                    mark_origin(&mut child, at);
                     children.push(child);
                 }
                 Ok(Event::CData(ref cdata)) => {
@@ -1923,6 +2288,42 @@ impl XmlParser {
         Ok(Value::Object(obj))
     }
 
+    /// The byte offset of the tag the reader just read (`len` bytes long,
+    /// markup included), when origins are recorded.
+    fn tag_offset(&self, reader: &Reader<&[u8]>, len: usize) -> Option<usize> {
+        self.origins
+            .then(|| (reader.buffer_position() as usize).saturating_sub(len))
+    }
+
+    /// Replaces the byte offsets [`mark_origin`] left under `__origin__` with
+    /// their `file:line:column` in `content`.
+    fn locate_origins(&self, value: &mut Value, content: &str) {
+        if !self.origins {
+            return;
+        }
+        match value {
+            Value::Object(obj) => {
+                if let Some(Value::Integer(offset)) = obj.get("__origin__") {
+                    let location =
+                        SourceLocation::from_offset(&self.source_name, content, *offset as usize);
+                    obj.insert(
+                        "__origin__".to_string(),
+                        Value::String(location.to_string()),
+                    );
+                }
+                for child in obj.values_mut() {
+                    self.locate_origins(child, content);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.locate_origins(item, content);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Re-serializes an embedded `<svg>` element (start tag through its
     /// matching end tag) into a single verbatim markup string, stored under
     /// `content`. `attrs` carries the already-parsed start-tag attributes; only
@@ -2161,6 +2562,30 @@ impl Default for XmlParser {
 }
 
 /// Converts kebab-case to snake_case.
+/// Records a raw element's byte offset under `__origin__`, for
+/// [`XmlParser::locate_origins`] to turn into `file:line:column`.
+fn mark_origin(element: &mut Value, offset: Option<usize>) {
+    if let (Some(offset), Value::Object(obj)) = (offset, element) {
+        obj.insert("__origin__".to_string(), Value::Integer(offset as i64));
+    }
+}
+
+/// Drops the `__origin__` keys of elements that didn't become components
+/// (data sources, app blocks, bindings, …); components carry theirs under
+/// [`ORIGIN_KEY`].
+fn strip_raw_origins(value: &mut Value) {
+    match value {
+        Value::Object(obj) => {
+            obj.shift_remove("__origin__");
+            for child in obj.values_mut() {
+                strip_raw_origins(child);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_raw_origins),
+        _ => {}
+    }
+}
+
 fn kebab_to_snake(s: &str) -> String {
     s.replace('-', "_")
 }
@@ -2174,7 +2599,7 @@ fn is_non_empty_map(v: &Value) -> bool {
 }
 
 /// Flattens an [`SfcDefinition`] into the `Value` stored under `config["sfc"][tag]`
-/// (`template`/`style`/`script`/`props`/`slots`/`source_path`). Public so
+/// (`template`/`style`/`script`/`props`/`slots`/`emits`/`state`/`source_path`). Public so
 /// `nemo build` produces artifacts in the same shape the runtime reads back.
 pub fn sfc_definition_to_value(sfc: SfcDefinition, source_path: &str) -> Value {
     XmlParser::sfc_to_value(sfc, source_path)
@@ -2200,7 +2625,7 @@ pub fn sfc_default_tag(template_name: Option<&str>, source_path: &Path) -> Optio
 }
 
 /// Coerces a string value to the appropriate Value type.
-fn coerce_value(s: &str) -> Value {
+pub(crate) fn coerce_value(s: &str) -> Value {
     // Check for booleans
     if s == "true" {
         return Value::Bool(true);
@@ -2389,6 +2814,91 @@ mod tests {
         assert_eq!(title.default, None);
     }
 
+    #[test]
+    fn test_parse_sfc_enum_and_json_props() {
+        let sfc = r#"
+        <props>
+          <prop name="tone" type="string" one-of="info, warn,error" default="info" />
+          <prop name="points" type="array" default="[1, 2, 3]" />
+        </props>
+        <template name="chart">
+          <panel />
+        </template>
+        "#;
+        let def = XmlParser::new().parse_sfc(sfc).unwrap();
+        assert_eq!(def.props[0].one_of, vec!["info", "warn", "error"]);
+        assert_eq!(
+            def.props[1].default,
+            Some(Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(2),
+                Value::Integer(3)
+            ]))
+        );
+
+        // A default must satisfy its own declaration.
+        let bad = sfc.replace(r#"default="info""#, r#"default="loud""#);
+        let err = XmlParser::new().parse_sfc(&bad).unwrap_err();
+        assert!(err.message.contains("expected one of info, warn, error"));
+    }
+
+    #[test]
+    fn test_parse_sfc_emits() {
+        let sfc = r#"
+        <emits>
+          <event name="archive" />
+          <event name="item-moved" />
+          <event name="archive" />
+        </emits>
+        <template name="todo-card">
+          <panel />
+        </template>
+        "#;
+        let def = XmlParser::new().parse_sfc(sfc).unwrap();
+        // Names are kebab→snake normalized like the `on-*` attributes that
+        // handle them, and duplicates collapse.
+        assert_eq!(def.emits, vec!["archive", "item_moved"]);
+
+        let value = sfc_definition_to_value(def, "todo-card.nemo");
+        assert_eq!(
+            value.get("emits"),
+            Some(&Value::Array(vec![
+                Value::String("archive".to_string()),
+                Value::String("item_moved".to_string()),
+            ]))
+        );
+
+        let unnamed = r#"<emits><event /></emits><template><panel /></template>"#;
+        assert!(XmlParser::new().parse_sfc(unnamed).is_err());
+    }
+
+    #[test]
+    fn test_parse_sfc_instance_state() {
+        let sfc = r#"
+        <state>
+          <field name="open" type="bool" default="false" />
+          <field name="count" type="int" default="0" />
+          <field name="note" />
+        </state>
+        <template name="counter-card">
+          <panel />
+        </template>
+        "#;
+        let def = XmlParser::new().parse_sfc(sfc).unwrap();
+        // A component's <state> is per-instance fields, not the app block.
+        assert!(def.app_blocks.is_none());
+        assert_eq!(def.state.get("open"), Some(&Value::Bool(false)));
+        assert_eq!(def.state.get("count"), Some(&Value::Integer(0)));
+        assert_eq!(def.state.get("note"), Some(&Value::Null));
+
+        let value = sfc_definition_to_value(def, "counter-card.nemo");
+        let state = value.get("state").and_then(|v| v.as_object()).unwrap();
+        assert_eq!(
+            state.keys().collect::<Vec<_>>(),
+            vec!["open", "count", "note"]
+        );
+    }
+
     #[test]
     fn test_parse_sfc_slot_specs() {
         let sfc = r#"
@@ -2550,6 +3060,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_commands_block() {
+        let xml = r#"
+        <nemo>
+            <commands>
+                <command id="save" name="Save Tasks" key="ctrl-s" on-run="save_all" />
+                <command name="Clear Completed" on-run="clear_done"
+                         description="Remove checked-off tasks" />
+                <command key="ctrl-k" on-run="nameless" />
+            </commands>
+        </nemo>
+        "#;
+
+        let value = XmlParser::new().parse(xml).unwrap();
+        let commands = value.get("commands").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(commands.len(), 2, "a command without a name is skipped");
+
+        assert_eq!(commands[0].get("id"), Some(&Value::String("save".into())));
+        assert_eq!(
+            commands[0].get("key"),
+            Some(&Value::String("ctrl-s".into()))
+        );
+        assert_eq!(
+            commands[0].get("on_run"),
+            Some(&Value::String("save_all".into()))
+        );
+        // The id defaults to the name.
+        assert_eq!(
+            commands[1].get("id"),
+            Some(&Value::String("Clear Completed".into()))
+        );
+        assert_eq!(
+            commands[1].get("description"),
+            Some(&Value::String("Remove checked-off tasks".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_keymap_block() {
+        let xml = r#"
+        <nemo>
+            <keymap>
+                <key binding="ctrl-s" handler="on_save" when="focus:editor" />
+                <key binding="ctrl-k ctrl-d" handler="handlers::duplicate" />
+                <key handler="unbound" />
+            </keymap>
+        </nemo>
+        "#;
+
+        let value = XmlParser::new().parse(xml).unwrap();
+        let keys = value.get("keymap").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(keys.len(), 2, "a key without a binding is skipped");
+        assert_eq!(
+            keys[0].get("binding"),
+            Some(&Value::String("ctrl-s".into()))
+        );
+        assert_eq!(
+            keys[0].get("handler"),
+            Some(&Value::String("on_save".into()))
+        );
+        assert_eq!(
+            keys[0].get("when"),
+            Some(&Value::String("focus:editor".into()))
+        );
+        assert_eq!(keys[1].get("when"), None);
+    }
+
     #[test]
     fn test_parse_header_bar_menu_items() {
         let xml = r#"
@@ -2843,6 +3420,51 @@ mod tests {
         assert_eq!(commands.get("port"), Some(&Value::Integer(1883)));
     }
 
+    #[test]
+    fn test_parse_state_block() {
+        let xml = r#"
+        <nemo>
+            <state persist="true" version="2" migrate="migrate_prefs">
+                <field name="theme" default="dark" />
+                <field name="volume" type="float" default="3" />
+                <field name="recent" default='["a","b"]' />
+                <field name="window" default='{"w": 800, "h": 600}' />
+                <field name="selection" />
+            </state>
+        </nemo>
+        "#;
+
+        let parser = XmlParser::new();
+        let value = parser.parse(xml).unwrap();
+
+        let state = value.get("state").unwrap();
+        assert_eq!(state.get("persist"), Some(&Value::Bool(true)));
+        assert_eq!(state.get("version"), Some(&Value::Integer(2)));
+        assert_eq!(
+            state.get("migrate"),
+            Some(&Value::String("migrate_prefs".to_string()))
+        );
+
+        let fields = state.get("fields").unwrap();
+        assert_eq!(
+            fields.get("theme"),
+            Some(&Value::String("dark".to_string()))
+        );
+        assert_eq!(fields.get("volume"), Some(&Value::Float(3.0)));
+        assert_eq!(
+            fields
+                .get("recent")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len()),
+            Some(2)
+        );
+        assert_eq!(
+            fields.get("window").and_then(|w| w.get("w")),
+            Some(&Value::Integer(800))
+        );
+        assert_eq!(fields.get("selection"), Some(&Value::Null));
+    }
+
     #[test]
     fn test_parse_layout_with_components() {
         let xml = r#"
@@ -3901,4 +4523,65 @@ button:hover { opacity: 0.8; }
             "component SFC must have no app_blocks"
         );
     }
+
+    /// With origins on, each component carries the `file:line:column` of its
+    /// `<` — lines still count correctly below a blanked `<script>` — and the
+    /// script its start; raw elements (data, bindings) carry nothing.
+    #[test]
+    fn test_origins_locate_components_and_script() {
+        let app = r#"<script>
+fn go() {
+    bad()
+}
+</script>
+<data>
+  <source name="s" type="static" />
+</data>
+<template name="app">
+  <stack id="root">
+    <label id="title" text="hi" origin="north" />
+    <button id="go"
+            on-click="go">
+      <binding source="data.s" target="text" />
+    </button>
+  </stack>
+</template>"#;
+        let value = XmlParser::new()
+            .with_source_name("app.nemo")
+            .with_origins()
+            .compile_app_sfc(app)
+            .unwrap();
+        let layout = value.get("layout").unwrap();
+        let title = layout.get("component").unwrap().get("title").unwrap();
+        assert_eq!(
+            title.get(ORIGIN_KEY).unwrap().as_str(),
+            Some("app.nemo:11:5")
+        );
+        // An attribute named `origin` stays the component's own.
+        assert_eq!(title.get("origin").unwrap().as_str(), Some("north"));
+        let go = layout.get("component").unwrap().get("go").unwrap();
+        assert_eq!(go.get(ORIGIN_KEY).unwrap().as_str(), Some("app.nemo:12:5"));
+        assert!(go.get("binding").unwrap().get("__origin__").is_none());
+        assert!(!value.get("data").unwrap().to_string().contains("origin"));
+
+        let def = XmlParser::new()
+            .with_source_name("app.nemo")
+            .with_origins()
+            .parse_sfc(app)
+            .unwrap();
+        assert_eq!(
+            def.script_origin,
+            Some(SourceLocation::new("app.nemo", 2, 1))
+        );
+        let entry = XmlParser::sfc_to_value(def, "app.nemo");
+        assert_eq!(
+            entry.get(SCRIPT_ORIGIN_KEY).and_then(|v| v.as_str()),
+            Some("app.nemo:2:1")
+        );
+        assert!(entry.get("script_origin").is_none());
+
+        // Off by default: the tree carries no positions.
+        let plain = XmlParser::new().compile_app_sfc(app).unwrap();
+        assert!(!plain.to_string().contains(ORIGIN_KEY));
+    }
 }
@@ -104,6 +104,7 @@ pub enum PipelineError {
 /// | `InvalidPath` | Fatal       | Malformed path string; fix config         |
 /// | `TypeMismatch`| Fatal       | Schema/binding mismatch; fix config       |
 /// | `LockError`   | Fatal       | Mutex poisoned — unrecoverable            |
+/// | `Persistence` | Transient   | State file unreadable/unwritable; retried on next flush |
 #[derive(Debug, Error)]
 pub enum RepositoryError {
     /// Path not found.
@@ -125,6 +126,10 @@ pub enum RepositoryError {
     /// Lock error.
     #[error("Failed to acquire lock")]
     LockError,
+
+    /// Persistent store I/O or (de)serialization error.
+    #[error("Persistence error: {0}")]
+    Persistence(String),
 }
 
 /// Error in the binding system.
@@ -56,6 +56,7 @@
 //! | `sources`   | Concrete sources: HTTP, WebSocket, File, Timer, etc.  |
 //! | `transform` | `Transform` trait, `Pipeline`, built-in transforms    |
 //! | `repository`| `DataRepository`, `DataStore`, `DataPath`              |
+//! | `persist`   | `FileStore`, `StatePersister` for `state.*` on disk    |
 //! | `binding`   | `BindingSystem`, `Binding`, `BindingTarget`            |
 //! | `action`    | `ActionSystem`, `Action`, `TriggerCondition`           |
 //! | `error`     | All error types for the data layer                     |
@@ -63,6 +64,7 @@
 pub mod action;
 pub mod binding;
 pub mod error;
+pub mod persist;
 pub mod repository;
 pub mod source;
 pub mod sources;
@@ -76,6 +78,7 @@ pub use error::{
     ActionError, BindingError, DataFlowError, DataSourceError, PipelineError, RepositoryError,
     TransformError,
 };
+pub use persist::{FileStore, StatePersister, DEFAULT_PERSIST_DEBOUNCE};
 pub use repository::{
     DataPath, DataRepository, DataStore, MemoryStore, PathSegment, RepositoryChange,
 };
@@ -0,0 +1,312 @@
+//! Persistent storage for the repository's `state.*` namespace.
+//!
+//! A `<state persist="true">` block keeps the `state` subtree on disk so user
+//! preferences and small local datasets survive restarts. [`FileStore`] is the
+//! [`DataStore`] that owns the versioned JSON file; [`StatePersister`] watches
+//! the repository for `state.*` changes and flushes a debounced snapshot.
+//!
+//! The on-disk envelope records the declared schema version next to the
+//! values, so the host can run a migration when the app's declared version
+//! changes:
+//!
+//! ```json
+//! { "version": 2, "values": { "theme": "dark", "recent": ["a.csv"] } }
+//! ```
+
+use crate::error::RepositoryError;
+use crate::repository::{DataPath, DataRepository, DataStore};
+use indexmap::IndexMap;
+use nemo_config::Value;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Default delay between the last `state.*` change and the flush to disk.
+pub const DEFAULT_PERSIST_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The serialized form of a [`FileStore`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StateFile {
+    version: i64,
+    #[serde(default)]
+    values: IndexMap<String, Value>,
+}
+
+/// A [`DataStore`] backed by a versioned JSON file.
+///
+/// Writes only touch memory; [`flush`](Self::flush) persists them atomically
+/// (temp file + rename), so a crash mid-write never leaves a truncated file.
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    /// Version of the held values; `None` until a file is read or a version set.
+    version: Option<i64>,
+    data: IndexMap<String, Value>,
+}
+
+impl FileStore {
+    /// Opens the store at `path`. A missing file yields an empty, unversioned
+    /// store; an unreadable or malformed file is an error.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, RepositoryError> {
+        let path = path.into();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    path,
+                    version: None,
+                    data: IndexMap::new(),
+                })
+            }
+            Err(e) => {
+                return Err(RepositoryError::Persistence(format!(
+                    "{}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        let file: StateFile = serde_json::from_str(&content)
+            .map_err(|e| RepositoryError::Persistence(format!("{}: {}", path.display(), e)))?;
+        Ok(Self {
+            path,
+            version: Some(file.version),
+            data: file.values,
+        })
+    }
+
+    /// Returns the backing file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the version of the held values (`None` for a store opened on a
+    /// missing file).
+    pub fn version(&self) -> Option<i64> {
+        self.version
+    }
+
+    /// Sets the version written by the next [`flush`](Self::flush).
+    pub fn set_version(&mut self, version: i64) {
+        self.version = Some(version);
+    }
+
+    /// Returns all held values as a `Value::Object`.
+    pub fn values(&self) -> Value {
+        Value::Object(self.data.clone())
+    }
+
+    /// Replaces all held values with the entries of `values`. A non-object
+    /// clears the store.
+    pub fn replace(&mut self, values: &Value) {
+        self.data = values.as_object().cloned().unwrap_or_default();
+    }
+
+    /// Writes the store to disk, creating parent directories as needed.
+    pub fn flush(&self) -> Result<(), RepositoryError> {
+        let persistence = |e: std::io::Error| {
+            RepositoryError::Persistence(format!("{}: {}", self.path.display(), e))
+        };
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(persistence)?;
+        }
+        let file = StateFile {
+            version: self.version.unwrap_or_default(),
+            values: self.data.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| RepositoryError::Persistence(e.to_string()))?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(persistence)?;
+        std::fs::rename(&tmp, &self.path).map_err(persistence)
+    }
+}
+
+impl DataStore for FileStore {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.data.get(key).cloned()
+    }
+
+    fn set(&mut self, key: &str, value: Value) {
+        self.data.insert(key.to_string(), value);
+    }
+
+    fn delete(&mut self, key: &str) {
+        self.data.shift_remove(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.data.keys().cloned().collect()
+    }
+
+    fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+/// Flushes the repository's `state` subtree to a [`FileStore`] a short
+/// debounce after the last `state.*` change.
+///
+/// Cloning shares the underlying store, so the host can keep a handle for a
+/// final synchronous [`flush`](Self::flush) at shutdown while the watcher task
+/// runs.
+#[derive(Clone)]
+pub struct StatePersister {
+    store: Arc<Mutex<FileStore>>,
+    debounce: Duration,
+}
+
+impl StatePersister {
+    /// Creates a persister writing to `store` after `debounce` of quiet.
+    pub fn new(store: FileStore, debounce: Duration) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            debounce,
+        }
+    }
+
+    /// Snapshots the repository's `state` subtree and writes it now.
+    pub fn flush(&self, repository: &DataRepository) -> Result<(), RepositoryError> {
+        let snapshot = repository
+            .get(&DataPath::parse("state")?)
+            .unwrap_or_else(|| Value::Object(IndexMap::new()));
+        let mut store = self.store.lock().map_err(|_| RepositoryError::LockError)?;
+        store.replace(&snapshot);
+        store.flush()
+    }
+
+    /// Spawns the watcher task on the current tokio runtime. Each `state.*`
+    /// change (re)arms the debounce timer; when it fires the snapshot is
+    /// flushed. A pending flush is written before the task exits on
+    /// `shutdown` or when the repository's change channel closes.
+    pub fn spawn(&self, repository: Arc<DataRepository>, shutdown: Arc<Notify>) -> JoinHandle<()> {
+        let persister = self.clone();
+        let mut changes = repository.subscribe();
+
+        tokio::spawn(async move {
+            let flush = |persister: &StatePersister| {
+                if let Err(e) = persister.flush(&repository) {
+                    tracing::warn!("Failed to persist state: {}", e);
+                }
+            };
+            let mut deadline: Option<Instant> = None;
+
+            loop {
+                let timer = async {
+                    match deadline {
+                        Some(at) => tokio::time::sleep_until(at).await,
+                        None => std::future::pending().await,
+                    }
+                };
+
+                tokio::select! {
+                    _ = shutdown.notified() => {
+                        if deadline.is_some() {
+                            flush(&persister);
+                        }
+                        break;
+                    }
+                    _ = timer => {
+                        deadline = None;
+                        flush(&persister);
+                    }
+                    result = changes.recv() => match result {
+                        Ok(change) => {
+                            if change.path.is_in_namespace("state") {
+                                deadline = Some(Instant::now() + persister.debounce);
+                            }
+                        }
+                        // Missed changes may have touched `state`; flush to be safe.
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            deadline = Some(Instant::now() + persister.debounce);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            if deadline.is_some() {
+                                flush(&persister);
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::open(dir.path().join("state.json")).unwrap();
+        assert_eq!(store.version(), None);
+        assert!(store.keys().is_empty());
+    }
+
+    #[test]
+    fn test_file_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+
+        let mut store = FileStore::open(&path).unwrap();
+        store.set("theme", Value::String("dark".into()));
+        store.set("volume", Value::Float(0.5));
+        store.set_version(3);
+        store.flush().unwrap();
+
+        let reopened = FileStore::open(&path).unwrap();
+        assert_eq!(reopened.version(), Some(3));
+        assert_eq!(reopened.get("theme"), Some(Value::String("dark".into())));
+        assert_eq!(reopened.get("volume"), Some(Value::Float(0.5)));
+    }
+
+    #[test]
+    fn test_file_store_malformed_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            FileStore::open(&path),
+            Err(RepositoryError::Persistence(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_persister_debounces_state_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let mut store = FileStore::open(&path).unwrap();
+        store.set_version(1);
+
+        let repository = Arc::new(DataRepository::new());
+        let shutdown = Arc::new(Notify::new());
+        let persister = StatePersister::new(store, Duration::from_millis(200));
+        let task = persister.spawn(Arc::clone(&repository), Arc::clone(&shutdown));
+
+        let count = DataPath::parse("state.count").unwrap();
+        repository.set(&count, Value::Integer(1)).unwrap();
+        repository.set(&count, Value::Integer(2)).unwrap();
+        // A `data.*` write alone never arms the timer.
+        repository
+            .set(&DataPath::parse("data.feed").unwrap(), Value::Integer(9))
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!path.exists(), "flushed before the debounce elapsed");
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let saved = FileStore::open(&path).unwrap();
+        assert_eq!(saved.version(), Some(1));
+        assert_eq!(saved.get("count"), Some(Value::Integer(2)));
+        assert_eq!(saved.get("feed"), None);
+
+        shutdown.notify_waiters();
+        task.await.unwrap();
+    }
+}
@@ -173,6 +173,12 @@ impl DataPath {
         Ok(())
     }
 
+    /// Returns `true` if this path lies under the top-level `namespace`
+    /// (`data`, `state`, `var`), including the namespace root itself.
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        matches!(self.segments.first(), Some(PathSegment::Property(p)) if p == namespace)
+    }
+
     /// Checks if this path matches another path (for subscriptions).
     pub fn matches(&self, other: &DataPath) -> bool {
         if self.segments.len() != other.segments.len() {
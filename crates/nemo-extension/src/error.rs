@@ -42,8 +42,14 @@ pub enum ExtensionError {
     UnloadError { id: String, reason: String },
 
     /// Script execution error (transient — may succeed after fix/reload).
+    /// `position` is the 1-based `(line, column)` in the script Rhai reports
+    /// the failure at, when it knows one.
     #[error("Script execution error in '{script_id}': {reason}")]
-    ScriptError { script_id: String, reason: String },
+    ScriptError {
+        script_id: String,
+        reason: String,
+        position: Option<(usize, usize)>,
+    },
 
     /// Plugin initialization error (fatal — entry function failed).
     #[error("Plugin initialization error in '{plugin_id}': {reason}")]
@@ -80,3 +86,52 @@ impl From<Box<rhai::EvalAltResult>> for ExtensionError {
         ExtensionError::Rhai(err.to_string())
     }
 }
+
+impl ExtensionError {
+    /// A [`ScriptError`](Self::ScriptError) for a failed evaluation, located
+    /// at the innermost position the error carries: for a failure inside a
+    /// nested function call, the failing statement rather than the call site.
+    pub(crate) fn script_eval(script_id: &str, err: &rhai::EvalAltResult) -> Self {
+        ExtensionError::ScriptError {
+            script_id: script_id.to_string(),
+            reason: err.to_string(),
+            position: eval_position(err),
+        }
+    }
+
+    /// A [`ScriptError`](Self::ScriptError) for a script that doesn't compile.
+    pub(crate) fn script_parse(script_id: &str, err: &rhai::ParseError) -> Self {
+        ExtensionError::ScriptError {
+            script_id: script_id.to_string(),
+            reason: err.to_string(),
+            position: line_column(err.position()),
+        }
+    }
+
+    /// The script id and `(line, column)` a script error happened at, when
+    /// known.
+    pub fn script_position(&self) -> Option<(&str, usize, usize)> {
+        match self {
+            ExtensionError::ScriptError {
+                script_id,
+                position: Some((line, column)),
+                ..
+            } => Some((script_id, *line, *column)),
+            _ => None,
+        }
+    }
+}
+
+/// The innermost known position of an evaluation error.
+fn eval_position(err: &rhai::EvalAltResult) -> Option<(usize, usize)> {
+    let inner = match err {
+        rhai::EvalAltResult::ErrorInFunctionCall(_, _, inner, _)
+        | rhai::EvalAltResult::ErrorInModule(_, inner, _) => eval_position(inner),
+        _ => None,
+    };
+    inner.or_else(|| line_column(err.position()))
+}
+
+fn line_column(position: rhai::Position) -> Option<(usize, usize)> {
+    Some((position.line()?, position.position().unwrap_or(1)))
+}
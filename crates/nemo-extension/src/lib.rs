@@ -182,6 +182,17 @@ impl ExtensionManager {
         self.rhai_engine.call(script_id, function, args)
     }
 
+    /// Calls a script function with [`PluginValue`] arguments, returning its
+    /// result as a [`PluginValue`].
+    pub fn call_script_value(
+        &mut self,
+        script_id: &str,
+        function: &str,
+        args: Vec<PluginValue>,
+    ) -> Result<PluginValue, ExtensionError> {
+        self.rhai_engine.call_value(script_id, function, args)
+    }
+
     /// Evaluates a RHAI expression.
     pub fn eval<T: Clone + Send + Sync + 'static>(&self, expr: &str) -> Result<T, ExtensionError> {
         self.rhai_engine.eval(expr)
@@ -253,18 +253,12 @@ impl RhaiEngine {
         let ast = self
             .engine
             .compile(source)
-            .map_err(|e| ExtensionError::ScriptError {
-                script_id: id.to_string(),
-                reason: e.to_string(),
-            })?;
+            .map_err(|e| ExtensionError::script_parse(id, &e))?;
 
         let mut scope = Scope::new();
         self.engine
             .run_ast_with_scope(&mut scope, &ast)
-            .map_err(|e| ExtensionError::ScriptError {
-                script_id: id.to_string(),
-                reason: e.to_string(),
-            })?;
+            .map_err(|e| ExtensionError::script_eval(id, &e))?;
 
         let compiled = CompiledScript { ast, scope };
 
@@ -307,10 +301,32 @@ impl RhaiEngine {
 
         self.engine
             .call_fn(&mut script.scope, &script.ast, function, args)
-            .map_err(|e| ExtensionError::ScriptError {
-                script_id: script_id.to_string(),
-                reason: e.to_string(),
-            })
+            .map_err(|e| ExtensionError::script_eval(script_id, &e))
+    }
+
+    /// Calls a function in a script with [`PluginValue`] arguments and
+    /// converts its return value back to a [`PluginValue`].
+    ///
+    /// For hosts that do not depend on `rhai` directly (e.g. the `<state>`
+    /// `migrate(old_version, value)` hook).
+    pub fn call_value(
+        &mut self,
+        script_id: &str,
+        function: &str,
+        args: Vec<PluginValue>,
+    ) -> Result<PluginValue, ExtensionError> {
+        let script = self
+            .scripts
+            .get_mut(script_id)
+            .ok_or_else(|| ExtensionError::NotFound {
+                id: script_id.to_string(),
+            })?;
+        let args: Vec<Dynamic> = args.into_iter().map(plugin_value_to_dynamic).collect();
+
+        self.engine
+            .call_fn::<Dynamic>(&mut script.scope, &script.ast, function, args)
+            .map(dynamic_to_plugin_value)
+            .map_err(|e| ExtensionError::script_eval(script_id, &e))
     }
 
     /// Evaluates an expression.
@@ -340,10 +356,7 @@ impl RhaiEngine {
 
         self.engine
             .run_ast(&script.ast)
-            .map_err(|e| ExtensionError::ScriptError {
-                script_id: script_id.to_string(),
-                reason: e.to_string(),
-            })?;
+            .map_err(|e| ExtensionError::script_eval(script_id, &e))?;
 
         Ok(Dynamic::UNIT)
     }
@@ -405,6 +418,24 @@ impl RhaiEngine {
                 }
             });
 
+        let ctx = context.clone();
+        self.engine
+            .register_fn("get_state", move |path: &str| -> Dynamic {
+                match ctx.get_state(path) {
+                    Some(value) => plugin_value_to_dynamic(value),
+                    None => Dynamic::UNIT,
+                }
+            });
+
+        let ctx = context.clone();
+        self.engine
+            .register_fn("set_state", move |path: &str, value: Dynamic| {
+                let plugin_value = dynamic_to_plugin_value(value);
+                if let Err(e) = ctx.set_state(path, plugin_value) {
+                    tracing::warn!("Failed to set state: {}", e);
+                }
+            });
+
         let ctx = context.clone();
         self.engine
             .register_fn("get_config", move |path: &str| -> Dynamic {
@@ -614,6 +645,98 @@ impl RhaiEngine {
             }
         });
 
+        // Localization: `t("key")` / `t("key", #{ name: "Ada" })` render a
+        // message from the app's `<i18n>` catalogs in the active locale (the
+        // key itself when no catalog defines it); `set_locale("de")` switches
+        // the UI language.
+        let ctx = context.clone();
+        self.engine.register_fn("t", move |key: &str| -> String {
+            ctx.translate(key, PluginValue::Null)
+                .unwrap_or_else(|| key.to_string())
+        });
+
+        let ctx = context.clone();
+        self.engine
+            .register_fn("t", move |key: &str, args: Dynamic| -> String {
+                ctx.translate(key, dynamic_to_plugin_value(args))
+                    .unwrap_or_else(|| key.to_string())
+            });
+
+        let ctx = context.clone();
+        self.engine.register_fn("get_locale", move || -> Dynamic {
+            match ctx.get_locale() {
+                Some(locale) => locale.into(),
+                None => Dynamic::UNIT,
+            }
+        });
+
+        let ctx = context.clone();
+        self.engine.register_fn("set_locale", move |locale: &str| {
+            if let Err(e) = ctx.set_locale(locale) {
+                tracing::warn!("set_locale failed: {}", e);
+            }
+        });
+
+        // Data/image export of a table or chart: `.csv`/`.json` write the bound
+        // data at once, `.png` is captured after the next frame. Returns
+        // whether the export was written (or queued), logging why not.
+        let ctx = context.clone();
+        self.engine.register_fn(
+            "export_component",
+            move |component_id: &str, path: &str| match ctx.export_component(component_id, path) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("export_component failed: {}", e);
+                    false
+                }
+            },
+        );
+
+        // SFC custom events: `emit("archive", payload)` from a `.nemo`
+        // script reaches the consuming instance's `on-archive` handler.
+        let ctx = context.clone();
+        self.engine
+            .register_fn("emit", move |event: &str, payload: Dynamic| {
+                let plugin_value = dynamic_to_plugin_value(payload);
+                if let Err(e) = ctx.emit_component_event(event, plugin_value) {
+                    tracing::warn!("emit failed: {}", e);
+                }
+            });
+
+        let ctx = context.clone();
+        self.engine.register_fn("emit", move |event: &str| {
+            if let Err(e) = ctx.emit_component_event(event, PluginValue::Null) {
+                tracing::warn!("emit failed: {}", e);
+            }
+        });
+
+        // Per-instance SFC state: `self.state.count += 1` in a `.nemo` script
+        // reads and writes the running instance's `<state>` fields. `self`
+        // resolves to a handle rather than a scope variable so it is visible
+        // inside functions, which don't see the script scope.
+        self.engine
+            .register_type_with_name::<SfcSelf>("SfcSelf")
+            .register_type_with_name::<SfcState>("SfcState")
+            .register_get("state", |this: &mut SfcSelf| SfcState(this.0.clone()))
+            .register_indexer_get(|state: &mut SfcState, field: &str| -> Dynamic {
+                match state.0.get_instance_state(field) {
+                    Some(value) => plugin_value_to_dynamic(value),
+                    None => Dynamic::UNIT,
+                }
+            })
+            .register_indexer_set(|state: &mut SfcState, field: &str, value: Dynamic| {
+                let plugin_value = dynamic_to_plugin_value(value);
+                if let Err(e) = state.0.set_instance_state(field, plugin_value) {
+                    tracing::warn!("Failed to set instance state: {}", e);
+                }
+            });
+        let ctx = context.clone();
+        #[allow(deprecated)] // `on_var` is marked volatile, not deprecated
+        self.engine.on_var(move |name, _, _| match name {
+            "self" => Ok(Some(Dynamic::from(SfcSelf(ctx.clone())))),
+            _ => Ok(None),
+        });
+
         let ctx = context.clone();
         self.engine.register_fn("forward", move || {
             if let Err(e) = ctx.forward(None) {
@@ -748,6 +871,16 @@ impl RhaiEngine {
     }
 }
 
+/// The value of `self` in a script: a handle whose `state` property reaches
+/// the running SFC instance's `<state>` fields through the context.
+#[derive(Clone)]
+struct SfcSelf(Arc<dyn PluginContext>);
+
+/// `self.state`: fields are read and written through the indexer, which Rhai
+/// also uses for property access (`self.state.count`).
+#[derive(Clone)]
+struct SfcState(Arc<dyn PluginContext>);
+
 impl Default for RhaiEngine {
     fn default() -> Self {
         Self::new(RhaiConfig::default())
@@ -1008,6 +1141,54 @@ mod tests {
         assert_eq!(result, 15);
     }
 
+    #[test]
+    fn test_script_error_positions() {
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+        let script = "fn helper() {\n    missing_fn(1)\n}\nfn handler() {\n    helper()\n}\n";
+        engine.load_script("test", script).unwrap();
+
+        // The failing statement inside the nested call, not the call site.
+        let err = engine.call::<i64>("test", "handler", ()).unwrap_err();
+        assert_eq!(err.script_position(), Some(("test", 2, 5)));
+
+        let err = engine.load_script("broken", "let x = ;").unwrap_err();
+        assert_eq!(
+            err.script_position().map(|(id, line, _)| (id, line)),
+            Some(("broken", 1))
+        );
+    }
+
+    #[test]
+    fn test_call_value_converts_plugin_values() {
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+
+        let script = r#"
+            fn migrate(old_version, value) {
+                if old_version < 2 {
+                    value.theme = "dark";
+                }
+                value
+            }
+        "#;
+
+        engine.load_script("handlers", script).unwrap();
+        let mut old = indexmap::IndexMap::new();
+        old.insert("count".to_string(), PluginValue::Integer(3));
+        let result = engine
+            .call_value(
+                "handlers",
+                "migrate",
+                vec![PluginValue::Integer(1), PluginValue::Object(old)],
+            )
+            .unwrap();
+
+        let PluginValue::Object(map) = result else {
+            panic!("expected object, got {:?}", result);
+        };
+        assert_eq!(map.get("count"), Some(&PluginValue::Integer(3)));
+        assert_eq!(map.get("theme"), Some(&PluginValue::String("dark".into())));
+    }
+
     #[test]
     fn test_module_state_persists_across_calls() {
         // Regression: scripts with top-level `let` declarations (like the
@@ -1379,12 +1560,15 @@ mod tests {
 
     /// Minimal in-memory `PluginContext` for driving example handlers:
     /// a key/value data store (get_data/set_data) and a component-property
-    /// store (get/set_component_property). Enough to exercise the Rhai host
-    /// API without a running app.
+    /// store (get/set_component_property), plus a log of `emit()`ed SFC
+    /// events and a single SFC instance's state. Enough to exercise the Rhai
+    /// host API without a running app.
     #[derive(Default)]
     struct MockContext {
         data: std::sync::Mutex<HashMap<String, PluginValue>>,
         props: std::sync::Mutex<HashMap<(String, String), PluginValue>>,
+        emitted: std::sync::Mutex<Vec<(String, PluginValue)>>,
+        instance_state: std::sync::Mutex<HashMap<String, PluginValue>>,
     }
 
     impl nemo_plugin_api::PluginContext for MockContext {
@@ -1427,6 +1611,115 @@ mod tests {
                 .insert((component_id.to_string(), property.to_string()), value);
             Ok(())
         }
+        fn emit_component_event(
+            &self,
+            event: &str,
+            payload: PluginValue,
+        ) -> Result<(), nemo_plugin_api::PluginError> {
+            self.emitted
+                .lock()
+                .unwrap()
+                .push((event.to_string(), payload));
+            Ok(())
+        }
+        fn get_instance_state(&self, field: &str) -> Option<PluginValue> {
+            self.instance_state.lock().unwrap().get(field).cloned()
+        }
+        fn set_instance_state(
+            &self,
+            field: &str,
+            value: PluginValue,
+        ) -> Result<(), nemo_plugin_api::PluginError> {
+            self.instance_state
+                .lock()
+                .unwrap()
+                .insert(field.to_string(), value);
+            Ok(())
+        }
+        fn translate(&self, key: &str, args: PluginValue) -> Option<String> {
+            let name = match args {
+                PluginValue::Object(map) => match map.get("name") {
+                    Some(PluginValue::String(name)) => name.clone(),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            };
+            (key == "greeting").then(|| format!("Hello, {name}!"))
+        }
+    }
+
+    #[test]
+    fn test_emit_forwards_event_and_payload() {
+        let ctx = Arc::new(MockContext::default());
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+        engine.register_context(ctx.clone());
+        engine
+            .eval::<()>(r#"emit("archive", 42); emit("close")"#)
+            .unwrap();
+        let emitted = ctx.emitted.lock().unwrap();
+        assert_eq!(
+            *emitted,
+            vec![
+                ("archive".to_string(), PluginValue::Integer(42)),
+                ("close".to_string(), PluginValue::Null),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_self_state_reads_and_writes_instance_state() {
+        let ctx = Arc::new(MockContext::default());
+        ctx.instance_state
+            .lock()
+            .unwrap()
+            .insert("count".to_string(), PluginValue::Integer(1));
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+        engine.register_context(ctx.clone());
+        engine
+            .load_script(
+                "sfc:counter",
+                r#"
+                fn bump(id, data) {
+                    self.state.count += 1;
+                    self.state["open"] = true;
+                    self.state.count
+                }
+                "#,
+            )
+            .unwrap();
+        let count: i64 = engine
+            .call(
+                "sfc:counter",
+                "bump",
+                ("counter1".to_string(), String::new()),
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+        let state = ctx.instance_state.lock().unwrap();
+        assert_eq!(state.get("count"), Some(&PluginValue::Integer(2)));
+        assert_eq!(state.get("open"), Some(&PluginValue::Bool(true)));
+    }
+
+    #[test]
+    fn test_t_translates_and_falls_back_to_the_key() {
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+        engine.register_context(Arc::new(MockContext::default()));
+        let greeting: String = engine.eval(r#"t("greeting", #{ name: "Ada" })"#).unwrap();
+        assert_eq!(greeting, "Hello, Ada!");
+        let missing: String = engine.eval(r#"t("nav.home")"#).unwrap();
+        assert_eq!(missing, "nav.home");
+    }
+
+    #[test]
+    fn test_export_component_returns_false_when_unsupported() {
+        // MockContext keeps the trait's default `export_component`, which
+        // reports the operation as unsupported; scripts see `false`.
+        let mut engine = RhaiEngine::new(RhaiConfig::default());
+        engine.register_context(Arc::new(MockContext::default()));
+        let written: bool = engine
+            .eval(r#"export_component("sales", "sales.csv")"#)
+            .unwrap();
+        assert!(!written);
     }
 
     #[test]
@@ -1,7 +1,7 @@
 //! Binding management for connecting data to components.
 
 use crate::node::BindingMode;
-use nemo_config::Value;
+use nemo_config::{coerce_prop_value, Messages, SourceLocation, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -88,6 +88,8 @@ pub struct BindingManager {
     source_index: HashMap<String, Vec<BindingId>>,
     /// Index from component ID to binding IDs.
     component_index: HashMap<String, Vec<BindingId>>,
+    /// Message catalogs for `t('key')` transforms, in the active locale.
+    messages: Option<Messages>,
 }
 
 impl BindingManager {
@@ -97,6 +99,7 @@ impl BindingManager {
             bindings: HashMap::new(),
             source_index: HashMap::new(),
             component_index: HashMap::new(),
+            messages: None,
         }
     }
 
@@ -187,6 +190,36 @@ impl BindingManager {
         self.bindings.len()
     }
 
+    /// Sets the catalogs `t('key')` transforms translate with. Translated
+    /// bindings forget their last value so the next data change for their
+    /// source re-renders them in the new locale.
+    pub fn set_messages(&mut self, messages: Option<Messages>) {
+        self.messages = messages;
+        for binding in self.bindings.values_mut() {
+            if binding.transform.as_deref().is_some_and(is_translation) {
+                binding.last_value = None;
+            }
+        }
+    }
+
+    /// Returns the catalogs `t('key')` transforms translate with.
+    pub fn messages(&self) -> Option<&Messages> {
+        self.messages.as_ref()
+    }
+
+    /// Returns the source paths of bindings with a `t('key')` transform.
+    pub fn translated_sources(&self) -> Vec<String> {
+        let mut sources: Vec<String> = self
+            .bindings
+            .values()
+            .filter(|b| b.transform.as_deref().is_some_and(is_translation))
+            .map(|b| b.source.clone())
+            .collect();
+        sources.sort();
+        sources.dedup();
+        sources
+    }
+
     /// Processes a data change and returns updates to apply.
     pub fn on_data_changed(&mut self, source_path: &str, new_value: &Value) -> Vec<BindingUpdate> {
         let mut updates = Vec::new();
@@ -199,19 +232,40 @@ impl BindingManager {
                         continue;
                     }
 
-                    // Apply transform if present
+                    // Apply transform if present. A transform that can't
+                    // apply passes the value through and reports why.
+                    let mut error = None;
                     let transformed = if let Some(ref transform) = binding.transform {
-                        apply_transform(transform, new_value)
+                        if is_translation(transform) {
+                            apply_translation(transform, new_value, self.messages.as_ref())
+                        } else {
+                            try_transform(transform, new_value).unwrap_or_else(|e| {
+                                error = Some(format!(
+                                    "binding '{}' → {}.{}: {}",
+                                    binding.source,
+                                    binding.target.component_id,
+                                    binding.target.property_path,
+                                    e
+                                ));
+                                new_value.clone()
+                            })
+                        }
                     } else {
                         new_value.clone()
                     };
 
                     binding.last_value = Some(transformed.clone());
+                    let origin = error
+                        .as_ref()
+                        .and(binding.transform.as_deref())
+                        .and_then(prop_origin);
 
                     updates.push(BindingUpdate {
                         binding_id: id,
                         target: binding.target.clone(),
                         value: transformed,
+                        error,
+                        origin,
                     });
                 }
             }
@@ -227,16 +281,140 @@ impl Default for BindingManager {
     }
 }
 
+/// Whether a transform is a `t('key')` translation.
+fn is_translation(transform: &str) -> bool {
+    transform.trim().starts_with("t(")
+}
+
+/// Applies a `t('key')` (or `t('key', value)`) transform: the message `key`
+/// with the bound value as its argument — an object supplies named
+/// `{ $name }` arguments, anything else is `{ $value }`. Without catalogs,
+/// or for an unknown key, the key itself.
+fn apply_translation(transform: &str, value: &Value, messages: Option<&Messages>) -> Value {
+    let args = transform
+        .trim()
+        .strip_prefix("t(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or_default();
+    let key = args
+        .split(',')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"');
+    Value::String(match messages {
+        Some(messages) => messages.translate(key, value),
+        None => key.to_string(),
+    })
+}
+
+/// Prefix of a transform that places the bound value between two literal
+/// strings, written as a JSON pair: `concat:["Count: ", " items"]`.
+const CONCAT_PREFIX: &str = "concat:";
+
+/// Builds a transform that renders `prefix`, the bound value, then `suffix`.
+/// Unlike a format transform, the surrounding text is taken literally, so it
+/// may itself contain `value`.
+pub fn concat_transform(prefix: &str, suffix: &str) -> String {
+    format!("{}{}", CONCAT_PREFIX, serde_json::json!([prefix, suffix]))
+}
+
+/// Prefix of a transform that coerces the bound value to the type declared
+/// for an SFC prop before anything else applies (see [`prop_transform`]).
+const PROP_PREFIX: &str = "prop:";
+
+/// The declaration a bound SFC prop's transform carries.
+#[derive(Debug, Serialize, Deserialize)]
+struct PropCoercion {
+    prop: String,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    one_of: Vec<String>,
+    /// Where the instance binding the prop was written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<String>,
+    /// The transform applied to the coerced value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    then: Option<String>,
+}
+
+/// Builds a transform for SFC prop `prop` bound with `:prop="data.x"`: each
+/// value is coerced to the declared `ty` and checked against `one_of`, as a
+/// static prop is at load, then `then` applies. A value that doesn't fit
+/// passes through and is reported at `at`, the instance.
+pub fn prop_transform(
+    prop: &str,
+    ty: &str,
+    one_of: &[String],
+    at: Option<&SourceLocation>,
+    then: Option<&str>,
+) -> String {
+    let coercion = PropCoercion {
+        prop: prop.to_string(),
+        ty: ty.to_string(),
+        one_of: one_of.to_vec(),
+        at: at.map(|loc| loc.to_string()),
+        then: then.map(str::to_string),
+    };
+    format!(
+        "{}{}",
+        PROP_PREFIX,
+        serde_json::to_string(&coercion).unwrap_or_default()
+    )
+}
+
+/// Where to report a failure of `transform`, when it knows better than the
+/// component it targets: a bound prop reports at its instance.
+fn prop_origin(transform: &str) -> Option<SourceLocation> {
+    let coercion: PropCoercion =
+        serde_json::from_str(transform.trim().strip_prefix(PROP_PREFIX)?).ok()?;
+    SourceLocation::parse(&coercion.at?)
+}
+
 /// Applies a binding transform to a value.
 ///
 /// Supported transforms:
+/// - Prop coercion: `prop:{"prop": …, "type": …}` (see [`prop_transform`])
+/// - Concatenation: `concat:["before", "after"]` (see [`concat_transform`])
 /// - Field extraction: "payload.temperature" extracts a nested field from an Object
 /// - String format: any expression containing "value" does string interpolation
 /// - Identity: empty or unrecognized transform passes value through
-fn apply_transform(transform: &str, value: &Value) -> Value {
+///
+/// Fails when a field extraction finds no such field; the caller passes the
+/// value through and reports the error. A null value (data not loaded yet)
+/// passes through without error.
+fn try_transform(transform: &str, value: &Value) -> Result<Value, String> {
     let transform = transform.trim();
     if transform.is_empty() {
-        return value.clone();
+        return Ok(value.clone());
+    }
+
+    if let Some(decl) = transform.strip_prefix(PROP_PREFIX) {
+        let coercion: PropCoercion = serde_json::from_str(decl)
+            .map_err(|e| format!("transform '{}' is not a prop declaration: {}", transform, e))?;
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        let coerced =
+            coerce_prop_value(&coercion.ty, &coercion.one_of, value).map_err(|expected| {
+                format!("prop '{}' {}, got '{}'", coercion.prop, expected, value)
+            })?;
+        return match &coercion.then {
+            Some(then) => try_transform(then, &coerced),
+            None => Ok(coerced),
+        };
+    }
+
+    if let Some(parts) = transform.strip_prefix(CONCAT_PREFIX) {
+        let (prefix, suffix): (String, String) = serde_json::from_str(parts)
+            .map_err(|e| format!("transform '{}' is not a concat pair: {}", transform, e))?;
+        return Ok(Value::String(format!(
+            "{}{}{}",
+            prefix,
+            display_value(value),
+            suffix
+        )));
     }
 
     // Comparison transforms (from `n:if` conditions): "== 'literal'" or
@@ -244,18 +422,21 @@ fn apply_transform(transform: &str, value: &Value) -> Value {
     // value satisfies the comparison. The literal may be a quoted string,
     // a number, `true`/`false`, or `null`.
     if let Some(rest) = transform.strip_prefix("==") {
-        return Value::Bool(value_equals(value, rest.trim()));
+        return Ok(Value::Bool(value_equals(value, rest.trim())));
     }
     if let Some(rest) = transform.strip_prefix("!=") {
-        return Value::Bool(!value_equals(value, rest.trim()));
+        return Ok(Value::Bool(!value_equals(value, rest.trim())));
     }
     let transform = transform.trim();
     if transform.is_empty() {
-        return value.clone();
+        return Ok(value.clone());
     }
 
     // Field extraction: "field.subfield" extracts nested fields from Object values
     if !transform.contains("value") && !transform.contains(' ') {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
         let parts: Vec<&str> = transform.split('.').collect();
         let mut current = value;
         for part in &parts {
@@ -264,37 +445,53 @@ fn apply_transform(transform: &str, value: &Value) -> Value {
                     if let Some(v) = obj.get(*part) {
                         current = v;
                     } else {
-                        return value.clone();
+                        return Err(format!(
+                            "transform '{}' found no field '{}'",
+                            transform, part
+                        ));
                     }
                 }
-                _ => return value.clone(),
+                other => {
+                    return Err(format!(
+                        "transform '{}' can't read field '{}' of {} value",
+                        transform,
+                        part,
+                        other.type_name()
+                    ))
+                }
             }
         }
-        return current.clone();
+        return Ok(current.clone());
     }
 
     // String format: replace "value" with the stringified data
     if transform.contains("value") {
-        let value_str = match value {
-            Value::String(s) => s.clone(),
-            Value::Integer(i) => i.to_string(),
-            Value::Float(f) => f.to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Null => "null".to_string(),
-            _ => format!("{:?}", value),
-        };
-        return Value::String(transform.replace("value", &value_str));
+        return Ok(Value::String(
+            transform.replace("value", &display_value(value)),
+        ));
     }
 
     // Fallback: pass through
-    value.clone()
+    Ok(value.clone())
+}
+
+/// The text a format or concat transform puts in place of the bound value.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        _ => format!("{:?}", value),
+    }
 }
 
 /// Compares a bound `Value` against a literal string from a comparison
 /// transform. The literal may be a quoted string (`'error'`), a number
 /// (`42`, `3.14`), a boolean (`true`/`false`), or `null`. Unquoted strings
 /// are compared as strings.
-fn value_equals(value: &Value, literal: &str) -> bool {
+pub(crate) fn value_equals(value: &Value, literal: &str) -> bool {
     let literal = literal.trim();
     // Quoted string literal: 'error' or "error".
     if (literal.starts_with('\'') && literal.ends_with('\''))
@@ -335,6 +532,12 @@ pub struct BindingUpdate {
     pub target: ComponentProperty,
     /// New value.
     pub value: Value,
+    /// Why the binding's transform couldn't apply (the value then passed
+    /// through untransformed).
+    pub error: Option<String>,
+    /// Where to report `error` instead of the target component's origin: the
+    /// instance of a bound SFC prop.
+    pub origin: Option<SourceLocation>,
 }
 
 #[cfg(test)]
@@ -392,9 +595,14 @@ mod tests {
         let updates = manager.on_data_changed("data.test", &Value::Integer(42));
         assert_eq!(updates.len(), 1);
         assert_eq!(updates[0].value, Value::Integer(42));
+        assert!(updates[0].error.is_none());
     }
 
-    // ── apply_transform ───────────────────────────────────────────────
+    // ── try_transform ─────────────────────────────────────────────────
+
+    fn apply_transform(transform: &str, value: &Value) -> Value {
+        try_transform(transform, value).unwrap()
+    }
 
     #[test]
     fn test_transform_empty_passthrough() {
@@ -403,6 +611,16 @@ mod tests {
         assert_eq!(apply_transform("  ", &val), val);
     }
 
+    #[test]
+    fn test_transform_concat_keeps_text_literal() {
+        let transform = concat_transform("value: ", " (max \"value\")");
+        assert_eq!(
+            apply_transform(&transform, &Value::Integer(3)),
+            Value::String("value: 3 (max \"value\")".into())
+        );
+        assert!(try_transform("concat:[1]", &Value::Integer(3)).is_err());
+    }
+
     #[test]
     fn test_transform_field_extraction_single() {
         let mut obj = indexmap::IndexMap::new();
@@ -431,15 +649,71 @@ mod tests {
         obj.insert("a".to_string(), Value::Integer(1));
         let val = Value::Object(obj);
 
-        // Missing key returns original value
-        assert_eq!(apply_transform("nonexistent", &val), val);
+        let err = try_transform("nonexistent", &val).unwrap_err();
+        assert!(err.contains("no field 'nonexistent'"), "{err}");
     }
 
     #[test]
     fn test_transform_field_extraction_on_non_object() {
         let val = Value::Integer(42);
-        // Extracting a field from a non-object returns original
-        assert_eq!(apply_transform("field", &val), val);
+        let err = try_transform("field", &val).unwrap_err();
+        assert!(err.contains("field 'field' of integer value"), "{err}");
+        // Data that hasn't loaded yet isn't an error.
+        assert_eq!(apply_transform("field", &Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_failed_transform_passes_value_through_with_error() {
+        let mut manager = BindingManager::new();
+        let target = ComponentProperty::new("gauge", "value");
+        manager.bind(
+            "data.reading",
+            target,
+            BindingMode::OneWay,
+            Some("payload.temp".to_string()),
+        );
+
+        let updates = manager.on_data_changed("data.reading", &Value::Integer(7));
+        assert_eq!(updates[0].value, Value::Integer(7));
+        let err = updates[0].error.as_deref().unwrap();
+        assert!(
+            err.starts_with("binding 'data.reading' → gauge.value"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_prop_transform_coerces_then_applies() {
+        let int = prop_transform("pct", "int", &[], None, None);
+        assert_eq!(
+            apply_transform(&int, &Value::String("42".into())),
+            Value::Integer(42)
+        );
+        assert_eq!(apply_transform(&int, &Value::Null), Value::Null);
+        let text = prop_transform("pct", "int", &[], None, Some(&concat_transform("", "%")));
+        assert_eq!(
+            apply_transform(&text, &Value::String("7".into())),
+            Value::String("7%".into())
+        );
+        let tone = prop_transform("tone", "string", &["info".to_string()], None, None);
+        assert!(try_transform(&tone, &Value::String("warn".into())).is_err());
+
+        let mut manager = BindingManager::new();
+        let at = SourceLocation::new("app.xml", 4, 5);
+        manager.bind(
+            "data.pct",
+            ComponentProperty::new("gauge_bar", "value"),
+            BindingMode::OneWay,
+            Some(prop_transform("pct", "int", &[], Some(&at), None)),
+        );
+        let updates = manager.on_data_changed("data.pct", &Value::String("lots".into()));
+        assert_eq!(updates[0].value, Value::String("lots".into()));
+        let err = updates[0].error.as_deref().unwrap();
+        assert!(
+            err.ends_with("prop 'pct' expected int, got 'lots'"),
+            "{err}"
+        );
+        assert_eq!(updates[0].origin, Some(at));
     }
 
     #[test]
@@ -493,4 +767,42 @@ mod tests {
             Value::String("5 of 5".to_string())
         );
     }
+
+    #[test]
+    fn test_translation_transform() {
+        let catalog = nemo_config::parse_ftl(
+            "unread = { $value } unread\nwelcome = Hi { $name }\n",
+            "en.ftl",
+        )
+        .unwrap();
+        let mut manager = BindingManager::new();
+        manager.set_messages(Some(Messages::new(
+            indexmap::IndexMap::from([("en".to_string(), catalog)]),
+            "en",
+        )));
+        manager.bind(
+            "data.unread",
+            ComponentProperty::new("badge", "text"),
+            BindingMode::OneWay,
+            Some("t('unread', value)".to_string()),
+        );
+        manager.bind(
+            "data.user",
+            ComponentProperty::new("hello", "text"),
+            BindingMode::OneWay,
+            Some("t(\"welcome\")".to_string()),
+        );
+        assert_eq!(
+            manager.translated_sources(),
+            ["data.unread".to_string(), "data.user".to_string()]
+        );
+
+        let updates = manager.on_data_changed("data.unread", &Value::Integer(3));
+        assert_eq!(updates[0].value, Value::String("3 unread".to_string()));
+
+        let mut user = indexmap::IndexMap::new();
+        user.insert("name".to_string(), Value::String("Ada".to_string()));
+        let updates = manager.on_data_changed("data.user", &Value::Object(user));
+        assert_eq!(updates[0].value, Value::String("Hi Ada".to_string()));
+    }
 }
@@ -0,0 +1,105 @@
+//! Runtime side of `n:if`/`n:else-if`/`n:else` chains and `n:switch`.
+//!
+//! The directives pass gives every branch of a chain its position as the
+//! `n:branch` property and binds the condition of each branch up to and
+//! including its own to `n:branch_<n>`; an `n:else` also gets
+//! `n:branch_else = true` and no condition of its own. The `n:` namespace
+//! keeps them apart from a component's own attributes. A branch is active when
+//! its own condition holds and every earlier one doesn't, so exactly one
+//! branch of a chain with an `n:else` renders.
+
+use nemo_config::Value;
+use std::collections::HashMap;
+
+/// Property holding a branch's position in its chain.
+pub const BRANCH: &str = "n:branch";
+
+/// Property marking the `n:else` branch.
+pub const BRANCH_ELSE: &str = "n:branch_else";
+
+/// Property a chain's `n`th condition is bound to.
+pub fn condition_property(n: usize) -> String {
+    format!("n:branch_{n}")
+}
+
+/// Truthiness of a bound condition: `false`, `null`, zero, and empty
+/// strings, arrays and objects are false.
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Integer(i) => *i != 0,
+        Value::Float(f) => *f != 0.0,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+/// Whether a component is the active branch of its chain. Components outside
+/// a chain are always active; a condition with no value yet counts as false.
+pub fn is_branch_active(properties: &HashMap<String, Value>) -> bool {
+    let Some(index) = properties.get(BRANCH).and_then(|v| v.as_i64()) else {
+        return true;
+    };
+    let holds = |n: i64| {
+        properties
+            .get(&condition_property(n as usize))
+            .is_some_and(is_truthy)
+    };
+    if (0..index).any(holds) {
+        return false;
+    }
+    properties.get(BRANCH_ELSE).and_then(|v| v.as_bool()) == Some(true) || holds(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        assert!(!is_truthy(&Value::Null));
+        assert!(!is_truthy(&Value::Integer(0)));
+        assert!(!is_truthy(&Value::String(String::new())));
+        assert!(is_truthy(&Value::String("error".into())));
+        assert!(is_truthy(&Value::Float(0.5)));
+    }
+
+    #[test]
+    fn test_is_branch_active() {
+        assert!(is_branch_active(&HashMap::new()), "not in a chain");
+
+        // n:else-if at position 1: first condition false, own true.
+        let mut p = props(&[
+            (BRANCH, Value::Integer(1)),
+            ("n:branch_0", Value::Bool(false)),
+            ("n:branch_1", Value::String("x".into())),
+        ]);
+        assert!(is_branch_active(&p));
+        p.insert(condition_property(0), Value::Bool(true));
+        assert!(!is_branch_active(&p), "an earlier branch won");
+
+        // n:else at position 2 before any data: active.
+        let p = props(&[
+            (BRANCH, Value::Integer(2)),
+            (BRANCH_ELSE, Value::Bool(true)),
+        ]);
+        assert!(is_branch_active(&p));
+
+        // n:if head with no value yet: inactive.
+        let p = props(&[(BRANCH, Value::Integer(0))]);
+        assert!(!is_branch_active(&p));
+
+        // A component's own `branch` attribute isn't a chain position.
+        let p = props(&[("branch", Value::Integer(3))]);
+        assert!(is_branch_active(&p));
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::error::LayoutError;
 use crate::node::{LayoutConfig, LayoutNode, LayoutType};
-use nemo_config::Value;
+use nemo_config::{SourceLocation, Value};
 use nemo_registry::ComponentRegistry;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -20,6 +20,8 @@ pub struct BuildResult {
     pub handlers: HashMap<String, String>,
     /// Child build results.
     pub children: Vec<BuildResult>,
+    /// Where the component was written, if known.
+    pub origin: Option<SourceLocation>,
 }
 
 /// Builder for constructing layouts from configuration.
@@ -98,6 +100,7 @@ impl LayoutBuilder {
             properties: resolved_props,
             handlers: node.handlers.clone(),
             children,
+            origin: node.origin.clone(),
         })
     }
 
@@ -4,18 +4,29 @@
 //! - Layout nodes representing the component tree structure
 //! - A builder for constructing layouts from configuration
 //! - Binding management for connecting data to components
+//! - Branch selection for `n:else-if`/`n:else` chains and `n:switch`
 //! - State coordination for persisting component state
+//! - Render-time pseudo-class styles from SFC `<style>` rules
+//! - Window-size conditions (`n:show-when`, `@media`) for responsive layouts
+//! - Row building and keyed diffing for virtualized lists
 //! - A layout manager that orchestrates the complete lifecycle
 
 pub mod binding;
+pub mod branch;
 pub mod builder;
 pub mod error;
 pub mod list_binding;
 pub mod manager;
 pub mod node;
+pub mod responsive;
 pub mod state;
+pub mod style_state;
+pub mod virtual_list;
 
-pub use binding::{ActiveBinding, BindingId, BindingManager, BindingUpdate, ComponentProperty};
+pub use binding::{
+    concat_transform, prop_transform, ActiveBinding, BindingId, BindingManager, BindingUpdate,
+    ComponentProperty,
+};
 pub use error::{BindingError, LayoutError, StateError};
 pub use list_binding::ListBindingManager;
 pub use manager::{BuiltComponent, LayoutManager};
@@ -2,7 +2,7 @@
 //!
 //! Watches data source paths, diffs arrays, and creates/removes component
 //! instances by expanding the loop template per item. Reuses
-//! `LayoutManager::insert_component`/`remove_component` (from
+//! `LayoutManager::insert_tree`/`remove_component` (from
 //! runtime-component-creation) — it does not reimplement insertion.
 //!
 //! Keying: when `n:key` is present, items are matched by key value (stable
@@ -10,9 +10,9 @@
 //! matched by index (destroy/recreate on reorder).
 
 use crate::binding::ComponentProperty;
-use crate::manager::LayoutManager;
+use crate::manager::{child_nodes, LayoutManager, NodeParts};
 use crate::node::ListBindingSpec;
-use nemo_config::Value;
+use nemo_config::{Value, ORIGIN_KEY};
 use std::collections::HashMap;
 
 /// A registered list binding: the container component ID and the spec.
@@ -106,6 +106,49 @@ impl ListBindingManager {
         any_changes
     }
 
+    /// Returns the source path of the list binding that fills `container_id`,
+    /// if the container is a live-data `n:for`.
+    pub fn source_for(&self, container_id: &str) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(_, entries)| entries.iter().any(|e| e.container_id == container_id))
+            .map(|(source, _)| source.as_str())
+    }
+
+    /// Reorders `items`, the current array bound to `container_id`, to follow
+    /// `order`, the container's children after a drag. Each item is matched
+    /// to its instance by the binding's key (or index), so children that
+    /// aren't instances, and items without an instance, keep their places.
+    /// `None` when `container_id` isn't a list container.
+    pub fn reorder_items(
+        &self,
+        container_id: &str,
+        order: &[String],
+        items: &[Value],
+    ) -> Option<Vec<Value>> {
+        let entry = self
+            .bindings
+            .values()
+            .flatten()
+            .find(|e| e.container_id == container_id)?;
+        let mut index_of: HashMap<String, usize> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (instance_id_for(container_id, &entry.spec, i, item), i))
+            .collect();
+        // Item indices in their instances' new order, and the positions
+        // those items occupy in the array.
+        let moved: Vec<usize> = order.iter().filter_map(|id| index_of.remove(id)).collect();
+        let mut slots = moved.clone();
+        slots.sort_unstable();
+
+        let mut reordered = items.to_vec();
+        for (slot, index) in slots.into_iter().zip(moved) {
+            reordered[slot] = items[index].clone();
+        }
+        Some(reordered)
+    }
+
     /// Returns the source paths watched by list bindings.
     pub fn sources(&self) -> impl Iterator<Item = &str> {
         self.bindings.keys().map(|s| s.as_str())
@@ -224,7 +267,9 @@ fn instance_id_for(
 }
 
 /// Inserts a new component instance for `data.source[index]`, expanding the
-/// loop template with the item's data.
+/// loop template with the item's data. The instance and its whole subtree are
+/// built by [`LayoutManager::insert_tree`], with the bindings and handlers
+/// the template declares.
 fn insert_instance(
     manager: &mut LayoutManager,
     container_id: &str,
@@ -240,83 +285,11 @@ fn insert_instance(
     // binding source paths. The instance's component type comes from the
     // template's `type` field.
     let expanded = expand_template_for_index(template, source, index, item_var, item);
-
-    let component_type = expanded
-        .get("type")
-        .and_then(|v| v.as_str())
-        .unwrap_or("panel")
-        .to_string();
-
-    // Extract properties (excluding structural keys).
-    let mut properties: HashMap<String, Value> = HashMap::new();
-    if let Some(obj) = expanded.as_object() {
-        for (key, val) in obj {
-            match key.as_str() {
-                "type" | "id" | "component" | "binding" | "slot" | "vars" | "list_binding" => {
-                    continue;
-                }
-                _ => {
-                    properties.insert(key.clone(), val.clone());
-                }
-            }
-        }
-    }
-
-    // Extract handlers.
-    let mut handlers: HashMap<String, String> = HashMap::new();
-    if let Some(obj) = expanded.as_object() {
-        for (key, val) in obj {
-            if let Some(event) = key.strip_prefix("on_") {
-                if let Some(h) = val.as_str() {
-                    handlers.insert(event.to_string(), h.to_string());
-                }
-            }
-        }
-    }
-
-    manager.insert_component(
-        instance_id,
-        &component_type,
-        Some(container_id),
-        properties,
-        handlers,
-    )?;
+    manager.insert_tree(instance_id, Some(container_id), &expanded)?;
 
     // Set up per-instance bindings: for each `${item_var.field}` placeholder
     // in the template, bind `data.source[index].field` to the child property.
     setup_instance_bindings(manager, instance_id, source, index, item_var, template);
-
-    // Recursively insert children from the expanded template's `component` map.
-    if let Some(children) = expanded.get("component").and_then(|v| v.as_object()) {
-        for (child_id, child_val) in children {
-            let full_child_id = format!("{}_{}", instance_id, child_id);
-            let child_type = child_val
-                .get("type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("panel")
-                .to_string();
-            let mut child_props: HashMap<String, Value> = HashMap::new();
-            if let Some(child_obj) = child_val.as_object() {
-                for (k, v) in child_obj {
-                    match k.as_str() {
-                        "type" | "id" | "component" | "binding" | "slot" | "vars"
-                        | "list_binding" => continue,
-                        _ => {
-                            child_props.insert(k.clone(), v.clone());
-                        }
-                    }
-                }
-            }
-            let _ = manager.insert_component(
-                &full_child_id,
-                &child_type,
-                Some(instance_id),
-                child_props,
-                HashMap::new(),
-            );
-        }
-    }
-
     Ok(())
 }
 
@@ -402,7 +375,8 @@ fn setup_instance_bindings(
     };
     for (key, val) in obj {
         match key.as_str() {
-            "type" | "id" | "component" | "binding" | "slot" | "vars" | "list_binding" => continue,
+            "type" | "id" | "component" | "binding" | "slot" | "vars" | "list_binding"
+            | ORIGIN_KEY => continue,
             _ => {}
         }
         // Check if this property value contains a ${item_var.field} placeholder.
@@ -427,9 +401,10 @@ fn setup_instance_bindings(
 }
 
 /// Updates an existing instance when the array changes: re-substitutes the
-/// template with the new item data, updates the instance's properties (and its
-/// children's) in place, and refreshes per-index bindings. The instance keeps
-/// its component ID so widget state (input focus/caret) is preserved.
+/// template with the new item data, updates the properties of the instance
+/// and its subtree in place, and refreshes per-index bindings. The instance
+/// keeps its component ID (and the bindings its template declares) so widget
+/// state (input focus/caret) is preserved.
 fn update_instance(
     manager: &mut LayoutManager,
     instance_id: &str,
@@ -440,51 +415,35 @@ fn update_instance(
     template: &Value,
 ) {
     let expanded = expand_template_for_index(template, source, index, item_var, item);
-
-    // Update the instance's own properties.
-    let mut properties: HashMap<String, Value> = HashMap::new();
-    if let Some(obj) = expanded.as_object() {
-        for (key, val) in obj {
-            match key.as_str() {
-                "type" | "id" | "component" | "binding" | "slot" | "vars" | "list_binding" => {
-                    continue;
-                }
-                _ => {
-                    properties.insert(key.clone(), val.clone());
-                }
-            }
-        }
-    }
-    let _ = manager.set_properties(instance_id, properties);
-
-    // Update children's properties too.
-    if let Some(children) = expanded.get("component").and_then(|v| v.as_object()) {
-        for (child_id, child_val) in children {
-            let full_child_id = format!("{}_{}", instance_id, child_id);
-            let mut child_props: HashMap<String, Value> = HashMap::new();
-            if let Some(child_obj) = child_val.as_object() {
-                for (k, v) in child_obj {
-                    match k.as_str() {
-                        "type" | "id" | "component" | "binding" | "slot" | "vars"
-                        | "list_binding" => continue,
-                        _ => {
-                            child_props.insert(k.clone(), v.clone());
-                        }
-                    }
-                }
-            }
-            let _ = manager.set_properties(&full_child_id, child_props);
-        }
-    }
+    update_tree(manager, instance_id, instance_id, &expanded);
 
     // Refresh per-index bindings for the new index.
-    manager.bindings_mut().unbind_component(instance_id);
+    let item_prefix = format!("{}.", source);
+    let stale: Vec<_> = manager
+        .bindings()
+        .bindings_for_component(instance_id)
+        .into_iter()
+        .filter(|b| b.source.starts_with(&item_prefix))
+        .map(|b| b.id)
+        .collect();
+    for id in stale {
+        manager.bindings_mut().unbind(id);
+    }
     setup_instance_bindings(manager, instance_id, source, index, item_var, template);
 }
 
+/// Sets the properties of instance `root` and its descendants (named as
+/// [`LayoutManager::insert_tree`] names them) from an expanded template node.
+fn update_tree(manager: &mut LayoutManager, root: &str, id: &str, node: &Value) {
+    let _ = manager.set_properties(id, NodeParts::of(node).properties);
+    for (child_id, child) in child_nodes(node, root, id) {
+        update_tree(manager, root, &child_id, child);
+    }
+}
+
 /// Resolves a key expression (`user.id`) against a loop item, returning the
 /// key value as a string.
-fn resolve_key(key_expr: &str, item_var: &str, item: &Value) -> Option<String> {
+pub(crate) fn resolve_key(key_expr: &str, item_var: &str, item: &Value) -> Option<String> {
     let prefix = format!("{}.", item_var);
     let field = key_expr.strip_prefix(&prefix)?;
     item.as_object()
@@ -549,6 +508,33 @@ mod tests {
         assert!(manager.get_component("list_1").is_some());
     }
 
+    #[test]
+    fn test_reorder_items_follows_instances_by_key() {
+        let mut lbm = ListBindingManager::new();
+        lbm.register("list", make_spec("data.users", Some("user.name")));
+        let items = vec![user_obj("A"), user_obj("B"), user_obj("C"), user_obj("D")];
+
+        // `C` has no instance (filtered out), and a static sibling sits
+        // among the instances: dragging `A` below `D` only swaps the items
+        // that are shown.
+        let order: Vec<String> = ["header", "list_B", "list_D", "list_A"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let names: Vec<Value> = lbm
+            .reorder_items("list", &order, &items)
+            .unwrap()
+            .iter()
+            .filter_map(|u| u.get("name").cloned())
+            .collect();
+        let expected: Vec<Value> = ["B", "D", "C", "A"]
+            .iter()
+            .map(|s| Value::String(s.to_string()))
+            .collect();
+        assert_eq!(names, expected);
+        assert!(lbm.reorder_items("other", &order, &items).is_none());
+    }
+
     #[test]
     fn test_list_binding_shrink() {
         let mut manager = make_manager();
@@ -775,4 +761,63 @@ mod tests {
             Some("Bob"),
         );
     }
+
+    /// Every level of the template is built, with the handlers and bindings
+    /// it declares, and survives an in-place update.
+    #[test]
+    fn test_list_binding_builds_nested_template() {
+        let mut manager = make_manager();
+        manager
+            .insert_component("list", "stack", None, HashMap::new(), HashMap::new())
+            .unwrap();
+        let template: Value = serde_json::from_str(
+            r#"{"type": "stack", "component": {"row": {"type": "stack", "component": {
+                "name": {"type": "label", "text": "${user.name}", "bind_visible": "data.show"},
+                "remove": {"type": "button", "label": "x", "on_click": "remove_user"}
+            }}}}"#,
+        )
+        .unwrap();
+        let mut lbm = ListBindingManager::new();
+        lbm.register(
+            "list",
+            ListBindingSpec {
+                template,
+                ..make_spec("data.users", None)
+            },
+        );
+
+        lbm.on_data_changed(
+            "data.users",
+            &Value::Array(vec![user_obj("Alice")]),
+            &mut manager,
+        );
+        let name = manager.get_component("list_0_name").unwrap();
+        assert_eq!(name.parent.as_deref(), Some("list_0_row"));
+        assert_eq!(name.properties.get("text"), Some(&Value::from("Alice")));
+        let remove = manager.get_component("list_0_remove").unwrap();
+        assert_eq!(
+            remove.handlers.get("click").map(String::as_str),
+            Some("remove_user")
+        );
+        assert_eq!(manager.bindings().bindings_for_source("data.show").len(), 1);
+
+        lbm.on_data_changed(
+            "data.users",
+            &Value::Array(vec![user_obj("Bob")]),
+            &mut manager,
+        );
+        let name = manager.get_component("list_0_name").unwrap();
+        assert_eq!(name.properties.get("text"), Some(&Value::from("Bob")));
+        assert_eq!(manager.bindings().bindings_for_source("data.show").len(), 1);
+    }
+
+    #[test]
+    fn test_source_for_container() {
+        let mut lbm = ListBindingManager::new();
+        lbm.register("list", make_spec("data.users", None));
+        assert_eq!(lbm.source_for("list"), Some("data.users"));
+        assert_eq!(lbm.source_for("other"), None);
+        lbm.unregister_container("list");
+        assert_eq!(lbm.source_for("list"), None);
+    }
 }
@@ -4,9 +4,9 @@ use crate::binding::{BindingManager, BindingUpdate, ComponentProperty};
 use crate::builder::{BuildResult, LayoutBuilder};
 use crate::error::LayoutError;
 use crate::list_binding::ListBindingManager;
-use crate::node::{LayoutConfig, LayoutNode};
+use crate::node::{BindingMode, BindingSpec, LayoutConfig, LayoutNode};
 use crate::state::StateCoordinator;
-use nemo_config::Value;
+use nemo_config::{SourceLocation, Value, ORIGIN_KEY};
 use nemo_registry::ComponentRegistry;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -44,6 +44,45 @@ pub struct BuiltComponent {
     pub children: Vec<String>,
     /// Parent component ID (if any).
     pub parent: Option<String>,
+    /// Where the component was written, if known (see [`LayoutNode::origin`]).
+    pub origin: Option<SourceLocation>,
+}
+
+impl BuiltComponent {
+    /// Creates a component with no properties, handlers, children or parent.
+    pub fn new(id: impl Into<String>, component_type: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            component_type: component_type.into(),
+            properties: HashMap::new(),
+            handlers: HashMap::new(),
+            children: Vec::new(),
+            parent: None,
+            origin: None,
+        }
+    }
+
+    /// Sets property values.
+    pub fn with_props<K: Into<String>>(
+        mut self,
+        props: impl IntoIterator<Item = (K, Value)>,
+    ) -> Self {
+        self.properties
+            .extend(props.into_iter().map(|(key, value)| (key.into(), value)));
+        self
+    }
+
+    /// Sets the child component IDs.
+    pub fn with_children<S: Into<String>>(mut self, children: impl IntoIterator<Item = S>) -> Self {
+        self.children = children.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the parent component ID.
+    pub fn with_parent(mut self, parent: impl Into<String>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
 }
 
 impl LayoutManager {
@@ -120,6 +159,7 @@ impl LayoutManager {
             handlers: result.handlers.clone(),
             children: child_ids,
             parent,
+            origin: result.origin.clone(),
         };
 
         self.components.insert(result.id.clone(), component);
@@ -157,7 +197,10 @@ impl LayoutManager {
     /// Clears the current layout.
     pub fn clear(&mut self) {
         self.components.clear();
+        // The catalogs outlive a layout; only the bindings are reset.
+        let messages = self.bindings.messages().cloned();
         self.bindings = BindingManager::new();
+        self.bindings.set_messages(messages);
         self.list_bindings = ListBindingManager::new();
         self.current_config = None;
     }
@@ -250,6 +293,24 @@ impl LayoutManager {
         }
     }
 
+    /// Moves `child_id` to position `to` among its container's children, as
+    /// when the user drags it onto a sibling. `to` is clamped to the end.
+    /// Returns the child's old and new index, or `None` when `child_id` isn't
+    /// a child of `container_id`.
+    pub fn move_child(
+        &mut self,
+        container_id: &str,
+        child_id: &str,
+        to: usize,
+    ) -> Option<(usize, usize)> {
+        let container = self.components.get_mut(container_id)?;
+        let from = container.children.iter().position(|c| c == child_id)?;
+        let child = container.children.remove(from);
+        let to = to.min(container.children.len());
+        container.children.insert(to, child);
+        Some((from, to))
+    }
+
     /// Updates a component property.
     pub fn set_property(
         &mut self,
@@ -269,6 +330,26 @@ impl LayoutManager {
         Ok(())
     }
 
+    /// Updates a component property from user interaction (a sorted header,
+    /// a selected row) and returns the source paths of the `two_way` bindings
+    /// that target it, so the caller can write the value back to the
+    /// repository.
+    pub fn set_property_from_ui(
+        &mut self,
+        component_id: &str,
+        property: &str,
+        value: Value,
+    ) -> Result<Vec<String>, LayoutError> {
+        self.set_property(component_id, property, value)?;
+        Ok(self
+            .bindings
+            .bindings_for_component(component_id)
+            .into_iter()
+            .filter(|b| b.mode == BindingMode::TwoWay && b.target.property_path == property)
+            .map(|b| b.source.clone())
+            .collect())
+    }
+
     /// Generates a unique dynamic component ID (`__dyn_N`).
     ///
     /// The counter is monotonic and never resets, so IDs remain document-wide
@@ -327,6 +408,7 @@ impl LayoutManager {
             handlers,
             children: Vec::new(),
             parent: parent.map(|p| p.to_string()),
+            origin: None,
         };
 
         if let Some(parent_id) = parent {
@@ -339,6 +421,54 @@ impl LayoutManager {
         Ok(())
     }
 
+    /// Inserts the component subtree described by a layout `Value` node (the
+    /// shape `parse_layout_config` reads: `type`, properties, `on_*`
+    /// handlers, `bind_*`/`binding` bindings, `component` children) at
+    /// runtime.
+    ///
+    /// The node becomes component `id` and every descendant keyed `<key>` in
+    /// a `component` map becomes `<id>_<key>`, the way a template instance
+    /// scopes its children; an entry of a `component` array becomes
+    /// `<parent id>_<index>`. A nested `list_binding` is not registered — the
+    /// node is built as a plain, empty container.
+    pub fn insert_tree(
+        &mut self,
+        id: &str,
+        parent: Option<&str>,
+        node: &Value,
+    ) -> Result<(), LayoutError> {
+        self.insert_subtree(id, id, parent, node)
+    }
+
+    /// Inserts `node` as component `id`, then its children (see
+    /// [`Self::insert_tree`]); `root` is the id of the subtree's root.
+    fn insert_subtree(
+        &mut self,
+        root: &str,
+        id: &str,
+        parent: Option<&str>,
+        node: &Value,
+    ) -> Result<(), LayoutError> {
+        let component_type = node.get("type").and_then(|v| v.as_str()).unwrap_or("panel");
+        let parts = NodeParts::of(node);
+        self.insert_component(id, component_type, parent, parts.properties, parts.handlers)?;
+        if let Some(component) = self.components.get_mut(id) {
+            component.origin = parts.origin;
+        }
+        for spec in parts.bindings {
+            self.bindings.bind(
+                spec.source,
+                ComponentProperty::new(id, spec.target),
+                spec.mode,
+                spec.transform,
+            );
+        }
+        for (child_id, child) in child_nodes(node, root, id) {
+            self.insert_subtree(root, &child_id, Some(id), child)?;
+        }
+        Ok(())
+    }
+
     /// Removes a component and all its descendants recursively.
     ///
     /// Refuses to remove the current root (the component returned by
@@ -458,10 +588,83 @@ impl LayoutManager {
     }
 }
 
+/// A layout `Value` node split the way [`LayoutManager::insert_tree`] builds
+/// it.
+pub(crate) struct NodeParts {
+    pub(crate) properties: HashMap<String, Value>,
+    pub(crate) handlers: HashMap<String, String>,
+    pub(crate) bindings: Vec<BindingSpec>,
+    pub(crate) origin: Option<SourceLocation>,
+}
+
+impl NodeParts {
+    pub(crate) fn of(node: &Value) -> Self {
+        let mut parts = NodeParts {
+            properties: HashMap::new(),
+            handlers: HashMap::new(),
+            bindings: Vec::new(),
+            origin: node
+                .get(ORIGIN_KEY)
+                .and_then(|v| v.as_str())
+                .and_then(SourceLocation::parse),
+        };
+        let Some(obj) = node.as_object() else {
+            return parts;
+        };
+        for (key, val) in obj {
+            match key.as_str() {
+                "type" | "id" | "component" | "slot" | "vars" | "list_binding" | ORIGIN_KEY => {}
+                "binding" => {
+                    let blocks = match val {
+                        Value::Array(arr) => arr.iter().collect(),
+                        other => vec![other],
+                    };
+                    parts
+                        .bindings
+                        .extend(blocks.into_iter().filter_map(BindingSpec::from_value));
+                }
+                _ => {
+                    if let Some(event) = key.strip_prefix("on_") {
+                        if let Some(handler) = val.as_str() {
+                            parts
+                                .handlers
+                                .insert(event.to_string(), handler.to_string());
+                        }
+                    } else if let Some(target) = key.strip_prefix("bind_") {
+                        if let Some(source) = val.as_str() {
+                            parts.bindings.push(BindingSpec::one_way(source, target));
+                        }
+                    } else {
+                        parts.properties.insert(key.clone(), val.clone());
+                    }
+                }
+            }
+        }
+        parts
+    }
+}
+
+/// The `component` children of a layout `Value` node in the subtree rooted
+/// at `root`, with the ids [`LayoutManager::insert_tree`] gives them.
+pub(crate) fn child_nodes<'a>(node: &'a Value, root: &str, id: &str) -> Vec<(String, &'a Value)> {
+    match node.get("component") {
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(key, child)| (format!("{}_{}", root, key), child))
+            .collect(),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .enumerate()
+            .map(|(i, child)| (format!("{}_{}", id, i), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::node::{BindingSpec, LayoutType};
+    use crate::node::LayoutType;
     use nemo_registry::register_all_builtins;
 
     fn setup_manager() -> LayoutManager {
@@ -470,6 +673,23 @@ mod tests {
         LayoutManager::new(registry)
     }
 
+    #[test]
+    fn test_built_component_builder() {
+        let component = BuiltComponent::new("save", "button")
+            .with_props([("label", Value::String("Save".into()))])
+            .with_children(["icon"])
+            .with_parent("toolbar");
+
+        assert_eq!(component.component_type, "button");
+        assert_eq!(
+            component.properties.get("label"),
+            Some(&Value::String("Save".into()))
+        );
+        assert_eq!(component.children, ["icon"]);
+        assert_eq!(component.parent.as_deref(), Some("toolbar"));
+        assert!(component.handlers.is_empty());
+    }
+
     #[test]
     fn test_manager_creation() {
         let manager = setup_manager();
@@ -553,6 +773,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_property_from_ui_returns_two_way_sources() {
+        let mut manager = setup_manager();
+
+        let mut table = LayoutNode::new("table").with_id("grid");
+        table
+            .config
+            .bindings
+            .push(BindingSpec::two_way("state.sort", "sort"));
+        table
+            .config
+            .bindings
+            .push(BindingSpec::one_way("data.rows", "data"));
+
+        let root = LayoutNode::new("stack").with_id("root").with_child(table);
+        manager
+            .apply_layout(LayoutConfig::new(LayoutType::Stack, root))
+            .unwrap();
+
+        let sort = Value::String("name:desc".into());
+        let sources = manager
+            .set_property_from_ui("grid", "sort", sort.clone())
+            .unwrap();
+        assert_eq!(sources, vec!["state.sort".to_string()]);
+        assert_eq!(manager.get_property("grid", "sort"), Some(&sort));
+
+        // One-way targets are never written back.
+        let sources = manager
+            .set_property_from_ui("grid", "data", Value::Array(vec![]))
+            .unwrap();
+        assert!(sources.is_empty());
+    }
+
     // ── Runtime insert/remove ──────────────────────────────────────────
 
     #[test]
@@ -742,6 +995,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_move_child() {
+        let mut manager = setup_manager();
+        let root = LayoutNode::new("stack")
+            .with_id("root")
+            .with_child(LayoutNode::new("panel").with_id("a"))
+            .with_child(LayoutNode::new("panel").with_id("b"))
+            .with_child(LayoutNode::new("panel").with_id("c"));
+        manager
+            .apply_layout(LayoutConfig::new(LayoutType::Stack, root))
+            .unwrap();
+        let children = |m: &LayoutManager| m.get_component("root").unwrap().children.clone();
+
+        assert_eq!(manager.move_child("root", "a", 2), Some((0, 2)));
+        assert_eq!(children(&manager), vec!["b", "c", "a"]);
+        assert_eq!(manager.move_child("root", "a", 0), Some((2, 0)));
+        assert_eq!(children(&manager), vec!["a", "b", "c"]);
+        assert_eq!(manager.move_child("root", "b", 99), Some((1, 2)));
+        assert_eq!(children(&manager), vec!["a", "c", "b"]);
+        assert_eq!(manager.move_child("root", "zzz", 0), None);
+        assert_eq!(manager.move_child("nope", "a", 0), None);
+    }
+
     #[test]
     fn test_generate_dynamic_id_unique() {
         let mut manager = setup_manager();
@@ -1,6 +1,6 @@
 //! Layout node types.
 
-use nemo_config::Value;
+use nemo_config::{SourceLocation, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -23,6 +23,10 @@ pub struct LayoutNode {
     pub layout_hints: LayoutHints,
     /// Event handlers.
     pub handlers: HashMap<String, String>,
+    /// Where the node was written, when the config recorded origins. Runtime
+    /// errors about the component point here.
+    #[serde(default)]
+    pub origin: Option<SourceLocation>,
 }
 
 impl LayoutNode {
@@ -36,6 +40,7 @@ impl LayoutNode {
             list_binding: None,
             layout_hints: LayoutHints::default(),
             handlers: HashMap::new(),
+            origin: None,
         }
     }
 
@@ -69,6 +74,12 @@ impl LayoutNode {
         self
     }
 
+    /// Sets where the node was written.
+    pub fn with_origin(mut self, origin: SourceLocation) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
     /// Returns the effective ID (generated if not set).
     pub fn effective_id(&self) -> String {
         self.id
@@ -154,6 +165,22 @@ impl BindingSpec {
         self.transform = Some(transform.into());
         self
     }
+
+    /// Reads a `binding` block: `{ source, target, mode?, transform? }`, where
+    /// `mode` is `"two_way"` or anything else for one-way. `None` when the
+    /// block isn't an object.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        let text = |key: &str| obj.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        let spec = match text("mode") {
+            "two_way" => Self::two_way(text("source"), text("target")),
+            _ => Self::one_way(text("source"), text("target")),
+        };
+        Some(match obj.get("transform").and_then(|v| v.as_str()) {
+            Some(transform) => spec.with_transform(transform),
+            None => spec,
+        })
+    }
 }
 
 /// Specification for a live-data list binding (runtime `n:for`).
@@ -0,0 +1,98 @@
+//! Render-time side of responsive layouts.
+//!
+//! The directives pass turns `n:show-when="width < 900"` into the
+//! [`SHOW_WHEN`] property, and style folding turns SFC `<style>` `@media`
+//! rules into [`MEDIA_STYLE`] entries (`{when, style}`, `when` in the
+//! `n:show-when` syntax). Both are evaluated against the window size on every
+//! render, so they follow resizes.
+
+use indexmap::IndexMap;
+use nemo_config::{Value, ViewportCondition};
+use std::collections::HashMap;
+
+/// Property holding a component's `n:show-when` condition.
+pub const SHOW_WHEN: &str = "show_when";
+
+/// Property holding a component's folded `@media` rules.
+pub const MEDIA_STYLE: &str = "media_style";
+
+/// Whether a component is shown in a `width`×`height` window. Components
+/// without a condition, or with one that doesn't parse (the loader rejects
+/// those), are always shown.
+pub fn is_shown(properties: &HashMap<String, Value>, width: f32, height: f32) -> bool {
+    properties
+        .get(SHOW_WHEN)
+        .and_then(|v| v.as_str())
+        .and_then(|expr| ViewportCondition::parse(expr).ok())
+        .is_none_or(|condition| condition.matches(width, height))
+}
+
+/// The declarations of a component's `@media` rules that match a
+/// `width`×`height` window. Entries are in fold order, so an earlier entry's
+/// value for an attribute wins.
+pub fn media_declarations(
+    properties: &HashMap<String, Value>,
+    width: f32,
+    height: f32,
+) -> IndexMap<String, Value> {
+    let mut decls = IndexMap::new();
+    let Some(entries) = properties.get(MEDIA_STYLE).and_then(|v| v.as_array()) else {
+        return decls;
+    };
+    for entry in entries {
+        let matches = entry
+            .get("when")
+            .and_then(|v| v.as_str())
+            .and_then(|when| ViewportCondition::parse(when).ok())
+            .is_some_and(|condition| condition.matches(width, height));
+        if let (true, Some(style)) = (matches, entry.get("style").and_then(|v| v.as_object())) {
+            for (attr, value) in style {
+                decls.entry(attr.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+    decls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(when: &str, pairs: &[(&str, i64)]) -> Value {
+        let style = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::Integer(*v)))
+            .collect();
+        let mut entry = IndexMap::new();
+        entry.insert("when".to_string(), Value::String(when.to_string()));
+        entry.insert("style".to_string(), Value::Object(style));
+        Value::Object(entry)
+    }
+
+    #[test]
+    fn test_is_shown() {
+        let mut props = HashMap::new();
+        assert!(is_shown(&props, 100.0, 100.0));
+        props.insert(SHOW_WHEN.to_string(), Value::String("width < 900".into()));
+        assert!(is_shown(&props, 800.0, 600.0));
+        assert!(!is_shown(&props, 1200.0, 600.0));
+    }
+
+    #[test]
+    fn test_media_declarations() {
+        let mut props = HashMap::new();
+        props.insert(
+            MEDIA_STYLE.to_string(),
+            Value::Array(vec![
+                media("width <= 600", &[("padding", 4)]),
+                media("width <= 900", &[("padding", 8), ("width", 300)]),
+            ]),
+        );
+        assert!(media_declarations(&props, 1200.0, 800.0).is_empty());
+        let mid = media_declarations(&props, 800.0, 800.0);
+        assert_eq!(mid["padding"].as_i64(), Some(8));
+        let narrow = media_declarations(&props, 500.0, 800.0);
+        assert_eq!(narrow["padding"].as_i64(), Some(4));
+        assert_eq!(narrow["width"].as_i64(), Some(300));
+    }
+}
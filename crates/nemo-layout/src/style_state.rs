@@ -0,0 +1,179 @@
+//! Render-time side of SFC `<style>` pseudo-class rules.
+//!
+//! Style folding (`fold_sfc_styles` in the `nemo` binary) applies plain rules
+//! as inline attributes, but a rule ending in `:hover`, `:active`, `:focus` or
+//! `:disabled` only holds some of the time. Its declarations are folded into an
+//! object property instead (`hover_style`, …) that the renderer applies while
+//! the state holds: `:hover`/`:active` as gpui style refinements, limited to
+//! [`REFINABLE_PROPERTIES`], and `:focus`/`:disabled` by overlaying the
+//! component's properties ([`with_active_states`], which also overlays the
+//! matching `@media` rules from [`crate::responsive`]).
+
+use crate::responsive;
+use indexmap::IndexMap;
+use nemo_config::Value;
+use std::collections::HashMap;
+
+/// A pseudo-class a style rule can be limited to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StyleState {
+    Hover,
+    Active,
+    Focus,
+    Disabled,
+}
+
+/// Properties a `:hover`/`:active` rule can set (gpui applies those states as
+/// a style refinement of the component's wrapper).
+pub const REFINABLE_PROPERTIES: &[&str] = &["background", "border_color", "border", "rounded"];
+
+impl StyleState {
+    /// All states, in the order their overlays are applied.
+    pub const ALL: [StyleState; 4] = [
+        StyleState::Hover,
+        StyleState::Active,
+        StyleState::Focus,
+        StyleState::Disabled,
+    ];
+
+    /// Parses a pseudo-class name (without the `:`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "hover" => Some(StyleState::Hover),
+            "active" => Some(StyleState::Active),
+            "focus" => Some(StyleState::Focus),
+            "disabled" => Some(StyleState::Disabled),
+            _ => None,
+        }
+    }
+
+    /// The component property holding the state's declarations.
+    pub const fn property(self) -> &'static str {
+        match self {
+            StyleState::Hover => "hover_style",
+            StyleState::Active => "active_style",
+            StyleState::Focus => "focus_style",
+            StyleState::Disabled => "disabled_style",
+        }
+    }
+
+    /// Whether the state follows the pointer, so it can only set
+    /// [`REFINABLE_PROPERTIES`].
+    pub const fn is_pointer(self) -> bool {
+        matches!(self, StyleState::Hover | StyleState::Active)
+    }
+}
+
+/// The declarations a component's style rules give it for `state`.
+pub fn state_declarations(
+    properties: &HashMap<String, Value>,
+    state: StyleState,
+) -> Option<&IndexMap<String, Value>> {
+    properties
+        .get(state.property())
+        .and_then(|v| v.as_object())
+        .filter(|decls| !decls.is_empty())
+}
+
+/// The component's properties with the declarations of its `@media` rules
+/// matching the `viewport` (width, height), then its `:focus` (when `focused`)
+/// and `:disabled` (when `disabled` is true) rules on top, or `None` when none
+/// apply. Later overlays win, so `:disabled` beats `:focus` beats `@media`.
+pub fn with_active_states(
+    properties: &HashMap<String, Value>,
+    focused: bool,
+    viewport: (f32, f32),
+) -> Option<HashMap<String, Value>> {
+    let media = responsive::media_declarations(properties, viewport.0, viewport.1);
+    let disabled = properties.get("disabled").and_then(|v| v.as_bool()) == Some(true);
+    let states = [
+        (StyleState::Focus, focused),
+        (StyleState::Disabled, disabled),
+    ]
+    .into_iter()
+    .filter(|(_, holds)| *holds)
+    .filter_map(|(state, _)| state_declarations(properties, state));
+    let overlays: Vec<_> = std::iter::once(&media)
+        .filter(|decls| !decls.is_empty())
+        .chain(states)
+        .collect();
+    if overlays.is_empty() {
+        return None;
+    }
+    let mut merged = properties.clone();
+    for decls in overlays {
+        merged.extend(decls.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decls(pairs: &[(&str, Value)]) -> Value {
+        Value::Object(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_and_property() {
+        for state in StyleState::ALL {
+            let name = state.property().trim_end_matches("_style");
+            assert_eq!(StyleState::parse(name), Some(state));
+        }
+        assert_eq!(StyleState::parse("visited"), None);
+        assert!(StyleState::Hover.is_pointer());
+        assert!(!StyleState::Disabled.is_pointer());
+    }
+
+    const VIEWPORT: (f32, f32) = (1280.0, 800.0);
+
+    #[test]
+    fn test_with_active_states() {
+        let mut props = HashMap::new();
+        props.insert("background".to_string(), Value::String("#ffffff".into()));
+        props.insert(
+            "focus_style".to_string(),
+            decls(&[
+                ("background", Value::String("theme.accent".into())),
+                ("border", Value::Integer(2)),
+            ]),
+        );
+        props.insert(
+            "disabled_style".to_string(),
+            decls(&[("background", Value::String("theme.muted".into()))]),
+        );
+
+        assert!(with_active_states(&props, false, VIEWPORT).is_none());
+
+        let focused = with_active_states(&props, true, VIEWPORT).unwrap();
+        assert_eq!(focused["background"].as_str(), Some("theme.accent"));
+        assert_eq!(focused["border"].as_i64(), Some(2));
+
+        props.insert("disabled".to_string(), Value::Bool(true));
+        let disabled = with_active_states(&props, true, VIEWPORT).unwrap();
+        assert_eq!(disabled["background"].as_str(), Some("theme.muted"));
+        assert_eq!(disabled["border"].as_i64(), Some(2));
+
+        // A matching `@media` rule applies underneath the states.
+        let mut media = IndexMap::new();
+        media.insert("when".to_string(), Value::String("width < 900".into()));
+        media.insert(
+            "style".to_string(),
+            decls(&[("padding", Value::Integer(4))]),
+        );
+        props.insert(
+            "media_style".to_string(),
+            Value::Array(vec![Value::Object(media)]),
+        );
+        props.remove("disabled");
+        assert!(with_active_states(&props, false, VIEWPORT).is_none());
+        let narrow = with_active_states(&props, false, (800.0, 600.0)).unwrap();
+        assert_eq!(narrow["padding"].as_i64(), Some(4));
+    }
+}
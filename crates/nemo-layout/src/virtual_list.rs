@@ -0,0 +1,396 @@
+//! Row building and keyed diffing for `<virtual-list>`.
+//!
+//! Unlike live-data `n:for` (see [`crate::list_binding`]), a virtual list does
+//! not insert a component subtree for every item into the [`LayoutManager`].
+//! The renderer asks for rows only as they scroll into view: each one is
+//! expanded from the item template with the item's values substituted
+//! literally ([`row_node`]) and inserted under the list with
+//! [`LayoutManager::insert_tree`], so its bindings, write-backs and handlers
+//! work as anywhere else. The renderer keeps rows by key, so a row that
+//! scrolls out and back, or survives a data update unchanged, is reused
+//! rather than rebuilt.
+//!
+//! [`LayoutManager`]: crate::manager::LayoutManager
+//! [`LayoutManager::insert_tree`]: crate::manager::LayoutManager::insert_tree
+
+use crate::binding::value_equals;
+use crate::branch::is_truthy;
+use crate::list_binding::resolve_key;
+use nemo_config::{compile_directives_node, Value};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Keys of `items`, in order: the value of the `key` expression (`item.id`)
+/// per item, or the index when there is no key or an item lacks it. A
+/// repeated key gets its index appended so row ids stay unique.
+pub fn item_keys(items: &[Value], key: Option<&str>, item_var: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let key = key
+                .and_then(|expr| resolve_key(expr, item_var, item))
+                .unwrap_or_else(|| index.to_string());
+            if seen.insert(key.clone()) {
+                key
+            } else {
+                format!("{}~{}", key, index)
+            }
+        })
+        .collect()
+}
+
+/// The change that turns the `old` key sequence into `new`: the range of old
+/// rows to replace and how many new rows take their place, found by trimming
+/// the common prefix and suffix. `None` when nothing changed.
+///
+/// Appending a page or replacing a single row touches only that stretch of
+/// the list, so the measured heights and scroll position of the rest survive.
+pub fn splice_range(old: &[String], new: &[String]) -> Option<(Range<usize>, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let replaced = prefix..old.len() - suffix;
+    let inserted = new.len() - suffix - prefix;
+    if replaced.is_empty() && inserted == 0 {
+        None
+    } else {
+        Some((replaced, inserted))
+    }
+}
+
+/// Expands the item template for one item.
+///
+/// A string that is exactly one `${item}` or `${item.path}` placeholder takes
+/// the value itself, so numbers and booleans keep their type; placeholders
+/// inside longer strings are replaced by the value's text. Paths may be
+/// nested (`${row.user.name}`) and index into arrays; a missing value becomes
+/// null, or empty text inside a longer string.
+pub fn expand_item(template: &Value, item_var: &str, item: &Value) -> Value {
+    match template {
+        Value::String(s) => expand_string(s, item_var, item),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, v)| (k.clone(), expand_item(v, item_var, item)))
+                .collect(),
+        ),
+        Value::Array(arr) => {
+            Value::Array(arr.iter().map(|v| expand_item(v, item_var, item)).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Resolves a placeholder expression against the item, or `None` when it
+/// doesn't refer to the item variable.
+fn lookup(expr: &str, item_var: &str, item: &Value) -> Option<Value> {
+    let expr = expr.trim();
+    let path = if expr == item_var {
+        ""
+    } else {
+        expr.strip_prefix(item_var)?.strip_prefix('.')?
+    };
+    let mut current = item;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let next = match current {
+            Value::Array(_) => segment.parse().ok().and_then(|i| current.get_index(i)),
+            _ => current.get(segment),
+        };
+        match next {
+            Some(value) => current = value,
+            None => return Some(Value::Null),
+        }
+    }
+    Some(current.clone())
+}
+
+fn expand_string(s: &str, item_var: &str, item: &Value) -> Value {
+    if !s.contains("${") {
+        return Value::String(s.to_string());
+    }
+    if s.starts_with("${") && s.ends_with('}') && s.matches("${").count() == 1 {
+        if let Some(value) = lookup(&s[2..s.len() - 1], item_var, item) {
+            return value;
+        }
+    }
+    let mut result = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + len + 1];
+        match lookup(&placeholder[2..placeholder.len() - 1], item_var, item) {
+            Some(Value::Null) => {}
+            Some(value) => result.push_str(&value.to_string()),
+            // Not ours (e.g. a `${var.*}` left for another pass): keep it.
+            None => result.push_str(placeholder),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    Value::String(result)
+}
+
+/// The layout node of one item's row: the item template expanded for the
+/// item, then compiled the way the layout is. Directives that test the item
+/// (`n:if="msg.unread"`, `n:switch="msg.kind"`, `n:for="tag in msg.tags"`)
+/// are settled here, since no binding can watch a loop variable; the rest
+/// become bindings as usual. `None` when the row's own `n:if` is false.
+pub fn row_node(template: &Value, item_var: &str, item: &Value) -> Option<Value> {
+    let mut node = expand_item(template, item_var, item);
+    if let Some(cond) = node.get("n:if") {
+        match item_test(cond, item_var, item) {
+            Some(false) => return None,
+            Some(true) => {
+                if let Some(obj) = node.as_object_mut() {
+                    obj.shift_remove("n:if");
+                }
+            }
+            None => {}
+        }
+    }
+    resolve_item_directives(&mut node, item_var, item);
+    compile_directives_node(&mut node);
+    Some(node)
+}
+
+/// Evaluates a directive condition against the item, or `None` when it
+/// tests something else. A `${item.path}` condition was already replaced by
+/// its value, which is taken for its truthiness.
+fn item_test(cond: &Value, item_var: &str, item: &Value) -> Option<bool> {
+    let Value::String(cond) = cond else {
+        return Some(is_truthy(cond));
+    };
+    let cond = cond.trim();
+    for (op, negate) in [("==", false), ("!=", true)] {
+        if let Some((lhs, rhs)) = cond.split_once(op) {
+            let value = lookup(lhs, item_var, item)?;
+            return Some(value_equals(&value, rhs) != negate);
+        }
+    }
+    lookup(cond, item_var, item).map(|value| is_truthy(&value))
+}
+
+/// Settles the directives among `node`'s children that test the item: a
+/// chain whose `n:if` tests it keeps only the branch taken, an `n:switch` on
+/// it keeps only the matching `n:case`, and an `n:for` over it loops over the
+/// item's array as a literal. Recurses into the children kept.
+fn resolve_item_directives(node: &mut Value, item_var: &str, item: &Value) {
+    let Some(obj) = node.as_object_mut() else {
+        return;
+    };
+    let switch = match obj.get("n:switch").and_then(|v| v.as_str()) {
+        Some(source) => lookup(source, item_var, item),
+        None => None,
+    };
+    if switch.is_some() {
+        obj.shift_remove("n:switch");
+    }
+    let Some(map) = obj.get_mut("component").and_then(|v| v.as_object_mut()) else {
+        return;
+    };
+    // `Some(taken)` while a chain settled here is open.
+    let mut chain: Option<bool> = None;
+    for (key, mut child) in std::mem::take(map) {
+        let Some(child_obj) = child.as_object_mut() else {
+            map.insert(key, child);
+            continue;
+        };
+        let keep = if let Some(cond) = child_obj.get("n:if") {
+            chain = item_test(cond, item_var, item);
+            if chain.is_some() {
+                child_obj.shift_remove("n:if");
+            }
+            chain != Some(false)
+        } else if let (Some(value), Some(case)) = (&switch, child_obj.get("n:case")) {
+            let taken = chain.get_or_insert(false);
+            let hit = !*taken && value_equals(value, &case_literal(case));
+            *taken |= hit;
+            child_obj.shift_remove("n:case");
+            hit
+        } else if let (Some(taken), Some(cond)) = (chain, child_obj.get("n:else_if")) {
+            if taken {
+                false
+            } else {
+                match item_test(cond, item_var, item) {
+                    Some(hit) => {
+                        chain = Some(hit);
+                        child_obj.shift_remove("n:else_if");
+                        hit
+                    }
+                    // A condition on other data takes over the rest of the
+                    // chain as its `n:if`.
+                    None => {
+                        chain = None;
+                        if let Some(cond) = child_obj.shift_remove("n:else_if") {
+                            child_obj.insert("n:if".to_string(), cond);
+                        }
+                        true
+                    }
+                }
+            }
+        } else if let (Some(taken), true) = (chain, child_obj.contains_key("n:else")) {
+            chain = None;
+            child_obj.shift_remove("n:else");
+            !taken
+        } else {
+            // Other children end a chain, except among a switch's cases.
+            if switch.is_none() {
+                chain = None;
+            }
+            true
+        };
+        if !keep {
+            continue;
+        }
+        if let Some(for_expr) = child_obj.get("n:for").and_then(|v| v.as_str()) {
+            if let Some((var, source)) = for_expr.split_once(" in ") {
+                if let Some(value) = lookup(source, item_var, item) {
+                    let items = match value {
+                        Value::Array(items) => Value::Array(items),
+                        _ => Value::Array(Vec::new()),
+                    };
+                    let literal = serde_json::to_string(&items).unwrap_or_default();
+                    child_obj.insert(
+                        "n:for".to_string(),
+                        Value::String(format!("{} in {}", var.trim(), literal)),
+                    );
+                }
+            }
+        }
+        resolve_item_directives(&mut child, item_var, item);
+        map.insert(key, child);
+    }
+}
+
+/// The text an `n:case` value is compared as.
+fn case_literal(case: &Value) -> String {
+    match case {
+        Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_item_keys() {
+        let items = parse(r#"[{"id": 7}, {"id": 9}, {"name": "x"}, {"id": 7}]"#);
+        let items = items.as_array().unwrap();
+        assert_eq!(
+            item_keys(items, Some("msg.id"), "msg"),
+            vec!["7", "9", "2", "7~3"]
+        );
+        assert_eq!(item_keys(items, None, "msg"), vec!["0", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_splice_range() {
+        let keys = |s: &str| -> Vec<String> { s.chars().map(String::from).collect() };
+        assert_eq!(splice_range(&keys("abc"), &keys("abc")), None);
+        // Appending a page.
+        assert_eq!(splice_range(&keys("abc"), &keys("abcde")), Some((3..3, 2)));
+        // Removing from the middle.
+        assert_eq!(splice_range(&keys("abcd"), &keys("ad")), Some((1..3, 0)));
+        // Prepending.
+        assert_eq!(splice_range(&keys("bc"), &keys("abc")), Some((0..0, 1)));
+        // Repeated keys don't let prefix and suffix overlap.
+        assert_eq!(splice_range(&keys("aa"), &keys("aaa")), Some((2..2, 1)));
+        assert_eq!(splice_range(&keys("ab"), &keys("xy")), Some((0..2, 2)));
+        assert_eq!(splice_range(&keys("ab"), &[]), Some((0..2, 0)));
+    }
+
+    #[test]
+    fn test_expand_item() {
+        let item = parse(r#"{"id": 3, "ok": true, "user": {"name": "Ada"}, "tags": ["a", "b"]}"#);
+        let template = parse(
+            r#"{
+                "type": "stack",
+                "visible": "${row.ok}",
+                "component": {
+                    "name": {"type": "label", "text": "${row.user.name} (#${row.id})"},
+                    "tag": {"type": "label", "text": "${row.tags.1}${row.missing}"},
+                    "env": {"type": "label", "text": "${var.title}: ${row.id}"}
+                }
+            }"#,
+        );
+        let expanded = expand_item(&template, "row", &item);
+        assert_eq!(expanded.get("visible"), Some(&Value::Bool(true)));
+        let label = |id: &str| {
+            expanded
+                .get("component")
+                .unwrap()
+                .get(id)
+                .unwrap()
+                .get("text")
+                .cloned()
+        };
+        assert_eq!(label("name"), Some(Value::String("Ada (#3)".into())));
+        assert_eq!(label("tag"), Some(Value::String("b".into())));
+        assert_eq!(label("env"), Some(Value::String("${var.title}: 3".into())));
+        assert_eq!(
+            expand_item(&Value::String("${row.nope}".into()), "row", &item),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_row_node_settles_item_directives() {
+        let template = parse(
+            r#"{
+                "type": "stack",
+                "n:switch": "msg.kind",
+                "component": {
+                    "unread": {"type": "icon", "n:if": "msg.unread"},
+                    "read": {"type": "icon", "n:else": true},
+                    "mine": {"type": "label", "n:if": "msg.author == 'me'", "text": "You"},
+                    "online": {"type": "icon", "n:if": "data.online"},
+                    "text": {"type": "text", "n:case": "text", "content": "${msg.body}"},
+                    "image": {"type": "image", "n:case": "image"},
+                    "tag": {"type": "tag", "n:for": "t in msg.tags", "label": "${t}"}
+                }
+            }"#,
+        );
+        let item = parse(
+            r#"{"kind": "text", "unread": true, "author": "ada", "body": "Hi", "tags": ["a", "b"]}"#,
+        );
+        let node = row_node(&template, "msg", &item).unwrap();
+        let children = node.get("component").and_then(|c| c.as_object()).unwrap();
+        let ids: Vec<&str> = children.keys().map(String::as_str).collect();
+        assert_eq!(ids, vec!["unread", "online", "text", "tag_0", "tag_1"]);
+        assert!(node.get("n:switch").is_none());
+        assert_eq!(
+            children["text"].get("content"),
+            Some(&Value::String("Hi".into()))
+        );
+        assert_eq!(
+            children["tag_1"].get("label"),
+            Some(&Value::String("b".into()))
+        );
+        // A condition on other data is still a binding.
+        assert_eq!(
+            children["online"].get("bind_visible"),
+            Some(&Value::String("data.online".into()))
+        );
+
+        let template = parse(r#"{"type": "label", "n:if": "msg.unread"}"#);
+        assert!(row_node(&template, "msg", &parse(r#"{"unread": false}"#)).is_none());
+        let node = row_node(&template, "msg", &parse(r#"{"unread": 1}"#)).unwrap();
+        assert!(node.get("n:if").is_none());
+    }
+}
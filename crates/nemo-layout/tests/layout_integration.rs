@@ -3,7 +3,7 @@
 //! These tests verify layout construction, component management, property
 //! access, data binding propagation, and layout lifecycle operations.
 
-use nemo_config::Value;
+use nemo_config::{SourceLocation, Value};
 use nemo_layout::{BindingSpec, LayoutConfig, LayoutManager, LayoutNode, LayoutType};
 use nemo_registry::{register_all_builtins, ComponentRegistry};
 use std::sync::Arc;
@@ -79,6 +79,21 @@ fn nested_component_tree() {
     assert!(lm.get_component("subtitle").is_some());
 }
 
+#[test]
+fn built_components_keep_their_origin() {
+    let mut lm = make_manager();
+    let origin = SourceLocation::new("app.nemo", 42, 7);
+    let root = LayoutNode::new("stack")
+        .with_id("root")
+        .with_child(label_node("title", "Hello").with_origin(origin.clone()));
+
+    lm.apply_layout(LayoutConfig::new(LayoutType::Stack, root))
+        .unwrap();
+
+    assert_eq!(lm.get_component("title").unwrap().origin, Some(origin));
+    assert_eq!(lm.get_component("root").unwrap().origin, None);
+}
+
 // ── Property access ──────────────────────────────────────────────────────
 
 #[test]
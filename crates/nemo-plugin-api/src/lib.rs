@@ -403,6 +403,86 @@ pub trait PluginContext: Send + Sync {
     fn set_roundness(&self, _value: &str) -> Result<(), PluginError> {
         Err(PluginError::Unsupported("set_roundness".to_string()))
     }
+
+    /// Translates message `key` from the app's `<i18n>` catalogs in the
+    /// active locale. `args` is an object of named `{ $name }` arguments, or
+    /// any other value as `{ $value }`. Returns `None` when the app has no
+    /// catalogs or no locale defines `key`.
+    fn translate(&self, _key: &str, _args: PluginValue) -> Option<String> {
+        None
+    }
+
+    /// Returns the active locale, or `None` when the app has no catalogs.
+    fn get_locale(&self) -> Option<String> {
+        None
+    }
+
+    /// Switches the UI language to `locale` (falling back to its language,
+    /// then the app's default locale). Labels built from `${t(…)}` and
+    /// `t('key')` binding transforms re-render. Applied asynchronously; see
+    /// [`Self::set_roundness`].
+    ///
+    /// The default implementation reports the operation as unsupported so
+    /// existing plugin SDKs continue to compile without change.
+    fn set_locale(&self, _locale: &str) -> Result<(), PluginError> {
+        Err(PluginError::Unsupported("set_locale".to_string()))
+    }
+
+    /// Exports a `table` or chart component to `path`. The extension picks
+    /// the format: `.csv` and `.json` write the component's bound `data`,
+    /// `.png` captures a chart as drawn (applied asynchronously, after the
+    /// next frame).
+    ///
+    /// The default implementation reports the operation as unsupported so
+    /// existing plugin SDKs continue to compile without change.
+    fn export_component(&self, _component_id: &str, _path: &str) -> Result<(), PluginError> {
+        Err(PluginError::Unsupported("export_component".to_string()))
+    }
+
+    /// Raises a single-file component's custom event (declared in its
+    /// `<emits>` block) from the SFC script handler that is running. The host
+    /// routes it to the enclosing instance's `on-<event>` handler, called with
+    /// the instance id and `payload`, once the current handler returns.
+    ///
+    /// The default implementation reports the operation as unsupported so
+    /// existing plugin SDKs continue to compile without change.
+    fn emit_component_event(&self, _event: &str, _payload: PluginValue) -> Result<(), PluginError> {
+        Err(PluginError::Unsupported("emit_component_event".to_string()))
+    }
+
+    /// Reads a field of the running single-file component instance's
+    /// `<state>` block (the instance enclosing the component whose handler is
+    /// running). Returns `None` outside an SFC handler or for an unset field.
+    fn get_instance_state(&self, _field: &str) -> Option<PluginValue> {
+        None
+    }
+
+    /// Writes a field of the running single-file component instance's
+    /// `<state>` block; bindings on `${state.<field>}` in that instance update.
+    ///
+    /// The default implementation reports the operation as unsupported so
+    /// existing plugin SDKs continue to compile without change.
+    fn set_instance_state(&self, _field: &str, _value: PluginValue) -> Result<(), PluginError> {
+        Err(PluginError::Unsupported("set_instance_state".to_string()))
+    }
+
+    /// Gets a value from the app's `state.*` namespace (e.g. `"theme"` reads
+    /// `state.theme`). State declared with `<state persist="true">` survives
+    /// restarts.
+    ///
+    /// The default implementation returns `None`.
+    fn get_state(&self, _path: &str) -> Option<PluginValue> {
+        None
+    }
+
+    /// Sets a value in the app's `state.*` namespace. Persisted state is
+    /// written to disk shortly after the last change.
+    ///
+    /// The default implementation reports the operation as unsupported so
+    /// existing plugin SDKs continue to compile without change.
+    fn set_state(&self, _path: &str, _value: PluginValue) -> Result<(), PluginError> {
+        Err(PluginError::Unsupported("set_state".to_string()))
+    }
 }
 
 /// Log level.
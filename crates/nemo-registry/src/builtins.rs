@@ -33,6 +33,19 @@ fn reg(
     let _ = registry.register_component(d);
 }
 
+/// Helper: adds the attributes a `<form>` reads from a named field (its
+/// payload key and validation rules) to an input component's schema.
+fn form_field(schema: ConfigSchema) -> ConfigSchema {
+    schema
+        .property("name", PropertySchema::string())
+        .property("required", PropertySchema::boolean().with_default(false))
+        .property("min_length", PropertySchema::integer())
+        .property("max_length", PropertySchema::integer())
+        .property("pattern", PropertySchema::string())
+        .property("validator", PropertySchema::string())
+        .property("message", PropertySchema::string())
+}
+
 /// Registers all built-in components.
 pub fn register_builtin_components(registry: &ComponentRegistry) {
     register_layout_components(registry);
@@ -107,6 +120,49 @@ fn register_layout_components(registry: &ComponentRegistry) {
             .property("shadow", PropertySchema::string()),
     );
 
+    reg(
+        registry,
+        "grid",
+        ComponentCategory::Layout,
+        "Grid",
+        "Places children on column and row tracks (px, fr, auto) with row/column spans",
+        ConfigSchema::new("grid")
+            .property("columns", PropertySchema::any())
+            .property("rows", PropertySchema::any())
+            .property("gap", PropertySchema::integer().with_default(0i64))
+            .property("flex", PropertySchema::float()),
+    );
+
+    reg(
+        registry,
+        "split",
+        ComponentCategory::Layout,
+        "Split",
+        "Divides its space between child panes separated by draggable dividers",
+        ConfigSchema::new("split")
+            .property(
+                "direction",
+                PropertySchema::string()
+                    .with_default("horizontal")
+                    .one_of(enum_vals(&["horizontal", "vertical"])),
+            )
+            .property("sizes", PropertySchema::any())
+            .property("min_size", PropertySchema::integer().with_default(40i64))
+            .property("flex", PropertySchema::float()),
+    );
+
+    reg(
+        registry,
+        "drop_target",
+        ComponentCategory::Layout,
+        "Drop Target",
+        "Container that receives dragged items and files dropped from the OS",
+        ConfigSchema::new("drop_target")
+            .property("accept", PropertySchema::string())
+            .property("spacing", PropertySchema::integer().with_default(4i64))
+            .property("flex", PropertySchema::float()),
+    );
+
     reg(
         registry,
         "tabs",
@@ -224,6 +280,21 @@ fn register_container_components(registry: &ComponentRegistry) {
             .property("on_leave", PropertySchema::string()),
     );
 
+    reg(
+        registry,
+        "form",
+        ComponentCategory::Input,
+        "Form",
+        "Collects and validates its named input fields and submits them as one object",
+        ConfigSchema::new("form")
+            .property(
+                "submit_label",
+                PropertySchema::string().with_default("Submit"),
+            )
+            .property("value", PropertySchema::any())
+            .property("errors", PropertySchema::any()),
+    );
+
     reg(
         registry,
         "nav_link",
@@ -333,8 +404,22 @@ fn register_basic_components(registry: &ComponentRegistry) {
         "menu_item",
         ComponentCategory::Display,
         "Menu Item",
-        "A menu entry for use inside a DropdownButton",
-        ConfigSchema::new("menu_item").property("label", PropertySchema::string()),
+        "A menu entry for use inside a DropdownButton or ContextMenu",
+        ConfigSchema::new("menu_item")
+            .property("label", PropertySchema::string())
+            .property("icon", PropertySchema::string())
+            .property("value", PropertySchema::string())
+            .property("separator", PropertySchema::boolean().with_default(false))
+            .property("disabled", PropertySchema::boolean().with_default(false)),
+    );
+
+    reg(
+        registry,
+        "context_menu",
+        ComponentCategory::Navigation,
+        "Context Menu",
+        "Right-click menu of menu-item children for its parent component",
+        ConfigSchema::new("context_menu"),
     );
 
     reg(
@@ -428,10 +513,14 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Text Input",
         "A text input field",
-        ConfigSchema::new("input")
-            .property("placeholder", PropertySchema::string())
-            .property("value", PropertySchema::string())
-            .property("disabled", PropertySchema::boolean().with_default(false)),
+        form_field(
+            ConfigSchema::new("input")
+                .property("placeholder", PropertySchema::string())
+                .property("value", PropertySchema::string())
+                .property("disabled", PropertySchema::boolean().with_default(false))
+                .property("min", PropertySchema::integer())
+                .property("max", PropertySchema::integer()),
+        ),
     );
 
     reg(
@@ -440,13 +529,15 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Textarea",
         "A multi-line text input area",
-        ConfigSchema::new("textarea")
-            .property("placeholder", PropertySchema::string())
-            .property("default_value", PropertySchema::string())
-            .property("rows", PropertySchema::integer())
-            .property("auto_grow_min", PropertySchema::integer())
-            .property("auto_grow_max", PropertySchema::integer())
-            .property("disabled", PropertySchema::boolean().with_default(false)),
+        form_field(
+            ConfigSchema::new("textarea")
+                .property("placeholder", PropertySchema::string())
+                .property("default_value", PropertySchema::string())
+                .property("rows", PropertySchema::integer())
+                .property("auto_grow_min", PropertySchema::integer())
+                .property("auto_grow_max", PropertySchema::integer())
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
     );
 
     reg(
@@ -486,10 +577,12 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Checkbox",
         "A checkbox input",
-        ConfigSchema::new("checkbox")
-            .property("label", PropertySchema::string())
-            .property("checked", PropertySchema::boolean().with_default(false))
-            .property("disabled", PropertySchema::boolean().with_default(false)),
+        form_field(
+            ConfigSchema::new("checkbox")
+                .property("label", PropertySchema::string())
+                .property("checked", PropertySchema::boolean().with_default(false))
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
     );
 
     reg(
@@ -498,7 +591,7 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Select",
         "A select input built from option children",
-        ConfigSchema::new("select").property("value", PropertySchema::string()),
+        form_field(ConfigSchema::new("select").property("value", PropertySchema::string())),
     );
 
     reg(
@@ -518,12 +611,14 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Radio",
         "A radio button group built from option children",
-        ConfigSchema::new("radio")
-            .property("value", PropertySchema::string())
-            .property(
-                "direction",
-                PropertySchema::string().with_default("vertical"),
-            ),
+        form_field(
+            ConfigSchema::new("radio")
+                .property("value", PropertySchema::string())
+                .property(
+                    "direction",
+                    PropertySchema::string().with_default("vertical"),
+                ),
+        ),
     );
 
     reg(
@@ -532,11 +627,13 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Slider",
         "A range slider input",
-        ConfigSchema::new("slider")
-            .property("min", PropertySchema::float().with_default(0.0))
-            .property("max", PropertySchema::float().with_default(100.0))
-            .property("step", PropertySchema::float().with_default(1.0))
-            .property("value", PropertySchema::float().with_default(0.0)),
+        form_field(
+            ConfigSchema::new("slider")
+                .property("min", PropertySchema::float().with_default(0.0))
+                .property("max", PropertySchema::float().with_default(100.0))
+                .property("step", PropertySchema::float().with_default(1.0))
+                .property("value", PropertySchema::float().with_default(0.0)),
+        ),
     );
 
     reg(
@@ -545,10 +642,84 @@ fn register_input_components(registry: &ComponentRegistry) {
         ComponentCategory::Input,
         "Switch",
         "A toggle switch",
-        ConfigSchema::new("switch")
-            .property("checked", PropertySchema::boolean().with_default(false))
-            .property("label", PropertySchema::string())
-            .property("disabled", PropertySchema::boolean().with_default(false)),
+        form_field(
+            ConfigSchema::new("switch")
+                .property("checked", PropertySchema::boolean().with_default(false))
+                .property("label", PropertySchema::string())
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
+    );
+
+    reg(
+        registry,
+        "date_picker",
+        ComponentCategory::Input,
+        "Date Picker",
+        "A calendar date picker, optionally picking a range",
+        form_field(
+            ConfigSchema::new("date_picker")
+                .property("value", PropertySchema::any())
+                .property("range", PropertySchema::boolean().with_default(false))
+                .property("min", PropertySchema::string())
+                .property("max", PropertySchema::string())
+                .property("placeholder", PropertySchema::string())
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
+    );
+
+    reg(
+        registry,
+        "datetime_picker",
+        ComponentCategory::Input,
+        "Date-Time Picker",
+        "A calendar date picker with a time field",
+        form_field(
+            ConfigSchema::new("datetime_picker")
+                .property("value", PropertySchema::string())
+                .property("min", PropertySchema::string())
+                .property("max", PropertySchema::string())
+                .property("placeholder", PropertySchema::string())
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
+    );
+
+    reg(
+        registry,
+        "color_picker",
+        ComponentCategory::Input,
+        "Color Picker",
+        "A color picker with palette swatches",
+        form_field(
+            ConfigSchema::new("color_picker")
+                .property("value", PropertySchema::string())
+                .property(
+                    "format",
+                    PropertySchema::string()
+                        .with_default("hex")
+                        .one_of(enum_vals(&["hex", "hsl"])),
+                )
+                .property("swatches", PropertySchema::array(PropertySchema::string()))
+                .property("label", PropertySchema::string()),
+        ),
+    );
+
+    reg(
+        registry,
+        "number_input",
+        ComponentCategory::Input,
+        "Number Input",
+        "A numeric field with stepper buttons",
+        form_field(
+            ConfigSchema::new("number_input")
+                .property("value", PropertySchema::float())
+                .property("min", PropertySchema::float())
+                .property("max", PropertySchema::float())
+                .property("step", PropertySchema::float().with_default(1.0))
+                .property("precision", PropertySchema::integer().min(0))
+                .property("unit", PropertySchema::string())
+                .property("placeholder", PropertySchema::string())
+                .property("disabled", PropertySchema::boolean().with_default(false)),
+        ),
     );
 
     reg(
@@ -628,6 +799,17 @@ fn register_display_components(registry: &ComponentRegistry) {
             .require("content"),
     );
 
+    reg(
+        registry,
+        "markdown",
+        ComponentCategory::Display,
+        "Markdown",
+        "Rendered CommonMark with tables, task lists and highlighted code blocks",
+        ConfigSchema::new("markdown")
+            .property("content", PropertySchema::string().with_default(""))
+            .property("router", PropertySchema::string()),
+    );
+
     reg(
         registry,
         "progress",
@@ -638,6 +820,31 @@ fn register_display_components(registry: &ComponentRegistry) {
             .property("value", PropertySchema::float())
             .property("max", PropertySchema::float().with_default(100.0)),
     );
+
+    reg(
+        registry,
+        "stat",
+        ComponentCategory::Display,
+        "Stat",
+        "A KPI card with a headline value, its change and an optional sparkline",
+        ConfigSchema::new("stat")
+            .property("label", PropertySchema::string())
+            .property("value", PropertySchema::any())
+            .property("previous", PropertySchema::float())
+            .property("delta", PropertySchema::float())
+            .property(
+                "format",
+                PropertySchema::string()
+                    .with_default("number")
+                    .one_of(enum_vals(&["number", "compact", "percent"])),
+            )
+            .property("decimals", PropertySchema::integer().min(0))
+            .property("prefix", PropertySchema::string())
+            .property("unit", PropertySchema::string())
+            .property("invert", PropertySchema::boolean().with_default(false))
+            .property("data", PropertySchema::any())
+            .property("field", PropertySchema::string()),
+    );
 }
 
 fn register_data_components(registry: &ComponentRegistry) {
@@ -655,9 +862,30 @@ fn register_data_components(registry: &ComponentRegistry) {
                     ConfigSchema::new("column")
                         .property("key", PropertySchema::string())
                         .property("label", PropertySchema::string())
-                        .property("width", PropertySchema::integer()),
+                        .property("width", PropertySchema::integer())
+                        .property("sortable", PropertySchema::boolean().with_default(true))
+                        .property("filterable", PropertySchema::boolean().with_default(true))
+                        .property(
+                            "format",
+                            PropertySchema::string()
+                                .one_of(enum_vals(&["text", "number", "date", "badge"])),
+                        )
+                        .property("decimals", PropertySchema::integer())
+                        .property("date_format", PropertySchema::string())
+                        .property("variants", PropertySchema::any()),
                 )),
             )
+            .property("sort", PropertySchema::string())
+            .property("filterable", PropertySchema::boolean().with_default(false))
+            .property("filters", PropertySchema::any())
+            .property(
+                "selection",
+                PropertySchema::string()
+                    .one_of(enum_vals(&["none", "single", "multi"]))
+                    .with_default("none"),
+            )
+            .property("selected", PropertySchema::any())
+            .property("row_key", PropertySchema::string())
             .property("stripe", PropertySchema::boolean().with_default(false))
             .property("bordered", PropertySchema::boolean().with_default(true)),
     );
@@ -680,6 +908,25 @@ fn register_data_components(registry: &ComponentRegistry) {
         ConfigSchema::new("list_item"),
     );
 
+    reg(
+        registry,
+        "virtual_list",
+        ComponentCategory::Data,
+        "Virtual List",
+        "A scrolling list that renders its item template only for rows in view",
+        ConfigSchema::new("virtual_list")
+            .property("items", PropertySchema::array(PropertySchema::any()))
+            .property("item", PropertySchema::string().with_default("item"))
+            .property("item_key", PropertySchema::string())
+            .property("item_template", PropertySchema::any())
+            .property("height", PropertySchema::integer())
+            .property("scroll_to", PropertySchema::integer())
+            .property(
+                "end_threshold",
+                PropertySchema::integer().with_default(5i64).min(0),
+            ),
+    );
+
     reg(
         registry,
         "tree",
@@ -762,6 +1009,11 @@ fn register_chart_components(registry: &ComponentRegistry) {
         ConfigSchema::new("line_chart")
             .property("x_field", PropertySchema::string())
             .property("y_field", PropertySchema::string())
+            .property("y2_field", PropertySchema::string())
+            .property("legend", PropertySchema::boolean().with_default(false))
+            .property("x_scale", PropertySchema::string())
+            .property("window", PropertySchema::any())
+            .property("downsample", PropertySchema::string())
             .property("data", PropertySchema::any())
             .property("dot", PropertySchema::boolean())
             .property("linear", PropertySchema::boolean())
@@ -779,12 +1031,99 @@ fn register_chart_components(registry: &ComponentRegistry) {
         ConfigSchema::new("realtime_chart")
             .property("x_field", PropertySchema::string())
             .property("y_fields", PropertySchema::any())
+            .property("y2_fields", PropertySchema::any())
+            .property("legend", PropertySchema::boolean().with_default(false))
+            .property("x_scale", PropertySchema::string())
+            .property("window", PropertySchema::any())
+            .property("downsample", PropertySchema::string())
             .property("data", PropertySchema::any())
             .property("linear", PropertySchema::boolean())
             .property("tick_margin", PropertySchema::integer())
             .require("x_field"),
     );
 
+    reg(
+        registry,
+        "threshold",
+        ComponentCategory::Charts,
+        "Threshold",
+        "A horizontal limit line inside a line, realtime or area chart",
+        ConfigSchema::new("threshold")
+            .property("value", PropertySchema::any())
+            .property("label", PropertySchema::string())
+            .property("color", PropertySchema::string())
+            .property("axis", PropertySchema::string()),
+    );
+
+    reg(
+        registry,
+        "band",
+        ComponentCategory::Charts,
+        "Band",
+        "A shaded value or x range inside a line, realtime or area chart",
+        ConfigSchema::new("band")
+            .property("from", PropertySchema::any())
+            .property("to", PropertySchema::any())
+            .property("label", PropertySchema::string())
+            .property("color", PropertySchema::string())
+            .property("axis", PropertySchema::string()),
+    );
+
+    reg(
+        registry,
+        "marker",
+        ComponentCategory::Charts,
+        "Marker",
+        "A vertical event line inside a line, realtime or area chart",
+        ConfigSchema::new("marker")
+            .property("at", PropertySchema::any())
+            .property("label", PropertySchema::string())
+            .property("color", PropertySchema::string()),
+    );
+
+    reg(
+        registry,
+        "gauge",
+        ComponentCategory::Charts,
+        "Gauge",
+        "A radial or linear gauge showing one value against colored ranges",
+        ConfigSchema::new("gauge")
+            .property("value", PropertySchema::float())
+            .property("min", PropertySchema::float().with_default(0.0))
+            .property("max", PropertySchema::float().with_default(100.0))
+            .property(
+                "variant",
+                PropertySchema::string()
+                    .with_default("radial")
+                    .one_of(enum_vals(&["radial", "linear"])),
+            )
+            .property("ranges", PropertySchema::any())
+            .property("label", PropertySchema::string())
+            .property("unit", PropertySchema::string())
+            .property(
+                "decimals",
+                PropertySchema::integer().with_default(0i64).min(0),
+            )
+            .property("height", PropertySchema::integer().with_default(160i64)),
+    );
+
+    reg(
+        registry,
+        "sparkline",
+        ComponentCategory::Charts,
+        "Sparkline",
+        "A small axis-less line chart for showing a trend inline",
+        ConfigSchema::new("sparkline")
+            .property("data", PropertySchema::any())
+            .property("field", PropertySchema::string())
+            .property("min", PropertySchema::float())
+            .property("max", PropertySchema::float())
+            .property("color", PropertySchema::string())
+            .property("fill", PropertySchema::boolean().with_default(false))
+            .property("dot", PropertySchema::boolean().with_default(true))
+            .property("height", PropertySchema::integer().with_default(32i64)),
+    );
+
     reg(
         registry,
         "bar_chart",
@@ -810,6 +1149,10 @@ fn register_chart_components(registry: &ComponentRegistry) {
         ConfigSchema::new("area_chart")
             .property("x_field", PropertySchema::string())
             .property("y_fields", PropertySchema::array(PropertySchema::string()))
+            .property("legend", PropertySchema::boolean().with_default(false))
+            .property("x_scale", PropertySchema::string())
+            .property("window", PropertySchema::any())
+            .property("downsample", PropertySchema::string())
             .property("data", PropertySchema::any())
             .property("fill_opacity", PropertySchema::float())
             .property("tick_margin", PropertySchema::integer())
@@ -1275,6 +1618,7 @@ mod tests {
         assert!(registry.has_component("button"));
         assert!(registry.has_component("label"));
         assert!(registry.has_component("table"));
+        assert!(registry.has_component("virtual_list"));
         assert!(registry.has_component("accordion"));
         assert!(registry.has_component("alert"));
         assert!(registry.has_component("avatar"));
@@ -1285,10 +1629,25 @@ mod tests {
         assert!(registry.has_component("tag"));
         assert!(registry.has_component("radio"));
         assert!(registry.has_component("slider"));
+        assert!(registry.has_component("date_picker"));
+        assert!(registry.has_component("datetime_picker"));
+        assert!(registry.has_component("color_picker"));
+        assert!(registry.has_component("number_input"));
         assert!(registry.has_component("switch"));
         assert!(registry.has_component("toggle"));
+        assert!(registry.has_component("form"));
+        assert!(registry.has_component("grid"));
+        assert!(registry.has_component("split"));
+        assert!(registry.has_component("drop_target"));
+        assert!(registry.has_component("context_menu"));
         assert!(registry.has_component("line_chart"));
         assert!(registry.has_component("realtime_chart"));
+        assert!(registry.has_component("threshold"));
+        assert!(registry.has_component("band"));
+        assert!(registry.has_component("marker"));
+        assert!(registry.has_component("gauge"));
+        assert!(registry.has_component("sparkline"));
+        assert!(registry.has_component("stat"));
         assert!(registry.has_component("bar_chart"));
         assert!(registry.has_component("area_chart"));
         assert!(registry.has_component("pie_chart"));
@@ -1306,6 +1665,7 @@ mod tests {
         assert!(registry.has_component("funnel_chart"));
         assert!(registry.has_component("textarea"));
         assert!(registry.has_component("code_editor"));
+        assert!(registry.has_component("markdown"));
         assert!(registry.has_component("text_editor"));
         assert!(registry.has_component("sidenav_bar"));
         assert!(registry.has_component("sidenav_bar_item"));
@@ -1,33 +1,57 @@
 //! GPUI application wrapper.
 
+use chrono::NaiveDate;
 use gpui::*;
-use gpui_component::input::{InputEvent, InputState};
+use gpui_component::calendar::{Date, Matcher};
+use gpui_component::color_picker::{ColorPickerEvent, ColorPickerState};
+use gpui_component::date_picker::{DatePickerEvent, DatePickerState};
+use gpui_component::input::{InputEvent, InputState, NumberInputEvent, StepAction};
 use gpui_component::slider::SliderState;
-use gpui_component::table::TableState;
+use gpui_component::table::{TableEvent, TableState};
 use gpui_component::tree::TreeState;
 use gpui_component::v_flex;
 use gpui_component::ActiveTheme;
+use gpui_component::Sizable;
+use gpui_component::WindowExt as _;
 use nemo_config::Value;
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::components::chart_annotations;
+use crate::components::chart_utils::{self, ChartView, InteractiveChart, XLayout};
+use crate::components::color_picker;
+use crate::components::context_menu;
+use crate::components::date_picker;
+use crate::components::export::{self, ExportBounds};
+use crate::components::markdown::{self, Block};
+use crate::components::number_input::{self, NumberSpec};
 use crate::components::state::{ComponentState, ComponentStates};
-use crate::components::table::NemoTableDelegate;
+use crate::components::table::{NemoTableDelegate, SortSpec, DEFAULT_COLUMN_WIDTH};
+use crate::components::time_series;
 use crate::components::tree::values_to_tree_items;
+use crate::components::virtual_list::VirtualListState;
 use gpui_component::input::TabSize;
 
 use crate::components::{
     apply_rounded, apply_shadow, Accordion, AccordionItemData, Alert, AreaChart, Avatar, Badge,
     BarChart, BubbleChart, Button, CandlestickChart, Checkbox, ClusteredBarChart,
-    ClusteredColumnChart, CodeEditor, Collapsible, ColumnChart, DropdownButton, FunnelChart,
-    HeatmapChart, Icon, Image, Label, LineChart, List, MenuItem, Modal, Notification, OptionData,
-    Panel, PieChart, Progress, PyramidChart, RadarChart, Radio, RealtimeChart, ScatterChart,
-    Select, SidenavBar, Slider, Spinner, Stack, StackedBarChart, StackedColumnChart, Svg, Switch,
-    TabItemData, Table, Tabs, Tag, Text, TextEditor, Textarea, Toggle, Tooltip, Tree,
+    ClusteredColumnChart, CodeEditor, Collapsible, ColorPicker, ColumnChart, DatePicker,
+    DropdownButton, FunnelChart, Gauge, HeatmapChart, Icon, Image, Label, LineChart, List,
+    Markdown, MenuItem, Modal, Notification, NumberInput, OptionData, Panel, PieChart, Progress,
+    PyramidChart, RadarChart, Radio, RealtimeChart, ScatterChart, Select, SidenavBar, Slider,
+    Sparkline, Spinner, Stack, StackedBarChart, StackedColumnChart, Stat, Svg, Switch, TabItemData,
+    Table, Tabs, Tag, Text, TextEditor, Textarea, Toggle, Tooltip, Tree, VirtualList,
 };
-use crate::containers::{AppShell, NavLink, Router};
-use crate::runtime::NemoRuntime;
+use crate::containers::drop_target::DraggedItem;
+use crate::containers::form::{self, FieldSpec};
+use crate::containers::grid::CellRequest;
+use crate::containers::split;
+use crate::containers::{AppShell, Draggable, DropTarget, Form, Grid, NavLink, Router, Split};
+use crate::runtime::{ExportIntent, NemoRuntime};
 use crate::theme::tokens::{FontSize, Space, TokenStyled};
+use crate::workspace::keymap;
+use nemo_layout::responsive;
+use nemo_layout::style_state::{self, StyleState};
 use nemo_layout::BuiltComponent;
 
 /// The main Nemo GPUI application.
@@ -42,11 +66,22 @@ pub struct App {
     /// `value` property differs from this cached copy do we push it back into
     /// the `InputState`, so we never fight the cursor while the user types.
     input_values: HashMap<String, String>,
+    /// Inline validation error per form field, keyed by the field's component
+    /// id; set by the last submit of its `<form>`.
+    form_errors: HashMap<String, String>,
+    /// Where each table and chart was last painted, for PNG export crops.
+    export_bounds: ExportBounds,
+    /// PNG exports drained from the runtime, captured after the next frame.
+    pending_exports: Vec<ExportIntent>,
+    /// Ids of components named by `focus:` conditions in the app's `<keymap>`.
+    key_contexts: BTreeSet<String>,
+    /// The key context the app's `<keymap>` bindings are scoped to.
+    keymap_scope: String,
 }
 
 impl App {
     /// Creates a new Nemo application.
-    pub fn new(runtime: Arc<NemoRuntime>, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(runtime: Arc<NemoRuntime>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         // Wait for data_notify signals and apply updates when data arrives.
         let poll_runtime = Arc::clone(&runtime);
         let data_notify = Arc::clone(&runtime.data_notify);
@@ -59,22 +94,39 @@ impl App {
             // seeded to its default path, before the data-update pass picks up
             // the route projection it flags (issue #81).
             let initial_enters = poll_runtime.fire_pending_initial_enters();
+            // A script's `set_locale()` re-renders translated labels and flags
+            // `t('key')` bindings for the data-update pass below.
+            let locale_changed = poll_runtime.apply_pending_locale();
             let data_updated = poll_runtime.apply_pending_data_updates();
             // A runtime `set_roundness()` call needs the gpui `Theme` (an `App`),
             // which only exists inside `this.update`; apply it there.
             let roundness = poll_runtime.take_pending_roundness();
-            if navigated || initial_enters || data_updated || roundness.is_some() {
-                let _ = this.update(cx, |_app: &mut App, cx: &mut Context<App>| {
+            // PNG exports need the window, which only `render` has.
+            let exports = poll_runtime.take_pending_exports();
+            if navigated
+                || initial_enters
+                || locale_changed
+                || data_updated
+                || roundness.is_some()
+                || !exports.is_empty()
+            {
+                let _ = this.update(cx, |app: &mut App, cx: &mut Context<App>| {
                     if let Some(value) = roundness {
                         crate::theme::apply_roundness(&value, cx);
                     }
+                    app.pending_exports.extend(exports);
                     cx.notify();
                 });
             }
         });
         _data_task.detach();
 
-        let _subscriptions = vec![];
+        // Responsive rules (`n:show-when`, `@media`) read the window size while
+        // rendering, so re-render on resize.
+        let _subscriptions = vec![cx.observe_window_bounds(window, |_, _, cx| cx.notify())];
+        let key_contexts = keymap::focus_contexts(&keymap::keymap_entries(
+            runtime.get_config("keymap").as_ref(),
+        ));
 
         // Run the one-shot on-load handler (if configured via
         // `<script on-load="…" />`) now that scripts are loaded and the layout
@@ -90,6 +142,11 @@ impl App {
             component_states: ComponentStates::new(),
             _subscriptions,
             input_values: HashMap::new(),
+            form_errors: HashMap::new(),
+            export_bounds: ExportBounds::default(),
+            pending_exports: Vec::new(),
+            key_contexts,
+            keymap_scope: keymap::AppKeymap::current_context(cx),
         }
     }
 
@@ -197,41 +254,193 @@ impl App {
     }
 
     /// Gets or creates a TableState entity for the given component.
+    ///
+    /// On first creation this subscribes to row clicks (writing the selection
+    /// to the `selected` property and firing `on-select` with the row data as
+    /// JSON) and, for `filterable="true"`, builds one filter input per
+    /// filterable column. Every render it pushes changed `data`, `sort` and
+    /// `filters` properties into the delegate, writing back a selection the
+    /// new data changed. Returns the state and the filter inputs.
     fn get_or_create_table_state(
         &mut self,
         component: &BuiltComponent,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Entity<TableState<NemoTableDelegate>> {
+    ) -> (
+        Entity<TableState<NemoTableDelegate>>,
+        Vec<(f32, Option<Entity<InputState>>)>,
+    ) {
         let current_data = match component.properties.get("data") {
-            Some(Value::Array(arr)) => arr.clone(),
-            _ => Vec::new(),
+            Some(Value::Array(arr)) => arr.as_slice(),
+            _ => &[],
         };
-
-        if let Some(ComponentState::Table { state, last_data }) =
-            self.component_states.get_mut(&component.id)
+        let current_sort = component.properties.get("sort");
+        let current_filters = component.properties.get("filters");
+
+        if let Some(ComponentState::Table {
+            state,
+            last_data,
+            last_sort,
+            last_filters,
+            filter_inputs,
+        }) = self.component_states.get_mut(&component.id)
         {
-            if *last_data != current_data {
-                let new_data = current_data.clone();
-                state.update(cx, |s, cx| {
-                    s.delegate_mut().set_rows(new_data);
+            // Compare before cloning: bound datasets can be large.
+            let data_changed = last_data.as_slice() != current_data;
+            let sort_changed = last_sort.as_ref() != current_sort;
+            let filters_changed = last_filters.as_ref() != current_filters;
+            if data_changed || sort_changed || filters_changed {
+                let new_data = data_changed.then(|| current_data.to_vec());
+                let new_sort = current_sort.and_then(SortSpec::parse);
+                let selected = state.update(cx, |s, cx| {
+                    let delegate = s.delegate_mut();
+                    let selected = new_data.and_then(|rows| delegate.set_rows(rows));
+                    if sort_changed {
+                        delegate.set_sort(new_sort);
+                    }
+                    if filters_changed && delegate.set_filters(current_filters) {
+                        // Show bound filters in the filter row, leaving an
+                        // input alone when it already reads the same (the
+                        // change came from typing in it).
+                        let texts: Vec<String> = delegate
+                            .column_specs()
+                            .iter()
+                            .map(|spec| delegate.filter_text(&spec.key).to_string())
+                            .collect();
+                        for ((_, input), text) in filter_inputs.iter().zip(texts) {
+                            let Some(input) = input else {
+                                continue;
+                            };
+                            if input.read(cx).value().trim().to_lowercase() != text {
+                                input.update(cx, |s, cx| s.set_value(text, window, cx));
+                            }
+                        }
+                    }
                     s.refresh(cx);
+                    selected
                 });
-                *last_data = current_data;
+                if let Some(selected) = selected {
+                    self.runtime
+                        .write_back_property(&component.id, "selected", selected);
+                }
+                if data_changed {
+                    *last_data = current_data.to_vec();
+                }
+                *last_sort = current_sort.cloned();
+                *last_filters = current_filters.cloned();
             }
-            return state.clone();
+            return (state.clone(), filter_inputs.clone());
         }
 
-        let delegate = NemoTableDelegate::from_properties(&component.properties);
+        let delegate = NemoTableDelegate::from_properties(&component.properties).with_runtime(
+            &component.id,
+            Arc::clone(&self.runtime),
+            component.handlers.get("sort").cloned(),
+        );
+        let filterable_columns: Vec<(String, f32)> = if component
+            .properties
+            .get("filterable")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            delegate
+                .column_specs()
+                .iter()
+                .map(|spec| {
+                    let width = spec.width.unwrap_or(DEFAULT_COLUMN_WIDTH);
+                    let key = spec
+                        .filterable
+                        .then(|| spec.key.clone())
+                        .unwrap_or_default();
+                    (key, width)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
         let state = cx.new(|cx| TableState::new(delegate, window, cx));
+
+        // Row clicks drive selection; the delegate maps the displayed row
+        // back to its source row.
+        let select_handler = component.handlers.get("select").cloned();
+        let sub_id = component.id.clone();
+        let runtime = Arc::clone(&self.runtime);
+        let subscription = cx.subscribe_in(
+            &state,
+            window,
+            move |_this: &mut App, table_state, event: &TableEvent, _window, cx| {
+                let TableEvent::SelectRow(row_ix) = event else {
+                    return;
+                };
+                let Some(selected) =
+                    table_state.update(cx, |s, _cx| s.delegate_mut().select_row(*row_ix))
+                else {
+                    return;
+                };
+                runtime.write_back_property(&sub_id, "selected", selected.clone());
+                if let Some(handler) = &select_handler {
+                    let payload = serde_json::to_string(&selected).unwrap_or_default();
+                    runtime.call_handler(handler, &sub_id, &payload);
+                }
+                cx.notify();
+            },
+        );
+        self._subscriptions.push(subscription);
+
+        // One slot per column keeps the filter row aligned; columns that opt
+        // out of filtering get an empty slot.
+        let mut filter_inputs = Vec::new();
+        for (key, width) in filterable_columns {
+            if key.is_empty() {
+                filter_inputs.push((width, None));
+                continue;
+            }
+            let initial = component
+                .properties
+                .get("filters")
+                .and_then(|f| f.get(&key))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let input = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder("Filter")
+                    .default_value(initial)
+            });
+            let table_state = state.clone();
+            let sub_id = component.id.clone();
+            let runtime = Arc::clone(&self.runtime);
+            let subscription = cx.subscribe_in(
+                &input,
+                window,
+                move |_this: &mut App, input_state, event: &InputEvent, _window, cx| {
+                    if !matches!(event, InputEvent::Change) {
+                        return;
+                    }
+                    let text = input_state.read(cx).value().to_string();
+                    let filters = table_state.update(cx, |s, cx| {
+                        s.delegate_mut().set_filter(&key, &text);
+                        s.refresh(cx);
+                        s.delegate().filters_value()
+                    });
+                    runtime.write_back_property(&sub_id, "filters", filters);
+                },
+            );
+            self._subscriptions.push(subscription);
+            filter_inputs.push((width, Some(input)));
+        }
+
         self.component_states.insert(
             component.id.clone(),
             ComponentState::Table {
                 state: state.clone(),
-                last_data: current_data,
+                last_data: current_data.to_vec(),
+                last_sort: current_sort.cloned(),
+                last_filters: current_filters.cloned(),
+                filter_inputs: filter_inputs.clone(),
             },
         );
-        state
+        (state, filter_inputs)
     }
 
     /// Gets or creates a TreeState entity for the given component.
@@ -300,6 +509,553 @@ impl App {
         state
     }
 
+    /// Gets or creates the zoom/pointer state of an interactive chart.
+    fn get_or_create_chart_view(&mut self, component: &BuiltComponent) -> Arc<Mutex<ChartView>> {
+        if let Some(ComponentState::Chart(view)) = self.component_states.get(&component.id) {
+            return Arc::clone(view);
+        }
+        let view = Arc::new(Mutex::new(ChartView::default()));
+        self.component_states.insert(
+            component.id.clone(),
+            ComponentState::Chart(Arc::clone(&view)),
+        );
+        view
+    }
+
+    /// Renders a cartesian chart from the items inside its zoom window and
+    /// wraps it in the shared interaction layer (`chart_utils::InteractiveChart`).
+    fn render_interactive_chart(
+        &mut self,
+        component: &BuiltComponent,
+        components: &HashMap<String, BuiltComponent>,
+        entity_id: EntityId,
+    ) -> AnyElement {
+        let layout = XLayout::for_component(component).unwrap_or(XLayout::Points);
+        let view = self.get_or_create_chart_view(component);
+        let data = Arc::new(chart_utils::extract_data_array(component));
+        let x_field = component
+            .properties
+            .get("x_field")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let values = chart_utils::x_values(layout, &data, x_field);
+        // A time chart only plots items with a time, and only those within
+        // its `window` of the newest; zooming happens inside that.
+        let window = time_series::window_ms(&component.properties)
+            .and_then(|window| chart_utils::extent(&values).map(|(_, newest)| newest - window));
+        let in_range = |i: &usize| {
+            layout != XLayout::Time
+                || (values[*i].is_finite() && window.is_none_or(|lo| values[*i] >= lo))
+        };
+        let visible = {
+            let mut view = view.lock().unwrap();
+            let mut visible = chart_utils::visible_indices(&values, view.zoom);
+            visible.retain(in_range);
+            if visible.is_empty() && view.zoom.is_some() {
+                // The data moved out from under the zoom window.
+                view.zoom = None;
+                visible = chart_utils::visible_indices(&values, None);
+                visible.retain(in_range);
+            }
+            visible
+        };
+
+        let mut zoomed = component.clone();
+        if visible.len() != data.len() {
+            zoomed.properties.insert(
+                "data".to_string(),
+                Value::Array(visible.iter().map(|&i| data[i].clone()).collect()),
+            );
+        }
+        let chart = match component.component_type.as_str() {
+            "line_chart" => LineChart::new(zoomed)
+                .annotations(chart_annotations::collect(component, components))
+                .view(Arc::clone(&view))
+                .entity_id(entity_id)
+                .into_any_element(),
+            "realtime_chart" => RealtimeChart::new(zoomed)
+                .annotations(chart_annotations::collect(component, components))
+                .view(Arc::clone(&view))
+                .entity_id(entity_id)
+                .into_any_element(),
+            "bar_chart" => BarChart::new(zoomed).into_any_element(),
+            "area_chart" => AreaChart::new(zoomed)
+                .annotations(chart_annotations::collect(component, components))
+                .view(Arc::clone(&view))
+                .entity_id(entity_id)
+                .into_any_element(),
+            "candlestick_chart" => CandlestickChart::new(zoomed).into_any_element(),
+            "column_chart" => ColumnChart::new(zoomed).into_any_element(),
+            "stacked_column_chart" => StackedColumnChart::new(zoomed).into_any_element(),
+            "clustered_column_chart" => ClusteredColumnChart::new(zoomed).into_any_element(),
+            "scatter_chart" => ScatterChart::new(zoomed).into_any_element(),
+            _ => BubbleChart::new(zoomed).into_any_element(),
+        };
+
+        InteractiveChart::new(
+            component.clone(),
+            chart,
+            layout,
+            data,
+            values,
+            visible,
+            view,
+        )
+        .runtime(Arc::clone(&self.runtime))
+        .entity_id(entity_id)
+        .into_any_element()
+    }
+
+    /// Captures queued PNG exports once the frame being rendered has been
+    /// drawn, cropping the window image to each chart's painted bounds.
+    fn capture_pending_exports(&mut self, window: &mut Window) {
+        for intent in std::mem::take(&mut self.pending_exports) {
+            let bounds = Arc::clone(&self.export_bounds);
+            window.on_next_frame(move |window, cx| {
+                let painted = bounds.lock().unwrap().get(&intent.component_id).copied();
+                let result = match painted {
+                    Some(painted) => export::capture_png(window, painted, &intent.path),
+                    None => Err(anyhow::anyhow!(
+                        "'{}' is not on screen",
+                        intent.component_id
+                    )),
+                };
+                match result {
+                    Ok(()) => {
+                        tracing::info!(
+                            "Exported {} to {}",
+                            intent.component_id,
+                            intent.path.display()
+                        );
+                        window.push_notification(
+                            format!("Exported to {}", intent.path.display()),
+                            cx,
+                        );
+                    }
+                    Err(e) => export::notify_failure(&intent.component_id, &e, window, cx),
+                }
+            });
+        }
+    }
+
+    /// Gets or creates the widgets of a `date_picker` or `datetime_picker`: the
+    /// calendar and, for a `datetime_picker`, its time field.
+    ///
+    /// On first creation this subscribes to calendar changes and time-field
+    /// commits (blur or Enter), which write `value` back through any `two_way`
+    /// binding and fire `on-change`. Every render it refreshes the `min`/`max`
+    /// bounds and pushes a `value` that a script or binding changed into the
+    /// widgets.
+    fn get_or_create_date_picker_state(
+        &mut self,
+        component: &BuiltComponent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> (Entity<DatePickerState>, Option<Entity<InputState>>) {
+        let id = component.id.clone();
+        let value = component
+            .properties
+            .get("value")
+            .cloned()
+            .unwrap_or(Value::Null);
+        let range = date_picker::is_range(component);
+        let limits = date_picker::bounds(component);
+
+        let (state, time) = match self.component_states.get_mut(&id) {
+            Some(ComponentState::DatePicker {
+                state,
+                time,
+                bounds,
+                last_value,
+            }) => {
+                // The calendar's matcher and the write-back read the bounds
+                // through the shared cell, so a bound `min`/`max` applies
+                // without recreating the widgets.
+                *bounds.lock().unwrap() = limits;
+                let widgets = (state.clone(), time.clone());
+                if *last_value == value {
+                    return widgets;
+                }
+                *last_value = value.clone();
+                widgets
+            }
+            _ => {
+                let bounds = Arc::new(Mutex::new(limits));
+                let matcher_bounds = Arc::clone(&bounds);
+                let state = cx.new(|cx| {
+                    let state = if range {
+                        DatePickerState::range(window, cx)
+                    } else {
+                        DatePickerState::new(window, cx)
+                    };
+                    state
+                        .date_format(date_picker::DATE_FORMAT)
+                        .disabled_matcher(Matcher::custom(move |date: &NaiveDate| {
+                            let (min, max) = *matcher_bounds.lock().unwrap();
+                            min.is_some_and(|min| *date < min) || max.is_some_and(|max| *date > max)
+                        }))
+                });
+                let time = (component.component_type == "datetime_picker")
+                    .then(|| cx.new(|cx| InputState::new(window, cx).placeholder("HH:MM")));
+
+                let handler = component.handlers.get("change").cloned();
+                let sub_id = id.clone();
+                self._subscriptions.push(cx.subscribe_in(
+                    &state,
+                    window,
+                    move |this: &mut App, _state, event: &DatePickerEvent, _window, cx| {
+                        let DatePickerEvent::Change(date) = event;
+                        let value = this.date_picker_value(&sub_id, date, cx);
+                        this.commit_picker_value(&sub_id, value, handler.as_deref(), cx);
+                    },
+                ));
+                if let Some(time) = &time {
+                    let handler = component.handlers.get("change").cloned();
+                    let sub_id = id.clone();
+                    let calendar = state.clone();
+                    self._subscriptions.push(cx.subscribe_in(
+                        time,
+                        window,
+                        move |this: &mut App, _time, event: &InputEvent, _window, cx| {
+                            if matches!(event, InputEvent::Blur | InputEvent::PressEnter { .. }) {
+                                let date = calendar.read(cx).date();
+                                let value = this.date_picker_value(&sub_id, &date, cx);
+                                this.commit_picker_value(&sub_id, value, handler.as_deref(), cx);
+                            }
+                        },
+                    ));
+                }
+                self.component_states.insert(
+                    id.clone(),
+                    ComponentState::DatePicker {
+                        state: state.clone(),
+                        time: time.clone(),
+                        bounds,
+                        last_value: value.clone(),
+                    },
+                );
+                (state, time)
+            }
+        };
+
+        // Push the property into the widgets. The time goes first: setting
+        // the date can report a change, whose write-back reads the time field.
+        if let Some(time) = &time {
+            let text = value
+                .as_str()
+                .and_then(date_picker::parse_datetime)
+                .map(|dt| dt.format(date_picker::TIME_FORMAT).to_string())
+                .unwrap_or_default();
+            time.update(cx, |s, cx| s.set_value(text, window, cx));
+        }
+        let date = if range {
+            let (start, end) = date_picker::range_bounds(&value);
+            Date::Range(start, end)
+        } else {
+            Date::Single(value.as_str().and_then(date_picker::parse_date))
+        };
+        state.update(cx, |s, cx| s.set_date(date, window, cx));
+        (state, time)
+    }
+
+    /// The `value` of a date picker for the picked `date`, combined with the
+    /// time field and current bounds of a `datetime_picker`.
+    fn date_picker_value(&self, id: &str, date: &Date, cx: &gpui::App) -> Value {
+        match date {
+            Date::Range(start, end) => date_picker::range_value(*start, *end),
+            Date::Single(date) => match self.component_states.get(id) {
+                Some(ComponentState::DatePicker {
+                    time: Some(time),
+                    bounds,
+                    ..
+                }) => {
+                    let (min, max) = *bounds.lock().unwrap();
+                    date_picker::datetime_value(*date, &time.read(cx).value(), min, max)
+                }
+                _ => date_picker::date_value(*date),
+            },
+        }
+    }
+
+    /// Gets or creates a `color_picker`'s state, subscribing once to write
+    /// picked colors back to `value` (in the `format` it asks for) and fire
+    /// `on-change`, and pushing script or binding changes of `value` in.
+    fn get_or_create_color_picker_state(
+        &mut self,
+        component: &BuiltComponent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ColorPickerState> {
+        let id = component.id.clone();
+        let value = component
+            .properties
+            .get("value")
+            .cloned()
+            .unwrap_or(Value::Null);
+        let color = value
+            .as_str()
+            .and_then(|v| color_picker::parse_color(v, cx));
+
+        if let Some(ComponentState::ColorPicker { state, last_value }) =
+            self.component_states.get_mut(&id)
+        {
+            let state = state.clone();
+            if *last_value != value {
+                *last_value = value;
+                if let Some(color) = color {
+                    state.update(cx, |s, cx| s.set_value(color, window, cx));
+                }
+            }
+            return state;
+        }
+
+        let state = cx.new(|cx| {
+            let state = ColorPickerState::new(window, cx);
+            match color {
+                Some(color) => state.default_value(color),
+                None => state,
+            }
+        });
+        let format = color_picker::ColorFormat::parse(
+            component.properties.get("format").and_then(|v| v.as_str()),
+        );
+        let handler = component.handlers.get("change").cloned();
+        let sub_id = id.clone();
+        self._subscriptions.push(cx.subscribe_in(
+            &state,
+            window,
+            move |this: &mut App, _state, event: &ColorPickerEvent, _window, cx| {
+                let ColorPickerEvent::Change(color) = event;
+                let value = color
+                    .map(|c| Value::String(color_picker::format_color(c, format)))
+                    .unwrap_or(Value::Null);
+                this.commit_picker_value(&sub_id, value, handler.as_deref(), cx);
+            },
+        ));
+        self.component_states.insert(
+            id,
+            ComponentState::ColorPicker {
+                state: state.clone(),
+                last_value: value,
+            },
+        );
+        state
+    }
+
+    /// Gets or creates a `number_input`'s text field.
+    ///
+    /// On first creation this subscribes to the stepper buttons and arrow keys,
+    /// and to blur/Enter, which parse the text, clamp and round it to the
+    /// input's bounds and precision, write `value` back and fire `on-change`.
+    /// Text that doesn't parse is reset to the last value. Every render it
+    /// pushes a `value` that a script or binding changed into the field.
+    fn get_or_create_number_input_state(
+        &mut self,
+        component: &BuiltComponent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<InputState> {
+        let id = component.id.clone();
+        let spec = NumberSpec::from_component(component);
+        let value = component
+            .properties
+            .get("value")
+            .cloned()
+            .unwrap_or(Value::Null);
+        let text = value.as_f64().map(|n| spec.format(n)).unwrap_or_default();
+
+        if let Some(ComponentState::NumberInput { state, last_value }) =
+            self.component_states.get_mut(&id)
+        {
+            let state = state.clone();
+            if *last_value != value {
+                *last_value = value;
+                state.update(cx, |s, cx| s.set_value(text, window, cx));
+            }
+            return state;
+        }
+
+        let placeholder = component
+            .properties
+            .get("placeholder")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(placeholder)
+                .default_value(text)
+        });
+        let unit = component
+            .properties
+            .get("unit")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let handler = component.handlers.get("change").cloned();
+        let sub_id = id.clone();
+        self._subscriptions.push(cx.subscribe_in(
+            &state,
+            window,
+            move |this: &mut App, input, event: &NumberInputEvent, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                let current = number_input::parse_number(&input.read(cx).value(), unit.as_deref());
+                let n = spec.step_from(current, matches!(action, StepAction::Increment));
+                input.update(cx, |s, cx| s.set_value(spec.format(n), window, cx));
+                this.commit_picker_value(&sub_id, spec.value_of(n), handler.as_deref(), cx);
+            },
+        ));
+        let handler = component.handlers.get("change").cloned();
+        let sub_id = id.clone();
+        let unit = component
+            .properties
+            .get("unit")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        self._subscriptions.push(cx.subscribe_in(
+            &state,
+            window,
+            move |this: &mut App, input, event: &InputEvent, window, cx| {
+                if !matches!(event, InputEvent::Blur | InputEvent::PressEnter { .. }) {
+                    return;
+                }
+                let typed = input.read(cx).value().to_string();
+                let value = match number_input::parse_number(&typed, unit.as_deref()) {
+                    Some(n) => spec.value_of(spec.normalize(n)),
+                    None if typed.trim().is_empty() => Value::Null,
+                    // Unparseable: fall back to the last good value.
+                    None => match this.component_states.get(&sub_id) {
+                        Some(ComponentState::NumberInput { last_value, .. }) => last_value.clone(),
+                        _ => Value::Null,
+                    },
+                };
+                let text = value.as_f64().map(|n| spec.format(n)).unwrap_or_default();
+                if text != typed {
+                    input.update(cx, |s, cx| s.set_value(text, window, cx));
+                }
+                this.commit_picker_value(&sub_id, value, handler.as_deref(), cx);
+            },
+        ));
+        self.component_states.insert(
+            id,
+            ComponentState::NumberInput {
+                state: state.clone(),
+                last_value: value,
+            },
+        );
+        state
+    }
+
+    /// Records a value picked in a date, color or number widget and, when it
+    /// changed, writes it back to the `value` property (and through any
+    /// `two_way` binding) and fires `on-change` with its text.
+    fn commit_picker_value(
+        &mut self,
+        id: &str,
+        value: Value,
+        handler: Option<&str>,
+        cx: &mut Context<Self>,
+    ) {
+        match self.component_states.get_mut(id) {
+            Some(
+                ComponentState::DatePicker { last_value, .. }
+                | ComponentState::ColorPicker { last_value, .. }
+                | ComponentState::NumberInput { last_value, .. },
+            ) if *last_value != value => *last_value = value.clone(),
+            _ => return,
+        }
+        self.runtime.write_back_property(id, "value", value.clone());
+        if let Some(handler) = handler {
+            self.runtime.call_handler(handler, id, &value_text(&value));
+        }
+        cx.notify();
+    }
+
+    /// Gets or creates the state of a `<virtual-list>` and syncs it to the
+    /// component's current items, returning the gpui list state to render.
+    fn get_or_create_virtual_list_state(
+        &mut self,
+        component: &BuiltComponent,
+        window: &mut Window,
+    ) -> ListState {
+        if !matches!(
+            self.component_states.get(&component.id),
+            Some(ComponentState::VirtualList(_))
+        ) {
+            let state = VirtualListState::new(component, Arc::clone(&self.runtime));
+            self.component_states.insert(
+                component.id.clone(),
+                ComponentState::VirtualList(Box::new(state)),
+            );
+        }
+        let Some(ComponentState::VirtualList(state)) = self.component_states.get_mut(&component.id)
+        else {
+            unreachable!("virtual list state was just inserted");
+        };
+        state.sync(component, window);
+        state.list.clone()
+    }
+
+    /// Renders row `ix` of a `<virtual-list>`, called by gpui only for rows in
+    /// (or near) view. A row first needed here is inserted into the layout
+    /// after this frame's snapshot was taken, so its components are read
+    /// afresh.
+    fn render_virtual_row(
+        &mut self,
+        list_id: &str,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let row_id = match self.component_states.get_mut(list_id) {
+            Some(ComponentState::VirtualList(state)) => state.row(ix),
+            _ => None,
+        };
+        let components = row_id
+            .as_deref()
+            .map(|id| self.snapshot_subtree(id))
+            .unwrap_or_default();
+        let Some(root) = row_id.and_then(|id| components.get(&id).cloned()) else {
+            return div().into_any_element();
+        };
+        let entity_id = cx.entity_id();
+        div()
+            .w_full()
+            .child(self.render_component(&root, &components, entity_id, window, cx))
+            .into_any_element()
+    }
+
+    /// Gets or creates the shared pane fractions for a `<split>` with
+    /// `pane_count` panes, re-reading the `sizes` property whenever it changes
+    /// (a bound value restored from state, or a script) or the pane count does.
+    fn get_or_create_split_state(
+        &mut self,
+        component: &BuiltComponent,
+        pane_count: usize,
+    ) -> Arc<Mutex<Vec<f32>>> {
+        let current = component.properties.get("sizes");
+        if let Some(ComponentState::Split { sizes, last_sizes }) =
+            self.component_states.get_mut(&component.id)
+        {
+            let stale = last_sizes.as_ref() != current || sizes.lock().unwrap().len() != pane_count;
+            if stale {
+                *sizes.lock().unwrap() = split::parse_sizes(current, pane_count);
+                *last_sizes = current.cloned();
+            }
+            return Arc::clone(sizes);
+        }
+
+        let sizes = Arc::new(Mutex::new(split::parse_sizes(current, pane_count)));
+        self.component_states.insert(
+            component.id.clone(),
+            ComponentState::Split {
+                sizes: Arc::clone(&sizes),
+                last_sizes: current.cloned(),
+            },
+        );
+        sizes
+    }
+
     /// Gets or creates an InputState entity configured for a Textarea component.
     fn get_or_create_textarea_state(
         &mut self,
@@ -347,6 +1103,56 @@ impl App {
         state
     }
 
+    /// Gets the parsed document and code-block editors for a markdown
+    /// component, re-parsing when its `content` changes.
+    fn get_or_create_markdown_state(
+        &mut self,
+        component: &BuiltComponent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> (Arc<Vec<Block>>, Vec<Entity<InputState>>) {
+        let content = component
+            .properties
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if let Some(ComponentState::Markdown {
+            content: last,
+            blocks,
+            code_states,
+        }) = self.component_states.get(&component.id)
+        {
+            if last == content {
+                return (Arc::clone(blocks), code_states.clone());
+            }
+        }
+
+        let blocks = Arc::new(markdown::parse(content));
+        let code_states: Vec<Entity<InputState>> = markdown::code_blocks(&blocks)
+            .into_iter()
+            .map(|(language, code)| {
+                let language = markdown::highlight_language(language.as_deref()).to_string();
+                cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .code_editor(language)
+                        .line_number(false)
+                        .searchable(false)
+                        .default_value(code)
+                })
+            })
+            .collect();
+
+        self.component_states.insert(
+            component.id.clone(),
+            ComponentState::Markdown {
+                content: content.to_string(),
+                blocks: Arc::clone(&blocks),
+                code_states: code_states.clone(),
+            },
+        );
+        (blocks, code_states)
+    }
+
     /// Gets or creates an InputState entity configured for a CodeEditor component.
     fn get_or_create_code_editor_state(
         &mut self,
@@ -454,25 +1260,149 @@ impl App {
         state
     }
 
+    /// Snapshots the root id and every built component from the layout manager.
+    fn snapshot_components(&self) -> (Option<String>, HashMap<String, BuiltComponent>) {
+        let layout_manager = self
+            .runtime
+            .layout_manager
+            .read()
+            .expect("layout_manager lock poisoned");
+        let root_id = layout_manager.root_id();
+        let components = layout_manager
+            .component_ids()
+            .into_iter()
+            .filter_map(|id| layout_manager.get_component(&id).cloned().map(|c| (id, c)))
+            .collect();
+        (root_id, components)
+    }
+
+    /// Snapshots component `id` and its descendants from the layout manager.
+    fn snapshot_subtree(&self, id: &str) -> HashMap<String, BuiltComponent> {
+        let layout_manager = self
+            .runtime
+            .layout_manager
+            .read()
+            .expect("layout_manager lock poisoned");
+        let mut components = HashMap::new();
+        let mut queue = vec![id.to_string()];
+        while let Some(id) = queue.pop() {
+            if let Some(component) = layout_manager.get_component(&id) {
+                queue.extend(component.children.iter().cloned());
+                components.insert(id, component.clone());
+            }
+        }
+        components
+    }
+
+    /// Reads the current value of a form field from its widget state, falling
+    /// back to its `value`/`checked` property before the widget first renders.
+    fn form_field_value(
+        &self,
+        field: &BuiltComponent,
+        components: &HashMap<String, BuiltComponent>,
+        cx: &gpui::App,
+    ) -> Value {
+        let state = self.component_states.get(&field.id);
+        match (field.component_type.as_str(), state) {
+            ("input" | "textarea", Some(ComponentState::Input(input))) => {
+                Value::String(input.read(cx).value().to_string())
+            }
+            ("select", Some(ComponentState::SelectedValue(selected))) => {
+                Value::String(selected.lock().unwrap().clone())
+            }
+            ("radio", Some(ComponentState::SelectedIndex(selected))) => {
+                let options = collect_options(field, components);
+                selected
+                    .lock()
+                    .unwrap()
+                    .and_then(|ix| options.get(ix))
+                    .map(|o| Value::String(o.value.clone()))
+                    .unwrap_or(Value::Null)
+            }
+            ("slider", Some(ComponentState::Slider(slider))) => {
+                Value::Float(slider.read(cx).value().start() as f64)
+            }
+            ("switch", Some(ComponentState::BoolState(checked))) => {
+                Value::Bool(*checked.lock().unwrap())
+            }
+            ("checkbox" | "switch", _) => Value::Bool(
+                field
+                    .properties
+                    .get("checked")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            ),
+            _ => field
+                .properties
+                .get("value")
+                .cloned()
+                .unwrap_or(Value::Null),
+        }
+    }
+
+    /// Validates a `<form>`'s fields and, when they all pass, writes the
+    /// values object to the form's `value` property (and through its
+    /// `two_way` bindings) and fires `on-submit` with it as JSON. Errors are
+    /// shown inline and written to the form's `errors` property.
+    fn submit_form(&mut self, form_id: &str, cx: &mut Context<Self>) {
+        let (_, components) = self.snapshot_components();
+        let Some(form) = components.get(form_id) else {
+            return;
+        };
+
+        let fields = form::collect_fields(form, &components);
+        for field in &fields {
+            self.form_errors.remove(&field.id);
+        }
+        let specs: Vec<(FieldSpec, Value)> = fields
+            .iter()
+            .filter_map(|field| {
+                let spec = FieldSpec::from_component(field)?;
+                Some((spec, self.form_field_value(field, &components, cx)))
+            })
+            .collect();
+        let ids: HashMap<String, String> = specs
+            .iter()
+            .map(|(spec, _)| (spec.name.clone(), spec.component_id.clone()))
+            .collect();
+
+        let runtime = Arc::clone(&self.runtime);
+        let result = form::validate_form(specs, |validator, value, values| {
+            runtime.call_validator(validator, value, values)
+        });
+
+        let errors = result
+            .errors
+            .iter()
+            .map(|(name, message)| (name.clone(), Value::String(message.clone())))
+            .collect();
+        self.runtime
+            .write_back_property(form_id, "errors", Value::Object(errors));
+
+        if result.is_valid() {
+            let payload = Value::Object(result.values);
+            self.runtime
+                .write_back_property(form_id, "value", payload.clone());
+            if let Some(handler) = form.handlers.get("submit") {
+                let data = serde_json::to_string(&payload).unwrap_or_default();
+                self.runtime.call_handler(handler, form_id, &data);
+            }
+        } else {
+            for (name, message) in result.errors {
+                if let Some(id) = ids.get(&name) {
+                    self.form_errors.insert(id.clone(), message);
+                }
+            }
+        }
+        cx.notify();
+    }
+
     /// Renders the layout from the layout manager.
     fn render_layout(&mut self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
         let entity_id = cx.entity_id();
 
         // Snapshot all components once to avoid re-acquiring the lock per component.
-        let (root_id, components) = {
-            let layout_manager = self
-                .runtime
-                .layout_manager
-                .read()
-                .expect("layout_manager lock poisoned");
-            let root_id = layout_manager.root_id();
-            let components: HashMap<String, BuiltComponent> = layout_manager
-                .component_ids()
-                .into_iter()
-                .filter_map(|id| layout_manager.get_component(&id).cloned().map(|c| (id, c)))
-                .collect();
-            (root_id, components)
-        };
+        let (root_id, components) = self.snapshot_components();
 
         // Render the root component from the snapshot. Stacks are content-sized
         // by default, but the layout root must fill the viewport — inject a
@@ -520,11 +1450,33 @@ impl App {
         component
             .children
             .iter()
-            .filter_map(|child_id| components.get(child_id))
-            .map(|child| self.render_component(child, components, entity_id, window, cx))
+            .enumerate()
+            .filter_map(|(index, child_id)| Some((index, components.get(child_id)?)))
+            .filter(|(_, child)| child.component_type != "context_menu")
+            .map(|(index, child)| {
+                let element = self.render_component(child, components, entity_id, window, cx);
+                self.wrap_draggable(element, child, &component.id, index, entity_id)
+            })
             .collect()
     }
 
+    /// Makes a `draggable="true"` child draggable among its siblings (see
+    /// [`crate::containers::drop_target`]); other elements pass through.
+    fn wrap_draggable(
+        &self,
+        element: AnyElement,
+        child: &BuiltComponent,
+        parent_id: &str,
+        index: usize,
+        entity_id: EntityId,
+    ) -> AnyElement {
+        match DraggedItem::from_child(child, parent_id, index) {
+            Some(item) => Draggable::new(item, element, Arc::clone(&self.runtime), entity_id)
+                .into_any_element(),
+            None => element,
+        }
+    }
+
     /// Wraps an element in a styled div if layout/decoration properties are present.
     fn apply_layout_styles(
         element: AnyElement,
@@ -583,6 +1535,12 @@ impl App {
         let rounded = props.get("rounded").and_then(|v| v.as_str());
         let background = props.get("background").and_then(|v| v.as_str());
 
+        // SFC `<style>` rules for the pointer states
+        let hover = style_state::state_declarations(props, StyleState::Hover)
+            .map(|decls| state_refinement(decls, cx));
+        let active = style_state::state_declarations(props, StyleState::Active)
+            .map(|decls| state_refinement(decls, cx));
+
         // Early return if nothing to apply
         if [
             width,
@@ -621,6 +1579,8 @@ impl App {
             && rounded.is_none()
             && background.is_none()
             && border_color.is_none()
+            && hover.is_none()
+            && active.is_none()
         {
             return element;
         }
@@ -755,7 +1715,17 @@ impl App {
             }
         } // end !is_panel decoration
 
-        wrapper.child(element).into_any_element()
+        if let Some(hover) = hover {
+            wrapper = wrapper.hover(move |style| style.refined(hover));
+        }
+        match active {
+            Some(active) => wrapper
+                .id(SharedString::from(format!("style-{}", component.id)))
+                .active(move |style| style.refined(active))
+                .child(element)
+                .into_any_element(),
+            None => wrapper.child(element).into_any_element(),
+        }
     }
 
     /// Renders a component and its children recursively.
@@ -768,12 +1738,18 @@ impl App {
         cx: &mut Context<Self>,
     ) -> AnyElement {
         // `n:if` / `visible` binding: a component whose `visible` property is
-        // explicitly `false` renders as an empty element (occupies no space).
+        // explicitly `false` renders as an empty element (occupies no space),
+        // as does a branch of an `n:else-if`/`n:switch` chain that isn't taken
+        // and one whose `n:show-when` doesn't hold for the window size.
+        let size = window.viewport_size();
+        let viewport = (f32::from(size.width), f32::from(size.height));
         if component
             .properties
             .get("visible")
             .and_then(|v| v.as_bool())
             == Some(false)
+            || !nemo_layout::branch::is_branch_active(&component.properties)
+            || !responsive::is_shown(&component.properties, viewport.0, viewport.1)
         {
             return div().into_any_element();
         }
@@ -784,12 +1760,55 @@ impl App {
                     .children(children)
                     .into_any_element()
             }
+            "grid" => {
+                let cells = component
+                    .children
+                    .iter()
+                    .filter_map(|id| components.get(id))
+                    .filter(|child| child.component_type != "context_menu")
+                    .map(|child| {
+                        let request = CellRequest::from_properties(&child.properties);
+                        let element =
+                            self.render_component(child, components, entity_id, window, cx);
+                        (request, element)
+                    })
+                    .collect();
+                Grid::new(component.clone()).cells(cells).into_any_element()
+            }
+            "split" => {
+                let children = self.render_children(component, components, entity_id, window, cx);
+                let sizes = self.get_or_create_split_state(component, children.len());
+                Split::new(component.clone())
+                    .children(children)
+                    .sizes(sizes)
+                    .runtime(Arc::clone(&self.runtime))
+                    .entity_id(entity_id)
+                    .into_any_element()
+            }
+            "drop_target" => {
+                let children = self.render_children(component, components, entity_id, window, cx);
+                DropTarget::new(component.clone())
+                    .children(children)
+                    .runtime(Arc::clone(&self.runtime))
+                    .entity_id(entity_id)
+                    .into_any_element()
+            }
             "panel" => {
                 let children = self.render_children(component, components, entity_id, window, cx);
                 Panel::new(component.clone())
                     .children(children)
                     .into_any_element()
             }
+            "form" => {
+                let children = self.render_children(component, components, entity_id, window, cx);
+                let form_id = component.id.clone();
+                Form::new(component.clone())
+                    .children(children)
+                    .on_submit(cx.listener(move |this, _event, _window, cx| {
+                        this.submit_form(&form_id, cx);
+                    }))
+                    .into_any_element()
+            }
             "label" => Label::new(component.clone()).into_any_element(),
             "button" => Button::new(component.clone())
                 .runtime(Arc::clone(&self.runtime))
@@ -817,6 +1836,15 @@ impl App {
                     .entity_id(entity_id)
                     .into_any_element()
             }
+            "markdown" => {
+                let (blocks, code_states) =
+                    self.get_or_create_markdown_state(component, window, cx);
+                Markdown::new(component.clone())
+                    .document(blocks, code_states)
+                    .runtime(Arc::clone(&self.runtime))
+                    .entity_id(entity_id)
+                    .into_any_element()
+            }
             "code_editor" => {
                 let input_state = self.get_or_create_code_editor_state(component, window, cx);
                 CodeEditor::new(component.clone())
@@ -852,6 +1880,9 @@ impl App {
                     .into_any_element()
             }
             "progress" => Progress::new(component.clone()).into_any_element(),
+            "gauge" => Gauge::new(component.clone()).into_any_element(),
+            "sparkline" => Sparkline::new(component.clone()).into_any_element(),
+            "stat" => Stat::new(component.clone()).into_any_element(),
             "image" => Image::new(component.clone()).into_any_element(),
             "svg" => Svg::new(component.clone())
                 .runtime(Arc::clone(&self.runtime))
@@ -916,9 +1947,11 @@ impl App {
                     .into_any_element()
             }
             "table" => {
-                let table_state = self.get_or_create_table_state(component, window, cx);
+                let (table_state, filter_inputs) =
+                    self.get_or_create_table_state(component, window, cx);
                 Table::new(component.clone())
                     .table_state(table_state)
+                    .filter_inputs(filter_inputs)
                     .into_any_element()
             }
             "list" => {
@@ -940,32 +1973,59 @@ impl App {
                     let Some(item) = components.get(item_id) else {
                         continue;
                     };
-                    items.push(self.render_children(item, components, entity_id, window, cx));
+                    let body = self.render_children(item, components, entity_id, window, cx);
+                    // A draggable <list-item> drags its whole row body.
+                    let index = component
+                        .children
+                        .iter()
+                        .position(|id| id == item_id)
+                        .unwrap_or_default();
+                    items.push(match DraggedItem::from_child(item, &component.id, index) {
+                        Some(drag) => vec![Draggable::new(
+                            drag,
+                            div().children(body).into_any_element(),
+                            Arc::clone(&self.runtime),
+                            entity_id,
+                        )
+                        .into_any_element()],
+                        None => body,
+                    });
                 }
 
                 List::new(component.clone()).items(items).into_any_element()
             }
+            "virtual_list" => {
+                let list_state = self.get_or_create_virtual_list_state(component, window);
+                let list_id = component.id.clone();
+                let rows = list(
+                    list_state,
+                    cx.processor(move |this, ix: usize, window, cx| {
+                        this.render_virtual_row(&list_id, ix, window, cx)
+                    }),
+                );
+                VirtualList::new(component.clone())
+                    .list(rows)
+                    .into_any_element()
+            }
             "tree" => {
                 let tree_state = self.get_or_create_tree_state(component, window, cx);
                 Tree::new(component.clone())
                     .tree_state(tree_state)
                     .into_any_element()
             }
-            "line_chart" => LineChart::new(component.clone()).into_any_element(),
-            "realtime_chart" => RealtimeChart::new(component.clone()).into_any_element(),
-            "bar_chart" => BarChart::new(component.clone()).into_any_element(),
-            "area_chart" => AreaChart::new(component.clone()).into_any_element(),
+            "line_chart"
+            | "realtime_chart"
+            | "bar_chart"
+            | "area_chart"
+            | "candlestick_chart"
+            | "column_chart"
+            | "stacked_column_chart"
+            | "clustered_column_chart"
+            | "scatter_chart"
+            | "bubble_chart" => self.render_interactive_chart(component, components, entity_id),
             "pie_chart" => PieChart::new(component.clone()).into_any_element(),
-            "candlestick_chart" => CandlestickChart::new(component.clone()).into_any_element(),
-            "column_chart" => ColumnChart::new(component.clone()).into_any_element(),
-            "stacked_column_chart" => StackedColumnChart::new(component.clone()).into_any_element(),
-            "clustered_column_chart" => {
-                ClusteredColumnChart::new(component.clone()).into_any_element()
-            }
             "stacked_bar_chart" => StackedBarChart::new(component.clone()).into_any_element(),
             "clustered_bar_chart" => ClusteredBarChart::new(component.clone()).into_any_element(),
-            "scatter_chart" => ScatterChart::new(component.clone()).into_any_element(),
-            "bubble_chart" => BubbleChart::new(component.clone()).into_any_element(),
             "heatmap_chart" => HeatmapChart::new(component.clone()).into_any_element(),
             "radar_chart" => RadarChart::new(component.clone()).into_any_element(),
             "pyramid_chart" => PyramidChart::new(component.clone()).into_any_element(),
@@ -1018,7 +2078,8 @@ impl App {
                     .entity_id(entity_id)
                     .into_any_element()
             }
-            "accordion_item" | "tab_item" | "option" | "menu_item" | "list_item" => {
+            "accordion_item" | "tab_item" | "option" | "menu_item" | "list_item" | "threshold"
+            | "band" | "marker" | "context_menu" => {
                 // These are rendered by their parent container; standalone fallback.
                 div().into_any_element()
             }
@@ -1090,6 +2151,25 @@ impl App {
                     .slider_state(slider_state)
                     .into_any_element()
             }
+            "date_picker" | "datetime_picker" => {
+                let (state, time) = self.get_or_create_date_picker_state(component, window, cx);
+                DatePicker::new(component.clone())
+                    .state(state)
+                    .time(time)
+                    .into_any_element()
+            }
+            "color_picker" => {
+                let state = self.get_or_create_color_picker_state(component, window, cx);
+                ColorPicker::new(component.clone())
+                    .state(state)
+                    .into_any_element()
+            }
+            "number_input" => {
+                let state = self.get_or_create_number_input_state(component, window, cx);
+                NumberInput::new(component.clone())
+                    .state(state)
+                    .into_any_element()
+            }
             "sidenav_bar" => {
                 // Collect child BuiltComponents for sidenav_bar_item rendering
                 let child_components: Vec<BuiltComponent> = component
@@ -1319,7 +2399,93 @@ impl App {
                     .into_any_element()
             }
         };
-        Self::apply_layout_styles(element, component, cx)
+        let element = match self.form_errors.get(&component.id) {
+            Some(message) => form::with_error(element, message, cx),
+            None => element,
+        };
+        // A `:focus` style rule needs the component's id as a key context too,
+        // to tell whether focus is inside it.
+        let has_focus_style =
+            style_state::state_declarations(&component.properties, StyleState::Focus).is_some();
+        let element = if self.key_contexts.contains(&component.id) || has_focus_style {
+            keymap::with_key_context(element, &component.id, &self.keymap_scope)
+        } else {
+            element
+        };
+        let menu_items = context_menu::context_menu_items(component, components);
+        let element = if export::is_exportable(&component.component_type) {
+            export::with_export_menu(
+                element,
+                component,
+                Arc::clone(&self.runtime),
+                Arc::clone(&self.export_bounds),
+                menu_items.unwrap_or_default(),
+            )
+        } else if let Some(items) = menu_items {
+            context_menu::with_context_menu(
+                element,
+                &component.id,
+                items,
+                Arc::clone(&self.runtime),
+            )
+        } else {
+            element
+        };
+        let focused = has_focus_style
+            && window
+                .context_stack()
+                .iter()
+                .any(|context| context.contains(&component.id));
+        match style_state::with_active_states(&component.properties, focused, viewport) {
+            Some(properties) => {
+                let styled = BuiltComponent {
+                    properties,
+                    ..component.clone()
+                };
+                Self::apply_layout_styles(element, &styled, cx)
+            }
+            None => Self::apply_layout_styles(element, component, cx),
+        }
+    }
+}
+
+/// A `:hover`/`:active` style rule's declarations as a gpui style refinement
+/// (see [`style_state::REFINABLE_PROPERTIES`]).
+fn state_refinement(decls: &indexmap::IndexMap<String, Value>, cx: &App) -> StyleRefinement {
+    let color = |key: &str| {
+        decls
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(|c| crate::components::resolve_color(c, cx))
+    };
+    let mut style = StyleRefinement::default();
+    if let Some(bg) = color("background") {
+        style = style.bg(bg);
+    }
+    if let Some(c) = color("border_color") {
+        style = style.border_color(c);
+    }
+    if let Some(b) = decls.get("border").and_then(|v| v.as_i64()) {
+        style = style.border(px(b as f32));
+    }
+    if let Some(r) = decls
+        .get("rounded")
+        .and_then(|v| v.as_str())
+        .and_then(|name| crate::theme::tokens::radius_for(name, cx))
+    {
+        style = style.rounded(r);
+    }
+    style
+}
+
+/// The text an `on-change` handler receives for a picked value: strings as
+/// they are, nothing for null, JSON for anything structured.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Object(_) | Value::Array(_) => serde_json::to_string(value).unwrap_or_default(),
+        other => other.to_string(),
     }
 }
 
@@ -1352,9 +2518,92 @@ fn collect_options(
         .collect()
 }
 
+impl App {
+    /// The dev-mode overlay listing runtime errors at their `file:line:col`,
+    /// pinned to the bottom of the window until dismissed.
+    fn render_error_overlay(
+        &self,
+        errors: &[nemo_config::SourceError],
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let runtime = Arc::clone(&self.runtime);
+        let entity_id = cx.entity_id();
+        let colors = &cx.theme().colors;
+        let title = match errors.len() {
+            1 => "1 runtime error".to_string(),
+            n => format!("{} runtime errors", n),
+        };
+        div()
+            .id("nemo-error-overlay")
+            .absolute()
+            .left_0()
+            .right_0()
+            .bottom_0()
+            .max_h(px(240.))
+            .overflow_y_scroll()
+            .flex()
+            .flex_col()
+            .gap_t(Space::Xs)
+            .p_t(Space::Md)
+            .bg(colors.background)
+            .border_t_2()
+            .border_color(colors.danger)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(colors.danger)
+                            .child(title),
+                    )
+                    .child(
+                        gpui_component::button::Button::new("nemo-error-overlay-dismiss")
+                            .label("Dismiss")
+                            .xsmall()
+                            .on_click(move |_, _window, cx| {
+                                runtime.clear_runtime_errors();
+                                cx.notify(entity_id);
+                            }),
+                    ),
+            )
+            .children(errors.iter().map(|error| {
+                let location = error
+                    .location
+                    .as_ref()
+                    .map(|l| l.to_string())
+                    .unwrap_or_default();
+                div()
+                    .flex()
+                    .gap_t(Space::Sm)
+                    .text_t(FontSize::Sm)
+                    .child(div().text_color(colors.muted_foreground).child(location))
+                    .child(div().child(error.message.clone()))
+            }))
+            .into_any_element()
+    }
+}
+
 impl Render for App {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Header bar is rendered by AppLayout; App only renders layout content.
-        self.render_layout(window, cx)
+        let layout = self.render_layout(window, cx);
+        self.capture_pending_exports(window);
+        let errors = if self.runtime.error_overlay() {
+            self.runtime.runtime_errors()
+        } else {
+            Vec::new()
+        };
+        if errors.is_empty() {
+            return layout;
+        }
+        div()
+            .relative()
+            .size_full()
+            .child(layout)
+            .child(self.render_error_overlay(&errors, cx))
+            .into_any_element()
     }
 }
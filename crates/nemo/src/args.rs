@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use nemo_config::SetOverride;
 use std::path::PathBuf;
 use tracing::Level;
 
@@ -65,6 +66,16 @@ pub struct Args {
     /// router's `default` for this launch only.
     #[arg(long)]
     pub route: Option<String>,
+
+    /// Apply the overlays of this `nemo.toml` profile (`[profiles.<name>]`).
+    #[arg(long, env = "NEMO_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Override a config value for this launch, e.g. `--set var.api_url=http://…`
+    /// or `--set data.source.prices.url=…`. Repeatable; wins over the profile
+    /// and `overrides.xml`.
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = SetOverride::parse)]
+    pub overrides: Vec<SetOverride>,
 }
 
 /// Nemo subcommands.
@@ -87,6 +98,8 @@ pub enum Command {
     /// Fetch remote component-library dependencies into `.nemo/packages` and
     /// write `nemo.lock`.
     Get(GetArgs),
+    /// Store and list the project's `${secret.name}` values.
+    Secret(SecretArgs),
     /// Render an application to a PNG image and exit (macOS-first).
     ///
     /// Requires a build with `--features screenshot` (enables gpui's offscreen
@@ -138,6 +151,15 @@ pub struct DevArgs {
     /// top-level `--route`). Format: `<path>` or `<router-id>=<path>`.
     #[arg(long)]
     pub route: Option<String>,
+
+    /// Apply the overlays of this `nemo.toml` profile (see the top-level
+    /// `--profile`).
+    #[arg(long, env = "NEMO_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Override a config value (see the top-level `--set`). Repeatable.
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = SetOverride::parse)]
+    pub overrides: Vec<SetOverride>,
 }
 
 /// Arguments for `nemo validate`.
@@ -153,6 +175,19 @@ pub struct ValidateArgs {
     /// Output format for diagnostics.
     #[arg(long, value_enum, default_value = "human")]
     pub format: ValidateFormat,
+
+    /// Validate with this `nemo.toml` profile's overlays applied. Repeatable:
+    /// each profile is checked on its own.
+    #[arg(long)]
+    pub profile: Vec<String>,
+
+    /// Validate the entry as written and then with each profile in `nemo.toml`.
+    #[arg(long, conflicts_with = "profile")]
+    pub all_profiles: bool,
+
+    /// Override a config value before validating (see the top-level `--set`).
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = SetOverride::parse)]
+    pub overrides: Vec<SetOverride>,
 }
 
 /// Output format for `nemo validate`.
@@ -208,6 +243,48 @@ pub struct GetArgs {
     pub target: Option<PathBuf>,
 }
 
+/// Arguments for `nemo secret`.
+#[derive(clap::Args, Debug)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    pub command: SecretCommand,
+}
+
+/// `nemo secret` subcommands.
+#[derive(Subcommand, Debug)]
+pub enum SecretCommand {
+    /// Store (or replace) a secret. Reads the value from stdin unless
+    /// `--value` is given, so it stays out of shell history.
+    Set(SecretSetArgs),
+    /// List the stored secret names (never the values).
+    List(SecretListArgs),
+}
+
+/// Arguments for `nemo secret set`.
+#[derive(clap::Args, Debug)]
+pub struct SecretSetArgs {
+    /// The name `${secret.<name>}` refers to.
+    pub name: String,
+
+    /// The value. Prefer stdin; an argument is visible to other processes.
+    #[arg(long)]
+    pub value: Option<String>,
+
+    /// Project directory (or a file inside it) whose store to use. Defaults to
+    /// the current directory (walks up to find `nemo.toml`).
+    #[arg(long)]
+    pub project: Option<PathBuf>,
+}
+
+/// Arguments for `nemo secret list`.
+#[derive(clap::Args, Debug)]
+pub struct SecretListArgs {
+    /// Project directory (or a file inside it) whose store to list. Defaults
+    /// to the current directory.
+    #[arg(long)]
+    pub project: Option<PathBuf>,
+}
+
 /// Arguments for `nemo screenshot`.
 #[derive(clap::Args, Debug)]
 pub struct ScreenshotArgs {
@@ -356,6 +433,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn profile_and_set_parse() {
+        let args = Args::try_parse_from([
+            "nemo",
+            "--app-config",
+            "app.nemo",
+            "--profile",
+            "staging",
+            "--set",
+            "var.beta=true",
+            "--set",
+            "app.window.width=800",
+        ])
+        .unwrap();
+        assert_eq!(args.profile.as_deref(), Some("staging"));
+        let keys: Vec<String> = args.overrides.iter().map(|o| o.path.to_string()).collect();
+        assert_eq!(keys, ["variable.beta.default", "app.window.width"]);
+        assert!(Args::try_parse_from(["nemo", "--set", "no-equals"]).is_err());
+
+        let args = Args::try_parse_from([
+            "nemo",
+            "validate",
+            "app.nemo",
+            "--profile",
+            "dev",
+            "--profile",
+            "prod",
+        ])
+        .unwrap();
+        match args.command {
+            Some(Command::Validate(v)) => {
+                assert_eq!(v.profile, ["dev", "prod"]);
+                assert!(!v.all_profiles);
+            }
+            other => panic!("expected Validate, got {other:?}"),
+        }
+    }
+
     #[test]
     fn watch_flag_parses_on_default_path() {
         let args = Args::try_parse_from(["nemo", "--app-config", "app.xml", "--watch"]).unwrap();
@@ -398,6 +513,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn secret_subcommand_parses() {
+        let args =
+            Args::try_parse_from(["nemo", "secret", "set", "api_token", "--value", "x"]).unwrap();
+        match args.command {
+            Some(Command::Secret(SecretArgs {
+                command: SecretCommand::Set(set),
+            })) => {
+                assert_eq!(set.name, "api_token");
+                assert_eq!(set.value.as_deref(), Some("x"));
+                assert_eq!(set.project, None);
+            }
+            other => panic!("expected Secret Set, got {other:?}"),
+        }
+        let args = Args::try_parse_from(["nemo", "secret", "list", "--project", "app"]).unwrap();
+        match args.command {
+            Some(Command::Secret(SecretArgs {
+                command: SecretCommand::List(list),
+            })) => assert_eq!(list.project, Some(PathBuf::from("app"))),
+            other => panic!("expected Secret List, got {other:?}"),
+        }
+        assert!(Args::try_parse_from(["nemo", "secret", "set"]).is_err());
+    }
+
     #[test]
     fn screenshot_subcommand_parses() {
         let args = Args::try_parse_from([
@@ -113,7 +113,8 @@ pub(crate) fn build_package(
 /// resolved config `Value` to `<out>/layout.json`. Loading that tree with
 /// `--dist` (or manifest `load = "dist"`) reproduces the same render, skipping
 /// the parse/resolve work — `ConfigurationLoader::load_from_dist` returns the
-/// same `Value` the source path produces.
+/// same `Value` the source path produces. Secret values are never written:
+/// `${secret.name}` references are kept and resolved when the tree is loaded.
 pub(crate) fn build_project(root: &Path, manifest: &ProjectManifest) -> Result<()> {
     let entry = root.join(&manifest.entry);
     if !entry.is_file() {
@@ -121,6 +122,8 @@ pub(crate) fn build_project(root: &Path, manifest: &ProjectManifest) -> Result<(
     }
 
     let loader = ConfigurationLoader::new(Arc::new(SchemaRegistry::new()));
+    // `${secret.…}` stays a reference in `dist/`; the launch resolves it.
+    loader.set_defer_secrets(true);
     let config = loader
         .load(&entry)
         .map_err(|e| anyhow!("loading {}: {e}", entry.display()))?;
@@ -218,6 +221,8 @@ fn compile_component(file: &Path) -> Result<CompiledComponent> {
         .map(str::to_string);
     let props = array_field(&flat, "props");
     let slots = array_field(&flat, "slots");
+    let emits = array_field(&flat, "emits");
+    let state = flat.get("state").cloned();
 
     Ok(CompiledComponent {
         tag,
@@ -225,6 +230,8 @@ fn compile_component(file: &Path) -> Result<CompiledComponent> {
         script,
         props,
         slots,
+        emits,
+        state,
         meta: ComponentMeta { name, source },
     })
 }
@@ -252,8 +259,9 @@ fn write_artifact(out_base: &Path, component: &CompiledComponent) -> Result<Path
 /// A compiled `.nemo` component artifact — the unit a component library ships.
 ///
 /// `template` is the style-folded, handler-rewritten body (the `TemplateMap`
-/// entry). `props`/`slots` carry the declared typed-prop and slot specs (same
-/// shape as `config["sfc"][tag]`) so consumers can validate usage.
+/// entry). `props`/`slots`/`emits` carry the declared typed-prop, slot and
+/// event specs (same shape as `config["sfc"][tag]`) so consumers can validate
+/// usage; `state` carries the per-instance fields and their initial values.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct CompiledComponent {
     tag: String,
@@ -264,6 +272,10 @@ struct CompiledComponent {
     props: Vec<Value>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     slots: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    emits: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<Value>,
     meta: ComponentMeta,
 }
 
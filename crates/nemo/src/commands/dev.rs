@@ -24,6 +24,12 @@ pub fn run(mut args: Args, dev: DevArgs) -> Result<()> {
     if dev.route.is_some() {
         args.route = dev.route;
     }
+    if dev.profile.is_some() {
+        args.profile = dev.profile;
+    }
+    if !dev.overrides.is_empty() {
+        args.overrides = dev.overrides;
+    }
 
     if args.app_config.is_none() {
         bail!("`nemo dev` requires --app-config <app.nemo|app.xml> (or NEMO_APP_CONFIG)");
@@ -16,4 +16,5 @@ pub mod new;
 pub mod schema;
 #[cfg(feature = "screenshot")]
 pub mod screenshot;
+pub mod secret;
 pub mod validate;
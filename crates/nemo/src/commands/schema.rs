@@ -21,7 +21,7 @@ use nemo_config::{ConfigSchema, PropertySchema, ValidationRule, Value};
 use nemo_registry::{
     attribute_families, register_all_builtins, structural_elements, universal_style_attributes,
     ActionDescriptor, ComponentCategory, ComponentDescriptor, ComponentMetadata, ComponentRegistry,
-    DataSourceDescriptor, SlotSpec, TransformDescriptor,
+    DataSourceDescriptor, EventSpec, SlotSpec, TransformDescriptor,
 };
 use serde::Serialize;
 
@@ -70,7 +70,7 @@ pub fn run(args: SchemaArgs) -> Result<()> {
 
 /// Synthesizes and registers a `ComponentDescriptor` for each single-file
 /// component in the config, so the schema export includes SFC tags (with their
-/// typed props and declared slots) alongside the builtins.
+/// typed props, declared slots and emitted events) alongside the builtins.
 fn register_sfc_descriptors(registry: &ComponentRegistry, config: &Value) {
     let sfc = match config.get("sfc").and_then(|v| v.as_object()) {
         Some(s) => s,
@@ -89,8 +89,17 @@ fn register_sfc_descriptors(registry: &ComponentRegistry, config: &Value) {
                     "int" | "integer" | "i64" => PropertySchema::integer(),
                     "float" | "number" | "f64" => PropertySchema::float(),
                     "bool" | "boolean" => PropertySchema::boolean(),
+                    "array" | "list" => PropertySchema::array(PropertySchema::any()),
+                    "object" | "map" => PropertySchema::object(ConfigSchema::new(name)),
                     _ => PropertySchema::string(),
                 };
+                // `one-of` values are stored as strings, which only compare
+                // equal to a string prop's values.
+                if let Some(one_of) = p.get("one_of").and_then(|v| v.as_array()) {
+                    if ty == "string" {
+                        ps = ps.one_of(one_of.clone());
+                    }
+                }
                 if let Some(default) = p.get("default") {
                     ps = ps.with_default(default.clone());
                 }
@@ -118,9 +127,26 @@ fn register_sfc_descriptors(registry: &ComponentRegistry, config: &Value) {
             })
             .unwrap_or_default();
 
+        let events: Vec<EventSpec> = def
+            .get("emits")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|e| {
+                        Some(EventSpec {
+                            name: e.as_str()?.to_string(),
+                            payload_schema: None,
+                            description: String::new(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let metadata = ComponentMetadata {
             display_name: tag.clone(),
             description: "Single-file component.".to_string(),
+            events,
             slots,
             ..Default::default()
         };
@@ -55,6 +55,8 @@ pub fn run(args: ScreenshotArgs) -> Result<()> {
         ws_args: WorkspaceArgs {
             extension_dirs: Vec::new(),
             initial_route: args.route.clone(),
+            profile: None,
+            overrides: Vec::new(),
         },
         watch: None,
         size_override,
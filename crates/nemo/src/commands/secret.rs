@@ -0,0 +1,66 @@
+//! `nemo secret` — manage the values `${secret.name}` resolves to.
+//!
+//! `set` writes to the project's store (see `nemo-config`'s `secret` module:
+//! the Secret Service, or the `NEMO_SECRET_PASSPHRASE`-encrypted
+//! `.nemo/secrets.json`, per `nemo.toml`'s `[secrets]`); `list` prints the
+//! stored names. Values are never printed.
+
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use nemo_config::Secrets;
+
+use crate::args::{SecretArgs, SecretCommand};
+
+pub fn run(args: SecretArgs) -> Result<()> {
+    match args.command {
+        SecretCommand::Set(set) => {
+            let secrets = store(set.project)?;
+            let value = match set.value {
+                Some(value) => value,
+                None => read_value(&set.name)?,
+            };
+            if value.is_empty() {
+                bail!("refusing to store an empty value for '{}'", set.name);
+            }
+            secrets
+                .set(&set.name, &value)
+                .with_context(|| format!("storing secret '{}'", set.name))?;
+            println!("stored '{}' in the {}", set.name, secrets.describe());
+        }
+        SecretCommand::List(list) => {
+            let secrets = store(list.project)?;
+            let names = secrets.list().context("listing secrets")?;
+            if names.is_empty() {
+                println!("No secrets in the {}.", secrets.describe());
+            }
+            for name in names {
+                println!("{name}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The store of the project at `project` (default: the current directory).
+fn store(project: Option<PathBuf>) -> Result<Secrets> {
+    let start = match project {
+        Some(project) => project,
+        None => std::env::current_dir().context("resolving the current directory")?,
+    };
+    Ok(Secrets::discover(&start))
+}
+
+/// Reads a value from stdin, dropping the trailing newline.
+fn read_value(name: &str) -> Result<String> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprintln!("Value for '{name}' (end with Ctrl-D):");
+    }
+    let mut value = String::new();
+    stdin
+        .read_to_string(&mut value)
+        .context("reading the value from stdin")?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -2,20 +2,26 @@
 //!
 //! Runs the same parse + resolve path the app uses at load time
 //! (`ConfigurationLoader::load`), reporting located diagnostics. `--strict`
-//! adds component-level lints. Exits non-zero if any error-severity diagnostic
-//! is produced.
+//! adds component-level lints. `--profile`/`--all-profiles` repeat the check
+//! with each `nemo.toml` profile's overlays applied, tagging diagnostics with
+//! the profile. Exits non-zero if any error-severity diagnostic is produced.
 
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use nemo_config::{
-    ConfigError, ConfigurationLoader, SchemaRegistry, SourceLocation, ValidationRule, Value,
+    find_project_root, redact, ConfigError, ConfigurationLoader, Messages, ProjectManifest,
+    SchemaRegistry, SourceLocation, ValidationRule, Value, MANIFEST_FILE,
 };
 use nemo_registry::{register_all_builtins, ComponentRegistry};
 use serde::Serialize;
 
 use crate::args::{ValidateArgs, ValidateFormat};
+use crate::components::chart_annotations::{ANNOTATED_CHARTS, ANNOTATION_TYPES};
+use crate::workspace::keymap::{
+    context_predicate, focus_ids, keymap_entries, parse_binding, same_binding, BUILTIN_SHORTCUTS,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -46,6 +52,9 @@ struct Diagnostic {
     line: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     column: Option<usize>,
+    /// The profile whose overlays were applied, when one was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
 }
 
 impl Diagnostic {
@@ -53,10 +62,11 @@ impl Diagnostic {
         Diagnostic {
             severity: Severity::Error,
             code: code.to_string(),
-            message: message.into(),
+            message: redact(&message.into()).into_owned(),
             file: None,
             line: None,
             column: None,
+            profile: None,
         }
     }
 
@@ -64,10 +74,11 @@ impl Diagnostic {
         Diagnostic {
             severity: Severity::Warning,
             code: code.to_string(),
-            message: message.into(),
+            message: redact(&message.into()).into_owned(),
             file: None,
             line: None,
             column: None,
+            profile: None,
         }
     }
 
@@ -85,16 +96,21 @@ impl Diagnostic {
 pub fn run(args: ValidateArgs) -> Result<()> {
     let path = args.app_config.as_path();
     let source = std::fs::read_to_string(path).unwrap_or_default();
+    let profiles = profiles_to_check(&args)?;
 
     let mut diagnostics = Vec::new();
     let loader = ConfigurationLoader::new(Arc::new(SchemaRegistry::new()));
-    match loader.load(path) {
-        Ok(value) => {
-            if args.strict {
-                diagnostics.extend(strict_lints(&value));
-            }
-        }
-        Err(err) => diagnostics.extend(config_error_to_diagnostics(err)),
+    loader.set_overrides(args.overrides.clone());
+    for profile in &profiles {
+        loader.set_profile(profile.as_deref());
+        diagnostics.extend(
+            validate_entry(&loader, path, args.strict)
+                .into_iter()
+                .map(|mut d| {
+                    d.profile = profile.clone();
+                    d
+                }),
+        );
     }
 
     let error_count = diagnostics
@@ -104,10 +120,17 @@ pub fn run(args: ValidateArgs) -> Result<()> {
     let warning_count = diagnostics.len() - error_count;
 
     match args.format {
-        ValidateFormat::Human => {
-            render_human(path, &source, &diagnostics, error_count, warning_count)
+        ValidateFormat::Human => render_human(
+            path,
+            &source,
+            &profiles,
+            &diagnostics,
+            error_count,
+            warning_count,
+        ),
+        ValidateFormat::Json => {
+            render_json(path, &profiles, &diagnostics, error_count, warning_count)
         }
-        ValidateFormat::Json => render_json(path, &diagnostics, error_count, warning_count),
     }
 
     if error_count > 0 {
@@ -117,6 +140,44 @@ pub fn run(args: ValidateArgs) -> Result<()> {
     Ok(())
 }
 
+/// The profiles to validate with, `None` being the entry as written:
+/// the `--profile` names if any, every `nemo.toml` profile (after the entry
+/// itself) with `--all-profiles`, and otherwise just the entry.
+fn profiles_to_check(args: &ValidateArgs) -> Result<Vec<Option<String>>> {
+    if args.all_profiles {
+        let root = find_project_root(&args.app_config).ok_or_else(|| {
+            anyhow!(
+                "--all-profiles needs a {MANIFEST_FILE} above {}",
+                args.app_config.display()
+            )
+        })?;
+        let manifest = ProjectManifest::load(&root.join(MANIFEST_FILE))?;
+        return Ok(std::iter::once(None)
+            .chain(manifest.profile_names().into_iter().map(Some))
+            .collect());
+    }
+    if args.profile.is_empty() {
+        return Ok(vec![None]);
+    }
+    Ok(args.profile.iter().cloned().map(Some).collect())
+}
+
+/// Loads the entry with the loader's current profile and runs the lints.
+fn validate_entry(loader: &ConfigurationLoader, path: &Path, strict: bool) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    match loader.load(path) {
+        Ok(value) => {
+            diagnostics.extend(keymap_lints(&value));
+            diagnostics.extend(i18n_lints(&value));
+            if strict {
+                diagnostics.extend(strict_lints(&value));
+            }
+        }
+        Err(err) => diagnostics.extend(config_error_to_diagnostics(err)),
+    }
+    diagnostics
+}
+
 /// Convert a structured `ConfigError` into one or more diagnostics.
 fn config_error_to_diagnostics(err: ConfigError) -> Vec<Diagnostic> {
     match err {
@@ -156,14 +217,32 @@ fn config_error_to_diagnostics(err: ConfigError) -> Vec<Diagnostic> {
                 ),
             )]
         }
+        err @ ConfigError::UnknownProfile { .. } => {
+            vec![Diagnostic::error("unknown-profile", err.to_string())]
+        }
     }
 }
 
-/// Structural keys on a component node that are not user-facing attributes.
+/// Structural keys on a component node that are not user-facing attributes,
+/// plus `class`, which only SFC `<style>` selectors read.
 fn is_structural_key(key: &str) -> bool {
     matches!(
         key,
-        "type" | "component" | "binding" | "slot" | "vars" | "template" | "list_binding"
+        "type"
+            | "component"
+            | "binding"
+            | "slot"
+            | "vars"
+            | "template"
+            | "list_binding"
+            | "class"
+            | "hover_style"
+            | "active_style"
+            | "focus_style"
+            | "disabled_style"
+            | "show_when"
+            | "media_style"
+            | "i18n_attrs"
     )
 }
 
@@ -202,12 +281,13 @@ struct SfcSlotLint {
 }
 
 /// SFC info needed for linting: the set of registered tags (valid component
-/// types that expand to built-ins), each tag's required prop names, and each
-/// tag's declared slots.
+/// types that expand to built-ins), each tag's required prop names, each
+/// tag's declared slots, and each tag's declared `<emits>` events.
 struct SfcLintInfo {
     tags: std::collections::HashSet<String>,
     required: std::collections::HashMap<String, Vec<String>>,
     slots: std::collections::HashMap<String, Vec<SfcSlotLint>>,
+    emits: std::collections::HashMap<String, Vec<String>>,
 }
 
 impl SfcLintInfo {
@@ -215,6 +295,7 @@ impl SfcLintInfo {
         let mut tags = std::collections::HashSet::new();
         let mut required = std::collections::HashMap::new();
         let mut slots = std::collections::HashMap::new();
+        let mut emits = std::collections::HashMap::new();
         if let Some(sfc) = root.get("sfc").and_then(|v| v.as_object()) {
             for (tag, def) in sfc {
                 tags.insert(tag.clone());
@@ -256,12 +337,24 @@ impl SfcLintInfo {
                     })
                     .unwrap_or_default();
                 slots.insert(tag.clone(), declared);
+
+                let events: Vec<String> = def
+                    .get("emits")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|e| e.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                emits.insert(tag.clone(), events);
             }
         }
         SfcLintInfo {
             tags,
             required,
             slots,
+            emits,
         }
     }
 }
@@ -280,6 +373,24 @@ fn lint_config(root: &Value, registry: &ComponentRegistry) -> Vec<Diagnostic> {
         lint_component_children(layout, registry, &sfc, &mut diagnostics, &mut template_refs);
     }
 
+    // An SFC script may only `emit()` events its `<emits>` block declares.
+    if let Some(defs) = root.get("sfc").and_then(|v| v.as_object()) {
+        for (tag, def) in defs {
+            let script = def.get("script").and_then(|v| v.as_str()).unwrap_or("");
+            let declared = sfc.emits.get(tag).map(Vec::as_slice).unwrap_or(&[]);
+            for event in emitted_events(script) {
+                if !declared.contains(&event) {
+                    diagnostics.push(Diagnostic::error(
+                        "undeclared-emit",
+                        format!(
+                            "SFC '{tag}' script emits '{event}', which its <emits> block does not declare"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
     // Lint template bodies too, and collect any template-to-template references.
     let templates = root
         .get("templates")
@@ -347,11 +458,12 @@ fn lint_component(
         template_refs.insert(name.to_string());
     }
 
-    // For an SFC usage, enforce its declared required props.
+    // For an SFC usage, enforce its declared required props (set statically
+    // or bound with `:name`).
     if is_sfc {
         if let Some(reqs) = sfc.required.get(ctype) {
             for req in reqs {
-                if !obj.contains_key(req) {
+                if !obj.contains_key(req) && !obj.contains_key(&format!(":{req}")) {
                     diagnostics.push(Diagnostic::error(
                         "missing-required",
                         format!("SFC '{ctype}' (id '{id}') is missing required prop '{req}'"),
@@ -360,6 +472,7 @@ fn lint_component(
             }
         }
         lint_sfc_slots(id, ctype, component, sfc, diagnostics);
+        lint_sfc_events(id, ctype, component, sfc, diagnostics);
     }
 
     // Component/attribute checks only apply to non-templated components (a
@@ -458,6 +571,35 @@ fn lint_component(
         ));
     }
 
+    // Only some charts draw `<threshold>`/`<band>`/`<marker>` children; under
+    // any other component they'd silently draw nothing.
+    if !ANNOTATED_CHARTS.contains(&ctype) {
+        if let Some(children) = obj.get("component").and_then(|c| c.as_object()) {
+            for (child_id, child) in children {
+                let child_type = child.get("type").and_then(|v| v.as_str()).unwrap_or("");
+                if ANNOTATION_TYPES.contains(&child_type) {
+                    diagnostics.push(Diagnostic::warning(
+                        "unsupported-annotation",
+                        format!(
+                            "<{child_type}> '{child_id}' in '{id}' (type '{ctype}') is not drawn; annotations only work on {}",
+                            ANNOTATED_CHARTS.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+        // Nor do they draw a legend.
+        if ctype.ends_with("_chart") && obj.contains_key("legend") {
+            diagnostics.push(Diagnostic::warning(
+                "unsupported-legend",
+                format!(
+                    "'{id}' (type '{ctype}') has no legend; only {} draw one",
+                    ANNOTATED_CHARTS.join(", ")
+                ),
+            ));
+        }
+    }
+
     lint_component_children(component, registry, sfc, diagnostics, template_refs);
 }
 
@@ -525,9 +667,228 @@ fn lint_sfc_slots(
     }
 }
 
+/// Flags an SFC usage's `on-*` handlers for events its tag doesn't declare.
+/// Such a handler binds to the template root's own event instead, which is
+/// rarely what a consumer of a component with `<emits>` means. Skipped when
+/// the SFC declares no events.
+fn lint_sfc_events(
+    id: &str,
+    ctype: &str,
+    component: &Value,
+    sfc: &SfcLintInfo,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let declared = match sfc.emits.get(ctype) {
+        Some(e) if !e.is_empty() => e,
+        _ => return,
+    };
+    let Some(obj) = component.as_object() else {
+        return;
+    };
+    for key in obj.keys() {
+        let Some(event) = key.strip_prefix("on_") else {
+            continue;
+        };
+        if !declared.iter().any(|d| d == event) {
+            diagnostics.push(Diagnostic::warning(
+                "unknown-event",
+                format!(
+                    "SFC '{ctype}' (id '{id}') handles '{event}', which it does not emit (declared: {})",
+                    declared.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// The event names a script passes to `emit("…")` as string literals,
+/// kebab→snake normalized the way the runtime normalizes them.
+fn emitted_events(script: &str) -> Vec<String> {
+    let mut events = Vec::new();
+    let mut rest = script;
+    while let Some(at) = rest.find("emit(") {
+        let preceded_by_ident = rest[..at]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        rest = &rest[at + "emit(".len()..];
+        if preceded_by_ident {
+            continue;
+        }
+        let Some(literal) = rest.trim_start().strip_prefix('"') else {
+            continue;
+        };
+        if let Some(end) = literal.find('"') {
+            let event = literal[..end].replace('-', "_");
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
+
+/// Checks the app's `<i18n>` catalogs: every message key the app uses
+/// (`${t('key')}` attributes, `t('key')` binding transforms) and every key
+/// another locale defines must be present in each locale. A missing key falls
+/// back to the default locale (or renders as the key), so these are warnings.
+fn i18n_lints(root: &Value) -> Vec<Diagnostic> {
+    let used = nemo_config::collect_message_keys(root);
+    let Some(messages) = Messages::from_config(root) else {
+        if used.is_empty() {
+            return Vec::new();
+        }
+        return vec![Diagnostic::warning(
+            "i18n-missing-catalog",
+            format!(
+                "The app translates {} message key(s) but declares no <i18n> catalogs",
+                used.len()
+            ),
+        )];
+    };
+    messages
+        .missing_keys(&used)
+        .into_iter()
+        .map(|(locale, key)| {
+            Diagnostic::warning(
+                "i18n-missing-key",
+                format!("Locale '{locale}' has no message '{key}'"),
+            )
+        })
+        .collect()
+}
+
+/// Checks the app's `<keymap>`: every key needs a handler, a parseable
+/// binding and condition, and no two keys may share a binding under the same
+/// condition. Keys that shadow a workspace shortcut or a `<commands>` key, and
+/// `focus:` conditions naming no component of the layout, a template or an SFC,
+/// are warnings.
+fn keymap_lints(root: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let entries = keymap_entries(root.get("keymap"));
+    if entries.is_empty() {
+        return diagnostics;
+    }
+
+    let mut component_ids = std::collections::HashSet::new();
+    let templates = root
+        .get("templates")
+        .and_then(|t| t.get("template"))
+        .and_then(|t| t.as_object())
+        .into_iter()
+        .flat_map(|templates| templates.values());
+    let sfc_templates = root
+        .get("sfc")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flat_map(|defs| defs.values())
+        .filter_map(|def| def.get("template"));
+    for node in root
+        .get("layout")
+        .into_iter()
+        .chain(templates)
+        .chain(sfc_templates)
+    {
+        collect_component_ids(node, &mut component_ids);
+    }
+    let builtins: Vec<_> = BUILTIN_SHORTCUTS
+        .iter()
+        .filter_map(|(name, keys)| Some((*name, parse_binding(keys).ok()?)))
+        .collect();
+    let command_keys: Vec<(String, Vec<gpui::Keystroke>)> = root
+        .get("commands")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let name = c.get("name").and_then(|v| v.as_str())?;
+            let keys = parse_binding(c.get("key").and_then(|v| v.as_str())?).ok()?;
+            Some((name.to_string(), keys))
+        })
+        .collect();
+
+    let mut seen: Vec<(Vec<gpui::Keystroke>, Option<String>, &str)> = Vec::new();
+    for entry in &entries {
+        let binding = entry.binding.as_str();
+        if entry.handler.is_none() {
+            diagnostics.push(Diagnostic::error(
+                "keymap-handler",
+                format!("Key '{binding}' has no handler"),
+            ));
+        }
+        if let Some(when) = entry.when.as_deref() {
+            if let Err(e) = context_predicate(when) {
+                diagnostics.push(Diagnostic::error(
+                    "keymap-when",
+                    format!("Key '{binding}': {e}"),
+                ));
+            }
+            for id in focus_ids(when).iter().filter(|id| !id.is_empty()) {
+                if !component_ids.contains(id.as_str()) {
+                    diagnostics.push(Diagnostic::warning(
+                        "keymap-unknown-focus",
+                        format!("Key '{binding}' depends on focus of unknown component '{id}'"),
+                    ));
+                }
+            }
+        }
+        let keys = match parse_binding(binding) {
+            Ok(keys) => keys,
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "keymap-binding",
+                    format!("Key '{binding}': {e}"),
+                ));
+                continue;
+            }
+        };
+
+        let when = entry
+            .when
+            .as_deref()
+            .map(|w| w.split_whitespace().collect::<String>());
+        if let Some((_, _, other)) = seen
+            .iter()
+            .find(|(k, w, _)| *w == when && same_binding(k, &keys))
+        {
+            diagnostics.push(Diagnostic::error(
+                "keymap-conflict",
+                format!("Key '{binding}' is bound more than once (also as '{other}')"),
+            ));
+        }
+        if when.is_none() {
+            if let Some((name, _)) = builtins.iter().find(|(_, k)| same_binding(k, &keys)) {
+                diagnostics.push(Diagnostic::warning(
+                    "keymap-shadows-builtin",
+                    format!("Key '{binding}' replaces the built-in '{name}' shortcut"),
+                ));
+            }
+        }
+        if let Some((name, _)) = command_keys.iter().find(|(_, k)| same_binding(k, &keys)) {
+            diagnostics.push(Diagnostic::warning(
+                "keymap-shadows-command",
+                format!("Key '{binding}' is also the key of command '{name}'"),
+            ));
+        }
+        seen.push((keys, when, binding));
+    }
+    diagnostics
+}
+
+/// Collects the ids of every component under a node's `component` maps.
+fn collect_component_ids<'a>(node: &'a Value, ids: &mut std::collections::HashSet<&'a str>) {
+    if let Some(children) = node.get("component").and_then(|c| c.as_object()) {
+        for (id, child) in children {
+            ids.insert(id.as_str());
+            collect_component_ids(child, ids);
+        }
+    }
+}
+
 fn render_human(
     path: &Path,
     source: &str,
+    profiles: &[Option<String>],
     diagnostics: &[Diagnostic],
     error_count: usize,
     warning_count: usize,
@@ -545,10 +906,27 @@ fn render_human(
             _ => d.file.clone().unwrap_or_default(),
         };
 
-        eprintln!("{}: [{}] {}", d.severity.label(), d.code, d.message);
+        match &d.profile {
+            Some(profile) => eprintln!(
+                "{}: [{}] {} (profile: {profile})",
+                d.severity.label(),
+                d.code,
+                d.message
+            ),
+            None => eprintln!("{}: [{}] {}", d.severity.label(), d.code, d.message),
+        }
         if !location.is_empty() {
             eprintln!("  --> {location}");
         }
+        // A profile overlay's diagnostics point into the overlay file.
+        let file_source;
+        let source = match &d.file {
+            Some(file) if Path::new(file) != path => {
+                file_source = std::fs::read_to_string(file).unwrap_or_default();
+                file_source.as_str()
+            }
+            _ => source,
+        };
         if let Some(line) = d.line {
             let loc = SourceLocation::new(
                 d.file.clone().unwrap_or_default(),
@@ -563,15 +941,38 @@ fn render_human(
     }
 
     if error_count == 0 && warning_count == 0 {
-        println!("{}: configuration is valid", path.display());
+        if profiles.iter().all(Option::is_none) {
+            println!("{}: configuration is valid", path.display());
+        } else {
+            println!(
+                "{}: configuration is valid with profiles: {}",
+                path.display(),
+                profile_labels(profiles).join(", ")
+            );
+        }
     } else {
         eprintln!("\n{error_count} error(s), {warning_count} warning(s)");
     }
 }
 
-fn render_json(path: &Path, diagnostics: &[Diagnostic], error_count: usize, warning_count: usize) {
+/// Names the checked profiles, `base` being the entry as written.
+fn profile_labels(profiles: &[Option<String>]) -> Vec<&str> {
+    profiles
+        .iter()
+        .map(|p| p.as_deref().unwrap_or("base"))
+        .collect()
+}
+
+fn render_json(
+    path: &Path,
+    profiles: &[Option<String>],
+    diagnostics: &[Diagnostic],
+    error_count: usize,
+    warning_count: usize,
+) {
     let report = serde_json::json!({
         "file": path.display().to_string(),
+        "profiles": profile_labels(profiles),
         "valid": error_count == 0,
         "errorCount": error_count,
         "warningCount": warning_count,
@@ -605,6 +1006,172 @@ mod tests {
         diags.iter().map(|d| d.code.as_str()).collect()
     }
 
+    #[test]
+    fn flags_keymap_problems() {
+        let value = parse(
+            r#"<nemo>
+                <commands><command name="Sync" key="ctrl-shift-s" on-run="sync" /></commands>
+                <keymap>
+                    <key binding="ctrl-s" handler="save" when="focus:editor" />
+                    <key binding="ctrl-s" handler="save_all" when="focus:editor" />
+                    <key binding="ctrl-s" handler="save_other" when="focus:other" />
+                    <key binding="ctrl-s" handler="save_anywhere" />
+                    <key binding="secondary-q" handler="quit" />
+                    <key binding="ctrl-shift-s" handler="sync_now" />
+                    <key binding="ctrl-foo-bar" handler="broken" />
+                    <key binding="f5" when="focus:editor &amp;&amp;" />
+                </keymap>
+                <layout type="stack"><stack id="outer"><input id="editor" /></stack></layout>
+            </nemo>"#,
+        );
+        let diags = keymap_lints(&value);
+        let count = |code: &str| codes(&diags).iter().filter(|c| **c == code).count();
+        // Only the second `focus:editor` ctrl-s conflicts; other conditions and
+        // the unconditional one are separate bindings.
+        assert_eq!(count("keymap-conflict"), 1, "{diags:?}");
+        assert_eq!(count("keymap-shadows-builtin"), 1, "{diags:?}");
+        assert_eq!(count("keymap-shadows-command"), 1, "{diags:?}");
+        assert_eq!(count("keymap-unknown-focus"), 1, "{diags:?}");
+        assert_eq!(count("keymap-binding"), 1, "{diags:?}");
+        assert_eq!(count("keymap-handler"), 1, "{diags:?}");
+        assert_eq!(count("keymap-when"), 1, "{diags:?}");
+
+        let clean =
+            parse(r#"<nemo><keymap><key binding="f5" handler="refresh" /></keymap></nemo>"#);
+        assert!(keymap_lints(&clean).is_empty());
+    }
+
+    #[test]
+    fn keymap_focus_finds_template_and_sfc_components() {
+        let dir = std::env::temp_dir().join(format!("nemo_validate_keymap_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("finder.nemo"),
+            r#"<template name="finder"><panel><input id="query" /></panel></template>"#,
+        )
+        .unwrap();
+        let value = ConfigurationLoader::new(std::sync::Arc::new(SchemaRegistry::new()))
+            .load_xml_string(
+                r#"<nemo>
+                    <imports><import src="./finder.nemo" /></imports>
+                    <templates>
+                        <template name="editor-pane"><panel><input id="editor" /></panel></template>
+                    </templates>
+                    <keymap>
+                        <key binding="ctrl-s" handler="save" when="focus:editor" />
+                        <key binding="ctrl-f" handler="find" when="focus:query" />
+                        <key binding="ctrl-g" handler="go" when="focus:nowhere" />
+                    </keymap>
+                    <layout type="stack"><finder id="f" /></layout>
+                </nemo>"#,
+                "t.xml",
+                Some(dir.as_path()),
+            )
+            .unwrap();
+        let diags = keymap_lints(&value);
+        assert_eq!(codes(&diags), vec!["keymap-unknown-focus"], "{diags:?}");
+        assert!(diags[0].message.contains("'nowhere'"), "{diags:?}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn checks_each_profile_combination() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("nemo.toml"),
+            "name = \"app\"\n\
+             [profiles.staging]\noverlays = [\"staging.xml\"]\n\
+             [profiles.broken]\noverlays = [\"missing.xml\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("staging.xml"),
+            r#"<nemo><variable name="host" default="staging" /></nemo>"#,
+        )
+        .unwrap();
+        let entry = dir.path().join("app.nemo");
+        std::fs::write(
+            &entry,
+            r#"<variable name="host" default="localhost" />
+               <template name="app"><stack id="root" /></template>"#,
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            app_config: entry.clone(),
+            strict: false,
+            format: ValidateFormat::Human,
+            profile: Vec::new(),
+            all_profiles: true,
+            overrides: Vec::new(),
+        };
+        let profiles = profiles_to_check(&args).unwrap();
+        assert_eq!(profile_labels(&profiles), ["base", "broken", "staging"]);
+
+        let loader = ConfigurationLoader::new(std::sync::Arc::new(SchemaRegistry::new()));
+        let mut failing = Vec::new();
+        for profile in &profiles {
+            loader.set_profile(profile.as_deref());
+            if !validate_entry(&loader, &entry, false).is_empty() {
+                failing.push(profile.clone());
+            }
+        }
+        assert_eq!(failing, [Some("broken".to_string())]);
+
+        loader.set_profile(Some("prod"));
+        assert_eq!(
+            codes(&validate_entry(&loader, &entry, false)),
+            ["unknown-profile"]
+        );
+    }
+
+    #[test]
+    fn flags_missing_message_keys_per_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let locales = dir.path().join("locales");
+        std::fs::create_dir(&locales).unwrap();
+        std::fs::write(
+            locales.join("en.ftl"),
+            "title = Inbox\nempty = Nothing here\n",
+        )
+        .unwrap();
+        std::fs::write(locales.join("de.ftl"), "title = Posteingang\n").unwrap();
+        let value = ConfigurationLoader::new(std::sync::Arc::new(SchemaRegistry::new()))
+            .load_xml_string(
+                r#"<nemo>
+                     <i18n dir="locales" />
+                     <layout type="stack">
+                       <label id="t" text="${t('title')}" />
+                       <label id="n" bind_text="data.count" />
+                       <label id="s" text="${t('status')}" />
+                     </layout>
+                   </nemo>"#,
+                "t.xml",
+                Some(dir.path()),
+            )
+            .unwrap();
+
+        let mut missing: Vec<String> = i18n_lints(&value)
+            .iter()
+            .inspect(|d| assert_eq!(d.code, "i18n-missing-key"))
+            .map(|d| d.message.clone())
+            .collect();
+        missing.sort();
+        assert_eq!(
+            missing,
+            [
+                "Locale 'de' has no message 'empty'",
+                "Locale 'de' has no message 'status'",
+                "Locale 'en' has no message 'status'",
+            ]
+        );
+
+        let uncatalogued = parse(
+            r#"<nemo><layout type="stack"><label id="t" text="${t('title')}" /></layout></nemo>"#,
+        );
+        assert_eq!(codes(&i18n_lints(&uncatalogued)), ["i18n-missing-catalog"]);
+    }
+
     #[test]
     fn flags_unknown_component_type() {
         let value = parse(r#"<nemo><layout type="stack"><notacomponent id="x" /></layout></nemo>"#);
@@ -612,6 +1179,30 @@ mod tests {
         assert!(codes(&diags).contains(&"unknown-component"), "{diags:?}");
     }
 
+    #[test]
+    fn flags_annotations_on_charts_that_do_not_draw_them() {
+        let value = parse(
+            r#"<nemo><layout type="stack">
+                <bar_chart id="bars" legend="true"><threshold id="limit" value="5" /></bar_chart>
+                <line_chart id="lines"><threshold id="max" value="5" /></line_chart>
+                <area_chart id="areas" legend="true"><band id="ok" from="1" to="2" /></area_chart>
+            </layout></nemo>"#,
+        );
+        let diags = lint_config(&value, &builtins());
+        let unsupported: Vec<_> = diags
+            .iter()
+            .filter(|d| d.code == "unsupported-annotation")
+            .collect();
+        assert_eq!(unsupported.len(), 1, "{diags:?}");
+        assert!(unsupported[0].message.contains("'limit' in 'bars'"));
+        let legends: Vec<_> = diags
+            .iter()
+            .filter(|d| d.code == "unsupported-legend")
+            .collect();
+        assert_eq!(legends.len(), 1, "{diags:?}");
+        assert!(legends[0].message.contains("'bars'"));
+    }
+
     #[test]
     fn flags_sfc_slot_violations() {
         let dir = std::env::temp_dir().join(format!("nemo_validate_slots_{}", std::process::id()));
@@ -670,6 +1261,51 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn flags_sfc_event_problems() {
+        let dir = std::env::temp_dir().join(format!("nemo_validate_emits_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("todo.nemo"),
+            r#"<emits><event name="archive" /></emits>
+               <template name="todo"><panel /></template>
+               <script>
+                 fn done(id, data) { emit("archive", id); emit( "item-moved", 1); }
+                 fn other(id, data) { re_emit("ignored"); }
+               </script>"#,
+        )
+        .unwrap();
+        let load = |xml: &str| {
+            ConfigurationLoader::new(std::sync::Arc::new(SchemaRegistry::new()))
+                .load_xml_string(xml, "t.xml", Some(dir.as_path()))
+                .unwrap()
+        };
+
+        let value = load(
+            r#"<nemo><imports><import src="./todo.nemo" /></imports>
+            <layout type="stack">
+              <todo id="a" on-archive="archived" />
+              <todo id="b" on-archived="archived" />
+            </layout></nemo>"#,
+        );
+        let diags = lint_config(&value, &builtins());
+        let with_code = |code: &str| -> Vec<&str> {
+            diags
+                .iter()
+                .filter(|d| d.code == code)
+                .map(|d| d.message.as_str())
+                .collect()
+        };
+        let undeclared = with_code("undeclared-emit");
+        assert_eq!(undeclared.len(), 1, "{diags:?}");
+        assert!(undeclared[0].contains("'item_moved'"), "{diags:?}");
+        let unknown = with_code("unknown-event");
+        assert_eq!(unknown.len(), 1, "{diags:?}");
+        assert!(unknown[0].contains("(id 'b')"), "{diags:?}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn flags_missing_required_sfc_prop_but_not_supplied() {
         let dir = std::env::temp_dir().join(format!("nemo_validate_sfc_{}", std::process::id()));
@@ -822,4 +1458,11 @@ mod tests {
         assert!(found.contains(&"missing-required"), "{diags:?}");
         assert!(found.contains(&"unknown-attribute"), "{diags:?}");
     }
+
+    #[test]
+    fn diagnostics_redact_resolved_secrets() {
+        nemo_config::register_redaction("pw-7c1f0e");
+        let d = Diagnostic::error("resolve", "login rejected pw-7c1f0e");
+        assert_eq!(d.message, "login rejected [redacted]");
+    }
 }
@@ -1,15 +1,24 @@
 use gpui::*;
 use gpui_component::chart::AreaChart as GpuiAreaChart;
+use gpui_component::plot::StrokeStyle;
 use nemo_config::Value;
 use nemo_macros::NemoComponent;
+use std::sync::{Arc, Mutex};
 
+use super::chart_annotations::Annotation;
 use super::chart_utils::{
     chart_color, empty_chart_placeholder, extract_data_array, get_f64_field, get_string_array,
-    get_string_field,
+    get_string_field, ChartView,
 };
+use super::time_series::{self, SeriesChart, SeriesSpec};
 
 /// An area chart component with filled regions under the lines.
 ///
+/// Setting `x-scale="time"`, `window`, `downsample` or `legend`, or giving it
+/// `<threshold>`/`<band>`/`<marker>` children, draws it with the time-series
+/// plot shared with `line_chart` instead (see [`super::time_series`] and
+/// [`super::chart_annotations`]).
+///
 /// # XML Configuration
 ///
 /// ```xml
@@ -23,6 +32,11 @@ use super::chart_utils::{
 /// | Property | Type | Description |
 /// |----------|------|-------------|
 /// | `x-field` | string | Data field for the x-axis |
+/// | `y-fields` | string[] | Data fields drawn as filled series |
+/// | `x-scale` | string | `"point"` (default) or `"time"` |
+/// | `window` | string | Only show the newest stretch, e.g. `"5m"` (implies a time axis) |
+/// | `downsample` | string | `"none"` (default), `"lttb"` or `"minmax"` |
+/// | `legend` | bool | Show a legend; clicking an entry hides or shows its series |
 /// | `fill-opacity` | float | Opacity of the filled area (0.0-1.0) |
 /// | `tick-margin` | int | Margin for axis tick labels |
 /// | `height` | int | Chart height in pixels |
@@ -36,6 +50,28 @@ pub struct AreaChart {
     tick_margin: Option<i64>,
     #[source]
     source: nemo_layout::BuiltComponent,
+    annotations: Vec<Annotation>,
+    view: Option<Arc<Mutex<ChartView>>>,
+    entity_id: Option<EntityId>,
+}
+
+impl AreaChart {
+    /// The chart's `<threshold>`/`<band>`/`<marker>` children.
+    pub fn annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// View state holding the series hidden from the legend.
+    pub fn view(mut self, view: Arc<Mutex<ChartView>>) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
 }
 
 impl RenderOnce for AreaChart {
@@ -50,9 +86,29 @@ impl RenderOnce for AreaChart {
             return empty_chart_placeholder(cx);
         }
 
-        let x_field = self.x_field.clone();
         let opacity = self.fill_opacity.unwrap_or(0.4) as f32;
 
+        if time_series::uses_series_plot(&self.source.properties) || !self.annotations.is_empty() {
+            let specs = y_fields
+                .into_iter()
+                .enumerate()
+                .map(|(i, field)| SeriesSpec {
+                    field,
+                    secondary: false,
+                    color: chart_color(i, cx),
+                })
+                .collect();
+            return SeriesChart::new(data, &self.source.properties, specs)
+                .stroke_style(StrokeStyle::Linear)
+                .tick_margin(self.tick_margin.unwrap_or(1) as usize)
+                .fill(opacity)
+                .annotations(self.annotations)
+                .view(self.view, self.entity_id)
+                .into_any_element();
+        }
+
+        let x_field = self.x_field.clone();
+
         let mut chart =
             GpuiAreaChart::new(data).x(move |item: &Value| get_string_field(item, &x_field));
 
@@ -0,0 +1,256 @@
+//! Declarative chart annotations: `<threshold>`, `<band>` and `<marker>`
+//! children of `line_chart`, `realtime_chart` and `area_chart`.
+//!
+//! Annotations are ordinary child components, so their attributes can be
+//! bound to data paths like any other property. The chart never renders them
+//! as elements; it reads them through [`collect`] and draws them with the
+//! time-series plot (see [`super::time_series`]).
+//!
+//! ```xml
+//! <realtime-chart id="boiler" x-field="ts" x-scale="time" y-fields='["temp"]'>
+//!   <threshold value="80" label="max" color="theme.danger">
+//!     <binding source="data.limits.temp_max" target="value" />
+//!   </threshold>
+//!   <band from="60" to="75" label="normal" color="theme.success" />
+//!   <band axis="x" from="2026-10-18T02:00:00Z" to="2026-10-18T03:00:00Z" label="maintenance" />
+//!   <marker at="2026-10-18T02:30:00Z" label="restart" />
+//! </realtime-chart>
+//! ```
+
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+use std::collections::HashMap;
+
+use super::time_series;
+
+/// Chart types that draw annotation children and a `legend`: the ones
+/// plotted on a continuous x axis by the time-series plot. Bar, column, pie
+/// and the other category charts are drawn by gpui-component on their own
+/// band scales and take neither.
+pub(crate) const ANNOTATED_CHARTS: &[&str] = &["line_chart", "realtime_chart", "area_chart"];
+
+/// Component types read as annotations.
+pub(crate) const ANNOTATION_TYPES: &[&str] = &["threshold", "band", "marker"];
+
+/// One annotation, with x positions still in `x-field` terms.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Annotation {
+    /// Horizontal line at a y value.
+    Threshold { value: f64, style: AnnotationStyle },
+    /// Shaded y range, or x range when `x_axis` is set.
+    Band {
+        from: Value,
+        to: Value,
+        x_axis: bool,
+        style: AnnotationStyle,
+    },
+    /// Vertical line at an x value.
+    Marker { at: Value, style: AnnotationStyle },
+}
+
+/// Label, colour and y-axis shared by every annotation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct AnnotationStyle {
+    pub label: Option<String>,
+    /// A `theme.*` reference or hex colour.
+    pub color: Option<String>,
+    /// Measured against the secondary y-axis (`axis="y2"`).
+    pub secondary: bool,
+}
+
+impl Annotation {
+    /// Reads a `threshold`, `band` or `marker` component; `None` for other
+    /// types or when a required position is missing.
+    pub(crate) fn from_component(component: &BuiltComponent) -> Option<Self> {
+        let props = &component.properties;
+        let text = |key: &str| {
+            props
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let axis = text("axis");
+        let style = AnnotationStyle {
+            label: text("label"),
+            color: text("color"),
+            secondary: axis.as_deref() == Some("y2"),
+        };
+        let position = |key: &str| props.get(key).filter(|v| !v.is_null()).cloned();
+        match component.component_type.as_str() {
+            "threshold" => Some(Annotation::Threshold {
+                value: number(props.get("value")?)?,
+                style,
+            }),
+            "band" => Some(Annotation::Band {
+                from: position("from")?,
+                to: position("to")?,
+                x_axis: axis.as_deref() == Some("x"),
+                style,
+            }),
+            "marker" => Some(Annotation::Marker {
+                at: position("at")?,
+                style,
+            }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn style(&self) -> &AnnotationStyle {
+        match self {
+            Annotation::Threshold { style, .. }
+            | Annotation::Band { style, .. }
+            | Annotation::Marker { style, .. } => style,
+        }
+    }
+
+    /// The y values this annotation needs on screen, with whether they are
+    /// on the secondary axis.
+    pub(crate) fn y_values(&self) -> Vec<(f64, bool)> {
+        match self {
+            Annotation::Threshold { value, style } => vec![(*value, style.secondary)],
+            Annotation::Band {
+                from,
+                to,
+                x_axis: false,
+                style,
+            } => [from, to]
+                .into_iter()
+                .filter_map(number)
+                .map(|v| (v, style.secondary))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A number, or a string holding one (attributes and bound values alike).
+pub(crate) fn number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// The annotations among a chart's children, in document order.
+pub(crate) fn collect(
+    chart: &BuiltComponent,
+    components: &HashMap<String, BuiltComponent>,
+) -> Vec<Annotation> {
+    chart
+        .children
+        .iter()
+        .filter_map(|id| components.get(id))
+        .filter(|child| child.properties.get("visible").and_then(|v| v.as_bool()) != Some(false))
+        .filter_map(Annotation::from_component)
+        .collect()
+}
+
+/// Resolves an annotation's x position to plot units: a timestamp on a time
+/// axis; otherwise the index of the item whose `x-field` label matches, or
+/// a number taken as an index.
+pub(crate) fn resolve_x(value: &Value, time: bool, labels: &[String]) -> Option<f64> {
+    if time {
+        return time_series::parse_time(value);
+    }
+    match value {
+        Value::String(s) => labels
+            .iter()
+            .position(|label| label == s)
+            .map(|i| i as f64)
+            .or_else(|| s.trim().parse().ok()),
+        other => other.as_f64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_component() {
+        let threshold = BuiltComponent::new("max", "threshold").with_props([
+            ("value", Value::String("80".into())),
+            ("label", Value::String("max".into())),
+            ("color", Value::String("theme.danger".into())),
+            ("axis", Value::String("y2".into())),
+        ]);
+        let style = AnnotationStyle {
+            label: Some("max".into()),
+            color: Some("theme.danger".into()),
+            secondary: true,
+        };
+        assert_eq!(
+            Annotation::from_component(&threshold),
+            Some(Annotation::Threshold { value: 80.0, style })
+        );
+
+        let band = BuiltComponent::new("normal", "band")
+            .with_props([("from", Value::Integer(60)), ("to", Value::Float(75.5))]);
+        let band = Annotation::from_component(&band).unwrap();
+        assert_eq!(band.y_values(), vec![(60.0, false), (75.5, false)]);
+
+        let x_band = BuiltComponent::new("spring", "band").with_props([
+            ("axis", Value::String("x".into())),
+            ("from", Value::String("Feb".into())),
+            ("to", Value::String("Mar".into())),
+        ]);
+        assert!(Annotation::from_component(&x_band)
+            .unwrap()
+            .y_values()
+            .is_empty());
+
+        // Missing positions (an unresolved binding) drop the annotation.
+        assert_eq!(
+            Annotation::from_component(&BuiltComponent::new("t", "threshold")),
+            None
+        );
+        assert_eq!(
+            Annotation::from_component(
+                &BuiltComponent::new("m", "marker").with_props([("at", Value::Null)])
+            ),
+            None
+        );
+        assert_eq!(
+            Annotation::from_component(&BuiltComponent::new("l", "label")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_collect_skips_hidden_and_other_children() {
+        let chart =
+            BuiltComponent::new("chart", "line_chart").with_children(["t", "l", "m", "gone"]);
+        let mut components = HashMap::new();
+        components.insert(
+            "t".to_string(),
+            BuiltComponent::new("t", "threshold").with_props([("value", Value::Integer(5))]),
+        );
+        components.insert("l".to_string(), BuiltComponent::new("l", "label"));
+        components.insert(
+            "m".to_string(),
+            BuiltComponent::new("m", "marker")
+                .with_props([("at", Value::Integer(3)), ("visible", Value::Bool(false))]),
+        );
+        let annotations = collect(&chart, &components);
+        assert_eq!(annotations.len(), 1);
+        assert!(matches!(annotations[0], Annotation::Threshold { value, .. } if value == 5.0));
+    }
+
+    #[test]
+    fn test_resolve_x() {
+        let labels = vec!["Jan".to_string(), "Feb".to_string()];
+        assert_eq!(
+            resolve_x(&Value::String("Feb".into()), false, &labels),
+            Some(1.0)
+        );
+        assert_eq!(resolve_x(&Value::Integer(0), false, &labels), Some(0.0));
+        assert_eq!(
+            resolve_x(&Value::String("Dec".into()), false, &labels),
+            None
+        );
+        assert_eq!(
+            resolve_x(&Value::String("1970-01-01T00:00:01Z".into()), true, &[]),
+            Some(1000.0)
+        );
+    }
+}
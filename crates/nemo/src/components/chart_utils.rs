@@ -1,7 +1,14 @@
 use gpui::*;
-use gpui_component::ActiveTheme;
+use gpui_component::plot::AXIS_GAP;
+use gpui_component::{ActiveTheme, Sizable};
 use nemo_config::Value;
 use nemo_layout::BuiltComponent;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use super::time_series;
+use crate::runtime::NemoRuntime;
 
 /// Extracts the `data` property from a component as a `Vec<Value>`.
 pub(crate) fn extract_data_array(source: &BuiltComponent) -> Vec<Value> {
@@ -66,3 +73,742 @@ pub(crate) fn empty_chart_placeholder(cx: &App) -> AnyElement {
         .child("No data")
         .into_any_element()
 }
+
+// ── Interaction ─────────────────────────────────────────────────────────
+
+/// Smallest drag, in pixels, treated as a range selection rather than a click.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Band padding of gpui-component's bar charts (matched by the column charts).
+const BAND_PADDING_INNER: f32 = 0.4;
+const BAND_PADDING_OUTER: f32 = 0.2;
+
+/// How a chart lays its items out along the x axis. The interaction layer
+/// uses this to map the pointer to the nearest item without the chart's
+/// own scales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum XLayout {
+    /// Evenly spaced points from edge to edge (line, area, realtime).
+    Points,
+    /// One padded band per item (bar, column, candlestick).
+    Bands,
+    /// Numeric `x-field` values on a padded linear scale right of the
+    /// y-axis gap (scatter, bubble).
+    Linear,
+    /// Parsed `x-field` timestamps from edge to edge (line and realtime
+    /// charts with a time axis).
+    Time,
+}
+
+impl XLayout {
+    /// The layout of an interactive chart; `None` for charts without a
+    /// horizontal x axis (pie, funnel, radar, horizontal bars, ...).
+    pub(crate) fn for_component(source: &BuiltComponent) -> Option<Self> {
+        match source.component_type.as_str() {
+            "line_chart" | "realtime_chart" if time_series::is_time_axis(&source.properties) => {
+                Some(XLayout::Time)
+            }
+            "line_chart" | "area_chart" | "realtime_chart" => Some(XLayout::Points),
+            "bar_chart"
+            | "column_chart"
+            | "candlestick_chart"
+            | "stacked_column_chart"
+            | "clustered_column_chart" => Some(XLayout::Bands),
+            "scatter_chart" | "bubble_chart" => Some(XLayout::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// Each item's position on the zoomable x axis: its index for point and
+/// band charts, its `x_field` value for linear ones and its timestamp in
+/// milliseconds for time ones (NaN when it has none).
+pub(crate) fn x_values(layout: XLayout, data: &[Value], x_field: &str) -> Vec<f64> {
+    match layout {
+        XLayout::Linear => data
+            .iter()
+            .map(|item| get_f64_field(item, x_field))
+            .collect(),
+        XLayout::Time => data
+            .iter()
+            .map(|item| {
+                item.get(x_field)
+                    .and_then(time_series::parse_time)
+                    .unwrap_or(f64::NAN)
+            })
+            .collect(),
+        _ => (0..data.len()).map(|i| i as f64).collect(),
+    }
+}
+
+/// Indices of the items inside the zoom window (all of them when unzoomed).
+pub(crate) fn visible_indices(values: &[f64], zoom: Option<(f64, f64)>) -> Vec<usize> {
+    (0..values.len())
+        .filter(|&i| zoom.is_none_or(|(lo, hi)| values[i] >= lo && values[i] <= hi))
+        .collect()
+}
+
+/// Smallest and largest finite value, or `None` when there is none.
+pub(crate) fn extent(values: &[f64]) -> Option<(f64, f64)> {
+    values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(None, |acc, &v| match acc {
+            None => Some((v, v)),
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+        })
+}
+
+/// Moves a zoom window by `fraction` of its span, kept inside `extent`.
+pub(crate) fn pan_zoom(zoom: (f64, f64), extent: (f64, f64), fraction: f64) -> (f64, f64) {
+    let span = zoom.1 - zoom.0;
+    let shift = (span * fraction)
+        .max(extent.0 - zoom.0)
+        .min(extent.1 - zoom.1);
+    (zoom.0 + shift, zoom.1 + shift)
+}
+
+/// Scales the zoom window about `anchor` by `factor` (below 1 zooms in).
+/// Returns `None` once the window covers the whole extent.
+pub(crate) fn scale_zoom(
+    zoom: Option<(f64, f64)>,
+    extent: (f64, f64),
+    anchor: f64,
+    factor: f64,
+) -> Option<(f64, f64)> {
+    let (lo, hi) = zoom.unwrap_or(extent);
+    let lo = (anchor - (anchor - lo) * factor).max(extent.0);
+    let hi = (anchor + (hi - anchor) * factor).min(extent.1);
+    if lo <= extent.0 && hi >= extent.1 {
+        None
+    } else {
+        Some((lo, hi))
+    }
+}
+
+/// Maps between the visible items of a chart and horizontal pixel offsets
+/// from its left edge, mirroring the scales the charts paint with.
+#[derive(Debug, Clone)]
+pub(crate) struct ChartGeometry {
+    layout: XLayout,
+    xs: Vec<f64>,
+}
+
+impl ChartGeometry {
+    /// `xs` are the visible items' [`x_values`], in render order.
+    pub(crate) fn new(layout: XLayout, xs: Vec<f64>) -> Self {
+        Self { layout, xs }
+    }
+
+    /// The padded domain scatter and bubble charts scale x over.
+    fn linear_domain(&self) -> (f64, f64) {
+        let (lo, hi) = extent(&self.xs).unwrap_or((0.0, 1.0));
+        let pad = (hi - lo).max(1.0) * 0.05;
+        (lo - pad, hi + pad)
+    }
+
+    /// Band start offset and step for `width`.
+    fn bands(&self, width: f32) -> (f32, f32) {
+        let n = self.xs.len() as f32;
+        let step = width / (n - BAND_PADDING_INNER + BAND_PADDING_OUTER * 2.0).max(1.0);
+        let bandwidth = step * (1.0 - BAND_PADDING_INNER);
+        (step * BAND_PADDING_OUTER + bandwidth / 2.0, step)
+    }
+
+    /// Horizontal centre of visible item `k`.
+    pub(crate) fn position(&self, k: usize, width: f32) -> f32 {
+        let n = self.xs.len();
+        match self.layout {
+            XLayout::Points if n <= 1 => width / 2.0,
+            XLayout::Points => width * k as f32 / (n - 1) as f32,
+            XLayout::Bands => {
+                let (start, step) = self.bands(width);
+                start + step * k as f32
+            }
+            XLayout::Linear => {
+                let (lo, hi) = self.linear_domain();
+                let plot = width - AXIS_GAP;
+                AXIS_GAP + ((self.xs[k] - lo) / (hi - lo)) as f32 * plot
+            }
+            XLayout::Time => match extent(&self.xs) {
+                Some((lo, hi)) if hi > lo => ((self.xs[k] - lo) / (hi - lo)) as f32 * width,
+                _ => width / 2.0,
+            },
+        }
+    }
+
+    /// The x value under pixel offset `x` (the inverse of [`Self::position`]).
+    pub(crate) fn value_at(&self, x: f32, width: f32) -> f64 {
+        let n = self.xs.len();
+        let first = self.xs.first().copied().unwrap_or(0.0);
+        match self.layout {
+            XLayout::Points if n <= 1 || width <= 0.0 => first,
+            XLayout::Points => first + (x / width * (n - 1) as f32) as f64,
+            XLayout::Bands => {
+                let (start, step) = self.bands(width);
+                first + ((x - start) / step) as f64
+            }
+            XLayout::Linear => {
+                let (lo, hi) = self.linear_domain();
+                let plot = (width - AXIS_GAP).max(1.0);
+                lo + ((x - AXIS_GAP) / plot) as f64 * (hi - lo)
+            }
+            XLayout::Time => match extent(&self.xs) {
+                Some((lo, hi)) if width > 0.0 => lo + (x / width) as f64 * (hi - lo),
+                _ => first,
+            },
+        }
+    }
+
+    /// The visible item whose centre is closest to pixel offset `x`.
+    pub(crate) fn nearest(&self, x: f32, width: f32) -> Option<usize> {
+        (0..self.xs.len()).min_by(|&a, &b| {
+            let da = (self.position(a, width) - x).abs();
+            let db = (self.position(b, width) - x).abs();
+            da.total_cmp(&db)
+        })
+    }
+}
+
+/// Fields listed in a tooltip: `x_field` first, then every other `*_field`
+/// and `*_fields` property in name order.
+pub(crate) fn tooltip_fields(source: &BuiltComponent) -> Vec<String> {
+    let props = &source.properties;
+    let x_field = props.get("x_field").and_then(|v| v.as_str());
+    let mut keys: Vec<&String> = props
+        .keys()
+        .filter(|k| k.ends_with("_field") || k.ends_with("_fields"))
+        .filter(|k| k.as_str() != "x_field")
+        .collect();
+    keys.sort();
+
+    let mut fields: Vec<String> = x_field.map(str::to_string).into_iter().collect();
+    for key in keys {
+        let names = match props.get(key.as_str()) {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        for name in names {
+            if !name.is_empty() && !fields.contains(&name) {
+                fields.push(name);
+            }
+        }
+    }
+    fields
+}
+
+/// `on-range-select` payload: the selected items and the `x_field` values of
+/// the first and last.
+pub(crate) fn range_payload(items: &[&Value], x_field: &str) -> Value {
+    let bound = |item: Option<&&Value>| {
+        item.and_then(|v| v.get(x_field))
+            .cloned()
+            .unwrap_or(Value::Null)
+    };
+    let mut payload = indexmap::IndexMap::new();
+    payload.insert("start".to_string(), bound(items.first()));
+    payload.insert("end".to_string(), bound(items.last()));
+    payload.insert(
+        "items".to_string(),
+        Value::Array(items.iter().map(|v| (*v).clone()).collect()),
+    );
+    Value::Object(payload)
+}
+
+/// Pointer state of an interactive chart, kept across renders.
+#[derive(Debug, Default)]
+pub(crate) struct ChartView {
+    /// Zoom window in [`x_values`] units; `None` shows everything.
+    pub zoom: Option<(f64, f64)>,
+    /// Hovered item, as an index into the visible items.
+    hover: Option<usize>,
+    /// In-progress drag as (start, current) pixel offsets.
+    drag: Option<(f32, f32)>,
+    /// Last painted bounds, to turn window coordinates into offsets.
+    bounds: Option<Bounds<Pixels>>,
+    /// Series fields switched off in the legend.
+    pub hidden: HashSet<String>,
+}
+
+/// Everything the pointer handlers of one chart need.
+struct ChartPointer {
+    view: Arc<Mutex<ChartView>>,
+    geometry: ChartGeometry,
+    data: Arc<Vec<Value>>,
+    values: Vec<f64>,
+    visible: Vec<usize>,
+    x_field: String,
+    component_id: String,
+    point_handler: Option<String>,
+    range_handler: Option<String>,
+    runtime: Arc<NemoRuntime>,
+    entity_id: EntityId,
+}
+
+impl ChartPointer {
+    /// Pixel offset of a window position and the chart width.
+    fn offset(&self, position: Point<Pixels>) -> Option<(f32, f32)> {
+        let bounds = self.view.lock().unwrap().bounds?;
+        let width = bounds.size.width.as_f32();
+        let x = (position.x - bounds.origin.x).as_f32().clamp(0.0, width);
+        Some((x, width))
+    }
+
+    fn mouse_move(&self, event: &MouseMoveEvent, cx: &mut App) {
+        let Some((x, width)) = self.offset(event.position) else {
+            return;
+        };
+        let mut view = self.view.lock().unwrap();
+        if let Some((start, _)) = view.drag {
+            if event.pressed_button == Some(MouseButton::Left) {
+                view.drag = Some((start, x));
+                cx.notify(self.entity_id);
+                return;
+            }
+            view.drag = None;
+        }
+        let hover = self.geometry.nearest(x, width);
+        if view.hover != hover {
+            view.hover = hover;
+            cx.notify(self.entity_id);
+        }
+    }
+
+    fn mouse_down(&self, event: &MouseDownEvent, cx: &mut App) {
+        let Some((x, _)) = self.offset(event.position) else {
+            return;
+        };
+        let mut view = self.view.lock().unwrap();
+        if event.click_count >= 2 {
+            view.zoom = None;
+            view.drag = None;
+            view.hover = None;
+            cx.notify(self.entity_id);
+        } else {
+            view.drag = Some((x, x));
+        }
+    }
+
+    fn mouse_up(&self, cx: &mut App) {
+        let Some((start, end)) = self.view.lock().unwrap().drag.take() else {
+            return;
+        };
+        let Some(width) = self
+            .view
+            .lock()
+            .unwrap()
+            .bounds
+            .map(|b| b.size.width.as_f32())
+        else {
+            return;
+        };
+
+        if (end - start).abs() < DRAG_THRESHOLD {
+            let Some(k) = self.geometry.nearest(start, width) else {
+                return;
+            };
+            if let Some(handler) = &self.point_handler {
+                let item = &self.data[self.visible[k]];
+                let payload = serde_json::to_string(item).unwrap_or_default();
+                self.runtime
+                    .call_handler(handler, &self.component_id, &payload);
+            }
+            cx.notify(self.entity_id);
+            return;
+        }
+
+        let a = self.geometry.value_at(start.min(end), width);
+        let b = self.geometry.value_at(start.max(end), width);
+        let selected: Vec<usize> = self
+            .visible
+            .iter()
+            .copied()
+            .filter(|&i| self.values[i] >= a && self.values[i] <= b)
+            .collect();
+        let selected_values: Vec<f64> = selected.iter().map(|&i| self.values[i]).collect();
+        if let Some(window) = extent(&selected_values) {
+            let mut view = self.view.lock().unwrap();
+            view.zoom = Some(window);
+            view.hover = None;
+        }
+        if let (Some(handler), false) = (&self.range_handler, selected.is_empty()) {
+            let items: Vec<&Value> = selected.iter().map(|&i| &self.data[i]).collect();
+            let payload = range_payload(&items, &self.x_field);
+            let payload = serde_json::to_string(&payload).unwrap_or_default();
+            self.runtime
+                .call_handler(handler, &self.component_id, &payload);
+        }
+        cx.notify(self.entity_id);
+    }
+
+    /// Wheel pans a zoomed chart; with the secondary modifier (cmd/ctrl) it
+    /// zooms about the pointer.
+    fn scroll(&self, event: &ScrollWheelEvent, window: &Window, cx: &mut App) {
+        let Some(full) = extent(&self.values) else {
+            return;
+        };
+        let Some((x, width)) = self.offset(event.position) else {
+            return;
+        };
+        let delta = event.delta.pixel_delta(window.line_height());
+        let mut view = self.view.lock().unwrap();
+        if event.modifiers.secondary() {
+            let factor = if delta.y > px(0.) { 0.8 } else { 1.25 };
+            let anchor = self.geometry.value_at(x, width);
+            view.zoom = scale_zoom(view.zoom, full, anchor, factor);
+        } else if let Some(zoom) = view.zoom {
+            let d = if delta.x.abs() > delta.y.abs() {
+                delta.x
+            } else {
+                delta.y
+            };
+            view.zoom = Some(pan_zoom(zoom, full, -(d.as_f32() / width.max(1.0)) as f64));
+        } else {
+            return;
+        }
+        view.hover = None;
+        cx.stop_propagation();
+        cx.notify(self.entity_id);
+    }
+}
+
+/// Wraps a rendered chart with the shared interaction layer: hover tooltip
+/// and crosshair on the nearest item, drag-to-zoom (double-click or the reset
+/// button to undo), wheel panning, and the `on-point-click` /
+/// `on-range-select` events.
+///
+/// The chart itself must already be rendered from the visible items only;
+/// zooming works by narrowing the data the chart is given.
+#[derive(IntoElement)]
+pub(crate) struct InteractiveChart {
+    source: BuiltComponent,
+    chart: AnyElement,
+    layout: XLayout,
+    data: Arc<Vec<Value>>,
+    values: Vec<f64>,
+    visible: Vec<usize>,
+    view: Arc<Mutex<ChartView>>,
+    runtime: Option<Arc<NemoRuntime>>,
+    entity_id: Option<EntityId>,
+}
+
+impl InteractiveChart {
+    /// `data` is the full data set with its [`x_values`], and `visible` the
+    /// indices the chart was rendered from.
+    pub(crate) fn new(
+        source: BuiltComponent,
+        chart: AnyElement,
+        layout: XLayout,
+        data: Arc<Vec<Value>>,
+        values: Vec<f64>,
+        visible: Vec<usize>,
+        view: Arc<Mutex<ChartView>>,
+    ) -> Self {
+        Self {
+            source,
+            chart,
+            layout,
+            data,
+            values,
+            visible,
+            view,
+            runtime: None,
+            entity_id: None,
+        }
+    }
+
+    pub(crate) fn runtime(mut self, runtime: Arc<NemoRuntime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    pub(crate) fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
+}
+
+/// The hover tooltip: the item's x value (a formatted date-time on a time
+/// axis), then its [`tooltip_fields`].
+fn tooltip(source: &BuiltComponent, layout: XLayout, item: &Value, cx: &App) -> Div {
+    use crate::theme::tokens::{font_size, radius_of, FontSize, Space, TokenStyled};
+    let mut rows = div()
+        .flex()
+        .flex_col()
+        .gap_t(Space::Xs)
+        .p_t(Space::Sm)
+        .min_w(px(96.))
+        .bg(cx.theme().colors.background)
+        .border_1()
+        .border_color(cx.theme().colors.border)
+        .rounded(radius_of("md", cx))
+        .shadow_md()
+        .text_size(font_size(FontSize::Xs));
+    for (i, field) in tooltip_fields(source).into_iter().enumerate() {
+        let time = (i == 0 && layout == XLayout::Time)
+            .then(|| item.get(&field).and_then(time_series::parse_time))
+            .flatten();
+        let value = match time {
+            Some(ms) => time_series::format_timestamp(ms),
+            None => item.get(&field).map(|v| v.to_string()).unwrap_or_default(),
+        };
+        rows = rows.child(if i == 0 {
+            div().font_weight(FontWeight::SEMIBOLD).child(value)
+        } else {
+            div()
+                .flex()
+                .justify_between()
+                .gap_t(Space::Md)
+                .child(
+                    div()
+                        .text_color(cx.theme().colors.muted_foreground)
+                        .child(field),
+                )
+                .child(value)
+        });
+    }
+    rows
+}
+
+impl RenderOnce for InteractiveChart {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let geometry = ChartGeometry::new(
+            self.layout,
+            self.visible.iter().map(|&i| self.values[i]).collect(),
+        );
+        let (hover, drag, zoomed, bounds) = {
+            let view = self.view.lock().unwrap();
+            (view.hover, view.drag, view.zoom.is_some(), view.bounds)
+        };
+        let width = bounds.map(|b| b.size.width.as_f32()).unwrap_or(0.0);
+        let id = self.source.id.clone();
+
+        let bounds_view = Arc::clone(&self.view);
+        let mut root = div()
+            .id(SharedString::from(format!("{}-interaction", id)))
+            .relative()
+            .size_full()
+            .child(
+                canvas(
+                    move |bounds, _window, _cx| bounds_view.lock().unwrap().bounds = Some(bounds),
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .child(self.chart);
+
+        let hovered = hover
+            .filter(|_| drag.is_none() && width > 0.0)
+            .and_then(|k| Some((k, self.data.get(*self.visible.get(k)?)?)));
+        if let Some((k, item)) = hovered {
+            let x = geometry.position(k, width);
+            root = root.child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .bottom(px(AXIS_GAP))
+                    .left(px(x))
+                    .w(px(1.))
+                    .bg(cx.theme().colors.muted_foreground),
+            );
+            let tooltip = tooltip(&self.source, self.layout, item, cx)
+                .absolute()
+                .top(px(4.));
+            root = root.child(if x > width / 2.0 {
+                tooltip.right(px(width - x + 8.))
+            } else {
+                tooltip.left(px(x + 8.))
+            });
+        }
+        if let Some((start, end)) = drag.filter(|(a, b)| (a - b).abs() >= DRAG_THRESHOLD) {
+            root = root.child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .bottom(px(AXIS_GAP))
+                    .left(px(start.min(end)))
+                    .w(px((end - start).abs()))
+                    .bg(cx.theme().colors.accent.opacity(0.3)),
+            );
+        }
+
+        let (Some(runtime), Some(entity_id)) = (self.runtime, self.entity_id) else {
+            return root;
+        };
+        if zoomed {
+            let view = Arc::clone(&self.view);
+            root = root.child(
+                div()
+                    .absolute()
+                    .top(px(4.))
+                    .right(px(4.))
+                    // Keep the click from starting a drag on the chart.
+                    .on_mouse_down(MouseButton::Left, |_, _window, cx| cx.stop_propagation())
+                    .child(
+                        gpui_component::button::Button::new(SharedString::from(format!(
+                            "{}-reset-zoom",
+                            id
+                        )))
+                        .label("Reset zoom")
+                        .xsmall()
+                        .on_click(move |_, _window, cx| {
+                            view.lock().unwrap().zoom = None;
+                            cx.notify(entity_id);
+                        }),
+                    ),
+            );
+        }
+
+        let pointer = Rc::new(ChartPointer {
+            view: Arc::clone(&self.view),
+            geometry,
+            data: self.data,
+            values: self.values,
+            visible: self.visible,
+            x_field: self
+                .source
+                .properties
+                .get("x_field")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            component_id: id,
+            point_handler: self.source.handlers.get("point_click").cloned(),
+            range_handler: self.source.handlers.get("range_select").cloned(),
+            runtime,
+            entity_id,
+        });
+
+        let on_move = Rc::clone(&pointer);
+        let on_down = Rc::clone(&pointer);
+        let on_up = Rc::clone(&pointer);
+        let on_up_out = Rc::clone(&pointer);
+        let on_scroll = Rc::clone(&pointer);
+        root.on_mouse_move(move |event, _window, cx| on_move.mouse_move(event, cx))
+            .on_mouse_down(MouseButton::Left, move |event, _window, cx| {
+                on_down.mouse_down(event, cx)
+            })
+            .on_mouse_up(MouseButton::Left, move |_, _window, cx| on_up.mouse_up(cx))
+            .on_mouse_up_out(MouseButton::Left, move |_, _window, cx| {
+                on_up_out.mouse_up(cx)
+            })
+            .on_scroll_wheel(move |event, window, cx| on_scroll.scroll(event, window, cx))
+            .on_hover(move |hovered, _window, cx| {
+                if !hovered {
+                    let mut view = pointer.view.lock().unwrap();
+                    if view.hover.take().is_some() {
+                        cx.notify(pointer.entity_id);
+                    }
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_indices_and_extent() {
+        let values = [3.0, 1.0, 4.0, 1.5, 9.0];
+        assert_eq!(visible_indices(&values, None), vec![0, 1, 2, 3, 4]);
+        assert_eq!(visible_indices(&values, Some((1.0, 3.0))), vec![0, 1, 3]);
+        assert_eq!(extent(&values), Some((1.0, 9.0)));
+        assert_eq!(extent(&[]), None);
+        assert_eq!(extent(&[f64::NAN, 2.0]), Some((2.0, 2.0)));
+    }
+
+    #[test]
+    fn test_pan_and_scale_zoom_stay_in_extent() {
+        let full = (0.0, 100.0);
+        assert_eq!(pan_zoom((10.0, 30.0), full, 0.5), (20.0, 40.0));
+        assert_eq!(pan_zoom((10.0, 30.0), full, -1.0), (0.0, 20.0));
+        assert_eq!(pan_zoom((70.0, 90.0), full, 1.0), (80.0, 100.0));
+
+        assert_eq!(scale_zoom(None, full, 50.0, 0.5), Some((25.0, 75.0)));
+        assert_eq!(
+            scale_zoom(Some((25.0, 75.0)), full, 25.0, 0.5),
+            Some((25.0, 50.0))
+        );
+        // Zooming out past the extent resets.
+        assert_eq!(scale_zoom(Some((25.0, 75.0)), full, 50.0, 4.0), None);
+    }
+
+    #[test]
+    fn test_geometry_points_and_bands() {
+        let points = ChartGeometry::new(XLayout::Points, vec![10.0, 11.0, 12.0, 13.0, 14.0]);
+        assert_eq!(points.position(0, 400.0), 0.0);
+        assert_eq!(points.position(4, 400.0), 400.0);
+        assert_eq!(points.nearest(140.0, 400.0), Some(1));
+        assert_eq!(points.value_at(200.0, 400.0), 12.0);
+
+        let bands = ChartGeometry::new(XLayout::Bands, vec![0.0, 1.0, 2.0, 3.0]);
+        // step = 400 / (4 - 0.4 + 0.4) = 100; first centre = 20 + 30.
+        assert_eq!(bands.position(0, 400.0), 50.0);
+        assert_eq!(bands.position(3, 400.0), 350.0);
+        assert_eq!(bands.nearest(260.0, 400.0), Some(2));
+        assert_eq!(bands.value_at(150.0, 400.0), 1.0);
+    }
+
+    #[test]
+    fn test_geometry_linear_round_trips() {
+        let linear = ChartGeometry::new(XLayout::Linear, vec![0.0, 50.0, 100.0]);
+        let width = 400.0;
+        for k in 0..3 {
+            let x = linear.position(k, width);
+            assert!((linear.value_at(x, width) - linear.xs[k]).abs() < 1e-3);
+            assert_eq!(linear.nearest(x + 1.0, width), Some(k));
+        }
+        // The y-axis gap sits left of the padded domain.
+        assert!(linear.position(0, width) > AXIS_GAP);
+    }
+
+    #[test]
+    fn test_geometry_time_spans_the_width() {
+        // Unevenly spaced samples sit at their time, not their index.
+        let time = ChartGeometry::new(XLayout::Time, vec![1000.0, 1100.0, 2000.0]);
+        assert_eq!(time.position(0, 400.0), 0.0);
+        assert_eq!(time.position(1, 400.0), 40.0);
+        assert_eq!(time.position(2, 400.0), 400.0);
+        assert_eq!(time.value_at(200.0, 400.0), 1500.0);
+        assert_eq!(time.nearest(150.0, 400.0), Some(1));
+        let single = ChartGeometry::new(XLayout::Time, vec![1000.0]);
+        assert_eq!(single.position(0, 400.0), 200.0);
+    }
+
+    #[test]
+    fn test_tooltip_fields_and_range_payload() {
+        let source = BuiltComponent::new("c", "stacked_column_chart").with_props([
+            ("x_field", Value::String("month".into())),
+            (
+                "y_fields",
+                Value::Array(vec![Value::String("a".into()), Value::String("b".into())]),
+            ),
+            ("label_field", Value::String("note".into())),
+        ]);
+        assert_eq!(tooltip_fields(&source), vec!["month", "note", "a", "b"]);
+
+        let item = |m: &str, v: i64| {
+            let mut obj = indexmap::IndexMap::new();
+            obj.insert("month".to_string(), Value::String(m.into()));
+            obj.insert("v".to_string(), Value::Integer(v));
+            Value::Object(obj)
+        };
+        let (jan, feb) = (item("jan", 1), item("feb", 2));
+        let payload = range_payload(&[&jan, &feb], "month");
+        assert_eq!(payload.get("start"), Some(&Value::String("jan".into())));
+        assert_eq!(payload.get("end"), Some(&Value::String("feb".into())));
+        assert_eq!(
+            payload
+                .get("items")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len()),
+            Some(2)
+        );
+    }
+}
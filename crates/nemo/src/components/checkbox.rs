@@ -1,6 +1,7 @@
 use gpui::*;
 use gpui_component::checkbox::Checkbox as GpuiCheckbox;
 use gpui_component::Disableable;
+use nemo_config::Value;
 use nemo_macros::NemoComponent;
 use std::sync::Arc;
 
@@ -64,14 +65,17 @@ impl RenderOnce for Checkbox {
             cb = cb.disabled(true);
         }
 
-        if let Some(handler) = change_handler {
-            if let (Some(runtime), Some(entity_id)) = (self.runtime, self.entity_id) {
-                cb = cb.on_click(move |new_checked, _window, cx| {
+        // Clicking writes `checked` back (so the box toggles without a handler
+        // and a `<form>` reads its state), then fires `on-change`.
+        if let (Some(runtime), Some(entity_id)) = (self.runtime, self.entity_id) {
+            cb = cb.on_click(move |new_checked, _window, cx| {
+                runtime.write_back_property(&component_id, "checked", Value::Bool(*new_checked));
+                if let Some(handler) = &change_handler {
                     let data = if *new_checked { "true" } else { "false" };
-                    runtime.call_handler(&handler, &component_id, data);
-                    cx.notify(entity_id);
-                });
-            }
+                    runtime.call_handler(handler, &component_id, data);
+                }
+                cx.notify(entity_id);
+            });
         }
 
         cb
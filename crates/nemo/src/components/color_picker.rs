@@ -0,0 +1,218 @@
+use gpui::*;
+use gpui_component::color_picker::{ColorPicker as GpuiColorPicker, ColorPickerState};
+use nemo_layout::BuiltComponent;
+
+use super::chart_utils::chart_color;
+use super::resolve_color;
+
+/// Swatches offered when `swatches` isn't set.
+const DEFAULT_SWATCHES: &[&str] = &[
+    "theme.primary",
+    "theme.info",
+    "theme.success",
+    "theme.warning",
+    "theme.danger",
+];
+
+/// A color picker with a palette of swatches.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <color_picker id="accent" value="#3b82f6" format="hex" on-change="set_accent">
+///   <binding source="data.prefs.accent" target="value" mode="two_way" />
+/// </color_picker>
+///
+/// <color_picker id="tag" format="hsl" swatches='["theme.success", "#e11d48"]' />
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `value` | string | Hex (`#rrggbb`, `#rrggbbaa`), `hsl(…)`/`hsla(…)` or `theme.*` |
+/// | `format` | string | Format written back: `"hex"` (default) or `"hsl"` |
+/// | `swatches` | JSON array | Colors offered as swatches (default: theme and chart colors) |
+/// | `label` | string | Text beside the swatch button |
+/// | `on-change` | string | Handler called with the new color |
+#[derive(IntoElement)]
+pub struct ColorPicker {
+    source: BuiltComponent,
+    state: Option<Entity<ColorPickerState>>,
+}
+
+impl ColorPicker {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            state: None,
+        }
+    }
+
+    pub fn state(mut self, state: Entity<ColorPickerState>) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
+impl RenderOnce for ColorPicker {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let Some(state) = self.state else {
+            return div().child("ColorPicker: missing state").into_any_element();
+        };
+        let props = &self.source.properties;
+        let mut picker = GpuiColorPicker::new(&state).featured_colors(swatches(&self.source, cx));
+        if let Some(label) = props.get("label").and_then(|v| v.as_str()) {
+            picker = picker.label(label.to_string());
+        }
+        picker.into_any_element()
+    }
+}
+
+/// The swatch colors of a picker: its `swatches`, or the theme's status
+/// colors followed by the chart palette.
+fn swatches(component: &BuiltComponent, cx: &App) -> Vec<Hsla> {
+    match component
+        .properties
+        .get("swatches")
+        .and_then(|v| v.as_array())
+    {
+        Some(list) => list
+            .iter()
+            .filter_map(|v| parse_color(v.as_str()?, cx))
+            .collect(),
+        None => DEFAULT_SWATCHES
+            .iter()
+            .filter_map(|name| resolve_color(name, cx))
+            .chain((0..5).map(|i| chart_color(i, cx)))
+            .collect(),
+    }
+}
+
+/// How a picker writes its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorFormat {
+    Hex,
+    Hsl,
+}
+
+impl ColorFormat {
+    pub(crate) fn parse(name: Option<&str>) -> Self {
+        match name {
+            Some("hsl") => ColorFormat::Hsl,
+            _ => ColorFormat::Hex,
+        }
+    }
+}
+
+/// Parses a picker value: hex, `hsl(…)`/`hsla(…)` or a `theme.*` color.
+pub(crate) fn parse_color(value: &str, cx: &App) -> Option<Hsla> {
+    let value = value.trim();
+    match parse_hsl(value) {
+        Some((h, s, l, a)) => Some(hsla(h, s, l, a)),
+        None => resolve_color(value, cx),
+    }
+}
+
+/// Formats a picked color for the `value` property.
+pub(crate) fn format_color(color: Hsla, format: ColorFormat) -> String {
+    match format {
+        ColorFormat::Hex => {
+            let rgb = color.to_rgb();
+            hex_string(rgb.r, rgb.g, rgb.b, rgb.a)
+        }
+        ColorFormat::Hsl => hsl_string(color.h, color.s, color.l, color.a),
+    }
+}
+
+/// `#rrggbb` from 0–1 channels, or `#rrggbbaa` when not opaque.
+pub(crate) fn hex_string(r: f32, g: f32, b: f32, a: f32) -> String {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut hex = format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b));
+    if byte(a) < 255 {
+        hex.push_str(&format!("{:02x}", byte(a)));
+    }
+    hex
+}
+
+/// `hsl(h, s%, l%)` from 0–1 components (hue as a fraction of a turn), or
+/// `hsla(…, a)` when not opaque.
+pub(crate) fn hsl_string(h: f32, s: f32, l: f32, a: f32) -> String {
+    let h = (h.rem_euclid(1.0) * 360.0).round() as u32 % 360;
+    let s = (s.clamp(0.0, 1.0) * 100.0).round();
+    let l = (l.clamp(0.0, 1.0) * 100.0).round();
+    if a < 1.0 {
+        format!(
+            "hsla({}, {}%, {}%, {})",
+            h,
+            s,
+            l,
+            (a * 100.0).round() / 100.0
+        )
+    } else {
+        format!("hsl({}, {}%, {}%)", h, s, l)
+    }
+}
+
+/// Parses `hsl(h, s%, l%)` or `hsla(h, s%, l%, a)` into 0–1 components.
+pub(crate) fn parse_hsl(value: &str) -> Option<(f32, f32, f32, f32)> {
+    let inner = value
+        .strip_prefix("hsla(")
+        .or_else(|| value.strip_prefix("hsl("))?
+        .strip_suffix(')')?;
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    let percent = |s: &str| -> Option<f32> {
+        Some(
+            s.strip_suffix('%')?
+                .trim()
+                .parse::<f32>()
+                .ok()?
+                .clamp(0.0, 100.0)
+                / 100.0,
+        )
+    };
+    let (h, s, l) = match parts.as_slice() {
+        [h, s, l] | [h, s, l, _] => (
+            h.trim_end_matches("deg").parse::<f32>().ok()?,
+            percent(s)?,
+            percent(l)?,
+        ),
+        _ => return None,
+    };
+    let a = match parts.get(3) {
+        Some(a) => a.parse::<f32>().ok()?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    Some((h.rem_euclid(360.0) / 360.0, s, l, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_string() {
+        assert_eq!(hex_string(1.0, 0.5, 0.0, 1.0), "#ff8000");
+        assert_eq!(hex_string(0.0, 0.0, 0.0, 0.5), "#00000080");
+        assert_eq!(hex_string(2.0, -1.0, 0.2, 1.0), "#ff0033");
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        assert_eq!(hsl_string(0.5, 0.5, 0.4, 1.0), "hsl(180, 50%, 40%)");
+        assert_eq!(hsl_string(1.0, 1.0, 0.5, 0.25), "hsla(0, 100%, 50%, 0.25)");
+        assert_eq!(parse_hsl("hsl(180, 50%, 40%)"), Some((0.5, 0.5, 0.4, 1.0)));
+        assert_eq!(
+            parse_hsl("hsla(-90deg, 100%, 50%, 0.5)"),
+            Some((0.75, 1.0, 0.5, 0.5))
+        );
+        assert_eq!(parse_hsl("hsl(180, 50, 40)"), None);
+        assert_eq!(parse_hsl("#ff0000"), None);
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(ColorFormat::parse(Some("hsl")), ColorFormat::Hsl);
+        assert_eq!(ColorFormat::parse(None), ColorFormat::Hex);
+    }
+}
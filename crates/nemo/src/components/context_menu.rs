@@ -0,0 +1,198 @@
+//! Right-click menus for any component.
+//!
+//! # XML Configuration
+//!
+//! ```xml
+//! <table id="tasks">
+//!   <context-menu on-select="task_action">
+//!     <menu-item label="Open" icon="external-link" on-click="open_task" />
+//!     <menu-item label="Duplicate" value="duplicate" />
+//!     <menu-item separator="true" />
+//!     <menu-item label="Delete" value="delete" disabled="true" />
+//!   </context-menu>
+//! </table>
+//! ```
+//!
+//! A `<context-menu>` child gives any component a right-click menu. A
+//! handler is called with the component's id (the menu's target) and the
+//! item's `value`, or its label when it has none.
+//!
+//! # Properties
+//!
+//! On `<menu-item>`:
+//!
+//! | Property | Type | Description |
+//! |----------|------|-------------|
+//! | `label` | string | Item text |
+//! | `icon` | string | Icon name |
+//! | `value` | string | Event data passed to the handler (default: the label) |
+//! | `separator` | bool | Draw a separator instead of an item |
+//! | `disabled` | bool | Show the item greyed out |
+//! | `on-click` | string | Handler for this item; falls back to the menu's `on-select` |
+
+use gpui::*;
+use gpui_component::menu::{ContextMenuExt as _, PopupMenu, PopupMenuItem};
+use gpui_component::Icon as GpuiIcon;
+use nemo_layout::BuiltComponent;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::icon::map_icon_name;
+use crate::runtime::NemoRuntime;
+
+/// One entry of a `<context-menu>`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ContextMenuItem {
+    pub label: String,
+    pub icon: Option<String>,
+    pub value: String,
+    pub handler: Option<String>,
+    pub separator: bool,
+    pub disabled: bool,
+}
+
+/// The items of a component's `<context-menu>` child, or `None` when it has
+/// no context menu.
+pub(crate) fn context_menu_items(
+    component: &BuiltComponent,
+    components: &HashMap<String, BuiltComponent>,
+) -> Option<Vec<ContextMenuItem>> {
+    let menu = component
+        .children
+        .iter()
+        .filter_map(|id| components.get(id))
+        .find(|c| c.component_type == "context_menu")?;
+    let fallback = menu.handlers.get("select");
+    Some(
+        menu.children
+            .iter()
+            .filter_map(|id| components.get(id))
+            .filter(|c| c.component_type == "menu_item")
+            .filter_map(|item| {
+                let props = &item.properties;
+                let flag = |key: &str| props.get(key).and_then(|v| v.as_bool()) == Some(true);
+                let label = props
+                    .get("label")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let separator = flag("separator");
+                if !separator && label.is_empty() {
+                    return None;
+                }
+                Some(ContextMenuItem {
+                    value: props
+                        .get("value")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| label.clone()),
+                    label,
+                    icon: props
+                        .get("icon")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    handler: item.handlers.get("click").or(fallback).cloned(),
+                    separator,
+                    disabled: flag("disabled"),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Gives `element` a right-click menu of `items`, whose handlers are called
+/// with `target_id`.
+pub(crate) fn with_context_menu(
+    element: AnyElement,
+    target_id: &str,
+    items: Vec<ContextMenuItem>,
+    runtime: Arc<NemoRuntime>,
+) -> AnyElement {
+    let target = target_id.to_string();
+    div()
+        .id(SharedString::from(format!("{}-context-menu", target_id)))
+        .child(element)
+        .context_menu(move |menu, _window, _cx| add_items(menu, &items, &target, &runtime))
+        .into_any_element()
+}
+
+/// Appends `items` to `menu`. Shared with the export menu, which lists a
+/// component's own items before its exports.
+pub(crate) fn add_items(
+    mut menu: PopupMenu,
+    items: &[ContextMenuItem],
+    target: &str,
+    runtime: &Arc<NemoRuntime>,
+) -> PopupMenu {
+    for item in items {
+        if item.separator {
+            menu = menu.separator();
+            continue;
+        }
+        let mut entry =
+            PopupMenuItem::new(SharedString::from(item.label.clone())).disabled(item.disabled);
+        if let Some(icon) = &item.icon {
+            entry = entry.icon(GpuiIcon::new(map_icon_name(icon)));
+        }
+        if let Some(handler) = item.handler.clone() {
+            let runtime = Arc::clone(runtime);
+            let target = target.to_string();
+            let value = item.value.clone();
+            entry = entry.on_click(move |_event, window, _cx| {
+                runtime.call_handler(&handler, &target, &value);
+                window.refresh();
+            });
+        }
+        menu = menu.item(entry);
+    }
+    menu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nemo_config::Value;
+
+    #[test]
+    fn test_context_menu_items() {
+        let mut components = HashMap::new();
+        let table = BuiltComponent::new("tasks", "table").with_children(["menu"]);
+        let mut menu = BuiltComponent::new("menu", "context_menu")
+            .with_children(["open", "sep", "delete", "blank"]);
+        menu.handlers
+            .insert("select".to_string(), "task_action".to_string());
+        let mut open = BuiltComponent::new("open", "menu_item")
+            .with_props([("label", Value::String("Open".into()))]);
+        open.handlers
+            .insert("click".to_string(), "open_task".to_string());
+        let sep =
+            BuiltComponent::new("sep", "menu_item").with_props([("separator", Value::Bool(true))]);
+        let delete = BuiltComponent::new("delete", "menu_item").with_props([
+            ("label", Value::String("Delete".into())),
+            ("value", Value::String("delete".into())),
+            ("disabled", Value::Bool(true)),
+        ]);
+        for c in [
+            table.clone(),
+            menu,
+            open,
+            sep,
+            delete,
+            BuiltComponent::new("blank", "menu_item"),
+        ] {
+            components.insert(c.id.clone(), c);
+        }
+
+        let items = context_menu_items(&table, &components).unwrap();
+        assert_eq!(items.len(), 3, "an item without a label is dropped");
+        assert_eq!(items[0].handler.as_deref(), Some("open_task"));
+        assert_eq!(items[0].value, "Open");
+        assert!(items[1].separator);
+        assert_eq!(items[2].handler.as_deref(), Some("task_action"));
+        assert_eq!(items[2].value, "delete");
+        assert!(items[2].disabled);
+
+        let plain = BuiltComponent::new("plain", "label");
+        assert!(context_menu_items(&plain, &components).is_none());
+    }
+}
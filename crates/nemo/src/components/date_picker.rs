@@ -0,0 +1,269 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use gpui::*;
+use gpui_component::date_picker::{DatePicker as GpuiDatePicker, DatePickerState};
+use gpui_component::input::{Input as GpuiInput, InputState};
+use gpui_component::{h_flex, Sizable};
+use indexmap::IndexMap;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+/// Format of a date value.
+pub(crate) const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Format of a date-time value.
+pub(crate) const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Format of the time field of a `datetime_picker`.
+pub(crate) const TIME_FORMAT: &str = "%H:%M";
+
+/// A calendar date picker, and with `datetime_picker` a time field beside
+/// it.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <date_picker id="due" value="2026-10-18" min="2026-01-01" on-change="set_due">
+///   <binding source="data.task.due" target="value" mode="two_way" />
+/// </date_picker>
+///
+/// <date_picker id="period" range="true" />
+///
+/// <datetime_picker id="starts" value="2026-10-18T09:30" />
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `value` | string / object | `"YYYY-MM-DD"` (`"YYYY-MM-DDTHH:MM"` for `datetime_picker`), or `{"start", "end"}` in range mode |
+/// | `range` | bool | Pick a start and end date (`date_picker` only) |
+/// | `min` / `max` | string | Earliest / latest selectable date |
+/// | `placeholder` | string | Text shown when empty |
+/// | `on-change` | string | Handler called with the new value |
+#[derive(IntoElement)]
+pub struct DatePicker {
+    source: BuiltComponent,
+    state: Option<Entity<DatePickerState>>,
+    time: Option<Entity<InputState>>,
+}
+
+impl DatePicker {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            state: None,
+            time: None,
+        }
+    }
+
+    pub fn state(mut self, state: Entity<DatePickerState>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn time(mut self, time: Option<Entity<InputState>>) -> Self {
+        self.time = time;
+        self
+    }
+}
+
+impl RenderOnce for DatePicker {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let Some(state) = self.state else {
+            return div().child("DatePicker: missing state").into_any_element();
+        };
+        let props = &self.source.properties;
+        let range = is_range(&self.source);
+        let mut picker = GpuiDatePicker::new(&state)
+            .cleanable(true)
+            .number_of_months(if range { 2 } else { 1 });
+        if let Some(placeholder) = props.get("placeholder").and_then(|v| v.as_str()) {
+            picker = picker.placeholder(placeholder.to_string());
+        }
+        if props.get("disabled").and_then(|v| v.as_bool()) == Some(true) {
+            picker = picker.disabled(true);
+        }
+        match self.time {
+            Some(time) => h_flex()
+                .gap_2()
+                .child(div().flex_1().child(picker))
+                .child(div().w(px(84.)).child(GpuiInput::new(&time).small()))
+                .into_any_element(),
+            None => picker.into_any_element(),
+        }
+    }
+}
+
+/// Whether a picker picks a date range.
+pub(crate) fn is_range(component: &BuiltComponent) -> bool {
+    component.component_type == "date_picker"
+        && component.properties.get("range").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Parses a date, or the date part of a date-time.
+pub(crate) fn parse_date(s: &str) -> Option<NaiveDate> {
+    let s = s.trim();
+    NaiveDate::parse_from_str(s, DATE_FORMAT)
+        .ok()
+        .or_else(|| parse_datetime(s).map(|dt| dt.date()))
+}
+
+/// Parses a date-time (`T` or space separated, with or without seconds); a
+/// bare date is midnight.
+pub(crate) fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, DATE_FORMAT)
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })
+}
+
+/// Parses the time field (`HH:MM`, optionally with seconds).
+pub(crate) fn parse_time(s: &str) -> Option<NaiveTime> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, TIME_FORMAT))
+        .ok()
+}
+
+/// The `min`/`max` bounds of a picker.
+pub(crate) fn bounds(component: &BuiltComponent) -> (Option<NaiveDate>, Option<NaiveDate>) {
+    let bound = |key: &str| {
+        component
+            .properties
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(parse_date)
+    };
+    (bound("min"), bound("max"))
+}
+
+/// Clamps a date into `min..=max`.
+pub(crate) fn clamp_date(
+    date: NaiveDate,
+    min: Option<NaiveDate>,
+    max: Option<NaiveDate>,
+) -> NaiveDate {
+    let date = min.map_or(date, |min| date.max(min));
+    max.map_or(date, |max| date.min(max))
+}
+
+/// Reads a range value: an object with `start`/`end`, or a two-item array.
+pub(crate) fn range_bounds(value: &Value) -> (Option<NaiveDate>, Option<NaiveDate>) {
+    let date = |v: Option<&Value>| v.and_then(|v| v.as_str()).and_then(parse_date);
+    match value {
+        Value::Object(_) => (date(value.get("start")), date(value.get("end"))),
+        Value::Array(_) => (date(value.get_index(0)), date(value.get_index(1))),
+        _ => (None, None),
+    }
+}
+
+/// The `value` of a single-date picker.
+pub(crate) fn date_value(date: Option<NaiveDate>) -> Value {
+    date.map(|d| Value::String(d.format(DATE_FORMAT).to_string()))
+        .unwrap_or(Value::Null)
+}
+
+/// The `value` of a range picker: `{"start", "end"}`, either possibly null.
+pub(crate) fn range_value(start: Option<NaiveDate>, end: Option<NaiveDate>) -> Value {
+    let mut obj = IndexMap::new();
+    obj.insert("start".to_string(), date_value(start));
+    obj.insert("end".to_string(), date_value(end));
+    Value::Object(obj)
+}
+
+/// The `value` of a `datetime_picker`: the date at the typed time (midnight
+/// when the time is blank or invalid), clamped to the date bounds.
+pub(crate) fn datetime_value(
+    date: Option<NaiveDate>,
+    time: &str,
+    min: Option<NaiveDate>,
+    max: Option<NaiveDate>,
+) -> Value {
+    let Some(date) = date else {
+        return Value::Null;
+    };
+    let time = parse_time(time).unwrap_or(NaiveTime::MIN);
+    Value::String(
+        clamp_date(date, min, max)
+            .and_time(time)
+            .format(DATETIME_FORMAT)
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dates_and_times() {
+        assert_eq!(parse_date(" 2026-10-18 "), Some(date("2026-10-18")));
+        assert_eq!(parse_date("2026-10-18T09:30"), Some(date("2026-10-18")));
+        assert_eq!(parse_date("18/10/2026"), None);
+        assert_eq!(
+            parse_datetime("2026-10-18 09:30:15").map(|d| d.to_string()),
+            Some("2026-10-18 09:30:15".to_string())
+        );
+        assert_eq!(
+            parse_datetime("2026-10-18").map(|d| d.time()),
+            Some(NaiveTime::MIN)
+        );
+        assert_eq!(parse_time("9:05"), NaiveTime::from_hms_opt(9, 5, 0));
+        assert_eq!(parse_time("25:00"), None);
+    }
+
+    #[test]
+    fn test_clamp_and_values() {
+        let (min, max) = (Some(date("2026-01-01")), Some(date("2026-12-31")));
+        assert_eq!(clamp_date(date("2025-06-01"), min, max), date("2026-01-01"));
+        assert_eq!(
+            clamp_date(date("2026-06-01"), min, None),
+            date("2026-06-01")
+        );
+        assert_eq!(
+            clamp_date(date("2027-06-01"), None, max),
+            date("2026-12-31")
+        );
+
+        assert_eq!(
+            datetime_value(Some(date("2027-02-01")), "14:45", min, max),
+            Value::String("2026-12-31T14:45".into())
+        );
+        assert_eq!(
+            datetime_value(Some(date("2026-02-01")), "later", None, None),
+            Value::String("2026-02-01T00:00".into())
+        );
+        assert_eq!(datetime_value(None, "10:00", None, None), Value::Null);
+    }
+
+    #[test]
+    fn test_range_round_trip() {
+        let value = range_value(Some(date("2026-10-01")), None);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"start":"2026-10-01","end":null}"#
+        );
+        assert_eq!(range_bounds(&value), (Some(date("2026-10-01")), None));
+
+        let pair: Value = serde_json::from_str(r#"["2026-10-01", "2026-10-07"]"#).unwrap();
+        assert_eq!(
+            range_bounds(&pair),
+            (Some(date("2026-10-01")), Some(date("2026-10-07")))
+        );
+        assert_eq!(range_bounds(&Value::Null), (None, None));
+    }
+}
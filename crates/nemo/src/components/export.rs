@@ -0,0 +1,434 @@
+//! Getting data out of a running view: the bound `data` of a `table` or chart
+//! as CSV or JSON, and a chart's pixels as PNG.
+//!
+//! Exportable components get a right-click menu ([`with_export_menu`]) that
+//! asks for a path; scripts call `export_component(id, path)`. Both go through
+//! [`NemoRuntime::export_component`], which writes data files straight away
+//! and queues PNG captures for the window (see [`capture_png`]).
+//!
+//! The format follows the path's extension: `.csv`, `.json` or `.png`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Context as _, Result};
+use gpui::*;
+use gpui_component::menu::{ContextMenuExt as _, PopupMenuItem};
+use gpui_component::notification::{Notification as Toast, NotificationType};
+use gpui_component::WindowExt as _;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+use super::chart_utils::extract_data_array;
+use super::context_menu::{self, ContextMenuItem};
+use super::table::{ColumnSpec, NemoTableDelegate};
+use crate::runtime::NemoRuntime;
+
+/// Last painted bounds of each exportable component, by id; a PNG export
+/// crops the window capture to them.
+pub(crate) type ExportBounds = Arc<Mutex<HashMap<String, Bounds<Pixels>>>>;
+
+/// What an export writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Csv,
+    Json,
+    Png,
+}
+
+impl ExportFormat {
+    /// The format named by a path's extension (case-insensitive).
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            "png" => Some(ExportFormat::Png),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Png => "png",
+        }
+    }
+
+    fn menu_label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "Export CSV…",
+            ExportFormat::Json => "Export JSON…",
+            ExportFormat::Png => "Export PNG…",
+        }
+    }
+}
+
+/// Whether a component type draws a chart (and so can be exported as PNG).
+pub(crate) fn is_chart(component_type: &str) -> bool {
+    component_type.ends_with("_chart") || matches!(component_type, "sparkline" | "gauge" | "stat")
+}
+
+/// Whether a component type has data to export.
+pub(crate) fn is_exportable(component_type: &str) -> bool {
+    component_type == "table" || is_chart(component_type)
+}
+
+/// Checks that `component` can be exported to `path` and returns the format.
+pub(crate) fn format_for(component: &BuiltComponent, path: &Path) -> Result<ExportFormat> {
+    let ty = component.component_type.as_str();
+    if !is_exportable(ty) {
+        bail!(
+            "'{}' is a {}, which has no data to export",
+            component.id,
+            ty
+        );
+    }
+    let Some(format) = ExportFormat::from_path(path) else {
+        bail!(
+            "cannot tell the export format of {}; use a .csv, .json or .png path",
+            path.display()
+        );
+    };
+    if format == ExportFormat::Png && !is_chart(ty) {
+        bail!("only charts can be exported as PNG");
+    }
+    Ok(format)
+}
+
+/// Column keys and header labels for a CSV export: a table's `columns` when
+/// it declares them, otherwise every key of the object rows in first-seen
+/// order. Rows of plain values export as a single `value` column.
+pub(crate) fn columns(component: &BuiltComponent, rows: &[Value]) -> Vec<(String, String)> {
+    if let Some(Value::Array(specs)) = component.properties.get("columns") {
+        let specs: Vec<(String, String)> = specs
+            .iter()
+            .filter_map(ColumnSpec::from_value)
+            .map(|spec| (spec.key, spec.label))
+            .collect();
+        if !specs.is_empty() {
+            return specs;
+        }
+    }
+    let mut keys: Vec<String> = Vec::new();
+    for row in rows {
+        match row.as_object() {
+            Some(object) => {
+                for key in object.keys() {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+            None if !keys.iter().any(|k| k == "value") => keys.push("value".to_string()),
+            None => {}
+        }
+    }
+    keys.into_iter().map(|k| (k.clone(), k)).collect()
+}
+
+/// Renders rows as CSV (RFC 4180 quoting) with a header line.
+pub(crate) fn to_csv(rows: &[Value], columns: &[(String, String)]) -> String {
+    let mut out = String::new();
+    let mut line = |cells: Vec<String>| {
+        let cells: Vec<String> = cells.iter().map(|c| csv_escape(c)).collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    };
+    line(columns.iter().map(|(_, label)| label.clone()).collect());
+    for row in rows {
+        line(
+            columns
+                .iter()
+                .map(|(key, _)| match row {
+                    Value::Object(object) => object.get(key).map(csv_cell).unwrap_or_default(),
+                    other if key == "value" => csv_cell(other),
+                    _ => String::new(),
+                })
+                .collect(),
+        );
+    }
+    out
+}
+
+/// The text of one CSV cell; nested arrays and objects are written as JSON.
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Writes a component's bound `data` to `path` as CSV or JSON. A table
+/// exports its current view, with its `filters` and `sort` applied.
+pub(crate) fn write_data(
+    component: &BuiltComponent,
+    path: &Path,
+    format: ExportFormat,
+) -> Result<()> {
+    let rows = export_rows(component);
+    let contents = match format {
+        ExportFormat::Csv => to_csv(&rows, &columns(component, &rows)),
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)? + "\n",
+        ExportFormat::Png => bail!("PNG exports are captured by the window"),
+    };
+    std::fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
+
+/// The rows an export writes. A gauge has no `data`; it exports its `value`.
+fn export_rows(component: &BuiltComponent) -> Vec<Value> {
+    match component.component_type.as_str() {
+        "table" => NemoTableDelegate::from_properties(&component.properties).view_rows(),
+        "gauge" => component
+            .properties
+            .get("value")
+            .cloned()
+            .into_iter()
+            .collect(),
+        _ => extract_data_array(component),
+    }
+}
+
+/// Captures the window and writes the part inside `bounds` to `path`.
+///
+/// Reads back the last drawn frame with gpui's `render_to_image`, the same
+/// path `nemo screenshot` uses, so it needs the `screenshot` build feature.
+#[cfg(feature = "screenshot")]
+pub(crate) fn capture_png(window: &mut Window, bounds: Bounds<Pixels>, path: &Path) -> Result<()> {
+    let image = window
+        .render_to_image()
+        .context("failed to render window to image")?;
+    let scale = window.scale_factor();
+    let (width, height) = image.dimensions();
+    let x = ((bounds.origin.x.as_f32() * scale).max(0.0) as u32).min(width);
+    let y = ((bounds.origin.y.as_f32() * scale).max(0.0) as u32).min(height);
+    let w = ((bounds.size.width.as_f32() * scale) as u32).min(width - x);
+    let h = ((bounds.size.height.as_f32() * scale) as u32).min(height - y);
+    if w == 0 || h == 0 {
+        bail!("the chart is not on screen");
+    }
+    ::image::imageops::crop_imm(&image, x, y, w, h)
+        .to_image()
+        .save(path)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(not(feature = "screenshot"))]
+pub(crate) fn capture_png(
+    _window: &mut Window,
+    _bounds: Bounds<Pixels>,
+    _path: &Path,
+) -> Result<()> {
+    bail!("PNG export needs a build with the `screenshot` feature")
+}
+
+/// Sizes a wrapper the way the element of `component_type` sizes itself, so
+/// wrapping it doesn't change the layout: gpui-component's plots fill their
+/// parent, tables, gauges and sparklines its width at their own height, and a
+/// stat fits its content.
+fn size_like(wrapper: Stateful<Div>, component_type: &str) -> Stateful<Div> {
+    match component_type {
+        "stat" => wrapper,
+        "table" | "gauge" | "sparkline" => wrapper.w_full(),
+        _ => wrapper.size_full(),
+    }
+}
+
+/// Wraps an exportable component so a right click offers its exports, after
+/// the items of its own `<context-menu>` if it has one. "Export PNG…" is only
+/// offered on charts in builds with the `screenshot` feature; only then is
+/// where the chart was painted recorded, for the capture to crop to.
+pub(crate) fn with_export_menu(
+    element: AnyElement,
+    component: &BuiltComponent,
+    runtime: Arc<NemoRuntime>,
+    bounds: ExportBounds,
+    items: Vec<ContextMenuItem>,
+) -> AnyElement {
+    let id = component.id.clone();
+    let png = cfg!(feature = "screenshot") && is_chart(&component.component_type);
+    let formats: Vec<ExportFormat> = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Png]
+        .into_iter()
+        .filter(|f| *f != ExportFormat::Png || png)
+        .collect();
+
+    let wrapper = size_like(
+        div().id(SharedString::from(format!("{}-export", id))),
+        &component.component_type,
+    );
+    let wrapper = if png {
+        let record_id = id.clone();
+        wrapper.relative().child(
+            canvas(
+                move |painted, _window, _cx| {
+                    bounds.lock().unwrap().insert(record_id, painted);
+                },
+                |_, _, _, _| {},
+            )
+            .absolute()
+            .size_full(),
+        )
+    } else {
+        wrapper
+    };
+    wrapper
+        .child(element)
+        .context_menu(move |menu, _window, _cx| {
+            let mut menu = context_menu::add_items(menu, &items, &id, &runtime);
+            if !items.is_empty() {
+                menu = menu.separator();
+            }
+            for &format in &formats {
+                let runtime = Arc::clone(&runtime);
+                let id = id.clone();
+                menu = menu.item(PopupMenuItem::new(format.menu_label()).on_click(
+                    move |_, window, cx| prompt_and_export(&runtime, &id, format, window, cx),
+                ));
+            }
+            menu
+        })
+        .into_any_element()
+}
+
+/// Asks for a file to save to, then exports there and reports the outcome.
+fn prompt_and_export(
+    runtime: &Arc<NemoRuntime>,
+    id: &str,
+    format: ExportFormat,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let directory = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let file_name = format!("{}.{}", id, format.extension());
+    let receiver = cx.prompt_for_new_path(&directory, Some(&file_name));
+    let runtime = Arc::clone(runtime);
+    let id = id.to_string();
+    window
+        .spawn(cx, async move |cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
+                return;
+            };
+            let result = runtime.export_component(&id, &path);
+            let _ = cx.update(|window, cx| match result {
+                // PNG captures report once the window has drawn them.
+                Ok(()) if format == ExportFormat::Png => {}
+                Ok(()) => window.push_notification(format!("Exported to {}", path.display()), cx),
+                Err(e) => notify_failure(&id, &e, window, cx),
+            });
+        })
+        .detach();
+}
+
+/// Shows a failed export as an error notification.
+pub(crate) fn notify_failure(id: &str, error: &anyhow::Error, window: &mut Window, cx: &mut App) {
+    tracing::warn!("Export of '{}' failed: {:#}", id, error);
+    window.push_notification(
+        Toast::new()
+            .message(format!("Export failed: {:#}", error))
+            .with_type(NotificationType::Error),
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_format_for() {
+        let table = BuiltComponent::new("c", "table");
+        let chart = BuiltComponent::new("c", "line_chart");
+        assert_eq!(
+            format_for(&table, Path::new("out/Rows.CSV")).unwrap(),
+            ExportFormat::Csv
+        );
+        assert_eq!(
+            format_for(&chart, Path::new("chart.png")).unwrap(),
+            ExportFormat::Png
+        );
+        assert!(format_for(&table, Path::new("rows.png")).is_err());
+        for ty in ["gauge", "stat", "sparkline"] {
+            assert_eq!(
+                format_for(&BuiltComponent::new("c", ty), Path::new("kpi.png")).unwrap(),
+                ExportFormat::Png
+            );
+        }
+        assert!(format_for(&table, Path::new("rows.txt")).is_err());
+        assert!(format_for(&BuiltComponent::new("c", "button"), Path::new("b.json")).is_err());
+    }
+
+    #[test]
+    fn test_csv_uses_table_columns_and_quotes() {
+        let table = BuiltComponent::new("c", "table").with_props([(
+            "columns",
+            json(r#"[{"key":"name","label":"Name"},{"key":"qty"}]"#),
+        )]);
+        let rows = json(
+            r#"[{"name":"Widget, large","qty":3,"extra":true},
+                {"name":"Say \"hi\"","qty":null}]"#,
+        );
+        let rows = rows.as_array().unwrap();
+        assert_eq!(
+            to_csv(rows, &columns(&table, rows)),
+            "Name,qty\n\"Widget, large\",3\n\"Say \"\"hi\"\"\",\n"
+        );
+    }
+
+    #[test]
+    fn test_table_exports_its_current_view() {
+        let table = BuiltComponent::new("c", "table").with_props([
+            (
+                "data",
+                json(
+                    r#"[{"name":"alice","qty":3},{"name":"bob","qty":1},{"name":"carol","qty":2}]"#,
+                ),
+            ),
+            ("sort", json(r#""qty:desc""#)),
+            ("filters", json(r#"{"name":"A"}"#)),
+        ]);
+        assert_eq!(
+            export_rows(&table),
+            json(r#"[{"name":"alice","qty":3},{"name":"carol","qty":2}]"#)
+                .as_array()
+                .unwrap()
+                .clone()
+        );
+    }
+
+    #[test]
+    fn test_csv_columns_from_rows() {
+        let chart = BuiltComponent::new("c", "bar_chart");
+        let rows = json(r#"[{"x":"a","y":1},{"x":"b","z":[1,2]}]"#);
+        let rows = rows.as_array().unwrap();
+        assert_eq!(
+            to_csv(rows, &columns(&chart, rows)),
+            "x,y,z\na,1,\nb,,\"[1,2]\"\n"
+        );
+
+        let values = vec![Value::Float(1.5), Value::Integer(2)];
+        assert_eq!(
+            to_csv(
+                &values,
+                &columns(&BuiltComponent::new("c", "sparkline"), &values)
+            ),
+            "value\n1.5\n2\n"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::f32::consts::PI;
+
+use gpui::*;
+use gpui_component::plot::{IntoPlot, Plot};
+use gpui_component::ActiveTheme;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+use super::chart_utils::chart_color;
+use super::resolve_color;
+use crate::theme::tokens::{font_size, FontSize, Space, TokenStyled};
+
+/// Start of the radial gauge's arc (lower left), clockwise from 3 o'clock.
+const ARC_START: f32 = PI * 0.75;
+
+/// Sweep of the radial gauge's arc.
+const ARC_SWEEP: f32 = PI * 1.5;
+
+/// A gauge showing one value between `min` and `max`, as a 270° dial or a
+/// horizontal bar.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <gauge id="pressure" value="72" min="0" max="120" unit="bar" label="Pressure"
+///   ranges='[{"from":0,"to":80,"color":"theme.success"},
+///            {"from":80,"to":100,"color":"theme.warning"},
+///            {"from":100,"to":120,"color":"theme.danger"}]'
+///   height="180">
+///   <binding source="data.plant.pressure" target="value" />
+/// </gauge>
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `value` | float | Current value |
+/// | `min` / `max` | float | Scale bounds (default: 0 and 100) |
+/// | `variant` | string | `"radial"` (default) or `"linear"` |
+/// | `ranges` | JSON array | Colored bands: `[{"from", "to", "color"}]` |
+/// | `label` | string | Caption under the value |
+/// | `unit` | string | Unit shown after the value |
+/// | `decimals` | int | Decimal places of the value (default: 0) |
+/// | `height` | int | Height in pixels (radial default: 160) |
+#[derive(IntoElement)]
+pub struct Gauge {
+    source: BuiltComponent,
+}
+
+impl Gauge {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self { source }
+    }
+}
+
+/// A colored stretch of the gauge scale.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GaugeRange {
+    pub from: f64,
+    pub to: f64,
+    pub color: String,
+}
+
+/// Reads the `ranges` property; entries missing `from`, `to` or `color` are
+/// skipped, and `from`/`to` may come in either order.
+pub(crate) fn parse_ranges(value: Option<&Value>) -> Vec<GaugeRange> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let a = item.get("from")?.as_f64()?;
+                    let b = item.get("to")?.as_f64()?;
+                    Some(GaugeRange {
+                        from: a.min(b),
+                        to: a.max(b),
+                        color: item.get("color")?.as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Position of `value` on the `min..max` scale, clamped to `0..=1`.
+pub(crate) fn fraction(value: f64, min: f64, max: f64) -> f32 {
+    if max <= min {
+        return 0.0;
+    }
+    ((value - min) / (max - min)).clamp(0.0, 1.0) as f32
+}
+
+/// The last range containing `value`; later ranges win where they overlap.
+pub(crate) fn range_for(value: f64, ranges: &[GaugeRange]) -> Option<&GaugeRange> {
+    ranges
+        .iter()
+        .rev()
+        .find(|r| value >= r.from && value <= r.to)
+}
+
+impl RenderOnce for Gauge {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let number = |key: &str| props.get(key).and_then(|v| v.as_f64());
+        let text = |key: &str| props.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let min = number("min").unwrap_or(0.0);
+        let max = number("max").unwrap_or(100.0);
+        let value = number("value");
+        let ranges = parse_ranges(props.get("ranges"));
+        let decimals = props
+            .get("decimals")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0)
+            .max(0) as usize;
+
+        let f = value.map(|v| fraction(v, min, max)).unwrap_or(0.0);
+        let fill = value
+            .and_then(|v| range_for(v, &ranges))
+            .and_then(|r| resolve_color(&r.color, cx))
+            .unwrap_or_else(|| chart_color(0, cx));
+        let range_colors: Vec<(f32, f32, Hsla)> = ranges
+            .iter()
+            .filter_map(|r| {
+                let color = resolve_color(&r.color, cx)?;
+                Some((fraction(r.from, min, max), fraction(r.to, min, max), color))
+            })
+            .collect();
+        let theme = &cx.theme().colors;
+        let value_text = value
+            .map(|v| format!("{:.*}", decimals, v))
+            .unwrap_or_else(|| "–".to_string());
+        let readout = div()
+            .flex()
+            .items_baseline()
+            .gap_t(Space::Xs)
+            .child(
+                div()
+                    .text_t(FontSize::Xl)
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child(value_text),
+            )
+            .children(text("unit").map(|unit| {
+                div()
+                    .text_t(FontSize::Sm)
+                    .text_color(theme.muted_foreground)
+                    .child(unit)
+            }));
+        let label = text("label").map(|label| {
+            div()
+                .text_size(font_size(FontSize::Xs))
+                .text_color(theme.muted_foreground)
+                .child(label)
+        });
+
+        if text("variant").as_deref() == Some("linear") {
+            let mut track = div()
+                .relative()
+                .w_full()
+                .h(px(8.))
+                .rounded_full()
+                .bg(theme.muted)
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .bottom_0()
+                        .left_0()
+                        .w(relative(f))
+                        .rounded_full()
+                        .bg(fill),
+                );
+            let mut scale = div().relative().w_full().h(px(3.));
+            for (a, b, color) in range_colors {
+                scale = scale.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .bottom_0()
+                        .left(relative(a))
+                        .w(relative(b - a))
+                        .bg(color),
+                );
+            }
+            track = track.mt(px(2.));
+            return div()
+                .flex()
+                .flex_col()
+                .w_full()
+                .gap_t(Space::Xs)
+                .child(
+                    div()
+                        .flex()
+                        .justify_between()
+                        .items_baseline()
+                        .children(label)
+                        .child(readout),
+                )
+                .child(scale)
+                .child(track)
+                .into_any_element();
+        }
+
+        let height = number("height").map(|h| h as f32).unwrap_or(160.0);
+        div()
+            .relative()
+            .w_full()
+            .h(px(height))
+            .child(GaugeDial {
+                fraction: value.map(|_| f),
+                fill,
+                track: theme.muted,
+                ranges: range_colors,
+            })
+            .child(
+                div()
+                    .absolute()
+                    .inset_0()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .justify_center()
+                    .child(readout)
+                    .children(label),
+            )
+            .into_any_element()
+    }
+}
+
+/// Points along a circular arc, clockwise from `start` through `sweep`
+/// radians (screen coordinates, y down).
+pub(crate) fn arc_points(
+    center: (f32, f32),
+    radius: f32,
+    start: f32,
+    sweep: f32,
+) -> Vec<(f32, f32)> {
+    // About one segment every 3 degrees.
+    let segments = ((sweep.abs() / (PI / 60.0)).ceil() as usize).max(1);
+    (0..=segments)
+        .map(|i| {
+            let angle = start + sweep * i as f32 / segments as f32;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+#[derive(IntoPlot)]
+struct GaugeDial {
+    /// Filled share of the dial; `None` draws the empty track only.
+    fraction: Option<f32>,
+    fill: Hsla,
+    track: Hsla,
+    ranges: Vec<(f32, f32, Hsla)>,
+}
+
+impl Plot for GaugeDial {
+    fn paint(&mut self, bounds: Bounds<Pixels>, window: &mut Window, _cx: &mut App) {
+        let w = bounds.size.width.as_f32();
+        let h = bounds.size.height.as_f32();
+        // The 270° arc is a little shorter than it is wide; centre it.
+        let radius = (w / 2.0).min(h / (1.0 + (PI / 4.0).sin())) * 0.8;
+        let thickness = (radius * 0.16).max(4.0);
+        let center = (w / 2.0, h / 2.0 + radius * 0.1);
+        let origin = bounds.origin;
+
+        let mut stroke = |radius: f32, from: f32, to: f32, width: f32, color: Hsla| {
+            if to <= from {
+                return;
+            }
+            let points: Vec<Point<Pixels>> = arc_points(
+                center,
+                radius,
+                ARC_START + ARC_SWEEP * from,
+                ARC_SWEEP * (to - from),
+            )
+            .into_iter()
+            .map(|(x, y)| point(origin.x + px(x), origin.y + px(y)))
+            .collect();
+            let mut builder = PathBuilder::stroke(px(width));
+            builder.add_polygon(&points, false);
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, color);
+            }
+        };
+
+        stroke(radius, 0.0, 1.0, thickness, self.track);
+        if let Some(f) = self.fraction {
+            stroke(radius, 0.0, f, thickness, self.fill);
+        }
+        // Ranges run as a thin ring just outside the track.
+        let ring = radius + thickness * 0.5 + 3.0;
+        for &(a, b, color) in &self.ranges {
+            stroke(ring, a, b, 3.0, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ranges_and_lookup() {
+        let ranges: Value = serde_json::from_str(
+            r#"[{"from":0,"to":80,"color":"theme.success"},
+                {"from":100,"to":80,"color":"theme.warning"},
+                {"from":90,"to":120,"color":"theme.danger"},
+                {"from":5,"color":"red"}]"#,
+        )
+        .unwrap();
+        let ranges = parse_ranges(Some(&ranges));
+        assert_eq!(ranges.len(), 3);
+        assert_eq!((ranges[1].from, ranges[1].to), (80.0, 100.0));
+
+        assert_eq!(range_for(50.0, &ranges).unwrap().color, "theme.success");
+        // Overlaps resolve to the later range.
+        assert_eq!(range_for(95.0, &ranges).unwrap().color, "theme.danger");
+        assert_eq!(range_for(-1.0, &ranges), None);
+        assert!(parse_ranges(None).is_empty());
+    }
+
+    #[test]
+    fn test_fraction_clamps() {
+        assert_eq!(fraction(50.0, 0.0, 200.0), 0.25);
+        assert_eq!(fraction(-10.0, 0.0, 100.0), 0.0);
+        assert_eq!(fraction(150.0, 0.0, 100.0), 1.0);
+        assert_eq!(fraction(5.0, 10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_arc_points_follow_the_circle() {
+        let points = arc_points((50.0, 50.0), 10.0, ARC_START, ARC_SWEEP);
+        let (first, last) = (points[0], points[points.len() - 1]);
+        // Starts lower left, ends lower right, symmetric about the centre.
+        assert!(first.0 < 50.0 && first.1 > 50.0);
+        assert!((first.0 + last.0 - 100.0).abs() < 1e-3);
+        assert!((first.1 - last.1).abs() < 1e-3);
+        assert!(points
+            .iter()
+            .all(|(x, y)| ((x - 50.0).hypot(y - 50.0) - 10.0).abs() < 1e-3));
+        // The top of the dial is reached half way.
+        let mid = points[points.len() / 2];
+        assert!((mid.0 - 50.0).abs() < 1e-3 && (mid.1 - 40.0).abs() < 1e-3);
+    }
+}
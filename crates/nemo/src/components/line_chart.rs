@@ -1,14 +1,24 @@
 use gpui::*;
 use gpui_component::chart::LineChart as GpuiLineChart;
+use gpui_component::plot::StrokeStyle;
 use nemo_config::Value;
 use nemo_macros::NemoComponent;
+use std::sync::{Arc, Mutex};
 
+use super::chart_annotations::Annotation;
 use super::chart_utils::{
-    empty_chart_placeholder, extract_data_array, get_f64_field, get_string_field,
+    chart_color, empty_chart_placeholder, extract_data_array, get_f64_field, get_string_field,
+    ChartView,
 };
+use super::time_series::{self, SeriesChart, SeriesSpec};
 
 /// A line chart component.
 ///
+/// Setting `x-scale="time"`, `window`, `y2-field`, `downsample` or `legend`,
+/// or giving it `<threshold>`/`<band>`/`<marker>` children, draws it with the
+/// time-series plot shared with `realtime_chart` instead (see
+/// [`super::time_series`] and [`super::chart_annotations`]).
+///
 /// # XML Configuration
 ///
 /// ```xml
@@ -24,6 +34,11 @@ use super::chart_utils::{
 /// |----------|------|-------------|
 /// | `x-field` | string | Data field for the x-axis |
 /// | `y-field` | string | Data field for the y-axis |
+/// | `y2-field` | string | Second series, scaled against a secondary y-axis |
+/// | `x-scale` | string | `"point"` (default) or `"time"` |
+/// | `window` | string | Only show the newest stretch, e.g. `"5m"` (implies a time axis) |
+/// | `downsample` | string | `"none"` (default), `"lttb"` or `"minmax"` |
+/// | `legend` | bool | Show a legend; clicking an entry hides or shows its series |
 /// | `dot` | bool | Show data point dots |
 /// | `linear` | bool | Use linear interpolation instead of curved |
 /// | `tick-margin` | int | Margin for axis tick labels |
@@ -42,6 +57,28 @@ pub struct LineChart {
     tick_margin: Option<i64>,
     #[source]
     source: nemo_layout::BuiltComponent,
+    annotations: Vec<Annotation>,
+    view: Option<Arc<Mutex<ChartView>>>,
+    entity_id: Option<EntityId>,
+}
+
+impl LineChart {
+    /// The chart's `<threshold>`/`<band>`/`<marker>` children.
+    pub fn annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// View state holding the series hidden from the legend.
+    pub fn view(mut self, view: Arc<Mutex<ChartView>>) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
 }
 
 impl RenderOnce for LineChart {
@@ -51,6 +88,38 @@ impl RenderOnce for LineChart {
             return empty_chart_placeholder(cx);
         }
 
+        if time_series::uses_series_plot(&self.source.properties) || !self.annotations.is_empty() {
+            let mut specs = vec![SeriesSpec {
+                field: self.y_field.clone(),
+                secondary: false,
+                color: chart_color(0, cx),
+            }];
+            if let Some(field) = self
+                .source
+                .properties
+                .get("y2_field")
+                .and_then(|v| v.as_str())
+            {
+                specs.push(SeriesSpec {
+                    field: field.to_string(),
+                    secondary: true,
+                    color: chart_color(1, cx),
+                });
+            }
+            let stroke_style = if self.linear == Some(true) {
+                StrokeStyle::Linear
+            } else {
+                StrokeStyle::Natural
+            };
+            return SeriesChart::new(data, &self.source.properties, specs)
+                .stroke_style(stroke_style)
+                .tick_margin(self.tick_margin.unwrap_or(1) as usize)
+                .dot(self.dot == Some(true))
+                .annotations(self.annotations)
+                .view(self.view, self.entity_id)
+                .into_any_element();
+        }
+
         let x_field = self.x_field.clone();
         let y_field = self.y_field.clone();
 
@@ -0,0 +1,879 @@
+use ::markdown::mdast::{AlignKind, Node};
+use ::markdown::ParseOptions;
+use gpui::*;
+use gpui_component::input::{Input as GpuiInput, InputState};
+use gpui_component::ActiveTheme;
+use nemo_macros::NemoComponent;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::runtime::NemoRuntime;
+use crate::theme::tokens::{radius_of, FontSize, Space, TokenStyled};
+
+/// Renders CommonMark (with GitHub tables, task lists and strikethrough).
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <markdown id="runbook" router="main">
+///   <binding source="data.docs.runbook" target="content" />
+/// </markdown>
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `content` | string | Markdown source to render |
+/// | `router` | string | Router that `/path` links navigate (the primary router by default) |
+/// | `on-link` | string | Handler called with a clicked link's URL instead of following it |
+///
+/// Links to `/path` navigate the router; links with a scheme (`https:`,
+/// `mailto:`) open in the system handler. Fenced code blocks are shown in
+/// read-only code editors, highlighted by the fence's language.
+#[derive(IntoElement, NemoComponent)]
+pub struct Markdown {
+    #[source]
+    source: nemo_layout::BuiltComponent,
+    #[property]
+    router: Option<String>,
+    blocks: Arc<Vec<Block>>,
+    code_states: Vec<Entity<InputState>>,
+    runtime: Option<Arc<NemoRuntime>>,
+    entity_id: Option<EntityId>,
+}
+
+impl Markdown {
+    /// The parsed document and one code-editor state per fenced code block,
+    /// in [`code_blocks`] order.
+    pub fn document(
+        mut self,
+        blocks: Arc<Vec<Block>>,
+        code_states: Vec<Entity<InputState>>,
+    ) -> Self {
+        self.blocks = blocks;
+        self.code_states = code_states;
+        self
+    }
+
+    pub fn runtime(mut self, runtime: Arc<NemoRuntime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
+}
+
+/// A block-level element of a parsed document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        text: Inline,
+    },
+    Paragraph(Inline),
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    List {
+        ordered: bool,
+        start: u32,
+        items: Vec<ListItem>,
+    },
+    Quote(Vec<Block>),
+    Table {
+        align: Vec<Align>,
+        header: Vec<Inline>,
+        rows: Vec<Vec<Inline>>,
+    },
+    Rule,
+}
+
+/// One list item; `checked` is set for task-list items (`- [x]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListItem {
+    pub checked: Option<bool>,
+    pub blocks: Vec<Block>,
+}
+
+/// Horizontal alignment of a table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+/// Styling of a run of inline text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InlineStyle {
+    pub strong: bool,
+    pub emphasis: bool,
+    pub strike: bool,
+    pub code: bool,
+    /// Index into [`Inline::links`].
+    pub link: Option<usize>,
+}
+
+/// Flattened inline content: the plain text plus non-overlapping styled
+/// ranges (unstyled text has no span), ready for a `StyledText`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inline {
+    pub text: String,
+    pub spans: Vec<(Range<usize>, InlineStyle)>,
+    pub links: Vec<String>,
+}
+
+impl Inline {
+    fn push(&mut self, text: &str, style: InlineStyle) {
+        if text.is_empty() {
+            return;
+        }
+        let start = self.text.len();
+        self.text.push_str(text);
+        if style == InlineStyle::default() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some((range, last)) if range.end == start && *last == style => {
+                range.end = self.text.len()
+            }
+            _ => self.spans.push((start..self.text.len(), style)),
+        }
+    }
+
+    /// Byte ranges of each clickable link, paired with its URL index.
+    pub fn link_ranges(&self) -> Vec<(Range<usize>, usize)> {
+        let mut ranges: Vec<(Range<usize>, usize)> = Vec::new();
+        for (range, style) in &self.spans {
+            let Some(link) = style.link else { continue };
+            match ranges.last_mut() {
+                Some((last, ix)) if *ix == link && last.end == range.start => last.end = range.end,
+                _ => ranges.push((range.clone(), link)),
+            }
+        }
+        ranges
+    }
+}
+
+/// Parses markdown into blocks. Link reference definitions are resolved and
+/// dropped; raw HTML is shown as text.
+pub fn parse(source: &str) -> Vec<Block> {
+    let root = match ::markdown::to_mdast(source, &ParseOptions::gfm()) {
+        Ok(root) => root,
+        Err(e) => {
+            tracing::warn!("markdown: {}", e);
+            return vec![Block::Paragraph(Inline {
+                text: source.to_string(),
+                ..Default::default()
+            })];
+        }
+    };
+    let mut definitions = HashMap::new();
+    collect_definitions(&root, &mut definitions);
+    let converter = Converter { definitions };
+    match &root {
+        Node::Root(root) => converter.blocks(&root.children),
+        other => converter.blocks(std::slice::from_ref(other)),
+    }
+}
+
+/// The fenced code blocks of a document as `(language, code)`, in the order
+/// [`Markdown`] renders them.
+pub fn code_blocks(blocks: &[Block]) -> Vec<(Option<String>, String)> {
+    let mut out = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Code { language, code } => out.push((language.clone(), code.clone())),
+            Block::List { items, .. } => {
+                for item in items {
+                    out.extend(code_blocks(&item.blocks));
+                }
+            }
+            Block::Quote(inner) => out.extend(code_blocks(inner)),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Maps a fence's info string to a highlighter language name, resolving
+/// common short aliases. Other names pass through; the highlighter shows
+/// languages it doesn't know as plain text.
+pub fn highlight_language(fence: Option<&str>) -> &str {
+    let lang = fence.unwrap_or("").trim();
+    match lang.to_ascii_lowercase().as_str() {
+        "" | "text" | "txt" | "plain" => "plain",
+        "sh" | "shell" | "zsh" | "console" => "bash",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "py" => "python",
+        "rs" => "rust",
+        "yml" => "yaml",
+        "md" => "markdown",
+        _ => lang,
+    }
+}
+
+/// What clicking a link does when the component has no `on-link` handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkAction {
+    /// Navigate the router to this path.
+    Navigate(String),
+    /// Open with the system handler.
+    Open(String),
+    /// Nothing (fragments and relative paths).
+    Ignore,
+}
+
+/// URL schemes a link may open with the system handler. Anything else — a
+/// `file:` path, a `javascript:` URL, a custom app scheme — is ignored, since
+/// the markdown may come from data the app doesn't control.
+const OPENABLE_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Classifies a link URL: `/path` navigates, an `http`, `https` or `mailto`
+/// URL opens, and other schemes are ignored with a warning.
+pub fn link_action(url: &str) -> LinkAction {
+    if url.starts_with('/') {
+        return LinkAction::Navigate(url.to_string());
+    }
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+    match scheme {
+        Some(scheme)
+            if OPENABLE_SCHEMES
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(scheme)) =>
+        {
+            LinkAction::Open(url.to_string())
+        }
+        Some(scheme) => {
+            tracing::warn!("markdown: not opening '{}' link: {}", scheme, url);
+            LinkAction::Ignore
+        }
+        None => LinkAction::Ignore,
+    }
+}
+
+fn collect_definitions(node: &Node, definitions: &mut HashMap<String, String>) {
+    if let Node::Definition(def) = node {
+        definitions
+            .entry(def.identifier.clone())
+            .or_insert_with(|| def.url.clone());
+    }
+    for child in node.children().into_iter().flatten() {
+        collect_definitions(child, definitions);
+    }
+}
+
+struct Converter {
+    definitions: HashMap<String, String>,
+}
+
+impl Converter {
+    fn blocks(&self, nodes: &[Node]) -> Vec<Block> {
+        let mut out = Vec::new();
+        for node in nodes {
+            match node {
+                Node::Heading(h) => out.push(Block::Heading {
+                    level: h.depth,
+                    text: self.inline(&h.children),
+                }),
+                Node::Paragraph(p) => out.push(Block::Paragraph(self.inline(&p.children))),
+                Node::Code(c) => out.push(Block::Code {
+                    language: c.lang.clone(),
+                    code: c.value.clone(),
+                }),
+                Node::Math(m) => out.push(Block::Code {
+                    language: None,
+                    code: m.value.clone(),
+                }),
+                Node::List(list) => out.push(Block::List {
+                    ordered: list.ordered,
+                    start: list.start.unwrap_or(1),
+                    items: list
+                        .children
+                        .iter()
+                        .filter_map(|n| match n {
+                            Node::ListItem(item) => Some(ListItem {
+                                checked: item.checked,
+                                blocks: self.blocks(&item.children),
+                            }),
+                            _ => None,
+                        })
+                        .collect(),
+                }),
+                Node::Blockquote(q) => out.push(Block::Quote(self.blocks(&q.children))),
+                Node::Table(table) => {
+                    let mut rows = table.children.iter().filter_map(|row| {
+                        let cells = row.children()?;
+                        Some(
+                            cells
+                                .iter()
+                                .map(|cell| self.inline(cell.children().map_or(&[][..], |c| c)))
+                                .collect::<Vec<_>>(),
+                        )
+                    });
+                    let header = rows.next().unwrap_or_default();
+                    out.push(Block::Table {
+                        align: table
+                            .align
+                            .iter()
+                            .map(|a| match a {
+                                AlignKind::Center => Align::Center,
+                                AlignKind::Right => Align::End,
+                                _ => Align::Start,
+                            })
+                            .collect(),
+                        header,
+                        rows: rows.collect(),
+                    });
+                }
+                Node::ThematicBreak(_) => out.push(Block::Rule),
+                Node::Html(html) => out.push(Block::Paragraph(Inline {
+                    text: html.value.clone(),
+                    ..Default::default()
+                })),
+                Node::Definition(_) => {}
+                other => {
+                    if let Some(children) = other.children() {
+                        out.extend(self.blocks(children));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn inline(&self, nodes: &[Node]) -> Inline {
+        let mut out = Inline::default();
+        self.push_inline(nodes, InlineStyle::default(), &mut out);
+        out
+    }
+
+    fn push_link(&self, url: &str, text: &[Node], style: InlineStyle, out: &mut Inline) {
+        out.links.push(url.to_string());
+        let style = InlineStyle {
+            link: Some(out.links.len() - 1),
+            ..style
+        };
+        self.push_inline(text, style, out);
+    }
+
+    fn push_inline(&self, nodes: &[Node], style: InlineStyle, out: &mut Inline) {
+        for node in nodes {
+            match node {
+                Node::Text(t) => out.push(&t.value, style),
+                Node::Strong(n) => self.push_inline(
+                    &n.children,
+                    InlineStyle {
+                        strong: true,
+                        ..style
+                    },
+                    out,
+                ),
+                Node::Emphasis(n) => self.push_inline(
+                    &n.children,
+                    InlineStyle {
+                        emphasis: true,
+                        ..style
+                    },
+                    out,
+                ),
+                Node::Delete(n) => self.push_inline(
+                    &n.children,
+                    InlineStyle {
+                        strike: true,
+                        ..style
+                    },
+                    out,
+                ),
+                Node::InlineCode(c) => out.push(
+                    &c.value,
+                    InlineStyle {
+                        code: true,
+                        ..style
+                    },
+                ),
+                Node::InlineMath(m) => out.push(
+                    &m.value,
+                    InlineStyle {
+                        code: true,
+                        ..style
+                    },
+                ),
+                Node::Break(_) => out.push("\n", style),
+                Node::Html(html) => out.push(&html.value, style),
+                Node::Link(link) => self.push_link(&link.url, &link.children, style, out),
+                Node::LinkReference(r) => match self.definitions.get(&r.identifier) {
+                    Some(url) => self.push_link(url, &r.children, style, out),
+                    None => self.push_inline(&r.children, style, out),
+                },
+                // Images are shown as a link to the image, labelled by alt text.
+                Node::Image(image) => {
+                    let label = if image.alt.is_empty() {
+                        &image.url
+                    } else {
+                        &image.alt
+                    };
+                    out.links.push(image.url.clone());
+                    let link = Some(out.links.len() - 1);
+                    out.push(label, InlineStyle { link, ..style });
+                }
+                Node::ImageReference(image) => match self.definitions.get(&image.identifier) {
+                    Some(url) => {
+                        out.links.push(url.clone());
+                        let link = Some(out.links.len() - 1);
+                        out.push(&image.alt, InlineStyle { link, ..style });
+                    }
+                    None => out.push(&image.alt, style),
+                },
+                Node::FootnoteReference(f) => {
+                    let label = f.label.as_deref().unwrap_or(&f.identifier);
+                    out.push(&format!("[{}]", label), style);
+                }
+                other => match other.children() {
+                    Some(children) => self.push_inline(children, style, out),
+                    None => out.push(&other.to_string(), style),
+                },
+            }
+        }
+    }
+}
+
+/// Link click context shared by every paragraph of one component.
+#[derive(Clone)]
+struct LinkTarget {
+    component_id: String,
+    router: Option<String>,
+    handler: Option<String>,
+    runtime: Arc<NemoRuntime>,
+    entity_id: EntityId,
+}
+
+impl LinkTarget {
+    fn follow(&self, url: &str, cx: &mut App) {
+        if let Some(handler) = &self.handler {
+            self.runtime.call_handler(handler, &self.component_id, url);
+            cx.notify(self.entity_id);
+            return;
+        }
+        match link_action(url) {
+            LinkAction::Navigate(path) => {
+                self.runtime.enqueue_navigation(self.router.clone(), path)
+            }
+            LinkAction::Open(url) => cx.open_url(&url),
+            LinkAction::Ignore => {}
+        }
+    }
+}
+
+/// Walks the block tree building elements, handing out code-editor states in
+/// document order.
+struct Renderer {
+    id: String,
+    next_text: usize,
+    code_states: std::vec::IntoIter<Entity<InputState>>,
+    link_target: Option<LinkTarget>,
+    link_color: Hsla,
+    code_bg: Hsla,
+    border: Hsla,
+    muted: Hsla,
+    line_height: Pixels,
+}
+
+impl Renderer {
+    fn blocks(&mut self, blocks: &[Block], cx: &App) -> Vec<AnyElement> {
+        blocks.iter().map(|b| self.block(b, cx)).collect()
+    }
+
+    fn block(&mut self, block: &Block, cx: &App) -> AnyElement {
+        match block {
+            Block::Heading { level, text } => {
+                let size = match level {
+                    1 => FontSize::Xxl,
+                    2 => FontSize::Xl,
+                    3 => FontSize::Lg,
+                    _ => FontSize::Base,
+                };
+                let mut heading = div().text_t(size).font_weight(FontWeight::SEMIBOLD);
+                if *level <= 2 {
+                    heading = heading.mt_t(Space::Sm);
+                }
+                heading.child(self.inline(text)).into_any_element()
+            }
+            Block::Paragraph(text) => div().w_full().child(self.inline(text)).into_any_element(),
+            Block::Code { code, .. } => match self.code_states.next() {
+                Some(state) => {
+                    let rows = code.lines().count().max(1) as f32;
+                    div()
+                        .w_full()
+                        .child(
+                            GpuiInput::new(&state)
+                                .disabled(true)
+                                .h(self.line_height * rows + px(8.)),
+                        )
+                        .into_any_element()
+                }
+                None => div()
+                    .w_full()
+                    .p_t(Space::Sm)
+                    .bg(self.code_bg)
+                    .rounded(radius_of("md", cx))
+                    .child(code.clone())
+                    .into_any_element(),
+            },
+            Block::List {
+                ordered,
+                start,
+                items,
+            } => {
+                let mut list = div().flex().flex_col().gap_t(Space::Xs);
+                for (ix, item) in items.iter().enumerate() {
+                    let marker = match (item.checked, ordered) {
+                        (Some(true), _) => "☑".to_string(),
+                        (Some(false), _) => "☐".to_string(),
+                        (None, true) => format!("{}.", *start as usize + ix),
+                        (None, false) => "•".to_string(),
+                    };
+                    list = list.child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap_t(Space::Sm)
+                            .child(div().flex_shrink_0().text_color(self.muted).child(marker))
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .min_w(px(0.))
+                                    .gap_t(Space::Xs)
+                                    .children(self.blocks(&item.blocks, cx)),
+                            ),
+                    );
+                }
+                list.pl_t(Space::Sm).into_any_element()
+            }
+            Block::Quote(inner) => div()
+                .flex()
+                .flex_col()
+                .gap_t(Space::Sm)
+                .pl_t(Space::Md)
+                .border_l_4()
+                .border_color(self.border)
+                .text_color(self.muted)
+                .children(self.blocks(inner, cx))
+                .into_any_element(),
+            Block::Table {
+                align,
+                header,
+                rows,
+            } => {
+                let mut table = div()
+                    .flex()
+                    .flex_col()
+                    .w_full()
+                    .border_1()
+                    .border_color(self.border)
+                    .rounded(radius_of("sm", cx));
+                table = table.child(self.table_row(header, align, true));
+                for row in rows {
+                    table = table.child(self.table_row(row, align, false));
+                }
+                table.into_any_element()
+            }
+            Block::Rule => div()
+                .w_full()
+                .h(px(1.))
+                .my_t(Space::Sm)
+                .bg(self.border)
+                .into_any_element(),
+        }
+    }
+
+    fn table_row(&mut self, cells: &[Inline], align: &[Align], header: bool) -> AnyElement {
+        let mut row = div().flex().flex_row().w_full();
+        if header {
+            row = row.font_weight(FontWeight::SEMIBOLD).bg(self.code_bg);
+        } else {
+            row = row.border_t_1().border_color(self.border);
+        }
+        for (ix, cell) in cells.iter().enumerate() {
+            let cell_div = div()
+                .flex()
+                .flex_1()
+                .min_w(px(0.))
+                .px_t(Space::Sm)
+                .py_t(Space::Xs);
+            let cell_div = match align.get(ix) {
+                Some(Align::Center) => cell_div.justify_center(),
+                Some(Align::End) => cell_div.justify_end(),
+                _ => cell_div,
+            };
+            row = row.child(cell_div.child(self.inline(cell)));
+        }
+        row.into_any_element()
+    }
+
+    fn inline(&mut self, inline: &Inline) -> AnyElement {
+        let highlights: Vec<(Range<usize>, HighlightStyle)> = inline
+            .spans
+            .iter()
+            .map(|(range, style)| (range.clone(), self.highlight(style)))
+            .collect();
+        let text =
+            StyledText::new(SharedString::from(inline.text.clone())).with_highlights(highlights);
+
+        let links = inline.link_ranges();
+        let Some(target) = self.link_target.clone().filter(|_| !links.is_empty()) else {
+            return text.into_any_element();
+        };
+        let id = SharedString::from(format!("{}-text-{}", self.id, self.next_text));
+        self.next_text += 1;
+        let urls: Vec<String> = links
+            .iter()
+            .map(|(_, ix)| inline.links[*ix].clone())
+            .collect();
+        InteractiveText::new(id, text)
+            .on_click(
+                links.into_iter().map(|(range, _)| range).collect(),
+                move |ix, _window, cx| {
+                    if let Some(url) = urls.get(ix) {
+                        target.follow(url, cx);
+                    }
+                },
+            )
+            .into_any_element()
+    }
+
+    fn highlight(&self, style: &InlineStyle) -> HighlightStyle {
+        let mut highlight = HighlightStyle::default();
+        if style.strong {
+            highlight.font_weight = Some(FontWeight::BOLD);
+        }
+        if style.emphasis {
+            highlight.font_style = Some(FontStyle::Italic);
+        }
+        if style.strike {
+            highlight.strikethrough = Some(StrikethroughStyle {
+                thickness: px(1.),
+                color: None,
+            });
+        }
+        if style.code {
+            highlight.background_color = Some(self.code_bg);
+        }
+        if style.link.is_some() {
+            highlight.color = Some(self.link_color);
+            highlight.underline = Some(UnderlineStyle {
+                thickness: px(1.),
+                color: Some(self.link_color),
+                wavy: false,
+            });
+        }
+        highlight
+    }
+}
+
+impl RenderOnce for Markdown {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let link_target = match (self.runtime, self.entity_id) {
+            (Some(runtime), Some(entity_id)) => Some(LinkTarget {
+                component_id: self.source.id.clone(),
+                router: self.router,
+                handler: self.source.handlers.get("link").cloned(),
+                runtime,
+                entity_id,
+            }),
+            _ => None,
+        };
+        let mut renderer = Renderer {
+            id: self.source.id.clone(),
+            next_text: 0,
+            code_states: self.code_states.into_iter(),
+            link_target,
+            link_color: cx.theme().colors.link,
+            code_bg: cx.theme().colors.muted,
+            border: cx.theme().colors.border,
+            muted: cx.theme().colors.muted_foreground,
+            line_height: window.line_height(),
+        };
+
+        div()
+            .id(SharedString::from(self.source.id.clone()))
+            .flex()
+            .flex_col()
+            .w_full()
+            .gap_t(Space::Sm)
+            .children(renderer.blocks(&self.blocks, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled(inline: &Inline) -> Vec<(&str, InlineStyle)> {
+        inline
+            .spans
+            .iter()
+            .map(|(range, style)| (&inline.text[range.clone()], *style))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let blocks = parse(
+            "# Runbook\n\nRestart the **worker**.\n\n1. Drain\n2. Restart\n\n- [x] done\n- [ ] todo\n\n> careful\n\n---\n",
+        );
+        assert!(matches!(&blocks[0], Block::Heading { level: 1, text } if text.text == "Runbook"));
+        assert!(matches!(&blocks[1], Block::Paragraph(p) if p.text == "Restart the worker."));
+        match &blocks[2] {
+            Block::List {
+                ordered: true,
+                start: 1,
+                items,
+            } => assert_eq!(items.len(), 2),
+            other => panic!("expected ordered list, got {:?}", other),
+        }
+        match &blocks[3] {
+            Block::List { items, .. } => {
+                assert_eq!(items[0].checked, Some(true));
+                assert_eq!(items[1].checked, Some(false));
+            }
+            other => panic!("expected task list, got {:?}", other),
+        }
+        assert!(matches!(&blocks[4], Block::Quote(inner) if inner.len() == 1));
+        assert_eq!(blocks[5], Block::Rule);
+    }
+
+    #[test]
+    fn test_inline_spans_are_flat() {
+        let blocks = parse("a **b _c_** `d` ~~e~~ [f](/g)");
+        let Block::Paragraph(p) = &blocks[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(p.text, "a b c d e f");
+        let bold = InlineStyle {
+            strong: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            styled(p),
+            vec![
+                ("b ", bold),
+                (
+                    "c",
+                    InlineStyle {
+                        emphasis: true,
+                        ..bold
+                    }
+                ),
+                (
+                    "d",
+                    InlineStyle {
+                        code: true,
+                        ..Default::default()
+                    }
+                ),
+                (
+                    "e",
+                    InlineStyle {
+                        strike: true,
+                        ..Default::default()
+                    }
+                ),
+                (
+                    "f",
+                    InlineStyle {
+                        link: Some(0),
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(p.links, vec!["/g".to_string()]);
+    }
+
+    #[test]
+    fn test_link_ranges_merge_styled_runs() {
+        let blocks = parse("See [the **docs**][ref].\n\n[ref]: https://example.com");
+        assert_eq!(blocks.len(), 1);
+        let Block::Paragraph(p) = &blocks[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(p.links, vec!["https://example.com".to_string()]);
+        let ranges = p.link_ranges();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&p.text[ranges[0].0.clone()], "the docs");
+    }
+
+    #[test]
+    fn test_table_and_code_blocks() {
+        let blocks = parse(
+            "| Name | Count |\n|:-----|------:|\n| a | 1 |\n| b | 2 |\n\n```sh\nls\n```\n\n- item\n\n  ```rust\n  fn main() {}\n  ```\n",
+        );
+        match &blocks[0] {
+            Block::Table {
+                align,
+                header,
+                rows,
+            } => {
+                assert_eq!(align, &vec![Align::Start, Align::End]);
+                assert_eq!(header[1].text, "Count");
+                assert_eq!(rows.len(), 2);
+                assert_eq!(rows[1][0].text, "b");
+            }
+            other => panic!("expected table, got {:?}", other),
+        }
+        assert_eq!(
+            code_blocks(&blocks),
+            vec![
+                (Some("sh".to_string()), "ls".to_string()),
+                (Some("rust".to_string()), "fn main() {}".to_string()),
+            ]
+        );
+        assert_eq!(highlight_language(Some("sh")), "bash");
+        assert_eq!(highlight_language(Some("rust")), "rust");
+        assert_eq!(highlight_language(None), "plain");
+    }
+
+    #[test]
+    fn test_link_action() {
+        assert_eq!(
+            link_action("/orders/42"),
+            LinkAction::Navigate("/orders/42".to_string())
+        );
+        assert_eq!(
+            link_action("https://example.com"),
+            LinkAction::Open("https://example.com".to_string())
+        );
+        assert_eq!(
+            link_action("mailto:ops@example.com"),
+            LinkAction::Open("mailto:ops@example.com".to_string())
+        );
+        assert_eq!(
+            link_action("HTTPS://example.com"),
+            LinkAction::Open("HTTPS://example.com".to_string())
+        );
+        // Only web and mail links open.
+        assert_eq!(link_action("file:///etc/passwd"), LinkAction::Ignore);
+        assert_eq!(link_action("javascript:alert(1)"), LinkAction::Ignore);
+        assert_eq!(link_action("ssh://host"), LinkAction::Ignore);
+        assert_eq!(link_action("#install"), LinkAction::Ignore);
+        assert_eq!(link_action("notes.md"), LinkAction::Ignore);
+    }
+}
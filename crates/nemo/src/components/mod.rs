@@ -7,15 +7,21 @@ mod bar_chart;
 mod bubble_chart;
 mod button;
 mod candlestick_chart;
+pub(crate) mod chart_annotations;
 pub(crate) mod chart_utils;
 mod checkbox;
 mod clustered_bar_chart;
 mod clustered_column_chart;
 mod code_editor;
 mod collapsible;
+pub(crate) mod color_picker;
 mod column_chart;
+pub(crate) mod context_menu;
+pub(crate) mod date_picker;
 mod dropdown_button;
+pub(crate) mod export;
 mod funnel_chart;
+mod gauge;
 mod heatmap_chart;
 pub(crate) mod icon;
 mod image;
@@ -23,8 +29,10 @@ mod input;
 mod label;
 mod line_chart;
 mod list;
+pub(crate) mod markdown;
 mod modal;
 mod notification;
+pub(crate) mod number_input;
 mod panel;
 mod pie_chart;
 mod progress;
@@ -36,10 +44,12 @@ mod scatter_chart;
 mod select;
 pub(crate) mod sidenav_bar;
 pub(crate) mod slider;
+pub(crate) mod sparkline;
 mod spinner;
 mod stack;
 mod stacked_bar_chart;
 mod stacked_column_chart;
+mod stat;
 pub(crate) mod state;
 mod svg;
 mod switch;
@@ -49,9 +59,11 @@ mod tag;
 mod text;
 mod text_editor;
 mod textarea;
+pub(crate) mod time_series;
 mod toggle;
 mod tooltip;
 pub(crate) mod tree;
+pub(crate) mod virtual_list;
 
 use gpui::*;
 use gpui_component::ActiveTheme;
@@ -188,6 +200,7 @@ pub(crate) fn apply_rounded(base: Div, rounded: Option<&str>, cx: &App) -> Div {
     }
 }
 
+pub use self::markdown::Markdown;
 pub use accordion::{Accordion, AccordionItemData};
 pub use alert::Alert;
 pub use area_chart::AreaChart;
@@ -202,9 +215,12 @@ pub use clustered_bar_chart::ClusteredBarChart;
 pub use clustered_column_chart::ClusteredColumnChart;
 pub use code_editor::CodeEditor;
 pub use collapsible::Collapsible;
+pub use color_picker::ColorPicker;
 pub use column_chart::ColumnChart;
+pub use date_picker::DatePicker;
 pub use dropdown_button::{DropdownButton, MenuItem};
 pub use funnel_chart::FunnelChart;
+pub use gauge::Gauge;
 pub use heatmap_chart::HeatmapChart;
 pub use icon::Icon;
 pub use image::Image;
@@ -214,6 +230,7 @@ pub use line_chart::LineChart;
 pub use list::List;
 pub use modal::Modal;
 pub use notification::Notification;
+pub use number_input::NumberInput;
 pub use panel::Panel;
 pub use pie_chart::PieChart;
 pub use progress::Progress;
@@ -225,10 +242,12 @@ pub use scatter_chart::ScatterChart;
 pub use select::{OptionData, Select};
 pub use sidenav_bar::SidenavBar;
 pub use slider::Slider;
+pub use sparkline::Sparkline;
 pub use spinner::Spinner;
 pub use stack::Stack;
 pub use stacked_bar_chart::StackedBarChart;
 pub use stacked_column_chart::StackedColumnChart;
+pub use stat::Stat;
 pub use svg::Svg;
 pub use switch::Switch;
 pub use table::Table;
@@ -240,6 +259,7 @@ pub use textarea::Textarea;
 pub use toggle::Toggle;
 pub use tooltip::Tooltip;
 pub use tree::Tree;
+pub use virtual_list::VirtualList;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,207 @@
+use gpui::*;
+use gpui_component::input::{InputState, NumberInput as GpuiNumberInput};
+use gpui_component::ActiveTheme;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+/// A numeric field with increment/decrement buttons.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <number_input id="qty" value="1" min="1" max="99" step="1" on-change="set_qty" />
+///
+/// <number_input id="weight" value="2.5" step="0.25" precision="2" unit="kg">
+///   <binding source="data.parcel.weight" target="value" mode="two_way" />
+/// </number_input>
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `value` | float | Current value |
+/// | `min` / `max` | float | Bounds; typed and stepped values are clamped to them |
+/// | `step` | float | Amount the buttons and arrow keys change the value by (default: 1) |
+/// | `precision` | int | Decimal places values are rounded to |
+/// | `unit` | string | Unit shown after the number |
+/// | `placeholder` | string | Text shown when empty |
+/// | `on-change` | string | Handler called with the new value |
+#[derive(IntoElement)]
+pub struct NumberInput {
+    source: BuiltComponent,
+    state: Option<Entity<InputState>>,
+}
+
+impl NumberInput {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            state: None,
+        }
+    }
+
+    pub fn state(mut self, state: Entity<InputState>) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
+impl RenderOnce for NumberInput {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let Some(state) = self.state else {
+            return div().child("NumberInput: missing state").into_any_element();
+        };
+        let props = &self.source.properties;
+        let mut input = GpuiNumberInput::new(&state);
+        if let Some(unit) = props.get("unit").and_then(|v| v.as_str()) {
+            input = input.suffix(
+                div()
+                    .text_color(cx.theme().colors.muted_foreground)
+                    .child(unit.to_string()),
+            );
+        }
+        if props.get("disabled").and_then(|v| v.as_bool()) == Some(true) {
+            input = input.disabled(true);
+        }
+        input.into_any_element()
+    }
+}
+
+/// The numeric constraints of a `number_input`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct NumberSpec {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: f64,
+    pub precision: Option<usize>,
+}
+
+impl NumberSpec {
+    pub(crate) fn from_component(component: &BuiltComponent) -> Self {
+        let props = &component.properties;
+        let number = |key: &str| props.get(key).and_then(|v| v.as_f64());
+        Self {
+            min: number("min"),
+            max: number("max"),
+            step: number("step").filter(|s| *s > 0.0).unwrap_or(1.0),
+            precision: props
+                .get("precision")
+                .and_then(|v| v.as_i64())
+                .map(|p| p.clamp(0, 12) as usize),
+        }
+    }
+
+    /// Clamps `n` into bounds and rounds it to `precision`.
+    pub(crate) fn normalize(&self, n: f64) -> f64 {
+        let n = self.min.map_or(n, |min| n.max(min));
+        let n = self.max.map_or(n, |max| n.min(max));
+        match self.precision {
+            Some(p) => {
+                let scale = 10f64.powi(p as i32);
+                (n * scale).round() / scale
+            }
+            None => n,
+        }
+    }
+
+    /// One step up (`up`) or down from `current`; an empty field steps from
+    /// `min`, or zero.
+    pub(crate) fn step_from(&self, current: Option<f64>, up: bool) -> f64 {
+        let Some(current) = current else {
+            return self.normalize(self.min.unwrap_or(0.0));
+        };
+        let next = if up {
+            current + self.step
+        } else {
+            current - self.step
+        };
+        // Round away float error from repeated fractional steps.
+        self.normalize((next * 1e9).round() / 1e9)
+    }
+
+    /// Whether values are whole numbers, so they're written back as integers.
+    pub(crate) fn is_integral(&self) -> bool {
+        match self.precision {
+            Some(p) => p == 0,
+            None => self.step.fract() == 0.0,
+        }
+    }
+
+    /// The text shown for `n`: `precision` decimals, or as few as needed.
+    pub(crate) fn format(&self, n: f64) -> String {
+        match self.precision {
+            Some(p) => format!("{:.*}", p, n),
+            None => n.to_string(),
+        }
+    }
+
+    /// The `value` written back for `n`.
+    pub(crate) fn value_of(&self, n: f64) -> Value {
+        if self.is_integral() && n.fract() == 0.0 {
+            Value::Integer(n as i64)
+        } else {
+            Value::Float(n)
+        }
+    }
+}
+
+/// Parses typed text, ignoring a trailing unit and digit-group separators.
+/// Returns `None` for empty or non-numeric text.
+pub(crate) fn parse_number(text: &str, unit: Option<&str>) -> Option<f64> {
+    let text = text.trim();
+    let text = unit
+        .and_then(|u| text.strip_suffix(u))
+        .unwrap_or(text)
+        .trim();
+    let cleaned: String = text.chars().filter(|c| *c != ',' && *c != '_').collect();
+    cleaned.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(min: Option<f64>, max: Option<f64>, step: f64, precision: Option<usize>) -> NumberSpec {
+        NumberSpec {
+            min,
+            max,
+            step,
+            precision,
+        }
+    }
+
+    #[test]
+    fn test_normalize_and_step() {
+        let s = spec(Some(0.0), Some(1.0), 0.1, None);
+        assert_eq!(s.normalize(1.7), 1.0);
+        assert_eq!(s.normalize(-3.0), 0.0);
+        assert_eq!(s.step_from(Some(0.2), true), 0.3);
+        assert_eq!(s.step_from(Some(0.95), true), 1.0);
+        assert_eq!(s.step_from(Some(0.05), false), 0.0);
+        assert_eq!(s.step_from(None, true), 0.0);
+
+        let s = spec(None, None, 0.25, Some(1));
+        assert_eq!(s.normalize(2.46), 2.5);
+        assert_eq!(s.format(2.0), "2.0");
+        assert_eq!(s.value_of(2.0), Value::Float(2.0));
+    }
+
+    #[test]
+    fn test_integral_values() {
+        let s = spec(Some(1.0), Some(99.0), 1.0, None);
+        assert!(s.is_integral());
+        assert_eq!(s.value_of(3.0), Value::Integer(3));
+        assert_eq!(s.format(3.0), "3");
+        assert!(!spec(None, None, 1.0, Some(2)).is_integral());
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number(" 1,250.5 ", None), Some(1250.5));
+        assert_eq!(parse_number("2.5 kg", Some("kg")), Some(2.5));
+        assert_eq!(parse_number("", None), None);
+        assert_eq!(parse_number("abc", None), None);
+        assert_eq!(parse_number("inf", None), None);
+    }
+}
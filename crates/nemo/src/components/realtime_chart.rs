@@ -1,20 +1,26 @@
 use gpui::*;
-use gpui_component::plot::{
-    scale::{Scale, ScaleLinear, ScalePoint},
-    shape::Line,
-    AxisText, Grid, IntoPlot, Plot, PlotAxis, StrokeStyle, AXIS_GAP,
-};
-use gpui_component::ActiveTheme;
-use nemo_config::Value;
+use gpui_component::plot::StrokeStyle;
 use nemo_macros::NemoComponent;
+use std::sync::{Arc, Mutex};
 
+use super::chart_annotations::Annotation;
+use super::chart_utils::ChartView;
 use super::chart_utils::{
-    chart_color, empty_chart_placeholder, extract_data_array, get_f64_field, get_string_array,
-    get_string_field,
+    chart_color, empty_chart_placeholder, extract_data_array, get_string_array,
 };
+use super::time_series::{SeriesChart, SeriesSpec};
 
 /// A realtime streaming line chart that updates as new data arrives.
 ///
+/// With `x-scale="time"` the x axis is a true time scale: `x-field` holds
+/// epoch seconds/milliseconds or date-time strings and gets round tick
+/// labels. `window` keeps only the newest stretch of samples, `downsample`
+/// thins every series to the pixel width, and `y2-fields` go on a secondary
+/// y-axis.
+/// `<threshold>`, `<band>` and `<marker>` children annotate the plot (see
+/// [`super::chart_annotations`]) and `legend` adds a legend whose entries
+/// toggle their series. See [`super::time_series`].
+///
 /// # XML Configuration
 ///
 /// ```xml
@@ -22,6 +28,12 @@ use super::chart_utils::{
 ///   linear="true" tick-margin="20" height="300">
 ///   <data bind="metricsStream" />
 /// </realtime-chart>
+///
+/// <realtime-chart id="telemetry" x-field="ts" x-scale="time" window="5m"
+///   y-fields='["temp"]' y2-fields='["pressure"]' downsample="minmax">
+///   <data bind="state.telemetry" />
+///   <threshold value="80" label="max" color="theme.danger" />
+/// </realtime-chart>
 /// ```
 ///
 /// # Properties
@@ -29,9 +41,14 @@ use super::chart_utils::{
 /// | Property | Type | Description |
 /// |----------|------|-------------|
 /// | `x-field` | string | Data field for the x-axis (typically time) |
+/// | `x-scale` | string | `"point"` (one slot per item, default) or `"time"` |
 /// | `y-fields` | JSON array | Array of data field names for each line series |
+/// | `y2-fields` | JSON array | Series scaled against a secondary y-axis on the right |
+/// | `window` | string | Only show the newest stretch, e.g. `"30s"`, `"5m"`, `"1h"` (implies a time axis) |
+/// | `downsample` | string | `"none"` (default), `"lttb"` or `"minmax"` |
+/// | `legend` | bool | Show a legend; clicking an entry hides or shows its series |
 /// | `linear` | bool | Use linear interpolation instead of curved |
-/// | `tick-margin` | int | Margin for axis tick labels |
+/// | `tick-margin` | int | Label every n-th item on a point axis |
 /// | `height` | int | Chart height in pixels |
 #[derive(IntoElement, NemoComponent)]
 pub struct RealtimeChart {
@@ -43,6 +60,28 @@ pub struct RealtimeChart {
     tick_margin: Option<i64>,
     #[source]
     source: nemo_layout::BuiltComponent,
+    annotations: Vec<Annotation>,
+    view: Option<Arc<Mutex<ChartView>>>,
+    entity_id: Option<EntityId>,
+}
+
+impl RealtimeChart {
+    /// The chart's `<threshold>`/`<band>`/`<marker>` children.
+    pub fn annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// View state holding the series hidden from the legend.
+    pub fn view(mut self, view: Arc<Mutex<ChartView>>) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
 }
 
 impl RenderOnce for RealtimeChart {
@@ -52,8 +91,9 @@ impl RenderOnce for RealtimeChart {
             return empty_chart_placeholder(cx);
         }
 
-        let y_fields = get_string_array(&self.source, "y_fields");
-        if y_fields.is_empty() {
+        let primary = get_string_array(&self.source, "y_fields");
+        let secondary = get_string_array(&self.source, "y2_fields");
+        if primary.is_empty() && secondary.is_empty() {
             return empty_chart_placeholder(cx);
         }
 
@@ -62,119 +102,25 @@ impl RenderOnce for RealtimeChart {
         } else {
             StrokeStyle::Natural
         };
-        let tick_margin = self.tick_margin.unwrap_or(1) as usize;
 
-        let series: Vec<(String, Hsla)> = y_fields
+        let specs: Vec<SeriesSpec> = primary
             .into_iter()
+            .map(|field| (field, false))
+            .chain(secondary.into_iter().map(|field| (field, true)))
             .enumerate()
-            .map(|(i, field)| (field, chart_color(i, cx)))
-            .collect();
-
-        MultiLineChartInner {
-            data,
-            x_field: self.x_field,
-            series,
-            stroke_style,
-            tick_margin,
-        }
-        .into_any_element()
-    }
-}
-
-/// Inner chart that implements Plot for multi-line rendering.
-#[derive(IntoPlot)]
-struct MultiLineChartInner {
-    data: Vec<Value>,
-    x_field: String,
-    series: Vec<(String, Hsla)>,
-    stroke_style: StrokeStyle,
-    tick_margin: usize,
-}
-
-impl Plot for MultiLineChartInner {
-    fn paint(&mut self, bounds: Bounds<Pixels>, window: &mut Window, cx: &mut App) {
-        if self.series.is_empty() {
-            return;
-        }
-
-        let width = bounds.size.width.as_f32();
-        let height = bounds.size.height.as_f32() - AXIS_GAP;
-
-        // X scale
-        let x_field = self.x_field.clone();
-        let x = ScalePoint::new(
-            self.data
-                .iter()
-                .map(|v| get_string_field(v, &x_field))
-                .collect(),
-            vec![0., width],
-        );
-
-        // Y scale: compute domain from all series values
-        let y_values: Vec<f64> = self
-            .data
-            .iter()
-            .flat_map(|item| {
-                self.series
-                    .iter()
-                    .map(move |(field, _)| get_f64_field(item, field))
+            .map(|(i, (field, secondary))| SeriesSpec {
+                field,
+                secondary,
+                color: chart_color(i, cx),
             })
-            .chain(Some(0.0))
             .collect();
-        let y = ScaleLinear::new(y_values, vec![height, 10.]);
-
-        // Draw X axis
-        let data_len = self.data.len();
-        let x_label = self.data.iter().enumerate().filter_map(|(i, d)| {
-            if (i + 1) % self.tick_margin == 0 {
-                let label = get_string_field(d, &self.x_field);
-                x.tick(&label).map(|x_tick| {
-                    let align = match i {
-                        0 if data_len == 1 => TextAlign::Center,
-                        0 => TextAlign::Left,
-                        i if i == data_len - 1 => TextAlign::Right,
-                        _ => TextAlign::Center,
-                    };
-                    AxisText::new(
-                        SharedString::from(label),
-                        x_tick,
-                        cx.theme().muted_foreground,
-                    )
-                    .align(align)
-                })
-            } else {
-                None
-            }
-        });
-
-        PlotAxis::new()
-            .x(height)
-            .x_label(x_label)
-            .stroke(cx.theme().border)
-            .paint(&bounds, window, cx);
 
-        // Draw grid
-        Grid::new()
-            .y((0..=3).map(|i| height * i as f32 / 4.0).collect())
-            .stroke(cx.theme().border)
-            .dash_array(&[px(4.), px(2.)])
-            .paint(&bounds, window);
-
-        // Draw one Line per series
-        for (field, color) in &self.series {
-            let x = x.clone();
-            let y = y.clone();
-            let x_field = self.x_field.clone();
-            let field = field.clone();
-
-            Line::new()
-                .data(&self.data)
-                .x(move |d| x.tick(&get_string_field(d, &x_field)))
-                .y(move |d| y.tick(&get_f64_field(d, &field)))
-                .stroke(*color)
-                .stroke_style(self.stroke_style)
-                .stroke_width(2.)
-                .paint(&bounds, window);
-        }
+        SeriesChart::new(data, &self.source.properties, specs)
+            .x_field(self.x_field)
+            .stroke_style(stroke_style)
+            .tick_margin(self.tick_margin.unwrap_or(1) as usize)
+            .annotations(self.annotations)
+            .view(self.view, self.entity_id)
+            .into_any_element()
     }
 }
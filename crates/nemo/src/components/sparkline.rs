@@ -0,0 +1,192 @@
+use gpui::*;
+use gpui_component::plot::{IntoPlot, Plot};
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+use super::chart_utils::{chart_color, extract_data_array};
+use super::resolve_color;
+
+/// Default sparkline height in pixels.
+const DEFAULT_HEIGHT: f32 = 32.0;
+
+/// A word-sized line chart without axes, for trends next to a number.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <sparkline id="cpu-trend" fill="true" color="theme.chart_2">
+///   <binding source="data.metrics.cpu_history" target="data" />
+/// </sparkline>
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `data` | array | Numbers, or objects read through `field` |
+/// | `field` | string | Value field when `data` holds objects |
+/// | `min` / `max` | float | Fixed y range (defaults to the data's extent) |
+/// | `color` | string | Line color (`theme.*` or hex; default: first chart color) |
+/// | `fill` | bool | Shade the area under the line |
+/// | `dot` | bool | Mark the latest value (default: true) |
+/// | `height` | int | Height in pixels (default: 32) |
+#[derive(IntoElement)]
+pub struct Sparkline {
+    source: BuiltComponent,
+}
+
+impl Sparkline {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self { source }
+    }
+}
+
+/// The numbers a sparkline plots: `data` entries that are numbers, or the
+/// `field` of entries that are objects. Anything else is skipped.
+pub(crate) fn values(data: &[Value], field: Option<&str>) -> Vec<f64> {
+    data.iter()
+        .filter_map(|item| match (item, field) {
+            (Value::Object(_), Some(field)) => item.get(field)?.as_f64(),
+            (item, _) => item.as_f64(),
+        })
+        .collect()
+}
+
+/// The y range to plot `values` over, honouring fixed `min`/`max`. A flat
+/// series gets a unit range so it draws through the middle.
+pub(crate) fn y_range(values: &[f64], min: Option<f64>, max: Option<f64>) -> (f64, f64) {
+    let lo = min.unwrap_or_else(|| values.iter().copied().fold(f64::INFINITY, f64::min));
+    let hi = max.unwrap_or_else(|| values.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+    if !lo.is_finite() || !hi.is_finite() {
+        (0.0, 1.0)
+    } else if hi > lo {
+        (lo, hi)
+    } else {
+        (lo - 0.5, lo + 0.5)
+    }
+}
+
+impl RenderOnce for Sparkline {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let number = |key: &str| props.get(key).and_then(|v| v.as_f64());
+        let values = values(
+            &extract_data_array(&self.source),
+            props.get("field").and_then(|v| v.as_str()),
+        );
+        let color = props
+            .get("color")
+            .and_then(|v| v.as_str())
+            .and_then(|c| resolve_color(c, cx))
+            .unwrap_or_else(|| chart_color(0, cx));
+        let height = number("height").map(|h| h as f32).unwrap_or(DEFAULT_HEIGHT);
+
+        div().w_full().h(px(height)).child(SparklinePlot {
+            range: y_range(&values, number("min"), number("max")),
+            values,
+            color,
+            fill: props.get("fill").and_then(|v| v.as_bool()) == Some(true),
+            dot: props.get("dot").and_then(|v| v.as_bool()) != Some(false),
+        })
+    }
+}
+
+#[derive(IntoPlot)]
+pub(crate) struct SparklinePlot {
+    pub values: Vec<f64>,
+    pub range: (f64, f64),
+    pub color: Hsla,
+    pub fill: bool,
+    pub dot: bool,
+}
+
+impl Plot for SparklinePlot {
+    fn paint(&mut self, bounds: Bounds<Pixels>, window: &mut Window, _cx: &mut App) {
+        let n = self.values.len();
+        if n == 0 {
+            return;
+        }
+        // Inset by the dot radius so the latest-value dot isn't clipped.
+        let inset = 2.5;
+        let width = bounds.size.width.as_f32() - inset * 2.0;
+        let height = bounds.size.height.as_f32() - inset * 2.0;
+        let (lo, hi) = self.range;
+        let origin = bounds.origin;
+        let points: Vec<Point<Pixels>> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let x = if n == 1 {
+                    width / 2.0
+                } else {
+                    width * i as f32 / (n - 1) as f32
+                };
+                let y = height * (1.0 - ((v.clamp(lo, hi) - lo) / (hi - lo)) as f32);
+                point(origin.x + px(inset + x), origin.y + px(inset + y))
+            })
+            .collect();
+
+        if self.fill && n > 1 {
+            let baseline = origin.y + px(inset + height);
+            let mut area = points.clone();
+            area.push(point(points[n - 1].x, baseline));
+            area.push(point(points[0].x, baseline));
+            area.push(points[0]);
+            let mut builder = PathBuilder::fill();
+            builder.add_polygon(&area, false);
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, self.color.opacity(0.2));
+            }
+        }
+
+        if n > 1 {
+            let mut builder = PathBuilder::stroke(px(1.5));
+            builder.add_polygon(&points, false);
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, self.color);
+            }
+        }
+
+        if self.dot || n == 1 {
+            let last = points[n - 1];
+            let r = px(inset);
+            window.paint_quad(PaintQuad {
+                bounds: Bounds::from_corners(
+                    point(last.x - r, last.y - r),
+                    point(last.x + r, last.y + r),
+                ),
+                corner_radii: Corners::all(r),
+                background: self.color.into(),
+                border_widths: Edges::default(),
+                border_color: transparent_black(),
+                border_style: Default::default(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_from_numbers_and_objects() {
+        let numbers = vec![Value::Integer(1), Value::Float(2.5), Value::Null];
+        assert_eq!(values(&numbers, None), vec![1.0, 2.5]);
+
+        let object =
+            |v: f64| Value::Object([("cpu".to_string(), Value::Float(v))].into_iter().collect());
+        let objects = vec![object(10.0), object(20.0)];
+        assert_eq!(values(&objects, Some("cpu")), vec![10.0, 20.0]);
+        assert!(values(&objects, Some("mem")).is_empty());
+    }
+
+    #[test]
+    fn test_y_range() {
+        assert_eq!(y_range(&[3.0, 1.0, 2.0], None, None), (1.0, 3.0));
+        assert_eq!(y_range(&[3.0, 1.0], Some(0.0), Some(10.0)), (0.0, 10.0));
+        assert_eq!(y_range(&[5.0, 5.0], None, None), (4.5, 5.5));
+        assert_eq!(y_range(&[], None, None), (0.0, 1.0));
+    }
+}
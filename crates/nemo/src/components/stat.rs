@@ -0,0 +1,265 @@
+use gpui::*;
+use gpui_component::{ActiveTheme, Icon, IconName, Sizable};
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+
+use super::chart_utils::{chart_color, extract_data_array};
+use super::sparkline::{self, SparklinePlot};
+use super::table::format_number;
+use super::time_series::format_value;
+use crate::theme::tokens::{radius_of, FontSize, Space, TokenStyled};
+
+/// A KPI card: a headline value with its unit, the change since the previous
+/// value and an optional sparkline of its history.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <stat id="revenue" label="Revenue" format="number" decimals="0" prefix="$">
+///   <binding source="data.kpi.revenue" target="value" />
+///   <binding source="data.kpi.revenue_last_week" target="previous" />
+///   <binding source="data.kpi.revenue_history" target="data" />
+/// </stat>
+///
+/// <stat id="errors" label="Error rate" value="0.012" previous="0.02"
+///   format="percent" decimals="1" invert="true" />
+/// ```
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `label` | string | Caption above the value |
+/// | `value` | any | The value; numbers are formatted, anything else shown as-is |
+/// | `previous` | float | Earlier value the delta is measured against |
+/// | `delta` | float | Explicit change, instead of `value - previous` |
+/// | `format` | string | `"number"` (default), `"compact"` (`1.2k`) or `"percent"` (0.42 → 42%) |
+/// | `decimals` | int | Decimal places |
+/// | `prefix` / `unit` | string | Text before / after the value |
+/// | `invert` | bool | A fall is good news (error rates, latency) |
+/// | `data` | array | History drawn as a sparkline (numbers, or objects with `field`) |
+/// | `field` | string | Value field of object history entries |
+#[derive(IntoElement)]
+pub struct Stat {
+    source: BuiltComponent,
+}
+
+impl Stat {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self { source }
+    }
+}
+
+/// How a stat formats numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StatFormat {
+    Number,
+    Compact,
+    Percent,
+}
+
+impl StatFormat {
+    pub(crate) fn parse(name: Option<&str>) -> Self {
+        match name {
+            Some("compact") => StatFormat::Compact,
+            Some("percent") => StatFormat::Percent,
+            _ => StatFormat::Number,
+        }
+    }
+
+    pub(crate) fn format(self, n: f64, decimals: Option<usize>) -> String {
+        match self {
+            StatFormat::Number => format_number(n, decimals),
+            StatFormat::Compact => format_value(n),
+            StatFormat::Percent => format!("{:.*}%", decimals.unwrap_or(0), n * 100.0),
+        }
+    }
+}
+
+/// Direction of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Trend {
+    Up,
+    Down,
+    Flat,
+}
+
+/// The change shown under the value: its direction and text. Percent stats
+/// show the change in percentage points; others show the relative change
+/// when there is a non-zero previous value, else the absolute change.
+pub(crate) fn delta(
+    value: Option<f64>,
+    previous: Option<f64>,
+    explicit: Option<f64>,
+    format: StatFormat,
+    decimals: Option<usize>,
+) -> Option<(Trend, String)> {
+    let change = explicit.or_else(|| Some(value? - previous?))?;
+    let trend = if change > 0.0 {
+        Trend::Up
+    } else if change < 0.0 {
+        Trend::Down
+    } else {
+        Trend::Flat
+    };
+    let sign = if change > 0.0 { "+" } else { "" };
+    let text = match (format, previous) {
+        (StatFormat::Percent, _) => {
+            format!("{}{:.*} pp", sign, decimals.unwrap_or(0), change * 100.0)
+        }
+        (_, Some(prev)) if explicit.is_none() && prev != 0.0 => {
+            format!("{}{:.1}%", sign, change / prev.abs() * 100.0)
+        }
+        _ => format!("{}{}", sign, format.format(change, decimals)),
+    };
+    Some((trend, text))
+}
+
+impl RenderOnce for Stat {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let number = |key: &str| props.get(key).and_then(|v| v.as_f64());
+        let text = |key: &str| props.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let format = StatFormat::parse(props.get("format").and_then(|v| v.as_str()));
+        let decimals = props
+            .get("decimals")
+            .and_then(|v| v.as_i64())
+            .map(|d| d.max(0) as usize);
+        let theme = &cx.theme().colors;
+
+        let value = match props.get("value") {
+            None | Some(Value::Null) => "–".to_string(),
+            Some(v) => match v.as_f64() {
+                Some(n) => format.format(n, decimals),
+                None => v
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| v.to_string()),
+            },
+        };
+        let headline = div()
+            .flex()
+            .items_baseline()
+            .gap_t(Space::Xs)
+            .children(text("prefix").map(|p| div().text_t(FontSize::Lg).child(p)))
+            .child(
+                div()
+                    .text_t(FontSize::Xxl)
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child(value),
+            )
+            .children(text("unit").map(|unit| {
+                div()
+                    .text_t(FontSize::Sm)
+                    .text_color(theme.muted_foreground)
+                    .child(unit)
+            }));
+
+        let change = delta(
+            number("value"),
+            number("previous"),
+            number("delta"),
+            format,
+            decimals,
+        )
+        .map(|(trend, change)| {
+            let invert = props.get("invert").and_then(|v| v.as_bool()) == Some(true);
+            let (icon, good) = match trend {
+                Trend::Up => (IconName::ArrowUp, !invert),
+                Trend::Down => (IconName::ArrowDown, invert),
+                Trend::Flat => (IconName::Minus, true),
+            };
+            let color = match (trend, good) {
+                (Trend::Flat, _) => theme.muted_foreground,
+                (_, true) => theme.success,
+                (_, false) => theme.danger,
+            };
+            div()
+                .flex()
+                .items_center()
+                .gap_t(Space::Xs)
+                .text_t(FontSize::Xs)
+                .text_color(color)
+                .child(Icon::new(icon).xsmall())
+                .child(change)
+        });
+
+        let history = sparkline::values(
+            &extract_data_array(&self.source),
+            props.get("field").and_then(|v| v.as_str()),
+        );
+        let trend_line = (history.len() > 1).then(|| {
+            div().w_full().h(px(32.)).child(SparklinePlot {
+                range: sparkline::y_range(&history, None, None),
+                values: history,
+                color: chart_color(0, cx),
+                fill: true,
+                dot: true,
+            })
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_t(Space::Xs)
+            .p_t(Space::Md)
+            .border_1()
+            .border_color(theme.border)
+            .rounded(radius_of("md", cx))
+            .bg(theme.background)
+            .children(text("label").map(|label| {
+                div()
+                    .text_t(FontSize::Sm)
+                    .text_color(theme.muted_foreground)
+                    .child(label)
+            }))
+            .child(headline)
+            .children(change)
+            .children(trend_line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(StatFormat::Number.format(1234567.0, None), "1,234,567");
+        assert_eq!(StatFormat::Number.format(12.345, Some(1)), "12.3");
+        assert_eq!(StatFormat::Compact.format(1_500_000.0, None), "1.5M");
+        assert_eq!(StatFormat::Percent.format(0.4234, Some(1)), "42.3%");
+        assert_eq!(StatFormat::parse(Some("bogus")), StatFormat::Number);
+    }
+
+    #[test]
+    fn test_delta() {
+        let n = StatFormat::Number;
+        assert_eq!(
+            delta(Some(120.0), Some(100.0), None, n, None),
+            Some((Trend::Up, "+20.0%".to_string()))
+        );
+        assert_eq!(
+            delta(Some(80.0), Some(100.0), None, n, None),
+            Some((Trend::Down, "-20.0%".to_string()))
+        );
+        // No previous value to compare against: absolute change.
+        assert_eq!(
+            delta(Some(5.0), Some(0.0), None, n, None),
+            Some((Trend::Up, "+5".to_string()))
+        );
+        assert_eq!(
+            delta(None, None, Some(-1500.0), n, None),
+            Some((Trend::Down, "-1,500".to_string()))
+        );
+        assert_eq!(
+            delta(Some(0.012), Some(0.02), None, StatFormat::Percent, Some(1)),
+            Some((Trend::Down, "-0.8 pp".to_string()))
+        );
+        assert_eq!(
+            delta(Some(3.0), Some(3.0), None, n, None),
+            Some((Trend::Flat, "0.0%".to_string()))
+        );
+        assert_eq!(delta(Some(3.0), None, None, n, None), None);
+    }
+}
@@ -1,4 +1,7 @@
+use chrono::NaiveDate;
 use gpui::Entity;
+use gpui_component::color_picker::ColorPickerState;
+use gpui_component::date_picker::DatePickerState;
 use gpui_component::input::InputState;
 use gpui_component::slider::SliderState;
 use gpui_component::table::TableState;
@@ -8,7 +11,10 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
+use super::chart_utils::ChartView;
+use super::markdown::Block;
 use super::table::NemoTableDelegate;
+use super::virtual_list::VirtualListState;
 
 /// Per-component runtime state.
 ///
@@ -59,11 +65,56 @@ pub enum ComponentState {
     Table {
         state: Entity<TableState<NemoTableDelegate>>,
         last_data: Vec<Value>,
+        /// Last `sort` property applied to the delegate, so a bound sort
+        /// change is pushed in without re-sorting on every render.
+        last_sort: Option<Value>,
+        /// Last `filters` property applied, diffed the same way as `sort`.
+        last_filters: Option<Value>,
+        /// Filter-row slots (column width, input) when `filterable="true"`;
+        /// `None` for columns that opt out of filtering.
+        filter_inputs: Vec<(f32, Option<Entity<InputState>>)>,
     },
     Tree {
         state: Entity<TreeState>,
         last_items: Vec<Value>,
     },
+    /// Pane fractions of a `<split>`, shared with its drag handlers.
+    Split {
+        sizes: Arc<Mutex<Vec<f32>>>,
+        /// Last `sizes` property applied, so a bound change is pushed in
+        /// without resetting a drag on every render.
+        last_sizes: Option<Value>,
+    },
+    /// Parsed `<markdown>` content and a read-only editor per code block.
+    Markdown {
+        content: String,
+        blocks: Arc<Vec<Block>>,
+        code_states: Vec<Entity<InputState>>,
+    },
+    /// Zoom window and pointer state of an interactive chart.
+    Chart(Arc<Mutex<ChartView>>),
+    /// A `date_picker`/`datetime_picker`: its calendar, the time field of a
+    /// `datetime_picker`, the `min`/`max` bounds shared with the calendar's
+    /// matcher, and the `value` last synced with them, so only a script or
+    /// binding change is pushed back into the widgets.
+    DatePicker {
+        state: Entity<DatePickerState>,
+        time: Option<Entity<InputState>>,
+        bounds: Arc<Mutex<(Option<NaiveDate>, Option<NaiveDate>)>>,
+        last_value: Value,
+    },
+    /// A `color_picker` and the `value` last synced with it.
+    ColorPicker {
+        state: Entity<ColorPickerState>,
+        last_value: Value,
+    },
+    /// A `number_input`'s text field and the `value` last synced with it.
+    NumberInput {
+        state: Entity<InputState>,
+        last_value: Value,
+    },
+    /// Scroll state, measured heights and cached rows of a `<virtual-list>`.
+    VirtualList(Box<VirtualListState>),
     /// Shared open indices for accordion items.
     Accordion(Arc<Mutex<HashSet<usize>>>),
     /// Shared boolean state (collapsible, switch, toggle).
@@ -1,44 +1,298 @@
 use gpui::*;
-use gpui_component::table::{Column, TableDelegate, TableState};
+use gpui_component::input::{Input, InputState};
+use gpui_component::table::{Column, ColumnSort, TableDelegate, TableState};
+use gpui_component::tag::Tag as GpuiTag;
+use gpui_component::{h_flex, v_flex, ActiveTheme, Sizable};
 use nemo_config::Value;
 use nemo_layout::BuiltComponent;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::runtime::NemoRuntime;
+
+/// Default column width in pixels, used by the filter row to line up with
+/// columns that don't declare a `width`.
+pub const DEFAULT_COLUMN_WIDTH: f32 = 120.0;
+
+/// How a column renders its cell values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellFormat {
+    /// The value's display string.
+    Text,
+    /// A number with thousands separators and optional fixed decimals.
+    Number { decimals: Option<usize> },
+    /// A date/time (RFC 3339 string, `YYYY-MM-DD`, or epoch seconds) rendered
+    /// with a `strftime` pattern.
+    Date { pattern: String },
+    /// A colored tag; `variants` maps a cell value to a tag variant
+    /// (`success`, `warning`, `danger`, `info`, `primary`).
+    Badge { variants: HashMap<String, String> },
+}
+
+impl CellFormat {
+    fn from_column(obj: &indexmap::IndexMap<String, Value>) -> Self {
+        match obj.get("format").and_then(|v| v.as_str()) {
+            Some("number") => CellFormat::Number {
+                decimals: obj
+                    .get("decimals")
+                    .and_then(|v| v.as_i64())
+                    .map(|d| d.max(0) as usize),
+            },
+            Some("date") => CellFormat::Date {
+                pattern: obj
+                    .get("date_format")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("%Y-%m-%d")
+                    .to_string(),
+            },
+            Some("badge") => CellFormat::Badge {
+                variants: obj
+                    .get("variants")
+                    .and_then(|v| v.as_object())
+                    .map(|m| {
+                        m.iter()
+                            .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            _ => CellFormat::Text,
+        }
+    }
+
+    /// Formats `value` as display text.
+    pub fn format(&self, value: &Value) -> String {
+        match (self, value) {
+            (_, Value::Null) => String::new(),
+            (CellFormat::Number { decimals }, v) => match v.as_f64() {
+                Some(n) => format_number(n, *decimals),
+                None => v.to_string(),
+            },
+            (CellFormat::Date { pattern }, v) => format_date(v, pattern),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Formats a number with `,` thousands separators.
+pub(crate) fn format_number(n: f64, decimals: Option<usize>) -> String {
+    let raw = match decimals {
+        Some(d) => format!("{:.*}", d, n),
+        None if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", n as i64),
+        None => n.to_string(),
+    };
+    let (sign, digits) = match raw.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", raw.as_str()),
+    };
+    let (int_part, frac_part) = match digits.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (digits, None),
+    };
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, ch) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+
+    match frac_part {
+        Some(f) => format!("{}{}.{}", sign, grouped, f),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+/// Formats a date value; unparseable values fall back to their display string.
+fn format_date(value: &Value, pattern: &str) -> String {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    let parsed = match value {
+        Value::Integer(secs) => DateTime::from_timestamp(*secs, 0).map(|d| d.naive_utc()),
+        Value::Float(secs) => DateTime::from_timestamp(*secs as i64, 0).map(|d| d.naive_utc()),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|d| d.naive_local())
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+            }),
+        _ => None,
+    };
+
+    match parsed {
+        Some(dt) => dt.format(pattern).to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Orders two cell values: numbers numerically, strings case-insensitively,
+/// nulls last. Runs for every comparison of a sort, so strings are compared
+/// in place instead of being lowercased into copies.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        _ => match (a.as_f64(), b.as_f64()) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            _ => {
+                let (x, y) = (sort_text(a), sort_text(b));
+                x.chars()
+                    .flat_map(char::to_lowercase)
+                    .cmp(y.chars().flat_map(char::to_lowercase))
+            }
+        },
+    }
+}
+
+/// The text a value sorts by: a string as-is, anything else rendered.
+fn sort_text(value: &Value) -> Cow<'_, str> {
+    match value.as_str() {
+        Some(text) => Cow::Borrowed(text),
+        None => Cow::Owned(value.to_string()),
+    }
+}
+
+/// The active sort: a column key and direction.
+///
+/// Serialized in the `sort` property as `"key"`/`"key:asc"` or `"key:desc"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub key: String,
+    pub descending: bool,
+}
+
+impl SortSpec {
+    /// Parses a `sort` property value. Empty strings and non-strings clear the sort.
+    pub fn parse(value: &Value) -> Option<Self> {
+        let s = value.as_str()?.trim();
+        let (key, descending) = match s.rsplit_once(':') {
+            Some((key, "desc")) => (key, true),
+            Some((key, "asc")) => (key, false),
+            _ => (s, false),
+        };
+        (!key.is_empty()).then(|| Self {
+            key: key.to_string(),
+            descending,
+        })
+    }
+
+    /// Renders the spec back to its `sort` property form.
+    pub fn to_value(&self) -> Value {
+        let dir = if self.descending { "desc" } else { "asc" };
+        Value::String(format!("{}:{}", self.key, dir))
+    }
+}
+
+/// Row selection behavior (`selection` property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    None,
+    Single,
+    /// Each click toggles the row in or out of the selection.
+    Multi,
+}
+
+impl SelectionMode {
+    fn from_value(value: Option<&Value>) -> Self {
+        match value.and_then(|v| v.as_str()) {
+            Some("single") => SelectionMode::Single,
+            Some("multi") | Some("multiple") => SelectionMode::Multi,
+            _ => SelectionMode::None,
+        }
+    }
+}
+
+/// A parsed `columns` entry.
+#[derive(Debug, Clone)]
+pub struct ColumnSpec {
+    pub key: String,
+    pub label: String,
+    pub width: Option<f32>,
+    pub sortable: bool,
+    pub filterable: bool,
+    pub format: CellFormat,
+}
+
+impl ColumnSpec {
+    pub(crate) fn from_value(value: &Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        let key = obj
+            .get("key")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string();
+        Some(Self {
+            label: obj
+                .get("label")
+                .and_then(|v| v.as_str())
+                .unwrap_or(&key)
+                .to_string(),
+            width: obj.get("width").and_then(|v| v.as_f64()).map(|w| w as f32),
+            sortable: obj
+                .get("sortable")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            filterable: obj
+                .get("filterable")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            format: CellFormat::from_column(obj),
+            key,
+        })
+    }
+
+    fn auto(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            label: key.to_string(),
+            width: None,
+            sortable: true,
+            filterable: true,
+            format: CellFormat::Text,
+        }
+    }
+}
 
 /// Table delegate that renders rows from `Vec<Value>` objects.
+///
+/// Sorting and filtering never copy rows: `view` holds indices into `rows`
+/// in display order, so only the visible slice is formatted per frame (the
+/// `DataTable` renders rows through a virtualized list) and large datasets
+/// stay cheap to re-sort.
 pub struct NemoTableDelegate {
+    component_id: String,
+    specs: Vec<ColumnSpec>,
     columns: Vec<Column>,
-    column_keys: Vec<String>,
     rows: Vec<Value>,
+    /// Indices into `rows` after filtering and sorting.
+    view: Vec<usize>,
+    sort: Option<SortSpec>,
+    /// Lowercased filter text by column key.
+    filters: HashMap<String, String>,
+    selection_mode: SelectionMode,
+    /// Selected indices into `rows`.
+    selected: BTreeSet<usize>,
+    /// Field identifying a row across data updates (`row_key`); without it a
+    /// row is identified by its whole value.
+    row_key: Option<String>,
+    runtime: Option<Arc<NemoRuntime>>,
+    sort_handler: Option<String>,
 }
 
 impl NemoTableDelegate {
     /// Build a delegate from the component's properties.
-    pub fn from_properties(properties: &std::collections::HashMap<String, Value>) -> Self {
-        let mut columns = Vec::new();
-        let mut column_keys = Vec::new();
-
-        // Parse explicit columns if provided
-        if let Some(Value::Array(cols)) = properties.get("columns") {
-            for col_val in cols {
-                if let Some(obj) = col_val.as_object() {
-                    let key = obj
-                        .get("key")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("?")
-                        .to_string();
-                    let label = obj
-                        .get("label")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or(&key)
-                        .to_string();
-                    let mut col = Column::new(key.clone(), label);
-                    if let Some(w) = obj.get("width").and_then(|v| v.as_i64()) {
-                        col = col.width(px(w as f32));
-                    }
-                    columns.push(col);
-                    column_keys.push(key);
-                }
-            }
-        }
+    pub fn from_properties(properties: &HashMap<String, Value>) -> Self {
+        let mut specs: Vec<ColumnSpec> = match properties.get("columns") {
+            Some(Value::Array(cols)) => cols.iter().filter_map(ColumnSpec::from_value).collect(),
+            _ => Vec::new(),
+        };
 
         // Parse data rows
         let rows = match properties.get("data") {
@@ -47,28 +301,245 @@ impl NemoTableDelegate {
         };
 
         // Auto-detect columns from first row if none specified
-        if columns.is_empty() {
-            if let Some(first) = rows.first() {
-                if let Some(obj) = first.as_object() {
-                    for key in obj.keys() {
-                        columns.push(Column::new(key.clone(), key.clone()));
-                        column_keys.push(key.clone());
-                    }
-                }
+        if specs.is_empty() {
+            if let Some(obj) = rows.first().and_then(|r| r.as_object()) {
+                specs = obj.keys().map(|k| ColumnSpec::auto(k)).collect();
             }
         }
 
-        Self {
-            columns,
-            column_keys,
+        let mut delegate = Self {
+            component_id: String::new(),
+            specs,
+            columns: Vec::new(),
             rows,
-        }
+            view: Vec::new(),
+            sort: properties.get("sort").and_then(SortSpec::parse),
+            filters: parse_filters(properties.get("filters")),
+            selection_mode: SelectionMode::from_value(properties.get("selection")),
+            selected: BTreeSet::new(),
+            row_key: properties
+                .get("row_key")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            runtime: None,
+            sort_handler: None,
+        };
+        delegate.rebuild_columns();
+        delegate.recompute_view();
+        delegate
+    }
+
+    /// Wires the delegate to the runtime so header sorts write the `sort`
+    /// property back and fire `on-sort`.
+    pub fn with_runtime(
+        mut self,
+        component_id: &str,
+        runtime: Arc<NemoRuntime>,
+        sort_handler: Option<String>,
+    ) -> Self {
+        self.component_id = component_id.to_string();
+        self.runtime = Some(runtime);
+        self.sort_handler = sort_handler;
+        self
+    }
+
+    /// The parsed column definitions.
+    pub fn column_specs(&self) -> &[ColumnSpec] {
+        &self.specs
     }
 
-    /// Replace the row data.
-    pub fn set_rows(&mut self, rows: Vec<Value>) {
+    /// Replace the row data. Selected rows that are still present — by
+    /// `row_key`, or by value without one — stay selected. Returns the new
+    /// selection payload when it differs from the old one, so the caller
+    /// writes `selected` back only on a real change.
+    pub fn set_rows(&mut self, rows: Vec<Value>) -> Option<Value> {
+        let before = self.selection_value();
+        let kept: Vec<&Value> = self
+            .selected
+            .iter()
+            .filter_map(|ix| self.rows.get(*ix))
+            .map(|row| self.row_identity(row))
+            .collect();
+        let selected = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| kept.contains(&self.row_identity(row)))
+            .map(|(ix, _)| ix)
+            .collect();
         self.rows = rows;
+        self.selected = selected;
+        self.recompute_view();
+
+        let after = self.selection_value();
+        (self.selection_mode != SelectionMode::None && after != before).then_some(after)
+    }
+
+    /// What identifies `row` across data updates: its `row_key` field, or
+    /// the row itself.
+    fn row_identity<'a>(&self, row: &'a Value) -> &'a Value {
+        self.row_key
+            .as_deref()
+            .and_then(|key| row.get(key))
+            .unwrap_or(row)
     }
+
+    /// Applies a `sort` property value (e.g. from a two-way binding).
+    /// Returns `true` if the sort changed.
+    pub fn set_sort(&mut self, sort: Option<SortSpec>) -> bool {
+        if self.sort == sort {
+            return false;
+        }
+        self.sort = sort;
+        self.rebuild_columns();
+        self.recompute_view();
+        true
+    }
+
+    /// Sets the filter text for a column; empty text clears it.
+    pub fn set_filter(&mut self, key: &str, text: &str) {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            self.filters.remove(key);
+        } else {
+            self.filters.insert(key.to_string(), text);
+        }
+        self.recompute_view();
+    }
+
+    /// Applies a `filters` property value (e.g. from a two-way binding),
+    /// replacing every column's filter. Returns `true` if the filters changed.
+    pub fn set_filters(&mut self, filters: Option<&Value>) -> bool {
+        let filters = parse_filters(filters);
+        if self.filters == filters {
+            return false;
+        }
+        self.filters = filters;
+        self.recompute_view();
+        true
+    }
+
+    /// The filter text for column `key`, empty when it isn't filtered.
+    pub fn filter_text(&self, key: &str) -> &str {
+        self.filters
+            .get(key)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// The rows as currently displayed: filtered and in sort order.
+    pub(crate) fn view_rows(&self) -> Vec<Value> {
+        self.view.iter().map(|ix| self.rows[*ix].clone()).collect()
+    }
+
+    /// The current filters as a `filters` property value.
+    pub fn filters_value(&self) -> Value {
+        Value::Object(
+            self.filters
+                .iter()
+                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                .collect(),
+        )
+    }
+
+    /// Records a click on displayed row `row_ix` and returns the new
+    /// selection payload — the row object for `single`, an array of rows for
+    /// `multi` — or `None` when selection is disabled.
+    pub fn select_row(&mut self, row_ix: usize) -> Option<Value> {
+        let source_ix = *self.view.get(row_ix)?;
+        match self.selection_mode {
+            SelectionMode::None => return None,
+            SelectionMode::Single => {
+                self.selected.clear();
+                self.selected.insert(source_ix);
+            }
+            SelectionMode::Multi => {
+                if !self.selected.remove(&source_ix) {
+                    self.selected.insert(source_ix);
+                }
+            }
+        }
+        Some(self.selection_value())
+    }
+
+    fn selection_value(&self) -> Value {
+        let mut rows = self
+            .selected
+            .iter()
+            .filter_map(|ix| self.rows.get(*ix).cloned());
+        match self.selection_mode {
+            SelectionMode::Multi => Value::Array(rows.collect()),
+            _ => rows.next().unwrap_or(Value::Null),
+        }
+    }
+
+    fn rebuild_columns(&mut self) {
+        self.columns = self
+            .specs
+            .iter()
+            .map(|spec| {
+                let mut col = Column::new(spec.key.clone(), spec.label.clone());
+                if let Some(w) = spec.width {
+                    col = col.width(px(w));
+                }
+                if matches!(spec.format, CellFormat::Number { .. }) {
+                    col = col.text_right();
+                }
+                if spec.sortable {
+                    col = match &self.sort {
+                        Some(s) if s.key == spec.key && s.descending => col.descending(),
+                        Some(s) if s.key == spec.key => col.ascending(),
+                        _ => col.sortable(),
+                    };
+                }
+                col
+            })
+            .collect();
+    }
+
+    fn recompute_view(&mut self) {
+        let filters = &self.filters;
+        let mut view: Vec<usize> = (0..self.rows.len())
+            .filter(|ix| {
+                filters.iter().all(|(key, needle)| {
+                    self.rows[*ix]
+                        .get(key)
+                        .map(|v| v.to_string().to_lowercase().contains(needle.as_str()))
+                        .unwrap_or(false)
+                })
+            })
+            .collect();
+
+        if let Some(sort) = &self.sort {
+            let rows = &self.rows;
+            let null = Value::Null;
+            view.sort_by(|a, b| {
+                let va = rows[*a].get(&sort.key).unwrap_or(&null);
+                let vb = rows[*b].get(&sort.key).unwrap_or(&null);
+                let ord = compare_values(va, vb);
+                if sort.descending {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            });
+        }
+
+        self.view = view;
+    }
+}
+
+/// Reads a `filters` property: lowercased filter text by column key, without
+/// empty entries.
+fn parse_filters(value: Option<&Value>) -> HashMap<String, String> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|m| {
+            m.iter()
+                .map(|(k, v)| (k.clone(), v.to_string().trim().to_lowercase()))
+                .filter(|(_, v)| !v.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 impl TableDelegate for NemoTableDelegate {
@@ -77,28 +548,98 @@ impl TableDelegate for NemoTableDelegate {
     }
 
     fn rows_count(&self, _cx: &App) -> usize {
-        self.rows.len()
+        self.view.len()
     }
 
     fn column(&self, col_ix: usize, _cx: &App) -> Column {
         self.columns[col_ix].clone()
     }
 
+    fn perform_sort(
+        &mut self,
+        col_ix: usize,
+        sort: ColumnSort,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) {
+        let Some(key) = self.specs.get(col_ix).map(|s| s.key.clone()) else {
+            return;
+        };
+        let spec = match sort {
+            ColumnSort::Ascending => Some(SortSpec {
+                key,
+                descending: false,
+            }),
+            ColumnSort::Descending => Some(SortSpec {
+                key,
+                descending: true,
+            }),
+            ColumnSort::Default => None,
+        };
+        if !self.set_sort(spec) {
+            return;
+        }
+
+        if let Some(runtime) = &self.runtime {
+            let value = self
+                .sort
+                .as_ref()
+                .map(SortSpec::to_value)
+                .unwrap_or_else(|| Value::String(String::new()));
+            runtime.write_back_property(&self.component_id, "sort", value.clone());
+            if let Some(handler) = &self.sort_handler {
+                runtime.call_handler(handler, &self.component_id, &value.to_string());
+            }
+        }
+    }
+
     fn render_td(
         &mut self,
         row_ix: usize,
         col_ix: usize,
         _window: &mut Window,
-        _cx: &mut Context<TableState<Self>>,
+        cx: &mut Context<TableState<Self>>,
     ) -> impl IntoElement {
-        let text = self
-            .rows
-            .get(row_ix)
-            .and_then(|row| self.column_keys.get(col_ix).and_then(|key| row.get(key)))
-            .map(|v| v.to_string())
-            .unwrap_or_default();
+        let Some(&source_ix) = self.view.get(row_ix) else {
+            return div().into_any_element();
+        };
+        let (Some(row), Some(spec)) = (self.rows.get(source_ix), self.specs.get(col_ix)) else {
+            return div().into_any_element();
+        };
+        let value = row.get(&spec.key).unwrap_or(&Value::Null);
 
-        div().child(text)
+        let cell = div().size_full().flex().items_center();
+        // `DataTable` highlights only the last clicked row, so multi-selection
+        // marks its rows itself.
+        let cell =
+            if self.selection_mode == SelectionMode::Multi && self.selected.contains(&source_ix) {
+                cell.bg(cx.theme().table_active)
+            } else {
+                cell
+            };
+
+        match &spec.format {
+            CellFormat::Badge { variants } => {
+                let text = value.to_string();
+                let tag = match variants.get(&text).map(String::as_str) {
+                    Some("primary") => GpuiTag::primary(),
+                    Some("danger") => GpuiTag::danger(),
+                    Some("success") => GpuiTag::success(),
+                    Some("warning") => GpuiTag::warning(),
+                    Some("info") => GpuiTag::info(),
+                    _ => GpuiTag::secondary(),
+                };
+                cell.child(tag.child(text)).into_any_element()
+            }
+            format => {
+                let cell = if matches!(format, CellFormat::Number { .. }) {
+                    cell.justify_end()
+                } else {
+                    cell
+                };
+                cell.child(format.format(value)).into_any_element()
+            }
+        }
     }
 }
 
@@ -107,7 +648,10 @@ impl TableDelegate for NemoTableDelegate {
 /// # XML Configuration
 ///
 /// ```xml
-/// <table id="users" columns='[{"key":"name","label":"Name"},{"key":"age","label":"Age"}]'
+/// <table id="users" selection="single" filterable="true" sort="age:desc" on-select="pick"
+///   columns='[{"key":"name","label":"Name"},
+///             {"key":"age","label":"Age","format":"number"},
+///             {"key":"status","format":"badge","variants":{"active":"success"}}]'
 ///   stripe="true" bordered="true" height="400" />
 /// ```
 ///
@@ -115,7 +659,14 @@ impl TableDelegate for NemoTableDelegate {
 ///
 /// | Property | Type | Description |
 /// |----------|------|-------------|
-/// | `columns` | JSON array | Array of column definitions with `key` and `label` |
+/// | `columns` | JSON array | Column definitions: `key`, `label`, `width`, `sortable`, `filterable`, `format` (`number`/`date`/`badge`), `decimals`, `date_format`, `variants` |
+/// | `data` | array | Row objects keyed by column `key` |
+/// | `sort` | string | Active sort, `"key:asc"` or `"key:desc"`; written back on header clicks |
+/// | `filterable` | bool | Show a filter row of per-column text inputs |
+/// | `filters` | object | Filter text by column key |
+/// | `selection` | string | `none` (default), `single`, or `multi` |
+/// | `selected` | any | Selected row (single) or rows (multi); written back on select |
+/// | `row_key` | string | Row field that keeps a row selected across data updates |
 /// | `stripe` | bool | Alternate row background colors |
 /// | `bordered` | bool | Show cell borders |
 /// | `height` | int | Fixed table height in pixels |
@@ -123,6 +674,8 @@ impl TableDelegate for NemoTableDelegate {
 pub struct Table {
     source: BuiltComponent,
     table_state: Option<Entity<TableState<NemoTableDelegate>>>,
+    /// Filter-row slots with the width of the column each one filters.
+    filter_inputs: Vec<(f32, Option<Entity<InputState>>)>,
 }
 
 impl Table {
@@ -130,6 +683,7 @@ impl Table {
         Self {
             source,
             table_state: None,
+            filter_inputs: Vec::new(),
         }
     }
 
@@ -137,6 +691,11 @@ impl Table {
         self.table_state = Some(state);
         self
     }
+
+    pub fn filter_inputs(mut self, inputs: Vec<(f32, Option<Entity<InputState>>)>) -> Self {
+        self.filter_inputs = inputs;
+        self
+    }
 }
 
 impl RenderOnce for Table {
@@ -172,6 +731,193 @@ impl RenderOnce for Table {
             .and_then(|v| v.as_i64())
             .unwrap_or(300) as f32;
 
-        div().w_full().h(px(height)).child(table).into_any_element()
+        if self.filter_inputs.is_empty() {
+            return div().w_full().h(px(height)).child(table).into_any_element();
+        }
+
+        let filter_row = h_flex().children(self.filter_inputs.iter().map(|(width, input)| {
+            let slot = div().w(px(*width)).p_1();
+            match input {
+                Some(input) => slot.child(Input::new(input).small()),
+                None => slot,
+            }
+        }));
+
+        v_flex()
+            .w_full()
+            .child(filter_row)
+            .child(div().w_full().h(px(height)).child(table))
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(name: &str, age: i64) -> Value {
+        let mut obj = indexmap::IndexMap::new();
+        obj.insert("name".to_string(), Value::String(name.into()));
+        obj.insert("age".to_string(), Value::Integer(age));
+        Value::Object(obj)
+    }
+
+    fn delegate(props: &[(&str, Value)]) -> NemoTableDelegate {
+        let mut properties: HashMap<String, Value> = props
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        properties.insert(
+            "data".to_string(),
+            Value::Array(vec![row("carol", 41), row("alice", 30), row("Bob", 25)]),
+        );
+        NemoTableDelegate::from_properties(&properties)
+    }
+
+    fn names(d: &NemoTableDelegate) -> Vec<String> {
+        d.view
+            .iter()
+            .map(|ix| d.rows[*ix].get("name").unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_spec_roundtrip() {
+        let spec = SortSpec::parse(&Value::String("age:desc".into())).unwrap();
+        assert_eq!(spec.key, "age");
+        assert!(spec.descending);
+        assert_eq!(spec.to_value(), Value::String("age:desc".into()));
+        assert_eq!(
+            SortSpec::parse(&Value::String("name".into())).map(|s| s.descending),
+            Some(false)
+        );
+        assert_eq!(SortSpec::parse(&Value::String(String::new())), None);
+    }
+
+    #[test]
+    fn test_sort_and_filter_reorder_view_only() {
+        let mut d = delegate(&[("sort", Value::String("name".into()))]);
+        assert_eq!(names(&d), vec!["alice", "Bob", "carol"]);
+
+        d.set_sort(Some(SortSpec {
+            key: "age".into(),
+            descending: true,
+        }));
+        assert_eq!(names(&d), vec!["carol", "alice", "Bob"]);
+
+        d.set_filter("name", "O");
+        assert_eq!(names(&d), vec!["carol", "Bob"]);
+        assert_eq!(d.view_rows(), vec![row("carol", 41), row("Bob", 25)]);
+        // Rows themselves are untouched.
+        assert_eq!(d.rows[0].get("name"), Some(&Value::String("carol".into())));
+    }
+
+    #[test]
+    fn test_bound_filters_replace_the_filter_row() {
+        let mut d = delegate(&[]);
+        d.set_filter("name", "a");
+        assert_eq!(names(&d), vec!["carol", "alice"]);
+
+        let mut filters = indexmap::IndexMap::new();
+        filters.insert("name".to_string(), Value::String("BO".into()));
+        let filters = Value::Object(filters);
+        assert!(d.set_filters(Some(&filters)));
+        assert_eq!(names(&d), vec!["Bob"]);
+        assert_eq!(d.filter_text("name"), "bo");
+        assert!(!d.set_filters(Some(&filters)), "unchanged");
+
+        assert!(d.set_filters(None));
+        assert_eq!(names(&d), vec!["carol", "alice", "Bob"]);
+    }
+
+    #[test]
+    fn test_compare_values() {
+        let s = |text: &str| Value::String(text.into());
+        assert_eq!(compare_values(&s("apple"), &s("Banana")), Ordering::Less);
+        assert_eq!(compare_values(&s("ÉCLAIR"), &s("éclair")), Ordering::Equal);
+        assert_eq!(
+            compare_values(&Value::Integer(10), &Value::Float(9.5)),
+            Ordering::Greater
+        );
+        assert_eq!(compare_values(&Value::Null, &s("a")), Ordering::Greater);
+        assert_eq!(
+            compare_values(&Value::Bool(false), &Value::Bool(true)),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_multi_selection_toggles_rows() {
+        let mut d = delegate(&[
+            ("selection", Value::String("multi".into())),
+            ("sort", Value::String("age".into())),
+        ]);
+        // Displayed order is Bob, alice, carol.
+        d.select_row(0);
+        let selected = d.select_row(2).unwrap();
+        assert_eq!(
+            selected,
+            Value::Array(vec![row("carol", 41), row("Bob", 25)])
+        );
+
+        let selected = d.select_row(0).unwrap();
+        assert_eq!(selected, Value::Array(vec![row("carol", 41)]));
+
+        let mut none = delegate(&[]);
+        assert_eq!(none.select_row(0), None);
+    }
+
+    #[test]
+    fn test_selection_survives_data_updates() {
+        let mut d = delegate(&[
+            ("selection", Value::String("multi".into())),
+            ("row_key", Value::String("name".into())),
+        ]);
+        d.select_row(0);
+        d.select_row(2);
+
+        // Rows move and one changes: the selection follows the keys.
+        let rows = vec![row("Bob", 26), row("dave", 50), row("carol", 41)];
+        assert_eq!(
+            d.set_rows(rows.clone()),
+            Some(Value::Array(vec![row("Bob", 26), row("carol", 41)]))
+        );
+        // The same data again changes nothing to write back.
+        assert_eq!(d.set_rows(rows), None);
+        // A selected row that's gone drops out.
+        assert_eq!(
+            d.set_rows(vec![row("carol", 41)]),
+            Some(Value::Array(vec![row("carol", 41)]))
+        );
+
+        // Without `row_key`, a row is matched by value.
+        let mut d = delegate(&[("selection", Value::String("single".into()))]);
+        d.select_row(1);
+        let rows = vec![row("alice", 30), row("zed", 1)];
+        assert_eq!(d.set_rows(rows), None);
+        assert_eq!(d.selection_value(), row("alice", 30));
+    }
+
+    #[test]
+    fn test_cell_formats() {
+        let number = CellFormat::Number { decimals: Some(2) };
+        assert_eq!(number.format(&Value::Float(-1234567.891)), "-1,234,567.89");
+        assert_eq!(
+            CellFormat::Number { decimals: None }.format(&Value::Integer(100000)),
+            "100,000"
+        );
+
+        let date = CellFormat::Date {
+            pattern: "%d/%m/%Y".into(),
+        };
+        assert_eq!(
+            date.format(&Value::String("2026-03-01".into())),
+            "01/03/2026"
+        );
+        assert_eq!(
+            date.format(&Value::String("2026-03-01T10:00:00Z".into())),
+            "01/03/2026"
+        );
+        assert_eq!(date.format(&Value::String("soon".into())), "soon");
     }
 }
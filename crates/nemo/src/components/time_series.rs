@@ -0,0 +1,1127 @@
+//! Time-series plotting shared by `realtime_chart`, `line_chart` and
+//! `area_chart`.
+//!
+//! The plot draws any number of line series on a common x axis, which is
+//! either the item index (labelled with the `x-field` strings, the default)
+//! or a true time scale (`x-scale="time"`) that parses `x-field` as epoch
+//! seconds/milliseconds or date-time strings. On top of that:
+//!
+//! * `window="5m"` keeps only the last five minutes of samples, measured from
+//!   the newest one, so a growing stream scrolls;
+//! * `downsample="lttb"` or `"minmax"` thins every series to the plot's pixel
+//!   width before drawing, keeping 100k point streams cheap to paint (the
+//!   default, `"none"`, draws every sample);
+//! * series listed in `y2-fields` are scaled against a secondary y-axis on the
+//!   right, for traces in different units.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use gpui::*;
+use gpui_component::plot::{
+    scale::{Scale, ScaleLinear},
+    shape::Line,
+    AxisText, Grid, IntoPlot, Plot, PlotAxis, StrokeStyle, AXIS_GAP,
+};
+use gpui_component::ActiveTheme;
+use nemo_config::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use super::chart_annotations::{number, resolve_x, Annotation};
+use super::chart_utils::{get_string_field, ChartView};
+use super::resolve_color;
+
+/// Gap in pixels between the top of the plot and the largest y value.
+const TOP_MARGIN: f32 = 10.0;
+
+/// Horizontal pixels per time-axis label.
+const TICK_SPACING: f32 = 100.0;
+
+/// Number of y-axis divisions (grid lines and labels).
+const Y_TICKS: usize = 4;
+
+/// Candidate time-axis steps in milliseconds, smallest first.
+const TIME_STEPS: &[f64] = &[
+    1.0,
+    2.0,
+    5.0,
+    10.0,
+    20.0,
+    50.0,
+    100.0,
+    200.0,
+    500.0,
+    SECOND,
+    2.0 * SECOND,
+    5.0 * SECOND,
+    10.0 * SECOND,
+    15.0 * SECOND,
+    30.0 * SECOND,
+    MINUTE,
+    2.0 * MINUTE,
+    5.0 * MINUTE,
+    10.0 * MINUTE,
+    15.0 * MINUTE,
+    30.0 * MINUTE,
+    HOUR,
+    2.0 * HOUR,
+    3.0 * HOUR,
+    6.0 * HOUR,
+    12.0 * HOUR,
+    DAY,
+    2.0 * DAY,
+    7.0 * DAY,
+    30.0 * DAY,
+    90.0 * DAY,
+    365.0 * DAY,
+];
+
+const SECOND: f64 = 1000.0;
+const MINUTE: f64 = 60.0 * SECOND;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+
+/// Numbers below this are epoch seconds, above it epoch milliseconds
+/// (1e11 seconds is the year 5138; 1e11 milliseconds is March 1973).
+const EPOCH_MS_THRESHOLD: f64 = 1e11;
+
+/// Whether a chart plots `x-field` on a time scale: `x-scale="time"`, or a
+/// sliding `window`, which only makes sense for times.
+pub(crate) fn is_time_axis(props: &HashMap<String, Value>) -> bool {
+    props.get("x_scale").and_then(|v| v.as_str()) == Some("time") || window_ms(props).is_some()
+}
+
+/// Whether a `line_chart` needs this plot rather than gpui-component's
+/// single-series line chart.
+pub(crate) fn uses_series_plot(props: &HashMap<String, Value>) -> bool {
+    is_time_axis(props)
+        || ["y2_field", "downsample", "legend"]
+            .iter()
+            .any(|key| props.contains_key(*key))
+}
+
+/// The `window` property in milliseconds.
+pub(crate) fn window_ms(props: &HashMap<String, Value>) -> Option<f64> {
+    match props.get("window")? {
+        Value::String(s) => parse_duration(s),
+        value => value.as_f64().map(|secs| secs * SECOND),
+    }
+    .filter(|ms| *ms > 0.0)
+}
+
+/// Parses a timestamp to epoch milliseconds. Numbers are epoch seconds, or
+/// milliseconds when large enough to be; strings may be RFC 3339
+/// (`2026-10-18T12:00:00Z`), a naive date-time (`2026-10-18 12:00:00`,
+/// taken as local time) or a date.
+pub(crate) fn parse_time(value: &Value) -> Option<f64> {
+    if let Some(n) = value.as_f64() {
+        return Some(if n.abs() < EPOCH_MS_THRESHOLD {
+            n * SECOND
+        } else {
+            n
+        });
+    }
+    let s = value.as_str()?.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis() as f64);
+    }
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp_millis() as f64)
+}
+
+/// Parses a duration such as `500ms`, `30s`, `5m`, `1.5h`, `1d` or `2w` to
+/// milliseconds. A bare number is seconds.
+pub(crate) fn parse_duration(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let unit_ms = match unit.trim() {
+        "ms" => 1.0,
+        "" | "s" | "sec" => SECOND,
+        "m" | "min" => MINUTE,
+        "h" => HOUR,
+        "d" => DAY,
+        "w" => 7.0 * DAY,
+        _ => return None,
+    };
+    Some(number * unit_ms)
+}
+
+/// Round tick positions covering `lo..=hi` with at most `max_ticks` ticks,
+/// and the step between them.
+pub(crate) fn time_ticks(lo: f64, hi: f64, max_ticks: usize) -> (f64, Vec<f64>) {
+    let span = (hi - lo).max(0.0);
+    let max_ticks = max_ticks.max(1) as f64;
+    let step = TIME_STEPS
+        .iter()
+        .copied()
+        .find(|step| span / step <= max_ticks)
+        .unwrap_or_else(|| (span / max_ticks / (365.0 * DAY)).ceil() * 365.0 * DAY);
+    let mut ticks = Vec::new();
+    let mut t = (lo / step).ceil() * step;
+    while t <= hi {
+        ticks.push(t);
+        t += step;
+    }
+    (step, ticks)
+}
+
+/// Formats a tick in `tz` with as much precision as the tick step needs.
+pub(crate) fn format_time_tick<Tz: TimeZone>(ms: f64, step: f64, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let Some(dt) = tz.timestamp_millis_opt(ms as i64).single() else {
+        return String::new();
+    };
+    let format = if step < SECOND {
+        "%H:%M:%S%.3f"
+    } else if step < MINUTE {
+        "%H:%M:%S"
+    } else if step < DAY {
+        "%H:%M"
+    } else {
+        "%b %d"
+    };
+    dt.format(format).to_string()
+}
+
+/// Formats a timestamp in full, for tooltips.
+pub(crate) fn format_timestamp(ms: f64) -> String {
+    Local
+        .timestamp_millis_opt(ms as i64)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Widens `lo..hi` to round numbers divisible into `ticks` equal steps
+/// (1, 2, 2.5 or 5 times a power of ten).
+pub(crate) fn nice_domain(lo: f64, hi: f64, ticks: usize) -> (f64, f64) {
+    let (lo, hi) = if hi > lo { (lo, hi) } else { (lo, lo + 1.0) };
+    let raw = (hi - lo) / ticks.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    for factor in [1.0, 2.0, 2.5, 5.0, 10.0] {
+        let step = factor * magnitude;
+        let nice_lo = (lo / step).floor() * step;
+        if nice_lo + step * ticks as f64 >= hi {
+            return (nice_lo, nice_lo + step * ticks as f64);
+        }
+    }
+    (lo, hi)
+}
+
+/// Compact y-axis label: `1.5k`, `2M`, `0.25`.
+pub(crate) fn format_value(v: f64) -> String {
+    let trim = |s: String| {
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            s
+        }
+    };
+    let abs = v.abs();
+    if abs >= 1e9 {
+        format!("{}G", trim(format!("{:.1}", v / 1e9)))
+    } else if abs >= 1e6 {
+        format!("{}M", trim(format!("{:.1}", v / 1e6)))
+    } else if abs >= 1e4 {
+        format!("{}k", trim(format!("{:.1}", v / 1e3)))
+    } else {
+        trim(format!("{:.2}", v))
+    }
+}
+
+/// How a series is thinned to the plot width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Downsample {
+    /// Largest-Triangle-Three-Buckets: keeps the visual shape.
+    Lttb,
+    /// Each bucket's smallest and largest sample: keeps every spike.
+    MinMax,
+    /// Draw every sample.
+    None,
+}
+
+impl Downsample {
+    pub(crate) fn from_props(props: &HashMap<String, Value>) -> Self {
+        match props.get("downsample").and_then(|v| v.as_str()) {
+            Some("lttb") => Downsample::Lttb,
+            Some("minmax") => Downsample::MinMax,
+            _ => Downsample::None,
+        }
+    }
+
+    /// Thins x-sorted `points` to about `target` points.
+    pub(crate) fn apply(self, points: &[(f64, f64)], target: usize) -> Vec<(f64, f64)> {
+        match self {
+            Downsample::Lttb => lttb(points, target),
+            Downsample::MinMax => minmax(points, target / 2),
+            Downsample::None => points.to_vec(),
+        }
+    }
+}
+
+/// Largest-Triangle-Three-Buckets downsampling of x-sorted points to
+/// `threshold` points, always keeping the first and last.
+pub(crate) fn lttb(points: &[(f64, f64)], threshold: usize) -> Vec<(f64, f64)> {
+    let n = points.len();
+    if threshold >= n || threshold < 3 {
+        return points.to_vec();
+    }
+    let every = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut sampled = Vec::with_capacity(threshold);
+    let mut a = 0;
+    sampled.push(points[0]);
+    for i in 0..threshold - 2 {
+        // Average of the next bucket, the third triangle corner.
+        let next_start = ((i + 1) as f64 * every) as usize + 1;
+        let next_end = (((i + 2) as f64 * every) as usize + 1).min(n);
+        let next = &points[next_start..next_end.max(next_start + 1).min(n)];
+        let (avg_x, avg_y) = next.iter().fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+        let (avg_x, avg_y) = (avg_x / next.len() as f64, avg_y / next.len() as f64);
+
+        let start = (i as f64 * every) as usize + 1;
+        let end = ((i + 1) as f64 * every) as usize + 1;
+        let (ax, ay) = points[a];
+        let chosen = (start..end.min(n - 1))
+            .max_by(|&p, &q| {
+                let area = |k: usize| {
+                    let (bx, by) = points[k];
+                    ((ax - avg_x) * (by - ay) - (ax - bx) * (avg_y - ay)).abs()
+                };
+                area(p).total_cmp(&area(q))
+            })
+            .unwrap_or(start.min(n - 2));
+        sampled.push(points[chosen]);
+        a = chosen;
+    }
+    sampled.push(points[n - 1]);
+    sampled
+}
+
+/// Min/max downsampling of x-sorted points into `buckets` equal-count
+/// buckets, keeping each bucket's extremes in x order.
+pub(crate) fn minmax(points: &[(f64, f64)], buckets: usize) -> Vec<(f64, f64)> {
+    let n = points.len();
+    if buckets == 0 || n <= buckets * 2 {
+        return points.to_vec();
+    }
+    let size = n.div_ceil(buckets);
+    let mut sampled = Vec::with_capacity(buckets * 2);
+    for chunk in points.chunks(size) {
+        let lo = (0..chunk.len())
+            .min_by(|&a, &b| chunk[a].1.total_cmp(&chunk[b].1))
+            .unwrap_or(0);
+        let hi = (0..chunk.len())
+            .max_by(|&a, &b| chunk[a].1.total_cmp(&chunk[b].1))
+            .unwrap_or(0);
+        sampled.push(chunk[lo.min(hi)]);
+        if lo != hi {
+            sampled.push(chunk[lo.max(hi)]);
+        }
+    }
+    sampled
+}
+
+/// One line of the plot.
+#[derive(Debug, Clone)]
+pub(crate) struct SeriesSpec {
+    /// Data field holding the y values.
+    pub field: String,
+    /// Scaled against the right-hand axis.
+    pub secondary: bool,
+    pub color: Hsla,
+}
+
+/// Data prepared for painting: x-sorted points per series and the domains
+/// they share.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Prepared {
+    /// Points per series, in series order.
+    pub series: Vec<Vec<(f64, f64)>>,
+    /// x extent of every plotted item.
+    pub x_domain: (f64, f64),
+    /// Primary and secondary y domains, widened to round numbers.
+    pub y_domain: (f64, f64),
+    pub y2_domain: (f64, f64),
+    /// Point-axis labels by item index (empty on a time axis).
+    pub labels: Vec<String>,
+}
+
+impl Prepared {
+    /// The x range the plot spans; a single item (or a single time) sits in
+    /// the middle.
+    pub fn x_range(&self) -> (f64, f64) {
+        let (lo, hi) = self.x_domain;
+        if hi > lo {
+            (lo, hi)
+        } else {
+            (lo - 1.0, lo + 1.0)
+        }
+    }
+
+    /// Horizontal position of `x` as a fraction of the plot width.
+    pub fn x_fraction(&self, x: f64) -> f32 {
+        let (lo, hi) = self.x_range();
+        ((x - lo) / (hi - lo)) as f32
+    }
+
+    /// Vertical position of `y` as a fraction of the plot's y range, from
+    /// the top.
+    pub fn y_fraction(&self, y: f64, secondary: bool) -> f32 {
+        let (lo, hi) = if secondary {
+            self.y2_domain
+        } else {
+            self.y_domain
+        };
+        ((hi - y) / (hi - lo)) as f32
+    }
+}
+
+/// Turns the data into per-series points. On a time axis, items whose
+/// `x_field` isn't a time are dropped, the rest sorted by time and cut to
+/// the `window` before the newest; otherwise x is the item index. Items
+/// without a numeric value for a series leave a gap in that series only.
+/// `extra` y values (annotations) are kept inside the y domains.
+pub(crate) fn prepare(
+    data: &[Value],
+    x_field: &str,
+    fields: &[(String, bool)],
+    time: bool,
+    window: Option<f64>,
+    extra: &[(f64, bool)],
+) -> Prepared {
+    let mut items: Vec<(f64, &Value)> = if time {
+        data.iter()
+            .filter_map(|item| Some((parse_time(item.get(x_field)?)?, item)))
+            .collect()
+    } else {
+        data.iter()
+            .enumerate()
+            .map(|(i, v)| (i as f64, v))
+            .collect()
+    };
+    if time {
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let (Some(window), Some(&(newest, _))) = (window, items.last()) {
+            items.retain(|(x, _)| *x >= newest - window);
+        }
+    }
+
+    let series: Vec<Vec<(f64, f64)>> = fields
+        .iter()
+        .map(|(field, _)| {
+            items
+                .iter()
+                .filter_map(|(x, item)| Some((*x, item.get(field)?.as_f64()?)))
+                .collect()
+        })
+        .collect();
+
+    let y_domain = |secondary: bool| {
+        let (lo, hi) = series
+            .iter()
+            .zip(fields)
+            .filter(|(_, (_, s))| *s == secondary)
+            .flat_map(|(points, _)| points.iter().map(|p| p.1))
+            .chain(
+                extra
+                    .iter()
+                    .filter(|(_, s)| *s == secondary)
+                    .map(|(y, _)| *y),
+            )
+            .fold((0.0f64, 0.0f64), |(lo, hi), y| (lo.min(y), hi.max(y)));
+        nice_domain(lo, hi, Y_TICKS)
+    };
+
+    Prepared {
+        x_domain: match (items.first(), items.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => (0.0, 0.0),
+        },
+        y_domain: y_domain(false),
+        y2_domain: y_domain(true),
+        labels: if time {
+            Vec::new()
+        } else {
+            data.iter().map(|v| get_string_field(v, x_field)).collect()
+        },
+        series,
+    }
+}
+
+/// A multi-series line plot on an index or time axis with optional
+/// secondary y-axis, annotations and a toggleable legend.
+///
+/// Everything but the lines and axes is laid out as overlays inside the
+/// plot's y range (`TOP_MARGIN` to the x axis), where a fraction of the
+/// height maps straight onto the y scale: bands behind the plot, then
+/// thresholds, markers, the y-axis labels (when a secondary axis is in use,
+/// so the two scales can be read apart) and the legend on top.
+#[derive(IntoElement)]
+pub(crate) struct SeriesChart {
+    data: Vec<Value>,
+    x_field: String,
+    specs: Vec<SeriesSpec>,
+    time: bool,
+    window: Option<f64>,
+    downsample: Downsample,
+    stroke_style: StrokeStyle,
+    tick_margin: usize,
+    dot: bool,
+    fill: Option<f32>,
+    legend: bool,
+    annotations: Vec<Annotation>,
+    view: Option<(Arc<Mutex<ChartView>>, EntityId)>,
+}
+
+impl SeriesChart {
+    /// Builds the plot from `props` (`x_field`, `x_scale`, `window`,
+    /// `downsample`, `legend`) and the series to draw.
+    pub(crate) fn new(
+        data: Vec<Value>,
+        props: &HashMap<String, Value>,
+        specs: Vec<SeriesSpec>,
+    ) -> Self {
+        Self {
+            data,
+            x_field: props
+                .get("x_field")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            specs,
+            time: is_time_axis(props),
+            window: window_ms(props),
+            downsample: Downsample::from_props(props),
+            stroke_style: StrokeStyle::Natural,
+            tick_margin: 1,
+            dot: false,
+            fill: None,
+            legend: props.get("legend").and_then(|v| v.as_bool()) == Some(true),
+            annotations: Vec::new(),
+            view: None,
+        }
+    }
+
+    /// Overrides the `x_field` property.
+    pub(crate) fn x_field(mut self, field: impl Into<String>) -> Self {
+        self.x_field = field.into();
+        self
+    }
+
+    pub(crate) fn stroke_style(mut self, style: StrokeStyle) -> Self {
+        self.stroke_style = style;
+        self
+    }
+
+    pub(crate) fn tick_margin(mut self, margin: usize) -> Self {
+        self.tick_margin = margin.max(1);
+        self
+    }
+
+    pub(crate) fn dot(mut self, dot: bool) -> Self {
+        self.dot = dot;
+        self
+    }
+
+    /// Fills under each line at this opacity, as `area_chart` does.
+    pub(crate) fn fill(mut self, opacity: f32) -> Self {
+        self.fill = Some(opacity);
+        self
+    }
+
+    pub(crate) fn annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// The chart's view state, which holds the series hidden from the
+    /// legend; without it the legend is not interactive.
+    pub(crate) fn view(
+        mut self,
+        view: Option<Arc<Mutex<ChartView>>>,
+        entity_id: Option<EntityId>,
+    ) -> Self {
+        self.view = view.zip(entity_id);
+        self
+    }
+}
+
+impl RenderOnce for SeriesChart {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let hidden = self
+            .view
+            .as_ref()
+            .map(|(view, _)| view.lock().unwrap().hidden.clone())
+            .unwrap_or_default();
+        let shown: Vec<SeriesSpec> = self
+            .specs
+            .iter()
+            .filter(|s| !hidden.contains(&s.field))
+            .cloned()
+            .collect();
+        let fields: Vec<(String, bool)> = shown
+            .iter()
+            .map(|s| (s.field.clone(), s.secondary))
+            .collect();
+        let extra: Vec<(f64, bool)> = self
+            .annotations
+            .iter()
+            .flat_map(Annotation::y_values)
+            .collect();
+        let prepared = prepare(
+            &self.data,
+            &self.x_field,
+            &fields,
+            self.time,
+            self.window,
+            &extra,
+        );
+
+        let has_secondary = self.specs.iter().any(|s| s.secondary);
+        let theme = &cx.theme().colors;
+        let mut back = overlay();
+        let mut front = overlay();
+        for annotation in &self.annotations {
+            let style = annotation.style();
+            let color = style.color.as_deref().and_then(|c| resolve_color(c, cx));
+            let label = style.label.clone().map(|text| {
+                div()
+                    .absolute()
+                    .text_xs()
+                    .whitespace_nowrap()
+                    .text_color(color.unwrap_or(theme.muted_foreground))
+                    .child(text)
+            });
+            match annotation {
+                Annotation::Threshold { value, style } => {
+                    let f = prepared.y_fraction(*value, style.secondary);
+                    let color = color.unwrap_or(theme.danger);
+                    front = front.child(
+                        div()
+                            .absolute()
+                            .top(relative(f))
+                            .left_0()
+                            .right_0()
+                            .h(px(1.))
+                            .bg(color),
+                    );
+                    if let Some(label) = label {
+                        front = front.child(label.bottom(relative(1.0 - f)).right(px(4.)));
+                    }
+                }
+                Annotation::Band {
+                    from,
+                    to,
+                    x_axis,
+                    style,
+                } => {
+                    let color = color.unwrap_or(theme.accent);
+                    let band = div().absolute().bg(color.opacity(0.15));
+                    let (band, label) = if *x_axis {
+                        let x = |v: &Value| {
+                            resolve_x(v, self.time, &prepared.labels)
+                                .map(|x| prepared.x_fraction(x))
+                        };
+                        let (Some(a), Some(b)) = (x(from), x(to)) else {
+                            continue;
+                        };
+                        let (a, b) = (a.min(b).max(0.0), a.max(b).min(1.0));
+                        if a >= b {
+                            continue;
+                        }
+                        (
+                            band.top_0().bottom_0().left(relative(a)).w(relative(b - a)),
+                            label.map(|l| l.top(px(2.)).left(relative(a)).ml(px(4.))),
+                        )
+                    } else {
+                        let y =
+                            |v: &Value| number(v).map(|v| prepared.y_fraction(v, style.secondary));
+                        let (Some(a), Some(b)) = (y(from), y(to)) else {
+                            continue;
+                        };
+                        let (a, b) = (a.min(b), a.max(b));
+                        (
+                            band.left_0().right_0().top(relative(a)).h(relative(b - a)),
+                            label.map(|l| l.top(relative(a)).mt(px(2.)).left(px(4.))),
+                        )
+                    };
+                    back = back.child(band);
+                    if let Some(label) = label {
+                        front = front.child(label);
+                    }
+                }
+                Annotation::Marker { at, .. } => {
+                    let Some(f) = resolve_x(at, self.time, &prepared.labels)
+                        .map(|x| prepared.x_fraction(x))
+                        .filter(|f| (0.0..=1.0).contains(f))
+                    else {
+                        continue;
+                    };
+                    let color = color.unwrap_or(theme.muted_foreground);
+                    front = front.child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .bottom_0()
+                            .left(relative(f))
+                            .w(px(1.))
+                            .bg(color),
+                    );
+                    if let Some(label) = label {
+                        front =
+                            front.child(label.top(px(-TOP_MARGIN)).left(relative(f)).ml(px(4.)));
+                    }
+                }
+            }
+        }
+
+        if has_secondary {
+            // Labels sit just below their grid line, primary on the left and
+            // secondary on the right.
+            let (y_domain, y2_domain) = (prepared.y_domain, prepared.y2_domain);
+            for i in 0..Y_TICKS {
+                let f = i as f32 / Y_TICKS as f32;
+                let at = |(lo, hi): (f64, f64)| format_value(hi - (hi - lo) * f as f64);
+                let label = || {
+                    div()
+                        .absolute()
+                        .top(relative(f))
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                };
+                front = front
+                    .child(label().left(px(2.)).child(at(y_domain)))
+                    .child(label().right(px(2.)).child(at(y2_domain)));
+            }
+        }
+
+        let legend = self
+            .legend
+            .then(|| legend(&self.specs, &hidden, self.view.clone(), cx));
+        let plot = SeriesPlot {
+            prepared,
+            specs: shown,
+            time: self.time,
+            downsample: self.downsample,
+            stroke_style: self.stroke_style,
+            tick_margin: self.tick_margin,
+            dot: self.dot,
+            fill: self.fill,
+        };
+        div()
+            .relative()
+            .size_full()
+            .child(back)
+            .child(plot)
+            .child(front)
+            .children(legend)
+    }
+}
+
+/// An absolutely positioned layer covering the plot's y range.
+fn overlay() -> Div {
+    div()
+        .absolute()
+        .top(px(TOP_MARGIN))
+        .bottom(px(AXIS_GAP))
+        .left_0()
+        .right_0()
+}
+
+/// One swatch and name per series; clicking one hides or shows it.
+fn legend(
+    specs: &[SeriesSpec],
+    hidden: &HashSet<String>,
+    view: Option<(Arc<Mutex<ChartView>>, EntityId)>,
+    cx: &App,
+) -> Div {
+    let theme = &cx.theme().colors;
+    let mut row = div()
+        .absolute()
+        .top(px(2.))
+        .left(px(4.))
+        .flex()
+        .flex_wrap()
+        .gap_3()
+        .px_1()
+        .rounded_sm()
+        .bg(theme.background.opacity(0.8))
+        .text_xs()
+        // Keep clicks from starting a drag on the chart underneath.
+        .on_mouse_down(MouseButton::Left, |_, _window, cx| cx.stop_propagation());
+    for (i, spec) in specs.iter().enumerate() {
+        let off = hidden.contains(&spec.field);
+        let mut item = div()
+            .id(("legend", i))
+            .flex()
+            .items_center()
+            .gap_1()
+            .cursor_pointer()
+            .text_color(if off {
+                theme.muted_foreground
+            } else {
+                theme.foreground
+            })
+            .child(
+                div()
+                    .size(px(8.))
+                    .rounded_full()
+                    .bg(if off { theme.muted } else { spec.color }),
+            )
+            .child(spec.field.clone());
+        if let Some((view, entity_id)) = view.clone() {
+            let field = spec.field.clone();
+            item = item.on_click(move |_, _window, cx| {
+                let mut view = view.lock().unwrap();
+                if !view.hidden.remove(&field) {
+                    view.hidden.insert(field.clone());
+                }
+                cx.notify(entity_id);
+            });
+        }
+        row = row.child(item);
+    }
+    row
+}
+
+#[derive(IntoPlot)]
+struct SeriesPlot {
+    prepared: Prepared,
+    specs: Vec<SeriesSpec>,
+    time: bool,
+    downsample: Downsample,
+    stroke_style: StrokeStyle,
+    tick_margin: usize,
+    dot: bool,
+    fill: Option<f32>,
+}
+
+impl Plot for SeriesPlot {
+    fn paint(&mut self, bounds: Bounds<Pixels>, window: &mut Window, cx: &mut App) {
+        if self.specs.is_empty() {
+            return;
+        }
+
+        let width = bounds.size.width.as_f32();
+        let height = bounds.size.height.as_f32() - AXIS_GAP;
+
+        let (lo, hi) = self.prepared.x_domain;
+        let x_range = self.prepared.x_range();
+        let x = ScaleLinear::new(vec![x_range.0, x_range.1], vec![0., width]);
+        let y_scale =
+            |(lo, hi): (f64, f64)| ScaleLinear::new(vec![lo, hi], vec![height, TOP_MARGIN]);
+        let y = y_scale(self.prepared.y_domain);
+        let y2 = y_scale(self.prepared.y2_domain);
+
+        // X axis labels
+        let muted = cx.theme().muted_foreground;
+        let align = |tick: f32| {
+            if tick < TICK_SPACING / 3.0 {
+                TextAlign::Left
+            } else if tick > width - TICK_SPACING / 3.0 {
+                TextAlign::Right
+            } else {
+                TextAlign::Center
+            }
+        };
+        let x_label: Vec<AxisText> = if self.time {
+            let max_ticks = (width / TICK_SPACING).floor().max(2.0) as usize;
+            let (step, ticks) = time_ticks(lo, hi, max_ticks);
+            ticks
+                .into_iter()
+                .filter_map(|t| {
+                    let tick = x.tick(&t)?;
+                    let label = format_time_tick(t, step, &Local);
+                    Some(AxisText::new(SharedString::from(label), tick, muted).align(align(tick)))
+                })
+                .collect()
+        } else {
+            let labels = &self.prepared.labels;
+            labels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| (i + 1) % self.tick_margin == 0)
+                .filter_map(|(i, label)| {
+                    let tick = x.tick(&(i as f64))?;
+                    let align = if labels.len() == 1 {
+                        TextAlign::Center
+                    } else {
+                        align(tick)
+                    };
+                    Some(AxisText::new(SharedString::from(label.clone()), tick, muted).align(align))
+                })
+                .collect()
+        };
+
+        PlotAxis::new()
+            .x(height)
+            .x_label(x_label)
+            .stroke(cx.theme().border)
+            .paint(&bounds, window, cx);
+
+        // Grid lines at the y-axis divisions.
+        Grid::new()
+            .y((0..Y_TICKS)
+                .map(|i| TOP_MARGIN + (height - TOP_MARGIN) * i as f32 / Y_TICKS as f32)
+                .collect())
+            .stroke(cx.theme().border)
+            .dash_array(&[px(4.), px(2.)])
+            .paint(&bounds, window);
+
+        // One line per series, thinned as `downsample` asks, over its fill
+        // down to the x axis when there is one.
+        let target = width.max(3.0) as usize;
+        for (points, spec) in self.prepared.series.iter().zip(&self.specs) {
+            let points = self.downsample.apply(points, target);
+            let x = x.clone();
+            let y = if spec.secondary {
+                y2.clone()
+            } else {
+                y.clone()
+            };
+            if let Some(opacity) = self.fill {
+                let origin = bounds.origin;
+                let mut area: Vec<Point<Pixels>> = points
+                    .iter()
+                    .filter_map(|p| {
+                        Some(point(
+                            origin.x + px(x.tick(&p.0)?),
+                            origin.y + px(y.tick(&p.1)?),
+                        ))
+                    })
+                    .collect();
+                if area.len() > 1 {
+                    let baseline = origin.y + px(height);
+                    let (first, last) = (area[0], area[area.len() - 1]);
+                    area.push(point(last.x, baseline));
+                    area.push(point(first.x, baseline));
+                    area.push(first);
+                    let mut builder = PathBuilder::fill();
+                    builder.add_polygon(&area, false);
+                    if let Ok(path) = builder.build() {
+                        window.paint_path(path, spec.color.opacity(opacity));
+                    }
+                }
+            }
+            let mut line = Line::new()
+                .data(&points)
+                .x(move |p: &(f64, f64)| x.tick(&p.0))
+                .y(move |p: &(f64, f64)| y.tick(&p.1))
+                .stroke(spec.color)
+                .stroke_style(self.stroke_style)
+                .stroke_width(2.);
+            if self.dot {
+                line = line.dot();
+            }
+            line.paint(&bounds, window);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn obj(pairs: &[(&str, Value)]) -> Value {
+        Value::Object(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let ms = 1_760_788_800_000.0; // 2025-10-18T12:00:00Z
+        assert_eq!(parse_time(&Value::Integer(1_760_788_800)), Some(ms));
+        assert_eq!(parse_time(&Value::Float(ms)), Some(ms));
+        assert_eq!(
+            parse_time(&Value::String("2025-10-18T12:00:00Z".into())),
+            Some(ms)
+        );
+        assert_eq!(
+            parse_time(&Value::String("2025-10-18T14:00:00.250+02:00".into())),
+            Some(ms + 250.0)
+        );
+        assert!(parse_time(&Value::String("2025-10-18 12:00:00".into())).is_some());
+        assert!(parse_time(&Value::String("2025-10-18".into())).is_some());
+        assert_eq!(parse_time(&Value::String("noon".into())), None);
+        assert_eq!(parse_time(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Some(500.0));
+        assert_eq!(parse_duration("30s"), Some(30_000.0));
+        assert_eq!(parse_duration("5m"), Some(300_000.0));
+        assert_eq!(parse_duration("1.5h"), Some(5_400_000.0));
+        assert_eq!(parse_duration("1d"), Some(86_400_000.0));
+        assert_eq!(parse_duration("10"), Some(10_000.0));
+        assert_eq!(parse_duration("5 parsecs"), None);
+        assert_eq!(parse_duration("m"), None);
+    }
+
+    #[test]
+    fn test_time_ticks_pick_round_steps() {
+        let t0 = 1_760_788_812_345.0;
+        let (step, ticks) = time_ticks(t0, t0 + 5.0 * MINUTE, 6);
+        assert_eq!(step, MINUTE);
+        assert_eq!(ticks.len(), 5);
+        assert!(ticks.iter().all(|t| t % MINUTE == 0.0));
+        assert!(ticks[0] >= t0 && *ticks.last().unwrap() <= t0 + 5.0 * MINUTE);
+
+        let (step, _) = time_ticks(t0, t0 + 2.0 * DAY, 4);
+        assert_eq!(step, 12.0 * HOUR);
+        let (step, _) = time_ticks(t0, t0 + 900.0, 5);
+        assert_eq!(step, 200.0);
+    }
+
+    #[test]
+    fn test_format_time_tick() {
+        let ms = 1_760_788_805_000.0; // 2025-10-18T12:00:05Z
+        assert_eq!(format_time_tick(ms, SECOND, &Utc), "12:00:05");
+        assert_eq!(format_time_tick(ms, MINUTE, &Utc), "12:00");
+        assert_eq!(format_time_tick(ms, 100.0, &Utc), "12:00:05.000");
+        assert_eq!(format_time_tick(ms, DAY, &Utc), "Oct 18");
+        let cest = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(format_time_tick(ms, MINUTE, &cest), "14:00");
+    }
+
+    #[test]
+    fn test_nice_domain_and_labels() {
+        assert_eq!(nice_domain(0.0, 87.0, 4), (0.0, 100.0));
+        assert_eq!(nice_domain(0.0, 9.0, 4), (0.0, 10.0));
+        assert_eq!(nice_domain(-3.0, 12.0, 4), (-5.0, 15.0));
+        assert_eq!(nice_domain(0.0, 0.0, 4), (0.0, 1.0));
+        assert_eq!(format_value(2500.0), "2500");
+        assert_eq!(format_value(25000.0), "25k");
+        assert_eq!(format_value(1_500_000.0), "1.5M");
+        assert_eq!(format_value(0.25), "0.25");
+        assert_eq!(format_value(-5.0), "-5");
+    }
+
+    #[test]
+    fn test_downsample_draws_every_sample_by_default() {
+        let props = |value: Option<&str>| -> HashMap<String, Value> {
+            value
+                .map(|v| ("downsample".to_string(), Value::String(v.into())))
+                .into_iter()
+                .collect()
+        };
+        assert_eq!(Downsample::from_props(&props(None)), Downsample::None);
+        assert_eq!(
+            Downsample::from_props(&props(Some("lttb"))),
+            Downsample::Lttb
+        );
+        assert_eq!(
+            Downsample::from_props(&props(Some("minmax"))),
+            Downsample::MinMax
+        );
+        assert_eq!(
+            Downsample::from_props(&props(Some("bogus"))),
+            Downsample::None
+        );
+    }
+
+    #[test]
+    fn test_lttb_keeps_ends_and_peaks() {
+        let points: Vec<(f64, f64)> = (0..1000)
+            .map(|i| (i as f64, if i == 500 { 100.0 } else { (i % 7) as f64 }))
+            .collect();
+        let sampled = lttb(&points, 50);
+        assert_eq!(sampled.len(), 50);
+        assert_eq!(sampled[0], points[0]);
+        assert_eq!(sampled[49], points[999]);
+        assert!(sampled.contains(&(500.0, 100.0)));
+        assert!(sampled.windows(2).all(|w| w[0].0 < w[1].0));
+        // Short series pass through untouched.
+        assert_eq!(lttb(&points[..10], 50), points[..10].to_vec());
+    }
+
+    #[test]
+    fn test_minmax_keeps_extremes_in_order() {
+        let points: Vec<(f64, f64)> = (0..100)
+            .map(|i| (i as f64, if i == 37 { -50.0 } else { i as f64 }))
+            .collect();
+        let sampled = minmax(&points, 10);
+        assert!(sampled.len() <= 20);
+        assert!(sampled.contains(&(37.0, -50.0)));
+        assert!(sampled.contains(&(99.0, 99.0)));
+        assert!(sampled.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_prepare_time_window_and_secondary_axis() {
+        let data: Vec<Value> = [30, 0, 10, 20, 40]
+            .iter()
+            .map(|s| {
+                obj(&[
+                    ("t", Value::Integer(1_700_000_000 + s)),
+                    ("cpu", Value::Float(*s as f64)),
+                    ("mem", Value::Integer(1000 * *s)),
+                ])
+            })
+            .chain(Some(obj(&[("t", Value::String("bogus".into()))])))
+            .collect();
+        let fields = vec![("cpu".to_string(), false), ("mem".to_string(), true)];
+
+        let prepared = prepare(&data, "t", &fields, true, Some(25_000.0), &[]);
+        let t0 = 1_700_000_000_000.0;
+        assert_eq!(prepared.x_domain, (t0 + 20_000.0, t0 + 40_000.0));
+        assert_eq!(
+            prepared.series[0],
+            vec![
+                (t0 + 20_000.0, 20.0),
+                (t0 + 30_000.0, 30.0),
+                (t0 + 40_000.0, 40.0)
+            ]
+        );
+        assert_eq!(prepared.y_domain, (0.0, 40.0));
+        assert_eq!(prepared.y2_domain, (0.0, 40_000.0));
+        assert!(prepared.labels.is_empty());
+
+        // Applying the window to already windowed data changes nothing.
+        let windowed: Vec<Value> = data
+            .iter()
+            .filter(|v| v.get("t").and_then(|t| t.as_i64()) >= Some(1_700_000_015))
+            .cloned()
+            .collect();
+        let again = prepare(&windowed, "t", &fields, true, Some(25_000.0), &[]);
+        assert_eq!(again, prepared);
+
+        // Annotation values widen the domain they belong to.
+        let widened = prepare(&windowed, "t", &fields, true, None, &[(90.0, false)]);
+        assert_eq!(widened.y_domain, (0.0, 100.0));
+        assert_eq!(widened.y2_domain, prepared.y2_domain);
+        assert_eq!(widened.y_fraction(75.0, false), 0.25);
+        assert_eq!(widened.y_fraction(10_000.0, true), 0.75);
+        assert_eq!(widened.x_fraction(t0 + 30_000.0), 0.5);
+
+        // Index axis keeps data order and labels; missing values are gaps.
+        let prepared = prepare(&data, "t", &fields, false, None, &[]);
+        assert_eq!(prepared.x_domain, (0.0, 5.0));
+        assert_eq!(prepared.series[0].len(), 5);
+        assert_eq!(prepared.labels.len(), 6);
+    }
+}
@@ -0,0 +1,346 @@
+use gpui::*;
+use nemo_config::Value;
+use nemo_layout::virtual_list::{item_keys, row_node, splice_range};
+use nemo_layout::BuiltComponent;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::runtime::NemoRuntime;
+
+/// Rows rendered beyond the visible window in each direction, in pixels.
+const OVERDRAW: f32 = 200.0;
+
+/// Default number of rows from the end at which `on-end-reached` fires.
+const DEFAULT_END_THRESHOLD: usize = 5;
+
+/// A scrolling list that renders its item template only for the rows in
+/// view, for collections too large to build a component per item.
+///
+/// # XML Configuration
+///
+/// ```xml
+/// <virtual-list id="feed" item="msg" n:key="msg.id" height="480"
+///   on-end-reached="load_more">
+///   <binding source="data.chat.messages" target="items" />
+///   <stack direction="horizontal" spacing="8">
+///     <label text="${msg.author}" />
+///     <text content="${msg.body}" />
+///   </stack>
+/// </virtual-list>
+/// ```
+///
+/// The single child is the item template; `${msg.field}` placeholders are
+/// filled from each item, and directives in it may test the item
+/// (`n:if="msg.unread"`). Rows are real components under the list while in
+/// view, so their bindings, two-way values and handlers work as anywhere
+/// else. Rows may differ in height; each is measured as it is first laid
+/// out.
+///
+/// # Properties
+///
+/// | Property | Type | Description |
+/// |----------|------|-------------|
+/// | `items` | array | The items to list (usually bound) |
+/// | `item` | string | Name of the item variable in the template (default: `item`) |
+/// | `n:key` | string | Key expression (`msg.id`) giving each row a stable identity |
+/// | `height` | int | Height in pixels (default: fill the parent) |
+/// | `scroll_to` | int | Index of a row to scroll into view |
+/// | `end_threshold` | int | Rows from the end at which `on-end-reached` fires (default: 5) |
+/// | `on-end-reached` | handler | Called with the item count when the end nears |
+#[derive(IntoElement)]
+pub struct VirtualList {
+    source: BuiltComponent,
+    list: Option<List>,
+}
+
+impl VirtualList {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self { source, list: None }
+    }
+
+    pub fn list(mut self, list: List) -> Self {
+        self.list = Some(list);
+        self
+    }
+}
+
+impl RenderOnce for VirtualList {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let height = self
+            .source
+            .properties
+            .get("height")
+            .and_then(|v| v.as_i64());
+        let mut el = div()
+            .id(SharedString::from(self.source.id.clone()))
+            .w_full();
+        el = match height {
+            Some(h) => el.h(px(h as f32)),
+            None => el.flex_1().size_full(),
+        };
+        el.children(self.list.map(|list| list.size_full()))
+    }
+}
+
+/// Most rows a list keeps in the layout; beyond this, the rows rendered
+/// longest ago are removed.
+const MAX_ROWS: usize = 200;
+
+/// Render state of a `<virtual-list>`: gpui's list state (scroll position and
+/// measured row heights), the items and keys it was last synced to, and the
+/// rows inserted into the layout so far, by key.
+pub(crate) struct VirtualListState {
+    pub list: ListState,
+    id: String,
+    runtime: Arc<NemoRuntime>,
+    template: Value,
+    item_var: String,
+    keys: Vec<String>,
+    items: Vec<Value>,
+    rows: HashMap<String, Row>,
+    /// Counts syncs, one per render, to age rows.
+    frame: u64,
+    last_scroll_to: Option<i64>,
+    end_reached: Option<EndReached>,
+    /// Item count the end was last checked at after layout.
+    checked_count: Option<usize>,
+}
+
+/// A row in the layout.
+struct Row {
+    /// The item it was built from.
+    item: Value,
+    /// Id of its root component; `None` when its `n:if` hid it.
+    id: Option<String>,
+    /// The frame it last rendered in.
+    used: u64,
+}
+
+/// `on-end-reached`, shared by the scroll handler and the check after
+/// layout: fires once per item count when the visible rows come within
+/// `threshold` of the end.
+#[derive(Clone)]
+struct EndReached {
+    handler: String,
+    id: String,
+    threshold: usize,
+    fired_at: Rc<Cell<Option<usize>>>,
+    runtime: Arc<NemoRuntime>,
+}
+
+impl EndReached {
+    fn check(&self, visible_end: usize, count: usize) {
+        if count > 0 && visible_end + self.threshold >= count && self.fired_at.get() != Some(count)
+        {
+            self.fired_at.set(Some(count));
+            self.runtime
+                .call_handler(&self.handler, &self.id, &count.to_string());
+        }
+    }
+}
+
+/// The index past the last row laid out inside the list's viewport.
+fn visible_end(list: &ListState) -> usize {
+    let bottom = list.viewport_bounds().bottom();
+    let mut ix = list.logical_scroll_top().item_ix;
+    while ix < list.item_count() && list.bounds_for_item(ix).is_some_and(|b| b.top() < bottom) {
+        ix += 1;
+    }
+    ix
+}
+
+impl VirtualListState {
+    /// Creates the state for `component`, wiring `on-end-reached` to the
+    /// list's scroll events.
+    pub fn new(component: &BuiltComponent, runtime: Arc<NemoRuntime>) -> Self {
+        let list = ListState::new(0, ListAlignment::Top, px(OVERDRAW));
+        let end_reached = component
+            .handlers
+            .get("end_reached")
+            .map(|handler| EndReached {
+                handler: handler.clone(),
+                id: component.id.clone(),
+                threshold: component
+                    .properties
+                    .get("end_threshold")
+                    .and_then(|v| v.as_i64())
+                    .map(|t| t.max(0) as usize)
+                    .unwrap_or(DEFAULT_END_THRESHOLD),
+                fired_at: Rc::new(Cell::new(None)),
+                runtime: Arc::clone(&runtime),
+            });
+        if let Some(end_reached) = end_reached.clone() {
+            list.set_scroll_handler(move |event: &ListScrollEvent, _window, _cx| {
+                end_reached.check(event.visible_range.end, event.count);
+            });
+        }
+        Self {
+            list,
+            id: component.id.clone(),
+            runtime,
+            template: Value::Null,
+            item_var: String::new(),
+            keys: Vec::new(),
+            items: Vec::new(),
+            rows: HashMap::new(),
+            frame: 0,
+            last_scroll_to: None,
+            end_reached,
+            checked_count: None,
+        }
+    }
+
+    /// Brings the list in line with the component's current `items` and
+    /// `scroll_to`. Only the stretch of rows whose keys changed is spliced,
+    /// and rows whose item changed in place are re-measured; the rest keep
+    /// their heights and the scroll position holds.
+    ///
+    /// Scrolling isn't the only way the end comes into view: a first page
+    /// shorter than the list never scrolls. So once the rows for a new item
+    /// count are laid out, the end is checked again.
+    pub fn sync(&mut self, component: &BuiltComponent, window: &mut Window) {
+        self.frame += 1;
+        let props = &component.properties;
+        let template = props.get("item_template").unwrap_or(&Value::Null);
+        let item_var = props.get("item").and_then(|v| v.as_str()).unwrap_or("item");
+        if &self.template != template || self.item_var != item_var {
+            // A new template invalidates every row.
+            self.template = template.clone();
+            self.item_var = item_var.to_string();
+            for key in self.rows.keys().cloned().collect::<Vec<_>>() {
+                self.remove_row(&key);
+            }
+            self.list.reset(self.items.len());
+        }
+        let items = match props.get("items") {
+            Some(Value::Array(items)) => items.as_slice(),
+            _ => &[],
+        };
+        if self.items.as_slice() != items {
+            let keys = item_keys(
+                items,
+                props.get("item_key").and_then(|v| v.as_str()),
+                item_var,
+            );
+            let (replaced, inserted) =
+                splice_range(&self.keys, &keys).unwrap_or((self.keys.len()..self.keys.len(), 0));
+            // Rows kept before and after the spliced stretch whose item
+            // changed in place need measuring again.
+            let shift = inserted as isize - replaced.len() as isize;
+            let stale: Vec<usize> = (0..self.items.len())
+                .filter(|i| !replaced.contains(i))
+                .map(|i| {
+                    (
+                        i,
+                        if i < replaced.start {
+                            i
+                        } else {
+                            (i as isize + shift) as usize
+                        },
+                    )
+                })
+                .filter(|&(old, new)| self.items[old] != items[new])
+                .map(|(_, new)| new)
+                .collect();
+            if !replaced.is_empty() || inserted > 0 {
+                self.list.splice(replaced, inserted);
+            }
+            for ix in stale {
+                self.list.splice(ix..ix + 1, 1);
+            }
+            let live: HashSet<&String> = keys.iter().collect();
+            let gone: Vec<String> = self
+                .rows
+                .keys()
+                .filter(|key| !live.contains(key))
+                .cloned()
+                .collect();
+            for key in gone {
+                self.remove_row(&key);
+            }
+            self.keys = keys;
+            self.items = items.to_vec();
+        }
+        self.evict_rows();
+
+        let scroll_to = props.get("scroll_to").and_then(|v| v.as_i64());
+        if scroll_to != self.last_scroll_to {
+            if let Some(ix) = scroll_to.filter(|&ix| ix >= 0 && (ix as usize) < self.items.len()) {
+                self.list.scroll_to_reveal_item(ix as usize);
+            }
+            self.last_scroll_to = scroll_to;
+        }
+
+        if let Some(end_reached) = self.end_reached.clone() {
+            if self.checked_count != Some(self.items.len()) {
+                self.checked_count = Some(self.items.len());
+                let list = self.list.clone();
+                window.on_next_frame(move |_window, _cx| {
+                    end_reached.check(visible_end(&list), list.item_count());
+                });
+            }
+        }
+    }
+
+    /// The id of the row at `ix`, inserted into the layout from the item
+    /// template on first use and reused for as long as its item is
+    /// unchanged. `None` for a row its `n:if` hides.
+    pub fn row(&mut self, ix: usize) -> Option<String> {
+        let item = self.items.get(ix)?.clone();
+        let key = self.keys.get(ix)?.clone();
+        if let Some(row) = self.rows.get_mut(&key) {
+            if row.item == item {
+                row.used = self.frame;
+                return row.id.clone();
+            }
+        }
+        self.remove_row(&key);
+        if self.template.is_null() {
+            return None;
+        }
+        let id = row_node(&self.template, &self.item_var, &item).and_then(|node| {
+            let id = format!("{}_{}", self.id, key);
+            self.runtime
+                .insert_virtual_row(&self.id, &id, &node)
+                .then_some(id)
+        });
+        self.rows.insert(
+            key,
+            Row {
+                item,
+                id: id.clone(),
+                used: self.frame,
+            },
+        );
+        id
+    }
+
+    /// Removes the row for `key` from the layout.
+    fn remove_row(&mut self, key: &str) {
+        if let Some(id) = self.rows.remove(key).and_then(|row| row.id) {
+            self.runtime.remove_virtual_row(&id);
+        }
+    }
+
+    /// Removes the rows rendered longest ago beyond [`MAX_ROWS`], never one
+    /// still in view.
+    fn evict_rows(&mut self) {
+        let Some(excess) = self.rows.len().checked_sub(MAX_ROWS) else {
+            return;
+        };
+        let mut by_age: Vec<(u64, String)> = self
+            .rows
+            .iter()
+            .map(|(key, row)| (row.used, key.clone()))
+            .collect();
+        by_age.sort();
+        for (used, key) in by_age.into_iter().take(excess) {
+            if used + 1 >= self.frame {
+                break;
+            }
+            self.remove_row(&key);
+        }
+    }
+}
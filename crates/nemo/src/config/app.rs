@@ -13,6 +13,9 @@ pub struct AppConfig {
     /// (e.g. `"3"`). Sets the gpui-component `Theme.radius`, which every widget
     /// and all nemo-drawn chrome scale from. `None` keeps the theme default.
     pub roundness: Option<String>,
+    /// UI language for apps that ship `<i18n>` catalogs (e.g. `"de"` or
+    /// `"pt-BR"`). `None` uses each app's default locale.
+    pub locale: Option<String>,
 }
 
 impl Default for AppConfig {
@@ -22,6 +25,7 @@ impl Default for AppConfig {
             theme_mode: None,
             font_family: None,
             roundness: None,
+            locale: None,
         }
     }
 }
@@ -0,0 +1,381 @@
+//! Drag-and-drop: **draggable** children and **drop-target** containers.
+//!
+//! Any child of a stack, panel or list (a `<list-item>`) with
+//! `draggable="true"` can be picked up. Dropping it onto one of its siblings
+//! moves it to that sibling's position: the container's child order changes,
+//! a live-data `n:for` container has its bound array reordered to match, and
+//! the container fires `on-reorder`.
+//!
+//! With `n:for`, the loop element becomes the container of its instances.
+//! Its `draggable`/`drag-*` attributes stay on the instances, and a reorder
+//! fires the `on-reorder` of the element enclosing the loop (unless the loop
+//! element has its own), as `todo_cards` below.
+//!
+//! `<drop-target>` lays out its children like a vertical stack and receives
+//! draggable items dropped from anywhere else, and files dropped from the
+//! OS. Items dropped onto a sibling inside a target still reorder; an item
+//! dropped onto a sibling in another container falls through to the
+//! enclosing target, which is what moves cards between kanban columns.
+//!
+//! # XML Configuration
+//!
+//! ```xml
+//! <stack direction="horizontal" spacing="12">
+//!   <drop-target id="todo" accept="card" on-drop="move_card" padding="8">
+//!     <stack id="todo_cards" on-reorder="reorder_cards">
+//!       <panel n:for="card in data.board.todo" n:key="card.id"
+//!              draggable="true" drag-group="card" drag-data="${card.id}">
+//!         <label text="${card.title}" />
+//!       </panel>
+//!     </stack>
+//!   </drop-target>
+//!   <drop-target id="inbox" accept="files" on-drop="ingest" />
+//! </stack>
+//! ```
+//!
+//! # Properties
+//!
+//! On a draggable child:
+//!
+//! | Property | Type | Description |
+//! |----------|------|-------------|
+//! | `draggable` | bool | Lets the user drag the child |
+//! | `drag-group` | string | Group name matched against a target's `accept` |
+//! | `drag-data` | any | Value passed to `on-drop` with the item |
+//! | `drag-label` | string | Text of the drag preview (default: `label`/`text`, else the id) |
+//!
+//! On the container of draggable children, `on-reorder` is called with
+//! `{"id", "from", "to", "order"}` after a reorder. For a live-data `n:for`,
+//! that is the loop element or, failing that, the element around it.
+//!
+//! On a `<drop-target>`:
+//!
+//! | Property | Type | Description |
+//! |----------|------|-------------|
+//! | `accept` | string | Comma separated drag groups, and `files` for OS file drops (default: everything) |
+//! | `spacing` | int | Gap between children in pixels (default: 4) |
+//! | `on-drop` | string | Handler called with the dropped item or files as JSON |
+
+use gpui::*;
+use gpui_component::ActiveTheme;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+use std::sync::Arc;
+
+use crate::runtime::NemoRuntime;
+
+/// Drag payload for a draggable child; also renders the drag preview.
+#[derive(Clone)]
+pub struct DraggedItem {
+    /// Id of the dragged component.
+    pub id: String,
+    /// Id of the container it was dragged from.
+    pub parent: String,
+    /// Its index among the container's children.
+    pub index: usize,
+    /// Its `drag-group`.
+    pub group: Option<String>,
+    /// Its `drag-data`.
+    pub data: Value,
+    /// Text of the drag preview.
+    label: String,
+}
+
+impl DraggedItem {
+    /// The payload for `child` at `index` in `parent`, or `None` when the
+    /// child isn't `draggable`.
+    pub fn from_child(child: &BuiltComponent, parent: &str, index: usize) -> Option<Self> {
+        let props = &child.properties;
+        if props.get("draggable").and_then(|v| v.as_bool()) != Some(true) {
+            return None;
+        }
+        let label = ["drag_label", "label", "text"]
+            .iter()
+            .find_map(|key| props.get(*key).and_then(|v| v.as_str()))
+            .unwrap_or(&child.id)
+            .to_string();
+        Some(Self {
+            id: child.id.clone(),
+            parent: parent.to_string(),
+            index,
+            group: props
+                .get("drag_group")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            data: props.get("drag_data").cloned().unwrap_or(Value::Null),
+            label,
+        })
+    }
+
+    /// The `on-drop` payload for this item.
+    pub fn drop_value(&self) -> Value {
+        let mut obj = indexmap::IndexMap::new();
+        obj.insert("kind".to_string(), Value::String("item".to_string()));
+        obj.insert("id".to_string(), Value::String(self.id.clone()));
+        obj.insert("source".to_string(), Value::String(self.parent.clone()));
+        obj.insert("index".to_string(), Value::Integer(self.index as i64));
+        obj.insert(
+            "group".to_string(),
+            self.group.clone().map(Value::String).unwrap_or(Value::Null),
+        );
+        obj.insert("data".to_string(), self.data.clone());
+        Value::Object(obj)
+    }
+}
+
+impl Render for DraggedItem {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors.accent)
+            .bg(cx.theme().colors.background)
+            .shadow_md()
+            .text_sm()
+            .child(self.label.clone())
+    }
+}
+
+/// The `on-drop` payload for files dropped from the OS.
+pub fn files_value(paths: &[std::path::PathBuf]) -> Value {
+    let mut obj = indexmap::IndexMap::new();
+    obj.insert("kind".to_string(), Value::String("files".to_string()));
+    obj.insert(
+        "paths".to_string(),
+        Value::Array(
+            paths
+                .iter()
+                .map(|p| Value::String(p.to_string_lossy().into_owned()))
+                .collect(),
+        ),
+    );
+    Value::Object(obj)
+}
+
+/// Whether a target with `accept` takes a drop of `group` (`Some("files")`
+/// for OS files). No `accept` takes everything.
+pub fn accepts(accept: Option<&str>, group: Option<&str>) -> bool {
+    let Some(accept) = accept else {
+        return true;
+    };
+    let Some(group) = group else {
+        return false;
+    };
+    accept
+        .split(',')
+        .map(str::trim)
+        .any(|name| name == "*" || name == group)
+}
+
+/// Wraps a draggable child: it can be picked up, and dropping one of its
+/// siblings onto it moves that sibling to its position.
+#[derive(IntoElement)]
+pub struct Draggable {
+    item: DraggedItem,
+    child: AnyElement,
+    runtime: Arc<NemoRuntime>,
+    entity_id: EntityId,
+}
+
+impl Draggable {
+    pub fn new(
+        item: DraggedItem,
+        child: AnyElement,
+        runtime: Arc<NemoRuntime>,
+        entity_id: EntityId,
+    ) -> Self {
+        Self {
+            item,
+            child,
+            runtime,
+            entity_id,
+        }
+    }
+}
+
+impl RenderOnce for Draggable {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let accent = cx.theme().colors.accent;
+        let DraggedItem {
+            id, parent, index, ..
+        } = self.item.clone();
+        let over_parent = parent.clone();
+        let drop_parent = parent.clone();
+        let runtime = self.runtime;
+        let entity_id = self.entity_id;
+
+        div()
+            .id(SharedString::from(format!("{}-drag", id)))
+            .cursor_grab()
+            .child(self.child)
+            .on_drag(self.item, |item, _offset, _window, cx| {
+                cx.new(|_| item.clone())
+            })
+            // Only siblings reorder; anything else falls through to an
+            // enclosing drop target.
+            .can_drop(move |value, _window, _cx| {
+                value
+                    .downcast_ref::<DraggedItem>()
+                    .is_some_and(|d| d.parent == parent && d.id != id)
+            })
+            .drag_over::<DraggedItem>(move |style, dragged, _window, _cx| {
+                if dragged.parent == over_parent {
+                    style.border_t_2().border_color(accent)
+                } else {
+                    style
+                }
+            })
+            .on_drop(move |dragged: &DraggedItem, _window, cx| {
+                runtime.reorder_child(&drop_parent, &dragged.id, index);
+                cx.notify(entity_id);
+            })
+    }
+}
+
+/// A container that receives dragged items and OS file drops.
+#[derive(IntoElement)]
+pub struct DropTarget {
+    source: BuiltComponent,
+    children: Vec<AnyElement>,
+    runtime: Option<Arc<NemoRuntime>>,
+    entity_id: Option<EntityId>,
+}
+
+impl DropTarget {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            children: Vec::new(),
+            runtime: None,
+            entity_id: None,
+        }
+    }
+
+    pub fn children(mut self, children: Vec<AnyElement>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn runtime(mut self, runtime: Arc<NemoRuntime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
+}
+
+impl RenderOnce for DropTarget {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let spacing = props.get("spacing").and_then(|v| v.as_i64()).unwrap_or(4);
+        let accept = props
+            .get("accept")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let highlight = cx.theme().colors.accent.opacity(0.15);
+        let mut container = div()
+            .id(SharedString::from(self.source.id.clone()))
+            .flex()
+            .flex_col()
+            .gap(px(spacing as f32))
+            .min_h(px(24.))
+            .children(self.children);
+        if crate::components::container_grows(props) {
+            container = container.flex_1();
+        }
+
+        let (Some(runtime), Some(entity_id)) = (self.runtime, self.entity_id) else {
+            return container;
+        };
+        let target_id = self.source.id.clone();
+        let file_target_id = target_id.clone();
+        let handler = self.source.handlers.get("drop").cloned();
+        let file_handler = handler.clone();
+        let file_runtime = Arc::clone(&runtime);
+        let can_accept = accept.clone();
+
+        container
+            .can_drop(move |value, _window, _cx| {
+                let group = if value.is::<ExternalPaths>() {
+                    Some("files")
+                } else if let Some(item) = value.downcast_ref::<DraggedItem>() {
+                    if can_accept.is_none() {
+                        return true;
+                    }
+                    item.group.as_deref()
+                } else {
+                    return false;
+                };
+                accepts(can_accept.as_deref(), group)
+            })
+            .drag_over::<DraggedItem>(move |style, _, _, _| style.bg(highlight))
+            .drag_over::<ExternalPaths>(move |style, _, _, _| style.bg(highlight))
+            .on_drop(move |item: &DraggedItem, _window, cx| {
+                if let Some(handler) = &handler {
+                    let data = serde_json::to_string(&item.drop_value()).unwrap_or_default();
+                    runtime.call_handler(handler, &target_id, &data);
+                }
+                cx.notify(entity_id);
+            })
+            .on_drop(move |paths: &ExternalPaths, _window, cx| {
+                if let Some(handler) = &file_handler {
+                    let data =
+                        serde_json::to_string(&files_value(paths.paths())).unwrap_or_default();
+                    file_runtime.call_handler(handler, &file_target_id, &data);
+                }
+                cx.notify(entity_id);
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts() {
+        assert!(accepts(None, None));
+        assert!(accepts(None, Some("files")));
+        assert!(accepts(Some("card, files"), Some("files")));
+        assert!(accepts(Some("card, files"), Some("card")));
+        assert!(!accepts(Some("card"), Some("files")));
+        assert!(!accepts(Some("card"), None));
+        assert!(accepts(Some("*"), Some("task")));
+    }
+
+    #[test]
+    fn test_dragged_item() {
+        let mut child = BuiltComponent::new("todo_7", "panel").with_parent("todo_cards");
+        assert!(DraggedItem::from_child(&child, "todo_cards", 2).is_none());
+
+        child
+            .properties
+            .insert("draggable".to_string(), Value::Bool(true));
+        child
+            .properties
+            .insert("drag_group".to_string(), Value::String("card".into()));
+        child
+            .properties
+            .insert("drag_data".to_string(), Value::Integer(7));
+        let item = DraggedItem::from_child(&child, "todo_cards", 2).unwrap();
+        assert_eq!(item.label, "todo_7");
+        assert_eq!(
+            serde_json::to_string(&item.drop_value()).unwrap(),
+            r#"{"kind":"item","id":"todo_7","source":"todo_cards","index":2,"group":"card","data":7}"#
+        );
+    }
+
+    #[test]
+    fn test_files_value() {
+        let value = files_value(&["/tmp/a.csv".into(), "/tmp/b.png".into()]);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"kind":"files","paths":["/tmp/a.csv","/tmp/b.png"]}"#
+        );
+    }
+}
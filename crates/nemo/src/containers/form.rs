@@ -0,0 +1,613 @@
+//! A validating **form** container.
+//!
+//! A `<form>` gathers the values of its named descendant fields (`input`,
+//! `textarea`, `select`, `checkbox`, `radio`, `slider`, `switch`) into one
+//! object, validates them, and on a valid submit writes the object to its
+//! `value` property (so a `two_way` binding stores it in the repository) and
+//! fires `on-submit` with it as JSON. Invalid fields get an inline error
+//! under them and nothing is submitted.
+//!
+//! Field rules are plain attributes on the field, read into [`FieldRule`]s.
+//! They are the form's own: a field holds what a person typed, so bounds
+//! compare as numbers with decimals and lengths count characters, unlike the
+//! config schema's `ValidationRule`s.
+//!
+//! | Attribute | Rule |
+//! |-----------|------|
+//! | `required="true"` | non-empty (a checkbox/switch must be on) |
+//! | `min` / `max` | [`FieldRule::Min`] / [`FieldRule::Max`]; text is parsed as a number, bounds may have decimals |
+//! | `min-length` / `max-length` | [`FieldRule::MinLength`] / [`FieldRule::MaxLength`], in characters |
+//! | `pattern` | [`FieldRule::Pattern`] (regex) |
+//! | `validator` | a Rhai `fn(value, values)` (`script::fn`), run through `NemoRuntime::call_validator` once the rules above pass; it sees every field, so it takes the place of a schema `custom` rule |
+//! | `message` | replaces the message of any failing rule |
+//!
+//! # XML Configuration
+//!
+//! ```xml
+//! <form id="signup" submit-label="Create account" on-submit="create_account">
+//!   <input name="email" required="true" pattern="^[^@]+@[^@]+$" message="Enter an email" />
+//!   <input name="age" min="18" max="120" />
+//!   <input name="password" min-length="8" />
+//!   <input name="confirm" validator="checks::same_password" />
+//!   <checkbox name="terms" label="I accept the terms" required="true" />
+//! </form>
+//! ```
+//!
+//! Fields nested in stacks or panels inside the form are collected too; a
+//! nested `<form>` owns its own fields.
+
+use gpui::*;
+use gpui_component::button::{Button as GpuiButton, ButtonVariants};
+use gpui_component::{h_flex, v_flex, ActiveTheme};
+use indexmap::IndexMap;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+use std::collections::HashMap;
+
+use crate::theme::tokens::{FontSize, Space, TokenStyled};
+
+/// Component types a form collects values from.
+pub const FIELD_TYPES: &[&str] = &[
+    "input",
+    "textarea",
+    "select",
+    "checkbox",
+    "radio",
+    "slider",
+    "switch",
+    "number_input",
+    "date_picker",
+    "datetime_picker",
+    "color_picker",
+];
+
+/// Message for a missing `required` value.
+const REQUIRED_MESSAGE: &str = "This field is required";
+
+/// A built-in check on a form field's value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldRule {
+    /// The number is at least this.
+    Min(f64),
+    /// The number is at most this.
+    Max(f64),
+    /// The text has at least this many characters (an array, items).
+    MinLength(usize),
+    /// The text has at most this many characters (an array, items).
+    MaxLength(usize),
+    /// The text matches this regex.
+    Pattern(String),
+}
+
+impl FieldRule {
+    /// Checks `value`, returning a message on violation. A rule that doesn't
+    /// apply to the value's type (`Min` on text, `Pattern` on a number)
+    /// passes.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let length = || match value {
+            Value::String(s) => Some(s.chars().count()),
+            Value::Array(a) => Some(a.len()),
+            _ => None,
+        };
+        match self {
+            FieldRule::Min(min) => match value.as_f64() {
+                Some(n) if n < *min => Err(format!("value {} is less than minimum {}", n, min)),
+                _ => Ok(()),
+            },
+            FieldRule::Max(max) => match value.as_f64() {
+                Some(n) if n > *max => Err(format!("value {} is greater than maximum {}", n, max)),
+                _ => Ok(()),
+            },
+            FieldRule::MinLength(min) => match length() {
+                Some(len) if len < *min => {
+                    Err(format!("length {} is less than minimum {}", len, min))
+                }
+                _ => Ok(()),
+            },
+            FieldRule::MaxLength(max) => match length() {
+                Some(len) if len > *max => {
+                    Err(format!("length {} is greater than maximum {}", len, max))
+                }
+                _ => Ok(()),
+            },
+            FieldRule::Pattern(pattern) => {
+                let Value::String(text) = value else {
+                    return Ok(());
+                };
+                let re =
+                    regex::Regex::new(pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+                if re.is_match(text) {
+                    Ok(())
+                } else {
+                    Err(format!("value does not match pattern {}", pattern))
+                }
+            }
+        }
+    }
+}
+
+/// The validation declared on one named form field.
+#[derive(Debug, Clone)]
+pub struct FieldSpec {
+    /// Id of the field component (where its inline error renders).
+    pub component_id: String,
+    /// Key of the field in the submitted object.
+    pub name: String,
+    pub required: bool,
+    pub rules: Vec<FieldRule>,
+    /// Custom Rhai validator (`script::fn`).
+    pub validator: Option<String>,
+    /// Replaces the message of any failing check.
+    pub message: Option<String>,
+}
+
+impl FieldSpec {
+    /// Reads the field spec from a component's attributes. Returns `None` for
+    /// components without a `name`.
+    pub fn from_component(component: &BuiltComponent) -> Option<Self> {
+        let props = &component.properties;
+        let name = props.get("name").and_then(|v| v.as_str())?.to_string();
+        let number = |key: &str| {
+            props
+                .get(key)
+                .and_then(|v| v.as_f64().or_else(|| v.as_str()?.trim().parse().ok()))
+        };
+        let count = |key: &str| number(key).map(|n| n.max(0.0) as usize);
+
+        // A slider's or number input's `min`/`max` are its range, not rules
+        // (and a date picker's are dates, which don't parse as numbers).
+        let mut rules = Vec::new();
+        if !matches!(component.component_type.as_str(), "slider" | "number_input") {
+            if let Some(min) = number("min") {
+                rules.push(FieldRule::Min(min));
+            }
+            if let Some(max) = number("max") {
+                rules.push(FieldRule::Max(max));
+            }
+        }
+        if let Some(min) = count("min_length") {
+            rules.push(FieldRule::MinLength(min));
+        }
+        if let Some(max) = count("max_length") {
+            rules.push(FieldRule::MaxLength(max));
+        }
+        if let Some(pattern) = props.get("pattern").and_then(|v| v.as_str()) {
+            rules.push(FieldRule::Pattern(pattern.to_string()));
+        }
+
+        Some(Self {
+            component_id: component.id.clone(),
+            name,
+            required: props
+                .get("required")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            rules,
+            validator: props
+                .get("validator")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            message: props
+                .get("message")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        })
+    }
+
+    /// Whether the field holds a number (it declares `min`/`max`), so text
+    /// input is parsed before validation and submitted as a number.
+    fn is_numeric(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|r| matches!(r, FieldRule::Min(_) | FieldRule::Max(_)))
+    }
+
+    /// Converts a raw field value to the submitted value: numeric fields
+    /// parse their text (blank becomes null), everything else passes through.
+    pub fn normalize(&self, raw: Value) -> Result<Value, String> {
+        let Value::String(text) = &raw else {
+            return Ok(raw);
+        };
+        if !self.is_numeric() {
+            return Ok(raw);
+        }
+        let text = text.trim();
+        if text.is_empty() {
+            Ok(Value::Null)
+        } else if let Ok(i) = text.parse::<i64>() {
+            Ok(Value::Integer(i))
+        } else if let Ok(f) = text.parse::<f64>() {
+            Ok(Value::Float(f))
+        } else {
+            Err("Enter a number".to_string())
+        }
+    }
+
+    /// Checks `required` and the declared rules. An empty optional field
+    /// skips its rules.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        if is_blank(value) {
+            return if self.required {
+                Err(REQUIRED_MESSAGE.to_string())
+            } else {
+                Ok(())
+            };
+        }
+        self.rules.iter().try_for_each(|rule| rule.check(value))
+    }
+
+    fn fail(&self, message: String) -> String {
+        self.message.clone().unwrap_or(message)
+    }
+}
+
+/// Whether a value counts as "not filled in" for `required`.
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+/// The outcome of validating a form.
+#[derive(Debug, Default, PartialEq)]
+pub struct FormResult {
+    /// Submitted values keyed by field name, in document order.
+    pub values: IndexMap<String, Value>,
+    /// Error message per invalid field name.
+    pub errors: IndexMap<String, String>,
+}
+
+impl FormResult {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Validates `fields` (spec + raw value). Built-in rules run first; a
+/// field's custom `validator` only runs once those pass, and is called with
+/// the field value and the complete values object.
+pub fn validate_form(
+    fields: Vec<(FieldSpec, Value)>,
+    mut custom: impl FnMut(&str, &Value, &Value) -> Result<(), String>,
+) -> FormResult {
+    let mut result = FormResult::default();
+    for (spec, raw) in &fields {
+        let checked = spec
+            .normalize(raw.clone())
+            .and_then(|value| spec.check(&value).map(|()| value));
+        match checked {
+            Ok(value) => {
+                result.values.insert(spec.name.clone(), value);
+            }
+            Err(message) => {
+                result.values.insert(spec.name.clone(), raw.clone());
+                result.errors.insert(spec.name.clone(), spec.fail(message));
+            }
+        }
+    }
+
+    let all = Value::Object(result.values.clone());
+    for (spec, _) in &fields {
+        let Some(validator) = &spec.validator else {
+            continue;
+        };
+        if result.errors.contains_key(&spec.name) {
+            continue;
+        }
+        let value = result.values.get(&spec.name).unwrap_or(&Value::Null);
+        if let Err(message) = custom(validator, value, &all) {
+            result.errors.insert(spec.name.clone(), spec.fail(message));
+        }
+    }
+    result
+}
+
+/// Collects the named field components under `form` in document order,
+/// descending through layout children but not into nested forms.
+pub fn collect_fields<'a>(
+    form: &BuiltComponent,
+    components: &'a HashMap<String, BuiltComponent>,
+) -> Vec<&'a BuiltComponent> {
+    let mut fields = Vec::new();
+    let mut stack: Vec<&str> = form.children.iter().rev().map(String::as_str).collect();
+    while let Some(id) = stack.pop() {
+        let Some(child) = components.get(id) else {
+            continue;
+        };
+        if child.component_type == "form" {
+            continue;
+        }
+        if FIELD_TYPES.contains(&child.component_type.as_str())
+            && child.properties.contains_key("name")
+        {
+            fields.push(child);
+        }
+        stack.extend(child.children.iter().rev().map(String::as_str));
+    }
+    fields
+}
+
+/// Renders a form: its children stacked vertically, then the submit button.
+#[derive(IntoElement)]
+pub struct Form {
+    source: BuiltComponent,
+    children: Vec<AnyElement>,
+    on_submit: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>>,
+}
+
+impl Form {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            children: Vec::new(),
+            on_submit: None,
+        }
+    }
+
+    pub fn children(mut self, children: Vec<AnyElement>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Sets the submit button's click handler.
+    pub fn on_submit(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_submit = Some(Box::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for Form {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let label = props
+            .get("submit_label")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Submit")
+            .to_string();
+
+        let mut button = GpuiButton::new(SharedString::from(format!("{}-submit", self.source.id)))
+            .primary()
+            .label(label);
+        if let Some(handler) = self.on_submit {
+            button = button.on_click(handler);
+        }
+
+        v_flex()
+            .gap_t(Space::Md)
+            .children(self.children)
+            .child(h_flex().justify_end().child(button))
+    }
+}
+
+/// Wraps a field element with its inline error message.
+pub fn with_error(element: AnyElement, message: &str, cx: &App) -> AnyElement {
+    v_flex()
+        .gap_t(Space::Xs)
+        .child(element)
+        .child(
+            div()
+                .text_t(FontSize::Sm)
+                .text_color(cx.theme().colors.danger)
+                .child(message.to_string()),
+        )
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(ty: &str, props: &[(&str, Value)]) -> FieldSpec {
+        FieldSpec::from_component(&BuiltComponent::new("f", ty).with_props(props.iter().cloned()))
+            .unwrap()
+    }
+
+    fn no_custom(_: &str, _: &Value, _: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_spec_from_attributes() {
+        assert!(FieldSpec::from_component(&BuiltComponent::new("x", "input")).is_none());
+
+        let s = spec(
+            "input",
+            &[
+                ("name", Value::from("age")),
+                ("required", Value::Bool(true)),
+                ("min", Value::Integer(18)),
+                ("max_length", Value::from("3")),
+            ],
+        );
+        assert_eq!(s.name, "age");
+        assert!(s.required);
+        assert_eq!(s.rules.len(), 2);
+        assert!(s.is_numeric());
+
+        // A slider's range is not a rule.
+        let slider = spec(
+            "slider",
+            &[("name", Value::from("vol")), ("min", Value::Integer(0))],
+        );
+        assert!(slider.rules.is_empty());
+        let number = spec(
+            "number_input",
+            &[("name", Value::from("qty")), ("max", Value::Integer(9))],
+        );
+        assert!(number.rules.is_empty());
+    }
+
+    #[test]
+    fn test_validate_form_rules_and_coercion() {
+        let fields = vec![
+            (
+                spec(
+                    "input",
+                    &[
+                        ("name", Value::from("email")),
+                        ("required", Value::Bool(true)),
+                    ],
+                ),
+                Value::from("  "),
+            ),
+            (
+                spec(
+                    "input",
+                    &[("name", Value::from("age")), ("min", Value::Integer(18))],
+                ),
+                Value::from("42"),
+            ),
+            (
+                spec(
+                    "input",
+                    &[
+                        ("name", Value::from("code")),
+                        ("pattern", Value::from("^[A-Z]{3}$")),
+                        ("message", Value::from("Three capitals")),
+                    ],
+                ),
+                Value::from("abc"),
+            ),
+            (
+                spec(
+                    "checkbox",
+                    &[
+                        ("name", Value::from("terms")),
+                        ("required", Value::Bool(true)),
+                    ],
+                ),
+                Value::Bool(false),
+            ),
+        ];
+
+        let result = validate_form(fields, no_custom);
+        assert!(!result.is_valid());
+        assert_eq!(result.values.get("age"), Some(&Value::Integer(42)));
+        assert_eq!(
+            result.errors.get("email").map(String::as_str),
+            Some(REQUIRED_MESSAGE)
+        );
+        assert_eq!(
+            result.errors.get("code").map(String::as_str),
+            Some("Three capitals")
+        );
+        assert!(result.errors.contains_key("terms"));
+        assert!(!result.errors.contains_key("age"));
+    }
+
+    #[test]
+    fn test_validate_form_numeric_and_optional() {
+        let age = spec(
+            "input",
+            &[("name", Value::from("age")), ("min", Value::Integer(18))],
+        );
+        let result = validate_form(vec![(age.clone(), Value::from("ten"))], no_custom);
+        assert_eq!(
+            result.errors.get("age").map(String::as_str),
+            Some("Enter a number")
+        );
+        let result = validate_form(vec![(age.clone(), Value::from("12"))], no_custom);
+        assert_eq!(
+            result.errors.get("age").map(String::as_str),
+            Some("value 12 is less than minimum 18")
+        );
+
+        // Blank optional numeric field submits null without running rules.
+        let result = validate_form(vec![(age, Value::from(""))], no_custom);
+        assert!(result.is_valid());
+        assert_eq!(result.values.get("age"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_field_rules_use_decimal_bounds_and_char_lengths() {
+        let ratio = spec(
+            "input",
+            &[("name", Value::from("ratio")), ("min", Value::from("0.5"))],
+        );
+        assert_eq!(ratio.rules, vec![FieldRule::Min(0.5)]);
+        let result = validate_form(vec![(ratio.clone(), Value::from("0.25"))], no_custom);
+        assert_eq!(
+            result.errors.get("ratio").map(String::as_str),
+            Some("value 0.25 is less than minimum 0.5")
+        );
+        let result = validate_form(vec![(ratio, Value::from("0.75"))], no_custom);
+        assert!(result.is_valid());
+
+        // Four characters, eight bytes.
+        let name = spec(
+            "input",
+            &[
+                ("name", Value::from("name")),
+                ("max_length", Value::Integer(4)),
+            ],
+        );
+        let result = validate_form(vec![(name.clone(), Value::from("éééé"))], no_custom);
+        assert!(result.is_valid());
+        let result = validate_form(vec![(name, Value::from("ééééé"))], no_custom);
+        assert_eq!(
+            result.errors.get("name").map(String::as_str),
+            Some("length 5 is greater than maximum 4")
+        );
+    }
+
+    #[test]
+    fn test_custom_validator_sees_all_values() {
+        let password = spec("input", &[("name", Value::from("password"))]);
+        let confirm = spec(
+            "input",
+            &[
+                ("name", Value::from("confirm")),
+                ("validator", Value::from("checks::same")),
+            ],
+        );
+        let mut calls = Vec::new();
+        let result = validate_form(
+            vec![
+                (password, Value::from("hunter22")),
+                (confirm, Value::from("hunter2")),
+            ],
+            |validator, value, all| {
+                calls.push(validator.to_string());
+                if all.get("password") == Some(value) {
+                    Ok(())
+                } else {
+                    Err("Passwords differ".to_string())
+                }
+            },
+        );
+        assert_eq!(calls, vec!["checks::same".to_string()]);
+        assert_eq!(
+            result.errors.get("confirm").map(String::as_str),
+            Some("Passwords differ")
+        );
+    }
+
+    #[test]
+    fn test_collect_fields_skips_nested_forms() {
+        let components: HashMap<String, BuiltComponent> = [
+            BuiltComponent::new("form", "form").with_children(["a", "row", "inner"]),
+            BuiltComponent::new("a", "input").with_props([("name", Value::from("a"))]),
+            BuiltComponent::new("row", "stack").with_children(["b", "label"]),
+            BuiltComponent::new("b", "switch").with_props([("name", Value::from("b"))]),
+            BuiltComponent::new("label", "input"),
+            BuiltComponent::new("inner", "form").with_children(["c"]),
+            BuiltComponent::new("c", "input").with_props([("name", Value::from("c"))]),
+        ]
+        .into_iter()
+        .map(|c| (c.id.clone(), c))
+        .collect();
+
+        let ids: Vec<&str> = collect_fields(&components["form"], &components)
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,653 @@
+//! A CSS-grid-like **grid** container.
+//!
+//! `<grid>` lays its children out on explicit column and row tracks. Each
+//! track is a fixed size (`200px`, or a bare number), a fraction of the
+//! remaining space (`1fr`, `2.5fr`), or `auto` (sized to content); `repeat(n,
+//! track)` expands to `n` copies. Children are placed row-major into the next
+//! free slot, or explicitly with `row`/`column` (1-based) and spanned with
+//! `row-span`/`col-span`. Rows beyond the declared `rows` are `auto`. A child
+//! stretches across its cell's width; give it `flex="1"` to fill the height.
+//!
+//! # XML Configuration
+//!
+//! ```xml
+//! <grid columns="240px 1fr 1fr" rows="auto 1fr 1fr" gap="8" flex="1">
+//!   <label text="Line 3" col-span="3" />
+//!   <panel row-span="2"> <!-- sidebar --> </panel>
+//!   <line-chart id="temps" col-span="2" />
+//!   <table id="events" />
+//!   <table id="alarms" />
+//! </grid>
+//! ```
+//!
+//! # Layout model
+//!
+//! gpui's grid only offers equal `1fr` tracks, so the grid is rendered as
+//! nested flex bands instead: [`decompose`] repeatedly cuts the grid along
+//! track lines that no cell crosses, and each band becomes a flex item whose
+//! basis is its fixed tracks (plus inner gaps) and whose grow factor is its
+//! `fr` total. Fixed and `fr` tracks therefore line up across bands; an `auto`
+//! track is sized to the content of each band separately. A layout no cut can
+//! separate (a "pinwheel" of row and column spans) falls back to ignoring row
+//! spans.
+
+use gpui::*;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// One grid track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    /// Fixed size in pixels.
+    Px(f32),
+    /// Share of the space left after fixed and auto tracks.
+    Fr(f32),
+    /// Sized to content.
+    Auto,
+}
+
+impl Track {
+    fn parse(token: &str) -> Option<Self> {
+        let token = token.trim();
+        if token.eq_ignore_ascii_case("auto") {
+            return Some(Track::Auto);
+        }
+        if let Some(fr) = token.strip_suffix("fr") {
+            return fr.trim().parse().ok().map(Track::Fr);
+        }
+        let px = token.strip_suffix("px").unwrap_or(token);
+        px.trim().parse().ok().map(Track::Px)
+    }
+}
+
+/// Parses a track list (`"200px 1fr 2fr"`, `"repeat(3, 1fr) auto"`).
+/// Unparseable tokens are skipped.
+pub fn parse_tracks(spec: &str) -> Vec<Track> {
+    let mut tracks = Vec::new();
+    let mut rest = spec.trim();
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix("repeat(") {
+            let Some(end) = inner.find(')') else {
+                break;
+            };
+            if let Some((count, track)) = inner[..end].split_once(',') {
+                let count = count.trim().parse::<usize>().unwrap_or(0);
+                let track = parse_tracks(track);
+                for _ in 0..count {
+                    tracks.extend_from_slice(&track);
+                }
+            }
+            rest = inner[end + 1..].trim_start();
+            continue;
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if let Some(track) = Track::parse(&rest[..end]) {
+            tracks.push(track);
+        }
+        rest = rest[end..].trim_start();
+    }
+    tracks
+}
+
+/// Reads a `columns`/`rows` property: a track list string, or a bare number
+/// meaning that many `1fr` tracks.
+pub fn tracks_from_value(value: Option<&Value>) -> Vec<Track> {
+    match value {
+        Some(Value::String(spec)) => parse_tracks(spec),
+        Some(v) => match v.as_i64() {
+            Some(n) if n > 0 => vec![Track::Fr(1.0); n as usize],
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    }
+}
+
+/// Where a child asked to go: 1-based `row`/`column` from its attributes,
+/// plus spans.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CellRequest {
+    pub row: Option<usize>,
+    pub column: Option<usize>,
+    pub row_span: usize,
+    pub col_span: usize,
+}
+
+impl CellRequest {
+    /// Reads the placement attributes from a child's properties.
+    pub fn from_properties(props: &HashMap<String, Value>) -> Self {
+        let int = |key: &str| {
+            props
+                .get(key)
+                .and_then(|v| v.as_i64().or_else(|| v.as_str()?.trim().parse().ok()))
+                .filter(|n| *n > 0)
+                .map(|n| n as usize)
+        };
+        Self {
+            row: int("row"),
+            column: int("column"),
+            row_span: int("row_span").unwrap_or(1),
+            col_span: int("col_span").unwrap_or(1),
+        }
+    }
+}
+
+/// A placed cell, 0-based.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub row: usize,
+    pub col: usize,
+    pub row_span: usize,
+    pub col_span: usize,
+}
+
+impl Placement {
+    fn rows(&self) -> Range<usize> {
+        self.row..self.row + self.row_span
+    }
+
+    fn cols(&self) -> Range<usize> {
+        self.col..self.col + self.col_span
+    }
+}
+
+/// Slot occupancy while placing cells; rows grow on demand.
+struct Occupancy {
+    columns: usize,
+    taken: Vec<Vec<bool>>,
+}
+
+impl Occupancy {
+    fn is_free(&self, p: &Placement) -> bool {
+        p.rows().all(|r| {
+            p.cols()
+                .all(|c| !self.taken.get(r).map(|row| row[c]).unwrap_or(false))
+        })
+    }
+
+    fn take(&mut self, p: &Placement) {
+        while self.taken.len() < p.row + p.row_span {
+            self.taken.push(vec![false; self.columns]);
+        }
+        for r in p.rows() {
+            for c in p.cols() {
+                self.taken[r][c] = true;
+            }
+        }
+    }
+}
+
+/// Assigns every request a slot in a grid of `columns` columns. Fully
+/// explicit requests are placed first (one that overlaps an earlier cell is
+/// auto-placed instead); the rest fill the first free slot in row-major
+/// order, keeping a requested `row` or `column`. Spans are clamped to the
+/// column count. Returns the placements (in request order) and the number of
+/// rows used.
+pub fn place_cells(requests: &[CellRequest], columns: usize) -> (Vec<Placement>, usize) {
+    let columns = columns.max(1);
+    let mut occupancy = Occupancy {
+        columns,
+        taken: Vec::new(),
+    };
+    let mut placements: Vec<Option<Placement>> = vec![None; requests.len()];
+
+    let clamp = |req: &CellRequest| {
+        let col = req.column.map(|c| c.saturating_sub(1).min(columns - 1));
+        let col_span = req.col_span.min(columns - col.unwrap_or(0)).max(1);
+        (col, col_span, req.row_span.max(1))
+    };
+
+    for (ix, req) in requests.iter().enumerate() {
+        let (Some(row), (Some(col), col_span, row_span)) = (req.row, clamp(req)) else {
+            continue;
+        };
+        let p = Placement {
+            row: row.saturating_sub(1),
+            col,
+            row_span,
+            col_span,
+        };
+        if occupancy.is_free(&p) {
+            occupancy.take(&p);
+            placements[ix] = Some(p);
+        }
+    }
+
+    for (ix, req) in requests.iter().enumerate() {
+        if placements[ix].is_some() {
+            continue;
+        }
+        let (col, col_span, row_span) = clamp(req);
+        let fixed_row = req
+            .row
+            .filter(|_| req.column.is_none())
+            .map(|r| r.saturating_sub(1));
+        let mut row = fixed_row.unwrap_or(0);
+        let placed = loop {
+            let cols: Vec<usize> = match col {
+                Some(c) => vec![c],
+                None => (0..=columns - col_span).collect(),
+            };
+            let found = cols
+                .into_iter()
+                .map(|col| Placement {
+                    row,
+                    col,
+                    row_span,
+                    col_span,
+                })
+                .find(|p| occupancy.is_free(p));
+            if let Some(p) = found {
+                break p;
+            }
+            // A requested row with no room overflows to the next free one.
+            row += 1;
+        };
+        occupancy.take(&placed);
+        placements[ix] = Some(placed);
+    }
+
+    let rows = occupancy.taken.len();
+    (placements.into_iter().flatten().collect(), rows)
+}
+
+/// The axis a band split runs along.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    /// Bands stacked top to bottom (cut between rows).
+    Rows,
+    /// Bands side by side (cut between columns).
+    Columns,
+}
+
+/// A grid region: one cell, nothing, or bands split along an axis.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridNode {
+    /// The child at this index fills the region.
+    Cell(usize),
+    Empty,
+    /// Bands covering consecutive track ranges of `axis`.
+    Split {
+        axis: Axis,
+        bands: Vec<(Range<usize>, GridNode)>,
+    },
+}
+
+/// Decomposes placed cells into nested bands (see the module docs).
+pub fn decompose(placements: &[Placement], rows: usize, cols: usize) -> GridNode {
+    let cells: Vec<(usize, Placement)> = placements.iter().copied().enumerate().collect();
+    decompose_region(cells, 0..rows, 0..cols)
+}
+
+fn decompose_region(
+    cells: Vec<(usize, Placement)>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+) -> GridNode {
+    match cells.as_slice() {
+        [] => return GridNode::Empty,
+        [(ix, p)] if p.rows() == rows && p.cols() == cols => return GridNode::Cell(*ix),
+        _ => {}
+    }
+
+    for axis in [Axis::Rows, Axis::Columns] {
+        let (range, span_of): (_, fn(&Placement) -> Range<usize>) = match axis {
+            Axis::Rows => (rows.clone(), Placement::rows),
+            Axis::Columns => (cols.clone(), Placement::cols),
+        };
+        let cuts: Vec<usize> = (range.start + 1..range.end)
+            .filter(|k| {
+                cells.iter().all(|(_, p)| {
+                    let span = span_of(p);
+                    !(span.start < *k && *k < span.end)
+                })
+            })
+            .collect();
+        if cuts.is_empty() {
+            continue;
+        }
+
+        let mut bounds = vec![range.start];
+        bounds.extend(cuts);
+        bounds.push(range.end);
+        let bands = bounds
+            .windows(2)
+            .map(|w| {
+                let band = w[0]..w[1];
+                let inside = cells
+                    .iter()
+                    .filter(|(_, p)| band.contains(&span_of(p).start))
+                    .copied()
+                    .collect();
+                let node = match axis {
+                    Axis::Rows => decompose_region(inside, band.clone(), cols.clone()),
+                    Axis::Columns => decompose_region(inside, rows.clone(), band.clone()),
+                };
+                (band, node)
+            })
+            .collect();
+        return GridNode::Split { axis, bands };
+    }
+
+    // No clean cut: drop row spans so every row boundary becomes one.
+    tracing::debug!("grid layout has interlocking spans; ignoring row spans");
+    let flattened = cells
+        .into_iter()
+        .map(|(ix, p)| (ix, Placement { row_span: 1, ..p }))
+        .collect();
+    decompose_region(flattened, rows, cols)
+}
+
+/// The flex sizing of a band covering several tracks.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BandSize {
+    /// Fixed tracks plus the gaps between the band's tracks.
+    pub px: f32,
+    /// Sum of `fr` tracks (the flex grow factor).
+    pub fr: f32,
+    /// Whether any track is `auto`.
+    pub auto: bool,
+}
+
+/// Sums the tracks in `range` (missing tracks count as `auto`).
+pub fn band_size(tracks: &[Track], range: Range<usize>, gap: f32) -> BandSize {
+    let mut size = BandSize {
+        px: gap * range.len().saturating_sub(1) as f32,
+        ..Default::default()
+    };
+    for ix in range {
+        match tracks.get(ix).copied().unwrap_or(Track::Auto) {
+            Track::Px(px) => size.px += px,
+            Track::Fr(fr) => size.fr += fr,
+            Track::Auto => size.auto = true,
+        }
+    }
+    size
+}
+
+/// Applies a band's size along `axis` to its wrapper.
+fn size_band(mut band: Div, size: BandSize, axis: Axis) -> Div {
+    if size.fr > 0.0 {
+        band.style().flex_grow = Some(size.fr);
+        band.style().flex_shrink = Some(1.0);
+        if !size.auto {
+            band = band.flex_basis(px(size.px));
+        }
+        match axis {
+            Axis::Rows => band.min_h(px(0.)),
+            Axis::Columns => band.min_w(px(0.)),
+        }
+    } else if !size.auto {
+        match axis {
+            Axis::Rows => band.h(px(size.px)).flex_shrink_0(),
+            Axis::Columns => band.w(px(size.px)).flex_shrink_0(),
+        }
+    } else {
+        band.flex_shrink_0()
+    }
+}
+
+/// Renders a decomposed grid, taking each cell's element out of `children`.
+fn render_node(
+    node: GridNode,
+    children: &mut [Option<AnyElement>],
+    columns: &[Track],
+    rows: &[Track],
+    gap: f32,
+) -> AnyElement {
+    match node {
+        GridNode::Cell(ix) => children[ix]
+            .take()
+            .unwrap_or_else(|| div().into_any_element()),
+        GridNode::Empty => div().into_any_element(),
+        GridNode::Split { axis, bands } => {
+            let tracks = match axis {
+                Axis::Rows => rows,
+                Axis::Columns => columns,
+            };
+            let mut container = div()
+                .flex()
+                .flex_1()
+                .min_w(px(0.))
+                .min_h(px(0.))
+                .gap(px(gap));
+            container = match axis {
+                Axis::Rows => container.flex_col(),
+                Axis::Columns => container.flex_row(),
+            };
+            for (range, child) in bands {
+                let size = band_size(tracks, range, gap);
+                let band = div()
+                    .flex()
+                    .flex_col()
+                    .child(render_node(child, children, columns, rows, gap));
+                container = container.child(size_band(band, size, axis));
+            }
+            container.into_any_element()
+        }
+    }
+}
+
+/// Renders children on grid tracks; see the module docs.
+#[derive(IntoElement)]
+pub struct Grid {
+    source: BuiltComponent,
+    cells: Vec<(CellRequest, AnyElement)>,
+}
+
+impl Grid {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            cells: Vec::new(),
+        }
+    }
+
+    /// Sets the children with their placement requests.
+    pub fn cells(mut self, cells: Vec<(CellRequest, AnyElement)>) -> Self {
+        self.cells = cells;
+        self
+    }
+}
+
+impl RenderOnce for Grid {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let mut columns = tracks_from_value(props.get("columns"));
+        if columns.is_empty() {
+            columns.push(Track::Fr(1.0));
+        }
+        let rows = tracks_from_value(props.get("rows"));
+        let gap = props.get("gap").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+
+        let (requests, elements): (Vec<_>, Vec<_>) = self.cells.into_iter().unzip();
+        let (placements, used_rows) = place_cells(&requests, columns.len());
+        let node = decompose(&placements, used_rows.max(rows.len()), columns.len());
+        let mut children: Vec<Option<AnyElement>> = elements.into_iter().map(Some).collect();
+
+        let mut root = div()
+            .id(SharedString::from(self.source.id.clone()))
+            .flex()
+            .flex_col();
+        if crate::components::container_grows(props) {
+            root = root.flex_1().min_h(px(0.));
+        }
+        root.child(render_node(node, &mut children, &columns, &rows, gap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(
+        row: Option<usize>,
+        column: Option<usize>,
+        row_span: usize,
+        col_span: usize,
+    ) -> CellRequest {
+        CellRequest {
+            row,
+            column,
+            row_span,
+            col_span,
+        }
+    }
+
+    fn auto() -> CellRequest {
+        req(None, None, 1, 1)
+    }
+
+    #[test]
+    fn test_parse_tracks() {
+        assert_eq!(
+            parse_tracks("200px 1fr  2.5fr auto 40"),
+            vec![
+                Track::Px(200.0),
+                Track::Fr(1.0),
+                Track::Fr(2.5),
+                Track::Auto,
+                Track::Px(40.0)
+            ]
+        );
+        assert_eq!(
+            parse_tracks("repeat(2, 1fr) auto"),
+            vec![Track::Fr(1.0), Track::Fr(1.0), Track::Auto]
+        );
+        assert_eq!(parse_tracks("wide 1fr"), vec![Track::Fr(1.0)]);
+        assert_eq!(
+            tracks_from_value(Some(&Value::Integer(3))),
+            vec![Track::Fr(1.0); 3]
+        );
+    }
+
+    #[test]
+    fn test_auto_placement_wraps_and_spans() {
+        let (placed, rows) = place_cells(&[req(None, None, 1, 3), auto(), auto(), auto()], 3);
+        assert_eq!(rows, 2);
+        assert_eq!(
+            placed[0],
+            Placement {
+                row: 0,
+                col: 0,
+                row_span: 1,
+                col_span: 3
+            }
+        );
+        assert_eq!((placed[1].row, placed[1].col), (1, 0));
+        assert_eq!((placed[3].row, placed[3].col), (1, 2));
+    }
+
+    #[test]
+    fn test_explicit_placement_first_and_overlap_demoted() {
+        // The explicit cell claims (1,2) before the auto cells flow around it.
+        let (placed, _) = place_cells(
+            &[
+                auto(),
+                auto(),
+                req(Some(1), Some(2), 2, 1),
+                req(Some(2), Some(2), 1, 1),
+            ],
+            2,
+        );
+        assert_eq!((placed[2].row, placed[2].col), (0, 1));
+        assert_eq!((placed[0].row, placed[0].col), (0, 0));
+        assert_eq!((placed[1].row, placed[1].col), (1, 0));
+        // (2,2) overlaps the row-spanning cell, so it is auto-placed in column 2.
+        assert_eq!((placed[3].row, placed[3].col), (2, 1));
+    }
+
+    #[test]
+    fn test_decompose_sidebar_layout() {
+        // Header across both columns, sidebar spanning two rows, two panes.
+        let (placed, rows) = place_cells(
+            &[req(None, None, 1, 2), req(None, None, 2, 1), auto(), auto()],
+            2,
+        );
+        let node = decompose(&placed, rows, 2);
+        assert_eq!(
+            node,
+            GridNode::Split {
+                axis: Axis::Rows,
+                bands: vec![
+                    (0..1, GridNode::Cell(0)),
+                    (
+                        1..3,
+                        GridNode::Split {
+                            axis: Axis::Columns,
+                            bands: vec![
+                                (0..1, GridNode::Cell(1)),
+                                (
+                                    1..2,
+                                    GridNode::Split {
+                                        axis: Axis::Rows,
+                                        bands: vec![
+                                            (1..2, GridNode::Cell(2)),
+                                            (2..3, GridNode::Cell(3)),
+                                        ],
+                                    }
+                                ),
+                            ],
+                        }
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_decompose_pinwheel_falls_back() {
+        // Four cells interlocking around a centre slot: no clean cut exists.
+        let placed = [
+            Placement {
+                row: 0,
+                col: 0,
+                row_span: 1,
+                col_span: 2,
+            },
+            Placement {
+                row: 0,
+                col: 2,
+                row_span: 2,
+                col_span: 1,
+            },
+            Placement {
+                row: 2,
+                col: 1,
+                row_span: 1,
+                col_span: 2,
+            },
+            Placement {
+                row: 1,
+                col: 0,
+                row_span: 2,
+                col_span: 1,
+            },
+        ];
+        let GridNode::Split { axis, bands } = decompose(&placed, 3, 3) else {
+            panic!("expected a split");
+        };
+        assert_eq!(axis, Axis::Rows);
+        assert_eq!(bands.len(), 3);
+    }
+
+    #[test]
+    fn test_band_size() {
+        let tracks = [
+            Track::Px(100.0),
+            Track::Fr(1.0),
+            Track::Fr(2.0),
+            Track::Auto,
+        ];
+        assert_eq!(
+            band_size(&tracks, 0..3, 8.0),
+            BandSize {
+                px: 116.0,
+                fr: 3.0,
+                auto: false
+            }
+        );
+        assert!(band_size(&tracks, 3..5, 0.0).auto);
+    }
+}
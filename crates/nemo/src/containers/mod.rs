@@ -12,9 +12,27 @@
 //! [`Router`] is a lower-level, chrome-free switching primitive: it renders one
 //! of its `<route>` children by URL-style path, with history, params, and
 //! lifecycle hooks. See [`router`].
+//!
+//! [`Grid`] places children on CSS-grid-like tracks and [`Split`] divides its
+//! space between panes with draggable dividers. See [`grid`] and [`split`].
+//!
+//! [`DropTarget`] receives dragged items and OS file drops, and [`Draggable`]
+//! lets `draggable="true"` children be reordered among their siblings. See
+//! [`drop_target`].
+//!
+//! [`Form`] collects and validates the values of its named input fields and
+//! submits them as one object. See [`form`].
 
 mod app_shell;
+pub mod drop_target;
+pub mod form;
+pub mod grid;
 pub mod router;
+pub mod split;
 
 pub use app_shell::AppShell;
+pub use drop_target::{Draggable, DropTarget};
+pub use form::Form;
+pub use grid::Grid;
 pub use router::{NavLink, Router};
+pub use split::Split;
@@ -0,0 +1,344 @@
+//! A resizable **split** container.
+//!
+//! `<split>` lays its children out as panes side by side (`direction=
+//! "horizontal"`, the default) or stacked (`"vertical"`), separated by
+//! dividers the user can drag. Pane sizes are fractions of the space left
+//! after the dividers; `sizes` sets them (`"30,70"`, percentages or any
+//! weights) and dragging writes them back as percentages, so a `two_way`
+//! binding to a `state.*` path keeps them across restarts.
+//!
+//! # XML Configuration
+//!
+//! ```xml
+//! <split id="main" direction="horizontal" sizes="25,75" min-size="120" flex="1">
+//!   <tree id="files" />
+//!   <split direction="vertical" sizes="60,40">
+//!     <code-editor id="editor" />
+//!     <table id="log" />
+//!   </split>
+//!   <binding source="state.layout.main" target="sizes" mode="two_way" />
+//! </split>
+//! ```
+//!
+//! # Properties
+//!
+//! | Property | Type | Description |
+//! |----------|------|-------------|
+//! | `direction` | string | `"horizontal"` (panes side by side, default) or `"vertical"` |
+//! | `sizes` | string/array | Pane sizes as weights (`"30,70"` or `[30, 70]`); equal by default |
+//! | `min-size` | int | Smallest a pane can be dragged to, in pixels (default: 40) |
+//! | `on-resize` | string | Handler called after a drag with the new sizes as JSON |
+
+use gpui::*;
+use gpui_component::ActiveTheme;
+use nemo_config::Value;
+use nemo_layout::BuiltComponent;
+use std::sync::{Arc, Mutex};
+
+use crate::runtime::NemoRuntime;
+
+/// Divider thickness in pixels.
+pub const DIVIDER_SIZE: f32 = 4.0;
+
+/// Default smallest pane size in pixels.
+pub const DEFAULT_MIN_PANE_SIZE: f32 = 40.0;
+
+/// Parses a `sizes` property into `count` fractions summing to 1. Accepts a
+/// comma/space separated string (`"30,70"`, `"1 2"`, `"25%, 75%"`) or an
+/// array of numbers. Anything unusable — wrong count, non-positive weights —
+/// gives equal panes.
+pub fn parse_sizes(value: Option<&Value>, count: usize) -> Vec<f32> {
+    let equal = vec![1.0 / count.max(1) as f32; count];
+    let weights: Vec<f32> = match value {
+        Some(Value::String(s)) => s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .filter_map(|t| t.trim_end_matches('%').parse().ok())
+            .collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_f64().map(|f| f as f32))
+            .collect(),
+        _ => return equal,
+    };
+    let total: f32 = weights.iter().sum();
+    if weights.len() != count || weights.iter().any(|w| *w <= 0.0) || total <= 0.0 {
+        return equal;
+    }
+    weights.iter().map(|w| w / total).collect()
+}
+
+/// Converts fractions to the `sizes` value written back after a drag:
+/// percentages rounded to one decimal.
+pub fn sizes_to_value(sizes: &[f32]) -> Value {
+    Value::Array(
+        sizes
+            .iter()
+            .map(|f| Value::Float((*f as f64 * 1000.0).round() / 10.0))
+            .collect(),
+    )
+}
+
+/// Moves the divider after pane `divider` to `offset` pixels from the start
+/// of a container `total` pixels long, trading space only between the two
+/// panes it separates. Each keeps at least `min_size` pixels where possible.
+pub fn resize(sizes: &[f32], divider: usize, offset: f32, total: f32, min_size: f32) -> Vec<f32> {
+    let mut sizes = sizes.to_vec();
+    if divider + 1 >= sizes.len() {
+        return sizes;
+    }
+    let content = total - DIVIDER_SIZE * (sizes.len() - 1) as f32;
+    if content <= 0.0 {
+        return sizes;
+    }
+
+    let start: f32 = sizes[..divider].iter().sum();
+    let pair = sizes[divider] + sizes[divider + 1];
+    // Position of the divider's centre in pane-content coordinates.
+    let position = (offset - DIVIDER_SIZE * (divider as f32 + 0.5)) / content;
+    let min = (min_size / content).min(pair / 2.0);
+    let boundary = position.clamp(start + min, start + pair - min);
+
+    sizes[divider] = boundary - start;
+    sizes[divider + 1] = pair - sizes[divider];
+    sizes
+}
+
+/// Drag payload for a split divider; also renders the (invisible) drag view.
+#[derive(Clone)]
+pub struct DividerDrag {
+    split_id: String,
+    divider: usize,
+}
+
+impl Render for DividerDrag {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// Renders panes with draggable dividers between them.
+#[derive(IntoElement)]
+pub struct Split {
+    source: BuiltComponent,
+    children: Vec<AnyElement>,
+    sizes: Arc<Mutex<Vec<f32>>>,
+    runtime: Option<Arc<NemoRuntime>>,
+    entity_id: Option<EntityId>,
+}
+
+impl Split {
+    pub fn new(source: BuiltComponent) -> Self {
+        Self {
+            source,
+            children: Vec::new(),
+            sizes: Arc::new(Mutex::new(Vec::new())),
+            runtime: None,
+            entity_id: None,
+        }
+    }
+
+    pub fn children(mut self, children: Vec<AnyElement>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Shared pane fractions, updated while dragging.
+    pub fn sizes(mut self, sizes: Arc<Mutex<Vec<f32>>>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    pub fn runtime(mut self, runtime: Arc<NemoRuntime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: EntityId) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
+}
+
+impl RenderOnce for Split {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let props = &self.source.properties;
+        let horizontal = props.get("direction").and_then(|v| v.as_str()) != Some("vertical");
+        let min_size = props
+            .get("min_size")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+            .unwrap_or(DEFAULT_MIN_PANE_SIZE);
+        let split_id = self.source.id.clone();
+        let sizes = self.sizes.lock().unwrap().clone();
+        let pane_count = self.children.len();
+        let divider_color = cx.theme().colors.border;
+        let divider_hover = cx.theme().colors.accent;
+
+        let mut container = div()
+            .id(SharedString::from(split_id.clone()))
+            .flex()
+            .size_full()
+            .min_w(px(0.))
+            .min_h(px(0.));
+        container = if horizontal {
+            container.flex_row()
+        } else {
+            container.flex_col()
+        };
+        if crate::components::container_grows(props) {
+            container = container.flex_1();
+        }
+
+        for (ix, child) in self.children.into_iter().enumerate() {
+            if ix > 0 {
+                let drag = DividerDrag {
+                    split_id: split_id.clone(),
+                    divider: ix - 1,
+                };
+                let divider = div()
+                    .id(SharedString::from(format!(
+                        "{}-divider-{}",
+                        split_id,
+                        ix - 1
+                    )))
+                    .flex_shrink_0()
+                    .bg(divider_color)
+                    .hover(|s| s.bg(divider_hover))
+                    .on_drag(drag, |drag, _offset, _window, cx| cx.new(|_| drag.clone()));
+                container = container.child(if horizontal {
+                    divider.w(px(DIVIDER_SIZE)).h_full().cursor_col_resize()
+                } else {
+                    divider.h(px(DIVIDER_SIZE)).w_full().cursor_row_resize()
+                });
+            }
+
+            let mut pane = div()
+                .flex()
+                .flex_col()
+                .flex_basis(px(0.))
+                .min_w(px(0.))
+                .min_h(px(0.))
+                .overflow_hidden()
+                .child(child);
+            pane.style().flex_grow = Some(sizes.get(ix).copied().unwrap_or(1.0));
+            pane.style().flex_shrink = Some(1.0);
+            container = container.child(pane);
+        }
+
+        // Dragging updates the shared fractions and repaints; dropping writes
+        // them back to `sizes` and fires `on-resize`.
+        let (Some(runtime), Some(entity_id)) = (self.runtime, self.entity_id) else {
+            return container;
+        };
+        let move_sizes = Arc::clone(&self.sizes);
+        let move_id = split_id.clone();
+        let drop_sizes = Arc::clone(&self.sizes);
+        let resize_handler = self.source.handlers.get("resize").cloned();
+
+        container
+            .on_drag_move::<DividerDrag>(move |event, _window, cx| {
+                let drag = event.drag(cx).clone();
+                if drag.split_id != move_id {
+                    return;
+                }
+                let (offset, total) = if horizontal {
+                    (
+                        event.event.position.x - event.bounds.origin.x,
+                        event.bounds.size.width,
+                    )
+                } else {
+                    (
+                        event.event.position.y - event.bounds.origin.y,
+                        event.bounds.size.height,
+                    )
+                };
+                let mut sizes = move_sizes.lock().unwrap();
+                if sizes.len() != pane_count {
+                    return;
+                }
+                *sizes = resize(
+                    &sizes,
+                    drag.divider,
+                    offset.as_f32(),
+                    total.as_f32(),
+                    min_size,
+                );
+                cx.notify(entity_id);
+            })
+            .on_drop::<DividerDrag>(move |drag, _window, cx| {
+                if drag.split_id != split_id {
+                    return;
+                }
+                let value = sizes_to_value(&drop_sizes.lock().unwrap());
+                runtime.write_back_property(&split_id, "sizes", value.clone());
+                if let Some(handler) = &resize_handler {
+                    let data = serde_json::to_string(&value).unwrap_or_default();
+                    runtime.call_handler(handler, &split_id, &data);
+                }
+                cx.notify(entity_id);
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: &[f32], b: &[f32]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4)
+    }
+
+    #[test]
+    fn test_parse_sizes() {
+        let s = |v: &str| parse_sizes(Some(&Value::String(v.into())), 2);
+        assert!(approx(&s("30,70"), &[0.3, 0.7]));
+        assert!(approx(&s("1 3"), &[0.25, 0.75]));
+        assert!(approx(&s("25%, 75%"), &[0.25, 0.75]));
+        // Wrong count or bad weights fall back to equal panes.
+        assert!(approx(&s("10,20,70"), &[0.5, 0.5]));
+        assert!(approx(&s("0,100"), &[0.5, 0.5]));
+        assert!(approx(&parse_sizes(None, 4), &[0.25; 4]));
+
+        let array = Value::Array(vec![Value::Integer(40), Value::Float(60.0)]);
+        assert!(approx(&parse_sizes(Some(&array), 2), &[0.4, 0.6]));
+    }
+
+    #[test]
+    fn test_sizes_to_value_round_trips() {
+        let value = sizes_to_value(&[1.0 / 3.0, 2.0 / 3.0]);
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Float(33.3), Value::Float(66.7)])
+        );
+        assert!(approx(&parse_sizes(Some(&value), 2), &[0.333, 0.667]));
+    }
+
+    #[test]
+    fn test_resize_moves_only_adjacent_panes() {
+        // Three panes sharing 400px of content between two dividers.
+        let sizes = [0.25, 0.25, 0.5];
+        let total = 400.0 + 2.0 * DIVIDER_SIZE;
+        // Drag the second divider to the middle of the content.
+        let offset = 200.0 + DIVIDER_SIZE * 1.5;
+        let resized = resize(&sizes, 1, offset, total, 0.0);
+        assert!(approx(&resized, &[0.25, 0.25, 0.5]));
+
+        let resized = resize(&sizes, 1, 300.0 + DIVIDER_SIZE * 1.5, total, 0.0);
+        assert!(approx(&resized, &[0.25, 0.5, 0.25]));
+    }
+
+    #[test]
+    fn test_resize_respects_min_size() {
+        let total = 200.0 + DIVIDER_SIZE;
+        let resized = resize(&[0.5, 0.5], 0, 0.0, total, 40.0);
+        assert!(approx(&resized, &[0.2, 0.8]));
+        let resized = resize(&[0.5, 0.5], 0, total, total, 40.0);
+        assert!(approx(&resized, &[0.8, 0.2]));
+        // Out-of-range divider is a no-op.
+        assert!(approx(
+            &resize(&[0.5, 0.5], 1, 10.0, total, 0.0),
+            &[0.5, 0.5]
+        ));
+    }
+}
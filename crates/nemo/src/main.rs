@@ -42,7 +42,7 @@ use project::ActiveProject;
 use window::get_window_options;
 use workspace::actions::{
     CloseProject, CloseSettings, OpenProject, OpenSettings, QuitApp, ReloadConfig,
-    ShowKeyboardShortcuts, ToggleDevPanel, ToggleTheme,
+    ShowKeyboardShortcuts, ToggleCommandPalette, ToggleDevPanel, ToggleTheme,
 };
 use workspace::utils::{apply_theme_from_runtime, create_runtime};
 use workspace::{FooterBar, HeaderBar, Workspace, WorkspaceArgs};
@@ -57,6 +57,8 @@ fn main() -> Result<()> {
         .with_max_level(args.log_level())
         .with_target(true)
         .with_thread_ids(true)
+        // Resolved `${secret.…}` values never reach the log.
+        .with_writer(|| nemo_config::RedactingWriter(std::io::stdout()))
         .finish();
 
     tracing::subscriber::set_global_default(subscriber)
@@ -70,6 +72,7 @@ fn main() -> Result<()> {
         Some(Command::Schema(schema_args)) => commands::schema::run(schema_args),
         Some(Command::Build(build_args)) => commands::build::run(build_args),
         Some(Command::Get(get_args)) => commands::get::run(get_args),
+        Some(Command::Secret(secret_args)) => commands::secret::run(secret_args),
         Some(Command::Screenshot(shot_args)) => dispatch_screenshot(shot_args),
         None => {
             let watch = args
@@ -152,6 +155,9 @@ pub(crate) fn run_app(mut args: Args, watch: Option<Duration>, dev_mode: bool) -
                 app_config: app_config.clone(),
                 strict: false,
                 format: args::ValidateFormat::Human,
+                profile: args.profile.iter().cloned().collect(),
+                all_profiles: false,
+                overrides: args.overrides.clone(),
             });
         }
 
@@ -161,6 +167,8 @@ pub(crate) fn run_app(mut args: Args, watch: Option<Duration>, dev_mode: bool) -
             for dir in &args.extension_dirs {
                 rt.add_extension_dir(dir)?;
             }
+            rt.config_loader.set_profile(args.profile.as_deref());
+            rt.config_loader.set_overrides(args.overrides.clone());
 
             info!("Loading configuration from: {:?}", app_config);
             rt.load_config()?;
@@ -186,6 +194,8 @@ pub(crate) fn run_app(mut args: Args, watch: Option<Duration>, dev_mode: bool) -
         ws_args: WorkspaceArgs {
             extension_dirs: args.extension_dirs.clone(),
             initial_route: args.route.clone(),
+            profile: args.profile.clone(),
+            overrides: args.overrides.clone(),
         },
         watch,
         size_override: None,
@@ -304,6 +314,7 @@ pub(crate) fn build_app_window(cx: &mut App, params: BootstrapParams) -> WindowH
     #[cfg(not(target_os = "macos"))]
     const PRIMARY: &str = "ctrl";
 
+    // Listed for users in `workspace::keymap::BUILTIN_SHORTCUTS`; keep in sync.
     cx.bind_keys([
         KeyBinding::new(&format!("{PRIMARY}-shift-r"), ReloadConfig, None),
         KeyBinding::new(&format!("{PRIMARY}-q"), QuitApp, None),
@@ -314,6 +325,7 @@ pub(crate) fn build_app_window(cx: &mut App, params: BootstrapParams) -> WindowH
         KeyBinding::new("escape", CloseSettings, None),
         KeyBinding::new("f10", ShowKeyboardShortcuts, None),
         KeyBinding::new(&format!("{PRIMARY}-shift-e"), ToggleDevPanel, None),
+        KeyBinding::new(&format!("{PRIMARY}-shift-p"), ToggleCommandPalette, None),
     ]);
 
     // Store workspace entity + main window ID for window close handler.
@@ -357,13 +369,20 @@ pub(crate) fn build_app_window(cx: &mut App, params: BootstrapParams) -> WindowH
     .detach();
 
     // If app_config provided, create runtime early so we can read window dimensions
+    let locale = nemo_config.lock().unwrap().app.locale.clone();
     let early_runtime = app_config_path.as_ref().and_then(|config_path| {
         match create_runtime(
             config_path,
             &ws_args.extension_dirs,
             ws_args.initial_route.as_deref(),
+            locale.as_deref(),
+            ws_args.profile.as_deref(),
+            &ws_args.overrides,
         ) {
-            Ok(rt) => Some(rt),
+            Ok(rt) => {
+                rt.set_error_overlay(dev_mode);
+                Some(rt)
+            }
             Err(e) => {
                 tracing::error!("Failed to load project: {}", e);
                 None
@@ -371,6 +390,9 @@ pub(crate) fn build_app_window(cx: &mut App, params: BootstrapParams) -> WindowH
         }
     });
 
+    // The app's `<keymap>` goes on top of the built-in bindings above.
+    workspace::keymap::bind_app_keymap(early_runtime.as_deref(), cx);
+
     // Read window dimensions from runtime config (if available)
     let (win_w, win_h, win_min_w, win_min_h) = if let Some(ref rt) = early_runtime {
         let w = rt
@@ -504,6 +526,7 @@ pub(crate) fn build_app_window(cx: &mut App, params: BootstrapParams) -> WindowH
                     _watcher: None,
                     dev_mode,
                     dev_panel_window: None,
+                    command_palette: None,
                 }
             });
 
@@ -1,12 +1,20 @@
 //! Nemo runtime - manages all subsystems.
 
 use anyhow::{Context, Result};
-use nemo_config::{ConfigurationLoader, SchemaRegistry, Value};
+use nemo_config::{
+    ConfigurationLoader, Messages, Overlay, SchemaRegistry, SourceError, SourceLocation, Value,
+    ViewportCondition, ORIGIN_KEY, SCRIPT_ORIGIN_KEY,
+};
 use nemo_data::{DataFlowEngine, DataRepository};
 use nemo_events::EventBus;
-use nemo_extension::{ExtensionManager, RhaiFeatures};
+use nemo_extension::{ExtensionError, ExtensionManager, RhaiFeatures};
 use nemo_integration::IntegrationGateway;
-use nemo_layout::{LayoutConfig, LayoutManager, LayoutNode, LayoutType};
+use nemo_layout::responsive;
+use nemo_layout::style_state::{StyleState, REFINABLE_PROPERTIES};
+use nemo_layout::{
+    concat_transform, prop_transform, BuiltComponent, LayoutConfig, LayoutManager, LayoutNode,
+    LayoutType,
+};
 use nemo_plugin_api::{LogLevel, PluginContext, PluginError, PluginValue};
 use nemo_registry::{register_all_builtins, ComponentRegistry};
 use std::collections::{HashMap, HashSet};
@@ -18,6 +26,40 @@ use std::sync::RwLock;
 use tokio::runtime::Runtime as TokioRuntime;
 use tracing::{debug, info, warn};
 
+use crate::components::export::{self, ExportFormat};
+
+/// A PNG export waiting for the window to capture it. Data exports are
+/// written straight away; only pixels need the UI, so `export_component` queues
+/// these for [`NemoRuntime::take_pending_exports`].
+#[derive(Debug, Clone)]
+pub(crate) struct ExportIntent {
+    pub component_id: String,
+    pub path: PathBuf,
+}
+
+/// A custom SFC event raised by `emit()`, waiting to reach its instance.
+#[derive(Debug, Clone)]
+struct EmitIntent {
+    /// The raising script (`sfc:<tag>`).
+    script_id: String,
+    /// The component whose handler raised it.
+    component_id: String,
+    event: String,
+    payload: Value,
+}
+
+/// SFC events raised by `emit()`, and the handler calls they can be raised
+/// from. An `emit()` runs inside `call_handler`, which holds the
+/// `extension_manager` write lock, so the event is queued and dispatched by
+/// [`NemoRuntime::call_handler`] once the raising handler returns.
+#[derive(Debug, Default)]
+pub(crate) struct EmitQueue {
+    /// `(script_id, component_id)` of each handler call in progress,
+    /// innermost last.
+    running: Mutex<Vec<(String, String)>>,
+    pending: Mutex<Vec<EmitIntent>>,
+}
+
 /// A pending navigation request.
 ///
 /// Navigation is **deferred**: `navigate()`/`back()`/`forward()` and
@@ -179,8 +221,40 @@ pub struct NemoRuntime {
     /// `set_roundness()` Rhai calls / plugins. Applied with `cx` by the App poll
     /// loop via [`Self::take_pending_roundness`] (needs the gpui `Theme`).
     roundness_intents: Arc<Mutex<Vec<String>>>,
+    /// Message catalogs from `<i18n>`, in the active locale. `None` when the
+    /// app declares none. Shared with scripts' `t()` through the context.
+    messages: Arc<RwLock<Option<Messages>>>,
+    /// Queued `set_locale()` calls from Rhai / plugins, applied outside the
+    /// extension lock by [`Self::apply_pending_locale`].
+    locale_intents: Arc<Mutex<Vec<String>>>,
+    /// Queued PNG exports from `export_component()`, captured by the App via
+    /// [`Self::take_pending_exports`] (needs the gpui `Window`).
+    export_intents: Arc<Mutex<Vec<ExportIntent>>>,
+    /// SFC `emit()` events, dispatched by [`Self::call_handler`].
+    emits: Arc<EmitQueue>,
+    /// User-driven property changes that found the layout locked, retried by
+    /// [`Self::apply_pending_data_updates`] so a sort or selection isn't lost.
+    pending_write_backs: Mutex<Vec<(String, String, Value)>>,
+    /// Drag reorders (container, child, index) that found the layout locked,
+    /// retried alongside [`Self::pending_write_backs`].
+    pending_reorders: Mutex<Vec<(String, String, usize)>>,
+    /// Writes `state.*` to disk when the app declares `<state persist="true">`.
+    /// Set by [`Self::setup_state`]; flushed one last time by [`Self::shutdown`].
+    state_persister: Mutex<Option<nemo_data::StatePersister>>,
+    /// Where each loaded script starts in the app's source, by script id:
+    /// line 1 of a `.rhai` file, or the `<script>` block of an SFC. Maps the
+    /// line a Rhai error reports back to a `file:line:col`.
+    script_origins: RwLock<HashMap<String, SourceLocation>>,
+    /// Recent errors from handlers, bindings and template expansion, newest
+    /// last, for the dev-mode overlay. See [`Self::report_error`].
+    runtime_errors: Mutex<Vec<SourceError>>,
+    /// Whether the App draws [`Self::runtime_errors`] over the layout.
+    error_overlay: AtomicBool,
 }
 
+/// How many runtime errors the dev overlay keeps; older ones are dropped.
+const MAX_RUNTIME_ERRORS: usize = 20;
+
 impl NemoRuntime {
     /// Creates a new Nemo runtime.
     pub fn new(config_path: &Path) -> Result<Self> {
@@ -202,6 +276,8 @@ impl NemoRuntime {
         let integration = Arc::new(IntegrationGateway::new());
         let schema_registry = Arc::new(SchemaRegistry::new());
         let config_loader = ConfigurationLoader::new(Arc::clone(&schema_registry));
+        // Record component origins so runtime errors can point at the source.
+        config_loader.set_origins(true);
         let config = Arc::new(RwLock::new(Value::Null));
 
         Ok(Self {
@@ -226,6 +302,16 @@ impl NemoRuntime {
             initial_route: Arc::new(Mutex::new(None)),
             pending_initial_enters: Arc::new(Mutex::new(Vec::new())),
             roundness_intents: Arc::new(Mutex::new(Vec::new())),
+            messages: Arc::new(RwLock::new(None)),
+            locale_intents: Arc::new(Mutex::new(Vec::new())),
+            export_intents: Arc::new(Mutex::new(Vec::new())),
+            emits: Arc::new(EmitQueue::default()),
+            pending_write_backs: Mutex::new(Vec::new()),
+            pending_reorders: Mutex::new(Vec::new()),
+            state_persister: Mutex::new(None),
+            script_origins: RwLock::new(HashMap::new()),
+            runtime_errors: Mutex::new(Vec::new()),
+            error_overlay: AtomicBool::new(false),
         })
     }
 
@@ -243,10 +329,21 @@ impl NemoRuntime {
     }
 
     /// Loads configuration from files.
+    ///
+    /// The entry is patched with the layers the loader was given — the
+    /// `--profile` overlays and `--set` overrides — plus the `overrides.xml`
+    /// settings overlay next to the entry, in that order of precedence below
+    /// `--set`.
     pub fn load_config(&self) -> Result<()> {
         info!("Loading configuration...");
 
         if self.config_path.exists() {
+            // The settings overlay (overrides.xml) is re-read on every load.
+            // Only the runtime applies it; build/validate/schema operate on
+            // the source entry so dist stays a faithful compile.
+            self.config_loader
+                .set_overlays(self.settings_overlay().into_iter().collect());
+
             // A `.json` config path is a built `dist/` tree (a serialized resolved
             // config `Value`), loaded back without re-parsing/resolving; anything
             // else is a source entry (app.nemo or app.xml). Scripts/themes
@@ -263,19 +360,30 @@ impl NemoRuntime {
                     .config_path
                     .parent()
                     .unwrap_or_else(|| std::path::Path::new("."));
-                self.config_loader
+                let mut loaded = self
+                    .config_loader
                     .load_from_dist(dir)
-                    .map_err(|e| anyhow::anyhow!("Failed to load built dist tree: {}", e))?
+                    .map_err(|e| anyhow::anyhow!("Failed to load built dist tree: {}", e))?;
+                self.config_loader
+                    .apply_overlays_to_dist(&mut loaded, &self.config_path)
+                    .map_err(|e| anyhow::anyhow!("Failed to apply overlays: {}", e))?;
+                loaded
             } else {
                 self.config_loader
                     .load(&self.config_path)
                     .map_err(|e| anyhow::anyhow!("Failed to load config file: {}", e))?
             };
 
-            // Apply the settings overlay (overrides.xml) if present next to the
-            // entry. Only the runtime applies it; build/validate/schema
-            // operate on the source entry so dist stays a faithful compile.
-            let loaded = self.apply_settings_overlay(loaded);
+            // Catalogs for `t('key')` transforms and scripts, in the locale
+            // `set_locale` picked before loading (or the app's default).
+            let messages = Messages::from_config(&loaded)
+                .map(|m| m.with_locale(self.config_loader.locale().as_deref()));
+            self.layout_manager
+                .write()
+                .expect("layout_manager lock poisoned")
+                .bindings_mut()
+                .set_messages(messages.clone());
+            *self.messages.write().expect("messages lock poisoned") = messages;
 
             {
                 let mut config = self.config.write().expect("config lock poisoned");
@@ -292,52 +400,28 @@ impl NemoRuntime {
         Ok(())
     }
 
-    /// Loads `overrides.xml` next to the config path (if present) and merges
-    /// its `app` key over the loaded config's `app` key (shallow merge: each
-    /// overlay `app` key overrides the config's). The overlay is a plain-XML
-    /// document written by `xml_edit::set_app_theme` to persist settings-UI
-    /// choices (theme name/mode) without mutating the source entry. Returns
-    /// `config` unchanged when the overlay is absent or malformed.
-    fn apply_settings_overlay(&self, mut config: Value) -> Value {
-        let Some(dir) = self.config_path.parent() else {
-            return config;
-        };
+    /// Reads `overrides.xml` next to the config path (if present) as an
+    /// overlay of its `app` key, merged over the entry's. The overlay is a
+    /// plain-XML document written by `xml_edit::set_app_theme` to persist
+    /// settings-UI choices (theme name/mode) without mutating the source
+    /// entry. Returns `None` when the overlay is absent or malformed.
+    fn settings_overlay(&self) -> Option<Overlay> {
+        let dir = self.config_path.parent()?;
         let overlay_path = dir.join(crate::workspace::xml_edit::OVERRIDES_FILE);
-        let Ok(overlay_content) = std::fs::read_to_string(&overlay_path) else {
-            return config; // No overlay — common case.
-        };
-        if overlay_content.trim().is_empty() {
-            return config;
+        if !overlay_path.is_file() {
+            return None; // No overlay — common case.
         }
-        let overlay = match self.config_loader.load_xml_string(
-            &overlay_content,
-            &overlay_path.display().to_string(),
-            Some(dir),
-        ) {
-            Ok(v) => v,
+        let overlay = match self.config_loader.read_overlay(&overlay_path) {
+            Ok(overlay) => overlay,
             Err(e) => {
                 warn!("Skipping malformed {}: {}", overlay_path.display(), e);
-                return config;
+                return None;
             }
         };
-        let Some(overlay_app) = overlay.get("app").cloned() else {
-            return config;
-        };
-        if let Value::Object(ref mut top) = config {
-            if let Some(config_app) = top.get_mut("app") {
-                if let (Some(config_obj), Some(overlay_obj)) =
-                    (config_app.as_object_mut(), overlay_app.as_object())
-                {
-                    for (k, v) in overlay_obj {
-                        config_obj.insert(k.clone(), v.clone());
-                    }
-                }
-            } else {
-                // Config has no `app` key; take the overlay's wholesale.
-                top.insert("app".to_string(), overlay_app);
-            }
-        }
-        config
+        let app = overlay.value.get("app")?.clone();
+        let mut value = indexmap::IndexMap::new();
+        value.insert("app".to_string(), app);
+        Some(Overlay::new(overlay.source, Value::Object(value)))
     }
 
     /// Initializes all subsystems.
@@ -428,6 +512,9 @@ impl NemoRuntime {
         // Load scripts from configuration
         self.load_scripts_from_config()?;
 
+        // Seed `state.*` (after scripts so the migrate hook is callable)
+        self.setup_state();
+
         // Apply layout from configuration
         self.apply_layout_from_config()?;
 
@@ -520,10 +607,15 @@ impl NemoRuntime {
                             let path = entry.path();
                             if path.extension().map(|e| e == "rhai").unwrap_or(false) {
                                 match ext.load_script(&path) {
-                                    Ok(id) => info!("Loaded script: {}", id),
-                                    Err(e) => {
-                                        tracing::warn!("Failed to load script {:?}: {}", path, e)
+                                    Ok(id) => {
+                                        info!("Loaded script: {}", id);
+                                        self.register_script_origin(&id, file_start(&path));
                                     }
+                                    Err(e) => self.report_error(locate_script_error(
+                                        format!("Failed to load script {:?}", path),
+                                        &e,
+                                        Some(&file_start(&path)),
+                                    )),
                                 }
                             }
                         }
@@ -553,10 +645,15 @@ impl NemoRuntime {
                                 .write()
                                 .expect("extension_manager lock poisoned");
                             match ext.load_script(&script_path) {
-                                Ok(id) => info!("Loaded script: {}", id),
-                                Err(e) => {
-                                    tracing::warn!("Failed to load script {:?}: {}", script_path, e)
+                                Ok(id) => {
+                                    info!("Loaded script: {}", id);
+                                    self.register_script_origin(&id, file_start(&script_path));
                                 }
+                                Err(e) => self.report_error(locate_script_error(
+                                    format!("Failed to load script {:?}", script_path),
+                                    &e,
+                                    Some(&file_start(&script_path)),
+                                )),
                             }
                         }
                     }
@@ -569,7 +666,7 @@ impl NemoRuntime {
         // instance is distinguished by the `component_id` its handlers receive.
         // The `sfc:` prefix keeps a single colon so `call_handler`'s first-`::`
         // split resolves `sfc:<tag>::<fn>` to (script_id=`sfc:<tag>`, fn).
-        let sfc_scripts: Vec<(String, String)> = {
+        let sfc_scripts: Vec<(String, String, Option<SourceLocation>)> = {
             let config = self.config.read().expect("config lock poisoned");
             config
                 .get("sfc")
@@ -577,10 +674,14 @@ impl NemoRuntime {
                 .map(|obj| {
                     obj.iter()
                         .filter_map(|(tag, def)| {
+                            let origin = def
+                                .get(SCRIPT_ORIGIN_KEY)
+                                .and_then(|v| v.as_str())
+                                .and_then(SourceLocation::parse);
                             def.get("script")
                                 .and_then(|v| v.as_str())
                                 .filter(|s| !s.trim().is_empty())
-                                .map(|s| (tag.clone(), s.to_string()))
+                                .map(|s| (tag.clone(), s.to_string(), origin))
                         })
                         .collect()
                 })
@@ -591,11 +692,20 @@ impl NemoRuntime {
                 .extension_manager
                 .write()
                 .expect("extension_manager lock poisoned");
-            for (tag, source) in sfc_scripts {
+            for (tag, source, origin) in sfc_scripts {
                 let id = format!("sfc:{}", tag);
                 match ext.load_script_source(&id, &source) {
-                    Ok(()) => info!("Loaded SFC script: {}", id),
-                    Err(e) => tracing::warn!("Failed to load SFC script {}: {}", id, e),
+                    Ok(()) => {
+                        info!("Loaded SFC script: {}", id);
+                        if let Some(origin) = origin {
+                            self.register_script_origin(&id, origin);
+                        }
+                    }
+                    Err(e) => self.report_error(locate_script_error(
+                        format!("Failed to load SFC script {}", id),
+                        &e,
+                        origin.as_ref(),
+                    )),
                 }
             }
         }
@@ -611,6 +721,10 @@ impl NemoRuntime {
             Arc::clone(&self.plugin_dirty_paths),
             Arc::clone(&self.nav_intents),
             Arc::clone(&self.roundness_intents),
+            Arc::clone(&self.messages),
+            Arc::clone(&self.locale_intents),
+            Arc::clone(&self.export_intents),
+            Arc::clone(&self.emits),
         ));
 
         {
@@ -694,38 +808,432 @@ impl NemoRuntime {
         Ok(())
     }
 
+    /// Logs a runtime error — with a snippet of its source line when it is
+    /// located — and keeps it for the dev-mode overlay. An error already
+    /// waiting there (a handler failing on every keystroke) isn't repeated.
+    /// Resolved secrets in the message are redacted first.
+    pub fn report_error(&self, mut error: SourceError) {
+        error.message = nemo_config::redact(&error.message).into_owned();
+        let mut errors = self.runtime_errors.lock().expect("runtime errors poisoned");
+        if errors.contains(&error) {
+            debug!("{}", error);
+            return;
+        }
+        tracing::error!("{}", error.render());
+        if errors.len() == MAX_RUNTIME_ERRORS {
+            errors.remove(0);
+        }
+        errors.push(error);
+    }
+
+    /// The runtime errors reported since the overlay was last cleared.
+    pub fn runtime_errors(&self) -> Vec<SourceError> {
+        self.runtime_errors
+            .lock()
+            .expect("runtime errors poisoned")
+            .clone()
+    }
+
+    /// Dismisses the reported runtime errors.
+    pub fn clear_runtime_errors(&self) {
+        self.runtime_errors
+            .lock()
+            .expect("runtime errors poisoned")
+            .clear();
+    }
+
+    /// Shows (dev mode) or hides the runtime error overlay.
+    pub fn set_error_overlay(&self, enabled: bool) {
+        self.error_overlay.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the App should draw [`Self::runtime_errors`] over the layout.
+    pub fn error_overlay(&self) -> bool {
+        self.error_overlay.load(Ordering::Relaxed)
+    }
+
+    /// Records where script `id` starts in the app's source.
+    fn register_script_origin(&self, id: &str, origin: SourceLocation) {
+        self.script_origins
+            .write()
+            .expect("script origins lock poisoned")
+            .insert(id.to_string(), origin);
+    }
+
+    /// The `file:line:col` of the component `id` was declared at, if known.
+    fn component_origin(&self, id: &str) -> Option<SourceLocation> {
+        let lm = self.layout_manager.try_read().ok()?;
+        lm.get_component(id)?.origin.clone()
+    }
+
+    /// Locates a failed script call: at the line of the script the error
+    /// names when the script's origin is known, otherwise at `fallback` (the
+    /// component whose event ran it).
+    fn script_error(
+        &self,
+        message: String,
+        err: &ExtensionError,
+        fallback: Option<&SourceLocation>,
+    ) -> SourceError {
+        let start = err.script_position().and_then(|(id, _, _)| {
+            self.script_origins
+                .read()
+                .expect("script origins lock poisoned")
+                .get(id)
+                .cloned()
+        });
+        match start {
+            Some(start) => locate_script_error(message, err, Some(&start)),
+            None => SourceError::new(format!("{}: {}", message, err)).or_at(fallback),
+        }
+    }
+
     /// Calls an event handler.
     ///
     /// Handler format: "script_id::function_name" or just "function_name" (uses default script)
     pub fn call_handler(&self, handler: &str, component_id: &str, event_data: &str) {
-        // Parse handler format: "script_id::function_name" or "function_name"
-        let (script_id, function_name) = if let Some(pos) = handler.find("::") {
-            (&handler[..pos], &handler[pos + 2..])
-        } else {
-            // Default to "handlers" script if no script specified
-            ("handlers", handler)
-        };
+        let (script_id, function_name) = split_handler(handler);
 
         debug!(
             "Calling handler: {}::{} for component {} with data: {}",
             script_id, function_name, component_id, event_data
         );
 
-        let mut ext = self
+        self.emits
+            .running
+            .lock()
+            .expect("emit queue poisoned")
+            .push((script_id.to_string(), component_id.to_string()));
+        let failure = {
+            let mut ext = self
+                .extension_manager
+                .write()
+                .expect("extension_manager lock poisoned");
+            match ext.call_script::<()>(
+                script_id,
+                function_name,
+                (component_id.to_string(), event_data.to_string()),
+            ) {
+                Ok(_) => {
+                    debug!(
+                        "Handler {}::{} executed successfully",
+                        script_id, function_name
+                    );
+                    None
+                }
+                Err(e) => Some(e),
+            }
+        };
+        if let Some(e) = failure {
+            self.report_error(self.script_error(
+                format!("Handler {}::{} failed", script_id, function_name),
+                &e,
+                self.component_origin(component_id).as_ref(),
+            ));
+        }
+        self.emits
+            .running
+            .lock()
+            .expect("emit queue poisoned")
+            .pop();
+        self.dispatch_pending_emits();
+    }
+
+    /// Delivers the events SFC scripts `emit()`ed during a handler call, now
+    /// that the extension lock is free. Each goes to the nearest enclosing
+    /// instance of the raising SFC (the emitting component or an ancestor
+    /// carrying its `sfc_instance` mark) and calls that instance's
+    /// `on-<event>` handler with the instance id and the payload: strings as
+    /// is, anything else as JSON. An event the instance doesn't handle is
+    /// dropped.
+    fn dispatch_pending_emits(&self) {
+        let pending = std::mem::take(&mut *self.emits.pending.lock().expect("emit queue poisoned"));
+        for intent in pending {
+            let tag = intent.script_id.trim_start_matches("sfc:");
+            let declared = self
+                .get_config(&format!("sfc.{}", tag))
+                .is_some_and(|def| sfc_emits(&def).contains(&intent.event));
+            if !declared {
+                tracing::warn!(
+                    "sfc '{}' emitted '{}', which its <emits> block does not declare",
+                    tag,
+                    intent.event
+                );
+                continue;
+            }
+            let target = {
+                let Ok(lm) = self.layout_manager.try_read() else {
+                    tracing::warn!("dropped '{}' event: layout is locked", intent.event);
+                    continue;
+                };
+                find_sfc_instance(&lm, &intent.component_id, tag).map(|instance| {
+                    (
+                        instance.id.clone(),
+                        instance
+                            .handlers
+                            .get(&format!("{}{}", EMIT_HANDLER_PREFIX, intent.event))
+                            .cloned(),
+                    )
+                })
+            };
+            match target {
+                Some((instance_id, Some(handler))) => {
+                    let data = match &intent.payload {
+                        Value::String(s) => s.clone(),
+                        Value::Null => String::new(),
+                        other => serde_json::to_string(other).unwrap_or_default(),
+                    };
+                    self.call_handler(&handler, &instance_id, &data);
+                }
+                Some((instance_id, None)) => debug!(
+                    "sfc instance '{}' does not handle '{}'",
+                    instance_id, intent.event
+                ),
+                None => tracing::warn!(
+                    "sfc '{}' emitted '{}' from '{}', which is not inside an instance of it",
+                    tag,
+                    intent.event,
+                    intent.component_id
+                ),
+            }
+        }
+    }
+
+    /// Runs a custom Rhai validator (`validator="script::fn"`) on a form
+    /// field, called as `fn(value, values)` with the whole form payload so
+    /// cross-field checks (password confirmation) work.
+    ///
+    /// `true`, `()`, and `""` pass; `false` fails with a generic message and a
+    /// non-empty string fails with that string. A validator that errors fails
+    /// closed, so a broken script never lets bad input through.
+    pub fn call_validator(
+        &self,
+        validator: &str,
+        value: &Value,
+        values: &Value,
+    ) -> Result<(), String> {
+        let (script_id, function_name) = split_handler(validator);
+        let result = self
             .extension_manager
             .write()
-            .expect("extension_manager lock poisoned");
-        match ext.call_script::<()>(
-            script_id,
-            function_name,
-            (component_id.to_string(), event_data.to_string()),
-        ) {
-            Ok(_) => debug!(
-                "Handler {}::{} executed successfully",
-                script_id, function_name
-            ),
-            Err(e) => tracing::warn!("Handler {}::{} failed: {}", script_id, function_name, e),
+            .expect("extension_manager lock poisoned")
+            .call_script_value(
+                script_id,
+                function_name,
+                vec![value_to_plugin_value(value), value_to_plugin_value(values)],
+            );
+        match result {
+            Ok(PluginValue::Bool(true) | PluginValue::Null) => Ok(()),
+            Ok(PluginValue::String(message)) if message.is_empty() => Ok(()),
+            Ok(PluginValue::String(message)) => Err(message),
+            Ok(_) => Err("Invalid value".to_string()),
+            Err(e) => {
+                self.report_error(self.script_error(
+                    format!("Validator {}::{} failed", script_id, function_name),
+                    &e,
+                    None,
+                ));
+                Err("Validation failed".to_string())
+            }
+        }
+    }
+
+    /// Writes a user-driven property change (a table sort, a row selection)
+    /// back to the component and through its `two_way` bindings into the
+    /// repository, so bound paths and other components observe it. A change
+    /// that finds the layout locked is retried on the next update pass.
+    pub fn write_back_property(&self, component_id: &str, property: &str, value: Value) {
+        let sources = match self.layout_manager.try_write() {
+            Ok(mut lm) => lm
+                .set_property_from_ui(component_id, property, value.clone())
+                .unwrap_or_default(),
+            Err(_) => {
+                debug!(
+                    "layout busy; retrying write-back of {}.{} next frame",
+                    component_id, property
+                );
+                self.pending_write_backs
+                    .lock()
+                    .expect("pending write-backs poisoned")
+                    .push((component_id.to_string(), property.to_string(), value));
+                self.data_dirty.store(true, Ordering::Release);
+                self.data_notify.notify_one();
+                return;
+            }
+        };
+        if sources.is_empty() {
+            return;
+        }
+
+        for source in sources {
+            let Ok(path) = nemo_data::DataPath::parse(&source) else {
+                continue;
+            };
+            match self.data_engine.repository.set(&path, value.clone()) {
+                Ok(()) => {
+                    if let Ok(mut paths) = self.plugin_dirty_paths.write() {
+                        paths.insert(source);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to write back {}: {}", source, e),
+            }
+        }
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
+    }
+
+    /// Inserts row `row_id` of `<virtual-list>` `list_id` into the layout
+    /// under the list, from the node built for its item
+    /// (`nemo_layout::virtual_list::row_node`). A row that is an SFC instance
+    /// gets its state, and the row's bindings are queued to receive their
+    /// current values. Failures are reported at the list; returns whether
+    /// the row was inserted.
+    pub fn insert_virtual_row(&self, list_id: &str, row_id: &str, node: &Value) -> bool {
+        let specs = {
+            let config = self.config.read().expect("config lock poisoned");
+            read_sfc_specs(&config)
+        };
+        let mut lm = self
+            .layout_manager
+            .write()
+            .expect("layout_manager lock poisoned");
+        if let Err(e) = lm.insert_tree(row_id, Some(list_id), node) {
+            let origin = lm.get_component(list_id).and_then(|c| c.origin.clone());
+            drop(lm);
+            self.report_error(
+                SourceError::new(format!("virtual-list row '{}' failed: {}", row_id, e))
+                    .or_at(origin.as_ref()),
+            );
+            return false;
+        }
+        let mut paths = if specs.values().any(|spec| !spec.state.is_empty()) {
+            adopt_sfc_instances(&mut lm, &specs, &self.data_engine.repository)
+        } else {
+            HashSet::new()
+        };
+        let mut queue = vec![row_id.to_string()];
+        while let Some(id) = queue.pop() {
+            paths.extend(
+                lm.bindings()
+                    .bindings_for_component(&id)
+                    .into_iter()
+                    .map(|b| b.source.clone()),
+            );
+            if let Some(component) = lm.get_component(&id) {
+                queue.extend(component.children.iter().cloned());
+            }
+        }
+        drop(lm);
+        if let Ok(mut dirty) = self.plugin_dirty_paths.write() {
+            dirty.extend(paths);
         }
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
+        true
+    }
+
+    /// Removes a row inserted by [`Self::insert_virtual_row`] once it has
+    /// scrolled far out of view or its item is gone.
+    pub fn remove_virtual_row(&self, row_id: &str) {
+        let mut lm = self
+            .layout_manager
+            .write()
+            .expect("layout_manager lock poisoned");
+        if let Err(e) = lm.remove_component(row_id) {
+            debug!("virtual-list row {} was already gone: {}", row_id, e);
+        }
+    }
+
+    /// Moves a dragged child to index `to` among its siblings and fires the
+    /// container's `on-reorder` with `{id, from, to, order}`. When the
+    /// container is a live-data `n:for`, the bound array is reordered the
+    /// same way and written back, so the new order lives in the data and
+    /// survives the list binding's next diff; items are matched to their
+    /// instances by key (or index), not by position among the children.
+    /// Such a container is the loop element itself, so without an
+    /// `on-reorder` of its own the enclosing container's fires instead. A
+    /// drop that finds the layout locked is retried on the next update pass.
+    pub fn reorder_child(&self, container_id: &str, child_id: &str, to: usize) {
+        let (from, to, order, reordered, handler) = match self.layout_manager.try_write() {
+            Ok(mut lm) => {
+                let Some((from, to)) = lm.move_child(container_id, child_id, to) else {
+                    return;
+                };
+                let Some(container) = lm.get_component(container_id) else {
+                    return;
+                };
+                let source = lm
+                    .list_bindings()
+                    .source_for(container_id)
+                    .map(str::to_string);
+                let reordered = source.as_ref().and_then(|source| {
+                    let path = nemo_data::DataPath::parse(source).ok()?;
+                    let Some(Value::Array(items)) = self.data_engine.repository.get(&path) else {
+                        return None;
+                    };
+                    let items = lm.list_bindings().reorder_items(
+                        container_id,
+                        &container.children,
+                        &items,
+                    )?;
+                    Some((source.clone(), path, items))
+                });
+                let handler = match container.handlers.get("reorder") {
+                    Some(handler) => Some((container_id.to_string(), handler.clone())),
+                    None => container
+                        .parent
+                        .as_deref()
+                        .filter(|_| source.is_some())
+                        .and_then(|parent| {
+                            let handler = lm.get_component(parent)?.handlers.get("reorder")?;
+                            Some((parent.to_string(), handler.clone()))
+                        }),
+                };
+                (from, to, container.children.clone(), reordered, handler)
+            }
+            Err(_) => {
+                debug!(
+                    "layout busy; retrying reorder of {} in {} next frame",
+                    child_id, container_id
+                );
+                self.pending_reorders
+                    .lock()
+                    .expect("pending reorders poisoned")
+                    .push((container_id.to_string(), child_id.to_string(), to));
+                self.data_dirty.store(true, Ordering::Release);
+                self.data_notify.notify_one();
+                return;
+            }
+        };
+        if from == to {
+            return;
+        }
+
+        if let Some((source, path, items)) = reordered {
+            match self.data_engine.repository.set(&path, Value::Array(items)) {
+                Ok(()) => {
+                    if let Ok(mut paths) = self.plugin_dirty_paths.write() {
+                        paths.insert(source);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to reorder {}: {}", source, e),
+            }
+        }
+
+        if let Some((owner, handler)) = handler {
+            let mut payload = indexmap::IndexMap::new();
+            payload.insert("id".to_string(), Value::String(child_id.to_string()));
+            payload.insert("from".to_string(), Value::Integer(from as i64));
+            payload.insert("to".to_string(), Value::Integer(to as i64));
+            payload.insert(
+                "order".to_string(),
+                Value::Array(order.into_iter().map(Value::String).collect()),
+            );
+            let data = serde_json::to_string(&Value::Object(payload)).unwrap_or_default();
+            self.call_handler(&handler, &owner, &data);
+        }
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
     }
 
     /// Parses and applies the layout configuration.
@@ -742,10 +1250,14 @@ impl NemoRuntime {
                 .collect()
         };
 
+        let mut expansion_errors = Vec::new();
         let layout_config = {
             let config = self.config.read().expect("config lock poisoned");
-            parse_layout_config(&config, &extra_templates)
+            parse_layout_config(&config, &extra_templates, &mut expansion_errors)
         };
+        for error in expansion_errors {
+            self.report_error(error);
+        }
 
         if let Some(layout_config) = layout_config {
             info!(
@@ -753,11 +1265,19 @@ impl NemoRuntime {
                 layout_config.root.children.len()
             );
 
-            self.layout_manager
-                .write()
-                .expect("layout_manager lock poisoned")
-                .apply_layout(layout_config)
-                .map_err(|e| anyhow::anyhow!("Failed to apply layout: {}", e))?;
+            {
+                let mut layout_manager = self
+                    .layout_manager
+                    .write()
+                    .expect("layout_manager lock poisoned");
+                layout_manager
+                    .apply_layout(layout_config)
+                    .map_err(|e| anyhow::anyhow!("Failed to apply layout: {}", e))?;
+                // SFC templates keep their `${t(…)}` attributes verbatim, and a
+                // dist tree was translated at build time; render both in the
+                // active locale.
+                self.localize_components(&mut layout_manager);
+            }
 
             let component_count = self
                 .layout_manager
@@ -765,6 +1285,7 @@ impl NemoRuntime {
                 .expect("layout_manager lock poisoned")
                 .component_count();
             info!("Layout applied with {} components", component_count);
+            self.adopt_sfc_instances();
         } else {
             debug!("No layout configuration found, using default view");
         }
@@ -772,6 +1293,178 @@ impl NemoRuntime {
         Ok(())
     }
 
+    /// Points the SFC state references of components built since the last
+    /// call at their instances and seeds the state of new instances (see
+    /// [`adopt_sfc_instances`]), marking the paths dirty so the bindings pick
+    /// up the values.
+    fn adopt_sfc_instances(&self) {
+        let specs = {
+            let config = self.config.read().expect("config lock poisoned");
+            read_sfc_specs(&config)
+        };
+        if specs.values().all(|spec| spec.state.is_empty()) {
+            return;
+        }
+        let paths = {
+            let mut lm = self
+                .layout_manager
+                .write()
+                .expect("layout_manager lock poisoned");
+            adopt_sfc_instances(&mut lm, &specs, &self.data_engine.repository)
+        };
+        if paths.is_empty() {
+            return;
+        }
+        if let Ok(mut dirty) = self.plugin_dirty_paths.write() {
+            dirty.extend(paths);
+        }
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
+    }
+
+    /// Seeds the `state.*` namespace from the `<state>` block.
+    ///
+    /// Field defaults are applied first; with `persist="true"` the values saved
+    /// by the previous run are merged over them. When the saved version differs
+    /// from the declared `version`, the `migrate` hook (default `migrate`, in
+    /// the `handlers` script) is called as `migrate(old_version, value)` and its
+    /// return value replaces the saved values. Failures are logged and never
+    /// abort startup.
+    fn setup_state(&self) {
+        let state_config = {
+            let config = self.config.read().expect("config lock poisoned");
+            config.get("state").cloned()
+        };
+        let Some(state_config) = state_config else {
+            return;
+        };
+
+        let mut values: indexmap::IndexMap<String, Value> = state_config
+            .get("fields")
+            .and_then(|f| f.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        let persist = state_config
+            .get("persist")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let mut store = None;
+        if persist {
+            let file = state_config
+                .get("file")
+                .and_then(|v| v.as_str())
+                .unwrap_or("state.json");
+            let path = self
+                .config_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(file);
+            match nemo_data::FileStore::open(&path) {
+                Ok(mut opened) => {
+                    let version = state_config
+                        .get("version")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(1);
+                    if let Some(saved) = self.migrate_state(&state_config, &opened, version) {
+                        if let Some(saved) = saved.as_object() {
+                            values.extend(saved.clone());
+                        }
+                    }
+                    opened.set_version(version);
+                    store = Some(opened);
+                }
+                Err(e) => tracing::warn!("Failed to load persisted state: {}", e),
+            }
+        }
+
+        let repository = &self.data_engine.repository;
+        let state = Value::Object(values);
+        if let Ok(path) = nemo_data::DataPath::parse("state") {
+            if let Err(e) = repository.set(&path, state.clone()) {
+                tracing::warn!("Failed to seed state: {}", e);
+                return;
+            }
+        }
+        if let Ok(mut paths) = self.plugin_dirty_paths.write() {
+            collect_value_paths("state", &state, &mut paths);
+        }
+        self.data_dirty.store(true, Ordering::Release);
+
+        if let Some(store) = store {
+            let debounce = state_config
+                .get("debounce")
+                .and_then(|v| v.as_i64())
+                .map(|ms| std::time::Duration::from_millis(ms.max(0) as u64))
+                .unwrap_or(nemo_data::DEFAULT_PERSIST_DEBOUNCE);
+            let persister = nemo_data::StatePersister::new(store, debounce);
+            let _guard = self.tokio_runtime.enter();
+            persister.spawn(Arc::clone(repository), Arc::clone(&self.shutdown));
+            if let Ok(mut slot) = self.state_persister.lock() {
+                *slot = Some(persister);
+            }
+        }
+    }
+
+    /// Returns the saved state values to restore, running the `migrate` hook
+    /// when the saved version differs from `version`. Returns `None` when
+    /// nothing was saved, and the unmigrated values when migration fails.
+    fn migrate_state(
+        &self,
+        state_config: &Value,
+        store: &nemo_data::FileStore,
+        version: i64,
+    ) -> Option<Value> {
+        let saved_version = store.version()?;
+        let saved = store.values();
+        if saved_version == version {
+            return Some(saved);
+        }
+
+        let hook = state_config
+            .get("migrate")
+            .and_then(|v| v.as_str())
+            .unwrap_or("migrate");
+        let (script_id, function_name) = split_handler(hook);
+        info!(
+            "Migrating state from version {} to {} via {}::{}",
+            saved_version, version, script_id, function_name
+        );
+
+        let mut ext = self
+            .extension_manager
+            .write()
+            .expect("extension_manager lock poisoned");
+        match ext.call_script_value(
+            script_id,
+            function_name,
+            vec![
+                PluginValue::Integer(saved_version),
+                value_to_plugin_value(&saved),
+            ],
+        ) {
+            Ok(migrated @ PluginValue::Object(_)) => Some(plugin_value_to_config_value(migrated)),
+            Ok(other) => {
+                tracing::warn!(
+                    "State migration {}::{} returned {:?}, expected an object; keeping saved state",
+                    script_id,
+                    function_name,
+                    other
+                );
+                Some(saved)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "State migration {}::{} failed: {}; keeping saved state",
+                    script_id,
+                    function_name,
+                    e
+                );
+                Some(saved)
+            }
+        }
+    }
+
     /// Parses data source configuration and registers sources with the DataFlowEngine.
     fn setup_data_sources(&self) -> Result<()> {
         let data_config = {
@@ -905,6 +1598,18 @@ impl NemoRuntime {
         }
     }
 
+    /// Writes the current `state.*` snapshot to the state file now, without
+    /// waiting out the persister's debounce. A no-op unless `<state
+    /// persist="true">`.
+    pub fn flush_state(&self) {
+        let persister = self.state_persister.lock().ok().and_then(|p| p.clone());
+        if let Some(persister) = persister {
+            if let Err(e) = persister.flush(&self.data_engine.repository) {
+                tracing::warn!("Failed to persist state: {}", e);
+            }
+        }
+    }
+
     /// Gracefully shuts down background tasks and stops data sources.
     pub fn shutdown(&self) {
         info!("Shutting down Nemo runtime...");
@@ -912,6 +1617,9 @@ impl NemoRuntime {
         // Signal all background tasks to stop
         self.shutdown.notify_waiters();
 
+        // Persist the final `state.*` snapshot, even inside the debounce window
+        self.flush_state();
+
         // Stop all data sources
         self.tokio_runtime.block_on(async {
             let results = self.data_engine.stop_all().await;
@@ -940,7 +1648,27 @@ impl NemoRuntime {
             return false;
         }
 
+        let retries = std::mem::take(
+            &mut *self
+                .pending_write_backs
+                .lock()
+                .expect("pending write-backs poisoned"),
+        );
+        for (component_id, property, value) in retries {
+            self.write_back_property(&component_id, &property, value);
+        }
+        let reorders = std::mem::take(
+            &mut *self
+                .pending_reorders
+                .lock()
+                .expect("pending reorders poisoned"),
+        );
+        for (container_id, child_id, to) in reorders {
+            self.reorder_child(&container_id, &child_id, to);
+        }
+
         let mut any_updates = false;
+        let mut lists_changed = false;
 
         // Get source IDs and read their data from the repository
         let source_ids = self
@@ -954,6 +1682,7 @@ impl NemoRuntime {
 
                 if let Ok(mut layout_manager) = self.layout_manager.try_write() {
                     let updates = layout_manager.on_data_changed(&source_path, &value);
+                    self.report_binding_errors(&layout_manager, &updates);
                     if !updates.is_empty() {
                         layout_manager.apply_updates(updates);
                         any_updates = true;
@@ -962,6 +1691,7 @@ impl NemoRuntime {
                     // create/remove component instances after scalar bindings.
                     if layout_manager.on_list_data_changed(&source_path, &value) {
                         any_updates = true;
+                        lists_changed = true;
                     }
                 }
             }
@@ -981,6 +1711,7 @@ impl NemoRuntime {
                 if let Some(value) = self.data_engine.repository.get(&data_path) {
                     if let Ok(mut layout_manager) = self.layout_manager.try_write() {
                         let updates = layout_manager.on_data_changed(path, &value);
+                        self.report_binding_errors(&layout_manager, &updates);
                         if !updates.is_empty() {
                             layout_manager.apply_updates(updates);
                             any_updates = true;
@@ -988,15 +1719,35 @@ impl NemoRuntime {
                         // List bindings (runtime n:for).
                         if layout_manager.on_list_data_changed(path, &value) {
                             any_updates = true;
+                            lists_changed = true;
                         }
                     }
                 }
             }
         }
 
+        // New n:for items may be SFC instances with state of their own.
+        if lists_changed {
+            self.adopt_sfc_instances();
+        }
+
         any_updates
     }
 
+    /// Reports the bindings whose transform failed, located where the update
+    /// says (a bound SFC prop's instance) or else at the component they target.
+    fn report_binding_errors(&self, lm: &LayoutManager, updates: &[nemo_layout::BindingUpdate]) {
+        for update in updates {
+            if let Some(error) = &update.error {
+                let origin = update.origin.as_ref().or_else(|| {
+                    lm.get_component(&update.target.component_id)
+                        .and_then(|c| c.origin.as_ref())
+                });
+                self.report_error(SourceError::new(error.clone()).or_at(origin));
+            }
+        }
+    }
+
     // ── Router / navigation ────────────────────────────────────────────────
 
     /// Enqueues a navigation to `path` on `router` (the primary router when
@@ -1239,22 +1990,155 @@ impl NemoRuntime {
         any
     }
 
-    /// Drains queued global-roundness changes from `set_roundness()`. Returns the
-    /// latest requested value (a preset name or pixel string), or `None` if none
-    /// are pending. Only the last wins — intermediate values would just be
-    /// overwritten before the next render. The caller applies it with `cx`
-    /// (needs the gpui `Theme`), outside the extension lock.
-    pub fn take_pending_roundness(&self) -> Option<String> {
-        let pending: Vec<String> = {
-            let mut q = self
-                .roundness_intents
-                .lock()
-                .expect("roundness_intents poisoned");
+    /// Switches the UI language. Attributes written as `${t(…)}` re-render
+    /// in `locale` now; `t('key')` binding transforms re-run on the next
+    /// data pass. Before [`Self::load_config`] this picks the locale the
+    /// config loads in (the `locale` from `config.toml`). Returns the catalog
+    /// locale chosen — `de-AT` falls back to `de`, then to the app's default —
+    /// or `None` when the app has no catalogs.
+    pub fn set_locale(&self, locale: &str) -> Option<String> {
+        self.config_loader.set_locale(Some(locale));
+        let messages = {
+            let mut guard = self.messages.write().expect("messages lock poisoned");
+            let messages = guard.as_mut()?;
+            messages.set_locale(locale);
+            messages.clone()
+        };
+
+        let translated = {
+            let mut layout_manager = self
+                .layout_manager
+                .write()
+                .expect("layout_manager lock poisoned");
+            layout_manager
+                .bindings_mut()
+                .set_messages(Some(messages.clone()));
+            self.localize_components(&mut layout_manager);
+            layout_manager.bindings().translated_sources()
+        };
+        // Translated bindings forgot their last value; have the next data
+        // pass push their sources through again.
+        self.plugin_dirty_paths
+            .write()
+            .expect("plugin_dirty_paths lock poisoned")
+            .extend(translated);
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
+
+        info!("Locale set to {}", messages.locale());
+        Some(messages.locale().to_string())
+    }
+
+    /// Returns the active catalog locale, or `None` when the app has no
+    /// `<i18n>` catalogs.
+    pub fn locale(&self) -> Option<String> {
+        self.messages
+            .read()
+            .expect("messages lock poisoned")
+            .as_ref()
+            .map(|m| m.locale().to_string())
+    }
+
+    /// Returns the locales the app ships catalogs for.
+    pub fn locales(&self) -> Vec<String> {
+        self.messages
+            .read()
+            .expect("messages lock poisoned")
+            .as_ref()
+            .map(|m| m.locales())
+            .unwrap_or_default()
+    }
+
+    /// Applies the latest queued `set_locale()` call from a script or
+    /// plugin. Returns whether one was pending.
+    pub fn apply_pending_locale(&self) -> bool {
+        let pending =
+            std::mem::take(&mut *self.locale_intents.lock().expect("locale_intents poisoned"));
+        match pending.into_iter().next_back() {
+            Some(locale) => {
+                self.set_locale(&locale);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-resolves every component attribute recorded under `i18n_attrs`
+    /// (its `${t(…)}` source text) in the active locale.
+    fn localize_components(&self, layout_manager: &mut LayoutManager) {
+        if self
+            .messages
+            .read()
+            .expect("messages lock poisoned")
+            .is_none()
+        {
+            return;
+        }
+        let config = self.config.read().expect("config lock poisoned");
+        for id in layout_manager.component_ids() {
+            let Some(attrs) = layout_manager
+                .get_property(&id, I18N_ATTRS)
+                .and_then(|v| v.as_object())
+                .cloned()
+            else {
+                continue;
+            };
+            for (attr, text) in attrs {
+                let Some(text) = text.as_str() else {
+                    continue;
+                };
+                match self.config_loader.localize(text, &config) {
+                    Ok(value) => {
+                        let _ = layout_manager.set_property(&id, &attr, value);
+                    }
+                    Err(e) => warn!("Failed to localize '{}' of '{}': {}", attr, id, e),
+                }
+            }
+        }
+    }
+
+    /// Drains queued global-roundness changes from `set_roundness()`. Returns the
+    /// latest requested value (a preset name or pixel string), or `None` if none
+    /// are pending. Only the last wins — intermediate values would just be
+    /// overwritten before the next render. The caller applies it with `cx`
+    /// (needs the gpui `Theme`), outside the extension lock.
+    pub fn take_pending_roundness(&self) -> Option<String> {
+        let pending: Vec<String> = {
+            let mut q = self
+                .roundness_intents
+                .lock()
+                .expect("roundness_intents poisoned");
             std::mem::take(&mut *q)
         };
         pending.into_iter().next_back()
     }
 
+    /// Exports a `table` or chart to `path`, in the format named by its
+    /// extension. CSV and JSON are written from the component's bound `data`
+    /// before this returns; a PNG is queued for the window to capture after
+    /// its next frame (see [`Self::take_pending_exports`]).
+    pub fn export_component(&self, component_id: &str, path: &Path) -> Result<()> {
+        let component = self
+            .layout_manager
+            .read()
+            .expect("layout_manager lock poisoned")
+            .get_component(component_id)
+            .cloned()
+            .with_context(|| format!("no component with id '{}'", component_id))?;
+        if write_or_queue_export(&component, path, &self.export_intents)? {
+            self.data_dirty.store(true, Ordering::Release);
+            self.data_notify.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Drains queued PNG exports. The caller captures each one with the
+    /// window once the component has been drawn.
+    pub(crate) fn take_pending_exports(&self) -> Vec<ExportIntent> {
+        let mut q = self.export_intents.lock().expect("export_intents poisoned");
+        std::mem::take(&mut *q)
+    }
+
     /// Fires the one-shot `on-enter` hook for routers that the render pass just
     /// seeded to their default (or `--route`) path. A router's initial path is
     /// set through lazy initialization ([`Self::router_current_path`]) without a
@@ -1646,7 +2530,7 @@ fn deep_merge_values(base: &Value, overlay: &Value) -> Value {
     let mut result = base_obj.clone();
 
     for (key, overlay_val) in overlay_obj {
-        if key == "template" || key == "vars" {
+        if key == "template" || key == "vars" || key == "typed_vars" || key == "bound_props" {
             continue; // consumed during expansion
         }
         match key.as_str() {
@@ -1749,6 +2633,15 @@ fn strip_keys(value: &Value, keys: &[&str]) -> Value {
     }
 }
 
+/// The `file:line:col` the parser recorded for a component under
+/// [`ORIGIN_KEY`].
+fn value_origin(value: &Value) -> Option<SourceLocation> {
+    value
+        .get(ORIGIN_KEY)
+        .and_then(|v| v.as_str())
+        .and_then(SourceLocation::parse)
+}
+
 /// Extracts the `vars` block from a component instance as a `HashMap<String, String>`.
 /// Returns an empty map if no `vars` key is present. Errors if vars contains non-string values.
 fn extract_vars(instance: &Value) -> Result<HashMap<String, String>, String> {
@@ -1784,13 +2677,18 @@ fn extract_vars(instance: &Value) -> Result<HashMap<String, String>, String> {
     Ok(vars)
 }
 
+/// Component property holding the `${t(…)}` source of its translated
+/// attributes, recorded at load by `nemo_config::record_translations`.
+const I18N_ATTRS: &str = "i18n_attrs";
+
 /// Recursively walks a `Value` tree and replaces `${var_name}` patterns in strings
-/// with values from the vars map. Errors on undefined variables.
+/// with values from the vars map. Errors on undefined variables, located at
+/// the innermost component that used one.
 fn interpolate_variables(
     value: &Value,
     vars: &HashMap<String, String>,
     template_name: &str,
-) -> Result<Value, String> {
+) -> Result<Value, SourceError> {
     match value {
         Value::String(s) => {
             let mut result = s.clone();
@@ -1802,6 +2700,12 @@ fn interpolate_variables(
                 match result[after_open..].find('}') {
                     Some(end) => {
                         let var_name = &result[after_open..after_open + end];
+                        // `${t(…)}` is a translation, rendered in the active
+                        // locale once the layout is built; leave it in place.
+                        if var_name.starts_with("t(") {
+                            start = after_open + end + 1;
+                            continue;
+                        }
                         match vars.get(var_name) {
                             Some(replacement) => {
                                 let pattern = format!("${{{}}}", var_name);
@@ -1812,10 +2716,10 @@ fn interpolate_variables(
                             None => {
                                 let available: Vec<&str> =
                                     vars.keys().map(|k| k.as_str()).collect();
-                                return Err(format!(
+                                return Err(SourceError::new(format!(
                                     "Undefined variable '{}' in template '{}'. Available vars: {:?}",
                                     var_name, template_name, available
-                                ));
+                                )));
                             }
                         }
                     }
@@ -1825,12 +2729,12 @@ fn interpolate_variables(
             Ok(Value::String(result))
         }
         Value::Object(obj) => {
+            let origin = value_origin(value);
             let mut result = indexmap::IndexMap::new();
             for (key, val) in obj {
-                result.insert(
-                    key.clone(),
-                    interpolate_variables(val, vars, template_name)?,
-                );
+                let interpolated = interpolate_variables(val, vars, template_name)
+                    .map_err(|e| e.or_at(origin.as_ref()))?;
+                result.insert(key.clone(), interpolated);
             }
             Ok(Value::Object(result))
         }
@@ -1988,11 +2892,13 @@ fn expand_template(
     templates: &TemplateMap,
     expansion_stack: &mut Vec<String>,
     instance_id: Option<&str>,
-) -> Result<Value, String> {
+) -> Result<Value, SourceError> {
     let obj = match instance.as_object() {
         Some(o) => o,
         None => return Ok(instance.clone()),
     };
+    let origin = value_origin(instance);
+    let located = |e: SourceError| e.or_at(origin.as_ref());
 
     // Check for template = "name"
     let template_name = match obj.get("template").and_then(|v| v.as_str()) {
@@ -2003,19 +2909,34 @@ fn expand_template(
         }
     };
 
+    // A looped instance (`n:for` over live data on an SFC tag) is a list
+    // container: each item instantiates the template instead (see
+    // `compile_list_templates`), so the container itself stays a bare stack.
+    if let Some(list_binding) = obj.get("list_binding") {
+        let mut container = indexmap::IndexMap::new();
+        container.insert("type".to_string(), Value::String("stack".to_string()));
+        for key in ["id", ORIGIN_KEY] {
+            if let Some(val) = obj.get(key) {
+                container.insert(key.to_string(), val.clone());
+            }
+        }
+        container.insert("list_binding".to_string(), list_binding.clone());
+        return Ok(Value::Object(container));
+    }
+
     // Circular reference check
     if expansion_stack.contains(&template_name) {
-        return Err(format!(
+        return Err(located(SourceError::new(format!(
             "Circular template reference detected: {} -> {}",
             expansion_stack.join(" -> "),
             template_name
-        ));
+        ))));
     }
 
     // Look up the template
     let template_def = templates
         .get(&template_name)
-        .ok_or_else(|| format!("Unknown template: '{}'", template_name))?
+        .ok_or_else(|| located(format!("Unknown template: '{}'", template_name).into()))?
         .clone();
 
     // Collect all template-originated component IDs (including nested descendants)
@@ -2030,15 +2951,31 @@ fn expand_template(
 
     // Recursively expand the template itself (template-of-template)
     expansion_stack.push(template_name.clone());
-    let expanded_template = expand_template(&template_def, templates, expansion_stack, None)?;
+    let expanded_template =
+        expand_template(&template_def, templates, expansion_stack, None).map_err(located)?;
     expansion_stack.pop();
 
+    // Whole-value `${name}` placeholders of typed SFC props take the typed
+    // value; the rest interpolate as text below.
+    let expanded_template = match obj.get("typed_vars").and_then(|v| v.as_object()) {
+        Some(typed) => substitute_typed_vars(&expanded_template, typed),
+        None => expanded_template,
+    };
+
     // Interpolate template variables from instance vars block
-    let vars = extract_vars(instance)?;
+    let vars = extract_vars(instance).map_err(|e| located(e.into()))?;
     let interpolated = if vars.is_empty() {
         expanded_template
     } else {
-        interpolate_variables(&expanded_template, &vars, &template_name)?
+        interpolate_variables(&expanded_template, &vars, &template_name).map_err(located)?
+    };
+    // Bound SFC props interpolated as placeholders become bindings.
+    let interpolated = if vars.values().any(|v| v.contains(SFC_BOUND_PROP)) {
+        let bound = obj.get("bound_props").and_then(|v| v.as_object());
+        bind_sfc_prop_refs(&interpolated, &template_name, bound, origin.as_ref())
+            .map_err(located)?
+    } else {
+        interpolated
     };
 
     // Extract instance children before merging
@@ -2081,7 +3018,10 @@ fn expand_template(
     };
 
     // Strip consumed keys
-    let stripped = strip_keys(&with_slots, &["template", "slot", "vars"]);
+    let stripped = strip_keys(
+        &with_slots,
+        &["template", "slot", "vars", "typed_vars", "bound_props"],
+    );
 
     // Prefix template-originated child IDs with the instance ID for uniqueness.
     // This prevents ID collisions when the same template is used by multiple
@@ -2098,7 +3038,7 @@ fn expand_template(
     };
 
     // Recurse into merged children
-    expand_children(&scoped, templates, expansion_stack)
+    expand_children(&scoped, templates, expansion_stack).map_err(located)
 }
 
 /// Renames template-originated child IDs by prefixing them with the parent
@@ -2193,7 +3133,7 @@ fn expand_children(
     value: &Value,
     templates: &TemplateMap,
     expansion_stack: &mut Vec<String>,
-) -> Result<Value, String> {
+) -> Result<Value, SourceError> {
     let obj = match value.as_object() {
         Some(o) => o,
         None => return Ok(value.clone()),
@@ -2250,17 +3190,32 @@ fn collect_sfc_tags(config: &Value) -> HashSet<String> {
 #[derive(Debug, Clone)]
 struct SfcPropSpec {
     name: String,
+    ty: String,
+    one_of: Vec<String>,
     default: Option<Value>,
 }
 
-/// Reads declared props per SFC tag from `config["sfc"]`. Only the fields the
-/// runtime needs (name + coerced default) are kept; `type`/`required` are for
-/// `nemo validate`. Tags without a `<props>` block map to an empty vec.
-fn read_sfc_props(config: &Value) -> HashMap<String, Vec<SfcPropSpec>> {
+/// What an SFC tag declares about its instances: props (with defaults), the
+/// custom events its script can `emit`, and its per-instance `<state>` fields
+/// (with initial values).
+#[derive(Debug, Clone, Default)]
+struct SfcSpec {
+    props: Vec<SfcPropSpec>,
+    emits: Vec<String>,
+    state: indexmap::IndexMap<String, Value>,
+}
+
+/// Reads declared props, emits and state per SFC tag from `config["sfc"]`.
+/// Only the fields the runtime needs (name, type, choices and coerced default)
+/// are kept: static instance values were already coerced to `type` when the
+/// config loaded, and bound ones are coerced as their data arrives.
+/// `required` is for `nemo validate`. Tags without a
+/// `<props>`/`<emits>`/`<state>` block get empty collections.
+fn read_sfc_specs(config: &Value) -> HashMap<String, SfcSpec> {
     let mut map = HashMap::new();
     if let Some(sfc) = config.get("sfc").and_then(|v| v.as_object()) {
         for (tag, def) in sfc {
-            let specs = def
+            let props = def
                 .get("props")
                 .and_then(|v| v.as_array())
                 .map(|arr| {
@@ -2269,25 +3224,58 @@ fn read_sfc_props(config: &Value) -> HashMap<String, Vec<SfcPropSpec>> {
                             let name = p.get("name").and_then(|v| v.as_str())?.to_string();
                             Some(SfcPropSpec {
                                 name,
+                                ty: p
+                                    .get("type")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or("string")
+                                    .to_string(),
+                                one_of: p
+                                    .get("one_of")
+                                    .and_then(|v| v.as_array())
+                                    .map(|arr| arr.iter().map(|v| v.to_string()).collect())
+                                    .unwrap_or_default(),
                                 default: p.get("default").cloned(),
                             })
                         })
                         .collect()
                 })
                 .unwrap_or_default();
-            map.insert(tag.clone(), specs);
+            map.insert(
+                tag.clone(),
+                SfcSpec {
+                    props,
+                    emits: sfc_emits(def),
+                    state: def
+                        .get("state")
+                        .and_then(|v| v.as_object())
+                        .cloned()
+                        .unwrap_or_default(),
+                },
+            );
         }
     }
     map
 }
 
+/// The event names an SFC definition declares in its `<emits>` block.
+fn sfc_emits(def: &Value) -> Vec<String> {
+    def.get("emits")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|e| e.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Recursively rewrites SFC tag usages into template instances. Children are
 /// rewritten first (bottom-up) so a node that is itself an SFC tag keeps its
 /// already-rewritten children.
 fn rewrite_sfc_tags(
     value: &Value,
     sfc_tags: &HashSet<String>,
-    sfc_props: &HashMap<String, Vec<SfcPropSpec>>,
+    sfc_specs: &HashMap<String, SfcSpec>,
 ) -> Value {
     let obj = match value.as_object() {
         Some(o) => o,
@@ -2296,7 +3284,7 @@ fn rewrite_sfc_tags(
             if let Value::Array(arr) = value {
                 return Value::Array(
                     arr.iter()
-                        .map(|v| rewrite_sfc_tags(v, sfc_tags, sfc_props))
+                        .map(|v| rewrite_sfc_tags(v, sfc_tags, sfc_specs))
                         .collect(),
                 );
             }
@@ -2310,7 +3298,7 @@ fn rewrite_sfc_tags(
     if let Some(children) = obj.get("component") {
         result.insert(
             "component".to_string(),
-            rewrite_sfc_tags(children, sfc_tags, sfc_props),
+            rewrite_sfc_tags(children, sfc_tags, sfc_specs),
         );
     }
     // Object maps that are NOT the `component` key still need recursion when
@@ -2323,7 +3311,7 @@ fn rewrite_sfc_tags(
         let mut changed = false;
         for (k, v) in obj {
             if v.as_object().and_then(|o| o.get("type")).is_some() {
-                rewritten.insert(k.clone(), rewrite_sfc_tags(v, sfc_tags, sfc_props));
+                rewritten.insert(k.clone(), rewrite_sfc_tags(v, sfc_tags, sfc_specs));
                 changed = true;
             } else {
                 rewritten.insert(k.clone(), v.clone());
@@ -2338,8 +3326,8 @@ fn rewrite_sfc_tags(
     if let Some(t) = result.get("type").and_then(|v| v.as_str()) {
         if sfc_tags.contains(t) {
             let tag = t.to_string();
-            let props = sfc_props.get(&tag).map(|v| v.as_slice()).unwrap_or(&[]);
-            return sfc_node_to_instance(&Value::Object(result), &tag, props);
+            let spec = sfc_specs.get(&tag).cloned().unwrap_or_default();
+            return sfc_node_to_instance(&Value::Object(result), &tag, &spec);
         }
     }
 
@@ -2349,12 +3337,27 @@ fn rewrite_sfc_tags(
 /// Converts an SFC tag node (`{ type: "tag", <attrs>, component: {…} }`) into a
 /// template instance (`{ template: "tag", <attrs>, vars: {…}, component: {…} }`).
 ///
-/// Scalar attributes are kept at the top level (so `deep_merge_values` overlays
-/// them onto the template body) *and* folded into a `vars` map (so `${attr}`
-/// interpolation works). The `type` key is dropped — the template body supplies
-/// the real component type. Declared props the instance omits are filled from
-/// their (already type-coerced) defaults, into both the overlay attrs and vars.
-fn sfc_node_to_instance(node: &Value, tag: &str, props: &[SfcPropSpec]) -> Value {
+/// Attributes are kept at the top level (so `deep_merge_values` overlays them
+/// onto the template body) *and* folded into a `vars` map (so `${attr}`
+/// interpolation works; arrays and objects interpolate as JSON). Non-string
+/// values — props the config loader coerced to their declared type — are also
+/// kept in `typed_vars`, so a whole-attribute `${attr}` keeps the type. The
+/// `type` key is dropped — the template body supplies the real component type.
+/// Declared props the instance omits are filled from their (already
+/// type-coerced) defaults, into both the overlay attrs and vars.
+///
+/// A bound prop (`:name="data.x"`) binds the root's `name` to its source
+/// instead, and interpolates as an [`SFC_BOUND_PROP`] placeholder, which
+/// expansion turns into bindings. Its source and declaration are kept in
+/// `bound_props`, so each binding coerces the prop's values to its declared
+/// type and reports a bad one at the instance.
+///
+/// When the tag declares `<emits>`, the instance's `on_<event>` handlers for
+/// those events become `on_emit:<event>` (so they can't collide with a native
+/// event of the template root). When it declares `<emits>` or `<state>`, the
+/// root is marked with [`SFC_INSTANCE`] so an `emit()` or a `state` binding
+/// can find it.
+fn sfc_node_to_instance(node: &Value, tag: &str, spec: &SfcSpec) -> Value {
     let obj = match node.as_object() {
         Some(o) => o,
         None => return node.clone(),
@@ -2363,42 +3366,107 @@ fn sfc_node_to_instance(node: &Value, tag: &str, props: &[SfcPropSpec]) -> Value
     let mut inst = indexmap::IndexMap::new();
     inst.insert("template".to_string(), Value::String(tag.to_string()));
 
+    let origin = value_origin(node);
+    let mut bound = indexmap::IndexMap::new();
+    let mut bindings = Vec::new();
     let mut vars = indexmap::IndexMap::new();
+    let mut typed_vars = indexmap::IndexMap::new();
+    let mut add_var = |key: &str, val: &Value| {
+        let text = match val {
+            Value::Array(_) | Value::Object(_) => serde_json::to_string(val).ok(),
+            other => scalar_to_string(other),
+        };
+        if let Some(text) = text {
+            vars.insert(key.to_string(), Value::String(text));
+        }
+        if !matches!(val, Value::String(_) | Value::Null) {
+            typed_vars.insert(key.to_string(), val.clone());
+        }
+    };
     for (key, val) in obj {
         match key.as_str() {
             "type" => continue, // template body supplies the real type
-            "component" => {
-                inst.insert("component".to_string(), val.clone());
+            "component" | ORIGIN_KEY => {
+                inst.insert(key.clone(), val.clone());
+            }
+            "binding" => match val {
+                Value::Array(arr) => bindings.extend(arr.iter().cloned()),
+                other => bindings.push(other.clone()),
+            },
+            _ if key.starts_with(':') => {
+                let name = &key[1..];
+                let Some(source) = val.as_str() else {
+                    tracing::warn!("sfc '{}': bound prop '{}' needs a data path", tag, key);
+                    continue;
+                };
+                let prop = BoundProp::declared(name, source, spec, origin.as_ref());
+                match prop.transform(None) {
+                    Some(transform) => {
+                        let mut binding = indexmap::IndexMap::new();
+                        binding.insert("source".to_string(), val.clone());
+                        binding.insert("target".to_string(), Value::String(name.to_string()));
+                        binding.insert("transform".to_string(), Value::String(transform));
+                        bindings.push(Value::Object(binding));
+                    }
+                    None => {
+                        inst.insert(format!("bind_{}", name), val.clone());
+                    }
+                }
+                bound.insert(name.to_string(), prop.to_value());
+                add_var(name, &Value::String(format!("{}{})", SFC_BOUND_PROP, name)));
+            }
+            _ if key
+                .strip_prefix("on_")
+                .is_some_and(|event| spec.emits.iter().any(|e| e == event)) =>
+            {
+                inst.insert(
+                    format!("on_{}{}", EMIT_HANDLER_PREFIX, &key[3..]),
+                    val.clone(),
+                );
             }
             _ => {
                 inst.insert(key.clone(), val.clone());
-                if let Some(s) = scalar_to_string(val) {
-                    vars.insert(key.clone(), Value::String(s));
-                }
+                add_var(key, val);
             }
         }
     }
 
     // Fill defaults for declared props the instance did not supply.
-    for spec in props {
-        if inst.contains_key(&spec.name) {
+    for prop in &spec.props {
+        if inst.contains_key(&prop.name) || obj.contains_key(&format!(":{}", prop.name)) {
             continue;
         }
-        if let Some(default) = &spec.default {
-            inst.insert(spec.name.clone(), default.clone());
-            if let Some(s) = scalar_to_string(default) {
-                vars.insert(spec.name.clone(), Value::String(s));
-            }
+        if let Some(default) = &prop.default {
+            inst.insert(prop.name.clone(), default.clone());
+            add_var(&prop.name, default);
         }
     }
+    if !spec.emits.is_empty() || !spec.state.is_empty() {
+        inst.insert(SFC_INSTANCE.to_string(), Value::String(tag.to_string()));
+    }
 
     if !vars.is_empty() {
         inst.insert("vars".to_string(), Value::Object(vars));
     }
+    if !typed_vars.is_empty() {
+        inst.insert("typed_vars".to_string(), Value::Object(typed_vars));
+    }
+    if !bound.is_empty() {
+        inst.insert("bound_props".to_string(), Value::Object(bound));
+    }
+    insert_bindings(&mut inst, bindings);
 
     Value::Object(inst)
 }
 
+/// Property marking the root of an SFC instance whose tag declares `<emits>`
+/// or `<state>`; its value is the tag.
+const SFC_INSTANCE: &str = "sfc_instance";
+
+/// Handler-name prefix for an instance's handlers of its SFC's declared
+/// events (`on-archive` → handler `emit:archive`).
+const EMIT_HANDLER_PREFIX: &str = "emit:";
+
 /// Renders a scalar `Value` as the string used for `${}` interpolation. Returns
 /// `None` for objects/arrays/null, which are not interpolable.
 fn scalar_to_string(value: &Value) -> Option<String> {
@@ -2443,178 +3511,1039 @@ pub(crate) fn rewrite_sfc_handlers(value: &Value, tag: &str) -> Value {
     }
 }
 
-// ── SFC scoped `<style>` folding ───────────────────────────────────────────
-//
-// There is no runtime style cascade in nemo — styling is inline attributes
-// consumed by `apply_layout_styles`. So an SFC `<style>` block is folded at
-// compile time into inline props on the template nodes its selectors match,
-// after which the styles are indistinguishable from author-written attributes.
-// Folding is inherently scoped: it only touches this SFC's own template subtree.
+// ── SFC per-instance `<state>` ─────────────────────────────────────────────
 //
-// v1 selectors: type (`button {}`) and id (`#btn {}`). No class/combinator/
-// pseudo/media. Declarations are constrained to the universal style attributes
-// `apply_layout_styles` understands; unknown props warn and drop. Precedence
-// (low→high): `<style>` rule → template inline attr → instance attr — achieved
-// by folding only where the attr is absent (id rules before type rules), then
-// letting `deep_merge_values` overlay instance attrs at expand time.
-
-/// A parsed CSS-subset selector.
-#[derive(Debug, Clone)]
-enum StyleSelector {
-    /// `button {}` — matches a node whose `type` equals this (snake-cased) name.
-    Type(String),
-    /// `#btn {}` — matches the node whose id (component-map key) equals this.
-    Id(String),
-}
-
-/// A parsed style rule: one selector plus its already-normalized declarations
-/// (attribute name → coerced value).
-struct StyleRule {
-    selector: StyleSelector,
-    decls: Vec<(String, Value)>,
+// Each instance of an SFC that declares `<state>` gets its own copy of the
+// fields in the repository, at `var.sfc.<instance_id>.<field>` — outside the
+// app's `state.*` namespace, so it is never persisted and never clashes with
+// another instance. Template references compile in two steps: before
+// expansion, `${state.x}` attributes and `state.x` binding sources in the
+// SFC's own markup become bindings on a placeholder source naming the tag;
+// after expansion, when final instance ids are known, each placeholder is
+// resolved against the nearest enclosing instance of that tag. Scripts reach
+// the same paths through `self.state` (`RuntimeContext::instance_state_path`),
+// and `adopt_sfc_instances` writes the initial values once the layout is
+// built. Instances built later — live `n:for` items, components a plugin
+// creates — keep their placeholders until `adopt_sfc_instances` resolves them
+// against the built tree.
+
+/// Binding-source prefix of an unresolved state reference:
+/// `@sfc_state:<tag>:<field path>`.
+const SFC_STATE_REF: &str = "@sfc_state:";
+
+/// The repository path of `field` in SFC instance `instance_id`'s state.
+fn sfc_state_path(instance_id: &str, field: &str) -> String {
+    format!("var.sfc.{}.{}", instance_id, field)
 }
 
-/// Normalizes a CSS property name to a nemo universal-style attribute name.
-/// A few CSS names alias to nemo's (e.g. `border-radius` → `rounded`); the rest
-/// are just kebab→snake. Returns `None` for names not in the universal set.
-fn normalize_style_prop(name: &str) -> Option<String> {
-    let normalized = match name {
-        "border-radius" => "rounded".to_string(),
-        "background-color" => "background".to_string(),
-        other => other.replace('-', "_"),
-    };
-    nemo_registry::universal_style_attributes()
-        .iter()
-        .find(|a| a.name == normalized)
-        .map(|a| a.name.to_string())
+/// Turns `state.<field>[.<rest>]` into a placeholder source when `<field>` is
+/// one of the SFC's own fields. Anything else (`data.*`, the app's
+/// `state.*`) is not an instance reference and yields `None`.
+fn sfc_state_ref(source: &str, tag: &str, spec: &SfcSpec) -> Option<String> {
+    let path = source.trim().strip_prefix("state.")?;
+    let field = path.split('.').next().unwrap_or(path);
+    spec.state
+        .contains_key(field)
+        .then(|| format!("{}{}:{}", SFC_STATE_REF, tag, path))
 }
 
-/// Coerces a CSS declaration value to the `Value` shape the attribute expects,
-/// per the universal attribute's `value_type`. Strips a `px` suffix for
-/// integers. Returns `None` if the value can't be coerced (caller warns/drops).
-fn coerce_style_value(attr: &str, raw: &str) -> Option<Value> {
-    let raw = raw.trim();
-    let value_type = nemo_registry::universal_style_attributes()
-        .iter()
-        .find(|a| a.name == attr)
-        .map(|a| a.value_type)
-        .unwrap_or("string");
-    match value_type {
-        "integer" => {
-            let n = raw.trim_end_matches("px").trim();
-            n.parse::<i64>().ok().map(Value::Integer)
+/// Applies `f` to the `source` of a `binding` block (an object or an array of
+/// them).
+fn map_binding_sources(binding: &Value, f: &dyn Fn(&str) -> String) -> Value {
+    match binding {
+        Value::Array(arr) => Value::Array(arr.iter().map(|b| map_binding_sources(b, f)).collect()),
+        Value::Object(obj) => {
+            let mut result = obj.clone();
+            if let Some(source) = obj.get("source").and_then(|v| v.as_str()) {
+                result.insert("source".to_string(), Value::String(f(source)));
+            }
+            Value::Object(result)
         }
-        "float" => raw.parse::<f64>().ok().map(Value::Float),
-        "boolean" => raw.parse::<bool>().ok().map(Value::Bool),
-        // Colors, `rounded`, `shadow`: kept as strings; `apply_layout_styles`
-        // resolves them (incl. `theme.*` / semantic roles) at render time.
-        _ => Some(Value::String(raw.to_string())),
+        other => other.clone(),
     }
 }
 
-/// Parses a CSS-subset `<style>` body into style rules. Comments are stripped;
-/// each `sel[, sel2] { prop: val; … }` block is parsed. Unsupported selectors
-/// and declarations are warned about and skipped. `tag` is only for diagnostics.
-fn parse_style_rules(css: &str, tag: &str) -> Vec<StyleRule> {
-    // Strip /* … */ comments.
-    let mut cleaned = String::with_capacity(css.len());
-    let mut rest = css;
-    while let Some(start) = rest.find("/*") {
-        cleaned.push_str(&rest[..start]);
-        match rest[start + 2..].find("*/") {
-            Some(end) => rest = &rest[start + 2 + end + 2..],
-            None => {
-                rest = "";
-                break;
+/// Compiles references to an SFC's `<state>` fields in its template body into
+/// placeholder bindings (resolved by [`resolve_sfc_state_refs`]):
+///
+/// * `text="${state.count}"` binds `text` to the field; the attribute keeps
+///   the initial value so the first frame isn't blank.
+/// * `text="Count: ${state.count}"` becomes a `binding` whose concat
+///   transform rebuilds the text. One state reference per attribute: more
+///   than one is an error located at the node.
+/// * `bind_*` values and `binding` sources naming `state.<field>` (what
+///   `n:if="state.open"` compiles to) are rebased.
+///
+/// Only `component` children and the loop bodies of live `n:for`s are walked:
+/// slot content a consumer passes in is merged later and keeps referring to
+/// the consumer's own state.
+fn bind_sfc_state_refs(value: &Value, tag: &str, spec: &SfcSpec) -> Result<Value, SourceError> {
+    let obj = match value {
+        Value::Object(obj) => obj,
+        Value::Array(arr) => {
+            return Ok(Value::Array(
+                arr.iter()
+                    .map(|v| bind_sfc_state_refs(v, tag, spec))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        other => return Ok(other.clone()),
+    };
+    let rebase =
+        |source: &str| sfc_state_ref(source, tag, spec).unwrap_or_else(|| source.to_string());
+
+    let mut result = indexmap::IndexMap::new();
+    let mut bindings = Vec::new();
+    for (key, val) in obj {
+        if key == "component" {
+            let children = match val {
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .map(|(id, child)| Ok((id.clone(), bind_sfc_state_refs(child, tag, spec)?)))
+                        .collect::<Result<_, SourceError>>()?,
+                ),
+                other => bind_sfc_state_refs(other, tag, spec)?,
+            };
+            result.insert(key.clone(), children);
+        } else if key == "list_binding" {
+            let mut list_binding = val.clone();
+            if let (Some(template), Value::Object(lb)) = (val.get("template"), &mut list_binding) {
+                lb.insert(
+                    "template".to_string(),
+                    bind_sfc_state_refs(template, tag, spec)?,
+                );
+            }
+            result.insert(key.clone(), list_binding);
+        } else if key == "binding" {
+            match map_binding_sources(val, &rebase) {
+                Value::Array(arr) => bindings.extend(arr),
+                other => bindings.push(other),
             }
+        } else if let Some(source) = val.as_str().filter(|_| key.starts_with("bind_")) {
+            result.insert(key.clone(), Value::String(rebase(source)));
+        } else if let Some(text) = val.as_str().filter(|t| t.contains("${state.")) {
+            bind_state_attr(key, text, tag, spec, &mut result, &mut bindings)
+                .map_err(|e| SourceError::new(e).or_at(value_origin(value).as_ref()))?;
+        } else {
+            result.insert(key.clone(), val.clone());
         }
     }
-    cleaned.push_str(rest);
+    insert_bindings(&mut result, bindings);
+    Ok(Value::Object(result))
+}
 
-    let mut rules = Vec::new();
-    let mut remaining = cleaned.as_str();
-    while let Some(open) = remaining.find('{') {
-        let selectors_part = remaining[..open].trim().to_string();
-        let after = &remaining[open + 1..];
-        let close = match after.find('}') {
-            Some(c) => c,
-            None => break, // unterminated rule; stop
-        };
-        let body = &after[..close];
-        remaining = &after[close + 1..];
+/// Compiles one attribute whose text contains `${state.…}` (see
+/// [`bind_sfc_state_refs`]), inserting the initial value into `node` and the
+/// binding into `bindings`. A reference to an undeclared field is left as-is.
+/// Errors when the text holds a second `${state.…}`.
+fn bind_state_attr(
+    key: &str,
+    text: &str,
+    tag: &str,
+    spec: &SfcSpec,
+    node: &mut indexmap::IndexMap<String, Value>,
+    bindings: &mut Vec<Value>,
+) -> Result<(), String> {
+    let reference = text.find("${state.").and_then(|start| {
+        let end = start + text[start..].find('}')?;
+        let source = sfc_state_ref(&text[start + 2..end], tag, spec)?;
+        Some((start, end, source))
+    });
+    let Some((start, end, source)) = reference else {
+        node.insert(key.to_string(), Value::String(text.to_string()));
+        return Ok(());
+    };
+    let (prefix, suffix) = (&text[..start], &text[end + 1..]);
+    if suffix.contains("${state.") {
+        return Err(format!(
+            "sfc '{}': attribute '{}' references more than one state field; \
+             an attribute can bind only one",
+            tag, key
+        ));
+    }
+    let initial = spec.state.get(&text[start + "${state.".len()..end]);
+    bind_attr_text(key, prefix, suffix, source, initial, None, node, bindings);
+    Ok(())
+}
 
-        // Parse declarations once for this block.
-        let mut decls = Vec::new();
-        for decl in body.split(';') {
-            let decl = decl.trim();
-            if decl.is_empty() {
-                continue;
-            }
-            let (name, value) = match decl.split_once(':') {
-                Some((n, v)) => (n.trim(), v.trim()),
-                None => {
-                    tracing::warn!(
-                        "sfc '{}': ignoring malformed style declaration '{}'",
-                        tag,
-                        decl
-                    );
-                    continue;
-                }
-            };
-            match normalize_style_prop(name) {
-                Some(attr) => match coerce_style_value(&attr, value) {
-                    Some(v) => decls.push((attr, v)),
-                    None => tracing::warn!(
-                        "sfc '{}': ignoring style value '{}: {}' (not coercible)",
-                        tag,
-                        name,
-                        value
-                    ),
-                },
-                None => tracing::warn!(
-                    "sfc '{}': ignoring unsupported style property '{}'",
-                    tag,
-                    name
-                ),
+/// Binds attribute `key` to `source`, where the attribute's text is `prefix`,
+/// the bound value, then `suffix`. With no surrounding text this is a plain
+/// `bind_<key>`; otherwise a `binding` whose concat transform rebuilds the
+/// text. A declared bound prop coerces each value first (see
+/// [`BoundProp::transform`]). `initial`, when known, seeds the attribute so
+/// the first frame isn't blank.
+#[allow(clippy::too_many_arguments)]
+fn bind_attr_text(
+    key: &str,
+    prefix: &str,
+    suffix: &str,
+    source: String,
+    initial: Option<&Value>,
+    prop: Option<&BoundProp>,
+    node: &mut indexmap::IndexMap<String, Value>,
+    bindings: &mut Vec<Value>,
+) {
+    let transform = if prefix.is_empty() && suffix.is_empty() {
+        if let Some(initial) = initial.filter(|v| !v.is_null()) {
+            node.insert(key.to_string(), initial.clone());
+        }
+        match prop.and_then(|p| p.transform(None)) {
+            Some(transform) => transform,
+            None => {
+                node.insert(format!("bind_{}", key), Value::String(source));
+                return;
             }
         }
-        if decls.is_empty() {
-            continue;
+    } else {
+        if let Some(initial) = initial.and_then(scalar_to_string) {
+            node.insert(
+                key.to_string(),
+                Value::String(format!("{}{}{}", prefix, initial, suffix)),
+            );
         }
+        let concat = concat_transform(prefix, suffix);
+        prop.and_then(|p| p.transform(Some(&concat)))
+            .unwrap_or(concat)
+    };
+    let mut binding = indexmap::IndexMap::new();
+    binding.insert("source".to_string(), Value::String(source));
+    binding.insert("target".to_string(), Value::String(key.to_string()));
+    binding.insert("transform".to_string(), Value::String(transform));
+    bindings.push(Value::Object(binding));
+}
 
-        // A block may list several comma-separated selectors; each shares decls.
-        for sel in selectors_part.split(',') {
-            let sel = sel.trim();
-            if sel.is_empty() {
-                continue;
-            }
-            if let Some(id) = sel.strip_prefix('#') {
-                rules.push(StyleRule {
-                    selector: StyleSelector::Id(id.trim().to_string()),
-                    decls: decls.clone(),
-                });
-            } else if sel
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-            {
-                rules.push(StyleRule {
-                    selector: StyleSelector::Type(sel.replace('-', "_")),
-                    decls: decls.clone(),
-                });
-            } else {
-                tracing::warn!(
-                    "sfc '{}': ignoring unsupported style selector '{}' (v1 supports type and #id)",
-                    tag,
-                    sel
-                );
-            }
+/// Collects `bindings` into a node's `binding` key: a single object, or an
+/// array when there are several.
+fn insert_bindings(node: &mut indexmap::IndexMap<String, Value>, mut bindings: Vec<Value>) {
+    match bindings.len() {
+        0 => {}
+        1 => {
+            node.insert("binding".to_string(), bindings.remove(0));
+        }
+        _ => {
+            node.insert("binding".to_string(), Value::Array(bindings));
         }
     }
-    rules
 }
 
-/// Folds an SFC `<style>` block onto its template body: parses the CSS subset,
-/// then walks the template applying each matching rule's declarations as inline
+/// Resolves the placeholder sources [`bind_sfc_state_refs`] left in the
+/// expanded layout to `var.sfc.<instance_id>.<field>`, using the nearest
+/// enclosing instance (the node itself included) of the placeholder's tag.
+/// `scopes` holds the `(tag, id)` of the instances above `node`.
+fn resolve_sfc_state_refs(
+    node: &Value,
+    id: Option<&str>,
+    scopes: &mut Vec<(String, String)>,
+) -> Value {
+    let Some(obj) = node.as_object() else {
+        return node.clone();
+    };
+    let id = obj.get("id").and_then(|v| v.as_str()).or(id);
+    let scoped = match (obj.get(SFC_INSTANCE).and_then(|v| v.as_str()), id) {
+        (Some(tag), Some(id)) => {
+            scopes.push((tag.to_string(), id.to_string()));
+            true
+        }
+        _ => false,
+    };
+
+    let resolve = |source: &str| -> String {
+        let Some((tag, field)) = source
+            .strip_prefix(SFC_STATE_REF)
+            .and_then(|rest| rest.split_once(':'))
+        else {
+            return source.to_string();
+        };
+        match scopes.iter().rev().find(|(t, _)| t == tag) {
+            Some((_, instance_id)) => sfc_state_path(instance_id, field),
+            None => {
+                tracing::warn!(
+                    "state.{} of sfc '{}' is used outside an instance with an id",
+                    field,
+                    tag
+                );
+                source.to_string()
+            }
+        }
+    };
+    let mut result = indexmap::IndexMap::new();
+    for (key, val) in obj {
+        let resolved = if key == "binding" {
+            map_binding_sources(val, &resolve)
+        } else if let Some(source) = val.as_str().filter(|_| key.starts_with("bind_")) {
+            Value::String(resolve(source))
+        } else {
+            val.clone()
+        };
+        result.insert(key.clone(), resolved);
+    }
+    if let Some(children) = obj.get("component") {
+        let resolved = match children {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(child_id, child)| {
+                        (
+                            child_id.clone(),
+                            resolve_sfc_state_refs(child, Some(child_id), scopes),
+                        )
+                    })
+                    .collect(),
+            ),
+            Value::Array(arr) => Value::Array(
+                arr.iter()
+                    .map(|child| resolve_sfc_state_refs(child, None, scopes))
+                    .collect(),
+            ),
+            other => other.clone(),
+        };
+        result.insert("component".to_string(), resolved);
+    }
+
+    if scoped {
+        scopes.pop();
+    }
+    Value::Object(result)
+}
+
+// ── SFC bound props ────────────────────────────────────────────────────────
+//
+// A prop set as `:name="data.x"` on an SFC instance names a data source
+// instead of a value. The instance interpolates `${name}` as a placeholder
+// naming the prop; once the template body is interpolated, every attribute
+// holding a placeholder is turned into a binding on the prop's source, so the
+// prop follows the data instead of freezing its first value. A declared prop's
+// bindings coerce each value to its `type` as it arrives, the way the config
+// loader coerces a static one.
+
+/// Start of the placeholder a bound prop interpolates as: `@bind(<name>)`.
+const SFC_BOUND_PROP: &str = "@bind(";
+
+/// A bound SFC prop: its data source and, when the tag declares the prop, the
+/// type and choices its values must match. `at` is the instance, where a value
+/// that doesn't match is reported.
+#[derive(Debug, Clone)]
+struct BoundProp {
+    name: String,
+    source: String,
+    ty: Option<String>,
+    one_of: Vec<String>,
+    at: Option<SourceLocation>,
+}
+
+impl BoundProp {
+    /// The prop `name` bound to `source`, with its declaration from `spec`.
+    fn declared(name: &str, source: &str, spec: &SfcSpec, at: Option<&SourceLocation>) -> Self {
+        let declared = spec.props.iter().find(|p| p.name == name);
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            ty: declared.map(|p| p.ty.clone()),
+            one_of: declared.map(|p| p.one_of.clone()).unwrap_or_default(),
+            at: at.cloned(),
+        }
+    }
+
+    /// Reads back an entry of an instance's `bound_props` (see
+    /// [`Self::to_value`]).
+    fn from_value(name: &str, value: &Value, at: Option<&SourceLocation>) -> Option<Self> {
+        Some(Self {
+            name: name.to_string(),
+            source: value.get("source")?.as_str()?.to_string(),
+            ty: value
+                .get("type")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            one_of: value
+                .get("one_of")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            at: at.cloned(),
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        let mut obj = indexmap::IndexMap::new();
+        obj.insert("source".to_string(), Value::String(self.source.clone()));
+        if let Some(ty) = &self.ty {
+            obj.insert("type".to_string(), Value::String(ty.clone()));
+            obj.insert(
+                "one_of".to_string(),
+                Value::Array(self.one_of.iter().cloned().map(Value::String).collect()),
+            );
+        }
+        Value::Object(obj)
+    }
+
+    /// The transform a binding of this prop needs: coercion to the declared
+    /// type, then `then`. `then` alone when the prop isn't declared.
+    fn transform(&self, then: Option<&str>) -> Option<String> {
+        match &self.ty {
+            Some(ty) => Some(prop_transform(
+                &self.name,
+                ty,
+                &self.one_of,
+                self.at.as_ref(),
+                then,
+            )),
+            None => then.map(str::to_string),
+        }
+    }
+}
+
+/// Compiles bound-prop placeholders in an interpolated template body into
+/// bindings (see [`bind_attr_text`]): an attribute that is exactly the
+/// placeholder binds to the prop's source, one embedded in text becomes a
+/// concat binding. `bound` is the instance's `bound_props` and `at` its
+/// origin. One bound prop per attribute, and not alongside a `${state.…}`
+/// binding of the same attribute: either is an error located at the node.
+fn bind_sfc_prop_refs(
+    value: &Value,
+    template_name: &str,
+    bound: Option<&indexmap::IndexMap<String, Value>>,
+    at: Option<&SourceLocation>,
+) -> Result<Value, SourceError> {
+    let obj = match value {
+        Value::Object(obj) => obj,
+        Value::Array(arr) => {
+            return Ok(Value::Array(
+                arr.iter()
+                    .map(|v| bind_sfc_prop_refs(v, template_name, bound, at))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        other => return Ok(other.clone()),
+    };
+    let located = |message: String| SourceError::new(message).or_at(value_origin(value).as_ref());
+
+    let mut result = indexmap::IndexMap::new();
+    let mut bindings = Vec::new();
+    let mut bound_attrs = Vec::new();
+    for (key, val) in obj {
+        if key == "component" {
+            let children = match val {
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .map(|(id, child)| {
+                            Ok((
+                                id.clone(),
+                                bind_sfc_prop_refs(child, template_name, bound, at)?,
+                            ))
+                        })
+                        .collect::<Result<_, SourceError>>()?,
+                ),
+                other => bind_sfc_prop_refs(other, template_name, bound, at)?,
+            };
+            result.insert(key.clone(), children);
+        } else if key == "binding" {
+            match val {
+                Value::Array(arr) => bindings.extend(arr.iter().cloned()),
+                other => bindings.push(other.clone()),
+            }
+        } else if let Some(text) = val.as_str().filter(|t| t.contains(SFC_BOUND_PROP)) {
+            let start = text.find(SFC_BOUND_PROP).unwrap_or_default();
+            let open = start + SFC_BOUND_PROP.len();
+            let Some(end) = text[open..].find(')') else {
+                result.insert(key.clone(), val.clone());
+                continue;
+            };
+            let (prefix, suffix) = (&text[..start], &text[open + end + 1..]);
+            if suffix.contains(SFC_BOUND_PROP) {
+                return Err(located(format!(
+                    "template '{}': attribute '{}' uses more than one bound prop; \
+                     an attribute can bind only one",
+                    template_name, key
+                )));
+            }
+            let name = &text[open..open + end];
+            let Some(prop) = bound
+                .and_then(|b| b.get(name))
+                .and_then(|v| BoundProp::from_value(name, v, at))
+            else {
+                result.insert(key.clone(), val.clone());
+                continue;
+            };
+            bind_attr_text(
+                key,
+                prefix,
+                suffix,
+                prop.source.clone(),
+                None,
+                Some(&prop),
+                &mut result,
+                &mut bindings,
+            );
+            bound_attrs.push(key.as_str());
+        } else {
+            result.insert(key.clone(), val.clone());
+        }
+    }
+    // A state binding's text can't also follow a bound prop.
+    if let Some(key) = bound_attrs.iter().find(|key| {
+        bindings
+            .iter()
+            .filter(|b| b.get("target").and_then(|t| t.as_str()) == Some(**key))
+            .count()
+            > 1
+    }) {
+        return Err(located(format!(
+            "template '{}': attribute '{}' mixes a bound prop with a state field; \
+             an attribute can bind only one",
+            template_name, key
+        )));
+    }
+    insert_bindings(&mut result, bindings);
+    Ok(Value::Object(result))
+}
+
+/// Replaces strings that are exactly `${name}`, for a `name` in `typed`, with
+/// that typed value, so a whole-attribute placeholder of an `int`/`bool`/
+/// `array`/… prop keeps its type instead of being interpolated as text.
+fn substitute_typed_vars(value: &Value, typed: &indexmap::IndexMap<String, Value>) -> Value {
+    match value {
+        Value::String(s) => s
+            .strip_prefix("${")
+            .and_then(|rest| rest.strip_suffix('}'))
+            .and_then(|name| typed.get(name))
+            .cloned()
+            .unwrap_or_else(|| value.clone()),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, v)| (k.clone(), substitute_typed_vars(v, typed)))
+                .collect(),
+        ),
+        Value::Array(arr) => Value::Array(
+            arr.iter()
+                .map(|v| substitute_typed_vars(v, typed))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+// ── SFC scoped `<style>` folding ───────────────────────────────────────────
+//
+// There is no runtime style cascade in nemo — styling is inline attributes
+// consumed by `apply_layout_styles`. So an SFC `<style>` block is folded at
+// compile time into inline props on the template nodes its selectors match,
+// after which the styles are indistinguishable from author-written attributes.
+// Folding is inherently scoped: it only touches this SFC's own template subtree.
+//
+// Selectors are type (`button`), `.class` (matched against a node's
+// `class="…"` list) and `#id` parts, compounded (`button.primary`) and joined
+// by descendant (` `) or child (`>`) combinators. A rule ending in `:hover`,
+// `:active`, `:focus` or `:disabled` is folded into the node's `<state>_style`
+// object instead, which the renderer applies while the state holds
+// (`nemo_layout::style_state`). Declarations are constrained to the universal
+// style attributes `apply_layout_styles` understands; unknown props warn and
+// drop. Custom properties declared in a `:root` block are substituted into
+// `var(--name[, fallback])`; undeclared names resolve to theme tokens.
+// Rules inside a top-level `@media (max-width: 900px)` block are folded into
+// the node's `media_style` list and applied while the window matches
+// (`nemo_layout::responsive`).
+//
+// Precedence (low→high): `<style>` rule → template inline attr → instance
+// attr — achieved by folding only where the attr is absent, more specific
+// (then later) rules first, then letting `deep_merge_values` overlay instance
+// attrs at expand time.
+
+/// One compound selector (`button.primary#save`): every part given must match.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CompoundSelector {
+    /// Node `type` (snake-cased).
+    tag: Option<String>,
+    /// Node id (component-map key).
+    id: Option<String>,
+    /// Names that must all be in the node's `class` list.
+    classes: Vec<String>,
+}
+
+/// How a compound relates to the one after it in a selector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    /// `a b` — any ancestor.
+    Descendant,
+    /// `a > b` — the parent.
+    Child,
+}
+
+/// A parsed CSS-subset selector.
+#[derive(Debug, Clone, PartialEq)]
+struct StyleSelector {
+    /// The compounds before the subject, outermost first, each with the
+    /// combinator joining it to the next.
+    ancestors: Vec<(CompoundSelector, Combinator)>,
+    /// The compound the rule styles.
+    subject: CompoundSelector,
+    /// A trailing pseudo-class; the rule then applies at render time.
+    state: Option<StyleState>,
+}
+
+/// A parsed style rule: one selector plus its already-normalized declarations
+/// (attribute name → coerced value).
+struct StyleRule {
+    selector: StyleSelector,
+    decls: Vec<(String, Value)>,
+    /// The enclosing `@media` query, in `n:show-when` syntax.
+    media: Option<String>,
+}
+
+/// A `{ … }` block of a `<style>` body.
+struct StyleBlock<'a> {
+    selectors: &'a str,
+    body: &'a str,
+    /// The `@media` query the block is nested in.
+    media: Option<ViewportCondition>,
+}
+
+/// Splits a comment-free `<style>` body into blocks, descending into
+/// top-level `@media` blocks. Other at-rules are warned about and skipped.
+fn style_blocks<'a>(
+    css: &'a str,
+    media: Option<&ViewportCondition>,
+    tag: &str,
+    blocks: &mut Vec<StyleBlock<'a>>,
+) {
+    let mut remaining = css;
+    while let Some(open) = remaining.find('{') {
+        let prelude = remaining[..open].trim();
+        let after = &remaining[open + 1..];
+        if let Some(at_rule) = prelude.strip_prefix('@') {
+            let mut depth = 1;
+            let Some(close) = after.find(|c| {
+                depth += match c {
+                    '{' => 1,
+                    '}' => -1,
+                    _ => 0,
+                };
+                depth == 0
+            }) else {
+                break; // unterminated block; stop
+            };
+            match at_rule.strip_prefix("media") {
+                Some(query) if media.is_none() => match ViewportCondition::parse_media(query) {
+                    Ok(condition) => style_blocks(&after[..close], Some(&condition), tag, blocks),
+                    Err(e) => tracing::warn!("sfc '{}': ignoring @media block: {}", tag, e),
+                },
+                _ => tracing::warn!(
+                    "sfc '{}': ignoring '@{}' block (only top-level @media is supported)",
+                    tag,
+                    at_rule
+                ),
+            }
+            remaining = &after[close + 1..];
+            continue;
+        }
+        let close = match after.find('}') {
+            Some(c) => c,
+            None => break, // unterminated rule; stop
+        };
+        blocks.push(StyleBlock {
+            selectors: prelude,
+            body: &after[..close],
+            media: media.cloned(),
+        });
+        remaining = &after[close + 1..];
+    }
+}
+
+/// The parts of a template node selectors match against.
+struct StyleNode {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl StyleNode {
+    fn of(node: &Value, id: Option<&str>) -> Self {
+        StyleNode {
+            tag: node.get("type").and_then(|v| v.as_str()).map(String::from),
+            id: id.map(String::from),
+            classes: node
+                .get("class")
+                .and_then(|v| v.as_str())
+                .map(|c| c.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl CompoundSelector {
+    fn matches(&self, node: &StyleNode) -> bool {
+        self.tag
+            .as_ref()
+            .is_none_or(|t| node.tag.as_ref() == Some(t))
+            && self.id.as_ref().is_none_or(|i| node.id.as_ref() == Some(i))
+            && self.classes.iter().all(|c| node.classes.contains(c))
+    }
+}
+
+impl StyleSelector {
+    /// Whether the selector matches `node`, whose ancestors (outermost first)
+    /// are `chain`.
+    fn matches(&self, node: &StyleNode, chain: &[StyleNode]) -> bool {
+        self.subject.matches(node) && ancestors_match(&self.ancestors, chain)
+    }
+
+    /// `(ids, classes and pseudo-classes, types)`, compared lexicographically.
+    fn specificity(&self) -> (usize, usize, usize) {
+        let compounds = self
+            .ancestors
+            .iter()
+            .map(|(c, _)| c)
+            .chain(std::iter::once(&self.subject));
+        let mut spec = (0, usize::from(self.state.is_some()), 0);
+        for c in compounds {
+            spec.0 += usize::from(c.id.is_some());
+            spec.1 += c.classes.len();
+            spec.2 += usize::from(c.tag.is_some());
+        }
+        spec
+    }
+}
+
+/// Matches selector ancestors right to left against a node's ancestor chain.
+fn ancestors_match(parts: &[(CompoundSelector, Combinator)], chain: &[StyleNode]) -> bool {
+    let Some(((compound, combinator), outer)) = parts.split_last() else {
+        return true;
+    };
+    match combinator {
+        Combinator::Child => chain.split_last().is_some_and(|(parent, above)| {
+            compound.matches(parent) && ancestors_match(outer, above)
+        }),
+        Combinator::Descendant => (0..chain.len())
+            .rev()
+            .any(|i| compound.matches(&chain[i]) && ancestors_match(outer, &chain[..i])),
+    }
+}
+
+/// Parses one compound (`button.primary:hover`). `None` when malformed or a
+/// pseudo-class isn't supported.
+fn parse_compound(text: &str) -> Option<(CompoundSelector, Option<StyleState>)> {
+    let is_name = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let mut compound = CompoundSelector::default();
+    let mut state = None;
+    let tag_len = text.find(|c: char| !is_name(c)).unwrap_or(text.len());
+    if tag_len > 0 {
+        compound.tag = Some(text[..tag_len].replace('-', "_"));
+    }
+    let mut rest = &text[tag_len..];
+    while let Some(marker) = rest.chars().next() {
+        let body = &rest[marker.len_utf8()..];
+        let len = body.find(|c: char| !is_name(c)).unwrap_or(body.len());
+        if len == 0 || state.is_some() {
+            return None;
+        }
+        let name = &body[..len];
+        match marker {
+            '#' => compound.id = Some(name.to_string()),
+            '.' => compound.classes.push(name.to_string()),
+            ':' => state = Some(StyleState::parse(name)?),
+            _ => return None,
+        }
+        rest = &body[len..];
+    }
+    Some((compound, state))
+}
+
+/// Parses a selector: compounds joined by whitespace or `>`, with a
+/// pseudo-class allowed only on the last.
+fn parse_selector(text: &str) -> Option<StyleSelector> {
+    let spaced = text.replace('>', " > ");
+    let mut ancestors = Vec::new();
+    let mut subject: Option<CompoundSelector> = None;
+    let mut combinator = Combinator::Descendant;
+    let mut state = None;
+    for token in spaced.split_whitespace() {
+        if token == ">" {
+            if subject.is_none() || combinator == Combinator::Child {
+                return None;
+            }
+            combinator = Combinator::Child;
+            continue;
+        }
+        if state.is_some() {
+            return None;
+        }
+        let (compound, compound_state) = parse_compound(token)?;
+        if let Some(previous) = subject.replace(compound) {
+            ancestors.push((previous, combinator));
+        }
+        combinator = Combinator::Descendant;
+        state = compound_state;
+    }
+    if combinator == Combinator::Child {
+        return None;
+    }
+    Some(StyleSelector {
+        ancestors,
+        subject: subject?,
+        state,
+    })
+}
+
+/// The value of an undeclared custom property, from the theme tokens:
+/// `--space-<step>` is that spacing step in pixels, `--radius-<preset>` the
+/// rounding preset, and anything else the theme color `theme.<name>`.
+fn theme_token(name: &str) -> String {
+    if let Some(step) = name
+        .strip_prefix("space-")
+        .and_then(|n| nemo_tokens::Space::ALL.into_iter().find(|s| s.name() == n))
+    {
+        return (step.value() as i64).to_string();
+    }
+    if let Some(preset) = name
+        .strip_prefix("radius-")
+        .filter(|p| nemo_tokens::RADIUS_NAMES.contains(p))
+    {
+        return preset.to_string();
+    }
+    format!("theme.{}", name.replace('-', "_"))
+}
+
+/// Substitutes `var(--name[, fallback])` references in a declaration value:
+/// a custom property declared in `:root`, else the fallback, else the theme
+/// token of that name.
+fn substitute_vars(value: &str, vars: &HashMap<String, String>, depth: usize) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        out.push_str(&rest[..start]);
+        let inner_start = start + "var(".len();
+        // Find the matching `)`, allowing nested `var(...)` fallbacks.
+        let mut level = 1;
+        let Some(end) = rest[inner_start..].find(|c| {
+            level += match c {
+                '(' => 1,
+                ')' => -1,
+                _ => 0,
+            };
+            level == 0
+        }) else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let inner = &rest[inner_start..inner_start + end];
+        let (name, fallback) = match inner.split_once(',') {
+            Some((n, f)) => (n.trim(), Some(f.trim())),
+            None => (inner.trim(), None),
+        };
+        let name = name.trim_start_matches("--");
+        let resolved = match (vars.get(name), fallback) {
+            (Some(v), _) => v.clone(),
+            (None, Some(f)) => f.to_string(),
+            (None, None) => theme_token(name),
+        };
+        // Declared values may themselves use `var()`; bound cycles.
+        if depth < 8 {
+            out.push_str(&substitute_vars(&resolved, vars, depth + 1));
+        } else {
+            out.push_str(&resolved);
+        }
+        rest = &rest[inner_start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Normalizes a CSS property name to a nemo universal-style attribute name.
+/// A few CSS names alias to nemo's (e.g. `border-radius` → `rounded`); the rest
+/// are just kebab→snake. Returns `None` for names not in the universal set.
+fn normalize_style_prop(name: &str) -> Option<String> {
+    let normalized = match name {
+        "border-radius" => "rounded".to_string(),
+        "background-color" => "background".to_string(),
+        other => other.replace('-', "_"),
+    };
+    nemo_registry::universal_style_attributes()
+        .iter()
+        .find(|a| a.name == normalized)
+        .map(|a| a.name.to_string())
+}
+
+/// Coerces a CSS declaration value to the `Value` shape the attribute expects,
+/// per the universal attribute's `value_type`. Strips a `px` suffix for
+/// integers. Returns `None` if the value can't be coerced (caller warns/drops).
+fn coerce_style_value(attr: &str, raw: &str) -> Option<Value> {
+    let raw = raw.trim();
+    let value_type = nemo_registry::universal_style_attributes()
+        .iter()
+        .find(|a| a.name == attr)
+        .map(|a| a.value_type)
+        .unwrap_or("string");
+    match value_type {
+        "integer" => {
+            let n = raw.trim_end_matches("px").trim();
+            n.parse::<i64>().ok().map(Value::Integer)
+        }
+        "float" => raw.parse::<f64>().ok().map(Value::Float),
+        "boolean" => raw.parse::<bool>().ok().map(Value::Bool),
+        // Colors, `rounded`, `shadow`: kept as strings; `apply_layout_styles`
+        // resolves them (incl. `theme.*` / semantic roles) at render time.
+        _ => Some(Value::String(raw.to_string())),
+    }
+}
+
+/// Splits a rule body into `(name, value)` declarations, warning about
+/// malformed ones.
+fn split_declarations<'a>(body: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    body.split(';')
+        .map(str::trim)
+        .filter(|decl| !decl.is_empty())
+        .filter_map(|decl| match decl.split_once(':') {
+            Some((n, v)) => Some((n.trim(), v.trim())),
+            None => {
+                tracing::warn!(
+                    "sfc '{}': ignoring malformed style declaration '{}'",
+                    tag,
+                    decl
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses a CSS-subset `<style>` body into style rules, in the order they
+/// should be folded (most specific first, later rules before earlier ones).
+/// Comments are stripped; each `sel[, sel2] { prop: val; … }` block is parsed.
+/// Unsupported selectors and declarations are warned about and skipped. `tag`
+/// is only for diagnostics.
+fn parse_style_rules(css: &str, tag: &str) -> Vec<StyleRule> {
+    // Strip /* … */ comments.
+    let mut cleaned = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        cleaned.push_str(&rest[..start]);
+        match rest[start + 2..].find("*/") {
+            Some(end) => rest = &rest[start + 2 + end + 2..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    cleaned.push_str(rest);
+
+    let mut blocks = Vec::new();
+    style_blocks(&cleaned, None, tag, &mut blocks);
+
+    // Custom properties come from top-level `:root` blocks, wherever they
+    // appear.
+    let is_root = |selectors: &str| selectors.split(',').any(|s| s.trim() == ":root");
+    let mut vars = HashMap::new();
+    for block in &blocks {
+        if is_root(block.selectors) && block.media.is_none() {
+            for (name, value) in split_declarations(block.body, tag) {
+                if let Some(var) = name.strip_prefix("--") {
+                    vars.insert(var.to_string(), value.to_string());
+                }
+            }
+        }
+    }
+
+    let mut rules = Vec::new();
+    for block in blocks {
+        let selectors_part = block.selectors;
+        if is_root(selectors_part) {
+            continue;
+        }
+        let media = block.media.map(|condition| condition.to_string());
+        // Parse declarations once for this block.
+        let mut decls = Vec::new();
+        for (name, value) in split_declarations(block.body, tag) {
+            if name.starts_with("--") {
+                tracing::warn!(
+                    "sfc '{}': ignoring custom property '{}' outside :root",
+                    tag,
+                    name
+                );
+                continue;
+            }
+            let value = substitute_vars(value, &vars, 0);
+            match normalize_style_prop(name) {
+                Some(attr) => match coerce_style_value(&attr, &value) {
+                    Some(v) => decls.push((attr, v)),
+                    None => tracing::warn!(
+                        "sfc '{}': ignoring style value '{}: {}' (not coercible)",
+                        tag,
+                        name,
+                        value
+                    ),
+                },
+                None => tracing::warn!(
+                    "sfc '{}': ignoring unsupported style property '{}'",
+                    tag,
+                    name
+                ),
+            }
+        }
+        if decls.is_empty() {
+            continue;
+        }
+
+        // A block may list several comma-separated selectors; each shares decls.
+        for sel in selectors_part.split(',') {
+            let sel = sel.trim();
+            if sel.is_empty() {
+                continue;
+            }
+            let Some(selector) = parse_selector(sel) else {
+                tracing::warn!(
+                    "sfc '{}': ignoring unsupported style selector '{}' (supports type, \
+                     .class and #id parts, ' '/'>' combinators and \
+                     :hover/:active/:focus/:disabled)",
+                    tag,
+                    sel
+                );
+                continue;
+            };
+            if media.is_some() && selector.state.is_some() {
+                tracing::warn!(
+                    "sfc '{}': ignoring '{}' (pseudo-classes aren't supported inside @media)",
+                    tag,
+                    sel
+                );
+                continue;
+            }
+            let mut decls = decls.clone();
+            if selector.state.is_some_and(StyleState::is_pointer) {
+                decls.retain(|(attr, _)| {
+                    let keep = REFINABLE_PROPERTIES.contains(&attr.as_str());
+                    if !keep {
+                        tracing::warn!(
+                            "sfc '{}': ignoring '{}' in '{}' (:hover/:active set only {})",
+                            tag,
+                            attr,
+                            sel,
+                            REFINABLE_PROPERTIES.join(", ")
+                        );
+                    }
+                    keep
+                });
+            }
+            rules.push(StyleRule {
+                selector,
+                decls,
+                media: media.clone(),
+            });
+        }
+    }
+
+    // Fold-if-absent: the first rule to set an attribute wins, so fold the
+    // most specific first and, among equals, the one declared last.
+    let mut ordered: Vec<(usize, StyleRule)> = rules.into_iter().enumerate().collect();
+    ordered.sort_by_key(|(order, rule)| std::cmp::Reverse((rule.selector.specificity(), *order)));
+    ordered.into_iter().map(|(_, rule)| rule).collect()
+}
+
+/// Folds an SFC `<style>` block onto its template body: parses the CSS subset,
+/// then walks the template applying each matching rule's declarations as inline
 /// props where the attribute is absent (so template inline attrs win). Returns
 /// the template unchanged when there are no applicable rules.
 ///
@@ -2624,55 +4553,94 @@ pub(crate) fn fold_sfc_styles(body: &Value, css: &str, tag: &str) -> Value {
     if rules.is_empty() {
         return body.clone();
     }
-    apply_style_rules(body, None, &rules)
+    apply_style_rules(body, None, &mut Vec::new(), &rules)
 }
 
 /// Recursively applies style rules to a template node. `id` is the node's
 /// component-map key (its id), used for `#id` selectors; the root has none.
-/// Id rules are applied before type rules so id specificity wins under the
-/// fold-if-absent policy.
-fn apply_style_rules(node: &Value, id: Option<&str>, rules: &[StyleRule]) -> Value {
+/// `chain` holds the node's ancestors for combinators. `rules` are in fold
+/// order (see [`parse_style_rules`]).
+fn apply_style_rules(
+    node: &Value,
+    id: Option<&str>,
+    chain: &mut Vec<StyleNode>,
+    rules: &[StyleRule],
+) -> Value {
     let mut obj = match node.as_object() {
         Some(o) => o.clone(),
         None => return node.clone(),
     };
-    let node_type = obj.get("type").and_then(|v| v.as_str()).map(String::from);
+    let this = StyleNode::of(node, id);
+    // `@media` rules override the other rules but not the template's own
+    // attributes.
+    let authored: HashSet<String> = obj.keys().cloned().collect();
 
-    let matches = |sel: &StyleSelector| match sel {
-        StyleSelector::Id(want) => id == Some(want.as_str()),
-        StyleSelector::Type(want) => node_type.as_deref() == Some(want.as_str()),
-    };
-    // Two passes: id selectors first (higher specificity), then type selectors.
-    for want_id in [true, false] {
-        for rule in rules {
-            let is_id = matches!(rule.selector, StyleSelector::Id(_));
-            if is_id != want_id || !matches(&rule.selector) {
+    for rule in rules {
+        if !rule.selector.matches(&this, chain) {
+            continue;
+        }
+        if let Some(when) = &rule.media {
+            let style: indexmap::IndexMap<String, Value> = rule
+                .decls
+                .iter()
+                .filter(|(attr, _)| !authored.contains(attr))
+                .cloned()
+                .collect();
+            if style.is_empty() {
                 continue;
             }
-            for (attr, value) in &rule.decls {
-                obj.entry(attr.clone()).or_insert_with(|| value.clone());
+            let mut entry = indexmap::IndexMap::new();
+            entry.insert("when".to_string(), Value::String(when.clone()));
+            entry.insert("style".to_string(), Value::Object(style));
+            let entries = obj
+                .entry(responsive::MEDIA_STYLE.to_string())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(entries) = entries {
+                entries.push(Value::Object(entry));
+            }
+            continue;
+        }
+        // State rules fold into the state's object, applied at render time.
+        let target = match rule.selector.state {
+            None => &mut obj,
+            Some(state) => {
+                let entry = obj
+                    .entry(state.property().to_string())
+                    .or_insert_with(|| Value::Object(indexmap::IndexMap::new()));
+                match entry {
+                    Value::Object(decls) => decls,
+                    _ => continue,
+                }
             }
+        };
+        for (attr, value) in &rule.decls {
+            target.entry(attr.clone()).or_insert_with(|| value.clone());
         }
     }
 
     // Recurse into component children (object id→node, or anonymous array).
+    chain.push(this);
     match obj.get("component").cloned() {
         Some(Value::Object(children)) => {
             let mut new_children = indexmap::IndexMap::new();
             for (cid, child) in &children {
-                new_children.insert(cid.clone(), apply_style_rules(child, Some(cid), rules));
+                new_children.insert(
+                    cid.clone(),
+                    apply_style_rules(child, Some(cid), chain, rules),
+                );
             }
             obj.insert("component".to_string(), Value::Object(new_children));
         }
         Some(Value::Array(children)) => {
             let new_children: Vec<Value> = children
                 .iter()
-                .map(|c| apply_style_rules(c, None, rules))
+                .map(|c| apply_style_rules(c, None, chain, rules))
                 .collect();
             obj.insert("component".to_string(), Value::Array(new_children));
         }
         _ => {}
     }
+    chain.pop();
 
     Value::Object(obj)
 }
@@ -2684,60 +4652,55 @@ fn apply_style_rules(node: &Value, id: Option<&str>, rules: &[StyleRule]) -> Val
 /// `extra_templates` are templates registered by native plugins, merged
 /// with any templates defined in the XML config. Plugin templates are
 /// added first so XML-defined templates can override them.
-fn parse_layout_config(config: &Value, extra_templates: &TemplateMap) -> Option<LayoutConfig> {
+///
+/// A template that fails to expand is pushed onto `errors` and the layout is
+/// built unexpanded.
+fn parse_layout_config(
+    config: &Value,
+    extra_templates: &TemplateMap,
+    errors: &mut Vec<SourceError>,
+) -> Option<LayoutConfig> {
     let layout = config.get("layout")?;
-    let mut templates = extract_templates(config);
-
-    // Merge plugin-registered templates (XML templates take precedence)
-    for (name, value) in extra_templates {
-        templates
-            .entry(name.clone())
-            .or_insert_with(|| value.clone());
-    }
-
-    // Compile single-file components (`.nemo` SFCs): each imported SFC becomes a
-    // `TemplateMap` entry keyed by its tag, with template-authored `on_*`
-    // handlers rewritten to `sfc:<tag>::<fn>` and any nested SFC tags rewritten
-    // for composition. XML-defined `<templates>` take precedence on name clash.
-    let sfc_tags = collect_sfc_tags(config);
-    let sfc_props = read_sfc_props(config);
-    if !sfc_tags.is_empty() {
-        if let Some(sfc_map) = config.get("sfc").and_then(|v| v.as_object()) {
-            for (tag, def) in sfc_map {
-                if let Some(body) = def.get("template") {
-                    // Fold the scoped `<style>` block onto matching template
-                    // nodes as inline props (before tag/handler rewrites so
-                    // type/id selectors match the raw markup).
-                    let body = match def.get("style").and_then(|v| v.as_str()) {
-                        Some(css) => fold_sfc_styles(body, css, tag),
-                        None => body.clone(),
-                    };
-                    let body = rewrite_sfc_tags(&body, &sfc_tags, &sfc_props);
-                    let body = rewrite_sfc_handlers(&body, tag);
-                    templates.entry(tag.clone()).or_insert(body);
-                }
-            }
+    let compiled = match CompiledTemplates::new(config, extra_templates) {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            errors.push(SourceError {
+                message: format!("Template expansion failed: {}", e.message),
+                ..e
+            });
+            return None;
         }
-    }
+    };
 
     // Rewrite SFC tag usages in the layout into template instances before
     // expansion, so the existing expand/slot/scope pipeline handles them.
     let layout_owned;
-    let layout: &Value = if sfc_tags.is_empty() {
+    let layout: &Value = if compiled.sfc_tags.is_empty() {
         layout
     } else {
-        layout_owned = rewrite_sfc_tags(layout, &sfc_tags, &sfc_props);
+        layout_owned = rewrite_sfc_tags(layout, &compiled.sfc_tags, &compiled.sfc_specs);
         &layout_owned
     };
 
-    let expanded_layout = if templates.is_empty() {
+    let expanded_layout = if compiled.templates.is_empty() {
         layout.clone()
     } else {
         let mut stack = Vec::new();
-        expand_children(layout, &templates, &mut stack).unwrap_or_else(|e| {
-            tracing::error!("Template expansion failed: {}", e);
-            layout.clone()
-        })
+        expand_children(layout, &compiled.templates, &mut stack)
+            .and_then(|expanded| compile_list_templates(&expanded, &compiled))
+            .unwrap_or_else(|e| {
+                errors.push(SourceError {
+                    message: format!("Template expansion failed: {}", e.message),
+                    ..e
+                });
+                layout.clone()
+            })
+    };
+    // Point SFC state bindings at their instances, now that ids are final.
+    let expanded_layout = if compiled.has_sfc_state() {
+        resolve_sfc_state_refs(&expanded_layout, None, &mut Vec::new())
+    } else {
+        expanded_layout
     };
 
     // Get layout type
@@ -2752,10 +4715,156 @@ fn parse_layout_config(config: &Value, extra_templates: &TemplateMap) -> Option<
         })
         .unwrap_or(LayoutType::Stack);
 
-    // Parse root node - the layout block itself acts as a container
-    let root = parse_layout_node_as_root(&expanded_layout, &layout_type)?;
+    // Parse root node - the layout block itself acts as a container
+    let root = parse_layout_node_as_root(&expanded_layout, &layout_type)?;
+
+    Some(LayoutConfig::new(layout_type, root))
+}
+
+/// The templates a layout expands — `<templates>`, plugin-registered ones
+/// and compiled SFC bodies — with the SFC tags and specs their usages need.
+struct CompiledTemplates {
+    templates: TemplateMap,
+    sfc_tags: HashSet<String>,
+    sfc_specs: HashMap<String, SfcSpec>,
+}
+
+impl CompiledTemplates {
+    /// Collects the templates of `config`. `extra_templates` are templates
+    /// registered by native plugins; XML templates take precedence. Errors
+    /// when an SFC body binds its state in a way it can't (see
+    /// [`bind_sfc_state_refs`]).
+    fn new(config: &Value, extra_templates: &TemplateMap) -> Result<Self, SourceError> {
+        let mut templates = extract_templates(config);
+
+        // Merge plugin-registered templates (XML templates take precedence)
+        for (name, value) in extra_templates {
+            templates
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+
+        // Compile single-file components (`.nemo` SFCs): each imported SFC becomes a
+        // `TemplateMap` entry keyed by its tag, with template-authored `on_*`
+        // handlers rewritten to `sfc:<tag>::<fn>` and any nested SFC tags rewritten
+        // for composition. XML-defined `<templates>` take precedence on name clash.
+        let sfc_tags = collect_sfc_tags(config);
+        let sfc_specs = read_sfc_specs(config);
+        if !sfc_tags.is_empty() {
+            if let Some(sfc_map) = config.get("sfc").and_then(|v| v.as_object()) {
+                for (tag, def) in sfc_map {
+                    if let Some(body) = def.get("template") {
+                        // Fold the scoped `<style>` block onto matching template
+                        // nodes as inline props (before tag/handler rewrites so
+                        // type/id selectors match the raw markup).
+                        let body = match def.get("style").and_then(|v| v.as_str()) {
+                            Some(css) => fold_sfc_styles(body, css, tag),
+                            None => body.clone(),
+                        };
+                        // Bind `${state.x}` references to this SFC's own fields
+                        // (before tag rewrites fold attributes into `vars`).
+                        let body = match sfc_specs.get(tag) {
+                            Some(spec) if !spec.state.is_empty() => {
+                                bind_sfc_state_refs(&body, tag, spec)?
+                            }
+                            _ => body,
+                        };
+                        let body = rewrite_sfc_tags(&body, &sfc_tags, &sfc_specs);
+                        let body = rewrite_sfc_handlers(&body, tag);
+                        templates.entry(tag.clone()).or_insert(body);
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            templates,
+            sfc_tags,
+            sfc_specs,
+        })
+    }
+
+    /// Whether any SFC declares per-instance `<state>`.
+    fn has_sfc_state(&self) -> bool {
+        self.sfc_specs.values().any(|spec| !spec.state.is_empty())
+    }
 
-    Some(LayoutConfig::new(layout_type, root))
+    /// Expands a node built after the layout — a live `n:for` item, a
+    /// component created by a plugin — the way the layout's own nodes are
+    /// expanded. State references stay placeholders until the node's instance
+    /// is in the layout (see [`adopt_sfc_instances`]).
+    fn instantiate(&self, node: &Value) -> Result<Value, SourceError> {
+        let node = match node.get("type").and_then(|v| v.as_str()) {
+            // A plugin names a `<templates>` entry by its type.
+            Some(name) if !self.sfc_tags.contains(name) && self.templates.contains_key(name) => {
+                let mut obj = node.as_object().cloned().unwrap_or_default();
+                obj.shift_remove("type");
+                obj.insert("template".to_string(), Value::String(name.to_string()));
+                Value::Object(obj)
+            }
+            _ => rewrite_sfc_tags(node, &self.sfc_tags, &self.sfc_specs),
+        };
+        let node = expand_template(&node, &self.templates, &mut Vec::new(), None)?;
+        compile_list_templates(&node, self)
+    }
+}
+
+/// Whether `component_type` names an SFC or a `<templates>` entry of
+/// `config` rather than a built-in component.
+fn names_template(config: &Value, component_type: &str) -> bool {
+    config
+        .get("sfc")
+        .and_then(|sfc| sfc.get(component_type))
+        .or_else(|| {
+            config
+                .get("templates")
+                .and_then(|t| t.get("template"))
+                .and_then(|t| t.get(component_type))
+        })
+        .is_some()
+}
+
+/// Instantiates the loop body of every live `n:for` and the item template of
+/// every `<virtual-list>` under `node` (see [`CompiledTemplates::instantiate`]),
+/// so each item is built from plain components. The container of a looped
+/// SFC or template instance was left a bare `stack` by [`expand_template`].
+fn compile_list_templates(
+    node: &Value,
+    compiled: &CompiledTemplates,
+) -> Result<Value, SourceError> {
+    let Some(obj) = node.as_object() else {
+        return Ok(node.clone());
+    };
+    let mut result = obj.clone();
+    if let Some(Value::Object(list_binding)) = obj.get("list_binding") {
+        let mut list_binding = list_binding.clone();
+        if let Some(template) = list_binding.get("template") {
+            let template = compiled.instantiate(template)?;
+            list_binding.insert("template".to_string(), template);
+        }
+        result.insert("list_binding".to_string(), Value::Object(list_binding));
+    }
+    if obj.get("type").and_then(|v| v.as_str()) == Some("virtual_list") {
+        if let Some(template) = obj.get("item_template") {
+            result.insert("item_template".to_string(), compiled.instantiate(template)?);
+        }
+    }
+    let children = match obj.get("component") {
+        Some(Value::Object(map)) => Some(Value::Object(
+            map.iter()
+                .map(|(id, child)| Ok((id.clone(), compile_list_templates(child, compiled)?)))
+                .collect::<Result<_, SourceError>>()?,
+        )),
+        Some(Value::Array(arr)) => Some(Value::Array(
+            arr.iter()
+                .map(|child| compile_list_templates(child, compiled))
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => None,
+    };
+    if let Some(children) = children {
+        result.insert("component".to_string(), children);
+    }
+    Ok(Value::Object(result))
 }
 
 /// Parses the layout block as the root node, extracting components as children.
@@ -2815,6 +4924,10 @@ fn parse_component_from_value(value: &Value, default_id: Option<&str>) -> Option
         node = node.with_id(id);
     }
 
+    if let Some(origin) = value_origin(value) {
+        node = node.with_origin(origin);
+    }
+
     // Extract list_binding metadata (live-data n:for container).
     if let Some(lb) = obj.get("list_binding").and_then(|v| v.as_object()) {
         let source = lb
@@ -2848,7 +4961,7 @@ fn parse_component_from_value(value: &Value, default_id: Option<&str>) -> Option
     }
     for (key, val) in obj {
         match key.as_str() {
-            "type" | "id" | "list_binding" => continue,
+            "type" | "id" | "list_binding" | ORIGIN_KEY => continue,
             "component" => {
                 // Nested components - parsed as objects
                 // e.g., component: { button: {...} }
@@ -2890,37 +5003,11 @@ fn parse_component_from_value(value: &Value, default_id: Option<&str>) -> Option
                     } else {
                         vec![val.clone()]
                     };
-                    for binding_val in &binding_values {
-                        if let Some(binding_obj) = binding_val.as_object() {
-                            let source = binding_obj
-                                .get("source")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or_default()
-                                .to_string();
-                            let target = binding_obj
-                                .get("target")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or_default()
-                                .to_string();
-                            let mode = binding_obj
-                                .get("mode")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("one_way");
-                            let transform = binding_obj
-                                .get("transform")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-
-                            let mut spec = match mode {
-                                "two_way" => nemo_layout::BindingSpec::two_way(&source, &target),
-                                _ => nemo_layout::BindingSpec::one_way(&source, &target),
-                            };
-                            if let Some(t) = transform {
-                                spec = spec.with_transform(t);
-                            }
-                            node.config.bindings.push(spec);
-                        }
-                    }
+                    node.config.bindings.extend(
+                        binding_values
+                            .iter()
+                            .filter_map(nemo_layout::BindingSpec::from_value),
+                    );
                 } else {
                     // Regular property
                     node = node.with_prop(key.clone(), val.clone());
@@ -2943,6 +5030,10 @@ pub struct RuntimeContext {
     plugin_dirty_paths: Arc<RwLock<HashSet<String>>>,
     nav_intents: Arc<Mutex<Vec<NavIntent>>>,
     roundness_intents: Arc<Mutex<Vec<String>>>,
+    messages: Arc<RwLock<Option<Messages>>>,
+    locale_intents: Arc<Mutex<Vec<String>>>,
+    export_intents: Arc<Mutex<Vec<ExportIntent>>>,
+    emits: Arc<EmitQueue>,
 }
 
 impl RuntimeContext {
@@ -2958,6 +5049,10 @@ impl RuntimeContext {
         plugin_dirty_paths: Arc<RwLock<HashSet<String>>>,
         nav_intents: Arc<Mutex<Vec<NavIntent>>>,
         roundness_intents: Arc<Mutex<Vec<String>>>,
+        messages: Arc<RwLock<Option<Messages>>>,
+        locale_intents: Arc<Mutex<Vec<String>>>,
+        export_intents: Arc<Mutex<Vec<ExportIntent>>>,
+        emits: Arc<EmitQueue>,
     ) -> Self {
         Self {
             config,
@@ -2969,6 +5064,10 @@ impl RuntimeContext {
             plugin_dirty_paths,
             nav_intents,
             roundness_intents,
+            messages,
+            locale_intents,
+            export_intents,
+            emits,
         }
     }
 
@@ -2981,19 +5080,101 @@ impl RuntimeContext {
         self.data_dirty.store(true, Ordering::Release);
         self.data_notify.notify_one();
     }
-}
 
-impl PluginContext for RuntimeContext {
-    fn get_data(&self, path: &str) -> Option<PluginValue> {
-        // Read from the DataRepository under "data.<path>"
-        let data_path = nemo_data::DataPath::parse(&format!("data.{}", path)).ok()?;
-        self.data_repository
-            .get(&data_path)
-            .map(|v| value_to_plugin_value(&v))
+    /// Inserts component `id` of `component_type` under `parent_id` for
+    /// `create_component`. An SFC tag or a `<templates>` name is expanded into
+    /// its subtree as in the layout, and a new SFC instance gets its state.
+    fn insert_created(
+        &self,
+        lm: &mut LayoutManager,
+        parent_id: &str,
+        id: &str,
+        component_type: &str,
+        properties: PluginValue,
+    ) -> Result<(), PluginError> {
+        let (props, handlers) = split_props_and_handlers(properties);
+        let compiled = {
+            let config = self
+                .config
+                .try_read()
+                .map_err(|_| PluginError::ComponentFailed("config is locked".to_string()))?;
+            names_template(&config, component_type)
+                .then(|| CompiledTemplates::new(&config, &TemplateMap::new()))
+                .transpose()
+                .map_err(|e| PluginError::ComponentFailed(e.message))?
+        };
+        let Some(compiled) = compiled else {
+            return lm
+                .insert_component(id, component_type, Some(parent_id), props, handlers)
+                .map_err(|e| PluginError::ComponentFailed(e.to_string()));
+        };
+
+        let mut node: indexmap::IndexMap<String, Value> = props.into_iter().collect();
+        node.insert(
+            "type".to_string(),
+            Value::String(component_type.to_string()),
+        );
+        for (event, handler) in handlers {
+            node.insert(format!("on_{}", event), Value::String(handler));
+        }
+        let tree = compiled
+            .instantiate(&Value::Object(node))
+            .map_err(|e| PluginError::ComponentFailed(e.message))?;
+        lm.insert_tree(id, Some(parent_id), &tree)
+            .map_err(|e| PluginError::ComponentFailed(e.to_string()))?;
+        let paths = adopt_sfc_instances(lm, &compiled.sfc_specs, &self.data_repository);
+        if let Ok(mut dirty) = self.plugin_dirty_paths.write() {
+            dirty.extend(paths);
+        }
+        Ok(())
     }
 
-    fn set_data(&self, path: &str, value: PluginValue) -> Result<(), PluginError> {
-        let full_path = format!("data.{}", path);
+    /// The repository path of `field` in the state of the SFC instance whose
+    /// script handler is running: the instance enclosing the component the
+    /// handler was called for. Errors outside an SFC handler and for a field
+    /// the SFC's `<state>` block doesn't declare.
+    fn instance_state_path(&self, field: &str) -> Result<String, PluginError> {
+        let running = self
+            .emits
+            .running
+            .lock()
+            .expect("emit queue poisoned")
+            .last()
+            .cloned();
+        let Some((tag, component_id)) = running.and_then(|(script_id, component_id)| {
+            Some((script_id.strip_prefix("sfc:")?.to_string(), component_id))
+        }) else {
+            return Err(PluginError::InvalidConfig(
+                "self.state is only available in a single-file component's script".to_string(),
+            ));
+        };
+        let declared = self.config.try_read().is_ok_and(|config| {
+            get_nested_value(&config, &format!("sfc.{}.state.{}", tag, field)).is_some()
+        });
+        if !declared {
+            return Err(PluginError::InvalidConfig(format!(
+                "'{}' is not declared in the <state> of sfc '{}'",
+                field, tag
+            )));
+        }
+        let lm = self
+            .layout_manager
+            .try_read()
+            .map_err(|_| PluginError::InvalidConfig("layout is locked".to_string()))?;
+        find_sfc_instance(&lm, &component_id, &tag)
+            .map(|instance| sfc_state_path(&instance.id, field))
+            .ok_or_else(|| {
+                PluginError::InvalidConfig(format!(
+                    "'{}' is not inside an instance of sfc '{}'",
+                    component_id, tag
+                ))
+            })
+    }
+
+    /// Writes `value` at the full repository `path` (`data.*` / `state.*` /
+    /// `var.sfc.*`) and marks it for binding propagation. Shared by
+    /// `set_data`, `set_state` and `set_instance_state`.
+    fn write_path(&self, full_path: String, value: PluginValue) -> Result<(), PluginError> {
         let data_path = nemo_data::DataPath::parse(&full_path)
             .map_err(|e| PluginError::InvalidConfig(e.to_string()))?;
         let config_value = plugin_value_to_config_value(value);
@@ -3008,6 +5189,31 @@ impl PluginContext for RuntimeContext {
         self.data_notify.notify_one();
         Ok(())
     }
+}
+
+impl PluginContext for RuntimeContext {
+    fn get_data(&self, path: &str) -> Option<PluginValue> {
+        // Read from the DataRepository under "data.<path>"
+        let data_path = nemo_data::DataPath::parse(&format!("data.{}", path)).ok()?;
+        self.data_repository
+            .get(&data_path)
+            .map(|v| value_to_plugin_value(&v))
+    }
+
+    fn set_data(&self, path: &str, value: PluginValue) -> Result<(), PluginError> {
+        self.write_path(format!("data.{}", path), value)
+    }
+
+    fn get_state(&self, path: &str) -> Option<PluginValue> {
+        let data_path = nemo_data::DataPath::parse(&format!("state.{}", path)).ok()?;
+        self.data_repository
+            .get(&data_path)
+            .map(|v| value_to_plugin_value(&v))
+    }
+
+    fn set_state(&self, path: &str, value: PluginValue) -> Result<(), PluginError> {
+        self.write_path(format!("state.{}", path), value)
+    }
 
     fn emit_event(&self, event_type: &str, payload: PluginValue) {
         let json_value = plugin_value_to_json(payload);
@@ -3068,10 +5274,13 @@ impl PluginContext for RuntimeContext {
     ) -> Result<String, PluginError> {
         if let Ok(mut layout_manager) = self.layout_manager.try_write() {
             let id = layout_manager.generate_dynamic_id();
-            let (props, handlers) = split_props_and_handlers(properties);
-            layout_manager
-                .insert_component(&id, component_type, Some(parent_id), props, handlers)
-                .map_err(|e| PluginError::ComponentFailed(e.to_string()))?;
+            self.insert_created(
+                &mut layout_manager,
+                parent_id,
+                &id,
+                component_type,
+                properties,
+            )?;
             self.data_dirty.store(true, Ordering::Release);
             self.data_notify.notify_one();
             Ok(id)
@@ -3090,16 +5299,13 @@ impl PluginContext for RuntimeContext {
         properties: PluginValue,
     ) -> Result<(), PluginError> {
         if let Ok(mut layout_manager) = self.layout_manager.try_write() {
-            let (props, handlers) = split_props_and_handlers(properties);
-            layout_manager
-                .insert_component(
-                    component_id,
-                    component_type,
-                    Some(parent_id),
-                    props,
-                    handlers,
-                )
-                .map_err(|e| PluginError::ComponentFailed(e.to_string()))?;
+            self.insert_created(
+                &mut layout_manager,
+                parent_id,
+                component_id,
+                component_type,
+                properties,
+            )?;
             self.data_dirty.store(true, Ordering::Release);
             self.data_notify.notify_one();
             Ok(())
@@ -3175,6 +5381,266 @@ impl PluginContext for RuntimeContext {
         self.data_notify.notify_one();
         Ok(())
     }
+
+    fn translate(&self, key: &str, args: PluginValue) -> Option<String> {
+        let guard = self.messages.read().ok()?;
+        let messages = guard.as_ref()?;
+        messages.lookup(key)?;
+        Some(messages.translate(key, &plugin_value_to_config_value(args)))
+    }
+
+    fn get_locale(&self) -> Option<String> {
+        let guard = self.messages.read().ok()?;
+        guard.as_ref().map(|m| m.locale().to_string())
+    }
+
+    fn set_locale(&self, locale: &str) -> Result<(), PluginError> {
+        if let Ok(mut q) = self.locale_intents.lock() {
+            q.push(locale.to_string());
+        }
+        self.data_dirty.store(true, Ordering::Release);
+        self.data_notify.notify_one();
+        Ok(())
+    }
+
+    fn export_component(&self, component_id: &str, path: &str) -> Result<(), PluginError> {
+        let component = self
+            .layout_manager
+            .try_read()
+            .map_err(|_| PluginError::ComponentFailed("Layout manager is locked".to_string()))?
+            .get_component(component_id)
+            .cloned()
+            .ok_or_else(|| {
+                PluginError::ComponentFailed(format!("no component with id '{}'", component_id))
+            })?;
+        let queued = write_or_queue_export(&component, Path::new(path), &self.export_intents)
+            .map_err(|e| PluginError::ComponentFailed(format!("{:#}", e)))?;
+        if queued {
+            self.data_dirty.store(true, Ordering::Release);
+            self.data_notify.notify_one();
+        }
+        Ok(())
+    }
+
+    fn emit_component_event(&self, event: &str, payload: PluginValue) -> Result<(), PluginError> {
+        let running = self
+            .emits
+            .running
+            .lock()
+            .expect("emit queue poisoned")
+            .last()
+            .cloned();
+        let Some((script_id, component_id)) =
+            running.filter(|(script_id, _)| script_id.starts_with("sfc:"))
+        else {
+            return Err(PluginError::InvalidConfig(
+                "emit() can only be called from a single-file component's script".to_string(),
+            ));
+        };
+        self.emits
+            .pending
+            .lock()
+            .expect("emit queue poisoned")
+            .push(EmitIntent {
+                script_id,
+                component_id,
+                event: event.replace('-', "_"),
+                payload: plugin_value_to_config_value(payload),
+            });
+        Ok(())
+    }
+
+    fn get_instance_state(&self, field: &str) -> Option<PluginValue> {
+        let path = self.instance_state_path(field).ok()?;
+        let data_path = nemo_data::DataPath::parse(&path).ok()?;
+        self.data_repository
+            .get(&data_path)
+            .map(|v| value_to_plugin_value(&v))
+    }
+
+    fn set_instance_state(&self, field: &str, value: PluginValue) -> Result<(), PluginError> {
+        let path = self.instance_state_path(field)?;
+        self.write_path(path, value)
+    }
+}
+
+/// Writes a CSV/JSON export of `component` to `path`, or queues a PNG one.
+/// Returns whether a capture was queued (and the UI needs waking).
+fn write_or_queue_export(
+    component: &BuiltComponent,
+    path: &Path,
+    export_intents: &Mutex<Vec<ExportIntent>>,
+) -> Result<bool> {
+    match export::format_for(component, path)? {
+        ExportFormat::Png => {
+            export_intents
+                .lock()
+                .expect("export_intents poisoned")
+                .push(ExportIntent {
+                    component_id: component.id.clone(),
+                    path: path.to_path_buf(),
+                });
+            Ok(true)
+        }
+        format => export::write_data(component, path, format).map(|()| false),
+    }
+}
+
+/// Readies SFC instances built into `lm` after the layout was parsed — live
+/// `n:for` items, components created by plugins — and seeds every
+/// instance's `<state>`:
+///
+/// * each binding still on an [`SFC_STATE_REF`] placeholder is moved to the
+///   state path of the nearest enclosing instance of the placeholder's tag;
+/// * each `<state>` field of an instance that has no value yet gets its
+///   declared initial value.
+///
+/// Returns the paths bound or seeded, whose bindings need the current value.
+fn adopt_sfc_instances(
+    lm: &mut LayoutManager,
+    specs: &HashMap<String, SfcSpec>,
+    repository: &DataRepository,
+) -> HashSet<String> {
+    let mut paths = HashSet::new();
+
+    let mut placeholders = Vec::new();
+    for id in lm.component_ids() {
+        for binding in lm.bindings().bindings_for_component(&id) {
+            let Some((tag, field)) = binding
+                .source
+                .strip_prefix(SFC_STATE_REF)
+                .and_then(|rest| rest.split_once(':'))
+            else {
+                continue;
+            };
+            match find_sfc_instance(lm, &id, tag) {
+                Some(instance) => placeholders.push((
+                    binding.id,
+                    binding.target.clone(),
+                    binding.mode,
+                    binding.transform.clone(),
+                    sfc_state_path(&instance.id, field),
+                )),
+                None => tracing::warn!(
+                    "state.{} of sfc '{}' is used outside an instance with an id",
+                    field,
+                    tag
+                ),
+            }
+        }
+    }
+    for (id, target, mode, transform, path) in placeholders {
+        let bindings = lm.bindings_mut();
+        bindings.unbind(id);
+        bindings.bind(path.clone(), target, mode, transform);
+        paths.insert(path);
+    }
+
+    for id in lm.component_ids() {
+        let Some(spec) = lm
+            .get_component(&id)
+            .and_then(|c| c.properties.get(SFC_INSTANCE))
+            .and_then(|tag| specs.get(tag.as_str()?))
+        else {
+            continue;
+        };
+        for (field, initial) in &spec.state {
+            let path = sfc_state_path(&id, field);
+            let Ok(data_path) = nemo_data::DataPath::parse(&path) else {
+                continue;
+            };
+            if repository.get(&data_path).is_some() {
+                continue;
+            }
+            match repository.set(&data_path, initial.clone()) {
+                Ok(()) => {
+                    paths.insert(path);
+                }
+                Err(e) => tracing::warn!("Failed to seed {}: {}", path, e),
+            }
+        }
+    }
+    paths
+}
+
+/// The instance of SFC `tag` that `component_id` belongs to: the component
+/// itself or its nearest ancestor marked as an instance of `tag`.
+fn find_sfc_instance<'a>(
+    lm: &'a LayoutManager,
+    component_id: &str,
+    tag: &str,
+) -> Option<&'a BuiltComponent> {
+    let mut current = lm.get_component(component_id);
+    while let Some(component) = current {
+        if component
+            .properties
+            .get(SFC_INSTANCE)
+            .and_then(|v| v.as_str())
+            == Some(tag)
+        {
+            return Some(component);
+        }
+        current = component
+            .parent
+            .as_deref()
+            .and_then(|parent| lm.get_component(parent));
+    }
+    None
+}
+
+/// Splits a handler reference into `(script_id, function_name)`.
+///
+/// Handler format: `"script_id::function_name"`, or just `"function_name"`
+/// for the default `handlers` script.
+fn split_handler(handler: &str) -> (&str, &str) {
+    match handler.split_once("::") {
+        Some((script_id, function_name)) => (script_id, function_name),
+        None => ("handlers", handler),
+    }
+}
+
+/// Where a `.rhai` file's source starts: its first line.
+fn file_start(path: &Path) -> SourceLocation {
+    SourceLocation::new(path.display().to_string(), 1, 1)
+}
+
+/// Locates a script error at the line Rhai reports, counted from the
+/// script's `start` in the app's source, or at `start` itself when Rhai
+/// doesn't say.
+fn locate_script_error(
+    message: String,
+    err: &ExtensionError,
+    start: Option<&SourceLocation>,
+) -> SourceError {
+    let error = SourceError::new(format!("{}: {}", message, err));
+    match (start, err.script_position()) {
+        (Some(start), Some((_, line, column))) => error.at(script_location(start, line, column)),
+        _ => error.or_at(start),
+    }
+}
+
+/// Maps a 1-based `line:column` in a script to the app's source, given where
+/// the script starts. Only its first line shares a row with the text before
+/// it, so only that line's column is offset.
+fn script_location(start: &SourceLocation, line: usize, column: usize) -> SourceLocation {
+    let line = line.max(1);
+    let column = if line == 1 {
+        start.column + column.max(1) - 1
+    } else {
+        column
+    };
+    SourceLocation::new(start.file.clone(), start.line + line - 1, column)
+}
+
+/// Inserts `prefix` and the path of every nested object entry under it, so
+/// bindings on any depth (`state.window.w`) pick up a wholesale write.
+fn collect_value_paths(prefix: &str, value: &Value, paths: &mut HashSet<String>) {
+    paths.insert(prefix.to_string());
+    if let Value::Object(obj) = value {
+        for (key, child) in obj {
+            collect_value_paths(&format!("{}.{}", prefix, key), child, paths);
+        }
+    }
 }
 
 /// Converts a nemo_config::Value to a PluginValue.
@@ -3407,7 +5873,8 @@ mod sfc_tests {
     #[test]
     fn test_sfc_multi_instance_id_scoping_and_slots() {
         let config = sfc_config();
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let root = layout.root;
 
         // Two cards + one button.
@@ -3480,7 +5947,8 @@ mod sfc_tests {
             ),
         ]);
 
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let labels: Vec<Option<String>> = layout
             .root
             .children
@@ -3503,6 +5971,158 @@ mod sfc_tests {
         );
     }
 
+    #[test]
+    fn test_sfc_typed_and_bound_props() {
+        let template = obj(vec![
+            ("type", s("panel")),
+            (
+                "component",
+                obj(vec![
+                    (
+                        "bar",
+                        obj(vec![("type", s("progress")), ("value", s("${pct}"))]),
+                    ),
+                    (
+                        "caption",
+                        obj(vec![("type", s("label")), ("text", s("CPU value ${pct}%"))]),
+                    ),
+                    (
+                        "ticks",
+                        obj(vec![("type", s("label")), ("text", s("Marks: ${marks}"))]),
+                    ),
+                ]),
+            ),
+        ]);
+        let props = Value::Array(vec![
+            obj(vec![("name", s("pct")), ("type", s("int"))]),
+            obj(vec![
+                ("name", s("marks")),
+                ("type", s("array")),
+                ("default", Value::Array(vec![Value::Integer(50)])),
+            ]),
+        ]);
+        let config = obj(vec![
+            (
+                "sfc",
+                obj(vec![(
+                    "gauge",
+                    obj(vec![("template", template), ("props", props)]),
+                )]),
+            ),
+            (
+                "layout",
+                obj(vec![
+                    ("type", s("stack")),
+                    (
+                        "component",
+                        obj(vec![
+                            (
+                                "g1",
+                                obj(vec![("type", s("gauge")), ("pct", Value::Integer(42))]),
+                            ),
+                            (
+                                "g2",
+                                obj(vec![("type", s("gauge")), (":pct", s("data.cpu.pct"))]),
+                            ),
+                        ]),
+                    ),
+                ]),
+            ),
+        ]);
+
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
+        fn find<'a>(node: &'a LayoutNode, id: &str) -> Option<&'a LayoutNode> {
+            if node.id.as_deref() == Some(id) {
+                return Some(node);
+            }
+            node.children.iter().find_map(|c| find(c, id))
+        }
+        let node = |id: &str| find(&layout.root, id).unwrap_or_else(|| panic!("no node '{id}'"));
+        let prop = |id: &str, key: &str| node(id).config.properties.get(key).cloned();
+
+        // Static: a whole `${pct}` keeps the coerced Integer; embedded uses
+        // interpolate as text, and an array prop as JSON.
+        assert_eq!(prop("g1_bar", "value"), Some(Value::Integer(42)));
+        assert_eq!(prop("g1_caption", "text"), Some(s("CPU value 42%")));
+        assert_eq!(prop("g1_ticks", "text"), Some(s("Marks: [50]")));
+
+        // Bound: both uses follow `data.cpu.pct`, coerced to `int` first.
+        let bindings = |id: &str| node(id).config.bindings.clone();
+        let bar = bindings("g2_bar");
+        assert_eq!(bar.len(), 1);
+        assert_eq!(
+            (bar[0].source.as_str(), bar[0].target.as_str()),
+            ("data.cpu.pct", "value")
+        );
+        assert_eq!(
+            bar[0].transform,
+            Some(prop_transform("pct", "int", &[], None, None))
+        );
+        assert_eq!(prop("g2_bar", "value"), None);
+        let caption = bindings("g2_caption");
+        assert_eq!(caption[0].source, "data.cpu.pct");
+        assert_eq!(
+            caption[0].transform,
+            Some(prop_transform(
+                "pct",
+                "int",
+                &[],
+                None,
+                Some(&concat_transform("CPU value ", "%"))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sfc_attribute_binds_one_bound_prop() {
+        let template = obj(vec![
+            ("type", s("label")),
+            ("text", s("${used} of ${total}")),
+            (ORIGIN_KEY, s("usage.nemo:2:3")),
+        ]);
+        let props = Value::Array(vec![
+            obj(vec![("name", s("used"))]),
+            obj(vec![("name", s("total"))]),
+        ]);
+        let config = obj(vec![
+            (
+                "sfc",
+                obj(vec![(
+                    "usage",
+                    obj(vec![("template", template), ("props", props)]),
+                )]),
+            ),
+            (
+                "layout",
+                obj(vec![
+                    ("type", s("stack")),
+                    (
+                        "component",
+                        obj(vec![(
+                            "u1",
+                            obj(vec![
+                                ("type", s("usage")),
+                                (":used", s("data.disk.used")),
+                                (":total", s("data.disk.total")),
+                            ]),
+                        )]),
+                    ),
+                ]),
+            ),
+        ]);
+
+        let mut errors = Vec::new();
+        parse_layout_config(&config, &TemplateMap::new(), &mut errors);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(
+            errors[0].message.contains("more than one bound prop"),
+            "{}",
+            errors[0].message
+        );
+        assert_eq!(errors[0].location.as_ref().map(|l| l.line), Some(2));
+    }
+
     #[test]
     fn test_sfc_style_folding_and_precedence() {
         // Template: a panel (with an inline padding=20) wrapping a button #inner.
@@ -3539,7 +6159,8 @@ mod sfc_tests {
             ),
         ]);
 
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let panel = &layout.root.children[0];
         assert_eq!(panel.component_type, "panel");
 
@@ -3578,6 +6199,134 @@ mod sfc_tests {
         assert!(!button.config.properties.contains_key("color"));
     }
 
+    #[test]
+    fn test_sfc_style_selectors_states_and_vars() {
+        let template = obj(vec![
+            ("type", s("stack")),
+            ("class", s("card")),
+            (
+                "component",
+                obj(vec![
+                    (
+                        "header",
+                        obj(vec![("type", s("label")), ("class", s("title"))]),
+                    ),
+                    (
+                        "body",
+                        obj(vec![
+                            ("type", s("stack")),
+                            (
+                                "component",
+                                obj(vec![
+                                    (
+                                        "ok",
+                                        obj(vec![("type", s("button")), ("class", s("primary"))]),
+                                    ),
+                                    ("plain", obj(vec![("type", s("button"))])),
+                                ]),
+                            ),
+                        ]),
+                    ),
+                ]),
+            ),
+        ]);
+        let css = ":root { --accent: theme.primary; --gap: 6px; } \
+                   .card { padding: var(--space-md); } \
+                   button { height: 30px; background: #ffffff; } \
+                   .card > button { border: 3; } \
+                   .card button { border: 1; } \
+                   button.primary { background: var(--accent); } \
+                   #plain { rounded: var(--radius-lg); margin: var(--gap); } \
+                   stack > .title { border-color: var(--text-muted, theme.border); } \
+                   button:hover { background: var(--danger); height: 40px; } \
+                   .primary:disabled { background: theme.muted; } \
+                   a > > b, :visited { padding: 1; }";
+        let folded = fold_sfc_styles(&template, css, "card");
+        let child = |path: &[&str]| {
+            let mut node = &folded;
+            for id in path {
+                node = node.get("component").and_then(|c| c.get(id)).unwrap();
+            }
+            node.clone()
+        };
+
+        // Class on the root, with a theme spacing token.
+        assert_eq!(folded.get("padding"), Some(&Value::Integer(12)));
+        // Child combinator with a var() fallback.
+        assert_eq!(
+            child(&["header"]).get("border_color"),
+            Some(&s("theme.border"))
+        );
+        let ok = child(&["body", "ok"]);
+        // `button.primary` beats `button`; a `:root` variable.
+        assert_eq!(ok.get("background"), Some(&s("theme.primary")));
+        // Descendant matches, the non-adjacent child combinator doesn't.
+        assert_eq!(ok.get("border"), Some(&Value::Integer(1)));
+        assert_eq!(ok.get("height"), Some(&Value::Integer(30)));
+        assert_eq!(
+            ok.get("disabled_style").and_then(|d| d.get("background")),
+            Some(&s("theme.muted"))
+        );
+        let plain = child(&["body", "plain"]);
+        assert_eq!(plain.get("background"), Some(&s("#ffffff")));
+        assert_eq!(plain.get("rounded"), Some(&s("lg")));
+        assert_eq!(plain.get("margin"), Some(&Value::Integer(6)));
+        assert!(plain.get("disabled_style").is_none());
+        // `:hover` keeps only refinable properties, folded for render time.
+        let hover = plain.get("hover_style").unwrap();
+        assert_eq!(hover.get("background"), Some(&s("theme.danger")));
+        assert!(hover.get("height").is_none());
+    }
+
+    #[test]
+    fn test_sfc_style_media_rules() {
+        let template = obj(vec![
+            ("type", s("stack")),
+            ("padding", Value::Integer(20)),
+            (
+                "component",
+                obj(vec![("side", obj(vec![("type", s("panel"))]))]),
+            ),
+        ]);
+        let css = "stack { padding: 4; margin: 16; } \
+                   panel { width: 320; } \
+                   @media screen and (max-width: 900px) { \
+                     stack { padding: 2; margin: 8; } \
+                     #side { width: 200px; } \
+                     panel:hover { background: red; } \
+                   } \
+                   @font-face { font-family: x; } \
+                   @media (orientation: portrait) { panel { width: 1; } }";
+        let folded = fold_sfc_styles(&template, css, "page");
+        // Plain rules fold as before.
+        assert_eq!(folded.get("padding"), Some(&Value::Integer(20)));
+        assert_eq!(folded.get("margin"), Some(&Value::Integer(16)));
+        // The media rule skips the template's own `padding`.
+        let media = folded
+            .get("media_style")
+            .and_then(|m| m.as_array())
+            .unwrap();
+        assert_eq!(media.len(), 1);
+        assert_eq!(media[0].get("when"), Some(&s("width <= 900")));
+        let style = media[0].get("style").unwrap();
+        assert!(style.get("padding").is_none());
+        assert_eq!(style.get("margin"), Some(&Value::Integer(8)));
+
+        let side = folded.get("component").and_then(|c| c.get("side")).unwrap();
+        assert_eq!(side.get("width"), Some(&Value::Integer(320)));
+        let media = side.get("media_style").and_then(|m| m.as_array()).unwrap();
+        assert_eq!(
+            media.len(),
+            1,
+            "pseudo-class and unsupported queries dropped"
+        );
+        assert_eq!(
+            media[0].get("style").and_then(|st| st.get("width")),
+            Some(&Value::Integer(200))
+        );
+        assert!(side.get("hover_style").is_none());
+    }
+
     #[test]
     fn test_sfc_named_and_default_slot_routing() {
         // An SFC with a named `header` slot and a default slot.
@@ -3635,7 +6384,8 @@ mod sfc_tests {
             ),
         ]);
 
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let panel = layout
             .root
             .children
@@ -3682,7 +6432,8 @@ mod sfc_tests {
     #[test]
     fn test_sfc_interpolation_and_scoped_handler() {
         let config = sfc_config();
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
 
         let button = layout
             .root
@@ -3706,6 +6457,407 @@ mod sfc_tests {
             Some("sfc:labeled-button::handleClick")
         );
     }
+
+    /// An SFC script's `emit()` reaches the consuming instance's `on-<event>`
+    /// handler with the instance id, once the raising handler has returned.
+    /// Undeclared events, and handlers of the root's own events, are left
+    /// alone.
+    #[test]
+    fn test_sfc_emit_routes_to_instance_handler() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("scripts")).unwrap();
+        std::fs::write(
+            dir.path().join("scripts").join("handlers.rhai"),
+            "fn archived(id, data) { set_data(\"test.archived\", id); set_data(\"test.payload\", data); }\n\
+             fn closed(id, data) { set_data(\"test.closed\", id); }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("todo-card.nemo"),
+            r#"<emits><event name="archive" /></emits>
+<template name="todo-card">
+  <panel>
+    <button id="archive" label="Archive" on-click="archive_clicked" />
+  </panel>
+</template>
+<script>
+fn archive_clicked(id, data) { emit("archive", #{ origin: id }); emit("close"); }
+</script>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<script src="./scripts"/>
+<imports><import src="./todo-card.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <todo-card id="card1" on-archive="archived" on-close="closed" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+
+        let card = rt
+            .layout_manager
+            .read()
+            .unwrap()
+            .get_component("card1")
+            .cloned()
+            .expect("instance root");
+        assert_eq!(
+            card.handlers.get("emit:archive").map(String::as_str),
+            Some("archived")
+        );
+        // `close` isn't declared, so its handler stays a plain root handler.
+        assert_eq!(
+            card.handlers.get("close").map(String::as_str),
+            Some("closed")
+        );
+
+        rt.call_handler("sfc:todo_card::archive_clicked", "card1_archive", "click");
+        let get = |p: &str| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse(p).unwrap())
+        };
+        assert_eq!(
+            get("data.test.archived").and_then(|v| v.as_str().map(String::from)),
+            Some("card1".to_string())
+        );
+        assert_eq!(
+            get("data.test.payload").and_then(|v| v.as_str().map(String::from)),
+            Some(r#"{"origin":"card1_archive"}"#.to_string())
+        );
+        assert!(get("data.test.closed").is_none());
+    }
+
+    #[test]
+    fn test_bind_sfc_state_refs() {
+        let mut spec = SfcSpec::default();
+        spec.state.insert("count".to_string(), Value::Integer(0));
+        spec.state.insert("open".to_string(), Value::Bool(false));
+        let body = obj(vec![
+            ("type", s("panel")),
+            (
+                "component",
+                obj(vec![
+                    (
+                        "total",
+                        obj(vec![
+                            ("type", s("label")),
+                            ("text", s("value: ${state.count}")),
+                        ]),
+                    ),
+                    (
+                        "raw",
+                        obj(vec![
+                            ("type", s("label")),
+                            ("text", s("${state.count}")),
+                            ("bind_visible", s("state.open")),
+                            ("bind_title", s("state.theme")),
+                        ]),
+                    ),
+                ]),
+            ),
+        ]);
+
+        let out = bind_sfc_state_refs(&body, "counter", &spec).unwrap();
+        let child = |id: &str| out.get("component").and_then(|c| c.get(id)).unwrap();
+        // Embedded: initial text plus a concat binding, which keeps the
+        // surrounding text literally.
+        let total = child("total");
+        assert_eq!(total.get("text"), Some(&s("value: 0")));
+        let binding = total.get("binding").unwrap();
+        assert_eq!(binding.get("source"), Some(&s("@sfc_state:counter:count")));
+        assert_eq!(
+            binding.get("transform"),
+            Some(&s(&concat_transform("value: ", "")))
+        );
+        // Whole value: typed initial value plus a plain bind; declared binding
+        // sources are rebased, the app's `state.theme` is not.
+        let raw = child("raw");
+        assert_eq!(raw.get("text"), Some(&Value::Integer(0)));
+        assert_eq!(raw.get("bind_text"), Some(&s("@sfc_state:counter:count")));
+        assert_eq!(raw.get("bind_visible"), Some(&s("@sfc_state:counter:open")));
+        assert_eq!(raw.get("bind_title"), Some(&s("state.theme")));
+        // Two references can't be one binding: the load fails at the node.
+        let both = obj(vec![
+            ("type", s("label")),
+            ("text", s("${state.count}/${state.open}")),
+            (ORIGIN_KEY, s("counter.nemo:4:5")),
+        ]);
+        let err = bind_sfc_state_refs(&both, "counter", &spec).unwrap_err();
+        assert!(err.message.contains("more than one state field"), "{err:?}");
+        assert_eq!(err.location.map(|l| l.line), Some(4));
+
+        let layout = obj(vec![(
+            "component",
+            obj(vec![(
+                "c1",
+                deep_merge_values(&out, &obj(vec![(SFC_INSTANCE, s("counter"))])),
+            )]),
+        )]);
+        let resolved = resolve_sfc_state_refs(&layout, None, &mut Vec::new());
+        let raw = resolved
+            .get("component")
+            .and_then(|c| c.get("c1"))
+            .and_then(|c| c.get("component"))
+            .and_then(|c| c.get("raw"))
+            .unwrap();
+        assert_eq!(raw.get("bind_text"), Some(&s("var.sfc.c1.count")));
+        assert_eq!(raw.get("bind_visible"), Some(&s("var.sfc.c1.open")));
+    }
+
+    #[test]
+    fn test_sfc_state_is_per_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("counter-card.nemo"),
+            r#"<state>
+  <field name="count" type="int" default="0" />
+  <field name="open" type="bool" default="false" />
+</state>
+<template name="counter-card">
+  <panel>
+    <label id="count" text="Count: ${state.count}" />
+    <label id="body" text="Body" n:if="state.open" />
+    <button id="bump" label="+" on-click="bump" />
+  </panel>
+</template>
+<script>
+fn bump(id, data) { self.state.count += 1; self.state.open = true; }
+</script>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<imports><import src="./counter-card.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <counter-card id="c1" />
+    <counter-card id="c2" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+
+        let get = |p: &str| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse(p).unwrap())
+        };
+        let prop = |id: &str, key: &str| {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property(id, key)
+                .cloned()
+        };
+        assert_eq!(get("var.sfc.c1.count"), Some(Value::Integer(0)));
+        assert_eq!(get("var.sfc.c2.open"), Some(Value::Bool(false)));
+        // The attribute starts at the initial value, before any update.
+        assert_eq!(
+            prop("c1_count", "text"),
+            Some(Value::String("Count: 0".into()))
+        );
+
+        rt.call_handler("sfc:counter_card::bump", "c1_bump", "click");
+        rt.apply_pending_data_updates();
+
+        assert_eq!(get("var.sfc.c1.count"), Some(Value::Integer(1)));
+        assert_eq!(get("var.sfc.c2.count"), Some(Value::Integer(0)));
+        assert_eq!(
+            prop("c1_count", "text"),
+            Some(Value::String("Count: 1".into()))
+        );
+        assert_eq!(
+            prop("c2_count", "text"),
+            Some(Value::String("Count: 0".into()))
+        );
+        assert_eq!(prop("c1_body", "visible"), Some(Value::Bool(true)));
+        assert_ne!(prop("c2_body", "visible"), Some(Value::Bool(true)));
+        // Instance state stays out of the app's `state.*` namespace.
+        assert!(get("state.count").is_none());
+    }
+
+    /// Instances a live `n:for` creates get their own state, like the ones
+    /// in the layout.
+    #[test]
+    fn test_sfc_state_in_live_n_for() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("counter-card.nemo"),
+            r#"<props><prop name="title" type="string" /></props>
+<state><field name="count" type="int" default="0" /></state>
+<template name="counter-card">
+  <panel>
+    <label id="title" text="${title}" />
+    <label id="count" text="Count: ${state.count}" />
+    <button id="bump" label="+" on-click="bump" />
+  </panel>
+</template>
+<script>
+fn bump(id, data) { self.state.count += 1; }
+</script>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<imports><import src="./counter-card.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <counter-card id="cards" n:for="card in data.cards" n:key="card.id"
+                  title="${card.id}" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+        let get = |p: &str| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse(p).unwrap())
+        };
+        let prop = |id: &str, key: &str| {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property(id, key)
+                .cloned()
+        };
+        let card = |id: &str| obj(vec![("id", s(id))]);
+        rt.data_engine
+            .repository
+            .set(
+                &nemo_data::DataPath::parse("data.cards").unwrap(),
+                Value::Array(vec![card("a"), card("b")]),
+            )
+            .unwrap();
+        rt.plugin_dirty_paths
+            .write()
+            .unwrap()
+            .insert("data.cards".to_string());
+        rt.data_dirty.store(true, Ordering::Release);
+        rt.apply_pending_data_updates();
+
+        assert_eq!(get("var.sfc.cards_a.count"), Some(Value::Integer(0)));
+        assert_eq!(get("var.sfc.cards_b.count"), Some(Value::Integer(0)));
+        assert_eq!(prop("cards_b_title", "text"), Some(s("b")));
+        assert_eq!(prop("cards_a_count", "text"), Some(s("Count: 0")));
+
+        rt.call_handler("sfc:counter_card::bump", "cards_a_bump", "click");
+        rt.apply_pending_data_updates();
+
+        assert_eq!(get("var.sfc.cards_a.count"), Some(Value::Integer(1)));
+        assert_eq!(prop("cards_a_count", "text"), Some(s("Count: 1")));
+        assert_eq!(prop("cards_b_count", "text"), Some(s("Count: 0")));
+    }
+
+    /// `<virtual-list>` rows are layout components: their bindings receive
+    /// values, directives on the item are settled and SFCs get state.
+    #[test]
+    fn test_virtual_list_rows_join_the_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("counter-card.nemo"),
+            r#"<state><field name="count" type="int" default="0" /></state>
+<template name="counter-card">
+  <panel><label id="count" text="Count: ${state.count}" /></panel>
+</template>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<imports><import src="./counter-card.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <virtual-list id="feed" item="msg" n:key="msg.id">
+      <stack>
+        <label id="body" text="${msg.body}" />
+        <label id="flag" n:if="msg.unread" text="new" />
+        <label id="status" bind-text="state.status" />
+        <counter-card id="card" />
+      </stack>
+    </virtual-list>
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+        let template = rt
+            .layout_manager
+            .read()
+            .unwrap()
+            .get_property("feed", "item_template")
+            .cloned()
+            .unwrap();
+        let item = obj(vec![("id", Value::Integer(1)), ("body", s("hi"))]);
+        let node = nemo_layout::virtual_list::row_node(&template, "msg", &item).unwrap();
+        assert!(rt.insert_virtual_row("feed", "feed_1", &node));
+
+        rt.data_engine
+            .repository
+            .set(
+                &nemo_data::DataPath::parse("state.status").unwrap(),
+                s("online"),
+            )
+            .unwrap();
+        rt.data_dirty.store(true, Ordering::Release);
+        rt.apply_pending_data_updates();
+
+        let prop = |id: &str, key: &str| {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property(id, key)
+                .cloned()
+        };
+        assert_eq!(prop("feed_1_body", "text"), Some(s("hi")));
+        assert!(rt
+            .layout_manager
+            .read()
+            .unwrap()
+            .get_component("feed_1_flag")
+            .is_none());
+        assert_eq!(prop("feed_1_status", "text"), Some(s("online")));
+        assert_eq!(
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse("var.sfc.feed_1_card.count").unwrap()),
+            Some(Value::Integer(0))
+        );
+        assert_eq!(prop("feed_1_card_count", "text"), Some(s("Count: 0")));
+
+        rt.remove_virtual_row("feed_1");
+        assert!(rt
+            .layout_manager
+            .read()
+            .unwrap()
+            .get_component("feed_1_body")
+            .is_none());
+    }
 }
 
 #[cfg(test)]
@@ -3862,7 +7014,7 @@ mod template_tests {
         let result = expand_template(&instance, &templates, &mut stack, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.contains("Circular"), "Error was: {}", err);
+        assert!(err.message.contains("Circular"), "Error was: {}", err);
     }
 
     #[test]
@@ -3872,7 +7024,73 @@ mod template_tests {
         let mut stack = Vec::new();
         let result = expand_template(&instance, &templates, &mut stack, None);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Unknown template"));
+        assert!(result.unwrap_err().message.contains("Unknown template"));
+    }
+
+    #[test]
+    fn test_expansion_errors_carry_innermost_origin() {
+        let template = obj(vec![
+            ("type", s("panel")),
+            (ORIGIN_KEY, s("card.nemo:2:1")),
+            (
+                "component",
+                obj(vec![(
+                    "title",
+                    obj(vec![
+                        ("type", s("label")),
+                        (ORIGIN_KEY, s("card.nemo:3:5")),
+                        ("text", s("${missing}")),
+                    ]),
+                )]),
+            ),
+        ]);
+        let mut templates = TemplateMap::new();
+        templates.insert("card".to_string(), template);
+
+        let instance = obj(vec![
+            ("template", s("card")),
+            (ORIGIN_KEY, s("app.nemo:10:3")),
+            ("vars", obj(vec![("other", s("x"))])),
+        ]);
+        let err = expand_template(&instance, &templates, &mut Vec::new(), None).unwrap_err();
+        assert_eq!(err.location, Some(SourceLocation::new("card.nemo", 3, 5)));
+
+        let unknown = obj(vec![
+            ("template", s("nope")),
+            (ORIGIN_KEY, s("app.nemo:12:3")),
+        ]);
+        let err = expand_template(&unknown, &templates, &mut Vec::new(), None).unwrap_err();
+        assert_eq!(err.to_string(), "app.nemo:12:3: Unknown template: 'nope'");
+    }
+
+    #[test]
+    fn test_parsed_component_keeps_origin() {
+        let value = obj(vec![
+            ("type", s("button")),
+            (ORIGIN_KEY, s("app.nemo:42:7")),
+        ]);
+        let node = parse_component_from_value(&value, Some("go")).unwrap();
+        assert_eq!(node.origin, Some(SourceLocation::new("app.nemo", 42, 7)));
+        assert!(!node.config.properties.contains_key(ORIGIN_KEY));
+
+        // A prop that happens to be called `origin` is left alone.
+        let value = obj(vec![("type", s("label")), ("origin", s("north"))]);
+        let node = parse_component_from_value(&value, Some("compass")).unwrap();
+        assert_eq!(node.origin, None);
+        assert_eq!(node.config.properties.get("origin"), Some(&s("north")));
+    }
+
+    #[test]
+    fn test_script_location_offsets_from_script_start() {
+        let start = SourceLocation::new("card.nemo", 5, 3);
+        assert_eq!(
+            script_location(&start, 1, 4),
+            SourceLocation::new("card.nemo", 5, 6)
+        );
+        assert_eq!(
+            script_location(&start, 3, 9),
+            SourceLocation::new("card.nemo", 7, 9)
+        );
     }
 }
 
@@ -4055,7 +7273,7 @@ mod runtime_tests {
                 ),
             ]),
         )]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.children.len(), 1);
         assert_eq!(layout.root.children[0].component_type, "button");
     }
@@ -4063,14 +7281,14 @@ mod runtime_tests {
     #[test]
     fn test_parse_layout_config_dock() {
         let config = obj(vec![("layout", obj(vec![("type", s("dock"))]))]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.component_type, "dock");
     }
 
     #[test]
     fn test_parse_layout_config_missing() {
         let config = obj(vec![("app", obj(vec![]))]);
-        assert!(parse_layout_config(&config, &TemplateMap::new()).is_none());
+        assert!(parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).is_none());
     }
 
     #[test]
@@ -4088,7 +7306,7 @@ mod runtime_tests {
                 ),
             ]),
         )]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         let btn = &layout.root.children[0];
         assert_eq!(
             btn.handlers.get("click").map(|s| s.as_str()),
@@ -4114,7 +7332,7 @@ mod runtime_tests {
                 ),
             ]),
         )]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         let lbl = &layout.root.children[0];
         assert_eq!(lbl.config.bindings.len(), 1);
         assert_eq!(lbl.config.bindings[0].source, "data.sensors.temperature");
@@ -4207,6 +7425,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         // set_data should store and mark dirty
@@ -4240,6 +7462,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
         assert_eq!(ctx.get_data("nonexistent"), None);
     }
@@ -4270,6 +7496,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         assert_eq!(
@@ -4312,6 +7542,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         assert_eq!(
@@ -4368,6 +7602,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         ));
 
         let script = r###"
@@ -4449,6 +7687,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         ));
 
         let script = r###"
@@ -4533,6 +7775,10 @@ mod runtime_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         ));
 
         // The handler creates a label under `root` and stores the generated
@@ -4808,8 +8054,8 @@ mod template_tests_continued {
                 ]),
             ),
         ]);
-        let layout_config =
-            parse_layout_config(&config, &TemplateMap::new()).expect("Layout parse failed");
+        let layout_config = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new())
+            .expect("Layout parse failed");
         let root = &layout_config.root;
 
         // page_a's inner child should be "page_a_inner"
@@ -4853,8 +8099,8 @@ mod template_tests_continued {
                 ]),
             ),
         ]);
-        let layout_config =
-            parse_layout_config(&config, &TemplateMap::new()).expect("Layout parse failed");
+        let layout_config = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new())
+            .expect("Layout parse failed");
 
         let nav = &layout_config.root.children[0];
         assert_eq!(
@@ -4935,8 +8181,8 @@ mod template_tests_continued {
                 ]),
             ),
         ]);
-        let layout_config =
-            parse_layout_config(&config, &TemplateMap::new()).expect("Layout parse failed");
+        let layout_config = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new())
+            .expect("Layout parse failed");
 
         // nav_btn should be a ghost button with label
         let root = &layout_config.root;
@@ -5062,8 +8308,8 @@ mod template_vars_tests {
 
         let mut stack = Vec::new();
         let err = expand_template(&instance, &templates, &mut stack, None).unwrap_err();
-        assert!(err.contains("Undefined variable 'undefined_var'"));
-        assert!(err.contains("ns"));
+        assert!(err.message.contains("Undefined variable 'undefined_var'"));
+        assert!(err.message.contains("ns"));
     }
 
     #[test]
@@ -5164,7 +8410,7 @@ mod template_vars_tests {
 
         let mut stack = Vec::new();
         let err = expand_template(&instance, &templates, &mut stack, None).unwrap_err();
-        assert!(err.contains("must be a string"));
+        assert!(err.message.contains("must be a string"));
     }
 
     #[test]
@@ -5312,8 +8558,8 @@ mod error_path_tests {
         let result = interpolate_variables(&input, &vars, "my_template");
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.contains("Undefined variable 'missing'"));
-        assert!(err.contains("my_template"));
+        assert!(err.message.contains("Undefined variable 'missing'"));
+        assert!(err.message.contains("my_template"));
     }
 
     #[test]
@@ -5322,7 +8568,10 @@ mod error_path_tests {
         let input = s("${a} and ${b}");
         let result = interpolate_variables(&input, &vars, "tmpl");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Undefined variable 'b'"));
+        assert!(result
+            .unwrap_err()
+            .message
+            .contains("Undefined variable 'b'"));
     }
 
     #[test]
@@ -5402,6 +8651,7 @@ mod error_path_tests {
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
+            .message
             .contains("Unknown template: 'nonexistent'"));
     }
 
@@ -5417,7 +8667,7 @@ mod error_path_tests {
         let instance = obj(vec![("template", s("tmpl")), ("vars", s("not_an_object"))]);
         let result = expand_template(&instance, &templates, &mut stack, None);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("must be an object"));
+        assert!(result.unwrap_err().message.contains("must be an object"));
     }
 
     // ── parse_layout_config edge cases ───────────────────────────────
@@ -5425,35 +8675,35 @@ mod error_path_tests {
     #[test]
     fn test_parse_layout_config_unknown_type_defaults_to_stack() {
         let config = obj(vec![("layout", obj(vec![("type", s("foobar"))]))]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.component_type, "stack");
     }
 
     #[test]
     fn test_parse_layout_config_missing_type_defaults_to_stack() {
         let config = obj(vec![("layout", obj(vec![]))]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.component_type, "stack");
     }
 
     #[test]
     fn test_parse_layout_config_grid_type() {
         let config = obj(vec![("layout", obj(vec![("type", s("grid"))]))]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.component_type, "grid");
     }
 
     #[test]
     fn test_parse_layout_config_tiles_type() {
         let config = obj(vec![("layout", obj(vec![("type", s("tiles"))]))]);
-        let layout = parse_layout_config(&config, &TemplateMap::new()).unwrap();
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).unwrap();
         assert_eq!(layout.root.component_type, "tiles");
     }
 
     #[test]
     fn test_parse_layout_config_no_layout_key() {
         let config = obj(vec![("app", obj(vec![("title", s("Test"))]))]);
-        assert!(parse_layout_config(&config, &TemplateMap::new()).is_none());
+        assert!(parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).is_none());
     }
 
     #[test]
@@ -5486,7 +8736,7 @@ mod error_path_tests {
             ),
         ]);
         // Should not panic — falls back to raw layout on expansion failure
-        let layout = parse_layout_config(&config, &TemplateMap::new());
+        let layout = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new());
         assert!(layout.is_some());
     }
 
@@ -5540,8 +8790,8 @@ mod error_path_tests {
         "#;
 
         let config = nemo_config::XmlParser::new().parse(xml).unwrap();
-        let layout_config =
-            parse_layout_config(&config, &TemplateMap::new()).expect("layout should parse");
+        let layout_config = parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new())
+            .expect("layout should parse");
 
         let registry = Arc::new(ComponentRegistry::new());
         register_all_builtins(&registry);
@@ -5653,6 +8903,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         // Setting property on a nonexistent component should return error
@@ -5700,6 +8954,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let props = pv_obj(vec![("label", PluginValue::String("Click".into()))]);
@@ -5754,6 +9012,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let props = pv_obj(vec![("text", PluginValue::String("Hello".into()))]);
@@ -5792,6 +9054,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let result = ctx.create_component("root", "no_such_type", PluginValue::Null);
@@ -5826,6 +9092,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let props = pv_obj(vec![("text", PluginValue::String("Temp".into()))]);
@@ -5875,6 +9145,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let result = ctx.remove_component("root");
@@ -5913,6 +9187,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
 
         let props = pv_obj(vec![
@@ -6018,6 +9296,314 @@ mod error_path_tests {
         assert!(get("data.route.main.params.id").is_none());
     }
 
+    #[test]
+    fn test_write_back_retries_when_layout_is_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<template name="app">
+  <stack id="root">
+    <input id="name" value="" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+        let value = |rt: &NemoRuntime| {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property("name", "value")
+                .cloned()
+        };
+
+        {
+            let _held = rt.layout_manager.write().unwrap();
+            rt.write_back_property("name", "value", s("typed"));
+        }
+        assert_eq!(value(&rt), Some(s("")));
+
+        rt.apply_pending_data_updates();
+        assert_eq!(value(&rt), Some(s("typed")));
+    }
+
+    #[test]
+    fn test_reorder_in_live_n_for_reorders_data_and_fires_parent_handler() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("scripts")).unwrap();
+        std::fs::write(
+            dir.path().join("scripts").join("handlers.rhai"),
+            "fn reordered(id, data) { set_data(\"test.owner\", id); }",
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<script src="./scripts"/>
+<template name="app">
+  <stack id="root">
+    <stack id="column" on-reorder="reordered">
+      <panel id="cards" n:for="card in data.board.cards" n:key="card.id"
+             draggable="true" drag-group="card">
+        <label text="${card.title}" />
+      </panel>
+    </stack>
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+        let card = |id: &str| obj(vec![("id", s(id)), ("title", s(id))]);
+        let cards = Value::Array(vec![card("a"), card("b"), card("c")]);
+        let get = |p: &str| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse(p).unwrap())
+        };
+        rt.data_engine
+            .repository
+            .set(
+                &nemo_data::DataPath::parse("data.board").unwrap(),
+                obj(vec![("cards", cards)]),
+            )
+            .unwrap();
+        rt.plugin_dirty_paths
+            .write()
+            .unwrap()
+            .insert("data.board".to_string());
+        rt.data_dirty.store(true, Ordering::Release);
+        rt.apply_pending_data_updates();
+
+        let (first, draggable) = {
+            let lm = rt.layout_manager.read().unwrap();
+            let container = lm.get_component("cards").unwrap();
+            assert!(!container.properties.contains_key("draggable"));
+            let first = container.children[0].clone();
+            let draggable = lm
+                .get_component(&first)
+                .unwrap()
+                .properties
+                .contains_key("draggable");
+            (first, draggable)
+        };
+        assert!(draggable, "instances stay draggable");
+
+        rt.reorder_child("cards", &first, 2);
+        let ids = || -> Vec<Value> {
+            get("data.board.cards")
+                .and_then(|v| v.as_array().cloned())
+                .unwrap()
+                .iter()
+                .filter_map(|c| c.get("id").cloned())
+                .collect()
+        };
+        assert_eq!(ids(), vec![s("b"), s("c"), s("a")]);
+        assert_eq!(get("data.test.owner"), Some(s("column")));
+
+        // A drop while the layout is locked isn't lost.
+        rt.apply_pending_data_updates();
+        {
+            let _held = rt.layout_manager.write().unwrap();
+            rt.reorder_child("cards", &first, 0);
+        }
+        assert_eq!(ids(), vec![s("b"), s("c"), s("a")]);
+        rt.apply_pending_data_updates();
+        assert_eq!(ids(), vec![s("a"), s("b"), s("c")]);
+    }
+
+    #[test]
+    fn test_bound_sfc_prop_values_are_coerced_and_located() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("gauge.nemo"),
+            r#"<props><prop name="pct" type="int" /></props>
+<template name="gauge">
+  <progress id="bar" value="${pct}" />
+</template>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<imports><import src="./gauge.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <gauge id="cpu" :pct="state.cpu" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+
+        let set = |value: Value| {
+            rt.data_engine
+                .repository
+                .set(&nemo_data::DataPath::parse("state.cpu").unwrap(), value)
+                .unwrap();
+            rt.plugin_dirty_paths
+                .write()
+                .unwrap()
+                .insert("state.cpu".to_string());
+            rt.data_dirty.store(true, Ordering::Release);
+            rt.apply_pending_data_updates();
+        };
+        let value = || {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property("cpu", "value")
+                .cloned()
+        };
+
+        set(s("42"));
+        assert_eq!(value(), Some(Value::Integer(42)));
+        assert!(rt.runtime_errors().is_empty());
+
+        set(s("lots"));
+        assert_eq!(value(), Some(s("lots")), "a bad value passes through");
+        // Both the root's `pct` and the progress `value` follow the prop.
+        let errors = rt.runtime_errors();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        for error in &errors {
+            assert!(error.message.contains("prop 'pct'"), "{}", error.message);
+            let location = error.location.as_ref().expect("located");
+            assert!(location.file.ends_with("app.nemo"), "{location:?}");
+            assert_eq!(location.line, 5);
+        }
+    }
+
+    #[test]
+    fn test_reported_errors_are_redacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(&config_path, r#"<app title="t"/>"#).unwrap();
+        let rt = NemoRuntime::new(&config_path).unwrap();
+
+        nemo_config::register_redaction("overlay-secret-5b1c");
+        rt.report_error(SourceError::new("login failed: overlay-secret-5b1c"));
+        let errors = rt.runtime_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "login failed: [redacted]");
+    }
+
+    #[test]
+    fn test_persisted_state_is_migrated_and_flushed_on_shutdown() {
+        use std::io::Write;
+        let dir = tempfile::tempdir().unwrap();
+        let scripts_dir = dir.path().join("scripts");
+        std::fs::create_dir(&scripts_dir).unwrap();
+        {
+            let mut f = std::fs::File::create(scripts_dir.join("handlers.rhai")).unwrap();
+            writeln!(
+                f,
+                "fn migrate(old_version, value) {{ value.volume = value.level * 10; value }}"
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            dir.path().join("state.json"),
+            r#"{"version": 1, "values": {"level": 3, "theme": "dark"}}"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        {
+            let mut f = std::fs::File::create(&config_path).unwrap();
+            write!(
+                f,
+                r#"<app title="t"/>
+<script src="./scripts"/>
+<state persist="true" version="2">
+  <field name="theme" default="light"/>
+  <field name="volume" type="int" default="0"/>
+</state>
+<template name="app"><stack id="root"/></template>"#
+            )
+            .unwrap();
+        }
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+
+        // Saved values win over defaults, after the v1 → v2 migration ran.
+        let ctx_state = |p: &str| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse(p).unwrap())
+        };
+        assert_eq!(ctx_state("state.theme"), Some(Value::String("dark".into())));
+        assert_eq!(ctx_state("state.volume"), Some(Value::Integer(30)));
+
+        rt.data_engine
+            .repository
+            .set(
+                &nemo_data::DataPath::parse("state.theme").unwrap(),
+                Value::String("solarized".into()),
+            )
+            .unwrap();
+        rt.shutdown();
+
+        let saved = nemo_data::FileStore::open(dir.path().join("state.json")).unwrap();
+        assert_eq!(saved.version(), Some(2));
+        assert_eq!(
+            nemo_data::DataStore::get(&saved, "theme"),
+            Some(Value::String("solarized".into()))
+        );
+    }
+
+    /// A hot reload builds the new runtime while the old one still runs: the
+    /// old one's pending state, flushed first, is what the new one loads.
+    #[test]
+    fn test_flushed_state_is_seen_by_a_reloaded_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<state persist="true" debounce="60000">
+  <field name="theme" default="light"/>
+</state>
+<template name="app"><stack id="root"/></template>"#,
+        )
+        .unwrap();
+        let theme = |rt: &NemoRuntime| {
+            rt.data_engine
+                .repository
+                .get(&nemo_data::DataPath::parse("state.theme").unwrap())
+        };
+
+        let old = NemoRuntime::new(&config_path).unwrap();
+        old.load_config().unwrap();
+        old.initialize().unwrap();
+        old.data_engine
+            .repository
+            .set(
+                &nemo_data::DataPath::parse("state.theme").unwrap(),
+                Value::String("dark".into()),
+            )
+            .unwrap();
+        old.flush_state();
+
+        let new = NemoRuntime::new(&config_path).unwrap();
+        new.load_config().unwrap();
+        new.initialize().unwrap();
+        assert_eq!(theme(&new), Some(Value::String("dark".into())));
+        old.shutdown();
+        new.shutdown();
+    }
+
     /// Regression guard for issue #81: the default route's `on-enter` fires
     /// once at startup. The render pass seeds the router to its `default` via
     /// [`NemoRuntime::router_current_path`], which queues the initial enter;
@@ -6129,6 +9715,10 @@ mod error_path_tests {
             Arc::new(RwLock::new(HashSet::new())),
             Arc::new(Mutex::new(Vec::new())),
             Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RwLock::new(None)),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(EmitQueue::default()),
         );
         assert_eq!(ctx.get_config("any.path"), None);
     }
@@ -6241,6 +9831,59 @@ mod error_path_tests {
         );
     }
 
+    #[test]
+    fn test_profile_overlay_layers_under_settings_and_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            dir.path().join("nemo.toml"),
+            "name = \"app\"\n[profiles.staging]\noverlays = [\"staging.xml\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &config_path,
+            r#"<app title="SFC">
+               <window title="SFC" width="400" height="300" />
+               <theme name="nord" mode="dark" />
+               </app>
+               <template name="app">
+                 <stack id="root"><label id="hi" text="Hi" /></stack>
+               </template>"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("staging.xml"),
+            r#"<nemo><app title="Staging"><theme name="dracula" /></app></nemo>"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("overrides.xml"),
+            r#"<nemo><app><theme name="gruvbox" mode="light" /></app></nemo>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.config_loader.set_profile(Some("staging"));
+        rt.config_loader.set_overrides(vec![
+            nemo_config::SetOverride::parse("app.title=CLI").unwrap()
+        ]);
+        rt.load_config().unwrap();
+
+        let get = |path: &str| {
+            rt.get_config(path)
+                .and_then(|v| v.as_str().map(String::from))
+        };
+        // Profile < overrides.xml < --set.
+        assert_eq!(get("app.theme.name").as_deref(), Some("gruvbox"));
+        assert_eq!(get("app.title").as_deref(), Some("CLI"));
+        // The entry's window survives every layer.
+        assert_eq!(get("app.window.title").as_deref(), Some("SFC"));
+
+        rt.config_loader.set_profile(Some("prod"));
+        let err = rt.load_config().unwrap_err().to_string();
+        assert!(err.contains("Unknown profile 'prod'"), "{err}");
+    }
+
     #[test]
     fn test_settings_overlay_absent_uses_entry_theme() {
         let dir = tempfile::tempdir().unwrap();
@@ -6299,7 +9942,8 @@ mod control_flow_directive_tests {
         let config = loader
             .load_xml_string(xml, "test.xml", None)
             .expect("config should load");
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let registry = Arc::new(ComponentRegistry::new());
         register_all_builtins(&registry);
         let mut manager = LayoutManager::new(registry);
@@ -6370,7 +10014,8 @@ mod control_flow_directive_tests {
         let config = loader
             .load_xml_string(xml, "test.xml", None)
             .expect("config loads");
-        let layout = parse_layout_config(&config, &TemplateMap::new()).expect("layout");
+        let layout =
+            parse_layout_config(&config, &TemplateMap::new(), &mut Vec::new()).expect("layout");
         let labels: Vec<String> = layout
             .root
             .children
@@ -6614,4 +10259,61 @@ mod proptests {
             prop_assert!(templates.is_empty());
         }
     }
+
+    #[test]
+    fn test_translated_labels_follow_the_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let locales = dir.path().join("locales");
+        std::fs::create_dir(&locales).unwrap();
+        std::fs::write(locales.join("en.ftl"), "title = Inbox\ngreeting = Hello\n").unwrap();
+        std::fs::write(
+            locales.join("de.json"),
+            r#"{"title": "Posteingang", "greeting": "Hallo"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("hello-card.nemo"),
+            r#"<props><prop name="who" /></props>
+<template name="hello-card">
+  <panel>
+    <label id="msg" text="${t('greeting')}, ${who}" />
+  </panel>
+</template>"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("app.nemo");
+        std::fs::write(
+            &config_path,
+            r#"<app title="t"/>
+<i18n dir="locales" default="en" />
+<imports><import src="./hello-card.nemo"/></imports>
+<template name="app">
+  <stack id="root">
+    <label id="title" text="${t('title')}" />
+    <hello-card id="h1" who="Ada" />
+  </stack>
+</template>"#,
+        )
+        .unwrap();
+
+        let rt = NemoRuntime::new(&config_path).unwrap();
+        rt.load_config().unwrap();
+        rt.initialize().unwrap();
+
+        let text = |id: &str| {
+            rt.layout_manager
+                .read()
+                .unwrap()
+                .get_property(id, "text")
+                .cloned()
+        };
+        assert_eq!(rt.locales(), ["de", "en"]);
+        assert_eq!(rt.locale().as_deref(), Some("en"));
+        assert_eq!(text("title"), Some(Value::String("Inbox".into())));
+        assert_eq!(text("h1_msg"), Some(Value::String("Hello, Ada".into())));
+
+        assert_eq!(rt.set_locale("de-CH").as_deref(), Some("de"));
+        assert_eq!(text("title"), Some(Value::String("Posteingang".into())));
+        assert_eq!(text("h1_msg"), Some(Value::String("Hallo, Ada".into())));
+    }
 }
@@ -1,4 +1,6 @@
-use gpui::actions;
+use gpui::{actions, Action};
+
+use super::command_palette::AppCommand;
 
 actions!(
     nemo,
@@ -12,5 +14,24 @@ actions!(
         OpenSettings,
         CloseSettings,
         ToggleDevPanel,
+        ToggleCommandPalette,
     ]
 );
+
+/// Runs an app `<keymap>` handler; bound per key by
+/// [`super::keymap::bind_app_keymap`].
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = nemo, no_json)]
+pub struct RunKeymapHandler {
+    pub handler: String,
+    /// The binding as written, passed to the handler as event data.
+    pub binding: String,
+}
+
+/// Runs an app `<commands>` command from its `key`; bound per command by
+/// [`super::keymap::bind_app_keymap`].
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = nemo, no_json)]
+pub struct RunCommand {
+    pub command: AppCommand,
+}
@@ -0,0 +1,317 @@
+//! App command palette.
+//!
+//! An app declares its commands in a top-level `<commands>` block:
+//!
+//! ```xml
+//! <commands>
+//!   <command name="New Task" key="ctrl-n" on-run="new_task" />
+//!   <command id="sync" name="Sync Now" description="Pull from the server"
+//!            key="ctrl-shift-s" on-run="sync" />
+//! </commands>
+//! ```
+//!
+//! `ctrl-shift-p` (`cmd-shift-p` on macOS) opens a palette that fuzzy
+//! filters the commands by name; a command's `key` also runs it directly,
+//! except while a text input has focus (see
+//! [`super::keymap::bind_app_keymap`]).
+//! Handlers are called with `"commands"` as the component id and the
+//! command's `id` (default: its name) as the event data.
+
+use std::sync::Arc;
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::input::{Input as GpuiInput, InputEvent, InputState};
+use gpui_component::kbd::Kbd;
+use gpui_component::label::Label;
+use gpui_component::{h_flex, v_flex, ActiveTheme, WindowExt as _};
+
+use crate::runtime::NemoRuntime;
+
+/// Component id handlers receive when a command runs.
+const COMMANDS_TARGET: &str = "commands";
+
+/// A `<command>` declared by the app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppCommand {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub key: Option<String>,
+    pub handler: Option<String>,
+}
+
+impl AppCommand {
+    /// The parsed `key`, or `None` when unset or not a valid keystroke.
+    pub fn keystroke(&self) -> Option<Keystroke> {
+        Keystroke::parse(self.key.as_deref()?).ok()
+    }
+
+    /// Calls the command's `on-run` handler.
+    pub fn run(&self, runtime: &NemoRuntime) {
+        if let Some(handler) = &self.handler {
+            runtime.call_handler(handler, COMMANDS_TARGET, &self.id);
+        }
+    }
+}
+
+/// Reads the app's `<commands>` into a list of [`AppCommand`]. Returns empty
+/// when none are declared.
+pub fn commands_from_config(runtime: &NemoRuntime) -> Vec<AppCommand> {
+    let Some(commands) = runtime
+        .get_config("commands")
+        .and_then(|v| v.as_array().cloned())
+    else {
+        return Vec::new();
+    };
+
+    commands
+        .iter()
+        .filter_map(|command| {
+            let obj = command.as_object()?;
+            let text = |key: &str| {
+                obj.get(key)
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+            };
+            let name = text("name")?;
+            Some(AppCommand {
+                id: text("id").unwrap_or_else(|| name.clone()),
+                name,
+                description: text("description"),
+                key: text("key"),
+                handler: text("on_run"),
+            })
+        })
+        .collect()
+}
+
+/// Whether a pressed keystroke triggers `binding`.
+pub fn keystroke_matches(binding: &Keystroke, pressed: &Keystroke) -> bool {
+    binding.modifiers == pressed.modifiers && binding.key.eq_ignore_ascii_case(&pressed.key)
+}
+
+/// Fuzzy match score of `query` against `name`: every query character must
+/// appear in order. Runs of consecutive characters and matches at word starts
+/// score higher; `None` when it doesn't match.
+pub fn match_score(name: &str, query: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + name[pos..].iter().position(|c| *c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(name[found - 1], ' ' | '-' | '_' | '.' | ':') {
+            score += 8;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+/// Indices of the commands matching `query`, best first. Ties keep their
+/// declared order, as does an empty query.
+pub fn filter_commands(commands: &[AppCommand], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(usize, i32)> = commands
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| match_score(&c.name, query).map(|s| (i, s)))
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// The palette shown in a dialog: a filter input over the app's commands.
+pub struct CommandPalette {
+    runtime: Arc<NemoRuntime>,
+    commands: Vec<AppCommand>,
+    input: Entity<InputState>,
+    matches: Vec<usize>,
+    selected: usize,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CommandPalette {
+    pub fn new(
+        runtime: Arc<NemoRuntime>,
+        commands: Vec<AppCommand>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Run a command…"));
+        let subscription = cx.subscribe_in(
+            &input,
+            window,
+            |this, input, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => {
+                    let query = input.read(cx).value().to_string();
+                    this.matches = filter_commands(&this.commands, &query);
+                    this.selected = 0;
+                    cx.notify();
+                }
+                InputEvent::PressEnter { .. } => {
+                    if let Some(ix) = this.matches.get(this.selected).copied() {
+                        this.run(ix, window, cx);
+                    }
+                }
+                _ => {}
+            },
+        );
+        let matches = (0..commands.len()).collect();
+        Self {
+            runtime,
+            commands,
+            input,
+            matches,
+            selected: 0,
+            _subscriptions: vec![subscription],
+        }
+    }
+
+    /// Focuses the filter input.
+    pub fn focus(&self, window: &mut Window, cx: &mut App) {
+        self.input.update(cx, |state, cx| state.focus(window, cx));
+    }
+
+    /// Closes the palette and runs the command at `ix`.
+    fn run(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(command) = self.commands.get(ix).cloned() else {
+            return;
+        };
+        window.close_dialog(cx);
+        command.run(&self.runtime);
+        window.refresh();
+    }
+
+    fn move_selection(&mut self, down: bool, cx: &mut Context<Self>) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.selected = if down {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+        cx.notify();
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let active = cx.theme().colors.list_active;
+        let hover = cx.theme().colors.list_hover;
+        let muted = cx.theme().colors.muted_foreground;
+
+        let rows = self.matches.iter().enumerate().map(|(row, &ix)| {
+            let command = &self.commands[ix];
+            h_flex()
+                .id(("command", ix))
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .cursor_pointer()
+                .when(row == self.selected, |this| this.bg(active))
+                .hover(move |s| s.bg(hover))
+                .on_click(cx.listener(move |this, _, window, cx| this.run(ix, window, cx)))
+                .child(v_flex().child(Label::new(command.name.clone())).when_some(
+                    command.description.clone(),
+                    |this, description| {
+                        this.child(div().text_xs().text_color(muted).child(description))
+                    },
+                ))
+                .when_some(command.keystroke(), |this, keystroke| {
+                    this.child(Kbd::new(keystroke))
+                })
+        });
+        let rows: Vec<_> = rows.collect();
+
+        v_flex()
+            .gap_2()
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                match event.keystroke.key.as_str() {
+                    "up" => this.move_selection(false, cx),
+                    "down" => this.move_selection(true, cx),
+                    _ => return,
+                }
+                cx.stop_propagation();
+            }))
+            .child(GpuiInput::new(&self.input))
+            .child(
+                v_flex()
+                    .id("command-palette-list")
+                    .max_h(px(360.))
+                    .overflow_y_scroll()
+                    .children(rows)
+                    .when(self.matches.is_empty(), |this| {
+                        this.child(div().p_2().text_color(muted).child("No matching commands"))
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str) -> AppCommand {
+        AppCommand {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            key: None,
+            handler: None,
+        }
+    }
+
+    #[test]
+    fn test_match_score() {
+        assert_eq!(match_score("New Task", ""), Some(0));
+        assert!(match_score("New Task", "nt").is_some());
+        assert!(match_score("New Task", "tn").is_none());
+        assert!(match_score("New Task", "NEW").is_some());
+        // Word starts and consecutive runs beat scattered matches.
+        assert!(match_score("Sync Now", "sn") > match_score("Reassign", "sn"));
+        assert!(match_score("Task", "tas") > match_score("Toggle Status", "tas"));
+    }
+
+    #[test]
+    fn test_filter_commands() {
+        let commands = vec![
+            command("Open Settings"),
+            command("New Task"),
+            command("Sync Now"),
+        ];
+        assert_eq!(filter_commands(&commands, ""), vec![0, 1, 2]);
+        assert_eq!(filter_commands(&commands, "s"), vec![0, 2, 1]);
+        assert_eq!(filter_commands(&commands, "task"), vec![1]);
+        assert!(filter_commands(&commands, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_keystroke_matches() {
+        let mut cmd = command("New Task");
+        cmd.key = Some("ctrl-shift-n".to_string());
+        let binding = cmd.keystroke().unwrap();
+        assert!(keystroke_matches(
+            &binding,
+            &Keystroke::parse("ctrl-shift-n").unwrap()
+        ));
+        assert!(!keystroke_matches(
+            &binding,
+            &Keystroke::parse("ctrl-n").unwrap()
+        ));
+        cmd.key = Some("ctrl-foo-bar".to_string());
+        assert!(cmd.keystroke().is_none());
+    }
+}
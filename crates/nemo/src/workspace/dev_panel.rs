@@ -450,18 +450,19 @@ fn file_icon(id: &str) -> IconName {
     }
 }
 
-/// Pushes a success toast notification.
+/// Pushes a success toast notification, with resolved secrets redacted.
 fn window_push_success(window: &mut Window, cx: &mut App, msg: impl Into<SharedString>) {
+    let msg: SharedString = msg.into();
     let toast = Toast::new()
-        .message(msg)
+        .message(SharedString::from(nemo_config::redact(&msg).into_owned()))
         .with_type(NotificationType::Success);
     window.push_notification(toast, cx);
 }
 
-/// Pushes an error toast notification.
+/// Pushes an error toast notification, with resolved secrets redacted.
 fn window_push_error(window: &mut Window, cx: &mut App, msg: &str) {
     let toast = Toast::new()
-        .message(SharedString::from(msg.to_string()))
+        .message(SharedString::from(nemo_config::redact(msg).into_owned()))
         .with_type(NotificationType::Error);
     window.push_notification(toast, cx);
 }
@@ -0,0 +1,304 @@
+//! App keyboard shortcuts declared in `<keymap>`.
+//!
+//! ```xml
+//! <keymap>
+//!   <key binding="ctrl-s" handler="on_save" when="focus:editor" />
+//!   <key binding="ctrl-k ctrl-d" handler="handlers::duplicate" />
+//! </keymap>
+//! ```
+//!
+//! Each `<key>` is compiled into a gpui [`KeyBinding`] for the
+//! [`RunKeymapHandler`] action. `when` is a gpui context predicate in which
+//! `focus:<id>` stands for "focus is inside component `<id>`"; those
+//! components get their id as a key context when rendered, so
+//! `focus:editor && !focus:search` works too.
+//!
+//! The `key` of each `<commands>` entry is bound the same way, to the
+//! [`RunCommand`] action, outside text inputs so typing isn't taken over.
+//!
+//! gpui can't remove individual bindings, so each bind bumps the
+//! [`AppKeymap`] generation and scopes its keys to that generation's key
+//! context ([`AppKeymap::context`]). Only the workspace and the current app's
+//! components carry it, so an older app's keys match nothing and the key
+//! falls through as if unbound.
+
+use std::collections::BTreeSet;
+
+use gpui::*;
+use nemo_config::Value;
+use tracing::warn;
+
+use super::actions::{RunCommand, RunKeymapHandler};
+use super::command_palette::{commands_from_config, keystroke_matches};
+use crate::runtime::NemoRuntime;
+
+/// Component id handlers receive when a key runs them.
+pub const KEYMAP_TARGET: &str = "keymap";
+
+/// Prefix of a `when` term that names a component.
+const FOCUS_PREFIX: &str = "focus:";
+
+/// Where a `<commands>` key applies: anywhere but in a text input (the key
+/// context gpui-component's inputs set).
+const COMMAND_KEY_CONTEXT: &str = "!Input";
+
+/// The workspace's own shortcuts as `(name, keystrokes)`; `secondary` is cmd
+/// on macOS and ctrl elsewhere. Keep in sync with `cx.bind_keys` in `main.rs`.
+pub const BUILTIN_SHORTCUTS: &[(&str, &str)] = &[
+    ("Open Project", "secondary-o"),
+    ("Close Project", "secondary-w"),
+    ("Reload Configuration", "secondary-shift-r"),
+    ("Toggle Light/Dark Theme", "secondary-shift-t"),
+    ("Settings", "secondary-p"),
+    ("Close Settings", "escape"),
+    ("Command Palette", "secondary-shift-p"),
+    ("Keyboard Shortcuts", "f10"),
+    ("Toggle Dev Panel (dev mode)", "secondary-shift-e"),
+    ("Quit Application", "secondary-q"),
+];
+
+/// A `<key>` declared by the app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeymapEntry {
+    pub binding: String,
+    pub handler: Option<String>,
+    pub when: Option<String>,
+}
+
+/// Reads the `keymap` array of a config.
+pub fn keymap_entries(keymap: Option<&Value>) -> Vec<KeymapEntry> {
+    let Some(keys) = keymap.and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    keys.iter()
+        .filter_map(|key| {
+            let text = |name: &str| {
+                key.get(name)
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+            };
+            Some(KeymapEntry {
+                binding: text("binding")?,
+                handler: text("handler"),
+                when: text("when"),
+            })
+        })
+        .collect()
+}
+
+/// Parses a binding: one or more space separated keystrokes.
+pub fn parse_binding(binding: &str) -> Result<Vec<Keystroke>, String> {
+    let keystrokes = binding
+        .split_whitespace()
+        .map(|part| Keystroke::parse(part).map_err(|_| format!("invalid keystroke '{part}'")))
+        .collect::<Result<Vec<_>, _>>()?;
+    if keystrokes.is_empty() {
+        return Err("empty binding".to_string());
+    }
+    Ok(keystrokes)
+}
+
+/// Whether two parsed bindings are the same key sequence.
+pub fn same_binding(a: &[Keystroke], b: &[Keystroke]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| keystroke_matches(a, b))
+}
+
+/// The component ids named by `focus:<id>` terms in a `when`.
+pub fn focus_ids(when: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut rest = when;
+    while let Some(start) = rest.find(FOCUS_PREFIX) {
+        let after = &rest[start + FOCUS_PREFIX.len()..];
+        let len = after
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(after.len());
+        ids.push(after[..len].to_string());
+        rest = &after[len..];
+    }
+    ids
+}
+
+/// Compiles a `when` into a gpui context predicate: `focus:<id>` becomes the
+/// component's key context, anything else is passed through.
+pub fn context_predicate(when: &str) -> Result<String, String> {
+    if focus_ids(when).iter().any(|id| id.is_empty()) {
+        return Err(format!("'{FOCUS_PREFIX}' needs a component id in '{when}'"));
+    }
+    let predicate = when.replace(FOCUS_PREFIX, "");
+    KeyBindingContextPredicate::parse(&predicate)
+        .map_err(|e| format!("invalid condition '{when}': {e}"))?;
+    Ok(predicate)
+}
+
+/// Ids of the components a keymap's conditions refer to.
+pub fn focus_contexts(entries: &[KeymapEntry]) -> BTreeSet<String> {
+    entries
+        .iter()
+        .filter_map(|entry| entry.when.as_deref())
+        .flat_map(focus_ids)
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Scopes a binding's context predicate to the keymap context `scope`, so it
+/// only matches while that keymap is current.
+pub fn scoped_predicate(scope: &str, predicate: Option<&str>) -> String {
+    match predicate {
+        Some(predicate) => format!("{scope} && ({predicate})"),
+        None => scope.to_string(),
+    }
+}
+
+/// Gives a component named in a `focus:` condition its id as key context,
+/// alongside the current keymap's `scope`.
+pub fn with_key_context(element: AnyElement, id: &str, scope: &str) -> AnyElement {
+    let mut context = KeyContext::default();
+    context.add(id.to_string());
+    context.add(scope.to_string());
+    div().key_context(context).child(element).into_any_element()
+}
+
+/// Generation of the bound app keymap.
+#[derive(Default)]
+pub struct AppKeymap {
+    pub generation: usize,
+}
+
+impl AppKeymap {
+    /// The key context the current generation's bindings are scoped to.
+    pub fn context(&self) -> String {
+        format!("nemo_keymap_{}", self.generation)
+    }
+
+    /// The current keymap context; empty before any keymap was bound.
+    pub fn current_context(cx: &App) -> String {
+        cx.try_global::<Self>()
+            .map(Self::context)
+            .unwrap_or_default()
+    }
+}
+
+impl Global for AppKeymap {}
+
+/// Binds the keymap and command keys of `runtime`'s app, replacing the
+/// previous app's. A `<keymap>` key wins over a command with the same key.
+/// Keys with an invalid binding or condition, or no handler, are skipped
+/// with a warning (`nemo validate` reports them).
+pub fn bind_app_keymap(runtime: Option<&NemoRuntime>, cx: &mut App) {
+    let scope = {
+        let keymap = cx.default_global::<AppKeymap>();
+        keymap.generation += 1;
+        keymap.context()
+    };
+    let Some(runtime) = runtime else {
+        return;
+    };
+
+    let mut bindings = Vec::new();
+    for command in commands_from_config(runtime) {
+        let Some(key) = command.key.clone() else {
+            continue;
+        };
+        if command.handler.is_none() {
+            warn!(
+                "commands: '{}' has no on-run handler; key skipped",
+                command.name
+            );
+            continue;
+        }
+        if let Err(e) = parse_binding(&key) {
+            warn!("commands: '{}' key '{}': {}; skipped", command.name, key, e);
+            continue;
+        }
+        bindings.push(KeyBinding::new(
+            &key,
+            RunCommand { command },
+            Some(&scoped_predicate(&scope, Some(COMMAND_KEY_CONTEXT))),
+        ));
+    }
+    for entry in keymap_entries(runtime.get_config("keymap").as_ref()) {
+        let Some(handler) = entry.handler.clone() else {
+            warn!("keymap: '{}' has no handler; skipped", entry.binding);
+            continue;
+        };
+        if let Err(e) = parse_binding(&entry.binding) {
+            warn!("keymap: '{}': {}; skipped", entry.binding, e);
+            continue;
+        }
+        let context = match entry.when.as_deref().map(context_predicate).transpose() {
+            Ok(context) => context,
+            Err(e) => {
+                warn!("keymap: '{}': {}; skipped", entry.binding, e);
+                continue;
+            }
+        };
+        bindings.push(KeyBinding::new(
+            &entry.binding,
+            RunKeymapHandler {
+                handler,
+                binding: entry.binding.clone(),
+            },
+            Some(&scoped_predicate(&scope, context.as_deref())),
+        ));
+    }
+    cx.bind_keys(bindings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keymap_entries() {
+        let value: Value = serde_json::from_str(
+            r#"[
+                {"binding": "ctrl-s", "handler": "on_save", "when": "focus:editor"},
+                {"binding": " ", "handler": "blank"},
+                {"binding": "f5"}
+            ]"#,
+        )
+        .unwrap();
+        let entries = keymap_entries(Some(&value));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].when.as_deref(), Some("focus:editor"));
+        assert_eq!(entries[1].handler, None);
+        assert!(keymap_entries(None).is_empty());
+    }
+
+    #[test]
+    fn test_context_predicate() {
+        assert_eq!(context_predicate("focus:editor").unwrap(), "editor");
+        assert_eq!(
+            context_predicate("focus:editor && !focus:search-box").unwrap(),
+            "editor && !search-box"
+        );
+        assert_eq!(
+            focus_ids("focus:editor && !focus:search-box"),
+            vec!["editor", "search-box"]
+        );
+        assert!(context_predicate("focus:").is_err());
+        assert!(context_predicate("editor &&").is_err());
+    }
+
+    #[test]
+    fn test_scoped_predicate() {
+        let scope = AppKeymap { generation: 3 }.context();
+        assert_eq!(scoped_predicate(&scope, None), "nemo_keymap_3");
+        let scoped = scoped_predicate(&scope, Some("editor || search-box"));
+        assert_eq!(scoped, "nemo_keymap_3 && (editor || search-box)");
+        assert!(KeyBindingContextPredicate::parse(&scoped).is_ok());
+        let commands = scoped_predicate(&scope, Some(COMMAND_KEY_CONTEXT));
+        assert!(KeyBindingContextPredicate::parse(&commands).is_ok());
+    }
+
+    #[test]
+    fn test_parse_binding() {
+        let a = parse_binding("ctrl-k ctrl-d").unwrap();
+        assert_eq!(a.len(), 2);
+        assert!(same_binding(&a, &parse_binding("ctrl-k  ctrl-d").unwrap()));
+        assert!(!same_binding(&a, &parse_binding("ctrl-k").unwrap()));
+        assert!(parse_binding("").is_err());
+    }
+}
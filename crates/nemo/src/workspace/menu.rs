@@ -14,7 +14,7 @@ use gpui::{Menu, MenuItem, NoAction, OsAction, SystemMenuType};
 
 use super::actions::{
     CloseProject, OpenProject, OpenSettings, QuitApp, ReloadConfig, ShowKeyboardShortcuts,
-    ToggleDevPanel, ToggleTheme,
+    ToggleCommandPalette, ToggleDevPanel, ToggleTheme,
 };
 
 /// Builds the application menu bar.
@@ -22,7 +22,10 @@ use super::actions::{
 /// `app_title` names the first (application) menu; `dev_mode` gates the
 /// dev-panel toggle so it only appears when launched via `nemo dev`.
 pub fn app_menus(app_title: String, dev_mode: bool) -> Vec<Menu> {
-    let mut view_items = vec![MenuItem::action("Toggle Theme", ToggleTheme)];
+    let mut view_items = vec![
+        MenuItem::action("Command Palette…", ToggleCommandPalette),
+        MenuItem::action("Toggle Theme", ToggleTheme),
+    ];
     if dev_mode {
         view_items.push(MenuItem::action("Toggle Dev Panel", ToggleDevPanel));
     }
@@ -13,9 +13,11 @@ use std::time::Duration;
 use tracing::info;
 
 pub mod actions;
+pub mod command_palette;
 mod dev_panel;
 mod footer_bar;
 mod header_bar;
+pub mod keymap;
 pub mod layout;
 pub mod main_view;
 pub mod menu;
@@ -24,11 +26,13 @@ pub mod settings;
 pub mod utils;
 pub mod xml_edit;
 use actions::{
-    CloseProject, CloseSettings, OpenProject, OpenSettings, QuitApp, ReloadConfig,
-    ShowKeyboardShortcuts, ToggleDevPanel, ToggleTheme,
+    CloseProject, CloseSettings, OpenProject, OpenSettings, QuitApp, ReloadConfig, RunCommand,
+    RunKeymapHandler, ShowKeyboardShortcuts, ToggleCommandPalette, ToggleDevPanel, ToggleTheme,
 };
+use command_palette::{commands_from_config, CommandPalette};
 pub use footer_bar::FooterBar;
 pub use header_bar::{menu_items_from_config, HeaderBar};
+use keymap::{bind_app_keymap, AppKeymap, BUILTIN_SHORTCUTS, KEYMAP_TARGET};
 use layout::AppLayout;
 use project_loader::{ProjectLoaderView, ProjectSelected};
 use settings::SettingsView;
@@ -49,6 +53,10 @@ pub struct WorkspaceArgs {
     /// Launch-time `<router>` starting-path override (`--route`). Applies only
     /// to the CLI-launched app, not projects later opened via the loader.
     pub initial_route: Option<String>,
+    /// `nemo.toml` profile (`--profile`) and `--set` overrides layered over
+    /// the CLI-launched app's entry, including on hot reload.
+    pub profile: Option<String>,
+    pub overrides: Vec<nemo_config::SetOverride>,
 }
 
 /// The root workspace entity that manages the application state.
@@ -72,6 +80,8 @@ pub struct Workspace {
     /// Whether the app was launched via `nemo dev` (enables the dev panel).
     pub dev_mode: bool,
     pub dev_panel_window: Option<WindowHandle<gpui_component::Root>>,
+    /// The open command palette, so a second toggle closes it.
+    pub command_palette: Option<WeakEntity<CommandPalette>>,
 }
 
 impl Workspace {
@@ -89,10 +99,23 @@ impl Workspace {
         recent.save();
 
         // A project opened via the loader is a different app than the one the
-        // CLI `--route` targeted, so don't apply the launch override here.
-        match create_runtime(&app_config_path, &self.ws_args.extension_dirs, None) {
+        // CLI `--route`, `--profile` and `--set` targeted, so don't apply the
+        // launch overrides here (nor on its later reloads).
+        self.ws_args.profile = None;
+        self.ws_args.overrides.clear();
+        let locale = self.nemo_config.lock().unwrap().app.locale.clone();
+        match create_runtime(
+            &app_config_path,
+            &self.ws_args.extension_dirs,
+            None,
+            locale.as_deref(),
+            None,
+            &[],
+        ) {
             Ok(rt) => {
+                rt.set_error_overlay(self.dev_mode);
                 apply_theme_from_runtime(&rt, cx);
+                bind_app_keymap(Some(&rt), cx);
                 let header_bar = self.create_header_bar(&rt, window, cx);
                 let footer_bar = self.create_footer_bar(&rt, window, cx);
                 let app_entity = cx.new(|cx| app::App::new(Arc::clone(&rt), window, cx));
@@ -190,11 +213,28 @@ impl Workspace {
         tracing::info!("Reloading configuration from: {:?}", config_path);
 
         // Hot-reload recreates the runtime (router state resets to defaults);
-        // `--route` is a launch-time override, not reapplied on reload.
-        match create_runtime(&config_path, &self.ws_args.extension_dirs, None) {
+        // `--route` is a launch-time override, not reapplied on reload. The
+        // profile and `--set` overrides are, so a reload re-reads the profile's
+        // overlay files.
+        let locale = self.nemo_config.lock().unwrap().app.locale.clone();
+        // The new runtime loads state.json; write the old one's pending
+        // changes first, so a reload inside the debounce window keeps them.
+        if let Some(project) = cx.try_global::<ActiveProject>() {
+            project.runtime.flush_state();
+        }
+        match create_runtime(
+            &config_path,
+            &self.ws_args.extension_dirs,
+            None,
+            locale.as_deref(),
+            self.ws_args.profile.as_deref(),
+            &self.ws_args.overrides,
+        ) {
             Ok(rt) => {
+                rt.set_error_overlay(self.dev_mode);
                 self.shutdown(cx);
                 apply_theme_from_runtime(&rt, cx);
+                bind_app_keymap(Some(&rt), cx);
                 let header_bar = self.create_header_bar(&rt, window, cx);
                 let footer_bar = self.create_footer_bar(&rt, window, cx);
                 let app_entity = cx.new(|cx| app::App::new(Arc::clone(&rt), window, cx));
@@ -216,7 +256,7 @@ impl Workspace {
                 tracing::error!("Reload failed: {}", e);
                 window.push_notification(
                     Toast::new()
-                        .message(format!("Reload failed: {}", e))
+                        .message(nemo_config::redact(&format!("Reload failed: {}", e)).into_owned())
                         .with_type(NotificationType::Error),
                     cx,
                 );
@@ -436,20 +476,81 @@ impl Workspace {
                 .w(px(420.))
                 .close_button(true)
                 .child(
-                    v_flex()
-                        .gap_t(Space::Xs)
-                        .child(shortcut_row("Open Project", "ctrl-o"))
-                        .child(shortcut_row("Close Project", "ctrl-w"))
-                        .child(shortcut_row("Reload Configuration", "ctrl-shift-r"))
-                        .child(shortcut_row("Toggle Light/Dark Theme", "ctrl-shift-t"))
-                        .child(shortcut_row("Settings", "ctrl-p"))
-                        .child(shortcut_row("Keyboard Shortcuts", "f10"))
-                        .child(shortcut_row("Toggle Dev Panel (dev mode)", "ctrl-shift-e"))
-                        .child(shortcut_row("Quit Application", "ctrl-q")),
+                    v_flex().gap_t(Space::Xs).children(
+                        BUILTIN_SHORTCUTS
+                            .iter()
+                            .map(|(name, keys)| shortcut_row(name, keys)),
+                    ),
                 )
         });
     }
 
+    /// Opens the command palette over the app's `<commands>`, or closes it
+    /// when it's already open. Does nothing without a loaded project.
+    fn toggle_command_palette(
+        &mut self,
+        _: &ToggleCommandPalette,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .command_palette
+            .take()
+            .is_some_and(|palette| palette.upgrade().is_some())
+            && window.has_active_dialog(cx)
+        {
+            window.close_dialog(cx);
+            return;
+        }
+        let Some(runtime) = cx
+            .try_global::<ActiveProject>()
+            .map(|project| project.runtime.clone())
+        else {
+            return;
+        };
+        let commands = commands_from_config(&runtime);
+        let palette = cx.new(|cx| CommandPalette::new(runtime, commands, window, cx));
+        self.command_palette = Some(palette.downgrade());
+        let view = palette.clone();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog.w(px(520.)).child(view.clone())
+        });
+        palette.read(cx).focus(window, cx);
+    }
+
+    /// Runs an app `<keymap>` handler.
+    fn run_keymap_handler(
+        &mut self,
+        action: &RunKeymapHandler,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match cx
+            .try_global::<ActiveProject>()
+            .map(|project| project.runtime.clone())
+        {
+            Some(runtime) => {
+                runtime.call_handler(&action.handler, KEYMAP_TARGET, &action.binding);
+                window.refresh();
+            }
+            None => cx.propagate(),
+        }
+    }
+
+    /// Runs the app command bound to a key.
+    fn run_command(&mut self, action: &RunCommand, window: &mut Window, cx: &mut Context<Self>) {
+        match cx
+            .try_global::<ActiveProject>()
+            .map(|project| project.runtime.clone())
+        {
+            Some(runtime) => {
+                action.command.run(&runtime);
+                window.refresh();
+            }
+            None => cx.propagate(),
+        }
+    }
+
     /// Toggles the dev panel builder window. When no builder window is open,
     /// opens one. When the builder window is already open, focuses it.
     /// Only acts in dev mode with a loaded project.
@@ -595,6 +696,7 @@ impl Render for Workspace {
             tracing::info!("Closing current project");
             self.shutdown(cx);
             cx.remove_global::<ActiveProject>();
+            bind_app_keymap(None, cx);
             self.current_config_path = None;
             self.current_route = "/".to_string();
             // Recreate loader so it gets fresh recent projects list
@@ -655,6 +757,7 @@ impl Render for Workspace {
             .bg(bg_color)
             .text_color(text_color)
             .track_focus(&self.focus_handle)
+            .key_context(AppKeymap::current_context(cx).as_str())
             .on_action(cx.listener(Self::reload_config))
             .on_action(cx.listener(Self::quit_app))
             .on_action(cx.listener(Self::close_project))
@@ -664,6 +767,9 @@ impl Render for Workspace {
             .on_action(cx.listener(Self::open_settings))
             .on_action(cx.listener(Self::close_settings))
             .on_action(cx.listener(Self::toggle_dev_panel))
+            .on_action(cx.listener(Self::toggle_command_palette))
+            .on_action(cx.listener(Self::run_keymap_handler))
+            .on_action(cx.listener(Self::run_command))
             .child(routes);
 
         if let Some(dialog_layer) = Root::render_dialog_layer(window, cx) {
@@ -3,9 +3,12 @@
 //! Provides a native GPUI settings page accessible via `ctrl-p`.
 //! Shows general application settings and plugin-contributed settings sections.
 
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::button::{Button as GpuiButton, DropdownButton as GpuiDropdownButton};
+use gpui_component::h_flex;
 use gpui_component::input::{Input as GpuiInput, InputEvent, InputState};
+use gpui_component::kbd::Kbd;
 use gpui_component::label::Label;
 use gpui_component::menu::PopupMenuItem;
 use gpui_component::slider::{Slider as GpuiSlider, SliderState};
@@ -22,6 +25,9 @@ use crate::config::NemoConfig;
 use crate::runtime::NemoRuntime;
 use crate::theme;
 use crate::theme::tokens::{radius_of, Space, TokenStyled};
+use crate::workspace::command_palette::commands_from_config;
+use crate::workspace::keymap::{keymap_entries, parse_binding, BUILTIN_SHORTCUTS};
+use crate::workspace::utils::shortcut_row;
 use crate::workspace::xml_edit;
 
 /// Callback invoked when a settings dropdown item is chosen. Receives the
@@ -41,6 +47,8 @@ pub enum SettingsPage {
     Global,
     /// Project settings, persisted to the loaded project's `app.xml`.
     Project,
+    /// Read-only list of the workspace's and the app's shortcuts.
+    Keyboard,
     Plugin(usize),
 }
 
@@ -209,6 +217,11 @@ impl SettingsView {
         for (page, id, label) in [
             (SettingsPage::Global, "settings-global", "Global"),
             (SettingsPage::Project, "settings-project", "Project"),
+            (
+                SettingsPage::Keyboard,
+                "settings-keyboard",
+                "Keyboard Shortcuts",
+            ),
         ] {
             let is_selected = self.selected_page == page;
             let item_bg = if is_selected {
@@ -375,6 +388,25 @@ impl SettingsView {
             })
         };
 
+        // Apps that ship `<i18n>` catalogs get a language picker: the choice
+        // re-renders translated labels now and is the startup locale after.
+        let locales = self.runtime.locales();
+        let cur_locale = self.runtime.locale().unwrap_or_default();
+        let on_locale: OnSelect = {
+            let cfg = Arc::clone(&self.nemo_config);
+            let runtime = Arc::clone(&self.runtime);
+            let entity = entity.clone();
+            Rc::new(move |sel, window, cx| {
+                runtime.set_locale(&sel);
+                if let Ok(mut c) = cfg.lock() {
+                    c.app.locale = Some(sel);
+                    let _ = c.save();
+                }
+                entity.update(cx, |_this, cx| cx.notify());
+                window.refresh();
+            })
+        };
+
         v_flex()
             .gap_t(Space::Lg)
             .child(
@@ -403,6 +435,17 @@ impl SettingsView {
                         "Color Mode",
                         Self::selector("global-mode".to_string(), cur_mode, mode_options, on_mode),
                     ))
+                    .when(!locales.is_empty(), |this| {
+                        this.child(Self::setting_field(
+                            "Language",
+                            Self::selector(
+                                "global-locale".to_string(),
+                                cur_locale,
+                                locales,
+                                on_locale,
+                            ),
+                        ))
+                    })
                     .child(
                         v_flex()
                             .gap_t(Space::Xs)
@@ -538,6 +581,65 @@ impl SettingsView {
             )
     }
 
+    /// Renders the Keyboard Shortcuts page: the app's `<keymap>` and command
+    /// keys, then the workspace's own shortcuts.
+    fn render_keyboard_page(&self, cx: &mut Context<Self>) -> Div {
+        let muted = cx.theme().colors.muted_foreground;
+
+        let mut app_rows: Vec<AnyElement> =
+            keymap_entries(self.runtime.get_config("keymap").as_ref())
+                .into_iter()
+                .map(|entry| {
+                    let mut label = entry.handler.unwrap_or_else(|| "(no handler)".to_string());
+                    if let Some(when) = entry.when {
+                        label.push_str(&format!(" — when {when}"));
+                    }
+                    keymap_row(&label, &entry.binding, muted).into_any_element()
+                })
+                .collect();
+        app_rows.extend(
+            commands_from_config(&self.runtime)
+                .into_iter()
+                .filter_map(|command| {
+                    let key = command.key?;
+                    Some(keymap_row(&command.name, &key, muted).into_any_element())
+                }),
+        );
+        let app_section = if app_rows.is_empty() {
+            v_flex().child(
+                Label::new("This app declares no shortcuts.")
+                    .text_size(px(14.))
+                    .text_color(muted),
+            )
+        } else {
+            v_flex().children(app_rows)
+        };
+
+        v_flex()
+            .gap_t(Space::Lg)
+            .child(
+                Label::new("Keyboard Shortcuts")
+                    .text_size(px(18.))
+                    .font_weight(FontWeight::SEMIBOLD),
+            )
+            .child(
+                v_flex()
+                    .gap_t(Space::Xs)
+                    .child(Label::new("Application").font_weight(FontWeight::SEMIBOLD))
+                    .child(app_section),
+            )
+            .child(
+                v_flex()
+                    .gap_t(Space::Xs)
+                    .child(Label::new("Workspace").font_weight(FontWeight::SEMIBOLD))
+                    .children(
+                        BUILTIN_SHORTCUTS
+                            .iter()
+                            .map(|(name, keys)| shortcut_row(name, keys)),
+                    ),
+            )
+    }
+
     /// Renders a plugin settings page from its PluginValue definition.
     fn render_plugin_page(
         &mut self,
@@ -793,6 +895,29 @@ fn settings_row(label: &str, value: &str, muted: Hsla) -> Div {
         )
 }
 
+/// Renders an app shortcut: its label and keys, or the binding as text when
+/// it doesn't parse.
+fn keymap_row(label: &str, binding: &str, muted: Hsla) -> Div {
+    let keys = match parse_binding(binding) {
+        Ok(keystrokes) => h_flex()
+            .gap_1()
+            .children(keystrokes.into_iter().map(Kbd::new)),
+        Err(_) => h_flex().child(
+            Label::new(binding.to_string())
+                .text_size(px(14.))
+                .text_color(muted),
+        ),
+    };
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_between()
+        .py_1p5()
+        .child(Label::new(label.to_string()).text_size(px(14.)))
+        .child(keys)
+}
+
 impl Render for SettingsView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let bg = cx.theme().colors.background;
@@ -803,6 +928,7 @@ impl Render for SettingsView {
         let content = match &self.selected_page {
             SettingsPage::Global => self.render_global_page(cx).into_any_element(),
             SettingsPage::Project => self.render_project_page(cx).into_any_element(),
+            SettingsPage::Keyboard => self.render_keyboard_page(cx).into_any_element(),
             SettingsPage::Plugin(idx) => {
                 let idx = *idx;
                 self.render_plugin_page(idx, window, cx)
@@ -9,11 +9,16 @@ use crate::theme;
 use crate::theme::tokens::{Space, TokenStyled};
 
 /// Creates a NemoRuntime, applies extension dirs, loads config, and initializes.
-/// Returns the runtime wrapped in Arc on success.
+/// `locale` (the `config.toml` language) picks the catalog `${t(…)}` text is
+/// rendered in; `profile` and `overrides` (`--profile`, `--set`) are layered
+/// over the entry. Returns the runtime wrapped in Arc on success.
 pub fn create_runtime(
     config_path: &std::path::Path,
     extension_dirs: &[PathBuf],
     initial_route: Option<&str>,
+    locale: Option<&str>,
+    profile: Option<&str>,
+    overrides: &[nemo_config::SetOverride],
 ) -> Result<Arc<runtime::NemoRuntime>> {
     let rt = runtime::NemoRuntime::new(config_path)?;
 
@@ -21,6 +26,12 @@ pub fn create_runtime(
         let _ = rt.add_extension_dir(dir);
     }
 
+    if let Some(locale) = locale {
+        rt.set_locale(locale);
+    }
+    rt.config_loader.set_profile(profile);
+    rt.config_loader.set_overrides(overrides.to_vec());
+
     rt.load_config()?;
     rt.initialize()?;
 
@@ -6,23 +6,11 @@
 use nemo_config::Value;
 use nemo_layout::BuiltComponent;
 use nemo_macros::NemoComponent;
-use std::collections::HashMap;
 
 // ── Helper ────────────────────────────────────────────────────────────────
 
 fn make_component(props: Vec<(&str, Value)>) -> BuiltComponent {
-    let mut properties = HashMap::new();
-    for (k, v) in props {
-        properties.insert(k.to_string(), v);
-    }
-    BuiltComponent {
-        id: "test".to_string(),
-        component_type: "test".to_string(),
-        properties,
-        handlers: HashMap::new(),
-        children: vec![],
-        parent: None,
-    }
+    BuiltComponent::new("test", "test").with_props(props)
 }
 
 // ── Basic property extraction ─────────────────────────────────────────────